@@ -1,15 +1,26 @@
 // src/indicators/accum_dist_line.rs
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
 
+#[derive(Default)]
 pub struct AccumDistLine;
 impl AccumDistLine {
     pub fn new() -> Self { AccumDistLine }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
+
+    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> {
+        let mut cum_sum = 0.0;
+        candles.iter().map(|c| {
+            let range = c.high - c.low;
+            let clv = if range != 0.0 { ((c.close - c.low) - (c.high - c.close)) / range } else { 0.0 };
+            cum_sum += clv * c.volume.unwrap_or(0.0);
+            Some(cum_sum)
+        }).collect()
+    }
 }
+
 impl TechnicalIndicator for AccumDistLine {
     fn name(&self) -> &'static str { "Accumulation/Distribution Line" }
     fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Running total of money flow volume, weighting volume by where the close sits in the bar's range" }
     fn params(&self) -> Vec<IndicatorParam> { vec![] }
     fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
 }
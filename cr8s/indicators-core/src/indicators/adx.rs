@@ -0,0 +1,73 @@
+// src/indicators/adx.rs
+use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct ADX;
+impl ADX {
+    pub fn new() -> Self { ADX }
+
+    // Wilder's ADX: smoothed DX over directional movement smoothed the same way
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize) -> Vec<Option<f64>> {
+        let len = candles.len();
+        let mut adx = vec![None; len];
+        if period == 0 || len < period * 2 { return adx; }
+
+        let mut tr = vec![0.0; len];
+        let mut plus_dm = vec![0.0; len];
+        let mut minus_dm = vec![0.0; len];
+        for i in 1..len {
+            let up_move = candles[i].high - candles[i-1].high;
+            let down_move = candles[i-1].low - candles[i].low;
+            plus_dm[i] = if up_move > down_move && up_move > 0.0 { up_move } else { 0.0 };
+            minus_dm[i] = if down_move > up_move && down_move > 0.0 { down_move } else { 0.0 };
+            let high_low = candles[i].high - candles[i].low;
+            let high_close = (candles[i].high - candles[i-1].close).abs();
+            let low_close = (candles[i].low - candles[i-1].close).abs();
+            tr[i] = high_low.max(high_close).max(low_close);
+        }
+
+        let p = period as f64;
+        let mut tr_smooth: f64 = tr[1..=period].iter().sum();
+        let mut plus_smooth: f64 = plus_dm[1..=period].iter().sum();
+        let mut minus_smooth: f64 = minus_dm[1..=period].iter().sum();
+        let mut dx = vec![0.0; len];
+        for i in period..len {
+            if i > period {
+                tr_smooth = tr_smooth - tr_smooth / p + tr[i];
+                plus_smooth = plus_smooth - plus_smooth / p + plus_dm[i];
+                minus_smooth = minus_smooth - minus_smooth / p + minus_dm[i];
+            }
+            if tr_smooth == 0.0 { continue; }
+            let plus_di = 100.0 * plus_smooth / tr_smooth;
+            let minus_di = 100.0 * minus_smooth / tr_smooth;
+            if plus_di + minus_di != 0.0 {
+                dx[i] = 100.0 * (plus_di - minus_di).abs() / (plus_di + minus_di);
+            }
+        }
+
+        let mut prev = dx[period..period * 2].iter().sum::<f64>() / p;
+        adx[period * 2 - 1] = Some(prev);
+        for i in period * 2..len {
+            prev = (prev * (p - 1.0) + dx[i]) / p;
+            adx[i] = Some(prev);
+        }
+        adx
+    }
+}
+
+impl TechnicalIndicator for ADX {
+    fn name(&self) -> &'static str { "Average Directional Index" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Strength of the prevailing trend regardless of direction, from smoothed directional movement" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(14) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(14) as usize * 2
+    }
+}
@@ -0,0 +1,62 @@
+// src/indicators/alligator.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct Alligator;
+impl Alligator {
+    pub fn new() -> Self { Alligator }
+
+    // Wilder-smoothed (SMMA) median price, seeded with an SMA and displaced `shift` bars forward
+    pub(crate) fn smma_line(&self, candles: &[Candle], period: usize, shift: usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        if period == 0 || candles.len() < period { return result; }
+        let median: Vec<f64> = candles.iter().map(|c| (c.high + c.low) / 2.0).collect();
+        let mut prev = median[..period].iter().sum::<f64>() / period as f64;
+        for i in period-1..candles.len() {
+            if i >= period { prev = (prev * (period as f64 - 1.0) + median[i]) / period as f64; }
+            if i + shift < candles.len() { result[i + shift] = Some(prev); }
+        }
+        result
+    }
+
+    fn lines(options: &IndicatorOptions) -> [(usize, usize); 3] {
+        let get = |name: &str, default: u64| options.values.get(name).and_then(|v| v.as_u64()).unwrap_or(default) as usize;
+        [
+            (get("jaw_period", 13), get("jaw_shift", 8)),
+            (get("teeth_period", 8), get("teeth_shift", 5)),
+            (get("lips_period", 5), get("lips_shift", 3)),
+        ]
+    }
+}
+impl TechnicalIndicator for Alligator {
+    fn name(&self) -> &'static str { "Williams Alligator" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Three forward-shifted smoothed averages of the median price (jaw, teeth, lips)" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "jaw_period".into(), param_type: "int".into(), default_value: json!(13) },
+            IndicatorParam { name: "teeth_period".into(), param_type: "int".into(), default_value: json!(8) },
+            IndicatorParam { name: "lips_period".into(), param_type: "int".into(), default_value: json!(5) },
+            IndicatorParam { name: "jaw_shift".into(), param_type: "int".into(), default_value: json!(8) },
+            IndicatorParam { name: "teeth_shift".into(), param_type: "int".into(), default_value: json!(5) },
+            IndicatorParam { name: "lips_shift".into(), param_type: "int".into(), default_value: json!(3) },
+        ]
+    }
+    // The jaw only appears once its period has filled and been shifted forward
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        Self::lines(options).iter().map(|(period, shift)| period + shift).max().unwrap_or(0)
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let [(jaw_period, jaw_shift), _, _] = Self::lines(options);
+        self.smma_line(candles, jaw_period, jaw_shift)
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let mut out = HashMap::new();
+        for (key, (period, shift)) in ["jaw", "teeth", "lips"].into_iter().zip(Self::lines(options)) {
+            out.insert(key.to_string(), self.smma_line(candles, period, shift));
+        }
+        out
+    }
+}
@@ -0,0 +1,49 @@
+// src/indicators/aroon.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct Aroon;
+impl Aroon {
+    pub fn new() -> Self { Aroon }
+
+    // (up, down): 100 * (period - bars since the highest high / lowest low) / period, looking
+    // back over the last period + 1 bars
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+        let mut up = vec![None; candles.len()];
+        let mut down = vec![None; candles.len()];
+        if period == 0 { return (up, down); }
+        for i in period..candles.len() {
+            let window = &candles[i - period..=i];
+            // Latest bar wins ties, as it is the most recent extreme
+            let high_at = window.iter().enumerate().fold(0, |best, (j, c)| if c.high >= window[best].high { j } else { best });
+            let low_at = window.iter().enumerate().fold(0, |best, (j, c)| if c.low <= window[best].low { j } else { best });
+            up[i] = Some(100.0 * high_at as f64 / period as f64);
+            down[i] = Some(100.0 * low_at as f64 / period as f64);
+        }
+        (up, down)
+    }
+}
+impl TechnicalIndicator for Aroon {
+    fn name(&self) -> &'static str { "Aroon" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "How recently the period's high and low were set; compute returns the up - down oscillator" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(25) }]
+    }
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(25) as usize + 1
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(25) as usize;
+        let (up, down) = self.calculate(candles, period);
+        up.iter().zip(&down).map(|(u, d)| Some((*u)? - (*d)?)).collect()
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(25) as usize;
+        let (up, down) = self.calculate(candles, period);
+        let oscillator = up.iter().zip(&down).map(|(u, d)| Some((*u)? - (*d)?)).collect();
+        HashMap::from([("up".to_string(), up), ("down".to_string(), down), ("oscillator".to_string(), oscillator)])
+    }
+}
@@ -2,6 +2,7 @@
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct ATR;
 impl ATR {
     pub fn new() -> Self { ATR }
@@ -19,11 +20,10 @@ impl ATR {
         }
 
         let mut atr = Vec::with_capacity(candles.len());
-        let mut sum = 0.0;
         for i in 0..candles.len() {
             if i + 1 < period { atr.push(None); continue; }
             if i + 1 == period {
-                sum = trs[1..=i].iter().map(|v| v.unwrap_or(0.0)).sum();
+                let sum: f64 = trs[1..=i].iter().map(|v| v.unwrap_or(0.0)).sum();
                 atr.push(Some(sum / period as f64));
             } else {
                 let prev_atr = atr[i-1].unwrap();
@@ -36,12 +36,20 @@ impl ATR {
 impl TechnicalIndicator for ATR {
     fn name(&self) -> &'static str { "Average True Range" }
     fn group(&self) -> &'static str { "Volatility" }
+    fn description(&self) -> &'static str { "Average of true ranges over the period; a measure of volatility in price units" }
     fn params(&self) -> Vec<IndicatorParam> {
-        vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
+        vec![
+            IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) },
+            IndicatorParam { name: "as_percent".into(), param_type: "bool".into(), default_value: json!(false) },
+        ]
     }
 
     fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
         let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(14) as usize;
-        self.calculate(candles, period)
+        let as_percent = options.values.get("as_percent").and_then(|v| v.as_bool()).unwrap_or(false);
+        let atr = self.calculate(candles, period);
+        if !as_percent { return atr; }
+        // ATR% = ATR / close * 100, comparable across price levels
+        atr.iter().zip(candles).map(|(a, c)| a.filter(|_| c.close != 0.0).map(|a| a / c.close * 100.0)).collect()
     }
 }
@@ -2,6 +2,7 @@
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct BollingerBands;
 impl BollingerBands {
     pub fn new() -> Self { BollingerBands }
@@ -24,6 +25,8 @@ impl BollingerBands {
 impl TechnicalIndicator for BollingerBands {
     fn name(&self) -> &'static str { "Bollinger Bands" }
     fn group(&self) -> &'static str { "Volatility" }
+    fn description(&self) -> &'static str { "Moving average with bands a number of standard deviations above and below" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![
             IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(20) },
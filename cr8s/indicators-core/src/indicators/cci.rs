@@ -2,6 +2,7 @@
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct CCI;
 impl CCI {
     pub fn new() -> Self { CCI }
@@ -25,6 +26,7 @@ impl CCI {
 impl TechnicalIndicator for CCI {
     fn name(&self) -> &'static str { "Commodity Channel Index" }
     fn group(&self) -> &'static str { "Momentum" }
+    fn description(&self) -> &'static str { "Deviation of typical price from its moving average, scaled by mean absolute deviation" }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(20) }]
     }
@@ -0,0 +1,36 @@
+// src/indicators/chandelier_exit.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::ATR;
+use serde_json::json;
+
+#[derive(Default)]
+pub struct ChandelierExit;
+impl ChandelierExit {
+    pub fn new() -> Self { ChandelierExit }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize, atr_multiplier:f64) -> Vec<Option<f64>> {
+        let atr = ATR.calculate(candles, period);
+        (0..candles.len()).map(|i| {
+            if i + 1 < period { return None; }
+            let highest = candles[i+1-period..=i].iter().map(|c| c.high).fold(f64::MIN, f64::max);
+            atr[i].map(|a| highest - atr_multiplier * a)
+        }).collect()
+    }
+}
+
+impl TechnicalIndicator for ChandelierExit {
+    fn name(&self) -> &'static str { "Chandelier Exit" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Trailing stop placed a multiple of ATR below the highest high" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(22)},
+            IndicatorParam { name:"atr_multiplier".into(), param_type:"float".into(), default_value: json!(3.0)},
+        ]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(22) as usize;
+        let atr_multiplier = options.values.get("atr_multiplier").and_then(|v|v.as_f64()).unwrap_or(3.0);
+        self.calculate(candles, period, atr_multiplier)
+    }
+}
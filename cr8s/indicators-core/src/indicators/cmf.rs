@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct CMF;
 impl CMF {
     pub fn new() -> Self { CMF }
@@ -22,6 +23,7 @@ impl CMF {
 impl TechnicalIndicator for CMF {
     fn name(&self) -> &'static str { "Chaikin Money Flow" }
     fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Money flow volume summed over the period and normalised by total volume" }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(20) }]
     }
@@ -0,0 +1,17 @@
+// src/indicators/crossover.rs
+// Shared cross detection for signal outputs: 1.0 = crossed above, -1.0 = crossed below, 0.0 = no cross
+
+pub fn crossover(fast: &[Option<f64>], slow: &[Option<f64>]) -> Vec<Option<f64>> {
+    let len = fast.len().min(slow.len());
+    let mut result = vec![None; len];
+    for i in 1..len {
+        if let (Some(f0), Some(s0), Some(f1), Some(s1)) = (fast[i-1], slow[i-1], fast[i], slow[i]) {
+            result[i] = Some(if f0 <= s0 && f1 > s1 { 1.0 } else if f0 >= s0 && f1 < s1 { -1.0 } else { 0.0 });
+        }
+    }
+    result
+}
+
+pub fn cross_level(series: &[Option<f64>], level: f64) -> Vec<Option<f64>> {
+    crossover(series, &vec![Some(level); series.len()])
+}
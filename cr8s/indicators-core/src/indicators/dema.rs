@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct Dema;
 impl Dema {
     pub fn new() -> Self { Dema }
@@ -16,6 +17,8 @@ impl Dema {
 impl TechnicalIndicator for Dema {
     fn name(&self) -> &'static str { "Double Exponential Moving Average" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Double-smoothed EMA that reduces the lag of a single EMA" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
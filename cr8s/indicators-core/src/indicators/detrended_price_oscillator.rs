@@ -0,0 +1,28 @@
+// src/indicators/detrended_price_oscillator.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::SMA;
+use serde_json::json;
+
+#[derive(Default)]
+pub struct DetrendedPriceOscillator;
+impl DetrendedPriceOscillator {
+    pub fn new() -> Self { DetrendedPriceOscillator }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        SMA.calculate(candles, period).iter().zip(candles).map(|(sma, c)| sma.map(|s| c.close - s)).collect()
+    }
+}
+
+impl TechnicalIndicator for DetrendedPriceOscillator {
+    fn name(&self) -> &'static str { "Detrended Price Oscillator" }
+    fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Price minus a displaced moving average, isolating shorter cycles" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(20)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(20) as usize;
+        self.calculate(candles, period)
+    }
+}
@@ -0,0 +1,45 @@
+// src/indicators/donchian_channels.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct DonchianChannels;
+impl DonchianChannels {
+    pub fn new() -> Self { DonchianChannels }
+
+    // (middle, upper, lower): highest high and lowest low of the last `period` bars, and their midpoint
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize) -> [Vec<Option<f64>>; 3] {
+        let mut upper = vec![None; candles.len()];
+        let mut lower = vec![None; candles.len()];
+        let mut middle = vec![None; candles.len()];
+        if period == 0 { return [middle, upper, lower]; }
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &candles[i + 1 - period..=i];
+            let high = window.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+            let low = window.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+            upper[i] = Some(high);
+            lower[i] = Some(low);
+            middle[i] = Some((high + low) / 2.0);
+        }
+        [middle, upper, lower]
+    }
+}
+impl TechnicalIndicator for DonchianChannels {
+    fn name(&self) -> &'static str { "Donchian Channels" }
+    fn group(&self) -> &'static str { "Volatility" }
+    fn description(&self) -> &'static str { "Highest high and lowest low over the period, with their midpoint" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(20) }]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+        let [middle, _, _] = self.calculate(candles, period);
+        middle
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+        let [middle, upper, lower] = self.calculate(candles, period);
+        HashMap::from([("middle".to_string(), middle), ("upper".to_string(), upper), ("lower".to_string(), lower)])
+    }
+}
@@ -0,0 +1,43 @@
+// src/indicators/ease_of_movement.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct EaseOfMovement;
+impl EaseOfMovement {
+    pub fn new() -> Self { EaseOfMovement }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut eom = vec![None; candles.len()];
+        for i in 1..candles.len() {
+            let distance = (candles[i].high + candles[i].low) / 2.0 - (candles[i-1].high + candles[i-1].low) / 2.0;
+            let volume = candles[i].volume.unwrap_or(0.0);
+            let range = candles[i].high - candles[i].low;
+            // Bars without volume or range have no box ratio to divide by
+            if volume != 0.0 && range != 0.0 { eom[i] = Some(distance / (range / volume)); }
+        }
+        // Smoothed with an SMA over the period
+        let mut smoothed = vec![None; candles.len()];
+        for i in period..candles.len() {
+            let window: Option<Vec<f64>> = eom[i+1-period..=i].iter().copied().collect();
+            smoothed[i] = window.map(|w| w.iter().sum::<f64>() / period as f64);
+        }
+        smoothed
+    }
+}
+
+impl TechnicalIndicator for EaseOfMovement {
+    fn name(&self) -> &'static str { "Ease of Movement" }
+    fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Relates price change to volume to show how easily price moves" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(14)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize + 1
+    }
+}
@@ -1,6 +1,7 @@
 use crate::indicators::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct EMA;
 impl EMA {
     pub fn new() -> Self { EMA }
@@ -19,6 +20,8 @@ impl EMA {
 impl TechnicalIndicator for EMA {
     fn name(&self) -> &'static str { "Exponential Moving Average" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Moving average that weights recent closes exponentially more" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
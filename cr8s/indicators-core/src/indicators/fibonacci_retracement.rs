@@ -0,0 +1,52 @@
+// src/indicators/fibonacci_retracement.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+const LEVELS: [f64; 7] = [0.0, 0.236, 0.382, 0.5, 0.618, 0.786, 1.0];
+
+#[derive(Default)]
+pub struct FibonacciRetracement;
+impl FibonacciRetracement {
+    pub fn new() -> Self { FibonacciRetracement }
+
+    // Price at `level` between the lowest low and highest high of the trailing window
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize, level:f64) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &candles[i+1-period..=i];
+            let high = window.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+            let low = window.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+            result[i] = Some(low + (high - low) * level);
+        }
+        result
+    }
+
+    fn period(options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize
+    }
+}
+
+impl TechnicalIndicator for FibonacciRetracement {
+    fn name(&self) -> &'static str { "Fibonacci Retracement" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Retracement levels between the recent swing high and low" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(14)},
+            IndicatorParam { name:"level".into(), param_type:"float".into(), default_value: json!(0.618)},
+        ]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let level = options.values.get("level").and_then(|v|v.as_f64()).unwrap_or(0.618);
+        self.calculate(candles, Self::period(options), level)
+    }
+    fn compute_multi(&self, candles:&[Candle], options:&IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let period = Self::period(options);
+        let mut out: HashMap<String, Vec<Option<f64>>> = LEVELS.iter()
+            .map(|level| (format!("level_{}", level), self.calculate(candles, period, *level)))
+            .collect();
+        out.insert("value".to_string(), self.compute(candles, options));
+        out
+    }
+}
@@ -0,0 +1,46 @@
+// src/indicators/force_index.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct ForceIndex;
+impl ForceIndex {
+    pub fn new() -> Self { ForceIndex }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut force = vec![None; candles.len()];
+        for i in 1..candles.len() {
+            force[i] = candles[i].volume.map(|v| (candles[i].close - candles[i-1].close) * v);
+        }
+        if period <= 1 { return force; }
+        if candles.len() <= period { return vec![None; candles.len()]; }
+        // EMA of the raw force, seeded with the average of the first `period` values
+        let k = 2.0 / (period as f64 + 1.0);
+        let mut smoothed = vec![None; candles.len()];
+        let mut prev = force[1..=period].iter().map(|v| v.unwrap_or(0.0)).sum::<f64>() / period as f64;
+        smoothed[period] = Some(prev);
+        for i in period+1..candles.len() {
+            if let Some(v) = force[i] {
+                prev = v * k + prev * (1.0 - k);
+                smoothed[i] = Some(prev);
+            }
+        }
+        smoothed
+    }
+}
+
+impl TechnicalIndicator for ForceIndex {
+    fn name(&self) -> &'static str { "Force Index" }
+    fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Price change multiplied by volume, measuring the force behind a move" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(13)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(13) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(13) as usize + 1
+    }
+}
@@ -0,0 +1,43 @@
+// src/indicators/fractals.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+// Sparse marker series: Some(1.0) = up fractal (high above the `strength` bars on each side),
+// Some(-1.0) = down fractal (low below them), None = no fractal. The last `strength` bars are
+// always None because they can't be confirmed yet. A bar that is both is reported as an up fractal.
+#[derive(Default)]
+pub struct Fractals;
+impl Fractals {
+    pub fn new() -> Self { Fractals }
+
+    pub(crate) fn calculate(&self, candles: &[Candle], strength: usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        if strength == 0 || candles.len() < 2 * strength + 1 { return result; }
+        for i in strength..candles.len() - strength {
+            let neighbours = || (i - strength..=i + strength).filter(move |&j| j != i);
+            if neighbours().all(|j| candles[j].high < candles[i].high) {
+                result[i] = Some(1.0);
+            } else if neighbours().all(|j| candles[j].low > candles[i].low) {
+                result[i] = Some(-1.0);
+            }
+        }
+        result
+    }
+}
+impl TechnicalIndicator for Fractals {
+    fn name(&self) -> &'static str { "Williams Fractals" }
+    fn group(&self) -> &'static str { "Signal" }
+    fn description(&self) -> &'static str { "Marks local highs (+1) and lows (-1) that stand out from the bars on each side" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name: "strength".into(), param_type: "int".into(), default_value: json!(2) }]
+    }
+    // A fractal needs `strength` bars on both sides of it
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        let strength = options.values.get("strength").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+        2 * strength + 1
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let strength = options.values.get("strength").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+        self.calculate(candles, strength)
+    }
+}
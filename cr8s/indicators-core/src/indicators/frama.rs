@@ -0,0 +1,50 @@
+// src/indicators/frama.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct Frama;
+impl Frama {
+    pub fn new() -> Self { Frama }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        let half = period / 2;
+        if half == 0 || candles.len() <= period { return result; }
+        let range = |window: &[Candle]| {
+            let high = window.iter().map(|c| c.close).fold(f64::MIN, f64::max);
+            let low = window.iter().map(|c| c.close).fold(f64::MAX, f64::min);
+            high - low
+        };
+        let mut prev = candles[period-1].close;
+        for i in period..candles.len() {
+            let window = &candles[i-period..i];
+            let n1 = range(&window[..half]) / half as f64;
+            let n2 = range(&window[half..]) / half as f64;
+            let n3 = range(window) / period as f64;
+            // Fractal dimension between 1 (trending) and 2 (noisy); flat windows count as trending
+            let dim = if n1 > 0.0 && n2 > 0.0 && n3 > 0.0 { ((n1 + n2) / n3).log2().abs() } else { 1.0 };
+            let alpha = (-4.6 * (dim - 1.0)).exp();
+            prev = alpha * candles[i].close + (1.0 - alpha) * prev;
+            result[i] = Some(prev);
+        }
+        result
+    }
+}
+
+impl TechnicalIndicator for Frama {
+    fn name(&self) -> &'static str { "Fractal Adaptive Moving Average" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Moving average whose smoothing adapts to the fractal dimension of price" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(10)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(10) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(10) as usize + 1
+    }
+}
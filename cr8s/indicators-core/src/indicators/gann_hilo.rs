@@ -0,0 +1,53 @@
+// src/indicators/gann_hilo.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct GannHiLo;
+impl GannHiLo {
+    pub fn new() -> Self { GannHiLo }
+
+    // Returns (activator, direction). Direction flips to +1 when the close breaks above the prior
+    // SMA of highs and to -1 when it breaks below the prior SMA of lows; otherwise it carries forward.
+    // In an uptrend the activator trails the SMA of lows, in a downtrend the SMA of highs.
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+        let mut activator = vec![None; candles.len()];
+        let mut direction = vec![None; candles.len()];
+        if period == 0 || candles.len() <= period { return (activator, direction); }
+
+        let sma = |i: usize, f: fn(&Candle) -> f64| candles[i+1-period..=i].iter().map(f).sum::<f64>() / period as f64;
+        let mut dir = 0.0;
+        for i in period..candles.len() {
+            let (prev_hi, prev_lo) = (sma(i - 1, |c| c.high), sma(i - 1, |c| c.low));
+            if candles[i].close > prev_hi { dir = 1.0; } else if candles[i].close < prev_lo { dir = -1.0; }
+            if dir == 0.0 { continue; }
+            activator[i] = Some(if dir > 0.0 { sma(i, |c| c.low) } else { sma(i, |c| c.high) });
+            direction[i] = Some(dir);
+        }
+        (activator, direction)
+    }
+}
+impl TechnicalIndicator for GannHiLo {
+    fn name(&self) -> &'static str { "Gann HiLo Activator" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Trailing stop that follows the SMA of lows in uptrends and the SMA of highs in downtrends" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(10) }]
+    }
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(10) as usize + 1
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        self.calculate(candles, period).0
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let (activator, direction) = self.calculate(candles, period);
+        let mut out = HashMap::new();
+        out.insert("activator".to_string(), activator);
+        out.insert("direction".to_string(), direction);
+        out
+    }
+}
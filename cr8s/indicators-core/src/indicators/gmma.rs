@@ -0,0 +1,47 @@
+// src/indicators/gmma.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::EMA;
+use std::collections::HashMap;
+
+const SHORT_PERIODS: [usize; 6] = [3, 5, 8, 10, 12, 15];
+const LONG_PERIODS: [usize; 6] = [30, 35, 40, 45, 50, 60];
+
+#[derive(Default)]
+pub struct GMMA;
+impl GMMA {
+    pub fn new() -> Self { GMMA }
+
+    fn ribbon(candles:&[Candle], periods:&[usize]) -> Vec<(usize, Vec<Option<f64>>)> {
+        periods.iter().map(|&p| (p, EMA.calculate(candles, p))).collect()
+    }
+
+    fn mean(ribbon:&[(usize, Vec<Option<f64>>)], i:usize) -> Option<f64> {
+        let values: Option<Vec<f64>> = ribbon.iter().map(|(_, ema)| ema[i]).collect();
+        values.map(|v| v.iter().sum::<f64>() / v.len() as f64)
+    }
+
+    // Spread between the average short-term and average long-term EMA
+    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> {
+        let short = Self::ribbon(candles, &SHORT_PERIODS);
+        let long = Self::ribbon(candles, &LONG_PERIODS);
+        (0..candles.len()).map(|i| Some(Self::mean(&short, i)? - Self::mean(&long, i)?)).collect()
+    }
+}
+
+impl TechnicalIndicator for GMMA {
+    fn name(&self) -> &'static str { "GMMA" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Guppy multiple moving averages: short- and long-term EMA ribbons" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> { vec![] }
+    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
+    fn compute_multi(&self, candles:&[Candle], _options:&IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let mut out = HashMap::new();
+        for (period, ema) in Self::ribbon(candles, &SHORT_PERIODS).into_iter().chain(Self::ribbon(candles, &LONG_PERIODS)) {
+            out.insert(format!("ema_{}", period), ema);
+        }
+        out.insert("value".to_string(), self.calculate(candles));
+        out
+    }
+    fn warmup(&self, _options: &IndicatorOptions) -> usize { LONG_PERIODS[LONG_PERIODS.len() - 1] }
+}
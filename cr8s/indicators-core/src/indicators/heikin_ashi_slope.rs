@@ -0,0 +1,40 @@
+// src/indicators/heikin_ashi_slope.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct HeikinAshiSlope;
+impl HeikinAshiSlope {
+    pub fn new() -> Self { HeikinAshiSlope }
+
+    // Least-squares slope of the Heikin-Ashi closes over each trailing window
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let ha_closes: Vec<f64> = candles.iter().map(|c| (c.open + c.high + c.low + c.close) / 4.0).collect();
+        let n = period as f64;
+        let sum_x: f64 = (0..period).map(|x| x as f64).sum();
+        let sum_xx: f64 = (0..period).map(|x| (x as f64).powi(2)).sum();
+        let denominator = n * sum_xx - sum_x * sum_x;
+        let mut slopes = vec![None; candles.len()];
+        if denominator == 0.0 { return slopes; }
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &ha_closes[i+1-period..=i];
+            let sum_y: f64 = window.iter().sum();
+            let sum_xy: f64 = window.iter().enumerate().map(|(x, y)| x as f64 * y).sum();
+            slopes[i] = Some((n * sum_xy - sum_x * sum_y) / denominator);
+        }
+        slopes
+    }
+}
+
+impl TechnicalIndicator for HeikinAshiSlope {
+    fn name(&self) -> &'static str { "Heikin-Ashi Slope" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Slope of Heikin-Ashi closes, a smoothed view of trend direction" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(10)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(10) as usize;
+        self.calculate(candles, period)
+    }
+}
@@ -0,0 +1,133 @@
+// src/indicators/historical_volatility.rs
+// Annualised historical volatility over a rolling window, as a decimal (0.25 = 25%) like the sigma
+// the option pricers take. The range-based estimators use the whole bar and need far fewer bars
+// than close-to-close for the same precision; Yang-Zhang also handles overnight gaps and drift.
+use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolEstimator {
+    CloseToClose, // sample deviation of log close-to-close returns
+    Parkinson,    // high-low range
+    GarmanKlass,  // high-low range and open-to-close move
+    YangZhang,    // overnight, open-to-close and Rogers-Satchell terms
+}
+
+impl VolEstimator {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "close_to_close" | "close" | "std" => Some(VolEstimator::CloseToClose),
+            "parkinson" => Some(VolEstimator::Parkinson),
+            "garman_klass" => Some(VolEstimator::GarmanKlass),
+            "yang_zhang" => Some(VolEstimator::YangZhang),
+            _ => None,
+        }
+    }
+
+    // Close-to-close and Yang-Zhang read the previous bar's close, so need one bar more than the window
+    fn needs_previous_close(self) -> bool {
+        matches!(self, VolEstimator::CloseToClose | VolEstimator::YangZhang)
+    }
+}
+
+// Sample variance, None with fewer than two values
+fn variance(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    Some(values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64)
+}
+
+// Per-bar variance of one window; `previous` is the close before window[0]
+fn window_variance(window: &[Candle], previous: Option<f64>, estimator: VolEstimator) -> Option<f64> {
+    let n = window.len() as f64;
+    let ln = |a: f64, b: f64| (a / b).ln();
+    match estimator {
+        VolEstimator::CloseToClose => {
+            let mut prev = previous?;
+            let returns: Vec<f64> = window.iter().map(|c| { let r = ln(c.close, prev); prev = c.close; r }).collect();
+            variance(&returns)
+        }
+        VolEstimator::Parkinson => {
+            let sum: f64 = window.iter().map(|c| ln(c.high, c.low).powi(2)).sum();
+            Some(sum / (4.0 * n * std::f64::consts::LN_2))
+        }
+        VolEstimator::GarmanKlass => {
+            let k = 2.0 * std::f64::consts::LN_2 - 1.0;
+            let sum: f64 = window.iter().map(|c| 0.5 * ln(c.high, c.low).powi(2) - k * ln(c.close, c.open).powi(2)).sum();
+            Some((sum / n).max(0.0))
+        }
+        VolEstimator::YangZhang => {
+            let mut prev = previous?;
+            let overnight: Vec<f64> = window.iter().map(|c| { let r = ln(c.open, prev); prev = c.close; r }).collect();
+            let open_close: Vec<f64> = window.iter().map(|c| ln(c.close, c.open)).collect();
+            let rogers_satchell = window.iter()
+                .map(|c| ln(c.high, c.close) * ln(c.high, c.open) + ln(c.low, c.close) * ln(c.low, c.open))
+                .sum::<f64>() / n;
+            let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+            Some(variance(&overnight)? + k * variance(&open_close)? + (1.0 - k) * rogers_satchell)
+        }
+    }
+}
+
+// Annualised volatility at each bar over the last `period` bars; `periods_per_year` is 252 for
+// daily bars. Windows touching a non-positive price are None.
+pub fn historical_volatility(candles: &[Candle], period: usize, estimator: VolEstimator, periods_per_year: f64) -> Vec<Option<f64>> {
+    let offset = usize::from(estimator.needs_previous_close());
+    let mut out = vec![None; candles.len()];
+    if period == 0 {
+        return out;
+    }
+    for i in (period - 1 + offset)..candles.len() {
+        let start = i + 1 - period;
+        let used = &candles[start - offset..=i];
+        if used.iter().any(|c| c.open <= 0.0 || c.high <= 0.0 || c.low <= 0.0 || c.close <= 0.0) {
+            continue;
+        }
+        let previous = (offset == 1).then(|| candles[start - 1].close);
+        out[i] = window_variance(&candles[start..=i], previous, estimator)
+            .filter(|v| v.is_finite())
+            .map(|v| (v * periods_per_year).sqrt());
+    }
+    out
+}
+
+// Volatility over the latest full window, e.g. as the default sigma for pricing
+pub fn realized_volatility(candles: &[Candle], period: usize, estimator: VolEstimator, periods_per_year: f64) -> Option<f64> {
+    let offset = usize::from(estimator.needs_previous_close());
+    let tail = &candles[candles.len().saturating_sub(period + offset)..];
+    historical_volatility(tail, period, estimator, periods_per_year).last().copied().flatten()
+}
+
+#[derive(Default)]
+pub struct HistoricalVolatility;
+impl HistoricalVolatility {
+    pub fn new() -> Self { HistoricalVolatility }
+}
+impl TechnicalIndicator for HistoricalVolatility {
+    fn name(&self) -> &'static str { "Historical Volatility" }
+    fn group(&self) -> &'static str { "Volatility" }
+    fn description(&self) -> &'static str { "Annualised volatility over a rolling window: close-to-close, Parkinson, Garman-Klass or Yang-Zhang" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(20) },
+            IndicatorParam { name: "estimator".into(), param_type: "string".into(), default_value: json!("close_to_close") }, // close_to_close, parkinson, garman_klass, yang_zhang
+            IndicatorParam { name: "periods_per_year".into(), param_type: "float".into(), default_value: json!(252.0) },
+        ]
+    }
+    // A window of returns plus the close before it
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(20) as usize + 1
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+        let estimator = options.values.get("estimator").and_then(|v| v.as_str())
+            .and_then(VolEstimator::parse)
+            .unwrap_or(VolEstimator::CloseToClose);
+        let periods_per_year = options.values.get("periods_per_year").and_then(|v| v.as_f64())
+            .filter(|p| *p > 0.0)
+            .unwrap_or(252.0);
+        historical_volatility(candles, period, estimator, periods_per_year)
+    }
+}
@@ -0,0 +1,47 @@
+// src/indicators/hma.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::WMA;
+use serde_json::json;
+
+#[derive(Default)]
+pub struct Hma;
+impl Hma {
+    pub fn new() -> Self { Hma }
+
+    // WMA(sqrt(n)) of 2 * WMA(n/2) - WMA(n)
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        let half = (period / 2).max(1);
+        let sqrt = ((period as f64).sqrt().round() as usize).max(1);
+        if period == 0 || candles.len() < period { return result; }
+        let wma_half = WMA.calculate(candles, half);
+        let wma_full = WMA.calculate(candles, period);
+        // The raw series starts where the full WMA does, so it has no gaps to smooth over
+        let start = period - 1;
+        let raw: Vec<Candle> = (start..candles.len())
+            .map(|i| Candle::from_close(candles[i].timestamp, 2.0 * wma_half[i].unwrap_or(0.0) - wma_full[i].unwrap_or(0.0)))
+            .collect();
+        for (offset, value) in WMA.calculate(&raw, sqrt).into_iter().enumerate() {
+            result[start + offset] = value;
+        }
+        result
+    }
+}
+
+impl TechnicalIndicator for Hma {
+    fn name(&self) -> &'static str { "Hull Moving Average" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Hull moving average built from weighted averages for low lag" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(14)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize;
+        period + ((period as f64).sqrt().round() as usize).max(1) - 1
+    }
+}
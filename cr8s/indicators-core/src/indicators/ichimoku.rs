@@ -0,0 +1,60 @@
+// src/indicators/ichimoku.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct Ichimoku;
+impl Ichimoku {
+    pub fn new() -> Self { Ichimoku }
+
+    // Midpoint of the highest high and lowest low over each trailing window
+    fn midpoint(candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &candles[i+1-period..=i];
+            let high = window.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+            let low = window.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+            result[i] = Some((high + low) / 2.0);
+        }
+        result
+    }
+
+    // Conversion line (Tenkan-sen)
+    pub(crate) fn calculate(&self, candles:&[Candle], conversion_period:usize) -> Vec<Option<f64>> {
+        Self::midpoint(candles, conversion_period)
+    }
+
+    fn period(options:&IndicatorOptions, name:&str, default:u64) -> usize {
+        options.values.get(name).and_then(|v| v.as_u64()).unwrap_or(default) as usize
+    }
+}
+
+impl TechnicalIndicator for Ichimoku {
+    fn name(&self) -> &'static str { "Ichimoku Kinko Hyo" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Conversion, base and cloud lines describing trend, momentum and support" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "conversion_period".into(), param_type: "int".into(), default_value: json!(9) },
+            IndicatorParam { name: "base_period".into(), param_type: "int".into(), default_value: json!(26) },
+            IndicatorParam { name: "leading_span_b_period".into(), param_type: "int".into(), default_value: json!(52) },
+        ]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        self.calculate(candles, Self::period(options, "conversion_period", 9))
+    }
+    // Cloud spans are given at the bar they're computed on, not displaced forward
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let conversion = self.compute(candles, options);
+        let base = Self::midpoint(candles, Self::period(options, "base_period", 26));
+        let span_a = conversion.iter().zip(base.iter()).map(|(c, b)| Some(((*c)? + (*b)?) / 2.0)).collect();
+        let span_b = Self::midpoint(candles, Self::period(options, "leading_span_b_period", 52));
+        let mut out = HashMap::new();
+        out.insert("value".to_string(), conversion);
+        out.insert("base".to_string(), base);
+        out.insert("leading_span_a".to_string(), span_a);
+        out.insert("leading_span_b".to_string(), span_b);
+        out
+    }
+}
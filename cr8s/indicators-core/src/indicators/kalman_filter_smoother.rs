@@ -0,0 +1,41 @@
+// src/indicators/kalman_filter_smoother.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct KalmanFilterSmoother;
+impl KalmanFilterSmoother {
+    pub fn new() -> Self { KalmanFilterSmoother }
+
+    // One-dimensional filter over the closes, starting from the first close
+    pub(crate) fn calculate(&self, candles:&[Candle], process_variance:f64, measurement_variance:f64) -> Vec<Option<f64>> {
+        let Some(first) = candles.first() else { return vec![]; };
+        let mut x = first.close;
+        let mut p = 1.0;
+        candles.iter().map(|c| {
+            p += process_variance;
+            let gain = p / (p + measurement_variance);
+            x += gain * (c.close - x);
+            p *= 1.0 - gain;
+            Some(x)
+        }).collect()
+    }
+}
+
+impl TechnicalIndicator for KalmanFilterSmoother {
+    fn name(&self) -> &'static str { "Kalman Filter Smoother" }
+    fn group(&self) -> &'static str { "Filter" }
+    fn description(&self) -> &'static str { "Kalman filter estimate of the underlying price level" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name:"process_variance".into(), param_type:"float".into(), default_value: json!(1.0)},
+            IndicatorParam { name:"measurement_variance".into(), param_type:"float".into(), default_value: json!(1.0)},
+        ]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let process_variance = options.values.get("process_variance").and_then(|v|v.as_f64()).unwrap_or(1.0);
+        let measurement_variance = options.values.get("measurement_variance").and_then(|v|v.as_f64()).unwrap_or(1.0);
+        self.calculate(candles, process_variance, measurement_variance)
+    }
+}
@@ -0,0 +1,43 @@
+// src/indicators/kama.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct Kama;
+impl Kama {
+    pub fn new() -> Self { Kama }
+
+    // Smoothing moves between a 2-bar and a 30-bar EMA with the efficiency ratio
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        if period == 0 || candles.len() <= period { return result; }
+        let (fast, slow) = (2.0 / 3.0, 2.0 / 31.0);
+        let mut prev = candles[period-1].close;
+        for i in period..candles.len() {
+            let change = (candles[i].close - candles[i-period].close).abs();
+            let volatility: f64 = candles[i+1-period..=i].windows(2).map(|w| (w[1].close - w[0].close).abs()).sum();
+            let er = if volatility != 0.0 { change / volatility } else { 0.0 };
+            let sc = (er * (fast - slow) + slow).powi(2);
+            prev += sc * (candles[i].close - prev);
+            result[i] = Some(prev);
+        }
+        result
+    }
+}
+
+impl TechnicalIndicator for Kama {
+    fn name(&self) -> &'static str { "Kaufman's Adaptive Moving Average" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Moving average that speeds up in trends and slows down in noise" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(10)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(10) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(10) as usize + 1
+    }
+}
@@ -0,0 +1,50 @@
+// src/indicators/keltner_channels.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct KeltnerChannels;
+impl KeltnerChannels {
+    pub fn new() -> Self { KeltnerChannels }
+
+    // (middle, upper, lower): EMA of closes with bands `multiplier` ATRs either side
+    pub(crate) fn calculate(&self, candles: &[Candle], ema_period: usize, atr_period: usize, multiplier: f64) -> [Vec<Option<f64>>; 3] {
+        use crate::indicators::{ATR, EMA};
+        let middle = EMA.calculate(candles, ema_period);
+        let atr = ATR.calculate(candles, atr_period);
+        let band = |sign: f64| -> Vec<Option<f64>> {
+            middle.iter().zip(&atr).map(|(m, a)| Some((*m)? + sign * multiplier * (*a)?)).collect()
+        };
+        let (upper, lower) = (band(1.0), band(-1.0));
+        [middle, upper, lower]
+    }
+
+    fn settings(options: &IndicatorOptions) -> (usize, usize, f64) {
+        let get = |name: &str, default: u64| options.values.get(name).and_then(|v| v.as_u64()).unwrap_or(default) as usize;
+        let multiplier = options.values.get("multiplier").and_then(|v| v.as_f64()).unwrap_or(2.0);
+        (get("ema_period", 20), get("atr_period", 10), multiplier)
+    }
+}
+impl TechnicalIndicator for KeltnerChannels {
+    fn name(&self) -> &'static str { "Keltner Channels" }
+    fn group(&self) -> &'static str { "Volatility" }
+    fn description(&self) -> &'static str { "EMA with bands a multiple of the average true range above and below" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "ema_period".into(), param_type: "int".into(), default_value: json!(20) },
+            IndicatorParam { name: "atr_period".into(), param_type: "int".into(), default_value: json!(10) },
+            IndicatorParam { name: "multiplier".into(), param_type: "float".into(), default_value: json!(2.0) },
+        ]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let (ema_period, atr_period, multiplier) = Self::settings(options);
+        let [middle, _, _] = self.calculate(candles, ema_period, atr_period, multiplier);
+        middle
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (ema_period, atr_period, multiplier) = Self::settings(options);
+        let [middle, upper, lower] = self.calculate(candles, ema_period, atr_period, multiplier);
+        HashMap::from([("middle".to_string(), middle), ("upper".to_string(), upper), ("lower".to_string(), lower)])
+    }
+}
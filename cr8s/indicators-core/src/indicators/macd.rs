@@ -1,12 +1,15 @@
 // src/indicators/macd.rs
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
+use std::collections::HashMap;
 
+#[derive(Default)]
 pub struct MACD;
 impl MACD {
     pub fn new() -> Self { MACD }
 
-    pub(crate) fn calculate(&self, candles: &[Candle], short_period: usize, long_period: usize, signal_period: usize) -> Vec<Option<f64>> {
+    // Fast EMA minus slow EMA
+    pub(crate) fn macd_line(&self, candles: &[Candle], short_period: usize, long_period: usize) -> Vec<Option<f64>> {
         use crate::indicators::EMA;
         let ema_short = EMA.calculate(candles, short_period);
         let ema_long = EMA.calculate(candles, long_period);
@@ -19,7 +22,11 @@ impl MACD {
             };
             macd_line.push(macd);
         }
-        
+        macd_line
+    }
+
+    pub(crate) fn calculate(&self, candles: &[Candle], short_period: usize, long_period: usize, signal_period: usize) -> Vec<Option<f64>> {
+        let macd_line = self.macd_line(candles, short_period, long_period);
 
         // Compute signal line
         let mut signal_line = Vec::with_capacity(candles.len());
@@ -32,11 +39,20 @@ impl MACD {
 
         signal_line
     }
+
+    fn periods(options: &IndicatorOptions) -> (usize, usize, usize) {
+        let short_period = options.values.get("short_period").and_then(|v| v.as_u64()).unwrap_or(12) as usize;
+        let long_period = options.values.get("long_period").and_then(|v| v.as_u64()).unwrap_or(26) as usize;
+        let signal_period = options.values.get("signal_period").and_then(|v| v.as_u64()).unwrap_or(9) as usize;
+        (short_period, long_period, signal_period)
+    }
 }
 
 impl TechnicalIndicator for MACD {
     fn name(&self) -> &'static str { "MACD" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Difference between fast and slow EMAs with a signal line" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![
             IndicatorParam { name: "short_period".into(), param_type: "int".into(), default_value: json!(12) },
@@ -46,9 +62,20 @@ impl TechnicalIndicator for MACD {
     }
 
     fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
-        let short_period = options.values.get("short_period").and_then(|v| v.as_u64()).unwrap_or(12) as usize;
-        let long_period = options.values.get("long_period").and_then(|v| v.as_u64()).unwrap_or(26) as usize;
-        let signal_period = options.values.get("signal_period").and_then(|v| v.as_u64()).unwrap_or(9) as usize;
+        let (short_period, long_period, signal_period) = Self::periods(options);
         self.calculate(candles, short_period, long_period, signal_period)
     }
+
+    // "value" stays the signal line; "macd" is the fast-minus-slow line it smooths
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (short_period, long_period, signal_period) = Self::periods(options);
+        let macd = self.macd_line(candles, short_period, long_period);
+        let signal = self.calculate(candles, short_period, long_period, signal_period);
+        let histogram = macd.iter().zip(signal.iter()).map(|(m, s)| Some((*m)? - (*s)?)).collect();
+        let mut out = HashMap::new();
+        out.insert("macd".to_string(), macd);
+        out.insert("value".to_string(), signal);
+        out.insert("histogram".to_string(), histogram);
+        out
+    }
 }
@@ -0,0 +1,42 @@
+// src/indicators/mfi.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct MFI;
+impl MFI {
+    pub fn new() -> Self { MFI }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let typical: Vec<f64> = candles.iter().map(|c| (c.high + c.low + c.close) / 3.0).collect();
+        let mut positive = vec![0.0; candles.len()];
+        let mut negative = vec![0.0; candles.len()];
+        for i in 1..candles.len() {
+            let flow = typical[i] * candles[i].volume.unwrap_or(0.0);
+            if typical[i] > typical[i-1] { positive[i] = flow; } else if typical[i] < typical[i-1] { negative[i] = flow; }
+        }
+        let mut mfi = vec![None; candles.len()];
+        for i in period..candles.len() {
+            let pos: f64 = positive[i+1-period..=i].iter().sum();
+            let neg: f64 = negative[i+1-period..=i].iter().sum();
+            mfi[i] = Some(if neg == 0.0 { 100.0 } else { 100.0 - 100.0 / (1.0 + pos / neg) });
+        }
+        mfi
+    }
+}
+
+impl TechnicalIndicator for MFI {
+    fn name(&self) -> &'static str { "Money Flow Index" }
+    fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Volume-weighted RSI using typical price" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(14)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(14) as usize + 1
+    }
+}
@@ -0,0 +1,285 @@
+pub mod ema; 
+pub mod rsi; 
+pub mod sma;
+pub mod macd;
+pub mod bollinger_bands;
+pub mod vwap;
+pub mod atr;
+pub mod stochastic;
+pub mod cci;
+pub mod adx;
+pub mod parabolic_sar;
+pub mod obv;
+pub mod cmf;
+pub mod williams_r;
+pub mod ichimoku;
+pub mod momentum;
+pub mod tema;
+pub mod dema;
+pub mod kama;
+pub mod wma;
+pub mod hma;
+pub mod frama;
+pub mod chandelier_exit;
+pub mod trix;
+pub mod mfi;
+pub mod force_index;
+pub mod ease_of_movement;
+pub mod accum_dist_line;
+pub mod price_volume_trend;
+pub mod volume_oscillator;
+pub mod ultimate_oscillator;
+pub mod detrended_price_oscillator;
+pub mod roc;
+pub mod z_score;
+pub mod gmma;
+pub mod schaff_trend_cycle;
+pub mod fibonacci_retracement;
+pub mod kalman_filter_smoother; 
+pub mod heikin_ashi_slope; 
+pub mod percent_b; 
+pub mod crossover;
+pub mod alligator;
+pub mod fractals;
+pub mod gann_hilo;
+pub mod supertrend;
+pub mod keltner_channels;
+pub mod donchian_channels;
+pub mod aroon;
+pub mod pivot_points;
+pub mod historical_volatility;
+
+pub use sma::SMA;
+pub use ema::EMA;
+pub use rsi::RSI;
+pub use macd::MACD;
+pub use bollinger_bands::BollingerBands;
+pub use vwap::VWAP;
+pub use atr::ATR;
+pub use stochastic::Stochastic;
+pub use cci::CCI;
+pub use adx::ADX;
+pub use parabolic_sar::ParabolicSAR;
+pub use obv::OBV;
+pub use cmf::CMF;
+pub use williams_r::WilliamsR;
+pub use ichimoku::Ichimoku;
+pub use momentum::Momentum;
+pub use tema::Tema;
+pub use dema::Dema;
+pub use kama::Kama;
+pub use wma::WMA;
+pub use hma::Hma;
+pub use frama::Frama;
+pub use chandelier_exit::ChandelierExit;
+pub use trix::TRIX;
+pub use mfi::MFI;
+pub use force_index::ForceIndex;
+pub use ease_of_movement::EaseOfMovement;
+pub use accum_dist_line::AccumDistLine;
+pub use price_volume_trend::PriceVolumeTrend;
+pub use volume_oscillator::VolumeOscillator;
+pub use ultimate_oscillator::UltimateOscillator;
+pub use detrended_price_oscillator::DetrendedPriceOscillator;
+pub use roc::RateOfChange;
+pub use z_score::ZScore;
+pub use gmma::GMMA;
+pub use schaff_trend_cycle::SchaffTrendCycle;
+pub use fibonacci_retracement::FibonacciRetracement;
+pub use heikin_ashi_slope::HeikinAshiSlope;
+pub use kalman_filter_smoother::KalmanFilterSmoother;
+pub use percent_b::PercentB;
+pub use alligator::Alligator;
+pub use fractals::Fractals;
+pub use gann_hilo::GannHiLo;
+pub use supertrend::SuperTrend;
+pub use keltner_channels::KeltnerChannels;
+pub use donchian_channels::DonchianChannels;
+pub use aroon::Aroon;
+pub use pivot_points::PivotPoints;
+pub use historical_volatility::{historical_volatility, realized_volatility, HistoricalVolatility, VolEstimator};
+
+
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<f64>,
+}
+
+impl Candle {
+    // Flat candle for close-only series: OHLC all equal the close, no volume
+    pub fn from_close(timestamp: i64, close: f64) -> Self {
+        Candle { timestamp, open: close, high: close, low: close, close, volume: None }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndicatorParam {
+    pub name: String,
+    pub param_type: String, // "int", "float", "bool", "string"
+    pub default_value: serde_json::Value,
+}
+
+impl IndicatorParam {
+    // Whether `value` fits the declared type. Integer params are non-negative, and periods at least 1.
+    pub fn check(&self, value: &serde_json::Value) -> Result<(), String> {
+        let ok = match self.param_type.as_str() {
+            "int" => match value.as_u64() {
+                Some(0) if self.name.ends_with("period") => return Err(format!("{} must be at least 1", self.name)),
+                Some(_) => true,
+                None => false,
+            },
+            "float" => value.as_f64().is_some_and(f64::is_finite),
+            "bool" => value.is_boolean(),
+            "string" => value.is_string(),
+            _ => true,
+        };
+        if ok {
+            Ok(())
+        } else {
+            let expected = match self.param_type.as_str() {
+                "int" => "a non-negative integer",
+                "float" => "a number",
+                "bool" => "true or false",
+                _ => "a string",
+            };
+            Err(format!("{} must be {}, got {}", self.name, expected, value))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndicatorOptions {
+    pub values: HashMap<String, serde_json::Value>,
+}
+
+// An option an indicator won't accept, and the parameter it was given for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamError {
+    pub param: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// Checks options against the indicator's declared params: every name must be one of them and every
+// value must fit its type. Indicators compute() with defaults for anything they can't read, so
+// without this a misspelt or mistyped option is silently ignored.
+pub fn validate_options(indicator: &dyn TechnicalIndicator, options: &IndicatorOptions) -> Result<(), ParamError> {
+    let params = indicator.params();
+    let mut names: Vec<&String> = options.values.keys().collect();
+    names.sort();
+    for name in names {
+        let Some(param) = params.iter().find(|p| &p.name == name) else {
+            let accepted: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+            let message = if accepted.is_empty() {
+                format!("{} takes no parameters, got {}", indicator.name(), name)
+            } else {
+                format!("{} has no parameter {}; accepted: {}", indicator.name(), name, accepted.join(", "))
+            };
+            return Err(ParamError { param: name.clone(), message });
+        };
+        param.check(&options.values[name]).map_err(|message| ParamError { param: name.clone(), message })?;
+    }
+    Ok(())
+}
+
+pub trait TechnicalIndicator: Sync + Send {
+    fn name(&self) -> &'static str;
+    fn group(&self) -> &'static str; // e.g., "Trend", "Volume", "Oscillator"
+    fn description(&self) -> &'static str;
+    fn params(&self) -> Vec<IndicatorParam>;
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>>;
+
+    // Named output series; single-line indicators expose just "value"
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let mut out = HashMap::new();
+        out.insert("value".to_string(), self.compute(candles, options));
+        out
+    }
+
+    // True when only closes are read, so the indicator can run on a bare price series
+    fn close_only(&self) -> bool {
+        false
+    }
+
+    // Candles needed before the first value: the longest integer param, as given in `options` or
+    // else its default
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        self.params()
+            .iter()
+            .filter(|p| p.param_type == "int")
+            .filter_map(|p| options.values.get(&p.name).unwrap_or(&p.default_value).as_u64())
+            .max()
+            .unwrap_or(0) as usize
+    }
+}
+
+// An indicator together with the options it runs with, for callers that configure an indicator
+// once and compute it many times
+#[derive(Clone)]
+pub struct BoundIndicator {
+    pub indicator: Arc<dyn TechnicalIndicator>,
+    pub options: IndicatorOptions,
+    // Which compute_multi series compute() returns; None is the indicator's own compute()
+    pub output: Option<String>,
+}
+
+impl BoundIndicator {
+    pub fn new(indicator: impl TechnicalIndicator + 'static) -> Self {
+        BoundIndicator { indicator: Arc::new(indicator), options: IndicatorOptions::default(), output: None }
+    }
+
+    // A registry indicator with the given options, or None for an unknown key
+    pub fn from_registry(key: &str, values: HashMap<String, serde_json::Value>) -> Option<Self> {
+        crate::INDICATOR_REGISTRY.get(key).map(|indicator| BoundIndicator {
+            indicator: Arc::clone(indicator),
+            options: IndicatorOptions { values },
+            output: None,
+        })
+    }
+
+    pub fn with_param(mut self, name: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.options.values.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn with_output(mut self, output: &str) -> Self {
+        self.output = Some(output.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.indicator.name()
+    }
+
+    pub fn warmup(&self) -> usize {
+        self.indicator.warmup(&self.options)
+    }
+
+    pub fn compute(&self, candles: &[Candle]) -> Vec<Option<f64>> {
+        match &self.output {
+            Some(output) => self.indicator
+                .compute_multi(candles, &self.options)
+                .remove(output)
+                .unwrap_or_else(|| vec![None; candles.len()]),
+            None => self.indicator.compute(candles, &self.options),
+        }
+    }
+
+    pub fn compute_multi(&self, candles: &[Candle]) -> HashMap<String, Vec<Option<f64>>> {
+        self.indicator.compute_multi(candles, &self.options)
+    }
+}
\ No newline at end of file
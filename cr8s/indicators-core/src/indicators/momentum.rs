@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct Momentum;
 impl Momentum {
     pub fn new() -> Self { Momentum }
@@ -18,6 +19,8 @@ impl Momentum {
 impl TechnicalIndicator for Momentum {
     fn name(&self) -> &'static str { "Momentum" }
     fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Difference between the close and the close a number of bars ago" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(10) }]
     }
@@ -1,7 +1,7 @@
 // src/indicators/obv.rs
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
 
+#[derive(Default)]
 pub struct OBV;
 impl OBV {
     pub fn new() -> Self { OBV }
@@ -22,6 +22,7 @@ impl OBV {
 impl TechnicalIndicator for OBV {
     fn name(&self) -> &'static str { "On-Balance Volume" }
     fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Cumulative volume added on up closes and subtracted on down closes" }
     fn params(&self) -> Vec<IndicatorParam> { vec![] }
     fn compute(&self, candles: &[Candle], _options: &IndicatorOptions) -> Vec<Option<f64>> {
         self.calculate(candles)
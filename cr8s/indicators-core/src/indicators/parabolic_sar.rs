@@ -2,17 +2,37 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct ParabolicSAR;
 impl ParabolicSAR {
     pub fn new() -> Self { ParabolicSAR }
 
     pub(crate) fn calculate(&self, candles: &[Candle], step: f64, max_af: f64) -> Vec<Option<f64>> {
         let mut sar = vec![None; candles.len()];
-        if candles.is_empty() { return sar; }
-        // simple stub implementation
-        sar[0] = Some(candles[0].low);
+        if candles.len() < 2 { return sar; }
+        // Starts long, with the first bar's high as the stop and its low as the extreme point
+        let mut is_long = true;
+        let mut af = step;
+        let mut ep = candles[0].low;
+        let mut value = candles[0].high;
+        sar[0] = Some(value);
         for i in 1..candles.len() {
-            sar[i] = Some(candles[i].low + (candles[i].high - candles[i].low) * step.min(max_af));
+            value += af * (ep - value);
+            let c = &candles[i];
+            if is_long && c.low < value || !is_long && c.high > value {
+                // Stop hit: reverse, restarting from the last extreme point
+                is_long = !is_long;
+                value = ep;
+                ep = if is_long { c.low } else { c.high };
+                af = step;
+                sar[i] = Some(value);
+            } else if is_long {
+                if c.high > ep { ep = c.high; af = (af + step).min(max_af); }
+                sar[i] = Some(value.min(c.low));
+            } else {
+                if c.low < ep { ep = c.low; af = (af + step).min(max_af); }
+                sar[i] = Some(value.max(c.high));
+            }
         }
         sar
     }
@@ -21,6 +41,7 @@ impl ParabolicSAR {
 impl TechnicalIndicator for ParabolicSAR {
     fn name(&self) -> &'static str { "Parabolic SAR" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Trailing stop-and-reverse points that accelerate with the trend" }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![
             IndicatorParam { name: "step".into(), param_type: "float".into(), default_value: json!(0.02) },
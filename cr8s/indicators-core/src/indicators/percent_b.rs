@@ -0,0 +1,39 @@
+// src/indicators/percent_b.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct PercentB;
+impl PercentB {
+    pub fn new() -> Self { PercentB }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize, std_dev:f64) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &candles[i+1-period..=i];
+            let mean = window.iter().map(|c| c.close).sum::<f64>() / period as f64;
+            let stddev = (window.iter().map(|c| (c.close - mean).powi(2)).sum::<f64>() / period as f64).sqrt();
+            let (upper, lower) = (mean + std_dev * stddev, mean - std_dev * stddev);
+            if upper != lower { result[i] = Some((candles[i].close - lower) / (upper - lower)); }
+        }
+        result
+    }
+}
+
+impl TechnicalIndicator for PercentB {
+    fn name(&self) -> &'static str { "%B" }
+    fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Position of the close within the Bollinger Bands" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(20)},
+            IndicatorParam { name:"std_dev".into(), param_type:"float".into(), default_value: json!(2.0)},
+        ]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(20) as usize;
+        let std_dev = options.values.get("std_dev").and_then(|v|v.as_f64()).unwrap_or(2.0);
+        self.calculate(candles, period, std_dev)
+    }
+}
@@ -0,0 +1,109 @@
+// src/indicators/pivot_points.rs
+use crate::resample::{bucket, Resolution, Session};
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+const LEVELS: [&str; 7] = ["pp", "r1", "r2", "r3", "s1", "s2", "s3"];
+
+#[derive(Clone, Copy)]
+pub enum PivotFormula {
+    Classic,
+    Fibonacci,
+    Camarilla,
+}
+
+#[derive(Default)]
+pub struct PivotPoints;
+impl PivotPoints {
+    pub fn new() -> Self { PivotPoints }
+
+    // Pivot and three support/resistance levels from one period's high, low and close,
+    // in LEVELS order
+    pub(crate) fn levels(formula: PivotFormula, high: f64, low: f64, close: f64) -> [f64; 7] {
+        let pp = (high + low + close) / 3.0;
+        let range = high - low;
+        match formula {
+            PivotFormula::Classic => [
+                pp,
+                2.0 * pp - low, pp + range, high + 2.0 * (pp - low),
+                2.0 * pp - high, pp - range, low - 2.0 * (high - pp),
+            ],
+            PivotFormula::Fibonacci => [
+                pp,
+                pp + 0.382 * range, pp + 0.618 * range, pp + range,
+                pp - 0.382 * range, pp - 0.618 * range, pp - range,
+            ],
+            PivotFormula::Camarilla => [
+                pp,
+                close + range * 1.1 / 12.0, close + range * 1.1 / 6.0, close + range * 1.1 / 4.0,
+                close - range * 1.1 / 12.0, close - range * 1.1 / 6.0, close - range * 1.1 / 4.0,
+            ],
+        }
+    }
+
+    // Every bar gets the levels of the previous (UTC) day or week; the first period has none
+    pub(crate) fn calculate(&self, candles: &[Candle], formula: PivotFormula, period: Resolution) -> Vec<Vec<Option<f64>>> {
+        let mut out = vec![vec![None; candles.len()]; LEVELS.len()];
+        let mut current: Option<(i64, f64, f64, f64)> = None; // period key, high, low, close
+        let mut previous: Option<[f64; 7]> = None;
+
+        for (i, candle) in candles.iter().enumerate() {
+            let key = bucket(candle.timestamp, period, &Session::UTC);
+            current = match current {
+                Some((k, high, low, _)) if k == key => Some((k, high.max(candle.high), low.min(candle.low), candle.close)),
+                Some((_, high, low, close)) => {
+                    previous = Some(Self::levels(formula, high, low, close));
+                    Some((key, candle.high, candle.low, candle.close))
+                }
+                None => Some((key, candle.high, candle.low, candle.close)),
+            };
+            if let Some(levels) = previous {
+                for (series, level) in out.iter_mut().zip(levels) {
+                    series[i] = Some(level);
+                }
+            }
+        }
+        out
+    }
+
+    fn settings(options: &IndicatorOptions) -> (PivotFormula, Resolution) {
+        let text = |name: &str| options.values.get(name).and_then(|v| v.as_str()).map(str::to_lowercase);
+        let formula = match text("formula").as_deref() {
+            Some("fibonacci") => PivotFormula::Fibonacci,
+            Some("camarilla") => PivotFormula::Camarilla,
+            _ => PivotFormula::Classic,
+        };
+        let period = match text("timeframe").as_deref() {
+            Some("weekly") => Resolution::Weeks(1),
+            _ => Resolution::Days(1),
+        };
+        (formula, period)
+    }
+}
+impl TechnicalIndicator for PivotPoints {
+    fn name(&self) -> &'static str { "Pivot Points" }
+    fn group(&self) -> &'static str { "Support/Resistance" }
+    fn description(&self) -> &'static str { "Pivot with three support and resistance levels from the previous day's or week's range" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "formula".into(), param_type: "string".into(), default_value: json!("classic") }, // classic, fibonacci, camarilla
+            IndicatorParam { name: "timeframe".into(), param_type: "string".into(), default_value: json!("daily") }, // daily, weekly
+        ]
+    }
+    // A full previous period has to close first, counted in daily bars
+    fn warmup(&self, options: &IndicatorOptions) -> usize {
+        match Self::settings(options).1 {
+            Resolution::Weeks(_) => 2 * 5,
+            _ => 2,
+        }
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let (formula, period) = Self::settings(options);
+        self.calculate(candles, formula, period).swap_remove(0)
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (formula, period) = Self::settings(options);
+        LEVELS.iter().map(|name| name.to_string()).zip(self.calculate(candles, formula, period)).collect()
+    }
+}
@@ -0,0 +1,32 @@
+// src/indicators/price_volume_trend.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+
+#[derive(Default)]
+pub struct PriceVolumeTrend;
+impl PriceVolumeTrend {
+    pub fn new() -> Self { PriceVolumeTrend }
+
+    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> {
+        let mut pvt = Vec::with_capacity(candles.len());
+        if candles.is_empty() { return pvt; }
+        let mut cum_pvt = 0.0;
+        pvt.push(Some(0.0));
+        for i in 1..candles.len() {
+            let prev_close = candles[i-1].close;
+            // A zero close has no percentage change, so the total carries over
+            if prev_close != 0.0 {
+                cum_pvt += candles[i].volume.unwrap_or(0.0) * (candles[i].close - prev_close) / prev_close;
+            }
+            pvt.push(Some(cum_pvt));
+        }
+        pvt
+    }
+}
+
+impl TechnicalIndicator for PriceVolumeTrend {
+    fn name(&self) -> &'static str { "Price Volume Trend" }
+    fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Cumulative volume weighted by percentage price change" }
+    fn params(&self) -> Vec<IndicatorParam> { vec![] }
+    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
+}
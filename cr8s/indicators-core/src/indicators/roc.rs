@@ -0,0 +1,35 @@
+// src/indicators/roc.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct RateOfChange;
+impl RateOfChange {
+    pub fn new() -> Self { RateOfChange }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut roc = vec![None; candles.len()];
+        for i in period..candles.len() {
+            let base = candles[i-period].close;
+            if base != 0.0 { roc[i] = Some((candles[i].close - base) / base * 100.0); }
+        }
+        roc
+    }
+}
+
+impl TechnicalIndicator for RateOfChange {
+    fn name(&self) -> &'static str { "Rate of Change" }
+    fn group(&self) -> &'static str { "Momentum" }
+    fn description(&self) -> &'static str { "Percentage change of the close over the period" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(12)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(12) as usize;
+        self.calculate(candles, period)
+    }
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(12) as usize + 1
+    }
+}
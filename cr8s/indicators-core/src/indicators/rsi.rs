@@ -1,6 +1,7 @@
 use crate::indicators::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct RSI;
 
 impl RSI {
@@ -23,8 +24,8 @@ impl RSI {
             losses.push((-change).max(0.0));
 
             if i >= period {
-                let avg_gain = gains[(i + 1 - period)..=i].iter().sum::<f64>() / period as f64;
-                let avg_loss = losses[(i + 1 - period)..=i].iter().sum::<f64>() / period as f64;
+                let avg_gain = gains[(i - period)..i].iter().sum::<f64>() / period as f64;
+                let avg_loss = losses[(i - period)..i].iter().sum::<f64>() / period as f64;
 
                 let rs = if avg_loss == 0.0 {
                     100.0
@@ -49,6 +50,14 @@ impl TechnicalIndicator for RSI {
         "Oscillator"
     }
 
+    fn description(&self) -> &'static str {
+        "Ratio of average gains to losses, scaled to 0-100"
+    }
+
+    fn close_only(&self) -> bool {
+        true
+    }
+
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam {
             name: "period".to_string(),
@@ -0,0 +1,59 @@
+// src/indicators/schaff_trend_cycle.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::EMA;
+use serde_json::json;
+
+#[derive(Default)]
+pub struct SchaffTrendCycle;
+impl SchaffTrendCycle {
+    pub fn new() -> Self { SchaffTrendCycle }
+
+    // Stochastic of `values` over `cycle` bars, smoothed by half towards the previous result
+    fn smoothed_stochastic(values:&[Option<f64>], cycle:usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; values.len()];
+        let mut prev: Option<f64> = None;
+        for i in cycle.saturating_sub(1)..values.len() {
+            let Some(window) = values[i+1-cycle..=i].iter().copied().collect::<Option<Vec<f64>>>() else { continue; };
+            let low = window.iter().cloned().fold(f64::INFINITY, f64::min);
+            let high = window.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let stoch = if high > low { (window[cycle-1] - low) / (high - low) * 100.0 } else { prev.unwrap_or(0.0) };
+            let next = prev.map_or(stoch, |p| p + 0.5 * (stoch - p));
+            result[i] = Some(next);
+            prev = Some(next);
+        }
+        result
+    }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], short_period:usize, long_period:usize, cycle_period:usize) -> Vec<Option<f64>> {
+        let fast = EMA.calculate(candles, short_period);
+        let slow = EMA.calculate(candles, long_period);
+        let macd: Vec<Option<f64>> = fast.iter().zip(slow.iter()).map(|(f, s)| Some((*f)? - (*s)?)).collect();
+        Self::smoothed_stochastic(&Self::smoothed_stochastic(&macd, cycle_period), cycle_period)
+    }
+}
+
+impl TechnicalIndicator for SchaffTrendCycle {
+    fn name(&self) -> &'static str { "Schaff Trend Cycle" }
+    fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Stochastic of MACD, cycling between 0 and 100" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name:"short_period".into(), param_type:"int".into(), default_value: json!(23)},
+            IndicatorParam { name:"long_period".into(), param_type:"int".into(), default_value: json!(50)},
+            IndicatorParam { name:"cycle_period".into(), param_type:"int".into(), default_value: json!(10)},
+        ]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let short_period = options.values.get("short_period").and_then(|v|v.as_u64()).unwrap_or(23) as usize;
+        let long_period = options.values.get("long_period").and_then(|v|v.as_u64()).unwrap_or(50) as usize;
+        let cycle_period = options.values.get("cycle_period").and_then(|v|v.as_u64()).unwrap_or(10) as usize;
+        self.calculate(candles, short_period, long_period, cycle_period)
+    }
+    // The cycle stochastic runs twice over the MACD line
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        let long_period = options.values.get("long_period").and_then(|v|v.as_u64()).unwrap_or(50) as usize;
+        let cycle_period = options.values.get("cycle_period").and_then(|v|v.as_u64()).unwrap_or(10) as usize;
+        long_period + 2 * cycle_period
+    }
+}
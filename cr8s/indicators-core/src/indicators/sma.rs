@@ -1,6 +1,7 @@
 use crate::indicators::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct SMA;
 impl SMA {
     pub fn new() -> Self { SMA }
@@ -21,6 +22,8 @@ impl SMA {
 impl TechnicalIndicator for SMA {
     fn name(&self) -> &'static str { "Simple Moving Average" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Arithmetic mean of the closes over the period" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
@@ -2,6 +2,7 @@
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct Stochastic;
 impl Stochastic {
     pub fn new() -> Self { Stochastic }
@@ -23,6 +24,7 @@ impl Stochastic {
 impl TechnicalIndicator for Stochastic {
     fn name(&self) -> &'static str { "Stochastic Oscillator" }
     fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Position of the close within the high-low range over the period" }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "k_period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
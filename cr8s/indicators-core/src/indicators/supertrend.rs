@@ -0,0 +1,71 @@
+// src/indicators/supertrend.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct SuperTrend;
+impl SuperTrend {
+    pub fn new() -> Self { SuperTrend }
+
+    // (line, direction): the trailing band price is on the right side of, and +1 / -1 for up / down
+    pub(crate) fn calculate(&self, candles: &[Candle], period: usize, multiplier: f64) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+        use crate::indicators::ATR;
+        let atr = ATR.calculate(candles, period);
+        let mut line = vec![None; candles.len()];
+        let mut direction = vec![None; candles.len()];
+        let mut bands: Option<(f64, f64)> = None; // final (upper, lower)
+        let mut up = true;
+
+        for i in 0..candles.len() {
+            let Some(atr) = atr[i] else { continue };
+            let mid = (candles[i].high + candles[i].low) / 2.0;
+            let (basic_upper, basic_lower) = (mid + multiplier * atr, mid - multiplier * atr);
+            // Bands only tighten while price stays inside them
+            let (upper, lower) = match bands {
+                Some((prev_upper, prev_lower)) => {
+                    let prev_close = candles[i - 1].close;
+                    (
+                        if basic_upper < prev_upper || prev_close > prev_upper { basic_upper } else { prev_upper },
+                        if basic_lower > prev_lower || prev_close < prev_lower { basic_lower } else { prev_lower },
+                    )
+                }
+                None => (basic_upper, basic_lower),
+            };
+            if let Some((prev_upper, prev_lower)) = bands {
+                if up && candles[i].close < prev_lower { up = false; }
+                else if !up && candles[i].close > prev_upper { up = true; }
+            }
+            bands = Some((upper, lower));
+            line[i] = Some(if up { lower } else { upper });
+            direction[i] = Some(if up { 1.0 } else { -1.0 });
+        }
+        (line, direction)
+    }
+
+    fn settings(options: &IndicatorOptions) -> (usize, f64) {
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let multiplier = options.values.get("multiplier").and_then(|v| v.as_f64()).unwrap_or(3.0);
+        (period, multiplier)
+    }
+}
+impl TechnicalIndicator for SuperTrend {
+    fn name(&self) -> &'static str { "SuperTrend" }
+    fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "ATR trailing stop that flips sides when price closes through it" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(10) },
+            IndicatorParam { name: "multiplier".into(), param_type: "float".into(), default_value: json!(3.0) },
+        ]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let (period, multiplier) = Self::settings(options);
+        self.calculate(candles, period, multiplier).0
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (period, multiplier) = Self::settings(options);
+        let (line, direction) = self.calculate(candles, period, multiplier);
+        HashMap::from([("value".to_string(), line), ("direction".to_string(), direction)])
+    }
+}
@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct Tema;
 impl Tema {
     pub fn new() -> Self { Tema }
@@ -18,6 +19,8 @@ impl Tema {
 impl TechnicalIndicator for Tema {
     fn name(&self) -> &'static str { "Triple Exponential Moving Average" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Triple-smoothed EMA that further reduces lag" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
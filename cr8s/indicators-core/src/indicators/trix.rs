@@ -0,0 +1,58 @@
+// src/indicators/trix.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct TRIX;
+impl TRIX {
+    pub fn new() -> Self { TRIX }
+
+    // EMA seeded with the SMA of the first `period` values; None until then
+    fn ema(values:&[Option<f64>], period:usize) -> Vec<Option<f64>> {
+        let k = 2.0 / (period as f64 + 1.0);
+        let mut result = vec![None; values.len()];
+        let Some(start) = values.iter().position(|v| v.is_some()) else { return result; };
+        if values.len() < start + period { return result; }
+        let mut prev = values[start..start+period].iter().map(|v| v.unwrap_or(0.0)).sum::<f64>() / period as f64;
+        result[start+period-1] = Some(prev);
+        for i in start+period..values.len() {
+            if let Some(v) = values[i] {
+                prev = v * k + prev * (1.0 - k);
+                result[i] = Some(prev);
+            }
+        }
+        result
+    }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let closes: Vec<Option<f64>> = candles.iter().map(|c| Some(c.close)).collect();
+        let ema3 = Self::ema(&Self::ema(&Self::ema(&closes, period), period), period);
+        // Percent change of the triple-smoothed EMA from one bar to the next
+        let mut trix = vec![None; candles.len()];
+        for i in 1..candles.len() {
+            trix[i] = match (ema3[i], ema3[i-1]) {
+                (Some(curr), Some(prev)) if prev != 0.0 => Some((curr - prev) / prev * 100.0),
+                _ => None,
+            };
+        }
+        trix
+    }
+}
+
+impl TechnicalIndicator for TRIX {
+    fn name(&self) -> &'static str { "TRIX" }
+    fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Rate of change of a triple-smoothed EMA" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(15)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(15) as usize;
+        self.calculate(candles, period)
+    }
+    // Each of the three EMAs waits out its own period
+    fn warmup(&self, options:&IndicatorOptions) -> usize {
+        options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(15) as usize * 3
+    }
+}
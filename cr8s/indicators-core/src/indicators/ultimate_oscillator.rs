@@ -0,0 +1,78 @@
+// src/indicators/ultimate_oscillator.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::crossover::cross_level;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct UltimateOscillator;
+impl UltimateOscillator {
+    pub fn new() -> Self { UltimateOscillator }
+
+    pub(crate) fn calculate(&self, candles: &[Candle], short_period: usize, mid_period: usize, long_period: usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        // Periods aren't required to be ordered, so every window has to fit from the first value on
+        let longest = short_period.max(mid_period).max(long_period);
+        if candles.len() <= longest { return result; }
+
+        // Buying pressure and true range per bar, starting at the second candle
+        let mut bp = vec![0.0; candles.len()];
+        let mut tr = vec![0.0; candles.len()];
+        for i in 1..candles.len() {
+            let prev_close = candles[i-1].close;
+            let low = candles[i].low.min(prev_close);
+            let high = candles[i].high.max(prev_close);
+            bp[i] = candles[i].close - low;
+            tr[i] = high - low;
+        }
+
+        let average = |i: usize, period: usize| {
+            let tr_sum: f64 = tr[i+1-period..=i].iter().sum();
+            if tr_sum == 0.0 { 0.0 } else { bp[i+1-period..=i].iter().sum::<f64>() / tr_sum }
+        };
+        for i in longest..candles.len() {
+            let uo = 100.0 * (4.0 * average(i, short_period) + 2.0 * average(i, mid_period) + average(i, long_period)) / 7.0;
+            result[i] = Some(uo);
+        }
+        result
+    }
+
+    fn periods(options: &IndicatorOptions) -> (usize, usize, usize) {
+        let short_period = options.values.get("short_period").and_then(|v| v.as_u64()).unwrap_or(7) as usize;
+        let mid_period = options.values.get("mid_period").and_then(|v| v.as_u64()).unwrap_or(14) as usize;
+        let long_period = options.values.get("long_period").and_then(|v| v.as_u64()).unwrap_or(28) as usize;
+        (short_period, mid_period, long_period)
+    }
+}
+impl TechnicalIndicator for UltimateOscillator {
+    fn name(&self) -> &'static str { "Ultimate Oscillator" }
+    fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Weighted average of buying pressure over three timeframes" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "short_period".into(), param_type: "int".into(), default_value: json!(7) },
+            IndicatorParam { name: "mid_period".into(), param_type: "int".into(), default_value: json!(14) },
+            IndicatorParam { name: "long_period".into(), param_type: "int".into(), default_value: json!(28) },
+            IndicatorParam { name: "overbought".into(), param_type: "float".into(), default_value: json!(70.0) },
+            IndicatorParam { name: "oversold".into(), param_type: "float".into(), default_value: json!(30.0) },
+        ]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let (short_period, mid_period, long_period) = Self::periods(options);
+        self.calculate(candles, short_period, mid_period, long_period)
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (short_period, mid_period, long_period) = Self::periods(options);
+        let overbought = options.values.get("overbought").and_then(|v| v.as_f64()).unwrap_or(70.0);
+        let oversold = options.values.get("oversold").and_then(|v| v.as_f64()).unwrap_or(30.0);
+        let value = self.calculate(candles, short_period, mid_period, long_period);
+
+        let mut out = HashMap::new();
+        out.insert("overbought".to_string(), vec![Some(overbought); value.len()]);
+        out.insert("oversold".to_string(), vec![Some(oversold); value.len()]);
+        out.insert("overbought_cross".to_string(), cross_level(&value, overbought));
+        out.insert("oversold_cross".to_string(), cross_level(&value, oversold));
+        out.insert("value".to_string(), value);
+        out
+    }
+}
@@ -0,0 +1,74 @@
+// src/indicators/volume_oscillator.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use crate::indicators::crossover::cross_level;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct VolumeOscillator;
+impl VolumeOscillator {
+    pub fn new() -> Self { VolumeOscillator }
+
+    pub(crate) fn calculate(&self, candles: &[Candle], short_period: usize, long_period: usize) -> Vec<Option<f64>> {
+        let volume_ema = |period: usize| {
+            let k = 2.0 / (period as f64 + 1.0);
+            let mut prev = 0.0;
+            let mut result = Vec::with_capacity(candles.len());
+            for (i, c) in candles.iter().enumerate() {
+                let vol = c.volume.unwrap_or(0.0);
+                prev = if i == 0 { vol } else { vol * k + prev * (1.0 - k) };
+                result.push(if i + 1 >= period { Some(prev) } else { None });
+            }
+            result
+        };
+        let short = volume_ema(short_period);
+        let long = volume_ema(long_period);
+        short.iter().zip(long.iter()).map(|(s, l)| match (s, l) {
+            (Some(s), Some(l)) if *l != 0.0 => Some((s - l) / l * 100.0),
+            _ => None,
+        }).collect()
+    }
+
+    fn periods(options: &IndicatorOptions) -> (usize, usize, usize) {
+        let short_period = options.values.get("short_period").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
+        let long_period = options.values.get("long_period").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let signal_period = options.values.get("signal_period").and_then(|v| v.as_u64()).unwrap_or(9) as usize;
+        (short_period, long_period, signal_period)
+    }
+}
+impl TechnicalIndicator for VolumeOscillator {
+    fn name(&self) -> &'static str { "Volume Oscillator" }
+    fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Percentage difference between a short and a long volume average" }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![
+            IndicatorParam { name: "short_period".into(), param_type: "int".into(), default_value: json!(5) },
+            IndicatorParam { name: "long_period".into(), param_type: "int".into(), default_value: json!(10) },
+            IndicatorParam { name: "signal_period".into(), param_type: "int".into(), default_value: json!(9) },
+        ]
+    }
+    fn compute(&self, candles: &[Candle], options: &IndicatorOptions) -> Vec<Option<f64>> {
+        let (short_period, long_period, _) = Self::periods(options);
+        self.calculate(candles, short_period, long_period)
+    }
+    fn compute_multi(&self, candles: &[Candle], options: &IndicatorOptions) -> HashMap<String, Vec<Option<f64>>> {
+        let (short_period, long_period, signal_period) = Self::periods(options);
+        let value = self.calculate(candles, short_period, long_period);
+
+        // Signal line: simple average of the oscillator over signal_period
+        let mut signal = vec![None; value.len()];
+        for i in 0..value.len() {
+            if i + 1 < signal_period { continue; }
+            let window: Option<Vec<f64>> = value[i+1-signal_period..=i].iter().copied().collect();
+            signal[i] = window.map(|w| w.iter().sum::<f64>() / signal_period as f64);
+        }
+        let histogram = value.iter().zip(signal.iter()).map(|(v, s)| Some((*v)? - (*s)?)).collect();
+
+        let mut out = HashMap::new();
+        out.insert("zero_cross".to_string(), cross_level(&value, 0.0));
+        out.insert("value".to_string(), value);
+        out.insert("signal".to_string(), signal);
+        out.insert("histogram".to_string(), histogram);
+        out
+    }
+}
@@ -1,7 +1,7 @@
 // src/indicators/vwap.rs
 use crate::{TechnicalIndicator, IndicatorParam, IndicatorOptions, Candle};
-use serde_json::json;
 
+#[derive(Default)]
 pub struct VWAP;
 impl VWAP {
     pub fn new() -> Self { VWAP }
@@ -26,6 +26,7 @@ impl VWAP {
 impl TechnicalIndicator for VWAP {
     fn name(&self) -> &'static str { "VWAP" }
     fn group(&self) -> &'static str { "Volume" }
+    fn description(&self) -> &'static str { "Cumulative volume-weighted average price" }
     fn params(&self) -> Vec<IndicatorParam> { vec![] }
     fn compute(&self, candles: &[Candle], _options: &IndicatorOptions) -> Vec<Option<f64>> {
         self.calculate(candles)
@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct WilliamsR;
 impl WilliamsR {
     pub fn new() -> Self { WilliamsR }
@@ -22,6 +23,7 @@ impl WilliamsR {
 impl TechnicalIndicator for WilliamsR {
     fn name(&self) -> &'static str { "Williams %R" }
     fn group(&self) -> &'static str { "Oscillator" }
+    fn description(&self) -> &'static str { "Close relative to the highest high over the period, from -100 to 0" }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name: "period".into(), param_type: "int".into(), default_value: json!(14) }]
     }
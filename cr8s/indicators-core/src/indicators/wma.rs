@@ -2,6 +2,7 @@
 use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
 use serde_json::json;
 
+#[derive(Default)]
 pub struct WMA;
 impl WMA {
     pub fn new() -> Self { WMA }
@@ -13,7 +14,7 @@ impl WMA {
             let mut weight = 0.0;
             for j in 0..period {
                 let w = (j+1) as f64;
-                sum += candles[i-period+1+j].close * w;
+                sum += candles[i+1-period+j].close * w;
                 weight += w;
             }
             result[i] = Some(sum / weight);
@@ -25,6 +26,8 @@ impl WMA {
 impl TechnicalIndicator for WMA {
     fn name(&self) -> &'static str { "Weighted Moving Average" }
     fn group(&self) -> &'static str { "Trend" }
+    fn description(&self) -> &'static str { "Moving average with linearly increasing weights on recent closes" }
+    fn close_only(&self) -> bool { true }
     fn params(&self) -> Vec<IndicatorParam> {
         vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(14)}]
     }
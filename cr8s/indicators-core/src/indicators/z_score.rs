@@ -0,0 +1,34 @@
+// src/indicators/z_score.rs
+use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct ZScore;
+impl ZScore {
+    pub fn new() -> Self { ZScore }
+
+    pub(crate) fn calculate(&self, candles:&[Candle], period:usize) -> Vec<Option<f64>> {
+        let mut result = vec![None; candles.len()];
+        for i in period.saturating_sub(1)..candles.len() {
+            let window = &candles[i+1-period..=i];
+            let mean = window.iter().map(|c| c.close).sum::<f64>() / period as f64;
+            let stddev = (window.iter().map(|c| (c.close - mean).powi(2)).sum::<f64>() / period as f64).sqrt();
+            result[i] = Some(if stddev != 0.0 { (candles[i].close - mean) / stddev } else { 0.0 });
+        }
+        result
+    }
+}
+
+impl TechnicalIndicator for ZScore {
+    fn name(&self) -> &'static str { "Z-Score" }
+    fn group(&self) -> &'static str { "Statistics" }
+    fn description(&self) -> &'static str { "Number of standard deviations the close sits from its moving average" }
+    fn close_only(&self) -> bool { true }
+    fn params(&self) -> Vec<IndicatorParam> {
+        vec![IndicatorParam { name:"period".into(), param_type:"int".into(), default_value: json!(20)}]
+    }
+    fn compute(&self, candles:&[Candle], options:&IndicatorOptions) -> Vec<Option<f64>> {
+        let period = options.values.get("period").and_then(|v|v.as_u64()).unwrap_or(20) as usize;
+        self.calculate(candles, period)
+    }
+}
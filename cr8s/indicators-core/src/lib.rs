@@ -0,0 +1,67 @@
+// Indicator implementations shared by the WASM package and the yeast API server.
+// New indicators are written once here and registered below.
+use std::collections::HashMap;
+use std::sync::Arc;
+use lazy_static::lazy_static;
+
+pub mod indicators;
+pub mod options;
+pub mod resample;
+pub mod stream;
+pub mod transform;
+
+pub use crate::indicators::*;
+pub use crate::resample::{resample, Resolution, Session};
+pub use crate::stream::{IndicatorStream, StreamError};
+pub use crate::transform::{heikin_ashi, renko, CandleTransform};
+
+// ======================
+// Indicator Registry
+// ======================
+lazy_static! {
+    pub static ref INDICATOR_REGISTRY: HashMap<&'static str, Arc<dyn TechnicalIndicator>> = {
+        let mut map = HashMap::new();
+        map.insert("rsi", Arc::new(RSI::new()) as Arc<dyn TechnicalIndicator>);
+        map.insert("ema", Arc::new(EMA::new()) as Arc<dyn TechnicalIndicator>);
+        map.insert("sma", Arc::new(SMA::new()) as Arc<dyn TechnicalIndicator>);
+
+        map.insert("williams_r", Arc::new(WilliamsR::new()));
+        map.insert("ichimoku", Arc::new(Ichimoku::new()));
+        map.insert("momentum", Arc::new(Momentum::new()));
+        map.insert("tema", Arc::new(Tema::new()));
+        map.insert("dema", Arc::new(Dema::new()));
+        map.insert("kama", Arc::new(Kama::new()));
+        map.insert("wma", Arc::new(WMA::new()));
+        map.insert("hma", Arc::new(Hma::new()));
+        map.insert("frama", Arc::new(Frama::new()));
+        map.insert("chandelier_exit", Arc::new(ChandelierExit::new()));
+        map.insert("trix", Arc::new(TRIX::new()));
+        map.insert("mfi", Arc::new(MFI::new()));
+        map.insert("force_index", Arc::new(ForceIndex::new()));
+        map.insert("ease_of_movement", Arc::new(EaseOfMovement::new()));
+        map.insert("accum_dist_line", Arc::new(AccumDistLine::new()));
+        map.insert("price_volume_trend", Arc::new(PriceVolumeTrend::new()));
+        map.insert("volume_oscillator", Arc::new(VolumeOscillator::new()));
+        map.insert("ultimate_oscillator", Arc::new(UltimateOscillator::new()));
+        map.insert("detrended_price_oscillator", Arc::new(DetrendedPriceOscillator::new()));
+        map.insert("roc", Arc::new(RateOfChange::new()));
+        map.insert("z_score", Arc::new(ZScore::new()));
+        map.insert("gmma", Arc::new(GMMA::new()));
+        map.insert("schaff_trend_cycle", Arc::new(SchaffTrendCycle::new()));
+        map.insert("fibonacci_retracement", Arc::new(FibonacciRetracement::new()));
+        map.insert("kalman_filter_smoother", Arc::new(KalmanFilterSmoother::new()));
+        map.insert("heikin_ashi_slope", Arc::new(HeikinAshiSlope::new()));
+        map.insert("percent_b", Arc::new(PercentB::new()));
+        map.insert("alligator", Arc::new(Alligator::new()));
+        map.insert("fractals", Arc::new(Fractals::new()));
+        map.insert("gann_hilo", Arc::new(GannHiLo::new()));
+        map.insert("supertrend", Arc::new(SuperTrend::new()));
+        map.insert("keltner_channels", Arc::new(KeltnerChannels::new()));
+        map.insert("donchian_channels", Arc::new(DonchianChannels::new()));
+        map.insert("aroon", Arc::new(Aroon::new()));
+        map.insert("pivot_points", Arc::new(PivotPoints::new()));
+        map.insert("historical_volatility", Arc::new(HistoricalVolatility::new()));
+
+        map
+    };
+}
@@ -0,0 +1,431 @@
+// Option pricing shared by the yeast API server and the WASM package: Black-Scholes and
+// Cox-Ross-Rubinstein Greeks, implied volatility, and P&L curves for multi-leg strategies.
+
+use std::f64::consts::E;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PricingModel {
+    BlackScholes,
+    Binomial, // Cox-Ross-Rubinstein with early exercise, for American-style options
+}
+
+impl PricingModel {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "black_scholes" | "bs" => Some(PricingModel::BlackScholes),
+            "binomial" | "crr" => Some(PricingModel::Binomial),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PricingModel::BlackScholes => "black_scholes",
+            PricingModel::Binomial => "binomial",
+        }
+    }
+}
+
+// Enough for prices within a cent on typical equity options without slowing full chains
+pub const BINOMIAL_STEPS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct OptionGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+    pub price: f64,
+}
+
+/// Approximate the standard normal PDF
+pub fn norm_pdf(x: f64) -> f64 {
+    (1.0 / (2.0 * std::f64::consts::PI).sqrt()) * (-0.5 * x * x).exp()
+}
+
+/// Approximate the standard normal CDF (Abramowitz and Stegun formula 7.1.26)
+pub fn norm_cdf(x: f64) -> f64 {
+    let k = 1.0 / (1.0 + 0.2316419 * x.abs());
+    let k_sum = k * (0.319381530 + k * (-0.356563782 + k * (1.781477937 + k * (-1.821255978 + 1.330274429 * k))));
+    let cdf = 1.0 - norm_pdf(x) * k_sum;
+
+    if x < 0.0 { 1.0 - cdf } else { cdf }
+}
+
+pub fn black_scholes_greeks(
+    s: f64,      // underlying price
+    k: f64,      // strike price
+    t: f64,      // time to expiration in years
+    r: f64,      // risk-free rate
+    sigma: f64,  // volatility
+    option_type: OptionType,
+) -> OptionGreeks {
+    let sqrt_t = t.sqrt();
+    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * sqrt_t);
+    let d2 = d1 - sigma * sqrt_t;
+
+    let price = match option_type {
+        OptionType::Call => s * norm_cdf(d1) - k * E.powf(-r * t) * norm_cdf(d2),
+        OptionType::Put => k * E.powf(-r * t) * norm_cdf(-d2) - s * norm_cdf(-d1),
+    };
+
+    let delta = match option_type {
+        OptionType::Call => norm_cdf(d1),
+        OptionType::Put => norm_cdf(d1) - 1.0,
+    };
+
+    let gamma = norm_pdf(d1) / (s * sigma * sqrt_t);
+
+    let theta = match option_type {
+        OptionType::Call => {
+            -(s * norm_pdf(d1) * sigma) / (2.0 * sqrt_t)
+            - r * k * E.powf(-r * t) * norm_cdf(d2)
+        }
+        OptionType::Put => {
+            -(s * norm_pdf(d1) * sigma) / (2.0 * sqrt_t)
+            + r * k * E.powf(-r * t) * norm_cdf(-d2)
+        }
+    };
+
+    let vega = s * norm_pdf(d1) * sqrt_t;
+
+    let rho = match option_type {
+        OptionType::Call => k * t * E.powf(-r * t) * norm_cdf(d2),
+        OptionType::Put => -k * t * E.powf(-r * t) * norm_cdf(-d2),
+    };
+
+    OptionGreeks {
+        delta,
+        gamma,
+        theta,
+        vega,
+        rho,
+        price,
+    }
+}
+
+/// Volatility that makes Black-Scholes reproduce `price`, found by bisection.
+/// None when the price sits outside no-arbitrage bounds or the option has expired.
+pub fn implied_volatility(
+    price: f64,
+    s: f64,
+    k: f64,
+    t: f64,
+    r: f64,
+    option_type: OptionType,
+) -> Option<f64> {
+    if price <= 0.0 || s <= 0.0 || k <= 0.0 || t <= 0.0 {
+        return None;
+    }
+
+    let discounted_strike = k * E.powf(-r * t);
+    let (lower, upper) = match option_type {
+        OptionType::Call => ((s - discounted_strike).max(0.0), s),
+        OptionType::Put => ((discounted_strike - s).max(0.0), discounted_strike),
+    };
+    if price <= lower || price >= upper {
+        return None;
+    }
+
+    // Price is increasing in sigma, so bisect between near-zero and 500% vol
+    let (mut lo, mut hi) = (1e-4, 5.0);
+    if black_scholes_greeks(s, k, t, r, hi, option_type).price < price {
+        return None;
+    }
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if black_scholes_greeks(s, k, t, r, mid, option_type).price < price {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-6 {
+            break;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+/// Greeks under the chosen model. Black-Scholes ignores the dividend yield `q`.
+#[allow(clippy::too_many_arguments)]
+pub fn option_greeks(
+    model: PricingModel,
+    s: f64,
+    k: f64,
+    t: f64,
+    r: f64,
+    q: f64,
+    sigma: f64,
+    option_type: OptionType,
+) -> OptionGreeks {
+    match model {
+        PricingModel::BlackScholes => black_scholes_greeks(s, k, t, r, sigma, option_type),
+        PricingModel::Binomial => binomial_greeks(s, k, t, r, q, sigma, option_type, true, BINOMIAL_STEPS),
+    }
+}
+
+/// Probability the underlying ends above `level` after `t` years, with the price lognormal at
+/// drift `r - q` and volatility `sigma`
+pub fn probability_above(s: f64, level: f64, t: f64, r: f64, q: f64, sigma: f64) -> f64 {
+    if t <= 0.0 || sigma <= 0.0 {
+        return if s > level { 1.0 } else { 0.0 };
+    }
+    let sd = sigma * t.sqrt();
+    norm_cdf(((s / level).ln() + (r - q - 0.5 * sigma * sigma) * t) / sd)
+}
+
+/// Probability the underlying trades at `barrier` at some point in the next `t` years (first
+/// passage of the same lognormal process); 1 when it is already there
+pub fn probability_of_touch(s: f64, barrier: f64, t: f64, r: f64, q: f64, sigma: f64) -> f64 {
+    let a = (barrier / s).ln();
+    if a == 0.0 {
+        return 1.0;
+    }
+    if t <= 0.0 || sigma <= 0.0 {
+        return 0.0;
+    }
+    let nu = r - q - 0.5 * sigma * sigma;
+    let sd = sigma * t.sqrt();
+    let reflected = (2.0 * nu * a / (sigma * sigma)).exp();
+    let p = if a > 0.0 {
+        norm_cdf((nu * t - a) / sd) + reflected * norm_cdf((-nu * t - a) / sd)
+    } else {
+        norm_cdf((a - nu * t) / sd) + reflected * norm_cdf((a + nu * t) / sd)
+    };
+    p.clamp(0.0, 1.0)
+}
+
+// Value at expiry
+pub fn intrinsic(s: f64, k: f64, option_type: OptionType) -> f64 {
+    match option_type {
+        OptionType::Call => (s - k).max(0.0),
+        OptionType::Put => (k - s).max(0.0),
+    }
+}
+
+/// Cox-Ross-Rubinstein tree. Returns the option value at the root along with the node values
+/// one and two steps in (down to up), which the Greeks are read off.
+#[allow(clippy::too_many_arguments)]
+fn crr_tree(
+    s: f64,
+    k: f64,
+    t: f64,
+    r: f64,
+    q: f64,
+    sigma: f64,
+    option_type: OptionType,
+    american: bool,
+    steps: usize,
+) -> (f64, [f64; 2], [f64; 3]) {
+    let dt = t / steps as f64;
+    let u = (sigma * dt.sqrt()).exp();
+    let d = 1.0 / u;
+    let p = (((r - q) * dt).exp() - d) / (u - d);
+    let discount = (-r * dt).exp();
+
+    // Terminal payoffs, index j = number of up moves
+    let mut values: Vec<f64> = (0..=steps)
+        .map(|j| intrinsic(s * u.powi(j as i32) * d.powi((steps - j) as i32), k, option_type))
+        .collect();
+    let mut step_one = [0.0; 2];
+    let mut step_two = [0.0; 3];
+
+    for i in (0..steps).rev() {
+        for j in 0..=i {
+            let continuation = discount * (p * values[j + 1] + (1.0 - p) * values[j]);
+            values[j] = if american {
+                let spot = s * u.powi(j as i32) * d.powi((i - j) as i32);
+                continuation.max(intrinsic(spot, k, option_type))
+            } else {
+                continuation
+            };
+        }
+        match i {
+            2 => step_two.copy_from_slice(&values[..3]),
+            1 => step_one.copy_from_slice(&values[..2]),
+            _ => {}
+        }
+    }
+
+    (values[0], step_one, step_two)
+}
+
+/// Binomial price and Greeks with continuous dividend yield `q`. Delta, gamma and theta come from
+/// the tree itself; vega and rho by re-pricing with bumped inputs. Units match black_scholes_greeks.
+#[allow(clippy::too_many_arguments)]
+pub fn binomial_greeks(
+    s: f64,
+    k: f64,
+    t: f64,
+    r: f64,
+    q: f64,
+    sigma: f64,
+    option_type: OptionType,
+    american: bool,
+    steps: usize,
+) -> OptionGreeks {
+    let steps = steps.max(3);
+    if t <= 0.0 || sigma <= 0.0 {
+        let price = intrinsic(s, k, option_type);
+        let delta = match option_type {
+            OptionType::Call if s > k => 1.0,
+            OptionType::Put if s < k => -1.0,
+            _ => 0.0,
+        };
+        return OptionGreeks { delta, gamma: 0.0, theta: 0.0, vega: 0.0, rho: 0.0, price };
+    }
+
+    let (price, step_one, step_two) = crr_tree(s, k, t, r, q, sigma, option_type, american, steps);
+    let dt = t / steps as f64;
+    let u = (sigma * dt.sqrt()).exp();
+    let d = 1.0 / u;
+
+    let delta = (step_one[1] - step_one[0]) / (s * u - s * d);
+    let upper_delta = (step_two[2] - step_two[1]) / (s * u * u - s);
+    let lower_delta = (step_two[1] - step_two[0]) / (s - s * d * d);
+    let gamma = (upper_delta - lower_delta) / (0.5 * (s * u * u - s * d * d));
+    // The middle node two steps in has the same spot as the root
+    let theta = (step_two[1] - price) / (2.0 * dt);
+
+    let sigma_bump = 0.01;
+    let vega = (crr_tree(s, k, t, r, q, sigma + sigma_bump, option_type, american, steps).0
+        - crr_tree(s, k, t, r, q, (sigma - sigma_bump).max(1e-4), option_type, american, steps).0)
+        / (sigma + sigma_bump - (sigma - sigma_bump).max(1e-4));
+
+    let rate_bump = 0.0001;
+    let rho = (crr_tree(s, k, t, r + rate_bump, q, sigma, option_type, american, steps).0
+        - crr_tree(s, k, t, r - rate_bump, q, sigma, option_type, american, steps).0)
+        / (2.0 * rate_bump);
+
+    OptionGreeks {
+        delta,
+        gamma,
+        theta,
+        vega,
+        rho,
+        price,
+    }
+}
+
+impl OptionType {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "call" | "c" => Some(OptionType::Call),
+            "put" | "p" => Some(OptionType::Put),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LegKind {
+    Call,
+    Put,
+    Stock,
+}
+
+// One leg of a strategy. Quantities are contracts (shares for stock) and negative when short;
+// premium is the per-share price paid or received. `expiry` (years from now) and `volatility`
+// are only needed to value the leg before expiration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyLeg {
+    pub kind: LegKind,
+    #[serde(default)]
+    pub strike: f64,
+    pub quantity: f64,
+    pub premium: f64,
+    #[serde(default)]
+    pub expiry: Option<f64>,
+    #[serde(default)]
+    pub volatility: Option<f64>,
+}
+
+impl StrategyLeg {
+    fn multiplier(&self, contract_size: f64) -> f64 {
+        match self.kind {
+            LegKind::Stock => self.quantity,
+            _ => self.quantity * contract_size,
+        }
+    }
+
+    // Per-share value with `years` left to expiry; intrinsic value at or after expiry
+    fn value(&self, spot: f64, years: f64, rate: f64) -> Option<f64> {
+        let option_type = match self.kind {
+            LegKind::Stock => return Some(spot),
+            LegKind::Call => OptionType::Call,
+            LegKind::Put => OptionType::Put,
+        };
+        if years <= 0.0 {
+            return Some(intrinsic(spot, self.strike, option_type));
+        }
+        let sigma = self.volatility.filter(|v| *v > 0.0)?;
+        Some(black_scholes_greeks(spot, self.strike, years, rate, sigma, option_type).price)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyPnl {
+    pub prices: Vec<f64>,
+    pub at_expiry: Vec<f64>,          // P&L with every option leg at intrinsic value
+    pub at_horizon: Option<Vec<f64>>, // P&L `horizon` years from now; None without expiries and volatilities for the option legs
+    pub breakevens: Vec<f64>,         // prices where the expiry curve crosses zero, interpolated on the grid
+    pub max_profit: f64,              // over the grid
+    pub max_loss: f64,
+}
+
+// P&L of the whole position at each underlying price. `contract_size` is the option multiplier
+// (100 for US equity options); `horizon` is how far ahead, in years, to value the legs for the
+// at_horizon curve. Legs already expired by then are taken at intrinsic value.
+pub fn strategy_pnl(legs: &[StrategyLeg], prices: &[f64], rate: f64, horizon: Option<f64>, contract_size: f64) -> StrategyPnl {
+    let cost: f64 = legs.iter().map(|leg| leg.premium * leg.multiplier(contract_size)).sum();
+    let curve = |years_ahead: Option<f64>| -> Option<Vec<f64>> {
+        prices.iter()
+            .map(|&spot| {
+                let value = legs.iter()
+                    .map(|leg| {
+                        let remaining = match (years_ahead, leg.kind) {
+                            (None, _) | (_, LegKind::Stock) => 0.0,
+                            (Some(ahead), _) => leg.expiry? - ahead,
+                        };
+                        Some(leg.value(spot, remaining, rate)? * leg.multiplier(contract_size))
+                    })
+                    .sum::<Option<f64>>()?;
+                Some(value - cost)
+            })
+            .collect()
+    };
+
+    let at_expiry = curve(None).unwrap_or_default();
+    let at_horizon = horizon.and_then(|h| curve(Some(h)));
+    let breakevens = prices.windows(2)
+        .zip(at_expiry.windows(2))
+        .filter_map(|(p, v)| match (v[0], v[1]) {
+            (0.0, _) => Some(p[0]),
+            (a, b) if a * b < 0.0 => Some(p[0] + (p[1] - p[0]) * a / (a - b)),
+            _ => None,
+        })
+        .chain(at_expiry.last().filter(|v| **v == 0.0).and(prices.last().copied()))
+        .collect();
+    let max_profit = at_expiry.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let max_loss = at_expiry.iter().copied().fold(f64::INFINITY, f64::min);
+
+    StrategyPnl {
+        prices: prices.to_vec(),
+        at_expiry,
+        at_horizon,
+        breakevens,
+        max_profit: if max_profit.is_finite() { max_profit } else { 0.0 },
+        max_loss: if max_loss.is_finite() { max_loss } else { 0.0 },
+    }
+}
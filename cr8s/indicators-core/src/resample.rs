@@ -0,0 +1,137 @@
+// Aggregating candles into coarser bars (1m -> 5m/1h, 1d -> 1wk/1mo, ...).
+// Open is the first bar's open, close the last bar's close, high/low the extremes and volume the
+// sum (None only when no bar in the bucket had one). Buckets are laid out in exchange-local time:
+// intraday buckets start at the session open and never cross midnight, days are calendar days,
+// weeks start on Monday and months on the 1st.
+
+use serde::{Deserialize, Serialize};
+
+use crate::indicators::Candle;
+
+const DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Seconds(i64), // intraday, e.g. 300 for 5m
+    Days(i64),
+    Weeks(i64),
+    Months(i64),
+}
+
+impl Resolution {
+    // Yahoo-style interval names: 5m, 1h, 1d, 1wk, 1mo, ...
+    pub fn parse(interval: &str) -> Option<Self> {
+        let interval = interval.trim().to_lowercase();
+        let split = interval.find(|c: char| !c.is_ascii_digit())?;
+        let (count, unit) = interval.split_at(split);
+        let n: i64 = count.parse().ok().filter(|n| *n > 0)?;
+        match unit {
+            "m" | "min" => Some(Resolution::Seconds(n * 60)),
+            "h" => Some(Resolution::Seconds(n * 3600)),
+            "d" => Some(Resolution::Days(n)),
+            "wk" | "w" => Some(Resolution::Weeks(n)),
+            "mo" => Some(Resolution::Months(n)),
+            _ => None,
+        }
+    }
+
+    // Nominal length, for comparing resolutions (a month counts as 30 days)
+    pub fn approx_seconds(&self) -> i64 {
+        match *self {
+            Resolution::Seconds(s) => s,
+            Resolution::Days(n) => n * DAY,
+            Resolution::Weeks(n) => n * 7 * DAY,
+            Resolution::Months(n) => n * 30 * DAY,
+        }
+    }
+
+    // Whether bars at `self` can be built exactly from bars at `finer`
+    pub fn is_multiple_of(&self, finer: &Resolution) -> bool {
+        match (*self, *finer) {
+            (Resolution::Seconds(a), Resolution::Seconds(b)) => a > b && a % b == 0,
+            (Resolution::Seconds(_), _) => false,
+            (Resolution::Days(a), Resolution::Days(b)) => a > b && a % b == 0,
+            (Resolution::Weeks(a), Resolution::Weeks(b)) | (Resolution::Months(a), Resolution::Months(b)) => a > b && a % b == 0,
+            (Resolution::Months(_), Resolution::Weeks(_)) => false, // weeks straddle month ends
+            (_, Resolution::Seconds(_)) | (_, Resolution::Days(1)) => true,
+            _ => false,
+        }
+    }
+}
+
+// Where the trading day sits: the exchange's UTC offset and the regular session open, both in
+// seconds (US equities: -5 * 3600 or -4 * 3600, and 9:30 = 34_200). Crypto and FX use 0 / 0.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub utc_offset: i64,
+    pub open: i64,
+}
+
+impl Session {
+    pub const UTC: Session = Session { utc_offset: 0, open: 0 };
+}
+
+// Days since 1970-01-01 -> (year, month 1-12), after Howard Hinnant's civil_from_days
+fn year_month(days: i64) -> (i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month)
+}
+
+// Key of the bucket `timestamp` falls in. Intraday keys are the bucket's start in UTC; coarser
+// keys are only meaningful for comparison.
+pub fn bucket(timestamp: i64, resolution: Resolution, session: &Session) -> i64 {
+    let local = timestamp + session.utc_offset;
+    let day = local.div_euclid(DAY);
+    match resolution {
+        Resolution::Seconds(length) => {
+            // Pre-market bars get buckets counted back from the open
+            let session_start = day * DAY + session.open;
+            session_start + (local - session_start).div_euclid(length) * length - session.utc_offset
+        }
+        Resolution::Days(n) => day.div_euclid(n),
+        // 1970-01-01 was a Thursday, so Monday-based weeks start three days later
+        Resolution::Weeks(n) => (day + 3).div_euclid(7).div_euclid(n),
+        Resolution::Months(n) => {
+            let (year, month) = year_month(day);
+            (year * 12 + month - 1).div_euclid(n)
+        }
+    }
+}
+
+// Candles must be in time order. Intraday output bars are stamped with their bucket start; daily
+// and coarser bars keep the timestamp of their first input bar, as Yahoo stamps them.
+pub fn resample(candles: &[Candle], resolution: Resolution, session: &Session) -> Vec<Candle> {
+    let mut out: Vec<Candle> = Vec::new();
+    let mut current_key = None;
+    for candle in candles {
+        let key = bucket(candle.timestamp, resolution, session);
+        match out.last_mut() {
+            Some(bar) if current_key == Some(key) => {
+                bar.high = bar.high.max(candle.high);
+                bar.low = bar.low.min(candle.low);
+                bar.close = candle.close;
+                bar.volume = match (bar.volume, candle.volume) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+            }
+            _ => {
+                let timestamp = match resolution {
+                    Resolution::Seconds(_) => key,
+                    _ => candle.timestamp,
+                };
+                out.push(Candle { timestamp, ..candle.clone() });
+                current_key = Some(key);
+            }
+        }
+    }
+    out
+}
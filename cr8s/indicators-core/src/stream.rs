@@ -0,0 +1,181 @@
+// Incremental indicator updates for live charts: push one candle at a time and get the latest
+// values back. A candle with the same timestamp as the last one replaces it (the bar that is still
+// forming), so every tick of the current bar can be pushed as it arrives.
+// SMA and EMA keep running state and update in constant time. Every other indicator is recomputed
+// over the candles the stream has kept: all of them unless `with_history` bounds the buffer. A
+// bound is exact for windowed indicators once it covers the warmup, and close for recursive ones
+// (EMA-based, Wilder smoothing) when it is several times longer.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::indicators::{Candle, IndicatorOptions, TechnicalIndicator};
+use crate::INDICATOR_REGISTRY;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamError {
+    OutOfOrder { last: i64, got: i64 }, // candle older than the last one pushed
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::OutOfOrder { last, got } => {
+                write!(f, "Candle at {} is older than the last one pushed ({})", got, last)
+            }
+        }
+    }
+}
+
+// Latest value of each output, keyed as in compute_multi ("value" for single-line indicators)
+pub type Latest = HashMap<String, Option<f64>>;
+
+#[derive(Debug, Clone)]
+enum Running {
+    Sma { period: usize, window: VecDeque<f64> },
+    Ema { period: usize, value: Option<f64>, count: usize },
+}
+
+impl Running {
+    fn for_key(key: &str, options: &IndicatorOptions) -> Option<Self> {
+        // Same default period as the batch SMA and EMA
+        let period = options.values.get("period").and_then(|v| v.as_u64()).unwrap_or(14).max(1) as usize;
+        match key {
+            "sma" => Some(Running::Sma { period, window: VecDeque::with_capacity(period + 1) }),
+            "ema" => Some(Running::Ema { period, value: None, count: 0 }),
+            _ => None,
+        }
+    }
+
+    // Matches the batch calculation value for value: the SMA sums its window in order, the EMA
+    // seeds with the first close
+    fn next(&mut self, close: f64) -> Option<f64> {
+        match self {
+            Running::Sma { period, window } => {
+                window.push_back(close);
+                if window.len() > *period {
+                    window.pop_front();
+                }
+                (window.len() == *period).then(|| window.iter().sum::<f64>() / *period as f64)
+            }
+            Running::Ema { period, value, count } => {
+                let k = 2.0 / (*period as f64 + 1.0);
+                let next = match *value {
+                    Some(prev) => close * k + prev * (1.0 - k),
+                    None => close,
+                };
+                *value = Some(next);
+                *count += 1;
+                (*count >= *period).then_some(next)
+            }
+        }
+    }
+}
+
+pub struct IndicatorStream {
+    indicator: Arc<dyn TechnicalIndicator>,
+    options: IndicatorOptions,
+    initial: Option<Running>,
+    running: Option<Running>,
+    before_last: Option<Running>, // state before the last candle, restored when it is replaced
+    candles: VecDeque<Candle>,    // the last candle only when running, else the recompute buffer
+    history: Option<usize>,
+}
+
+impl IndicatorStream {
+    pub fn from_key(key: &str, options: IndicatorOptions) -> Option<Self> {
+        let indicator = Arc::clone(INDICATOR_REGISTRY.get(key)?);
+        let initial = Running::for_key(key, &options);
+        Some(IndicatorStream {
+            indicator,
+            options,
+            running: initial.clone(),
+            initial,
+            before_last: None,
+            candles: VecDeque::new(),
+            history: None,
+        })
+    }
+
+    // Keep at most `candles` bars for recomputation (at least 1)
+    pub fn with_history(mut self, candles: usize) -> Self {
+        self.history = Some(candles.max(1));
+        self
+    }
+
+    pub fn push(&mut self, candle: Candle) -> Result<Latest, StreamError> {
+        let replace = match self.candles.back() {
+            Some(last) if candle.timestamp < last.timestamp => {
+                return Err(StreamError::OutOfOrder { last: last.timestamp, got: candle.timestamp });
+            }
+            Some(last) => candle.timestamp == last.timestamp,
+            None => false,
+        };
+        if replace {
+            self.candles.pop_back();
+        }
+
+        match &mut self.running {
+            Some(running) => {
+                match (&self.before_last, replace) {
+                    (Some(before), true) => *running = before.clone(),
+                    _ => self.before_last = Some(running.clone()),
+                }
+                let value = running.next(candle.close);
+                self.candles.clear();
+                self.candles.push_back(candle);
+                Ok(HashMap::from([("value".to_string(), value)]))
+            }
+            None => {
+                self.candles.push_back(candle);
+                Ok(self.recompute())
+            }
+        }
+    }
+
+    // Loads history in one go, computing once rather than once per candle. Candles must be in time
+    // order and not older than anything already pushed.
+    pub fn seed(&mut self, candles: &[Candle]) -> Result<Latest, StreamError> {
+        if self.running.is_some() {
+            let mut latest = Latest::new();
+            for candle in candles {
+                latest = self.push(candle.clone())?;
+            }
+            return Ok(latest);
+        }
+
+        let mut last = self.candles.back().map(|c| c.timestamp);
+        for candle in candles {
+            match last {
+                Some(ts) if candle.timestamp < ts => return Err(StreamError::OutOfOrder { last: ts, got: candle.timestamp }),
+                Some(ts) if candle.timestamp == ts => {
+                    self.candles.pop_back();
+                }
+                _ => {}
+            }
+            self.candles.push_back(candle.clone());
+            last = Some(candle.timestamp);
+        }
+        Ok(self.recompute())
+    }
+
+    pub fn reset(&mut self) {
+        self.running = self.initial.clone();
+        self.before_last = None;
+        self.candles.clear();
+    }
+
+    fn recompute(&mut self) -> Latest {
+        if let Some(max) = self.history {
+            while self.candles.len() > max {
+                self.candles.pop_front();
+            }
+        }
+        let candles = self.candles.make_contiguous();
+        self.indicator
+            .compute_multi(candles, &self.options)
+            .into_iter()
+            .map(|(name, values)| (name, values.last().copied().flatten()))
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+// Candle transforms whose output is itself a candle series, so any indicator can run on it.
+// Heikin-Ashi keeps one bar per input bar; Renko emits a brick each time price moves a full
+// brick from the last one (a reversal needs two), so it has its own, irregular time axis.
+
+use crate::indicators::Candle;
+
+const RENKO_ATR_PERIOD: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CandleTransform {
+    HeikinAshi,
+    Renko { brick_size: Option<f64> }, // None sizes bricks by the 14-bar average true range
+}
+
+impl CandleTransform {
+    pub fn apply(&self, candles: &[Candle]) -> Vec<Candle> {
+        match *self {
+            CandleTransform::HeikinAshi => heikin_ashi(candles),
+            CandleTransform::Renko { brick_size } => match brick_size.or_else(|| average_true_range(candles, RENKO_ATR_PERIOD)) {
+                Some(size) => renko(candles, size),
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+// Close is the bar's OHLC average, open the midpoint of the previous Heikin-Ashi body
+pub fn heikin_ashi(candles: &[Candle]) -> Vec<Candle> {
+    let mut out: Vec<Candle> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        let close = (candle.open + candle.high + candle.low + candle.close) / 4.0;
+        let open = match out.last() {
+            Some(prev) => (prev.open + prev.close) / 2.0,
+            None => (candle.open + candle.close) / 2.0,
+        };
+        out.push(Candle {
+            timestamp: candle.timestamp,
+            open,
+            high: candle.high.max(open).max(close),
+            low: candle.low.min(open).min(close),
+            close,
+            volume: candle.volume,
+        });
+    }
+    out
+}
+
+// Simple mean of the last `period` true ranges; None with fewer bars than that
+pub fn average_true_range(candles: &[Candle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() <= period {
+        return None;
+    }
+    let ranges = candles.windows(2).rev().take(period).map(|pair| {
+        let (prev, bar) = (&pair[0], &pair[1]);
+        (bar.high - bar.low).max((bar.high - prev.close).abs()).max((bar.low - prev.close).abs())
+    });
+    Some(ranges.sum::<f64>() / period as f64)
+}
+
+// Bricks built from closes, anchored at the first close. A brick is stamped with the bar that
+// completed it and carries the volume traded since the previous brick; further bricks from the
+// same bar have no volume.
+pub fn renko(candles: &[Candle], brick_size: f64) -> Vec<Candle> {
+    let mut bricks = Vec::new();
+    let Some(first) = candles.first() else {
+        return bricks;
+    };
+    if !brick_size.is_finite() || brick_size <= 0.0 {
+        return bricks;
+    }
+
+    let (mut bottom, mut top) = (first.close, first.close);
+    let mut pending_volume: Option<f64> = None;
+    for candle in &candles[1..] {
+        if let Some(v) = candle.volume {
+            pending_volume = Some(pending_volume.unwrap_or(0.0) + v);
+        }
+        loop {
+            let (open, close) = if candle.close >= top + brick_size {
+                (top, top + brick_size)
+            } else if candle.close <= bottom - brick_size {
+                (bottom, bottom - brick_size)
+            } else {
+                break;
+            };
+            bottom = open.min(close);
+            top = open.max(close);
+            bricks.push(Candle {
+                timestamp: candle.timestamp,
+                open,
+                high: top,
+                low: bottom,
+                close,
+                volume: pending_volume.take(),
+            });
+        }
+    }
+    bricks
+}
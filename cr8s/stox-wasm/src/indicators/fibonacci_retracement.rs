@@ -1,15 +0,0 @@
-// src/indicators/fibonacci_retracement.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct FibonacciRetracement;
-impl FibonacciRetracement {
-    pub fn new() -> Self { FibonacciRetracement }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for FibonacciRetracement {
-    fn name(&self) -> &'static str { "Fibonacci Retracement" }
-    fn group(&self) -> &'static str { "Trend" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
@@ -1,15 +0,0 @@
-// src/indicators/gmma.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct GMMA;
-impl GMMA {
-    pub fn new() -> Self { GMMA }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for GMMA {
-    fn name(&self) -> &'static str { "GMMA" }
-    fn group(&self) -> &'static str { "Trend" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
@@ -1,15 +0,0 @@
-// src/indicators/mfi.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct MFI;
-impl MFI {
-    pub fn new() -> Self { MFI }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for MFI {
-    fn name(&self) -> &'static str { "Money Flow Index" }
-    fn group(&self) -> &'static str { "Volume" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
@@ -1,15 +0,0 @@
-// src/indicators/percent_b.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct PercentB;
-impl PercentB {
-    pub fn new() -> Self { PercentB }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for PercentB {
-    fn name(&self) -> &'static str { "%B" }
-    fn group(&self) -> &'static str { "Oscillator" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
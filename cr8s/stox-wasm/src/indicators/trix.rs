@@ -1,15 +0,0 @@
-// src/indicators/trix.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct TRIX;
-impl TRIX {
-    pub fn new() -> Self { TRIX }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for TRIX {
-    fn name(&self) -> &'static str { "TRIX" }
-    fn group(&self) -> &'static str { "Oscillator" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
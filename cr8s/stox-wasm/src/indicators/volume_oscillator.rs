@@ -1,15 +0,0 @@
-// src/indicators/volume_oscillator.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct VolumeOscillator;
-impl VolumeOscillator {
-    pub fn new() -> Self { VolumeOscillator }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for VolumeOscillator {
-    fn name(&self) -> &'static str { "Volume Oscillator" }
-    fn group(&self) -> &'static str { "Volume" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
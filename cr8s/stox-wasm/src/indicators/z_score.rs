@@ -1,15 +0,0 @@
-// src/indicators/z_score.rs
-use crate::{Candle, TechnicalIndicator, IndicatorParam, IndicatorOptions};
-use serde_json::json;
-
-pub struct ZScore;
-impl ZScore {
-    pub fn new() -> Self { ZScore }
-    pub(crate) fn calculate(&self, candles:&[Candle]) -> Vec<Option<f64>> { vec![None; candles.len()] }
-}
-impl TechnicalIndicator for ZScore {
-    fn name(&self) -> &'static str { "Z-Score" }
-    fn group(&self) -> &'static str { "Statistics" }
-    fn params(&self) -> Vec<IndicatorParam> { vec![] }
-    fn compute(&self, candles:&[Candle], _options:&IndicatorOptions) -> Vec<Option<f64>> { self.calculate(candles) }
-}
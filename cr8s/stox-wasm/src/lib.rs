@@ -1,115 +1,639 @@
+use serde::de::DeserializeOwned;
 use serde::{Serialize, Deserialize};
 use serde_json::json;
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::Arc;
-use lazy_static::lazy_static;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
-mod indicators;
-
-use crate::indicators::{
-    TechnicalIndicator, IndicatorOptions, IndicatorParam, Candle, 
-    SMA, EMA, RSI, MACD, BollingerBands, VWAP, ATR, Stochastic, CCI, ADX, ParabolicSAR, OBV,
-    CMF, WilliamsR, Ichimoku, Momentum, Tema, Dema, Kama, WMA, Hma, Frama, ChandelierExit,
-    TRIX, MFI, ForceIndex, EaseOfMovement, AccumDistLine, PriceVolumeTrend, VolumeOscillator,
-    UltimateOscillator, DetrendedPriceOscillator, RateOfChange, ZScore, GMMA, SchaffTrendCycle,
-    FibonacciRetracement, KalmanFilterSmoother, HeikinAshiSlope, PercentB,
+use indicators_core::options::{self, LegKind, OptionType, StrategyLeg};
+use indicators_core::{
+    validate_options, Candle, CandleTransform, IndicatorOptions, IndicatorStream, Resolution, Session, TechnicalIndicator,
+    INDICATOR_REGISTRY,
 };
 
+// ======================
+// TypeScript types
+// ======================
+// Exports take and return plain JS objects; these give them real types in the generated .d.ts
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+export interface Candle {
+  timestamp: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  volume?: number | null;
+}
+export interface IndicatorOptions {
+  values: Record<string, number | boolean | string>;
+}
+export interface IndicatorParam {
+  name: string;
+  param_type: "int" | "float" | "bool" | "string";
+  default_value: number | boolean | string;
+}
+export interface IndicatorInfo {
+  key: string;
+  name: string;
+  group: string;
+  description: string;
+  warmup: number;
+  params: IndicatorParam[];
+}
+/** One value per candle; null where the indicator has no value yet */
+export type Series = (number | null)[];
+export interface WasmError {
+  code: "invalid_candles" | "invalid_options" | "unknown_indicator" | "invalid_param" | "insufficient_data";
+  message: string;
+  param: string | null;
+}
+export type Outcome<T> = { ok: true; value: T } | { ok: false; error: WasmError };
+export type ManyEntry =
+  | { key: string; ok: true; value: Record<string, Series> }
+  | { key: string; ok: false; error: WasmError };
+/** latest value of each output after a push, keyed as in compute_indicator_multi */
+export type Latest = Record<string, number | null>;
+export interface Session {
+  utc_offset?: number;
+  open?: number;
+}
+export interface PanelConfig {
+  indicators: { key: string; id?: string; options?: IndicatorOptions }[];
+}
+export interface AlignedSeries {
+  offset: number;
+  values: Series;
+}
+export interface PanelSeries {
+  id: string;
+  key: string;
+  name: string;
+  group: string;
+  warmup: number;
+  outputs: Record<string, AlignedSeries>;
+}
+export interface PanelResult {
+  length: number;
+  series: PanelSeries[];
+  errors: string[];
+}
+export interface OptionGreeks {
+  delta: number;
+  gamma: number;
+  theta: number;
+  vega: number;
+  rho: number;
+  price: number;
+}
+export interface StrategyLeg {
+  kind: "call" | "put" | "stock";
+  strike?: number;
+  /** contracts (shares for stock), negative when short */
+  quantity: number;
+  /** per-share price paid or received */
+  premium: number;
+  /** years to expiry and volatility, needed only for the at_horizon curve */
+  expiry?: number | null;
+  volatility?: number | null;
+}
+export interface StrategyPnl {
+  prices: number[];
+  at_expiry: number[];
+  at_horizon: number[] | null;
+  breakevens: number[];
+  max_profit: number;
+  max_loss: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Candle[]")]
+    pub type Candles;
+    #[wasm_bindgen(typescript_type = "IndicatorOptions | null | undefined")]
+    pub type OptionsArg;
+    #[wasm_bindgen(typescript_type = "Session | null | undefined")]
+    pub type SessionArg;
+    #[wasm_bindgen(typescript_type = "IndicatorInfo[]")]
+    pub type IndicatorList;
+    #[wasm_bindgen(typescript_type = "Series")]
+    pub type SeriesValue;
+    #[wasm_bindgen(typescript_type = "Outcome<Series>")]
+    pub type SeriesOutcome;
+    #[wasm_bindgen(typescript_type = "Outcome<Record<string, Series>>")]
+    pub type MultiOutcome;
+    #[wasm_bindgen(typescript_type = "PanelConfig")]
+    pub type PanelConfigArg;
+    #[wasm_bindgen(typescript_type = "PanelResult")]
+    pub type PanelResultValue;
+    #[wasm_bindgen(typescript_type = "[string, Candle[], IndicatorOptions][]")]
+    pub type BatchRequests;
+    #[wasm_bindgen(typescript_type = "Record<string, Series>")]
+    pub type BatchResult;
+    #[wasm_bindgen(typescript_type = "[string, IndicatorOptions | null][]")]
+    pub type ManyRequests;
+    #[wasm_bindgen(typescript_type = "Outcome<ManyEntry[]>")]
+    pub type ManyOutcome;
+    #[wasm_bindgen(typescript_type = "Candle")]
+    pub type CandleArg;
+    #[wasm_bindgen(typescript_type = "Outcome<number>")]
+    pub type HandleOutcome;
+    #[wasm_bindgen(typescript_type = "Outcome<Latest>")]
+    pub type LatestOutcome;
+    #[wasm_bindgen(typescript_type = "OptionGreeks")]
+    pub type GreeksValue;
+    #[wasm_bindgen(typescript_type = "StrategyLeg[]")]
+    pub type LegsArg;
+    #[wasm_bindgen(typescript_type = "StrategyPnl")]
+    pub type StrategyPnlValue;
+}
+
+// Maps become plain objects rather than JS Maps, and None becomes null, so results read like JSON
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, serde_wasm_bindgen::Error> {
+    serde_wasm_bindgen::from_value(value)
+}
 
 // ======================
-// Indicator Registry
+// Errors
 // ======================
-lazy_static! {
-    pub static ref INDICATOR_REGISTRY: HashMap<&'static str, Arc<dyn TechnicalIndicator>> = {
-        let mut map = HashMap::new();
-        map.insert("rsi", Arc::new(RSI::new()) as Arc<dyn TechnicalIndicator>);
-        map.insert("ema", Arc::new(EMA::new()) as Arc<dyn TechnicalIndicator>);
-        map.insert("sma", Arc::new(SMA::new()) as Arc<dyn TechnicalIndicator>);
-
-        map.insert("williams_r", Arc::new(WilliamsR::new()));
-        map.insert("ichimoku", Arc::new(Ichimoku::new()));
-        map.insert("momentum", Arc::new(Momentum::new()));
-        map.insert("tema", Arc::new(Tema::new()));
-        map.insert("dema", Arc::new(Dema::new()));
-        map.insert("kama", Arc::new(Kama::new()));
-        map.insert("wma", Arc::new(WMA::new()));
-        map.insert("hma", Arc::new(Hma::new()));
-        map.insert("frama", Arc::new(Frama::new()));
-        map.insert("chandelier_exit", Arc::new(ChandelierExit::new()));
-        map.insert("trix", Arc::new(TRIX::new()));
-        map.insert("mfi", Arc::new(MFI::new()));
-        map.insert("force_index", Arc::new(ForceIndex::new()));
-        map.insert("ease_of_movement", Arc::new(EaseOfMovement::new()));
-        map.insert("accum_dist_line", Arc::new(AccumDistLine::new()));
-        map.insert("price_volume_trend", Arc::new(PriceVolumeTrend::new()));
-        map.insert("volume_oscillator", Arc::new(VolumeOscillator::new()));
-        map.insert("ultimate_oscillator", Arc::new(UltimateOscillator::new()));
-        map.insert("detrended_price_oscillator", Arc::new(DetrendedPriceOscillator::new()));
-        map.insert("roc", Arc::new(RateOfChange::new()));
-        map.insert("z_score", Arc::new(ZScore::new()));
-        map.insert("gmma", Arc::new(GMMA::new()));
-        map.insert("schaff_trend_cycle", Arc::new(SchaffTrendCycle::new()));
-        map.insert("fibonacci_retracement", Arc::new(FibonacciRetracement::new()));
-        map.insert("kalman_filter_smoother", Arc::new(KalmanFilterSmoother::new()));
-        map.insert("heikin_ashi_slope", Arc::new(HeikinAshiSlope::new()));
-        map.insert("percent_b", Arc::new(PercentB::new()));
-
-        map
+// compute_indicator and compute_indicator_multi never throw; they return
+// { ok: true, value } or { ok: false, error: { code, message, param } }, where code is one of
+// invalid_candles, invalid_options, unknown_indicator, invalid_param or insufficient_data (streams
+// add unknown_stream and out_of_order) and param names the offending option (null when there
+// isn't one).
+#[derive(Serialize)]
+struct WasmError {
+    code: &'static str,
+    message: String,
+    param: Option<String>,
+}
+
+impl WasmError {
+    fn new(code: &'static str, message: impl Into<String>) -> Self {
+        WasmError { code, message: message.into(), param: None }
+    }
+
+    fn for_param(code: &'static str, param: &str, message: impl Into<String>) -> Self {
+        WasmError { code, message: message.into(), param: Some(param.to_string()) }
+    }
+}
+
+fn outcome<T: Serialize>(result: Result<T, WasmError>) -> JsValue {
+    let body = match result {
+        Ok(value) => json!({ "ok": true, "value": value }),
+        Err(error) => json!({ "ok": false, "error": error }),
     };
+    to_js(&body).unwrap_or_else(|e| e)
+}
+
+fn parse_candles(candles: JsValue) -> Result<Vec<Candle>, WasmError> {
+    let candles: Vec<Candle> = from_js(candles)
+        .map_err(|e| WasmError::new("invalid_candles", format!("Invalid candles: {}", e)))?;
+    // NaN or infinite prices would poison every rolling window they touch
+    if let Some(i) = candles.iter().position(|c| !finite_prices(c)) {
+        return Err(WasmError::new("invalid_candles", format!("Candle {} has a missing or non-finite price", i)));
+    }
+    Ok(candles)
+}
+
+fn finite_prices(candle: &Candle) -> bool {
+    [candle.open, candle.high, candle.low, candle.close].iter().all(|v| v.is_finite())
+}
+
+// Missing options (undefined or null) mean the indicator's defaults
+fn parse_options(options: JsValue) -> Result<IndicatorOptions, WasmError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(IndicatorOptions { values: HashMap::new() });
+    }
+    from_js(options)
+        .map_err(|e| WasmError::new("invalid_options", format!("Invalid options (expected {{ values: {{ ... }} }}): {}", e)))
+}
+
+// Options must be ones the indicator declares, of the right type, and every period (given or
+// default) must fit in the candles supplied
+fn check_options(indicator: &dyn TechnicalIndicator, options: &IndicatorOptions, candle_count: usize) -> Result<(), WasmError> {
+    validate_options(indicator, options).map_err(|e| WasmError::for_param("invalid_param", &e.param, e.message))?;
+    for param in indicator.params().iter().filter(|p| p.param_type == "int" && p.name.ends_with("period")) {
+        let period = options.values.get(&param.name).unwrap_or(&param.default_value).as_u64().unwrap_or(0) as usize;
+        if period > candle_count {
+            return Err(WasmError::for_param(
+                "insufficient_data",
+                &param.name,
+                format!("{} {} needs at least {} candles, got {}", param.name, period, period, candle_count),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn lookup(key: &str) -> Result<&'static dyn TechnicalIndicator, WasmError> {
+    INDICATOR_REGISTRY.get(key).map(|indicator| indicator.as_ref()).ok_or_else(|| {
+        let mut keys: Vec<&str> = INDICATOR_REGISTRY.keys().copied().collect();
+        keys.sort_unstable();
+        WasmError::new("unknown_indicator", format!("Unknown indicator {}; available: {}", key, keys.join(", ")))
+    })
+}
+
+// Looks the indicator up and parses and checks its inputs
+fn prepare(key: &str, candles: JsValue, options: JsValue) -> Result<(&'static dyn TechnicalIndicator, Vec<Candle>, IndicatorOptions), WasmError> {
+    let indicator = lookup(key)?;
+    let candles = parse_candles(candles)?;
+    let options = parse_options(options)?;
+    check_options(indicator, &options, candles.len())?;
+    Ok((indicator, candles, options))
 }
 
 // ======================
 // WASM Exports
 // ======================
 #[wasm_bindgen]
-pub fn get_indicators() -> JsValue {
-    let indicators: Vec<_> = INDICATOR_REGISTRY
-        .iter()
+pub fn get_indicators() -> Result<IndicatorList, JsValue> {
+    // Stable order for pickers: by group, then by display name
+    let mut entries: Vec<_> = INDICATOR_REGISTRY.iter().collect();
+    entries.sort_by(|(_, a), (_, b)| (a.group(), a.name()).cmp(&(b.group(), b.name())));
+
+    let indicators: Vec<_> = entries
+        .into_iter()
         .map(|(key, indicator)| {
             json!({
                 "key": key,
                 "name": indicator.name(),
                 "group": indicator.group(),
+                "description": indicator.description(),
+                "warmup": indicator.warmup(&IndicatorOptions::default()),
                 "params": indicator.params()
             })
         })
         .collect();
 
-    JsValue::from_serde(&indicators).unwrap()
+    to_js(&indicators).map(JsCast::unchecked_into)
 }
 
 #[wasm_bindgen]
-pub fn compute_indicator(key: &str, candles: JsValue, options: JsValue) -> JsValue {
-    let candles: Vec<Candle> = candles.into_serde().unwrap();
-    let options: IndicatorOptions = options.into_serde().unwrap();
+pub fn compute_indicator(key: &str, candles: Candles, options: OptionsArg) -> SeriesOutcome {
+    let computed = prepare(key, candles.into(), options.into()).map(|(indicator, candles, options)| indicator.compute(&candles, &options));
+    outcome(computed).unchecked_into()
+}
 
-    if let Some(indicator) = INDICATOR_REGISTRY.get(key) {
-        let result = indicator.compute(&candles, &options);
-        JsValue::from_serde(&result).unwrap()
-    } else {
-        JsValue::from_str("Indicator not found")
+#[wasm_bindgen]
+pub fn compute_indicator_multi(key: &str, candles: Candles, options: OptionsArg) -> MultiOutcome {
+    let computed = prepare(key, candles.into(), options.into()).map(|(indicator, candles, options)| indicator.compute_multi(&candles, &options));
+    outcome(computed).unchecked_into()
+}
+
+#[wasm_bindgen]
+pub fn compute_from_closes(closes: &js_sys::Float64Array, key: &str, options: OptionsArg) -> Result<SeriesValue, JsValue> {
+    let indicator = INDICATOR_REGISTRY
+        .get(key)
+        .ok_or_else(|| JsValue::from_str("Indicator not found"))?;
+    if !indicator.close_only() {
+        return Err(JsValue::from_str(&format!(
+            "{} needs high/low/volume data and cannot be computed from closes alone",
+            indicator.name()
+        )));
+    }
+
+    let options = parse_options(options.into()).map_err(|e| JsValue::from_str(&e.message))?;
+    let candles: Vec<Candle> = closes
+        .to_vec()
+        .into_iter()
+        .enumerate()
+        .map(|(i, close)| Candle::from_close(i as i64, close))
+        .collect();
+    check_options(indicator.as_ref(), &options, candles.len()).map_err(|e| JsValue::from_str(&e.message))?;
+
+    let result = indicator.compute(&candles, &options);
+    to_js(&result).map(JsCast::unchecked_into)
+}
+
+// Config for compute_panel:
+// {
+//   "indicators": [
+//     { "key": "rsi", "id": "rsi14", "options": { "values": { "period": 14 } } },
+//     { "key": "macd" }                       // id defaults to key, options to the indicator defaults
+//   ]
+// }
+// Response: { "length": n, "series": [{ id, key, name, group, warmup, outputs: { <name>: { offset, values } } }],
+// "errors": [...] }. `offset` is the index of the first value; `values` runs from there to the last candle.
+#[derive(Deserialize)]
+struct PanelConfig {
+    indicators: Vec<PanelIndicator>,
+}
+
+#[derive(Deserialize)]
+struct PanelIndicator {
+    key: String,
+    id: Option<String>,
+    options: Option<IndicatorOptions>,
+}
+
+#[derive(Serialize)]
+struct AlignedSeries {
+    offset: usize,
+    values: Vec<Option<f64>>,
+}
+
+fn align(values: Vec<Option<f64>>) -> AlignedSeries {
+    let offset = values.iter().position(|v| v.is_some()).unwrap_or(values.len());
+    AlignedSeries { offset, values: values[offset..].to_vec() }
+}
+
+#[wasm_bindgen]
+pub fn compute_panel(
+    open: &js_sys::Float64Array,
+    high: &js_sys::Float64Array,
+    low: &js_sys::Float64Array,
+    close: &js_sys::Float64Array,
+    volume: &js_sys::Float64Array,
+    config: PanelConfigArg,
+) -> Result<PanelResultValue, JsValue> {
+    let config: PanelConfig = from_js(config.into())
+        .map_err(|e| JsValue::from_str(&format!("Invalid panel config: {}", e)))?;
+
+    let (open, high, low, close, volume) = (open.to_vec(), high.to_vec(), low.to_vec(), close.to_vec(), volume.to_vec());
+    let len = close.len();
+    if open.len() != len || high.len() != len || low.len() != len || volume.len() != len {
+        return Err(JsValue::from_str("open, high, low, close and volume must have the same length"));
     }
+
+    // NaN volume marks a missing value
+    let candles: Vec<Candle> = (0..len)
+        .map(|i| Candle {
+            timestamp: i as i64,
+            open: open[i],
+            high: high[i],
+            low: low[i],
+            close: close[i],
+            volume: if volume[i].is_nan() { None } else { Some(volume[i]) },
+        })
+        .collect();
+
+    let mut series = Vec::new();
+    let mut errors = Vec::new();
+    for entry in config.indicators {
+        let Some(indicator) = INDICATOR_REGISTRY.get(entry.key.as_str()) else {
+            errors.push(format!("Indicator not found: {}", entry.key));
+            continue;
+        };
+        let options = entry.options.unwrap_or(IndicatorOptions { values: HashMap::new() });
+        if let Err(e) = check_options(indicator.as_ref(), &options, len) {
+            errors.push(format!("{}: {}", entry.id.as_deref().unwrap_or(&entry.key), e.message));
+            continue;
+        }
+        let outputs: HashMap<String, AlignedSeries> = indicator
+            .compute_multi(&candles, &options)
+            .into_iter()
+            .map(|(name, values)| (name, align(values)))
+            .collect();
+
+        series.push(json!({
+            "id": entry.id.unwrap_or_else(|| entry.key.clone()),
+            "key": entry.key,
+            "name": indicator.name(),
+            "group": indicator.group(),
+            "warmup": indicator.warmup(&options),
+            "outputs": outputs,
+        }));
+    }
+
+    let result = json!({ "length": len, "series": series, "errors": errors });
+    to_js(&result).map(JsCast::unchecked_into)
 }
 
+// Unknown keys, and requests whose options don't check out, are left out of the result. Each tuple
+// carries its own candles; compute_many takes one series for all of them.
 #[wasm_bindgen]
-pub fn compute_batch(requests: JsValue) -> JsValue {
-    let requests: Vec<(String, Vec<Candle>, IndicatorOptions)> = requests.into_serde().unwrap();
+pub fn compute_batch(requests: BatchRequests) -> Result<BatchResult, JsValue> {
+    let requests: Vec<(String, Vec<Candle>, IndicatorOptions)> = from_js(requests.into())
+        .map_err(|e| JsValue::from_str(&format!("Invalid batch (expected [key, candles, options] tuples): {}", e)))?;
     let mut results = HashMap::new();
     for (key, candles, options) in requests {
         if let Some(indicator) = INDICATOR_REGISTRY.get(key.as_str()) {
-            results.insert(key.clone(), indicator.compute(&candles, &options));
+            if check_options(indicator.as_ref(), &options, candles.len()).is_ok() {
+                results.insert(key.clone(), indicator.compute(&candles, &options));
+            }
+        }
+    }
+    to_js(&results).map(JsCast::unchecked_into)
+}
+
+// Many indicators over one candle series, deserialized once. `requests` is a list of
+// [key, options] pairs (options may be null for the defaults), e.g.
+// [["rsi", { values: { period: 14 } }], ["sma", { values: { period: 50 } }], ["keltner_channels", null]].
+// Returns { ok: true, value: [...] } with one entry per request, in order: { key, ok: true, value }
+// where value maps output names to series as in compute_indicator_multi, or { key, ok: false, error }.
+// Only unreadable candles or requests fail the call as a whole.
+#[wasm_bindgen]
+pub fn compute_many(candles: Candles, requests: ManyRequests) -> ManyOutcome {
+    let prepared = parse_candles(candles.into()).and_then(|candles| {
+        let requests: Vec<(String, Option<IndicatorOptions>)> = from_js(requests.into())
+            .map_err(|e| WasmError::new("invalid_options", format!("Invalid requests (expected [key, options] pairs): {}", e)))?;
+        Ok((candles, requests))
+    });
+
+    outcome(prepared.map(|(candles, requests)| {
+        requests
+            .into_iter()
+            .map(|(key, options)| {
+                let options = options.unwrap_or(IndicatorOptions { values: HashMap::new() });
+                let computed = lookup(&key).and_then(|indicator| {
+                    check_options(indicator, &options, candles.len())?;
+                    Ok(indicator.compute_multi(&candles, &options))
+                });
+                match computed {
+                    Ok(value) => json!({ "key": key, "ok": true, "value": value }),
+                    Err(error) => json!({ "key": key, "ok": false, "error": error }),
+                }
+            })
+            .collect::<Vec<_>>()
+    }))
+    .unchecked_into()
+}
+
+// Aggregates time-ordered candles to a coarser interval ("5m", "1h", "1d", "1wk", "1mo"), so one
+// fetch of fine bars can drive every chart timeframe. `session` is optional:
+// { "utc_offset": -14400, "open": 34200 } lays buckets out in exchange time (9:30 ET opens);
+// without it days start at UTC midnight.
+#[wasm_bindgen]
+pub fn resample_candles(candles: Candles, interval: &str, session: SessionArg) -> Result<Candles, JsValue> {
+    let candles: Vec<Candle> = from_js(candles.into())
+        .map_err(|e| JsValue::from_str(&format!("Invalid candles: {}", e)))?;
+    let resolution = Resolution::parse(interval)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown interval {}", interval)))?;
+    let session: JsValue = session.into();
+    let session: Session = if session.is_undefined() || session.is_null() {
+        Session::UTC
+    } else {
+        from_js(session)
+            .map_err(|e| JsValue::from_str(&format!("Invalid session: {}", e)))?
+    };
+
+    let result = indicators_core::resample(&candles, resolution, &session);
+    to_js(&result).map(JsCast::unchecked_into)
+}
+
+// ======================
+// Streaming
+// ======================
+// Live charts push each tick instead of recomputing the whole series: create_stream returns a
+// handle, push_candle updates it with one candle and returns the latest values. A candle with the
+// same timestamp as the last one replaces it, so the forming bar can be pushed on every tick.
+// Indicators other than SMA and EMA recompute over the candles the stream keeps; `history` bounds
+// that buffer (all candles by default).
+thread_local! {
+    static STREAMS: RefCell<(u32, HashMap<u32, IndicatorStream>)> = RefCell::new((0, HashMap::new()));
+}
+
+fn with_stream<T>(handle: u32, f: impl FnOnce(&mut IndicatorStream) -> Result<T, WasmError>) -> Result<T, WasmError> {
+    STREAMS.with(|streams| match streams.borrow_mut().1.get_mut(&handle) {
+        Some(stream) => f(stream),
+        None => Err(WasmError::new("unknown_stream", format!("No stream with handle {}", handle))),
+    })
+}
+
+#[wasm_bindgen]
+pub fn create_stream(key: &str, options: OptionsArg, history: Option<u32>) -> HandleOutcome {
+    let created = (|| -> Result<u32, WasmError> {
+        let indicator = lookup(key)?;
+        let options = parse_options(options.into())?;
+        validate_options(indicator, &options).map_err(|e| WasmError::for_param("invalid_param", &e.param, e.message))?;
+        let mut stream = IndicatorStream::from_key(key, options).ok_or_else(|| WasmError::new("unknown_indicator", key))?;
+        if let Some(history) = history {
+            stream = stream.with_history(history as usize);
+        }
+        Ok(STREAMS.with(|streams| {
+            let (next, streams) = &mut *streams.borrow_mut();
+            *next += 1;
+            streams.insert(*next, stream);
+            *next
+        }))
+    })();
+    outcome(created).unchecked_into()
+}
+
+#[wasm_bindgen]
+pub fn push_candle(handle: u32, candle: CandleArg) -> LatestOutcome {
+    let latest = (|| -> Result<_, WasmError> {
+        let candle: Candle = from_js(candle.into())
+            .map_err(|e| WasmError::new("invalid_candles", format!("Invalid candle: {}", e)))?;
+        if !finite_prices(&candle) {
+            return Err(WasmError::new("invalid_candles", "Candle has a missing or non-finite price"));
         }
+        with_stream(handle, |stream| stream.push(candle).map_err(|e| WasmError::new("out_of_order", e.to_string())))
+    })();
+    outcome(latest).unchecked_into()
+}
+
+// Loads the history a chart already shows in one call, computing once at the end
+#[wasm_bindgen]
+pub fn seed_stream(handle: u32, candles: Candles) -> LatestOutcome {
+    let latest = (|| -> Result<_, WasmError> {
+        let candles = parse_candles(candles.into())?;
+        with_stream(handle, |stream| stream.seed(&candles).map_err(|e| WasmError::new("out_of_order", e.to_string())))
+    })();
+    outcome(latest).unchecked_into()
+}
+
+// Forgets every candle pushed, e.g. when the chart switches symbol; false for an unknown handle
+#[wasm_bindgen]
+pub fn reset(handle: u32) -> bool {
+    with_stream(handle, |stream| {
+        stream.reset();
+        Ok(())
+    })
+    .is_ok()
+}
+
+#[wasm_bindgen]
+pub fn drop_stream(handle: u32) -> bool {
+    STREAMS.with(|streams| streams.borrow_mut().1.remove(&handle).is_some())
+}
+
+// Heikin-Ashi or Renko candles, ready for compute_indicator. `brick_size` is only read for Renko
+// and defaults to the 14-bar average true range.
+#[wasm_bindgen]
+pub fn transform_candles(candles: Candles, transform: &str, brick_size: Option<f64>) -> Result<Candles, JsValue> {
+    let candles: Vec<Candle> = from_js(candles.into())
+        .map_err(|e| JsValue::from_str(&format!("Invalid candles: {}", e)))?;
+    let transform = match transform {
+        "heikin_ashi" => CandleTransform::HeikinAshi,
+        "renko" => CandleTransform::Renko { brick_size },
+        other => return Err(JsValue::from_str(&format!("Unknown transform {}", other))),
+    };
+
+    to_js(&transform.apply(&candles)).map(JsCast::unchecked_into)
+}
+
+// ======================
+// Options math
+// ======================
+fn option_type(name: &str) -> Result<OptionType, JsValue> {
+    OptionType::parse(name).ok_or_else(|| JsValue::from_str(&format!("Unknown option type {} (expected call or put)", name)))
+}
+
+fn positive(values: &[(&str, f64)]) -> Result<(), JsValue> {
+    match values.iter().find(|(_, v)| !v.is_finite() || *v <= 0.0) {
+        Some((name, v)) => Err(JsValue::from_str(&format!("{} must be a positive number, got {}", name, v))),
+        None => Ok(()),
     }
-    JsValue::from_serde(&results).unwrap()
+}
+
+// Black-Scholes price and Greeks per share, in the server's units: `t` in years, `r` and `sigma`
+// as decimals (0.05 = 5%), theta per year, vega and rho per 1.00 change in sigma and r
+#[wasm_bindgen]
+pub fn black_scholes_greeks(s: f64, k: f64, t: f64, r: f64, sigma: f64, option_type: &str) -> Result<GreeksValue, JsValue> {
+    let option_type = self::option_type(option_type)?;
+    positive(&[("s", s), ("k", k), ("t", t), ("sigma", sigma)])?;
+    to_js(&options::black_scholes_greeks(s, k, t, r, sigma, option_type)).map(JsCast::unchecked_into)
+}
+
+// Volatility that reproduces `price` under Black-Scholes; undefined when the price is outside
+// no-arbitrage bounds or the option has expired
+#[wasm_bindgen]
+pub fn implied_volatility(price: f64, s: f64, k: f64, t: f64, r: f64, option_type: &str) -> Result<Option<f64>, JsValue> {
+    let option_type = self::option_type(option_type)?;
+    Ok(options::implied_volatility(price, s, k, t, r, option_type))
+}
+
+// P&L of a multi-leg position at each price in `prices`, at expiry and, when `horizon` (years from
+// now) is given and every option leg has an expiry and volatility, at that date. `contract_size`
+// defaults to 100 shares per option.
+#[wasm_bindgen]
+pub fn strategy_pnl(
+    legs: LegsArg,
+    prices: &js_sys::Float64Array,
+    rate: f64,
+    horizon: Option<f64>,
+    contract_size: Option<f64>,
+) -> Result<StrategyPnlValue, JsValue> {
+    let legs: Vec<StrategyLeg> = from_js(legs.into()).map_err(|e| JsValue::from_str(&format!("Invalid legs: {}", e)))?;
+    if let Some((i, _)) = legs.iter().enumerate().find(|(_, leg)| leg.kind != LegKind::Stock && (!leg.strike.is_finite() || leg.strike <= 0.0)) {
+        return Err(JsValue::from_str(&format!("Leg {} needs a positive strike", i)));
+    }
+    let prices = prices.to_vec();
+    if prices.iter().any(|p| !p.is_finite() || *p < 0.0) {
+        return Err(JsValue::from_str("prices must be non-negative numbers"));
+    }
+    let contract_size = contract_size.unwrap_or(100.0);
+    positive(&[("contract_size", contract_size)])?;
+
+    to_js(&options::strategy_pnl(&legs, &prices, rate, horizon, contract_size)).map(JsCast::unchecked_into)
 }
 
 /*
 const wasm = await import('/wasm/wasm.js');
 await wasm.default();
 
-// Get all indicators
-const indicators = JSON.parse(wasm.get_indicators());
+// Get all indicators (plain objects, typed as IndicatorInfo[] in stox_wasm.d.ts)
+const indicators = wasm.get_indicators();
 console.log(indicators);
 
 // Compute RSI
@@ -121,8 +645,11 @@ const candles = [
 
 const options = { values: { period: 14 } };
 
-const rsiResult = JSON.parse(
-  wasm.compute_indicator("rsi", JSON.stringify(candles), JSON.stringify(options))
-);
-console.log(rsiResult);
+// { ok: true, value: [...] } or { ok: false, error: { code, message, param } }
+const rsi = wasm.compute_indicator("rsi", candles, options);
+if (rsi.ok) {
+  console.log(rsi.value);
+} else {
+  console.error(rsi.error.code, rsi.error.param, rsi.error.message);
+}
 */
\ No newline at end of file
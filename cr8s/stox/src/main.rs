@@ -20,13 +20,13 @@ mod indicators;
 mod types;
 mod options_math;
 use crate::types::Candle;
-use crate::indicators::{
+use crate::indicators::{BoundIndicator, IndicatorRunner};
+use indicators_core::{
     SMA, EMA, RSI, MACD, BollingerBands, VWAP, ATR, Stochastic, CCI, ADX, ParabolicSAR, OBV,
     CMF, WilliamsR, Ichimoku, Momentum, Tema, Dema, Kama, WMA, Hma, Frama, ChandelierExit,
     TRIX, MFI, ForceIndex, EaseOfMovement, AccumDistLine, PriceVolumeTrend, VolumeOscillator,
     UltimateOscillator, DetrendedPriceOscillator, RateOfChange, ZScore, GMMA, SchaffTrendCycle,
     FibonacciRetracement, KalmanFilterSmoother, HeikinAshiSlope, PercentB,
-    TechnicalIndicator, IndicatorRunner
 };
 use crate::options_math::{black_scholes_greeks, calculate_pnl, OptionData, OptionType};
 
@@ -310,69 +310,57 @@ pub fn to_candles(result: &ResultItem) -> Vec<Candle> {
     candles
 }
 
-pub fn build_indicators() -> Vec<(String, Arc<dyn TechnicalIndicator + Send + Sync>)> {
+pub fn build_indicators() -> Vec<(String, BoundIndicator)> {
     vec![
-        ("SMA(5)".to_string(), Arc::new(SMA { period: 5 })),
-        ("EMA(5)".to_string(), Arc::new(EMA { period: 5 })),
-        ("RSI(14)".to_string(), Arc::new(RSI { period: 14 })),
-        ("MACD(12,26)".to_string(), Arc::new(MACD { fast_period: 12, slow_period: 26 })),
-        ("BollingerBands(20)".to_string(), Arc::new(BollingerBands { period: 20, k: 2.0 })),
-        ("VWAP".to_string(), Arc::new(VWAP {})),
-        ("ATR(14)".to_string(), Arc::new(ATR { period: 14 })),
-        ("Stochastic(14,3)".to_string(), Arc::new(Stochastic { k_period: 14, d_period: 3 })),
-        ("CCI(20)".to_string(), Arc::new(CCI { period: 20 })),
-        ("ADX(14)".to_string(), Arc::new(ADX { period: 14 })),
-        ("ParabolicSAR".to_string(), Arc::new(ParabolicSAR { step: 0.02, max_step: 0.2 })),
-        ("OBV".to_string(), Arc::new(OBV {})),
-        ("CMF(20)".to_string(), Arc::new(CMF { period: 20 })),
-        ("WilliamsR(14)".to_string(), Arc::new(WilliamsR { period: 14 })),
-        ("Ichimoku".to_string(), Arc::new(Ichimoku {
-            conversion_period: 9,
-            base_period: 26,
-            leading_span_b_period: 52,
-            displacement: 26,
-        })),
-        ("Momentum(10)".to_string(), Arc::new(Momentum { period: 10 })),
-        ("Tema(10)".to_string(), Arc::new(Tema { period: 10 })),
-        ("Dema(10)".to_string(), Arc::new(Dema { period: 10 })),
-        ("Kama(10)".to_string(), Arc::new(Kama { period: 10 })),
-        ("WMA(10)".to_string(), Arc::new(WMA { period: 10 })),
-        ("HMA(10)".to_string(), Arc::new(Hma { period: 10 })),
-        ("Frama(10)".to_string(), Arc::new(Frama { period: 10 })),
-        ("ChandelierExit(22, 3.0)".to_string(), Arc::new(ChandelierExit { period: 22, atr_multiplier: 3.0 })),
-        ("TRIX(15)".to_string(), Arc::new(TRIX { period: 15 })),
-        ("MFI(14)".to_string(), Arc::new(MFI { period: 14 })),
-        ("ForceIndex(13)".to_string(), Arc::new(ForceIndex { period: 13 })),
-        ("EaseOfMovement(14)".to_string(), Arc::new(EaseOfMovement { period: 14 })),
-        ("AccumDistLine".to_string(), Arc::new(AccumDistLine {})),
-        ("PriceVolumeTrend".to_string(), Arc::new(PriceVolumeTrend {})),
-        ("VolumeOscillator(14,28)".to_string(), Arc::new(VolumeOscillator { short_period: 14, long_period: 28 })),
-        ("UltimateOscillator(7,14,28)".to_string(), Arc::new(UltimateOscillator {
-            short_period: 7,
-            mid_period: 14,
-            long_period: 28,
-        })),
-        ("DetrendedPriceOscillator(20)".to_string(), Arc::new(DetrendedPriceOscillator { period: 20 })),
-        ("RateOfChange(12)".to_string(), Arc::new(RateOfChange { period: 12 })),
-        ("ZScore(20)".to_string(), Arc::new(ZScore { period: 20 })),
-        ("GMMA".to_string(), Arc::new(GMMA {
-            short_periods: vec![3, 5, 8, 10, 12, 15],
-            long_periods: vec![30, 35, 40, 45, 50, 60],
-        })),
-        ("SchaffTrendCycle".to_string(), Arc::new(SchaffTrendCycle {
-            cycle_period: 10,
-            fast_k: 23,
-            fast_d: 50,
-            short_period: 50,
-            long_period: 50,
-        })),
-        ("FibonacciRetracement(14)".to_string(), Arc::new(FibonacciRetracement { period: 14 })),
-        ("KalmanFilterSmoother".to_string(), Arc::new(KalmanFilterSmoother {
-            measurement_variance: 1.0,
-            process_variance: 1.0,
-        })),
-        ("HeikinAshiSlope(10)".to_string(), Arc::new(HeikinAshiSlope { period: 10 })),
-        ("PercentB(20, 2.0)".to_string(), Arc::new(PercentB { period: 20, std_dev_mult: 2.0 })),
+        ("SMA(5)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 5)),
+        ("EMA(5)".to_string(), BoundIndicator::new(EMA::new()).with_param("period", 5)),
+        ("RSI(14)".to_string(), BoundIndicator::new(RSI::new()).with_param("period", 14)),
+        ("MACD(12,26)".to_string(), BoundIndicator::new(MACD::new()).with_output("macd")),
+        ("BollingerBands(20)".to_string(), BoundIndicator::new(BollingerBands::new()).with_param("period", 20)),
+        ("VWAP".to_string(), BoundIndicator::new(VWAP::new())),
+        ("ATR(14)".to_string(), BoundIndicator::new(ATR::new()).with_param("period", 14)),
+        ("Stochastic(14,3)".to_string(), BoundIndicator::new(Stochastic::new()).with_param("k_period", 14)),
+        ("CCI(20)".to_string(), BoundIndicator::new(CCI::new()).with_param("period", 20)),
+        ("ADX(14)".to_string(), BoundIndicator::new(ADX::new()).with_param("period", 14)),
+        ("ParabolicSAR".to_string(), BoundIndicator::new(ParabolicSAR::new())),
+        ("OBV".to_string(), BoundIndicator::new(OBV::new())),
+        ("CMF(20)".to_string(), BoundIndicator::new(CMF::new()).with_param("period", 20)),
+        ("WilliamsR(14)".to_string(), BoundIndicator::new(WilliamsR::new()).with_param("period", 14)),
+        ("Ichimoku".to_string(), BoundIndicator::new(Ichimoku::new())),
+        ("Momentum(10)".to_string(), BoundIndicator::new(Momentum::new()).with_param("period", 10)),
+        ("Tema(10)".to_string(), BoundIndicator::new(Tema::new()).with_param("period", 10)),
+        ("Dema(10)".to_string(), BoundIndicator::new(Dema::new()).with_param("period", 10)),
+        ("Kama(10)".to_string(), BoundIndicator::new(Kama::new()).with_param("period", 10)),
+        ("WMA(10)".to_string(), BoundIndicator::new(WMA::new()).with_param("period", 10)),
+        ("HMA(10)".to_string(), BoundIndicator::new(Hma::new()).with_param("period", 10)),
+        ("Frama(10)".to_string(), BoundIndicator::new(Frama::new()).with_param("period", 10)),
+        ("ChandelierExit(22, 3.0)".to_string(), BoundIndicator::new(ChandelierExit::new())
+            .with_param("period", 22)
+            .with_param("atr_multiplier", 3.0)),
+        ("TRIX(15)".to_string(), BoundIndicator::new(TRIX::new()).with_param("period", 15)),
+        ("MFI(14)".to_string(), BoundIndicator::new(MFI::new()).with_param("period", 14)),
+        ("ForceIndex(13)".to_string(), BoundIndicator::new(ForceIndex::new()).with_param("period", 13)),
+        ("EaseOfMovement(14)".to_string(), BoundIndicator::new(EaseOfMovement::new()).with_param("period", 14)),
+        ("AccumDistLine".to_string(), BoundIndicator::new(AccumDistLine::new())),
+        ("PriceVolumeTrend".to_string(), BoundIndicator::new(PriceVolumeTrend::new())),
+        ("VolumeOscillator(14,28)".to_string(), BoundIndicator::new(VolumeOscillator::new())
+            .with_param("short_period", 14)
+            .with_param("long_period", 28)),
+        ("UltimateOscillator(7,14,28)".to_string(), BoundIndicator::new(UltimateOscillator::new())
+            .with_param("short_period", 7)
+            .with_param("mid_period", 14)
+            .with_param("long_period", 28)),
+        ("DetrendedPriceOscillator(20)".to_string(), BoundIndicator::new(DetrendedPriceOscillator::new()).with_param("period", 20)),
+        ("RateOfChange(12)".to_string(), BoundIndicator::new(RateOfChange::new()).with_param("period", 12)),
+        ("ZScore(20)".to_string(), BoundIndicator::new(ZScore::new()).with_param("period", 20)),
+        ("GMMA".to_string(), BoundIndicator::new(GMMA::new())),
+        ("SchaffTrendCycle".to_string(), BoundIndicator::new(SchaffTrendCycle::new())),
+        ("FibonacciRetracement(14)".to_string(), BoundIndicator::new(FibonacciRetracement::new()).with_param("period", 14)),
+        ("KalmanFilterSmoother".to_string(), BoundIndicator::new(KalmanFilterSmoother::new())),
+        ("HeikinAshiSlope(10)".to_string(), BoundIndicator::new(HeikinAshiSlope::new()).with_param("period", 10)),
+        ("PercentB(20, 2.0)".to_string(), BoundIndicator::new(PercentB::new())
+            .with_param("period", 20)
+            .with_param("std_dev", 2.0)),
     ]
 }
 
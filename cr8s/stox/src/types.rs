@@ -1,9 +1,2 @@
-#[derive(Debug, Clone)]
-pub struct Candle {
-    pub timestamp: i64,
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
-    pub volume: Option<f64>,
-}
+// Shared with indicators-core so the CLI runs the same indicator implementations as the API server
+pub use indicators_core::Candle;
@@ -0,0 +1,5 @@
+fn main() {
+    // The gRPC stubs are only generated with the `grpc` feature, which needs protoc
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/yeast.proto").expect("failed to compile proto/yeast.proto");
+}
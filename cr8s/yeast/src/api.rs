@@ -1,21 +1,48 @@
 // Complete implementation of the API methods and usage examples
 
-use chrono::{DateTime, Utc, TimeZone};
-use std::time::{UNIX_EPOCH, Duration, Instant};
-use std::collections::HashMap;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, TimeZone, Weekday};
+use std::time::{Duration, Instant};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
-use serde_json::from_str;
 use regex::Regex;
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{self, StreamExt};
 use tokio::sync::RwLock as AsyncRwLock;
+use tracing::{debug, warn};
 
 // Re-export your existing types
 use crate::types::Candle;
-use crate::indicators::{TechnicalIndicator, IndicatorRunner};
-use crate::options_math::{black_scholes_greeks, calculate_pnl, OptionData, OptionType, OptionGreeks};
+use indicators_core::{realized_volatility, resample, CandleTransform, Resolution, Session, VolEstimator, MACD, RSI, SMA};
+use crate::indicators::{BoundIndicator, IndicatorRunner, build_indicators, split_specs, relative_strength, relative_strength_slope};
+use crate::options_math::{option_greeks, implied_volatility, intrinsic, norm_pdf, probability_above, probability_of_touch, PricingModel, calculate_pnl, position_size, atr_position_size, OptionData, OptionType, OptionGreeks};
 use crate::og::*;
+use crate::portfolio::{
+    FillSource, Order, OrderStatus, PlaceOrderRequest, Portfolio, PortfolioManager, Position, StorageConfig, Transaction, TriggeredAlert,
+    Watchlist,
+};
+use crate::auth::Authenticator;
+use crate::candle_store::{CandleStore, range_start, tail_range};
+use crate::providers::{MarketDataProvider, ProviderFetcher};
+use crate::retry::SendWithRetry;
+use crate::response_cache::{self, ResponseCache, ResponseCacheStats, SendCached};
+use crate::signals::{Signal, SignalModel, SignalRule};
+use crate::vol_surface;
+use crate::unusual_activity;
+use crate::oi_profile;
+use crate::risk;
+use crate::optimizer;
+use crate::rebalance;
+use crate::orders;
+use crate::calendar;
+use crate::scheduler::{Job, JobOutcome, JobRequest, JobTask, Scheduler};
+use crate::sse::EventHub;
+use crate::yahoo_session;
+use crate::request_id;
+use crate::validation::{self, FieldError, Validator, VALID_INTERVALS, VALID_RANGES};
 
 // API Error Types
 #[derive(Debug, Serialize)]
@@ -26,6 +53,9 @@ pub enum ApiError {
     FetchError(String),
     CalculationError(String),
     InvalidParameters(String),
+    StorageError(String),
+    RateLimited(String), // upstream throttling us or failing persistently; try again later
+    InvalidFields(Vec<FieldError>), // from a Validator: every bad field in the request
 }
 
 impl fmt::Display for ApiError {
@@ -37,12 +67,83 @@ impl fmt::Display for ApiError {
             ApiError::FetchError(msg) => write!(f, "Fetch error: {}", msg),
             ApiError::CalculationError(msg) => write!(f, "Calculation error: {}", msg),
             ApiError::InvalidParameters(msg) => write!(f, "Invalid parameters: {}", msg),
+            ApiError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            ApiError::RateLimited(msg) => write!(f, "Rate limited: {}", msg),
+            ApiError::InvalidFields(errors) => {
+                let fields: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+                write!(f, "Invalid parameters: {}", fields.join("; "))
+            }
         }
     }
 }
 
 impl Error for ApiError {}
 
+impl ApiError {
+    // HTTP status both servers answer with
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::InvalidTicker(_)
+            | ApiError::InvalidDateRange(_)
+            | ApiError::InvalidParameters(_)
+            | ApiError::InvalidFields(_) => 400,
+            ApiError::DataNotFound(_) => 404,
+            ApiError::RateLimited(_) => 429,
+            ApiError::FetchError(_) => 502, // the upstream failed, not us
+            ApiError::CalculationError(_) | ApiError::StorageError(_) => 500,
+        }
+    }
+
+    // The `code` in error bodies; stable where the message wording isn't
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidTicker(_) => "invalid_ticker",
+            ApiError::InvalidDateRange(_) => "invalid_date_range",
+            ApiError::InvalidParameters(_) | ApiError::InvalidFields(_) => "invalid_parameters",
+            ApiError::DataNotFound(_) => "not_found",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::FetchError(_) => "upstream_error",
+            ApiError::CalculationError(_) => "calculation_error",
+            ApiError::StorageError(_) => "storage_error",
+        }
+    }
+}
+
+// Body of every error response: a message for people, a code for programs, and the id the
+// request was logged under (also sent as X-Request-Id)
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: &'static str,
+    pub request_id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>, // which request fields were rejected, when validation failed
+}
+
+impl ErrorResponse {
+    pub fn new(code: &'static str, error: String) -> Self {
+        Self { error, code, request_id: request_id::current(), fields: Vec::new() }
+    }
+}
+
+impl From<&ApiError> for ErrorResponse {
+    fn from(error: &ApiError) -> Self {
+        let mut body = Self::new(error.code(), error.to_string());
+        if let ApiError::InvalidFields(fields) = error {
+            body.fields = fields.clone();
+        }
+        body
+    }
+}
+
+// Fetchers box their errors; keep an ApiError (e.g. RateLimited from the retry layer) intact
+fn from_fetch_error(e: FetchError) -> ApiError {
+    match e.downcast::<ApiError>() {
+        Ok(api_error) => *api_error,
+        Err(e) => ApiError::FetchError(e.to_string()),
+    }
+}
+
 // API Request/Response Types
 #[derive(Debug, Deserialize)]
 pub struct HistoricalDataRequest {
@@ -53,9 +154,21 @@ pub struct HistoricalDataRequest {
     pub end_date: Option<String>,   // YYYY-MM-DD format
     pub include_indicators: Option<bool>,
     pub indicators: Option<Vec<IndicatorConfig>>,
+    pub parallel_indicators: Option<bool>, // compute the ticker x indicator matrix on the rayon pool after all fetches (default for multi-ticker requests)
+    pub min_candles: Option<usize>, // floor on the candles every indicator needs; each indicator's own warmup still applies
+    pub include_returns: Option<bool>, // add change, change_percent and log_return to each candle
+    pub benchmark: Option<String>,     // e.g. "SPY": adds a relative strength line vs this symbol
+    pub adjust: Option<bool>,          // back-adjust OHLC for splits and dividends; indicators then run on adjusted prices
+    pub resample_to: Option<String>,   // aggregate the fetched bars to a coarser interval, e.g. "1h" from "1m"
+    pub missing_data: Option<String>,  // bars with a null price: "drop" (default), "ffill", "interpolate" or "nan"
+    pub transform: Option<String>,     // "heikin_ashi" or "renko": candles and indicators use the transformed series
+    pub brick_size: Option<f64>,       // renko brick in price units; defaults to the 14-bar average true range
+    pub include_prepost: Option<bool>, // intraday only: keep pre- and post-market bars (default regular session only)
+    pub limit: Option<usize>,          // page size in bars (timestamps shared by all tickers); default everything
+    pub cursor: Option<String>,        // next_cursor from the previous page
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct IndicatorConfig {
     pub name: String,
     pub params: Option<HashMap<String, serde_json::Value>>,
@@ -63,8 +176,40 @@ pub struct IndicatorConfig {
 
 #[derive(Debug, Serialize)]
 pub struct HistoricalDataResponse {
-    pub data: HashMap<String, TickerData>,
+    pub data: BTreeMap<String, TickerData>, // ordered by ticker
     pub errors: Vec<String>,
+    pub next_cursor: Option<String>, // set when `limit` cut the candles short; pass back as `cursor`
+}
+
+impl HistoricalDataResponse {
+    // Keeps the first `limit` distinct timestamps after `after` across every ticker, so each
+    // ticker's page covers the same window and none is repeated or skipped on the next one
+    fn paginate(&mut self, after: Option<i64>, limit: Option<usize>) {
+        if after.is_none() && limit.is_none() {
+            return;
+        }
+        let mut timestamps: Vec<i64> = self.data.values()
+            .flat_map(|ticker| ticker.candles.iter().map(|c| c.timestamp))
+            .filter(|ts| after.is_none_or(|after| *ts > after))
+            .collect();
+        timestamps.sort_unstable();
+        timestamps.dedup();
+        let through = match limit {
+            Some(limit) if limit < timestamps.len() => {
+                self.next_cursor = Some(timestamps[limit - 1].to_string());
+                timestamps[limit - 1]
+            }
+            _ => i64::MAX,
+        };
+        for ticker in self.data.values_mut() {
+            ticker.retain_window(after, through);
+        }
+    }
+}
+
+// Cursors are the timestamp of the last bar already sent
+fn parse_cursor(cursor: &str) -> Result<i64, ApiError> {
+    cursor.parse().map_err(|_| ApiError::InvalidParameters(format!("Invalid cursor '{}'", cursor)))
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -72,19 +217,62 @@ pub struct TickerData {
     pub symbol: String,
     pub candles: Vec<CandleData>,
     pub indicators: Option<HashMap<String, Vec<Option<f64>>>>,
+    pub insufficient_data: Vec<InsufficientData>, // indicators left out because the range is shorter than their warmup
+    pub relative_strength: Option<RelativeStrengthData>,
     pub meta: TickerMeta,
 }
 
+impl TickerData {
+    // Candles in (after, through], with the indicator and relative strength series cut to match
+    fn retain_window(&mut self, after: Option<i64>, through: i64) {
+        let start = self.candles.partition_point(|c| after.is_some_and(|after| c.timestamp <= after));
+        let end = self.candles.partition_point(|c| c.timestamp <= through).max(start);
+        let cut = |series: &mut Vec<Option<f64>>| {
+            let end = end.min(series.len());
+            *series = series.get(start..end).map(<[_]>::to_vec).unwrap_or_default();
+        };
+        self.candles = self.candles[start..end].to_vec();
+        for series in self.indicators.iter_mut().flat_map(|indicators| indicators.values_mut()) {
+            cut(series);
+        }
+        if let Some(rs) = &mut self.relative_strength {
+            cut(&mut rs.line);
+            cut(&mut rs.slope);
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RelativeStrengthData {
+    pub benchmark: String,
+    pub line: Vec<Option<f64>>,  // close / benchmark close, rebased to 100; aligned with candles
+    pub slope: Vec<Option<f64>>, // 1 rising (outperforming), -1 falling, 0 flat, over RS_SLOPE_LOOKBACK bars
+}
+
+const RS_SLOPE_LOOKBACK: usize = 10;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InsufficientData {
+    pub indicator: String,
+    pub needed: usize,
+    pub got: usize,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct CandleData {
     pub timestamp: i64,
     pub datetime: String, // ISO 8601 format
+    pub exchange_datetime: String, // ISO 8601 in the exchange's local offset
+    pub session: Option<String>, // intraday bars: "pre", "regular" or "post"
     pub open: f64,
     pub high: f64,
     pub low: f64,
     pub close: f64,
     pub volume: Option<f64>,
     pub adj_close: Option<f64>,
+    pub change: Option<f64>,         // close - previous close; None on the first candle or unless include_returns
+    pub change_percent: Option<f64>,
+    pub log_return: Option<f64>,     // ln(close / previous close)
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -92,10 +280,12 @@ pub struct TickerMeta {
     pub currency: String,
     pub exchange: String,
     pub instrument_type: String,
+    pub asset_class: AssetClass,
+    pub volume_unit: Option<String>, // "shares", "contracts", "quote_currency"; None when there is no volume
     pub timezone: String,
     pub regular_market_price: f64,
-    pub fifty_two_week_high: f64,
-    pub fifty_two_week_low: f64,
+    pub fifty_two_week_high: Option<f64>,
+    pub fifty_two_week_low: Option<f64>,
     pub market_cap: Option<f64>,
     pub pe_ratio: Option<f64>,
     pub dividend_yield: Option<f64>,
@@ -112,20 +302,78 @@ pub struct OptionsChainRequest {
     pub include_greeks: Option<bool>,
     pub volatility: Option<f64>,      // For Greeks calculation
     pub risk_free_rate: Option<f64>,  // For Greeks calculation
+    pub min_moneyness: Option<f64>,   // strike / underlying
+    pub max_moneyness: Option<f64>,
+    pub min_delta: Option<f64>,       // compared against |delta|, so 0.2-0.4 selects both calls and puts
+    pub max_delta: Option<f64>,
+    pub max_spread_pct: Option<f64>,  // drop contracts whose (ask - bid) / mid exceeds this percent, or that have no quote
+    pub pricing_model: Option<String>, // "black_scholes" (default) or "binomial" for American exercise
+    pub dividend_yield: Option<f64>,   // continuous yield, used by the binomial model
+    pub moneyness: Option<String>,     // keep only "itm", "atm" or "otm" contracts, or a comma-separated mix
+    pub moneyness_band: Option<f64>,   // percent either side of the underlying that counts as ATM, default 5
+    pub max_expirations: Option<usize>, // nearest expiries kept after the other filters
 }
 
+// Where a strike sits relative to the underlying for one side of the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Moneyness {
+    Itm,
+    Atm,
+    Otm,
+}
+
+impl Moneyness {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "itm" => Some(Moneyness::Itm),
+            "atm" => Some(Moneyness::Atm),
+            "otm" => Some(Moneyness::Otm),
+            _ => None,
+        }
+    }
+
+    // ATM within `band_pct` percent of the underlying, else ITM or OTM for the option type
+    pub fn classify(strike: f64, underlying_price: f64, option_type: OptionType, band_pct: f64) -> Self {
+        if (strike / underlying_price - 1.0).abs() * 100.0 <= band_pct {
+            return Moneyness::Atm;
+        }
+        let in_the_money = match option_type {
+            OptionType::Call => strike < underlying_price,
+            OptionType::Put => strike > underlying_price,
+        };
+        if in_the_money { Moneyness::Itm } else { Moneyness::Otm }
+    }
+}
+
+// Greeks volatility when none is requested and the stock's own can't be measured
+const DEFAULT_VOLATILITY: f64 = 0.25;
+// Daily returns behind the realized volatility used when none is requested
+const REALIZED_VOL_WINDOW: usize = 20;
+// Integration grid for the probability analysis: points, and how many standard deviations it spans each side
+const PROBABILITY_GRID_POINTS: usize = 2000;
+const PROBABILITY_GRID_SIGMAS: f64 = 6.0;
+// Unusual activity scans: tickers per request, and chains fetched at once
+const UNUSUAL_MAX_TICKERS: usize = 100;
+const UNUSUAL_DEFAULT_CONCURRENCY: usize = 4;
+const UNUSUAL_MAX_CONCURRENCY: usize = 8;
+// Dated P&L curves per options P&L request
+const MAX_VALUATION_DATES: usize = 12;
+// Grid strikes per surface row
+const SURFACE_MAX_STEPS: usize = 200;
+
 #[derive(Debug, Serialize)]
 pub struct OptionsChainResponse {
     pub symbol: String,
     pub underlying_price: f64,
-    pub expirations: HashMap<String, ExpirationData>,
+    pub expirations: BTreeMap<String, ExpirationData>, // YYYY-MM-DD keys sort chronologically
     pub greeks_params: Option<GreeksParams>,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ExpirationData {
     pub expiration_date: String,
-    pub days_to_expiry: f64,
+    pub days_to_expiry: f64,         // calendar days until the 4pm ET close on expiry, fractional
+    pub trading_days_to_expiry: u32, // weekday sessions left, counting today and expiry day
     pub calls: Vec<OptionContractData>,
     pub puts: Vec<OptionContractData>,
 }
@@ -135,6 +383,8 @@ pub struct OptionContractData {
     pub strike: f64,
     pub bid: f64,
     pub ask: f64,
+    pub mid: f64,        // (bid + ask) / 2
+    pub spread_pct: f64, // (ask - bid) / mid * 100, 0 when there is no quote
     pub last: f64,
     pub volume: u64,
     pub open_interest: u64,
@@ -146,7 +396,8 @@ pub struct OptionContractData {
 pub struct GreeksData {
     pub delta: f64,
     pub gamma: f64,
-    pub theta: f64,
+    pub theta: f64,         // per year
+    pub theta_per_day: f64, // expected decay over one calendar day
     pub vega: f64,
     pub rho: f64,
     pub theoretical_price: f64,
@@ -155,7 +406,132 @@ pub struct GreeksData {
 #[derive(Debug, Serialize)]
 pub struct GreeksParams {
     pub volatility: f64,
+    pub volatility_source: String, // "requested", "historical" (20-day realized) or "default"
+    pub risk_free_rate: f64,
+    pub pricing_model: String,
+    pub dividend_yield: f64,
+}
+
+// Volatility Surface API
+#[derive(Debug, Deserialize)]
+pub struct VolSurfaceRequest {
+    pub ticker: String,
+    pub min_moneyness: Option<f64>, // grid strike / underlying, default 0.7
+    pub max_moneyness: Option<f64>, // default 1.3
+    pub steps: Option<usize>,       // grid strikes, default 25
+    pub max_days: Option<f64>,      // leave out expiries further away
+    pub risk_free_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VolSurfaceResponse {
+    pub symbol: String,
+    pub underlying_price: f64,
     pub risk_free_rate: f64,
+    pub moneyness: Vec<f64>, // grid columns, strike / underlying
+    pub strikes: Vec<f64>,   // the same columns as strikes
+    pub expirations: Vec<SurfaceSlice>, // grid rows, nearest expiry first
+}
+
+#[derive(Debug, Serialize)]
+pub struct SurfaceSlice {
+    pub expiration_date: String,
+    pub days_to_expiry: f64,
+    pub atm_iv: Option<f64>,       // smile at the underlying price
+    pub iv: Vec<Option<f64>>,      // one per grid strike; null outside the quoted strikes
+    pub quotes: Vec<SurfaceQuote>, // the out-of-the-money implied vols the smile was fitted to
+}
+
+#[derive(Debug, Serialize)]
+pub struct SurfaceQuote {
+    pub strike: f64,
+    pub iv: f64,
+}
+
+// Open Interest Profile API
+#[derive(Debug, Deserialize)]
+pub struct OiProfileRequest {
+    pub ticker: String,
+    pub max_days: Option<f64>, // leave out expiries further away
+}
+
+#[derive(Debug, Serialize)]
+pub struct OiProfileResponse {
+    pub symbol: String,
+    pub underlying_price: f64,
+    pub total_call_open_interest: u64,
+    pub total_put_open_interest: u64,
+    pub put_call_oi_ratio: Option<f64>,     // null without call open interest
+    pub put_call_volume_ratio: Option<f64>, // null without call volume
+    pub strikes: Vec<StrikeOpenInterest>,   // every expiry combined
+    pub expirations: Vec<ExpiryOiProfile>,  // nearest first
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpiryOiProfile {
+    pub expiration_date: String,
+    pub days_to_expiry: f64,
+    pub max_pain: Option<f64>, // strike where expiring contracts pay holders least; null without open interest
+    pub total_call_open_interest: u64,
+    pub total_put_open_interest: u64,
+    pub total_call_volume: u64,
+    pub total_put_volume: u64,
+    pub put_call_oi_ratio: Option<f64>,
+    pub put_call_volume_ratio: Option<f64>,
+    pub strikes: Vec<StrikeOpenInterest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StrikeOpenInterest {
+    pub strike: f64,
+    pub call_open_interest: u64,
+    pub put_open_interest: u64,
+    pub call_volume: u64,
+    pub put_volume: u64,
+}
+
+// Unusual Options Activity API
+#[derive(Debug, Deserialize)]
+pub struct UnusualActivityRequest {
+    pub tickers: Vec<String>,              // one chain, or a watchlist for a daily scan
+    pub min_volume: Option<u64>,           // contracts trading less are ignored, default 100
+    pub min_volume_oi_ratio: Option<f64>,  // default 2.0
+    pub min_sweep_premium: Option<f64>,    // dollars traded at the bid or ask, default 50000
+    pub min_iv_ratio: Option<f64>,         // implied vol / expiry median, default 1.5
+    pub max_days: Option<f64>,             // leave out expiries further away
+    pub risk_free_rate: Option<f64>,
+    pub limit: Option<usize>,              // top-ranked contracts returned, default 50
+    pub concurrency: Option<usize>,        // chains fetched at once
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnusualActivityResponse {
+    pub contracts: Vec<UnusualContract>, // highest score first
+    pub total_count: usize,              // flagged before `limit`
+    pub scanned: Vec<String>,            // tickers whose chains were scanned
+    pub errors: Vec<String>,             // tickers whose chains couldn't be fetched
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnusualContract {
+    pub symbol: String,
+    pub underlying_price: f64,
+    pub expiration_date: String,
+    pub days_to_expiry: f64,
+    pub option_type: String,
+    pub strike: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+    pub volume: u64,
+    pub open_interest: u64,
+    pub volume_oi_ratio: Option<f64>, // null without open interest
+    pub premium: f64,                 // volume x last x 100
+    pub sweep_side: Option<String>,   // "ask" (bought) or "bid" (sold) when the sweep signal fired
+    pub implied_volatility: Option<f64>,
+    pub iv_ratio: Option<f64>,        // implied vol / expiry median
+    pub signals: Vec<String>,         // "volume_oi", "sweep", "iv_spike"
+    pub score: f64,
 }
 
 // Options Math API
@@ -166,6 +542,11 @@ pub struct OptionsPnLRequest {
     pub volatility: Option<f64>,
     pub risk_free_rate: Option<f64>,
     pub days_to_expiry: Option<f64>,
+    pub pricing_model: Option<String>, // "black_scholes" (default) or "binomial"
+    pub dividend_yield: Option<f64>,
+    pub contract_size: Option<f64>,    // shares per contract for the aggregated Greeks, default 100
+    // Days from now to value the position at; default today, halfway to the nearest expiry, and that expiry
+    pub valuation_date_offsets: Option<Vec<f64>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -181,6 +562,41 @@ pub struct OptionPosition {
 pub struct OptionsPnLResponse {
     pub positions: Vec<PositionAnalysis>,
     pub portfolio: PortfolioAnalysis,
+    pub exposure: GreeksExposure,
+    pub probability: Option<ProbabilityAnalysis>, // None without positions
+}
+
+// Outcome at the nearest expiry, with the underlying lognormal from `underlying_price` at drift
+// risk_free_rate - dividend_yield and the request's volatility; the parameters are echoed here
+#[derive(Debug, Serialize)]
+pub struct ProbabilityAnalysis {
+    pub underlying_price: f64,
+    pub volatility: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    pub days: f64,           // horizon, the nearest expiry
+    pub expected_price: f64, // mean of the distribution
+    pub probability_of_profit: f64,
+    pub expected_pnl: f64,   // same units as the P&L curves
+    pub breakevens: Vec<BreakevenProbability>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BreakevenProbability {
+    pub price: f64,
+    pub probability_above: f64,    // of finishing above it at the horizon
+    pub probability_of_touch: f64, // of trading through it at any time before then
+}
+
+// Net Greeks of all positions in share terms, taken at the first underlying price
+#[derive(Debug, Serialize)]
+pub struct GreeksExposure {
+    pub underlying_price: f64,
+    pub net_delta: f64,         // share-equivalent delta
+    pub dollar_delta: f64,      // net_delta * underlying_price
+    pub net_gamma: f64,         // change in net_delta for a $1 move
+    pub net_theta_per_day: f64, // dollars per calendar day
+    pub net_vega: f64,          // dollars per volatility point (0.01)
 }
 
 #[derive(Debug, Serialize)]
@@ -192,11 +608,21 @@ pub struct PositionAnalysis {
 
 #[derive(Debug, Serialize)]
 pub struct PortfolioAnalysis {
-    pub total_greeks: GreeksData,
+    pub total_greeks: GreeksData, // per-share Greeks x quantity x contract size, summed; theoretical_price is the net mark
     pub total_pnl_curve: Vec<PnLPoint>,
     pub break_even_points: Vec<f64>,
     pub max_profit: Option<f64>,
     pub max_loss: Option<f64>,
+    pub pnl_curves_by_date: Vec<DatedPnLCurve>, // one per valuation date offset
+}
+
+// Whole-position P&L with every leg re-priced at the time it will have left on that date
+#[derive(Debug, Serialize)]
+pub struct DatedPnLCurve {
+    pub days_from_now: f64,
+    pub label: String, // "today", "expiry" (the nearest one) or "+10d"
+    pub pnl_curve: Vec<PnLPoint>,
+    pub break_even_points: Vec<f64>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -206,11 +632,97 @@ pub struct PnLPoint {
     pub total_value: f64,
 }
 
+// Position Sizing API
+#[derive(Debug, Deserialize)]
+pub struct PositionSizeRequest {
+    pub account_value: f64,
+    pub risk_pct: f64, // percent of the account to lose if the stop is hit, e.g. 1.0
+    pub entry: f64,
+    pub stop_distance: Option<f64>, // explicit distance from entry to stop, in price units
+    pub atr: Option<f64>,           // used with atr_multiplier when stop_distance is absent
+    pub atr_multiplier: Option<f64>, // default 2.0
+}
+
+#[derive(Debug, Serialize)]
+pub struct PositionSizeResponse {
+    pub shares: u64,
+    pub stop_distance: f64,
+    pub stop_price: f64, // for a long position
+    pub risk_amount: f64,
+    pub position_value: f64,
+}
+
+// Multi-timeframe Confluence API
+#[derive(Debug, Serialize)]
+pub struct ConfluenceResponse {
+    pub symbol: String,
+    pub timeframes: Vec<TimeframeScore>,
+    pub score: f64, // mean of the timeframe scores, -1 (bearish) to 1 (bullish)
+    pub bias: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeframeScore {
+    pub timeframe: String, // "daily", "weekly", "monthly"
+    pub candles: usize,
+    pub close: f64,
+    pub ma_alignment: Option<f64>, // 1 when close > SMA20 > SMA50, -1 when close < SMA20 < SMA50, else 0
+    pub rsi: Option<f64>,
+    pub macd: Option<f64>,
+    pub score: Option<f64>, // mean of the available signals; None without enough bars
+    pub bias: String,
+}
+
+// Indicator Signals API
+#[derive(Debug, Clone)]
+pub struct SignalsRequest {
+    pub ticker: String,
+    pub interval: Option<String>, // default 1d
+    pub range: Option<String>,    // default 1y
+    pub rules: Option<String>,    // e.g. "rsi(14,30,70),macd(12,26,9),sma_cross(50,200)"; see SignalRule::parse
+    pub weights: Option<String>,  // one per rule, e.g. "2,1,1"; all 1 when omitted
+    pub threshold: Option<f64>,   // |score| needed for a combined buy or sell, default 0.5
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignalsResponse {
+    pub symbol: String,
+    pub interval: String,
+    pub rules: Vec<RuleSignals>,
+    pub history: Vec<ScorePoint>, // one per bar, oldest first, for backtests
+    pub score: f64,               // latest weighted stance, -1 (sell) to 1 (buy)
+    pub signal: Signal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleSignals {
+    pub rule: String, // normalised spec, e.g. "rsi(14,30,70)"
+    pub weight: f64,
+    pub events: Vec<SignalEvent>, // bars where the rule fired
+    pub stance: Signal,           // its latest event, neutral if it never fired
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignalEvent {
+    pub date: String,
+    pub timestamp: i64,
+    pub signal: Signal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScorePoint {
+    pub timestamp: i64,
+    pub score: f64,
+    pub signal: Signal,
+}
+
 // Screener API
 // Enhanced screener request types
 #[derive(Debug, Deserialize)]
 pub struct ScreenerRequest {
+    #[serde(default)]
     pub filters: Vec<ScreenerFilter>,
+    pub tickers: Option<Vec<String>>, // screen this list instead of a Yahoo screener
     pub indicators: Option<Vec<IndicatorConfig>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>, // "asc" or "desc"
@@ -218,16 +730,101 @@ pub struct ScreenerRequest {
     pub offset: Option<usize>,
     pub screener_type: Option<String>, // "predefined" or "custom"
     pub predefined_screener: Option<String>, // "most_actives", "gainers", "losers", etc.
+    pub concurrency: Option<usize>, // symbols fetched at once for quotes / indicator candles
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ScreenerFilter {
-    pub field: String, // "price", "volume", "market_cap", "pe_ratio", "change_percent", etc.
+    pub field: String, // "price", "volume", "market_cap", "pe_ratio", "change_percent", or an indicator label like "rsi(period=14)"
     pub operator: String, // "gt", "lt", "gte", "lte", "eq", "between", "in"
     pub value: serde_json::Value,
     pub secondary_value: Option<serde_json::Value>, // For "between" operator
 }
 
+// Fields every screener result carries; anything else must name one of the request's indicators
+const SCREENER_QUOTE_FIELDS: [&str; 6] = ["price", "change", "change_percent", "volume", "market_cap", "pe_ratio"];
+
+// A quote field or the latest value of a computed indicator; None when the result doesn't have it
+fn screener_field(result: &ScreenerResult, field: &str) -> Option<f64> {
+    match field {
+        "price" => Some(result.price),
+        "change" => Some(result.change),
+        "change_percent" => Some(result.change_percent),
+        "volume" => Some(result.volume as f64),
+        "market_cap" => result.market_cap,
+        "pe_ratio" => result.pe_ratio,
+        _ => result.indicators.as_ref().and_then(|values| values.get(field).copied()),
+    }
+}
+
+impl ScreenerFilter {
+    fn number(&self, value: &serde_json::Value) -> Result<f64, ApiError> {
+        value.as_f64().ok_or_else(|| {
+            ApiError::InvalidParameters(format!("Filter on {} needs a numeric value, got {}", self.field, value))
+        })
+    }
+
+    // Every field the filter reads. A string value names another field, so
+    // {"field": "price", "operator": "gt", "value": "sma(period=200)"} means price above the SMA.
+    fn fields(&self) -> Vec<&str> {
+        let mut fields = vec![self.field.as_str()];
+        fields.extend(self.value.as_str());
+        fields.extend(self.secondary_value.as_ref().and_then(|v| v.as_str()));
+        fields
+    }
+
+    fn uses_indicators(&self) -> bool {
+        self.fields().iter().any(|field| !SCREENER_QUOTE_FIELDS.contains(field))
+    }
+
+    // Evaluates the filter against one result; a missing field never matches
+    pub fn matches(&self, result: &ScreenerResult) -> Result<bool, ApiError> {
+        let Some(actual) = screener_field(result, &self.field) else {
+            return Ok(false);
+        };
+        let operand = |value: &serde_json::Value| -> Result<Option<f64>, ApiError> {
+            match value.as_str() {
+                Some(field) => Ok(screener_field(result, field)),
+                None => self.number(value).map(Some),
+            }
+        };
+
+        Ok(match self.operator.as_str() {
+            "gt" => operand(&self.value)?.is_some_and(|v| actual > v),
+            "lt" => operand(&self.value)?.is_some_and(|v| actual < v),
+            "gte" => operand(&self.value)?.is_some_and(|v| actual >= v),
+            "lte" => operand(&self.value)?.is_some_and(|v| actual <= v),
+            "eq" => operand(&self.value)?.is_some_and(|v| (actual - v).abs() < 1e-9),
+            "between" => {
+                let secondary = self.secondary_value.as_ref()
+                    .ok_or_else(|| ApiError::InvalidParameters("Between operator requires secondary_value".to_string()))?;
+                match (operand(&self.value)?, operand(secondary)?) {
+                    (Some(a), Some(b)) => actual >= a.min(b) && actual <= a.max(b),
+                    _ => false,
+                }
+            }
+            "in" => {
+                let values = self.value.as_array()
+                    .ok_or_else(|| ApiError::InvalidParameters("In operator requires array value".to_string()))?
+                    .iter()
+                    .map(|v| self.number(v))
+                    .collect::<Result<Vec<_>, _>>()?;
+                values.iter().any(|v| (actual - v).abs() < 1e-9)
+            }
+            _ => return Err(ApiError::InvalidParameters(format!("Unknown operator: {}", self.operator))),
+        })
+    }
+}
+
+fn matches_all(filters: &[ScreenerFilter], result: &ScreenerResult) -> Result<bool, ApiError> {
+    for filter in filters {
+        if !filter.matches(result)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
 // Yahoo Finance screener response structures
 #[derive(Debug, Deserialize)]
 pub struct YahooScreenerResponse {
@@ -248,6 +845,7 @@ pub struct YahooScreenerResult {
     pub criteria: Option<serde_json::Value>,
     pub predefined: Option<bool>,
     pub count: Option<u32>,
+    pub total: Option<u32>, // matches across all pages
     pub quotes: Option<Vec<YahooScreenerQuote>>,
 }
 
@@ -345,14 +943,116 @@ pub struct ScreenerResponse {
 pub struct QuoteRequest {
     pub tickers: Vec<String>,
     pub fields: Option<Vec<String>>, // Specific fields to return
+    pub include_prepost: Option<bool>, // pre/post-market prices on each quote (default true)
 }
 
 #[derive(Debug, Serialize)]
 pub struct QuoteResponse {
-    pub quotes: HashMap<String, Quote>,
+    pub quotes: BTreeMap<String, Quote>, // ordered by ticker
     pub errors: Vec<String>,
 }
 
+// Corporate actions (/api/v1/events), oldest first
+#[derive(Debug, Serialize, Clone)]
+pub struct DividendAction {
+    pub date: String, // ex-dividend date, YYYY-MM-DD
+    pub timestamp: i64,
+    pub amount: f64,  // per share
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SplitAction {
+    pub date: String,
+    pub timestamp: i64,
+    pub numerator: f64,
+    pub denominator: f64,
+    pub ratio: String, // e.g. "4:1"
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorporateActionsResponse {
+    pub symbol: String,
+    pub dividends: Vec<DividendAction>,
+    pub splits: Vec<SplitAction>,
+}
+
+// Yahoo /v7/finance/quote: many symbols per request, with fundamentals the chart meta lacks
+#[derive(Debug, Deserialize)]
+pub struct YahooQuoteResponse {
+    #[serde(rename = "quoteResponse")]
+    pub quote_response: YahooQuoteResult,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YahooQuoteResult {
+    #[serde(default)]
+    pub result: Vec<YahooQuote>,
+    pub error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YahooQuote {
+    pub symbol: String,
+    pub regular_market_price: Option<f64>,
+    pub regular_market_change: Option<f64>,
+    pub regular_market_change_percent: Option<f64>,
+    pub regular_market_volume: Option<u64>,
+    pub regular_market_previous_close: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub bid_size: Option<u64>,
+    pub ask_size: Option<u64>,
+    pub fifty_two_week_high: Option<f64>,
+    pub fifty_two_week_low: Option<f64>,
+    pub market_cap: Option<f64>,
+    #[serde(rename = "trailingPE")]
+    pub trailing_pe: Option<f64>,
+    pub dividend_yield: Option<f64>, // percent, e.g. 0.52
+    pub pre_market_price: Option<f64>,
+    pub pre_market_change: Option<f64>,
+    pub post_market_price: Option<f64>,
+    pub post_market_change: Option<f64>,
+    pub regular_market_time: Option<i64>,
+}
+
+impl YahooQuote {
+    // None when Yahoo returned the symbol without a regular-market price (delisted, bad ticker)
+    pub fn into_quote(self) -> Option<Quote> {
+        let price = self.regular_market_price?;
+        let previous_close = self.regular_market_previous_close.unwrap_or(price);
+        let change = self.regular_market_change.unwrap_or(price - previous_close);
+        let change_percent = self.regular_market_change_percent
+            .unwrap_or_else(|| if previous_close != 0.0 { change / previous_close * 100.0 } else { 0.0 });
+        let last_updated = self.regular_market_time
+            .and_then(|t| Utc.timestamp_opt(t, 0).single())
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339();
+
+        Some(Quote {
+            symbol: self.symbol,
+            price,
+            change,
+            change_percent,
+            volume: self.regular_market_volume.unwrap_or(0),
+            bid: self.bid,
+            ask: self.ask,
+            bid_size: self.bid_size,
+            ask_size: self.ask_size,
+            high_52w: self.fifty_two_week_high.unwrap_or(price),
+            low_52w: self.fifty_two_week_low.unwrap_or(price),
+            market_cap: self.market_cap,
+            pe_ratio: self.trailing_pe,
+            dividend_yield: self.dividend_yield.map(|y| y / 100.0),
+            pre_market_price: self.pre_market_price,
+            pre_market_change: self.pre_market_change,
+            post_market_price: self.post_market_price,
+            post_market_change: self.post_market_change,
+            last_updated,
+        })
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct Quote {
     pub symbol: String,
@@ -369,39 +1069,349 @@ pub struct Quote {
     pub market_cap: Option<f64>,
     pub pe_ratio: Option<f64>,
     pub dividend_yield: Option<f64>,
+    pub pre_market_price: Option<f64>,
+    pub pre_market_change: Option<f64>,  // vs previous regular close
+    pub post_market_price: Option<f64>,
+    pub post_market_change: Option<f64>, // vs today's regular close
     pub last_updated: String, // ISO 8601 timestamp
 }
 
-// Watchlist API
+// Quote stream (/api/v1/stream) messages. Clients send
+// {"action": "subscribe", "tickers": ["AAPL"]} or {"action": "unsubscribe", "tickers": ["AAPL"]}
 #[derive(Debug, Deserialize)]
-pub struct WatchlistRequest {
-    pub name: String,
-    pub tickers: Vec<String>,
-    pub alerts: Option<Vec<AlertConfig>>,
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum StreamCommand {
+    Subscribe { tickers: Vec<String> },
+    Unsubscribe { tickers: Vec<String> },
 }
 
-#[derive(Debug, Deserialize)]
-pub struct AlertConfig {
-    pub ticker: String,
-    pub condition: String, // "price_above", "price_below", "volume_above", etc.
-    pub value: f64,
-    pub indicator: Option<String>,
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StreamMessage {
+    Subscriptions { tickers: Vec<String> },
+    Quotes { quotes: BTreeMap<String, Quote>, errors: Vec<String> },
+    Error { message: String },
 }
 
-#[derive(Debug, Serialize)]
-pub struct WatchlistResponse {
-    pub name: String,
-    pub quotes: Vec<Quote>,
-    pub alerts: Vec<TriggeredAlert>,
+// Server-sent events (/api/v1/sse) subscription
+#[derive(Debug, Clone)]
+pub struct SseSettings {
+    pub tickers: BTreeSet<String>,
+    pub interval: Duration,               // between quote pushes
+    pub candle_interval: String,          // intraday bars whose close sends an indicators event
+    pub indicators: Vec<IndicatorConfig>, // empty: no indicators events
 }
 
+// The `indicators` event: one ticker's bar that just closed and the indicator values on it
 #[derive(Debug, Serialize)]
-pub struct TriggeredAlert {
-    pub ticker: String,
-    pub condition: String,
-    pub current_value: f64,
-    pub trigger_value: f64,
-    pub timestamp: String,
+pub struct IndicatorUpdate {
+    pub symbol: String,
+    pub interval: String,
+    pub candle: CandleData,
+    pub indicators: HashMap<String, f64>, // indicators still in warmup are left out
+}
+
+#[derive(Debug)]
+pub struct ClosedCandles {
+    pub updates: Vec<IndicatorUpdate>,
+    pub next_close: Option<i64>, // unix time the earliest bar still forming closes; None outside trading hours
+    pub errors: Vec<String>,
+}
+
+// Portfolio Risk API
+#[derive(Debug, Deserialize)]
+pub struct PortfolioRiskRequest {
+    pub range: Option<String>,       // daily closes to measure over, default "1y"
+    pub benchmark: Option<String>,   // for beta, default "SPY"
+    pub confidence: Option<f64>,     // VaR / CVaR level, default 0.95
+    pub risk_free_rate: Option<f64>, // annual, for Sharpe and Sortino; default 0.01
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioRiskResponse {
+    pub portfolio_id: String,
+    pub benchmark: String,
+    pub range: String,
+    pub observations: usize,            // daily returns every holding and the benchmark share
+    pub market_value: f64,              // holdings at the last close, cash excluded
+    pub weights: BTreeMap<String, f64>, // share of market_value per symbol
+    pub beta: Option<f64>,
+    pub volatility: f64, // annualized
+    pub annual_return: f64,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub confidence: f64,
+    pub var: f64,        // one-day loss as a fraction of market_value
+    pub cvar: f64,
+    pub var_amount: f64, // the same in currency
+    pub cvar_amount: f64,
+    pub max_drawdown: f64,
+}
+
+// Correlation API
+#[derive(Debug, Deserialize)]
+pub struct CorrelationRequest {
+    pub tickers: Vec<String>,
+    pub range: Option<String>, // daily closes to correlate, default "1y"
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrelationResponse {
+    pub tickers: Vec<String>,                     // rows and columns of `matrix`; tickers that couldn't be fetched are left out
+    pub range: String,
+    pub observations: usize,                      // daily returns every ticker shares
+    pub matrix: Vec<Vec<Option<f64>>>,            // null where a ticker's price didn't move
+    pub average_correlation: Option<f64>,         // over all distinct pairs
+    pub average_by_ticker: BTreeMap<String, f64>, // each ticker against the rest; low values diversify
+    pub errors: Vec<String>,
+}
+
+// Batch API: one command over a list of tickers, a tickers file's contents or the NASDAQ listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchCommand {
+    #[default]
+    Quote,
+    Hist,
+    Options,
+}
+
+impl BatchCommand {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "quote" => Some(BatchCommand::Quote),
+            "hist" => Some(BatchCommand::Hist),
+            "options" => Some(BatchCommand::Options),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchRequest {
+    pub command: Option<BatchCommand>, // default quote
+    pub tickers: Option<Vec<String>>,
+    pub symbols: Option<String>, // a tickers file: one symbol per line, or the NASDAQ listings CSV
+    pub nasdaq: Option<bool>,    // every NASDAQ-listed symbol
+    pub range: Option<String>,   // hist: default 1mo
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub command: BatchCommand,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchResult>, // in ticker order
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    pub ticker: String,
+    pub summary: Option<String>, // one line, as the CLI prints it
+    pub error: Option<String>,
+}
+
+// Bundle API: the quote, candles, options summary and news for a symbol view in one call
+#[derive(Debug, Deserialize)]
+pub struct BundleRequest {
+    pub items: Vec<BundleItem>, // one per ticker
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleItem {
+    pub ticker: String,
+    pub quote: Option<bool>,
+    pub candles: Option<BundleCandles>,
+    pub options: Option<BundleOptions>,
+    pub news: Option<BundleNews>,
+}
+
+// The per-symbol part of HistoricalDataRequest; items asking for the same candles share one request
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct BundleCandles {
+    pub interval: Option<String>,
+    pub range: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub include_indicators: Option<bool>,
+    pub indicators: Option<Vec<IndicatorConfig>>,
+    pub include_returns: Option<bool>,
+    pub adjust: Option<bool>,
+    pub include_prepost: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleOptions {
+    pub max_days: Option<f64>, // leave out expiries further away
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleNews {
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleResponse {
+    pub results: BTreeMap<String, BundleResult>, // by ticker, upper-cased
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct BundleResult {
+    pub quote: Option<Quote>,
+    pub candles: Option<TickerData>,
+    pub options: Option<OptionsSummary>,
+    pub news: Option<NewsResponse>,
+    pub errors: Vec<String>, // the parts that failed, prefixed with their name; the rest are still filled in
+}
+
+// The open interest profile without its per-strike rows
+#[derive(Debug, Serialize)]
+pub struct OptionsSummary {
+    pub underlying_price: f64,
+    pub total_call_open_interest: u64,
+    pub total_put_open_interest: u64,
+    pub put_call_oi_ratio: Option<f64>,
+    pub put_call_volume_ratio: Option<f64>,
+    pub nearest_expiration: Option<String>,
+    pub nearest_max_pain: Option<f64>,
+    pub expirations: Vec<String>, // nearest first
+}
+
+impl From<OiProfileResponse> for OptionsSummary {
+    fn from(profile: OiProfileResponse) -> Self {
+        let nearest = profile.expirations.first();
+        Self {
+            underlying_price: profile.underlying_price,
+            total_call_open_interest: profile.total_call_open_interest,
+            total_put_open_interest: profile.total_put_open_interest,
+            put_call_oi_ratio: profile.put_call_oi_ratio,
+            put_call_volume_ratio: profile.put_call_volume_ratio,
+            nearest_expiration: nearest.map(|e| e.expiration_date.clone()),
+            nearest_max_pain: nearest.and_then(|e| e.max_pain),
+            expirations: profile.expirations.iter().map(|e| e.expiration_date.clone()).collect(),
+        }
+    }
+}
+
+impl BundleCandles {
+    fn to_request(&self, tickers: Vec<String>) -> HistoricalDataRequest {
+        HistoricalDataRequest {
+            tickers,
+            interval: self.interval.clone(),
+            range: self.range.clone(),
+            start_date: self.start_date.clone(),
+            end_date: self.end_date.clone(),
+            include_indicators: self.include_indicators.or(Some(self.indicators.is_some())),
+            indicators: self.indicators.clone(),
+            parallel_indicators: None,
+            min_candles: None,
+            include_returns: self.include_returns,
+            benchmark: None,
+            adjust: self.adjust,
+            resample_to: None,
+            missing_data: None,
+            transform: None,
+            brick_size: None,
+            include_prepost: self.include_prepost,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+// A finished piece of a bundle, for the ticker(s) it belongs to
+enum BundlePart {
+    Candles(Vec<String>, Result<HistoricalDataResponse, ApiError>),
+    Options(String, Result<OiProfileResponse, ApiError>),
+    News(String, Result<NewsResponse, ApiError>),
+}
+
+// The batch error naming `ticker`, for parts that came back without it
+fn batch_error_for(errors: &[String], ticker: &str) -> String {
+    errors.iter()
+        .find(|e| e.contains(ticker))
+        .cloned()
+        .unwrap_or_else(|| format!("No data returned for {}", ticker))
+}
+
+// Portfolio Rebalance API
+#[derive(Debug, Deserialize)]
+pub struct RebalanceRequest {
+    pub drift_threshold: Option<f64>, // weights closer than this to target are left alone, default 0.05
+    pub fractional: Option<bool>,     // trade fractional shares, default false
+}
+
+#[derive(Debug, Serialize)]
+pub struct RebalanceResponse {
+    pub portfolio_id: String,
+    pub total_value: f64, // holdings at the current price plus cash
+    pub cash_balance: f64,
+    pub cash_after: f64,
+    pub drift_threshold: f64,
+    pub trades: Vec<RebalanceTrade>, // one per held or targeted symbol
+}
+
+#[derive(Debug, Serialize)]
+pub struct RebalanceTrade {
+    pub symbol: String,
+    pub price: f64,
+    pub quantity: f64, // held now
+    pub current_weight: f64,
+    pub target_weight: f64, // 0 for holdings without a target
+    pub drift: f64,         // current - target
+    pub action: String,     // "buy", "sell" or "hold"
+    pub trade_quantity: f64,
+    pub trade_value: f64,
+    pub weight_after: f64,
+}
+
+// Portfolio Optimizer API
+#[derive(Debug, Deserialize)]
+pub struct OptimizeRequest {
+    pub tickers: Vec<String>,
+    pub range: Option<String>,           // daily returns to estimate from, default "1y"
+    pub long_only: Option<bool>,         // default true; otherwise weights may go down to -max_weight
+    pub max_weight: Option<f64>,         // cap on any one weight, default 1
+    pub risk_free_rate: Option<f64>,     // annual, default 0.01
+    pub frontier_points: Option<usize>,  // default 20
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptimizeResponse {
+    pub tickers: Vec<String>, // tickers that couldn't be fetched are left out
+    pub range: String,
+    pub observations: usize,
+    pub expected_returns: BTreeMap<String, f64>, // annualized mean daily return
+    pub volatilities: BTreeMap<String, f64>,     // annualized
+    pub min_variance: OptimizedPortfolio,
+    pub max_sharpe: Option<OptimizedPortfolio>,  // null when nothing has volatility
+    pub frontier: Vec<OptimizedPortfolio>,       // from minimum variance to the highest return
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptimizedPortfolio {
+    pub weights: BTreeMap<String, f64>,
+    pub expected_return: f64,
+    pub volatility: f64,
+    pub sharpe_ratio: Option<f64>,
+}
+
+// Watchlist quotes: one entry per member, in watchlist order
+#[derive(Debug, Serialize)]
+pub struct WatchlistQuotesResponse {
+    pub watchlist: Watchlist,
+    pub members: Vec<WatchlistMember>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchlistMember {
+    pub symbol: String,
+    pub quote: Option<Quote>,            // None when the symbol couldn't be quoted (see errors)
+    pub indicators: HashMap<String, f64>, // latest value of each requested indicator on daily bars
 }
 
 // Quote Summary API Types
@@ -414,6 +1424,21 @@ pub struct QuoteSummaryResponse {
     pub summary_detail: Option<SummaryDetail>,
     pub price: Option<PriceData>,
     pub summary_profile: Option<SummaryProfile>,
+    pub earnings: Option<EarningsData>,
+    pub calendar_events: Option<CalendarEvents>,
+    pub recommendation_trend: Option<Vec<AnalystRecommendation>>,
+    pub earnings_trend: Option<EarningsTrend>,
+    pub next_earnings: Option<UpcomingEarnings>,
+}
+
+// Next scheduled report, from the calendarEvents module
+#[derive(Debug, Serialize)]
+pub struct UpcomingEarnings {
+    pub date: String,                  // YYYY-MM-DD
+    pub date_range_end: Option<String>, // last day of the window when the date isn't confirmed yet
+    pub is_estimate: bool,
+    pub eps_estimate: Option<f64>,
+    pub revenue_estimate: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -509,11 +1534,15 @@ pub struct SummaryDetail {
     pub bid_size: Option<u32>,
     pub ask_size: Option<u32>,
     pub market_cap: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub trailing_pe: Option<f64>,
     pub yield_: Option<f64>,
     pub ytd_return: Option<f64>,
     pub total_assets: Option<f64>,
     pub expense_ratio: Option<f64>,
     pub beta: Option<f64>,
+    pub fifty_two_week_high: Option<f64>,
+    pub fifty_two_week_low: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -528,9 +1557,12 @@ pub struct PriceData {
     pub pre_market_price: Option<f64>,
     pub pre_market_change: Option<f64>,
     pub pre_market_change_percent: Option<f64>,
+    pub pre_market_time: Option<i64>,
     pub post_market_price: Option<f64>,
     pub post_market_change: Option<f64>,
     pub post_market_change_percent: Option<f64>,
+    pub post_market_time: Option<i64>,
+    pub market_state: Option<String>, // Yahoo's PREPRE, PRE, REGULAR, POST, POSTPOST or CLOSED
 }
 
 #[derive(Debug, Serialize)]
@@ -547,6 +1579,103 @@ pub struct SummaryProfile {
     pub full_time_employees: Option<u64>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct EarningsData {
+    pub financial_currency: Option<String>,
+    pub quarterly_eps: Vec<QuarterlyEps>,       // last four reported quarters
+    pub current_quarter: Option<String>,        // e.g. "4Q2024"
+    pub current_quarter_estimate: Option<f64>,
+    pub yearly: Vec<PeriodFinancials>,
+    pub quarterly: Vec<PeriodFinancials>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuarterlyEps {
+    pub quarter: String, // e.g. "3Q2024"
+    pub actual: Option<f64>,
+    pub estimate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeriodFinancials {
+    pub period: String, // "2023" or "3Q2024"
+    pub revenue: Option<f64>,
+    pub earnings: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CalendarEvents {
+    pub earnings_dates: Vec<String>, // one date, or the first and last day of an unconfirmed window
+    pub is_earnings_date_estimate: bool,
+    pub earnings_average: Option<f64>,
+    pub earnings_low: Option<f64>,
+    pub earnings_high: Option<f64>,
+    pub revenue_average: Option<f64>,
+    pub revenue_low: Option<f64>,
+    pub revenue_high: Option<f64>,
+    pub ex_dividend_date: Option<String>,
+    pub dividend_date: Option<String>,
+}
+
+// Cache endpoints (/api/v1/cache/stats, /api/v1/cache/clear)
+#[derive(Debug, Serialize)]
+pub struct CacheStatsResponse {
+    pub responses: ResponseCacheStats,
+    pub fundamentals_entries: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CacheClearResponse {
+    pub responses_cleared: usize,
+    pub fundamentals_cleared: usize,
+}
+
+// Symbol search (/api/v1/search)
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub results: Vec<SymbolMatch>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolMatch {
+    pub symbol: String,
+    pub name: String,
+    pub exchange: String,
+    pub asset_type: String, // lowercased Yahoo quoteType: equity, etf, index, cryptocurrency, ...
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YahooSearchResponse {
+    #[serde(default)]
+    pub quotes: Vec<YahooSearchQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YahooSearchQuote {
+    pub symbol: Option<String>, // absent on some non-security hits
+    #[serde(rename = "shortname")]
+    pub short_name: Option<String>,
+    #[serde(rename = "longname")]
+    pub long_name: Option<String>,
+    pub exchange: Option<String>,
+    pub exch_disp: Option<String>,
+    pub quote_type: Option<String>,
+}
+
+impl YahooSearchQuote {
+    pub fn into_match(self) -> Option<SymbolMatch> {
+        let symbol = self.symbol?;
+        Some(SymbolMatch {
+            name: self.long_name.or(self.short_name).unwrap_or_else(|| symbol.clone()),
+            exchange: self.exch_disp.or(self.exchange).unwrap_or_default(),
+            asset_type: self.quote_type.map(|t| t.to_lowercase()).unwrap_or_default(),
+            symbol,
+        })
+    }
+}
+
 // News API Types
 #[derive(Debug, Serialize)]
 pub struct NewsResponse {
@@ -570,6 +1699,13 @@ pub struct NewsStory {
 }
 
 // Calendar API Types
+#[derive(Debug, Deserialize)]
+pub struct CalendarRequest {
+    pub from: Option<String>,         // YYYY-MM-DD, default today
+    pub to: Option<String>,           // inclusive, default a week after `from`
+    pub tickers: Option<Vec<String>>, // only events for these symbols
+}
+
 #[derive(Debug, Serialize)]
 pub struct CalendarResponse {
     pub earnings: Vec<EarningsEvent>,
@@ -642,16 +1778,73 @@ pub struct FinancialStatement {
     pub data: HashMap<String, Option<f64>>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementPeriod {
+    Annual,
+    Quarterly,
+}
+
+impl StatementPeriod {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "annual" | "yearly" => Some(StatementPeriod::Annual),
+            "quarterly" | "quarter" => Some(StatementPeriod::Quarterly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatementPeriod::Annual => "annual",
+            StatementPeriod::Quarterly => "quarterly",
+        }
+    }
+}
+
+// Fundamentals API
+#[derive(Debug, Serialize)]
+pub struct FundamentalsResponse {
+    pub symbol: String,
+    pub period_type: String,
+    pub currency: Option<String>,
+    pub financials: FinancialReports, // statements newest first, items keyed like totalRevenue
+}
+
 #[derive(Debug, Serialize)]
 pub struct AnalysisReports {
-    pub analyst_recommendations: Vec<AnalystRecommendation>,
+    pub symbol: String,
+    pub analyst_recommendations: Vec<AnalystRecommendation>, // current month first
     pub earnings_estimates: Vec<EarningsEstimate>,
     pub revenue_estimates: Vec<RevenueEstimate>,
+    pub eps_trend: Vec<EpsTrend>,
 }
 
+// The earningsTrend module: one entry per period in each list
+#[derive(Debug, Serialize, Default)]
+pub struct EarningsTrend {
+    pub earnings_estimates: Vec<EarningsEstimate>,
+    pub revenue_estimates: Vec<RevenueEstimate>,
+    pub eps_trend: Vec<EpsTrend>,
+}
+
+// How the consensus EPS for a period has moved, and how many analysts revised it
 #[derive(Debug, Serialize)]
-pub struct AnalystRecommendation {
+pub struct EpsTrend {
     pub period: String,
+    pub current: Option<f64>,
+    pub seven_days_ago: Option<f64>,
+    pub thirty_days_ago: Option<f64>,
+    pub sixty_days_ago: Option<f64>,
+    pub ninety_days_ago: Option<f64>,
+    pub up_last_7_days: Option<u32>,
+    pub up_last_30_days: Option<u32>,
+    pub down_last_7_days: Option<u32>,
+    pub down_last_30_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalystRecommendation {
+    pub period: String, // "0m" is this month, "-1m" the one before
     pub strong_buy: u32,
     pub buy: u32,
     pub hold: u32,
@@ -662,7 +1855,7 @@ pub struct AnalystRecommendation {
 
 #[derive(Debug, Serialize)]
 pub struct EarningsEstimate {
-    pub period: String,
+    pub period: String, // "0q" this quarter, "+1q" next, "0y" this fiscal year, "+1y" next
     pub avg: Option<f64>,
     pub low: Option<f64>,
     pub high: Option<f64>,
@@ -682,134 +1875,192 @@ pub struct RevenueEstimate {
     pub sales_growth: Option<f64>,
 }
 
-// Crumb cache structure
+// quoteSummary modules /api/v1/quotesummary returns unless `modules` picks some
+pub const QUOTE_SUMMARY_MODULES: [&str; 10] = [
+    "assetProfile", "financialData", "defaultKeyStatistics", "summaryDetail", "price", "summaryProfile", "earnings", "calendarEvents",
+    "recommendationTrend", "earningsTrend",
+];
+
+// Symbols per /v7/finance/quote request; longer URLs start getting rejected
+const QUOTE_BATCH_SIZE: usize = 50;
+
+// Matches returned by /api/v1/search unless `limit` says otherwise
+const SEARCH_DEFAULT_LIMIT: u32 = 10;
+const SEARCH_MAX_LIMIT: u32 = 25;
+
+// Rows per visualization calendar request, and the most fetched for one calendar
+const CALENDAR_PAGE_SIZE: usize = 100;
+const CALENDAR_MAX_ROWS: usize = 1000;
+// Days covered by /api/v1/calendar when `to` is left out
+const CALENDAR_DEFAULT_DAYS: i64 = 7;
+
+// fundamentals-timeseries items per statement, requested with an annual or quarterly prefix
+const INCOME_STATEMENT_ITEMS: [&str; 18] = [
+    "TotalRevenue", "CostOfRevenue", "GrossProfit", "ResearchAndDevelopment", "SellingGeneralAndAdministration",
+    "OperatingExpense", "OperatingIncome", "InterestExpense", "PretaxIncome", "TaxProvision", "NetIncome",
+    "NetIncomeCommonStockholders", "EBIT", "EBITDA", "BasicEPS", "DilutedEPS", "BasicAverageShares", "DilutedAverageShares",
+];
+const BALANCE_SHEET_ITEMS: [&str; 18] = [
+    "TotalAssets", "CurrentAssets", "CashAndCashEquivalents", "CashCashEquivalentsAndShortTermInvestments",
+    "AccountsReceivable", "Inventory", "NetPPE", "Goodwill", "TotalLiabilitiesNetMinorityInterest", "CurrentLiabilities",
+    "AccountsPayable", "LongTermDebt", "TotalDebt", "NetDebt", "StockholdersEquity", "RetainedEarnings", "WorkingCapital",
+    "OrdinarySharesNumber",
+];
+const CASH_FLOW_ITEMS: [&str; 13] = [
+    "OperatingCashFlow", "CapitalExpenditure", "FreeCashFlow", "InvestingCashFlow", "FinancingCashFlow",
+    "DepreciationAndAmortization", "StockBasedCompensation", "CashDividendsPaid", "RepurchaseOfCapitalStock",
+    "IssuanceOfDebt", "RepaymentOfDebt", "ChangesInCash", "EndCashPosition",
+];
+// Start of the timeseries window; Yahoo only has the last few years anyway
+const FUNDAMENTALS_PERIOD_START: i64 = 493_590_046;
+
+// Yahoo caps a single screener call at 250 quotes
+const SCREENER_MAX_PAGE_SIZE: u32 = 250;
+// Quotes pulled from a Yahoo screener before local filters, sorting and paging are applied
+const SCREENER_UNIVERSE_SIZE: u32 = 250;
+// Symbols fetched at once when building a ticker universe or candles for indicator filters
+const SCREENER_DEFAULT_CONCURRENCY: usize = 8;
+const SCREENER_MAX_CONCURRENCY: usize = 16;
+// Daily bars fetched per candidate for indicator screening; a year covers a 200-day SMA
+const SCREENER_INDICATOR_RANGE: &str = "1y";
+// Symbols whose candles are fetched at once for watchlist indicators
+const WATCHLIST_INDICATOR_CONCURRENCY: usize = 4;
+
+const REBALANCE_DEFAULT_DRIFT: f64 = 0.05;
+const RISK_DEFAULT_RANGE: &str = "1y";
+const RISK_DEFAULT_BENCHMARK: &str = "SPY";
+const RISK_DEFAULT_CONFIDENCE: f64 = 0.95;
+// Daily histories fetched at once for portfolio analytics
+const HISTORY_FETCH_CONCURRENCY: usize = 4;
+// Tickers per /api/v1/batch request (the CLI's --tickers-file has no cap), and how many run at once
+const BATCH_MAX_TICKERS: usize = 500;
+const BATCH_DEFAULT_CONCURRENCY: usize = 8;
+const BATCH_MAX_CONCURRENCY: usize = 16;
+const BATCH_DEFAULT_RANGE: &str = "1mo";
+// Symbols per bundle request, and parts fetched at once
+const BUNDLE_MAX_ITEMS: usize = 20;
+const BUNDLE_CONCURRENCY: usize = 6;
+// How often the scheduler looks for due jobs
+const SCHEDULER_TICK: Duration = Duration::from_secs(1);
+// Bars next_candle paper orders fill against; orders left open longer only see the latest window
+const ORDER_CANDLE_INTERVAL: &str = "1m";
+const ORDER_CANDLE_RANGE: &str = "5d";
+// Intraday bars behind /api/v1/sse indicator events; five days covers the warmup at any interval
+const SSE_INDICATOR_RANGE: &str = "5d";
+// Tickers one correlation or optimization request may cover
+const ANALYTICS_MAX_TICKERS: usize = 50;
+const OPTIMIZER_DEFAULT_FRONTIER_POINTS: usize = 20;
+const OPTIMIZER_MAX_FRONTIER_POINTS: usize = 100;
+
+// quoteSummary fields merged into chart quotes; they move slowly so a short TTL saves a round trip
+const FUNDAMENTALS_TTL: Duration = Duration::from_secs(15 * 60);
+
 #[derive(Clone)]
-pub struct CrumbCache {
-    pub crumb: String,
+pub struct FundamentalsCache {
+    pub market_cap: Option<f64>,
+    pub pe_ratio: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub range_52w: Option<(f64, f64)>, // (low, high)
     pub expires_at: Instant,
 }
 
-impl CrumbCache {
+impl FundamentalsCache {
     pub fn is_expired(&self) -> bool {
         Instant::now() > self.expires_at
     }
 }
 
+pub const DEFAULT_STREAM_INTERVAL: Duration = Duration::from_secs(5);
+pub const MIN_STREAM_INTERVAL: Duration = Duration::from_secs(1);
+
+// Cookies and crumb live in the process-wide yahoo_session, so clients are cheap to create
 pub struct YahooFinanceClient {
     client: reqwest::Client,
-    crumb: Option<String>,
+    cache: Arc<ResponseCache>, // the owning StockDataApi's
 }
 
 impl YahooFinanceClient {
-    pub fn new() -> Self {
-        let jar = Arc::new(reqwest::cookie::Jar::default());
+    pub fn new(cache: Arc<ResponseCache>) -> Self {
         let client = reqwest::Client::builder()
-            .cookie_provider(jar)
+            .cookie_provider(yahoo_session::global().cookie_store())
             .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
             .timeout(Duration::from_secs(30))
             //.gzip(true)
             .build()
             .expect("Failed to create HTTP client");
 
-        Self {
-            client,
-            crumb: None,
-        }
-    }
-
-    // Enhanced crumb caching with TTL
-    pub async fn get_cached_crumb(&mut self, symbol: &str, cache: &AsyncRwLock<Option<CrumbCache>>) -> Result<String, ApiError> {
-        // Check cache first
-        {
-            let cache_read = cache.read().await;
-            if let Some(cached) = cache_read.as_ref() {
-                if !cached.is_expired() {
-                    return Ok(cached.crumb.clone());
-                }
-            }
-        }
-
-        // Cache miss or expired, fetch new crumb
-        let new_crumb = self.get_crumb(symbol).await?;
-        
-        // Update cache with 1 hour TTL
-        {
-            let mut cache_write = cache.write().await;
-            *cache_write = Some(CrumbCache {
-                crumb: new_crumb.clone(),
-                expires_at: Instant::now() + Duration::from_secs(3600), // 1 hour
-            });
-        }
-
-        Ok(new_crumb)
+        Self { client, cache }
     }
 
-    pub async fn get_crumb(&mut self, symbol: &str) -> Result<String, ApiError> {
-        if let Some(ref crumb) = self.crumb {
-            return Ok(crumb.clone());
+    // The session crumb, fetched (and saved with the session) when there is none or it has expired
+    pub async fn get_crumb(&self, symbol: &str) -> Result<String, ApiError> {
+        let session = yahoo_session::global();
+        let _renewal = session.renewal().await;
+        if let Some(crumb) = session.crumb() {
+            return Ok(crumb);
         }
 
         // Method 1: Try the dedicated crumb endpoint first (most reliable)
-        println!("Trying dedicated crumb endpoint...");
+        debug!("Trying dedicated crumb endpoint");
         match self.get_crumb_from_endpoint().await {
             Ok(crumb) => {
-                println!("Successfully got crumb from endpoint: {}", crumb);
-                self.crumb = Some(crumb.clone());
+                debug!("Got crumb from endpoint");
+                session.set_crumb(&crumb);
                 return Ok(crumb);
             }
             Err(e) => {
-                println!("Crumb endpoint failed: {}", e);
+                warn!(error = %e, "Crumb endpoint failed");
             }
         }
 
         // Method 2: Try HTML parsing approach
-        println!("Trying HTML parsing approach...");
+        debug!("Trying crumb from quote page HTML");
         match self.get_crumb_from_html(symbol).await {
             Ok(crumb) => {
-                println!("Successfully got crumb from HTML: {}", crumb);
-                self.crumb = Some(crumb.clone());
+                debug!("Got crumb from HTML");
+                session.set_crumb(&crumb);
                 return Ok(crumb);
             }
             Err(e) => {
-                println!("HTML parsing failed: {}", e);
+                warn!(error = %e, "Crumb HTML parsing failed");
             }
         }
 
         // Method 3: Try alternative approach without crumb
-        println!("All crumb methods failed, trying crumbless approach...");
+        warn!("All crumb methods failed");
         Err(ApiError::FetchError("Could not obtain crumb from any method".to_string()))
     }
 
     async fn get_crumb_from_endpoint(&self) -> Result<String, ApiError> {
-        // First establish session by visiting main page
-        let main_response = self.client
-            .get("https://finance.yahoo.com/")
-            .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8")
-            .header("Accept-Language", "en-US,en;q=0.5")
-            .header("Accept-Encoding", "gzip, deflate, br")
-            .header("DNT", "1")
-            .header("Connection", "keep-alive")
-            .header("Upgrade-Insecure-Requests", "1")
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(format!("Failed to establish session: {}", e)))?;
+        // First establish session by visiting main page, unless cookies were kept from before
+        if !yahoo_session::global().has_cookies() {
+            let main_response = self.client
+                .get("https://finance.yahoo.com/")
+                .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8")
+                .header("Accept-Language", "en-US,en;q=0.5")
+                .header("Accept-Encoding", "gzip, deflate, br")
+                .header("DNT", "1")
+                .header("Connection", "keep-alive")
+                .header("Upgrade-Insecure-Requests", "1")
+                .send_with_retry()
+                .await?;
+
+            if main_response.status() != 200 {
+                return Err(ApiError::FetchError(format!("Session establishment failed: {}", main_response.status())));
+            }
 
-        if main_response.status() != 200 {
-            return Err(ApiError::FetchError(format!("Session establishment failed: {}", main_response.status())));
+            // Brief delay
+            tokio::time::sleep(Duration::from_millis(500)).await;
         }
 
-        // Brief delay
-        tokio::time::sleep(Duration::from_millis(500)).await;
-
         // Now try the dedicated crumb endpoint
         let crumb_response = self.client
             .get("https://query2.finance.yahoo.com/v1/test/getcrumb")
             .header("Accept", "*/*")
             .header("Referer", "https://finance.yahoo.com/")
             .header("X-Requested-With", "XMLHttpRequest")
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(format!("Crumb endpoint request failed: {}", e)))?;
+            .send_with_retry()
+            .await?;
 
         if crumb_response.status() != 200 {
             return Err(ApiError::FetchError(format!("Crumb endpoint returned: {}", crumb_response.status())));
@@ -844,9 +2095,8 @@ impl YahooFinanceClient {
             .header("Sec-Fetch-Dest", "document")
             .header("Sec-Fetch-Mode", "navigate")
             .header("Sec-Fetch-Site", "same-origin")
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(format!("Failed to fetch quote page: {}", e)))?;
+            .send_with_retry()
+            .await?;
 
         if quote_response.status() != 200 {
             return Err(ApiError::FetchError(format!("Quote page fetch failed: {}", quote_response.status())));
@@ -878,7 +2128,7 @@ impl YahooFinanceClient {
                 if let Some(captures) = re.captures(&html) {
                     if let Some(crumb_match) = captures.get(1) {
                         let crumb = crumb_match.as_str().to_string();
-                        println!("Found crumb with pattern {}: {}", i + 1, crumb);
+                        debug!(pattern = i + 1, "Found crumb in HTML");
                         return Ok(crumb);
                     }
                 }
@@ -896,7 +2146,7 @@ impl YahooFinanceClient {
                         if let Some(captures) = re.captures(script_text) {
                             if let Some(crumb_match) = captures.get(1) {
                                 let crumb = crumb_match.as_str().to_string();
-                                println!("Found crumb in script tag with pattern {}: {}", i + 1, crumb);
+                                debug!(pattern = i + 1, "Found crumb in script tag");
                                 return Ok(crumb);
                             }
                         }
@@ -907,14 +2157,31 @@ impl YahooFinanceClient {
 
         // Save HTML snippet for debugging
         let preview = &html[..std::cmp::min(2000, html.len())];
-        println!("HTML preview (first 2000 chars): {}", preview);
+        debug!(%preview, "No crumb in HTML (first 2000 chars)");
 
         Err(ApiError::FetchError("Could not find crumb in HTML".to_string()))
     }
 
+    // Sends the request `send` builds around the session crumb. When Yahoo refuses the crumb (it
+    // or its cookies expired mid-session) both are dropped and the request goes once more with new ones.
+    async fn send_with_crumb<F>(&self, symbol: &str, send: F) -> Result<reqwest::Response, ApiError>
+    where
+        F: Fn(&str) -> BoxFuture<'static, Result<reqwest::Response, ApiError>>,
+    {
+        let crumb = self.get_crumb(symbol).await?;
+        let response = send(&crumb).await?;
+        if !yahoo_session::is_rejected(response.status()) {
+            return Ok(response);
+        }
+        warn!(status = response.status().as_u16(), "Yahoo refused the crumb; renewing it");
+        yahoo_session::global().reject(&crumb);
+        let crumb = self.get_crumb(symbol).await?;
+        send(&crumb).await
+    }
+
     // Alternative method that tries to work without crumb for some endpoints
     pub async fn try_crumbless_request(&self, ticker: &str) -> Result<serde_json::Value, ApiError> {
-        println!("Attempting crumbless request...");
+        debug!(ticker, "Attempting crumbless request");
         
         // Some endpoints might work without crumb
         let endpoints_to_try = [
@@ -924,32 +2191,32 @@ impl YahooFinanceClient {
         ];
 
         for endpoint in &endpoints_to_try {
-            println!("Trying endpoint: {}", endpoint);
+            debug!(%endpoint, "Trying crumbless endpoint");
             
             let response = self.client
                 .get(endpoint)
                 .header("Accept", "application/json")
                 .header("Referer", &format!("https://finance.yahoo.com/quote/{}", ticker))
-                .send()
+                .send_with_retry()
                 .await;
 
             match response {
                 Ok(resp) if resp.status() == 200 => {
                     match resp.json::<serde_json::Value>().await {
                         Ok(json) => {
-                            println!("Successful response from: {}", endpoint);
+                            debug!(%endpoint, "Crumbless endpoint succeeded");
                             return Ok(json);
                         }
                         Err(e) => {
-                            println!("JSON parse error from {}: {}", endpoint, e);
+                            warn!(%endpoint, error = %e, "Crumbless endpoint returned invalid JSON");
                         }
                     }
                 }
                 Ok(resp) => {
-                    println!("HTTP error from {}: {}", endpoint, resp.status());
+                    warn!(%endpoint, status = resp.status().as_u16(), "Crumbless endpoint failed");
                 }
                 Err(e) => {
-                    println!("Request error from {}: {}", endpoint, e);
+                    warn!(%endpoint, error = %e, "Crumbless endpoint request failed");
                 }
             }
         }
@@ -957,20 +2224,16 @@ impl YahooFinanceClient {
         Err(ApiError::FetchError("All crumbless endpoints failed".to_string()))
     }
 
-    pub async fn fetch_quote_summary(&mut self, ticker: &str) -> Result<QuoteSummaryResponse, ApiError> {
-        let crumb = self.get_crumb(ticker).await?;
-        
-        let modules = "assetProfile,financialData,defaultKeyStatistics,summaryDetail,price,summaryProfile";
-        let url = format!(
-            "https://query1.finance.yahoo.com/v10/finance/quoteSummary/{}?modules={}&crumb={}",
-            ticker, modules, crumb
-        );
-
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
+    // `modules` are names from QUOTE_SUMMARY_MODULES; the others come back as None
+    pub async fn fetch_quote_summary(&mut self, ticker: &str, modules: &[&str]) -> Result<QuoteSummaryResponse, ApiError> {
+        let modules = modules.join(",");
+        let response = self.send_with_crumb(ticker, |crumb| {
+            let url = format!(
+                "https://query1.finance.yahoo.com/v10/finance/quoteSummary/{}?modules={}&crumb={}",
+                ticker, modules, crumb
+            );
+            self.client.get(&url).send_cached(&self.cache)
+        }).await?;
 
         if response.status() != 200 {
             return Err(ApiError::FetchError(format!("HTTP {}", response.status())));
@@ -985,20 +2248,52 @@ impl YahooFinanceClient {
         self.parse_quote_summary(ticker, json)
     }
 
+    // One /v7/finance/quote call per QUOTE_BATCH_SIZE symbols. Symbols Yahoo doesn't know are
+    // simply absent from the result.
+    pub async fn fetch_quotes_batch(&mut self, symbols: &[String]) -> Result<Vec<YahooQuote>, ApiError> {
+        let Some(first) = symbols.first() else {
+            return Ok(Vec::new());
+        };
+        let mut quotes = Vec::with_capacity(symbols.len());
+        for chunk in symbols.chunks(QUOTE_BATCH_SIZE) {
+            let joined = urlencoding::encode(&chunk.join(",")).into_owned();
+            let response = self.send_with_crumb(first, |crumb| {
+                let url = format!(
+                    "https://query1.finance.yahoo.com/v7/finance/quote?symbols={}&crumb={}",
+                    joined, crumb
+                );
+                self.client
+                    .get(&url)
+                    .header("Accept", "application/json")
+                    .send_with_retry()
+            }).await?;
+
+            if response.status() != 200 {
+                return Err(ApiError::FetchError(format!("HTTP {}", response.status())));
+            }
+
+            let json: YahooQuoteResponse = response
+                .json()
+                .await
+                .map_err(|e| ApiError::FetchError(format!("JSON parsing failed: {}", e)))?;
+            if let Some(error) = json.quote_response.error {
+                return Err(ApiError::FetchError(format!("Quote batch error: {}", error)));
+            }
+            quotes.extend(json.quote_response.result);
+        }
+
+        Ok(quotes)
+    }
+
     pub async fn fetch_news(&mut self, ticker: &str, count: Option<u32>) -> Result<NewsResponse, ApiError> {
-        let crumb = self.get_crumb(ticker).await?;
         let count = count.unwrap_or(20);
-        
-        let url = format!(
-            "https://query1.finance.yahoo.com/v1/finance/search?q={}&quotesCount=0&newsCount={}&crumb={}",
-            ticker, count, crumb
-        );
-
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
+        let response = self.send_with_crumb(ticker, |crumb| {
+            let url = format!(
+                "https://query1.finance.yahoo.com/v1/finance/search?q={}&quotesCount=0&newsCount={}&crumb={}",
+                ticker, count, crumb
+            );
+            self.client.get(&url).send_cached(&self.cache)
+        }).await?;
 
         if response.status() != 200 {
             return Err(ApiError::FetchError(format!("HTTP {}", response.status())));
@@ -1012,85 +2307,197 @@ impl YahooFinanceClient {
         self.parse_news(json)
     }
 
-    pub async fn fetch_calendar(&mut self, from: &str, to: &str) -> Result<CalendarResponse, ApiError> {
-        let crumb = self.get_crumb("AAPL").await?; // Use any symbol to get crumb
-        
-        let earnings_url = format!(
-            "https://query1.finance.yahoo.com/v1/finance/calendar/earnings?from={}&to={}&crumb={}",
-            from, to, crumb
-        );
+    // Symbol lookup via the search endpoint with news turned off; needs no crumb
+    pub async fn fetch_symbol_search(&mut self, query: &str, limit: u32) -> Result<Vec<SymbolMatch>, ApiError> {
+        let limit = limit.to_string();
+        let response = self.client
+            .get("https://query1.finance.yahoo.com/v1/finance/search")
+            .query(&[("q", query), ("quotesCount", limit.as_str()), ("newsCount", "0"), ("enableFuzzyQuery", "true")])
+            .send_cached(&self.cache)
+            .await?;
 
-        let dividends_url = format!(
-            "https://query1.finance.yahoo.com/v1/finance/calendar/dividends?from={}&to={}&crumb={}",
-            from, to, crumb
-        );
+        if response.status() != 200 {
+            return Err(ApiError::FetchError(format!("HTTP {}", response.status())));
+        }
 
-        // Fetch earnings data
-        let earnings_response = self.client
-            .get(&earnings_url)
-            .send()
+        let json: YahooSearchResponse = response
+            .json()
             .await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
+            .map_err(|e| ApiError::FetchError(format!("JSON parsing failed: {}", e)))?;
 
-        let dividends_response = self.client
-            .get(&dividends_url)
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
-
-        let earnings_json: serde_json::Value = if earnings_response.status() == 200 {
-            earnings_response.json().await.unwrap_or_default()
-        } else {
-            serde_json::Value::Null
-        };
+        Ok(json.quotes.into_iter().filter_map(YahooSearchQuote::into_match).collect())
+    }
 
+    // Earnings, splits and IPOs come from Yahoo's visualization API, dividends from its calendar
+    // API. `from` and `to` are inclusive YYYY-MM-DD dates; `tickers` narrows every list.
+    pub async fn fetch_calendar(&mut self, from: NaiveDate, to: NaiveDate, tickers: Option<&[String]>) -> Result<CalendarResponse, ApiError> {
+        let earnings = self.fetch_calendar_rows("sp_earnings", &[
+            "ticker", "companyshortname", "startdatetime", "startdatetimetype", "epsestimate", "epsactual", "epssurprisepct",
+        ], from, to, tickers).await?;
+        let splits = self.fetch_calendar_rows("splits", &[
+            "ticker", "companyshortname", "startdatetime", "old_share_worth", "share_worth",
+        ], from, to, tickers).await?;
+        let ipos = self.fetch_calendar_rows("ipo_info", &[
+            "ticker", "companyshortname", "exchange_short_name", "startdatetime", "pricefrom", "priceto", "offerprice", "currencyname",
+        ], from, to, tickers).await?;
+
+        let dividends_response = self.send_with_crumb("AAPL", |crumb| {
+            let url = format!(
+                "https://query1.finance.yahoo.com/v1/finance/calendar/dividends?from={}&to={}&crumb={}",
+                from, to, crumb
+            );
+            self.client.get(&url).send_cached(&self.cache)
+        }).await?;
         let dividends_json: serde_json::Value = if dividends_response.status() == 200 {
             dividends_response.json().await.unwrap_or_default()
         } else {
             serde_json::Value::Null
         };
 
-        self.parse_calendar(earnings_json, dividends_json)
+        let mut calendar = self.parse_calendar(earnings, splits, ipos, dividends_json);
+        if let Some(tickers) = tickers {
+            let wanted: BTreeSet<String> = tickers.iter().map(|t| t.trim().to_uppercase()).collect();
+            let keep = |ticker: &str| wanted.contains(&ticker.to_uppercase());
+            calendar.earnings.retain(|e| keep(&e.ticker));
+            calendar.dividends.retain(|d| keep(&d.ticker));
+            calendar.splits.retain(|s| keep(&s.ticker));
+            calendar.ipos.retain(|i| keep(&i.ticker));
+        }
+        Ok(calendar)
+    }
+
+    // One visualization calendar as column id -> value objects, paged CALENDAR_PAGE_SIZE rows at a
+    // time up to CALENDAR_MAX_ROWS. Without tickers, earnings are limited to US listings, which
+    // otherwise run to thousands of rows a week.
+    async fn fetch_calendar_rows(
+        &self,
+        entity: &str,
+        fields: &[&str],
+        from: NaiveDate,
+        to: NaiveDate,
+        tickers: Option<&[String]>,
+    ) -> Result<Vec<serde_json::Value>, ApiError> {
+        let until = to.succ_opt().unwrap_or(to);
+        let mut operands = vec![
+            serde_json::json!({ "operator": "gte", "operands": ["startdatetime", from.to_string()] }),
+            serde_json::json!({ "operator": "lt", "operands": ["startdatetime", until.to_string()] }),
+        ];
+        match tickers {
+            Some(tickers) => operands.push(serde_json::json!({
+                "operator": "or",
+                "operands": tickers.iter()
+                    .map(|t| serde_json::json!({ "operator": "eq", "operands": ["ticker", t.trim().to_uppercase()] }))
+                    .collect::<Vec<_>>(),
+            })),
+            None if entity == "sp_earnings" => operands.push(serde_json::json!({ "operator": "eq", "operands": ["region", "us"] })),
+            None => {}
+        }
+
+        let mut rows = Vec::new();
+        while rows.len() < CALENDAR_MAX_ROWS {
+            let body = serde_json::json!({
+                "entityIdType": entity,
+                "includeFields": fields,
+                "sortField": "startdatetime",
+                "sortType": "ASC",
+                "size": CALENDAR_PAGE_SIZE,
+                "offset": rows.len(),
+                "query": { "operator": "and", "operands": operands },
+            });
+            let response = self.send_with_crumb("AAPL", |crumb| {
+                self.client
+                    .post(format!("https://query1.finance.yahoo.com/v1/finance/visualization?crumb={}", crumb))
+                    .header("Accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body)
+                    .send_with_retry()
+            }).await?;
+            if response.status() != 200 {
+                warn!(entity, status = response.status().as_u16(), "Yahoo calendar request failed");
+                break;
+            }
+            let json: serde_json::Value = response.json().await.unwrap_or_default();
+            let Some(document) = json.pointer("/finance/result/0/documents/0") else {
+                break;
+            };
+            let columns: Vec<&str> = document.get("columns")
+                .and_then(|c| c.as_array())
+                .map(|cols| cols.iter().map(|c| c.get("id").and_then(|id| id.as_str()).unwrap_or("")).collect())
+                .unwrap_or_default();
+            let page: Vec<serde_json::Value> = document.get("rows")
+                .and_then(|r| r.as_array())
+                .map(|page| page.iter()
+                    .filter_map(|row| row.as_array())
+                    .map(|row| serde_json::Value::Object(
+                        columns.iter().zip(row).map(|(id, value)| (id.to_string(), value.clone())).collect(),
+                    ))
+                    .collect())
+                .unwrap_or_default();
+            let full_page = page.len() == CALENDAR_PAGE_SIZE;
+            rows.extend(page);
+            if !full_page {
+                break;
+            }
+        }
+        Ok(rows)
     }
 
     pub async fn fetch_reports(&mut self, ticker: &str) -> Result<ReportsResponse, ApiError> {
-        let crumb = self.get_crumb(ticker).await?;
-        
-        let financials_url = format!(
-            "https://query1.finance.yahoo.com/v10/finance/quoteSummary/{}?modules=incomeStatementHistory,balanceSheetHistory,cashflowStatementHistory&crumb={}",
-            ticker, crumb
-        );
+        let (financials, _) = self.fetch_financial_statements(ticker, StatementPeriod::Annual).await?;
 
-        let analysis_url = format!(
-            "https://query1.finance.yahoo.com/v10/finance/quoteSummary/{}?modules=recommendationTrend,earningsEstimate,revenueEstimate&crumb={}",
-            ticker, crumb
-        );
+        Ok(ReportsResponse {
+            financials,
+            analysis: self.fetch_analysis(ticker).await?,
+        })
+    }
 
-        let financials_response = self.client
-            .get(&financials_url)
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
+    // Recommendation counts and estimate trends from the recommendationTrend and earningsTrend modules
+    pub async fn fetch_analysis(&mut self, ticker: &str) -> Result<AnalysisReports, ApiError> {
+        let summary = self.fetch_quote_summary(ticker, &["recommendationTrend", "earningsTrend"]).await?;
+        let trend = summary.earnings_trend.unwrap_or_default();
+        Ok(AnalysisReports {
+            symbol: summary.symbol,
+            analyst_recommendations: summary.recommendation_trend.unwrap_or_default(),
+            earnings_estimates: trend.earnings_estimates,
+            revenue_estimates: trend.revenue_estimates,
+            eps_trend: trend.eps_trend,
+        })
+    }
 
-        let analysis_response = self.client
-            .get(&analysis_url)
-            .send()
+    // Income statement, balance sheet and cash flow from the fundamentals-timeseries API, plus the
+    // reporting currency
+    pub async fn fetch_financial_statements(
+        &mut self,
+        ticker: &str,
+        period: StatementPeriod,
+    ) -> Result<(FinancialReports, Option<String>), ApiError> {
+        let prefix = period.as_str();
+        let types: Vec<String> = INCOME_STATEMENT_ITEMS.iter()
+            .chain(&BALANCE_SHEET_ITEMS)
+            .chain(&CASH_FLOW_ITEMS)
+            .map(|item| format!("{}{}", prefix, item))
+            .collect();
+        let (types, now) = (types.join(","), Utc::now().timestamp());
+        let response = self.send_with_crumb(ticker, |crumb| {
+            let url = format!(
+                "https://query2.finance.yahoo.com/ws/fundamentals-timeseries/v1/finance/timeseries/{}?symbol={}&type={}&period1={}&period2={}&crumb={}",
+                ticker, ticker, types, FUNDAMENTALS_PERIOD_START, now, crumb
+            );
+            self.client.get(&url).send_cached(&self.cache)
+        }).await?;
+        if response.status() != 200 {
+            return Err(ApiError::FetchError(format!("HTTP {}", response.status())));
+        }
+        let json: serde_json::Value = response
+            .json()
             .await
             .map_err(|e| ApiError::FetchError(e.to_string()))?;
 
-        let financials_json: serde_json::Value = if financials_response.status() == 200 {
-            financials_response.json().await.unwrap_or_default()
-        } else {
-            serde_json::Value::Null
-        };
-
-        let analysis_json: serde_json::Value = if analysis_response.status() == 200 {
-            analysis_response.json().await.unwrap_or_default()
-        } else {
-            serde_json::Value::Null
-        };
-
-        self.parse_reports(financials_json, analysis_json)
+        let (financials, currency) = self.parse_fundamentals_timeseries(&json, period);
+        if financials.income_statement.is_empty() && financials.balance_sheet.is_empty() && financials.cash_flow.is_empty() {
+            return Err(ApiError::DataNotFound(format!("No {} financial statements for {}", prefix, ticker)));
+        }
+        Ok((financials, currency))
     }
 
     // Predefined screener fetch
@@ -1099,26 +2506,23 @@ impl YahooFinanceClient {
         screener_id: &str,
         count: Option<u32>,
         offset: Option<u32>,
-        cache: &AsyncRwLock<Option<CrumbCache>>,
     ) -> Result<YahooScreenerResponse, ApiError> {
-        let crumb = self.get_cached_crumb("AAPL", cache).await?;
         let count = count.unwrap_or(100);
         let offset = offset.unwrap_or(0);
 
-        let url = format!(
-            "https://query2.finance.yahoo.com/v1/finance/screener/predefined/saved?count={}&offset={}&scrIds={}&crumb={}",
-            count, offset, screener_id, crumb
-        );
-
-        println!("Fetching predefined screener: {}", url);
+        debug!(screener_id, count, offset, "Fetching predefined screener");
 
-        let response = self.client
-            .get(&url)
-            .header("Accept", "application/json")
-            .header("Referer", "https://finance.yahoo.com/screener")
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(format!("Screener request failed: {}", e)))?;
+        let response = self.send_with_crumb("AAPL", |crumb| {
+            let url = format!(
+                "https://query2.finance.yahoo.com/v1/finance/screener/predefined/saved?count={}&offset={}&scrIds={}&crumb={}",
+                count, offset, screener_id, crumb
+            );
+            self.client
+                .get(&url)
+                .header("Accept", "application/json")
+                .header("Referer", "https://finance.yahoo.com/screener")
+                .send_cached(&self.cache)
+        }).await?;
 
         if response.status() != 200 {
             return Err(ApiError::FetchError(format!("HTTP {}: {}", response.status(), response.status())));
@@ -1132,6 +2536,47 @@ impl YahooFinanceClient {
         Ok(json)
     }
 
+    // Walks screener pages until `limit` quotes are gathered or Yahoo returns a short page. Each
+    // page that misses the response cache waits on the shared upstream rate limiter, which paces
+    // large pulls.
+    pub async fn fetch_predefined_screener_paginated(
+        &mut self,
+        screener_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<YahooScreenerResponse, ApiError> {
+        let mut offset = offset;
+        let mut first_result: Option<YahooScreenerResult> = None;
+        let mut quotes = Vec::new();
+
+        while (quotes.len() as u32) < limit {
+            let page_size = (limit - quotes.len() as u32).min(SCREENER_MAX_PAGE_SIZE);
+            let page = self.fetch_predefined_screener(screener_id, Some(page_size), Some(offset)).await?;
+
+            let mut result = match page.finance.result.into_iter().next() {
+                Some(result) => result,
+                None => break,
+            };
+            let page_quotes = result.quotes.take().unwrap_or_default();
+            let received = page_quotes.len() as u32;
+            quotes.extend(page_quotes);
+            first_result.get_or_insert(result);
+
+            if received < page_size {
+                break;
+            }
+            offset += received;
+        }
+
+        let mut result = first_result
+            .ok_or_else(|| ApiError::DataNotFound(format!("No screener results for {}", screener_id)))?;
+        result.quotes = Some(quotes);
+
+        Ok(YahooScreenerResponse {
+            finance: YahooScreenerFinance { result: vec![result] },
+        })
+    }
+
     // Custom screener with filters
     pub async fn fetch_custom_screener(
         &mut self,
@@ -1140,21 +2585,14 @@ impl YahooFinanceClient {
         sort_order: Option<&str>,
         count: Option<u32>,
         offset: Option<u32>,
-        cache: &AsyncRwLock<Option<CrumbCache>>,
     ) -> Result<YahooScreenerResponse, ApiError> {
-        let crumb = self.get_cached_crumb("AAPL", cache).await?;
         let count = count.unwrap_or(100);
         let offset = offset.unwrap_or(0);
 
         // Build the screener criteria
-        let criteria = self.build_screener_criteria(filters, sort_by, sort_order)?;
+        let criteria = self.build_screener_criteria(filters)?;
         
-        let url = format!(
-            "https://query2.finance.yahoo.com/v1/finance/screener?crumb={}",
-            crumb
-        );
-
-        println!("Fetching custom screener with criteria: {}", serde_json::to_string(&criteria).unwrap_or_default());
+        debug!(criteria = %serde_json::to_string(&criteria).unwrap_or_default(), "Fetching custom screener");
 
         let body = serde_json::json!({
             "size": count,
@@ -1165,15 +2603,15 @@ impl YahooFinanceClient {
             "query": criteria
         });
 
-        let response = self.client
-            .post(&url)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("Referer", "https://finance.yahoo.com/screener")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| ApiError::FetchError(format!("Custom screener request failed: {}", e)))?;
+        let response = self.send_with_crumb("AAPL", |crumb| {
+            self.client
+                .post(format!("https://query2.finance.yahoo.com/v1/finance/screener?crumb={}", crumb))
+                .header("Accept", "application/json")
+                .header("Content-Type", "application/json")
+                .header("Referer", "https://finance.yahoo.com/screener")
+                .json(&body)
+                .send_with_retry()
+        }).await?;
 
         if response.status() != 200 {
             let status = response.status();
@@ -1190,7 +2628,7 @@ impl YahooFinanceClient {
     }
 
     // Build Yahoo Finance screener criteria from our filters
-    fn build_screener_criteria(&self, filters: &[ScreenerFilter], sort_by: Option<&str>, sort_order: Option<&str>) -> Result<serde_json::Value, ApiError> {
+    fn build_screener_criteria(&self, filters: &[ScreenerFilter]) -> Result<serde_json::Value, ApiError> {
         let mut operator_filters = Vec::new();
 
         for filter in filters {
@@ -1349,7 +2787,14 @@ impl YahooFinanceClient {
             sector: ap.get("sector").and_then(|v| v.as_str()).map(String::from),
             long_business_summary: ap.get("longBusinessSummary").and_then(|v| v.as_str()).map(String::from),
             full_time_employees: ap.get("fullTimeEmployees").and_then(|v| v.as_u64()),
-            company_officers: Vec::new(), // Would parse officers array
+            company_officers: ap.get("companyOfficers").and_then(|o| o.as_array())
+                .map(|officers| officers.iter().filter_map(|o| Some(CompanyOfficer {
+                    name: o.get("name")?.as_str()?.to_string(),
+                    title: o.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    age: o.get("age").and_then(|v| v.as_u64()).map(|v| v as u32),
+                    total_pay: raw_f64(o, "totalPay"),
+                })).collect())
+                .unwrap_or_default(),
         });
 
         let financial_data = result.get("financialData").map(|fd| FinancialData {
@@ -1405,10 +2850,131 @@ impl YahooFinanceClient {
             forward_eps: dks.get("forwardEps").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
         });
 
-        // Parse other modules similarly...
-        let summary_detail = None; // Implement similar parsing
-        let price = None; // Implement similar parsing  
-        let summary_profile = None; // Implement similar parsing
+        let summary_detail = result.get("summaryDetail").map(|sd| SummaryDetail {
+            previous_close: sd.get("previousClose").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            regular_market_open: sd.get("regularMarketOpen").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            two_hundred_day_average: sd.get("twoHundredDayAverage").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            trailing_annual_dividend_yield: sd.get("trailingAnnualDividendYield").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            pay_out_ratio: sd.get("payoutRatio").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            volume_24hr: sd.get("volume24Hr").and_then(|v| v.get("raw")).and_then(|v| v.as_u64()),
+            regular_market_previous_close: sd.get("regularMarketPreviousClose").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            bid: sd.get("bid").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            ask: sd.get("ask").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            bid_size: sd.get("bidSize").and_then(|v| v.get("raw")).and_then(|v| v.as_u64()).map(|v| v as u32),
+            ask_size: sd.get("askSize").and_then(|v| v.get("raw")).and_then(|v| v.as_u64()).map(|v| v as u32),
+            market_cap: sd.get("marketCap").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            dividend_yield: sd.get("dividendYield").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            trailing_pe: sd.get("trailingPE").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            yield_: sd.get("yield").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            ytd_return: sd.get("ytdReturn").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            total_assets: sd.get("totalAssets").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            expense_ratio: sd.get("annualReportExpenseRatio").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            beta: sd.get("beta").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            fifty_two_week_high: sd.get("fiftyTwoWeekHigh").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+            fifty_two_week_low: sd.get("fiftyTwoWeekLow").and_then(|v| v.get("raw")).and_then(|v| v.as_f64()),
+        });
+
+        let price = result.get("price").and_then(|p| {
+            Some(PriceData {
+                regular_market_price: raw_f64(p, "regularMarketPrice")?,
+                regular_market_change: raw_f64(p, "regularMarketChange").unwrap_or(0.0),
+                regular_market_change_percent: raw_f64(p, "regularMarketChangePercent").map(|c| c * 100.0).unwrap_or(0.0),
+                regular_market_time: raw_f64(p, "regularMarketTime").map(|t| t as i64).unwrap_or(0),
+                regular_market_day_high: raw_f64(p, "regularMarketDayHigh").unwrap_or(0.0),
+                regular_market_day_low: raw_f64(p, "regularMarketDayLow").unwrap_or(0.0),
+                regular_market_volume: raw_f64(p, "regularMarketVolume").map(|v| v as u64).unwrap_or(0),
+                pre_market_price: raw_f64(p, "preMarketPrice"),
+                pre_market_change: raw_f64(p, "preMarketChange"),
+                pre_market_change_percent: raw_f64(p, "preMarketChangePercent").map(|c| c * 100.0),
+                pre_market_time: raw_f64(p, "preMarketTime").map(|t| t as i64),
+                post_market_price: raw_f64(p, "postMarketPrice"),
+                post_market_change: raw_f64(p, "postMarketChange"),
+                post_market_change_percent: raw_f64(p, "postMarketChangePercent").map(|c| c * 100.0),
+                post_market_time: raw_f64(p, "postMarketTime").map(|t| t as i64),
+                market_state: p.get("marketState").and_then(|v| v.as_str()).map(String::from),
+            })
+        });
+
+        let summary_profile = result.get("summaryProfile").map(|sp| SummaryProfile {
+            address1: sp.get("address1").and_then(|v| v.as_str()).map(String::from),
+            city: sp.get("city").and_then(|v| v.as_str()).map(String::from),
+            state: sp.get("state").and_then(|v| v.as_str()).map(String::from),
+            country: sp.get("country").and_then(|v| v.as_str()).map(String::from),
+            phone: sp.get("phone").and_then(|v| v.as_str()).map(String::from),
+            website: sp.get("website").and_then(|v| v.as_str()).map(String::from),
+            industry: sp.get("industry").and_then(|v| v.as_str()).map(String::from),
+            sector: sp.get("sector").and_then(|v| v.as_str()).map(String::from),
+            long_business_summary: sp.get("longBusinessSummary").and_then(|v| v.as_str()).map(String::from),
+            full_time_employees: sp.get("fullTimeEmployees").and_then(|v| v.as_u64()),
+        });
+
+        let earnings = result.get("earnings").map(|e| {
+            let chart = e.get("earningsChart");
+            let periods = |key: &str| -> Vec<PeriodFinancials> {
+                e.get("financialsChart").and_then(|fc| fc.get(key)).and_then(|rows| rows.as_array())
+                    .map(|rows| rows.iter().filter_map(|row| Some(PeriodFinancials {
+                        period: match row.get("date")? {
+                            serde_json::Value::String(period) => period.clone(),
+                            other => other.to_string(), // yearly rows carry the year as a number
+                        },
+                        revenue: raw_f64(row, "revenue"),
+                        earnings: raw_f64(row, "earnings"),
+                    })).collect())
+                    .unwrap_or_default()
+            };
+            EarningsData {
+                financial_currency: e.get("financialCurrency").and_then(|v| v.as_str()).map(String::from),
+                quarterly_eps: chart.and_then(|c| c.get("quarterly")).and_then(|q| q.as_array())
+                    .map(|rows| rows.iter().filter_map(|row| Some(QuarterlyEps {
+                        quarter: row.get("date")?.as_str()?.to_string(),
+                        actual: raw_f64(row, "actual"),
+                        estimate: raw_f64(row, "estimate"),
+                    })).collect())
+                    .unwrap_or_default(),
+                current_quarter: chart.and_then(|c| {
+                    let quarter = c.get("currentQuarterEstimateDate")?.as_str()?;
+                    let year = c.get("currentQuarterEstimateYear")?.as_i64()?;
+                    Some(format!("{}{}", quarter, year))
+                }),
+                current_quarter_estimate: chart.and_then(|c| raw_f64(c, "currentQuarterEstimate")),
+                yearly: periods("yearly"),
+                quarterly: periods("quarterly"),
+            }
+        });
+
+        let calendar_events = result.get("calendarEvents").map(|ce| {
+            let earnings = ce.get("earnings");
+            let field = |key: &str| earnings.and_then(|e| raw_f64(e, key));
+            let earnings_dates: Vec<String> = earnings.and_then(|e| e.get("earningsDate")).and_then(|d| d.as_array())
+                .map(|dates| dates.iter().filter_map(raw_date).collect())
+                .unwrap_or_default();
+            CalendarEvents {
+                is_earnings_date_estimate: earnings.and_then(|e| e.get("isEarningsDateEstimate")).and_then(|v| v.as_bool())
+                    .unwrap_or(earnings_dates.len() > 1),
+                earnings_dates,
+                earnings_average: field("earningsAverage"),
+                earnings_low: field("earningsLow"),
+                earnings_high: field("earningsHigh"),
+                revenue_average: field("revenueAverage"),
+                revenue_low: field("revenueLow"),
+                revenue_high: field("revenueHigh"),
+                ex_dividend_date: ce.get("exDividendDate").and_then(raw_date),
+                dividend_date: ce.get("dividendDate").and_then(raw_date),
+            }
+        });
+
+        let recommendation_trend = result.get("recommendationTrend").map(|rt| self.parse_recommendation_trend(rt));
+        let earnings_trend = result.get("earningsTrend").map(|et| self.parse_earnings_trend(et));
+
+        let next_earnings = calendar_events.as_ref().and_then(|events| {
+            Some(UpcomingEarnings {
+                date: events.earnings_dates.first()?.clone(),
+                date_range_end: events.earnings_dates.get(1).cloned(),
+                is_estimate: events.is_earnings_date_estimate,
+                eps_estimate: events.earnings_average,
+                revenue_estimate: events.revenue_average,
+            })
+        });
 
         Ok(QuoteSummaryResponse {
             symbol: ticker.to_string(),
@@ -1418,6 +2984,11 @@ impl YahooFinanceClient {
             summary_detail,
             price,
             summary_profile,
+            earnings,
+            calendar_events,
+            recommendation_trend,
+            earnings_trend,
+            next_earnings,
         })
     }
 
@@ -1468,26 +3039,14 @@ impl YahooFinanceClient {
         })
     }
 
-    fn parse_calendar(&self, earnings_json: serde_json::Value, dividends_json: serde_json::Value) -> Result<CalendarResponse, ApiError> {
-        let mut earnings = Vec::new();
+    fn parse_calendar(
+        &self,
+        earnings: Vec<serde_json::Value>,
+        splits: Vec<serde_json::Value>,
+        ipos: Vec<serde_json::Value>,
+        dividends_json: serde_json::Value,
+    ) -> CalendarResponse {
         let mut dividends = Vec::new();
-
-        // Parse earnings events
-        if let Some(earnings_array) = earnings_json.get("finance")
-            .and_then(|f| f.get("result"))
-            .and_then(|r| r.as_array())
-            .and_then(|arr| arr.first())
-            .and_then(|item| item.get("earnings"))
-            .and_then(|e| e.as_array())
-        {
-            for event in earnings_array {
-                if let Some(earnings_event) = self.parse_earnings_event(event) {
-                    earnings.push(earnings_event);
-                }
-            }
-        }
-
-        // Parse dividend events
         if let Some(dividends_array) = dividends_json.get("finance")
             .and_then(|f| f.get("result"))
             .and_then(|r| r.as_array())
@@ -1502,20 +3061,28 @@ impl YahooFinanceClient {
             }
         }
 
-        Ok(CalendarResponse {
-            earnings,
+        CalendarResponse {
+            earnings: earnings.iter().filter_map(|e| self.parse_earnings_event(e)).collect(),
             dividends,
-            splits: Vec::new(), // Would implement split parsing
-            ipos: Vec::new(),   // Would implement IPO parsing
-        })
+            splits: splits.iter().filter_map(|s| self.parse_split_event(s)).collect(),
+            ipos: ipos.iter().filter_map(|i| self.parse_ipo_event(i)).collect(),
+        }
     }
 
     fn parse_earnings_event(&self, event: &serde_json::Value) -> Option<EarningsEvent> {
+        let start = event.get("startdatetime")?;
+        // BMO / AMC when Yahoo knows the session, else the scheduled time
+        let call_time = match event.get("startdatetimetype").and_then(|t| t.as_str()) {
+            Some("BMO") => Some("before_open".to_string()),
+            Some("AMC") => Some("after_close".to_string()),
+            Some("TNS") => None,
+            _ => start.as_str().map(String::from),
+        };
         Some(EarningsEvent {
             ticker: event.get("ticker")?.as_str()?.to_string(),
-            company_name: event.get("companyshortname")?.as_str()?.to_string(),
-            earnings_date: event.get("startdatetime")?.as_str()?.to_string(),
-            earnings_call_time: event.get("startdatetime").and_then(|s| s.as_str()).map(String::from),
+            company_name: event.get("companyshortname").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+            earnings_date: calendar_date(start)?,
+            earnings_call_time: call_time,
             eps_estimate: event.get("epsestimate").and_then(|e| e.as_f64()),
             reported_eps: event.get("epsactual").and_then(|e| e.as_f64()),
             surprise_percent: event.get("epssurprisepct").and_then(|e| e.as_f64()),
@@ -1534,103 +3101,152 @@ impl YahooFinanceClient {
         })
     }
 
-    fn parse_reports(&self, financials_json: serde_json::Value, analysis_json: serde_json::Value) -> Result<ReportsResponse, ApiError> {
-        let financials = self.parse_financial_reports(financials_json);
-        let analysis = self.parse_analysis_reports(analysis_json);
+    // Yahoo quotes splits as share_worth new shares for old_share_worth old ones
+    fn parse_split_event(&self, event: &serde_json::Value) -> Option<SplitEvent> {
+        let from_factor = event.get("old_share_worth")?.as_f64()?;
+        let to_factor = event.get("share_worth")?.as_f64()?;
+        Some(SplitEvent {
+            ticker: event.get("ticker")?.as_str()?.to_string(),
+            company_name: event.get("companyshortname").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+            ex_date: calendar_date(event.get("startdatetime")?)?,
+            split_ratio: format!("{}:{}", to_factor, from_factor),
+            from_factor,
+            to_factor,
+        })
+    }
 
-        Ok(ReportsResponse {
-            financials,
-            analysis,
+    // A priced IPO has an offer price instead of a range
+    fn parse_ipo_event(&self, event: &serde_json::Value) -> Option<IpoEvent> {
+        let number = |key: &str| event.get(key).and_then(|v| v.as_f64());
+        let offer = number("offerprice");
+        Some(IpoEvent {
+            ticker: event.get("ticker")?.as_str()?.to_string(),
+            company_name: event.get("companyshortname").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+            ipo_date: calendar_date(event.get("startdatetime")?)?,
+            price_range_low: number("pricefrom").or(offer),
+            price_range_high: number("priceto").or(offer),
+            currency: event.get("currencyname").and_then(|c| c.as_str()).unwrap_or("USD").to_string(),
+            exchange: event.get("exchange_short_name").and_then(|e| e.as_str()).unwrap_or_default().to_string(),
         })
     }
 
-    fn parse_financial_reports(&self, json: serde_json::Value) -> FinancialReports {
-        let result = json.get("quoteSummary")
-            .and_then(|qs| qs.get("result"))
-            .and_then(|r| r.as_array())
-            .and_then(|arr| arr.first());
-
-        let mut income_statement = Vec::new();
-        let mut balance_sheet = Vec::new();
-        let mut cash_flow = Vec::new();
-
-        if let Some(result) = result {
-            // Parse income statement
-            if let Some(is_history) = result.get("incomeStatementHistory")
-                .and_then(|ish| ish.get("incomeStatementHistory"))
-                .and_then(|ish| ish.as_array())
-            {
-                for statement in is_history {
-                    if let Some(parsed) = self.parse_financial_statement(statement, "annual") {
-                        income_statement.push(parsed);
-                    }
-                }
-            }
+    // Each timeseries result holds one item (e.g. annualTotalRevenue) across report dates; they are
+    // regrouped into one FinancialStatement per statement and date, newest first
+    fn parse_fundamentals_timeseries(&self, json: &serde_json::Value, period: StatementPeriod) -> (FinancialReports, Option<String>) {
+        let mut statements: [BTreeMap<String, HashMap<String, Option<f64>>>; 3] = Default::default();
+        let mut currency = None;
 
-            // Parse balance sheet (similar pattern)
-            if let Some(bs_history) = result.get("balanceSheetHistory")
-                .and_then(|bsh| bsh.get("balanceSheetHistory"))
-                .and_then(|bsh| bsh.as_array())
-            {
-                for statement in bs_history {
-                    if let Some(parsed) = self.parse_financial_statement(statement, "annual") {
-                        balance_sheet.push(parsed);
-                    }
-                }
-            }
+        let series = json.pointer("/timeseries/result").and_then(|r| r.as_array()).map(Vec::as_slice).unwrap_or_default();
+        for result in series {
+            let Some(kind) = result.pointer("/meta/type/0").and_then(|t| t.as_str()) else {
+                continue;
+            };
+            let Some(item) = kind.strip_prefix(period.as_str()) else {
+                continue;
+            };
+            let statement = if INCOME_STATEMENT_ITEMS.contains(&item) {
+                0
+            } else if BALANCE_SHEET_ITEMS.contains(&item) {
+                1
+            } else if CASH_FLOW_ITEMS.contains(&item) {
+                2
+            } else {
+                continue;
+            };
+            // totalRevenue, like the quoteSummary statement keys
+            let key = item[..1].to_lowercase() + &item[1..];
 
-            // Parse cash flow (similar pattern)
-            if let Some(cf_history) = result.get("cashflowStatementHistory")
-                .and_then(|cfh| cfh.get("cashflowStatementHistory"))
-                .and_then(|cfh| cfh.as_array())
-            {
-                for statement in cf_history {
-                    if let Some(parsed) = self.parse_financial_statement(statement, "annual") {
-                        cash_flow.push(parsed);
-                    }
+            let points = result.get(kind).and_then(|p| p.as_array()).map(Vec::as_slice).unwrap_or_default();
+            for point in points.iter().filter(|p| !p.is_null()) {
+                let Some(date) = point.get("asOfDate").and_then(|d| d.as_str()) else {
+                    continue;
+                };
+                if currency.is_none() {
+                    currency = point.get("currencyCode").and_then(|c| c.as_str()).map(String::from);
                 }
+                let value = point.pointer("/reportedValue/raw").and_then(|v| v.as_f64());
+                statements[statement].entry(date.to_string()).or_default().insert(key.clone(), value);
             }
         }
 
-        FinancialReports {
-            income_statement,
-            balance_sheet,
-            cash_flow,
-        }
+        let [income_statement, balance_sheet, cash_flow] = statements.map(|by_date| {
+            by_date.into_iter()
+                .rev()
+                .map(|(date, data)| FinancialStatement { date, period_type: period.as_str().to_string(), data })
+                .collect::<Vec<_>>()
+        });
+        (FinancialReports { income_statement, balance_sheet, cash_flow }, currency)
     }
 
-    fn parse_financial_statement(&self, statement: &serde_json::Value, period_type: &str) -> Option<FinancialStatement> {
-        let date = statement.get("endDate")
-            .and_then(|d| d.get("fmt"))
-            .and_then(|f| f.as_str())?
-            .to_string();
-
-        let mut data = HashMap::new();
-        
-        // Extract all financial metrics (this is a simplified version)
-        if let Some(obj) = statement.as_object() {
-            for (key, value) in obj {
-                if let Some(raw_value) = value.get("raw").and_then(|r| r.as_f64()) {
-                    data.insert(key.clone(), Some(raw_value));
-                }
-            }
-        }
-
-        Some(FinancialStatement {
-            date,
-            period_type: period_type.to_string(),
-            data,
-        })
+    fn parse_recommendation_trend(&self, module: &serde_json::Value) -> Vec<AnalystRecommendation> {
+        let trend = module.get("trend").and_then(|t| t.as_array()).map(Vec::as_slice).unwrap_or_default();
+        trend.iter()
+            .filter_map(|month| {
+                let count = |key: &str| month.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                let counts = [count("strongBuy"), count("buy"), count("hold"), count("sell"), count("strongSell")];
+                let total: u32 = counts.iter().sum();
+                // Yahoo's 1 (strong buy) to 5 (strong sell) scale
+                let mean = counts.iter().zip(1..).map(|(n, score)| (n * score) as f64).sum::<f64>() / total.max(1) as f64;
+                Some(AnalystRecommendation {
+                    period: month.get("period")?.as_str()?.to_string(),
+                    strong_buy: counts[0],
+                    buy: counts[1],
+                    hold: counts[2],
+                    sell: counts[3],
+                    strong_sell: counts[4],
+                    mean_recommendation: mean,
+                })
+            })
+            .collect()
     }
 
-    fn parse_analysis_reports(&self, json: serde_json::Value) -> AnalysisReports {
-        // Parse analyst recommendations, earnings estimates, etc.
-        // This is simplified - you'd parse the actual nested structures
-        AnalysisReports {
-            analyst_recommendations: Vec::new(),
-            earnings_estimates: Vec::new(),
-            revenue_estimates: Vec::new(),
+    fn parse_earnings_trend(&self, module: &serde_json::Value) -> EarningsTrend {
+        let mut parsed = EarningsTrend::default();
+        let trend = module.get("trend").and_then(|t| t.as_array()).map(Vec::as_slice).unwrap_or_default();
+        for entry in trend {
+            let Some(period) = entry.get("period").and_then(|p| p.as_str()) else {
+                continue;
+            };
+            let count = |module: &serde_json::Value, key: &str| raw_f64(module, key).map(|n| n as u32);
+            if let Some(eps) = entry.get("earningsEstimate") {
+                parsed.earnings_estimates.push(EarningsEstimate {
+                    period: period.to_string(),
+                    avg: raw_f64(eps, "avg"),
+                    low: raw_f64(eps, "low"),
+                    high: raw_f64(eps, "high"),
+                    year_ago_eps: raw_f64(eps, "yearAgoEps"),
+                    number_of_estimates: count(eps, "numberOfAnalysts"),
+                    growth: raw_f64(eps, "growth"),
+                });
+            }
+            if let Some(revenue) = entry.get("revenueEstimate") {
+                parsed.revenue_estimates.push(RevenueEstimate {
+                    period: period.to_string(),
+                    avg: raw_f64(revenue, "avg"),
+                    low: raw_f64(revenue, "low"),
+                    high: raw_f64(revenue, "high"),
+                    year_ago_sales: raw_f64(revenue, "yearAgoRevenue"),
+                    number_of_estimates: count(revenue, "numberOfAnalysts"),
+                    sales_growth: raw_f64(revenue, "growth"),
+                });
+            }
+            if let Some(eps_trend) = entry.get("epsTrend") {
+                let revisions = entry.get("epsRevisions").unwrap_or(&serde_json::Value::Null);
+                parsed.eps_trend.push(EpsTrend {
+                    period: period.to_string(),
+                    current: raw_f64(eps_trend, "current"),
+                    seven_days_ago: raw_f64(eps_trend, "7daysAgo"),
+                    thirty_days_ago: raw_f64(eps_trend, "30daysAgo"),
+                    sixty_days_ago: raw_f64(eps_trend, "60daysAgo"),
+                    ninety_days_ago: raw_f64(eps_trend, "90daysAgo"),
+                    up_last_7_days: count(revisions, "upLast7days"),
+                    up_last_30_days: count(revisions, "upLast30days"),
+                    down_last_7_days: count(revisions, "downLast7Days"),
+                    down_last_30_days: count(revisions, "downLast30days"),
+                });
+            }
         }
+        parsed
     }
 }
 
@@ -1639,673 +3255,3623 @@ pub struct StockDataApi {
     chart_fetcher: Arc<dyn ChartFetcher + Send + Sync>,
     options_fetcher: Arc<dyn OptionsFetcher + Send + Sync>,
     indicator_runner: IndicatorRunner,
+    indicator_pool: Option<Arc<rayon::ThreadPool>>, // shared by every runner this API builds
+    indicator_timeout: Duration,
+    fundamentals_cache: AsyncRwLock<HashMap<String, FundamentalsCache>>,
+    response_cache: Arc<ResponseCache>, // upstream GETs made through YahooFinanceClient
+    portfolios: Arc<PortfolioManager>, // SQLite-backed calls go through with_portfolios
+    candle_store: Option<Arc<CandleStore>>,
+    provider: Option<Arc<dyn MarketDataProvider>>, // replaces Yahoo for candles, quotes and options when set
+    scheduler: Scheduler,
+    events: EventHub, // alert events for /api/v1/sse
+    max_tickers: usize, // per quotes/history/stream request
+    authenticator: Option<Arc<Authenticator>>, // checked by every server; None while auth is off
 }
 
+const DEFAULT_INDICATOR_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_TICKERS: usize = 10;
+
 impl StockDataApi {
     pub fn new(
         chart_fetcher: Arc<dyn ChartFetcher + Send + Sync>,
         options_fetcher: Arc<dyn OptionsFetcher + Send + Sync>,
-        indicators: Vec<(String, Arc<dyn TechnicalIndicator + Send + Sync>)>,
+        indicators: Vec<(String, BoundIndicator)>,
     ) -> Self {
         Self {
             chart_fetcher,
             options_fetcher,
-            indicator_runner: IndicatorRunner { indicators },
+            indicator_runner: IndicatorRunner::new(indicators),
+            indicator_pool: None,
+            indicator_timeout: DEFAULT_INDICATOR_TIMEOUT,
+            fundamentals_cache: AsyncRwLock::new(HashMap::new()),
+            response_cache: Arc::new(ResponseCache::new(response_cache::DEFAULT_TTL)),
+            portfolios: Arc::new(PortfolioManager::from_config(&StorageConfig::Memory)
+                .expect("in-memory portfolio store")),
+            candle_store: None,
+            provider: None,
+            scheduler: Scheduler::default(),
+            events: EventHub::default(),
+            max_tickers: DEFAULT_MAX_TICKERS,
+            authenticator: None,
         }
     }
 
-    // Historical Data Endpoint
-    pub async fn get_historical_data(&self, request: HistoricalDataRequest) -> Result<HistoricalDataResponse, ApiError> {
-        let mut data = HashMap::new();
-        let mut errors = Vec::new();
+    pub fn with_indicator_timeout(mut self, timeout: Duration) -> Self {
+        self.indicator_timeout = timeout;
+        self
+    }
 
-        let options = ChartQueryOptions {
-            interval: request.interval.as_deref().unwrap_or("1d"),
-            range: request.range.as_deref().unwrap_or("1mo"),
-        };
+    // How long upstream responses are reused (ApiConfig.cache_ttl)
+    pub fn with_response_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.response_cache = Arc::new(ResponseCache::new(ttl));
+        self
+    }
 
-        for ticker in &request.tickers {
-            match self.fetch_ticker_data(ticker, &options).await {
-                Ok(ticker_data) => {
-                    let processed_data = self.process_ticker_data(ticker_data, &request)?;
-                    data.insert(ticker.clone(), processed_data);
-                }
-                Err(e) => {
-                    errors.push(format!("Error fetching {}: {}", ticker, e));
-                }
-            }
-        }
+    fn yahoo_client(&self) -> YahooFinanceClient {
+        YahooFinanceClient::new(Arc::clone(&self.response_cache))
+    }
 
-        Ok(HistoricalDataResponse { data, errors })
+    // Prometheus text for /metrics
+    pub fn render_metrics(&self) -> String {
+        crate::metrics::global().render(&self.response_cache.stats())
     }
 
-    // Options Chain Endpoint
-    pub async fn get_options_chain(&self, request: OptionsChainRequest) -> Result<OptionsChainResponse, ApiError> {
-        // Get underlying price first
-        let chart_options = ChartQueryOptions::default();
-        let chart_data = self.fetch_ticker_data(&request.ticker, &chart_options).await?;
-        let underlying_price = self.extract_current_price(&chart_data)?;
+    // Caps indicator computation at `threads` workers instead of rayon's one-per-core global pool
+    pub fn with_indicator_parallelism(mut self, threads: usize) -> Result<Self, ApiError> {
+        let pool = IndicatorRunner::build_pool(threads)
+            .map_err(|e| ApiError::InvalidParameters(format!("Indicator thread pool: {}", e)))?;
+        self.indicator_pool = Some(pool);
+        self.indicator_runner = self.indicator_runner.with_pool(self.indicator_pool.clone());
+        Ok(self)
+    }
 
-        // Fetch options data
-        let options_data = self.options_fetcher.fetch_async(&request.ticker).await
-            .map_err(|e| ApiError::FetchError(e.to_string()))?;
+    pub fn with_portfolio_manager(mut self, portfolios: PortfolioManager) -> Self {
+        self.portfolios = Arc::new(portfolios);
+        self
+    }
 
-        // Process and filter options data
-        let processed_data = self.process_options_data(
-            options_data,
-            &request,
-            underlying_price,
-        )?;
+    // Routes candles, quotes and option chains through another data provider instead of Yahoo
+    pub fn with_provider(mut self, provider: Arc<dyn MarketDataProvider>) -> Self {
+        let fetcher = Arc::new(ProviderFetcher(Arc::clone(&provider)));
+        self.chart_fetcher = fetcher.clone();
+        self.options_fetcher = fetcher;
+        self.provider = Some(provider);
+        self
+    }
 
-        Ok(processed_data)
+    // Option chains only; a provider set afterwards replaces it
+    pub fn with_options_fetcher(mut self, fetcher: Arc<dyn OptionsFetcher + Send + Sync>) -> Self {
+        self.options_fetcher = fetcher;
+        self
     }
 
-    // Options P&L Analysis Endpoint
-    pub fn calculate_options_pnl(&self, request: OptionsPnLRequest) -> Result<OptionsPnLResponse, ApiError> {
-        let volatility = request.volatility.unwrap_or(0.25);
-        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
+    // Candles only; quotes and options keep their own source
+    pub fn with_chart_fetcher(mut self, fetcher: Arc<dyn ChartFetcher + Send + Sync>) -> Self {
+        self.chart_fetcher = fetcher;
+        self
+    }
 
-        let mut positions = Vec::new();
-        let mut portfolio_pnl_curves: Vec<Vec<PnLPoint>> = Vec::new();
+    pub fn with_candle_store(mut self, store: CandleStore) -> Self {
+        self.candle_store = Some(Arc::new(store));
+        self
+    }
 
-        // Calculate P&L for each position
-        for position in &request.positions {
-            let option_type = match position.option_type.as_str() {
-                "call" => OptionType::Call,
-                "put" => OptionType::Put,
-                _ => return Err(ApiError::InvalidParameters("Invalid option type".to_string())),
-            };
+    // Most symbols a client may ask for at once from quotes, historical data and the push
+    // endpoints (ApiConfig.max_tickers_per_request). Scheduled jobs, watchlists and portfolios
+    // quote whatever they hold.
+    pub fn with_max_tickers_per_request(mut self, max: usize) -> Self {
+        self.max_tickers = max.max(1);
+        self
+    }
 
-            let greeks = black_scholes_greeks(
-                request.underlying_prices[0], // Use first price for Greeks calculation
-                position.strike,
-                position.days_to_expiry / 365.0,
-                risk_free_rate,
-                volatility,
-                option_type,
-            );
+    pub fn max_tickers_per_request(&self) -> usize {
+        self.max_tickers
+    }
 
-            let mut pnl_curve = Vec::new();
-            for &price in &request.underlying_prices {
-                let current_greeks = black_scholes_greeks(
-                    price,
-                    position.strike,
-                    position.days_to_expiry / 365.0,
-                    risk_free_rate,
-                    volatility,
-                    option_type,
-                );
+    // Turns API key auth on for every server started with this API
+    pub fn with_authenticator(mut self, authenticator: Authenticator) -> Self {
+        self.authenticator = Some(Arc::new(authenticator));
+        self
+    }
 
-                pnl_curve.push(PnLPoint {
-                    underlying_price: price,
-                    pnl: calculate_pnl(position.quantity.into(), position.entry_price, current_greeks.price),
-                    total_value: current_greeks.price * position.quantity as f64,
-                });
-            }
+    pub fn authenticator(&self) -> Option<&Arc<Authenticator>> {
+        self.authenticator.as_ref()
+    }
 
-            portfolio_pnl_curves.push(pnl_curve.clone());
+    // Runs a portfolio-store call on the blocking pool, since the SQLite store does file I/O
+    // under a mutex that would otherwise stall the runtime's worker threads
+    pub async fn with_portfolios<T, F>(&self, job: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&PortfolioManager) -> Result<T, ApiError> + Send + 'static,
+    {
+        let portfolios = Arc::clone(&self.portfolios);
+        tokio::task::spawn_blocking(move || job(&portfolios))
+            .await
+            .map_err(|e| ApiError::StorageError(format!("storage task failed: {}", e)))?
+    }
 
-            positions.push(PositionAnalysis {
-                position: position.clone(),
-                greeks: GreeksData {
-                    delta: greeks.delta,
-                    gamma: greeks.gamma,
-                    theta: greeks.theta,
-                    vega: greeks.vega,
-                    rho: greeks.rho,
-                    theoretical_price: greeks.price,
-                },
-                pnl_curve,
-            });
-        }
+    async fn portfolio(&self, id: &str) -> Result<Portfolio, ApiError> {
+        let id = id.to_string();
+        self.with_portfolios(move |p| p.get_portfolio(&id)).await
+    }
 
-        // Calculate portfolio totals
-        let portfolio = self.calculate_portfolio_analysis(&portfolio_pnl_curves, &request.underlying_prices);
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
 
-        Ok(OptionsPnLResponse {
-            positions,
-            portfolio,
-        })
+    pub fn events(&self) -> &EventHub {
+        &self.events
     }
 
-    // Real-time Quotes Endpoint
-    pub async fn get_quotes(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
-        let mut quotes = HashMap::new();
-        let mut errors = Vec::new();
+    // Background loop: starts due scheduled jobs, each on its own task so a slow one doesn't hold up
+    // the rest
+    pub async fn run_scheduler(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            ticker.tick().await;
+            for (id, task) in self.scheduler.take_due(Utc::now()) {
+                let api = Arc::clone(&self);
+                tokio::spawn(async move { api.run_job(id, task).await });
+            }
+        }
+    }
 
-        let options = ChartQueryOptions {
-            interval: "1m",
-            range: "1d",
-        };
+    // Adds a job once its task's arguments check out
+    pub async fn add_scheduled_job(&self, request: JobRequest) -> Result<Job, ApiError> {
+        match &request.task {
+            JobTask::RefreshWatchlists { watchlist_id: Some(id) } => {
+                let id = id.clone();
+                self.with_portfolios(move |p| p.get_watchlist(&id)).await?;
+            }
+            JobTask::RunScreener { screener } => {
+                serde_json::from_value::<ScreenerRequest>(screener.clone())
+                    .map_err(|e| ApiError::InvalidParameters(format!("Screener request: {}", e)))?;
+            }
+            _ => {}
+        }
+        self.scheduler.add_job(request)
+    }
 
-        for ticker in &request.tickers {
-            match self.fetch_ticker_data(ticker, &options).await {
-                Ok(data) => {
-                    if let Ok(quote) = self.extract_quote_from_data(data) {
-                        quotes.insert(ticker.clone(), quote);
-                    } else {
-                        errors.push(format!("Could not extract quote for {}", ticker));
-                    }
-                }
-                Err(e) => {
-                    errors.push(format!("Error fetching quote for {}: {}", ticker, e));
+    // Runs a job straight away, whatever its schedule, and returns it with the run recorded
+    pub async fn run_job_now(&self, id: &str) -> Result<Job, ApiError> {
+        let task = self.scheduler.take_now(id)?;
+        self.run_job(id.to_string(), task).await;
+        self.scheduler.job(id)
+    }
+
+    async fn run_job(&self, id: String, task: JobTask) {
+        let started = Utc::now();
+        let timer = Instant::now();
+        let outcome = self.execute_job_task(&task).await;
+        if let Err(e) = &outcome {
+            warn!(job_id = %id, error = %e, "Scheduled job failed");
+        }
+        self.scheduler.finish(&id, started, timer.elapsed().as_millis() as u64, outcome);
+    }
+
+    async fn execute_job_task(&self, task: &JobTask) -> Result<JobOutcome, ApiError> {
+        match task {
+            JobTask::RefreshWatchlists { watchlist_id } => {
+                let ids: Vec<String> = match watchlist_id {
+                    Some(id) => vec![id.clone()],
+                    None => self.with_portfolios(|p| p.list_watchlists()).await?.into_iter().map(|w| w.id).collect(),
+                };
+                let (mut quoted, mut errors) = (0, 0);
+                for id in &ids {
+                    let response = self.get_watchlist_quotes(id, Some("")).await?;
+                    quoted += response.members.iter().filter(|m| m.quote.is_some()).count();
+                    errors += response.errors.len();
                 }
+                Ok(JobOutcome {
+                    summary: format!("Quoted {} symbols across {} watchlists ({} errors)", quoted, ids.len(), errors),
+                    result: None,
+                })
+            }
+            JobTask::SnapshotPortfolios => {
+                let symbols = self.with_portfolios(|p| p.held_symbols()).await?;
+                let prices: BTreeMap<String, f64> = if symbols.is_empty() {
+                    BTreeMap::new()
+                } else {
+                    let response = self.quotes(QuoteRequest { tickers: symbols, fields: None, include_prepost: None }).await?;
+                    response.quotes.into_iter().map(|(symbol, quote)| (symbol, quote.price)).collect()
+                };
+                let snapshots = self.with_portfolios(move |p| p.snapshot_portfolios(&prices)).await?;
+                Ok(JobOutcome { summary: format!("Recorded {} portfolio snapshots", snapshots.len()), result: None })
+            }
+            JobTask::RunScreener { screener } => {
+                let request: ScreenerRequest = serde_json::from_value(screener.clone())
+                    .map_err(|e| ApiError::InvalidParameters(format!("Screener request: {}", e)))?;
+                let response = self.screen_stocks(request).await?;
+                Ok(JobOutcome {
+                    summary: format!("{} matches", response.results.len()),
+                    result: serde_json::to_value(&response).ok(),
+                })
+            }
+            JobTask::EvaluateAlerts => {
+                let triggered = self.check_alerts_once().await?;
+                Ok(JobOutcome { summary: format!("{} alerts triggered", triggered.len()), result: None })
+            }
+            JobTask::ProcessOrders => {
+                let processed = self.process_orders_once().await?;
+                Ok(JobOutcome { summary: format!("{} orders filled or rejected", processed.len()), result: None })
             }
         }
+    }
 
-        Ok(QuoteResponse { quotes, errors })
+    // Background loop: every `interval`, quote the symbols that have pending alerts and let the
+    // portfolio manager evaluate them (and notify). Errors are logged and the loop carries on.
+    pub async fn run_alert_checker(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.check_alerts_once().await {
+                warn!(error = %e, "Alert check failed");
+            }
+        }
     }
 
-    // Helper methods
-    async fn fetch_ticker_data(&self, ticker: &str, options: &ChartQueryOptions<'_>) -> Result<ChartResponse, ApiError> {
-        self.chart_fetcher.fetch_async(ticker, options).await
-            .map_err(|e| ApiError::FetchError(e.to_string()))
+    pub async fn check_alerts_once(&self) -> Result<Vec<TriggeredAlert>, ApiError> {
+        let symbols = self.with_portfolios(|p| p.alert_symbols()).await?;
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self.quotes(QuoteRequest { tickers: symbols, fields: None, include_prepost: None }).await?;
+        for error in &response.errors {
+            warn!(%error, "Alert check");
+        }
+
+        let quotes = response.quotes;
+        let triggered = self.with_portfolios(move |p| p.check_alerts(&quotes)).await?;
+        self.events.publish_alerts(&triggered);
+        Ok(triggered)
     }
 
-    // Implementation of process_ticker_data
-    fn process_ticker_data(&self, chart_data: ChartResponse, request: &HistoricalDataRequest) -> Result<TickerData, ApiError> {
-        let result = chart_data.chart.result
-            .as_ref()
-            .and_then(|results| results.get(0))
-            .ok_or_else(|| ApiError::DataNotFound("No chart data found".to_string()))?;
+    // Background loop: every `interval`, try to fill the open paper orders
+    pub async fn run_order_processor(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.process_orders_once().await {
+                warn!(error = %e, "Order processing failed");
+            }
+        }
+    }
 
-        let candles = to_candles(result);
-        if candles.is_empty() {
-            return Err(ApiError::DataNotFound("No valid candles found".to_string()));
+    // Places a paper order; quote-filled orders that are already marketable fill straight away
+    pub async fn place_order(&self, request: PlaceOrderRequest) -> Result<Order, ApiError> {
+        let order = self.with_portfolios(move |p| p.place_order(request)).await?;
+        if order.fill_source != FillSource::Quote {
+            return Ok(order);
         }
+        // The order is saved either way; if it can't be quoted now the background pass retries it
+        match self.fill_from_quotes(vec![order.clone()]).await {
+            Ok(processed) => Ok(processed.into_iter().next().unwrap_or(order)),
+            Err(e) => {
+                warn!(order_id = %order.id, error = %e, "Immediate fill attempt failed; order left open");
+                Ok(order)
+            }
+        }
+    }
 
-        // Convert candles to API format
-        let mut candle_data = Vec::new();
-        for candle in &candles {
-            let datetime = UNIX_EPOCH + Duration::from_secs(candle.timestamp.try_into().unwrap());
-            let dt: DateTime<Utc> = datetime.into();
-            
-            candle_data.push(CandleData {
-                timestamp: candle.timestamp,
-                datetime: dt.to_rfc3339(),
-                open: candle.open,
-                high: candle.high,
-                low: candle.low,
-                close: candle.close,
-                volume: candle.volume,
-                adj_close: None, // You'd extract this from adjclose indicators
-            });
+    // Open orders that filled or were rejected on this pass
+    pub async fn process_orders_once(&self) -> Result<Vec<Order>, ApiError> {
+        let (by_quote, by_candle): (Vec<Order>, Vec<Order>) = self
+            .with_portfolios(|p| p.list_orders(None, Some(OrderStatus::Open)))
+            .await?
+            .into_iter()
+            .partition(|o| o.fill_source == FillSource::Quote);
+
+        let mut processed = self.fill_from_quotes(by_quote).await?;
+        processed.extend(self.fill_from_candles(by_candle).await?);
+        Ok(processed)
+    }
+
+    async fn fill_from_quotes(&self, pending: Vec<Order>) -> Result<Vec<Order>, ApiError> {
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut symbols: Vec<String> = pending.iter().map(|o| o.symbol.clone()).collect();
+        symbols.sort();
+        symbols.dedup();
+        let response = self.quotes(QuoteRequest { tickers: symbols, fields: None, include_prepost: None }).await?;
+        for error in &response.errors {
+            warn!(%error, "Order processing");
         }
 
-        // Calculate indicators if requested
-        let indicators = if request.include_indicators.unwrap_or(false) {
-            Some(self.indicator_runner.run(&candles))
-        } else {
-            None
-        };
+        let now = Utc::now().to_rfc3339();
+        let mut processed = Vec::new();
+        for order in pending {
+            let Some(quote) = response.quotes.get(&order.symbol) else { continue };
+            if let Some(price) = orders::quote_fill_price(&order, quote.price) {
+                processed.extend(self.fill_order(&order, price, now.clone()).await);
+            }
+        }
+        Ok(processed)
+    }
 
-        // Build metadata
-        let meta = TickerMeta {
-            currency: result.meta.currency.clone(),
-            exchange: result.meta.exchangeName.clone(),
-            instrument_type: result.meta.instrumentType.clone(),
-            timezone: result.meta.timezone.clone(),
-            regular_market_price: result.meta.regularMarketPrice,
-            fifty_two_week_high: result.meta.fiftyTwoWeekHigh,
-            fifty_two_week_low: result.meta.fiftyTwoWeekLow,
-            market_cap: None, // Not available in basic chart data
-            pe_ratio: None,
-            dividend_yield: None,
+    // Each order fills on the first intraday bar that opens after it was placed and reaches its price
+    async fn fill_from_candles(&self, pending: Vec<Order>) -> Result<Vec<Order>, ApiError> {
+        let mut by_symbol: BTreeMap<String, Vec<Order>> = BTreeMap::new();
+        for order in pending {
+            by_symbol.entry(order.symbol.clone()).or_default().push(order);
+        }
+        let options = ChartQueryOptions {
+            interval: ORDER_CANDLE_INTERVAL,
+            range: ORDER_CANDLE_RANGE,
+            include_pre_post: false,
+            period: None,
         };
 
-        Ok(TickerData {
-            symbol: result.meta.symbol.clone(),
-            candles: candle_data,
-            indicators,
-            meta,
-        })
+        let mut processed = Vec::new();
+        for (symbol, symbol_orders) in by_symbol {
+            let candles = match self.fetch_history(&symbol, &options).await.and_then(|chart| self.extract_candles(&chart)) {
+                Ok(candles) => candles,
+                Err(e) => {
+                    warn!(%symbol, error = %e, "No candles to fill orders against");
+                    continue;
+                }
+            };
+            for order in symbol_orders {
+                let placed = DateTime::parse_from_rfc3339(&order.created_at).map(|dt| dt.timestamp()).unwrap_or(i64::MAX);
+                let fill = candles.iter()
+                    .filter(|c| c.timestamp >= placed)
+                    .find_map(|c| orders::candle_fill_price(&order, c).map(|price| (price, c.timestamp)));
+                let Some((price, timestamp)) = fill else { continue };
+                let at = Utc.timestamp_opt(timestamp, 0).single().map(|dt| dt.to_rfc3339()).unwrap_or_else(|| order.created_at.clone());
+                processed.extend(self.fill_order(&order, price, at).await);
+            }
+        }
+        Ok(processed)
     }
 
-    // Implementation of process_options_data
-    fn process_options_data(
-        &self,
-        options_data: OptionProfitCalculatorResponse,
-        request: &OptionsChainRequest,
-        underlying_price: f64,
-    ) -> Result<OptionsChainResponse, ApiError> {
-        let mut expirations = HashMap::new();
-        
-        let volatility = request.volatility.unwrap_or(0.25);
-        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
-        let include_greeks = request.include_greeks.unwrap_or(false);
+    // A fill that can't be stored is logged and skipped so the rest of the pass still runs; the
+    // order stays open for the next one
+    async fn fill_order(&self, order: &Order, price: f64, at: String) -> Option<Order> {
+        let id = order.id.clone();
+        match self.with_portfolios(move |p| p.fill_order(&id, price, &at)).await {
+            Ok(order) => Some(order),
+            Err(e) => {
+                warn!(order_id = %order.id, symbol = %order.symbol, error = %e, "Could not fill order");
+                None
+            }
+        }
+    }
 
-        for (expiry_str, exp_data) in options_data.options {
-            // Calculate days to expiry (simplified - you'd want proper date parsing)
-            let days_to_expiry = 30.0; // Placeholder - parse expiry_str properly
-            let time_to_expiry = days_to_expiry / 365.0;
+    // Runs indicator work on the blocking pool so a pathological series can't stall the handler.
+    // On timeout the job keeps running in the background but the request fails fast.
+    async fn run_indicators_with_timeout<T, F>(&self, runner: IndicatorRunner, job: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(IndicatorRunner) -> T + Send + 'static,
+    {
+        let task = tokio::task::spawn_blocking(move || job(runner));
+        match tokio::time::timeout(self.indicator_timeout, task).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => Err(ApiError::CalculationError(format!("indicator task failed: {}", e))),
+            Err(_) => Err(ApiError::CalculationError("timeout".to_string())),
+        }
+    }
 
-            let mut calls = Vec::new();
-            let mut puts = Vec::new();
+    // Indicators named in the request come from the shared indicators-core registry
+    // (e.g. {"name": "rsi", "params": {"period": 21}}); otherwise the server's default set runs.
+    fn runner_for(&self, request: &HistoricalDataRequest) -> Result<IndicatorRunner, ApiError> {
+        match request.indicators.as_deref() {
+            Some(configs) => self.runner_from_configs(configs),
+            None => Ok(self.indicator_runner.clone()),
+        }
+    }
 
-            // Process calls
-            for (strike_str, quote) in exp_data.c {
-                let strike: f64 = strike_str.parse().unwrap_or(0.0);
-                
-                // Apply filters
-                if let Some(min_strike) = request.min_strike {
-                    if strike < min_strike { continue; }
-                }
-                if let Some(max_strike) = request.max_strike {
-                    if strike > max_strike { continue; }
-                }
-                if let Some(ref option_type) = request.option_type {
-                    if option_type == "put" { continue; }
-                }
+    fn runner_from_configs(&self, configs: &[IndicatorConfig]) -> Result<IndicatorRunner, ApiError> {
+        let indicators = build_indicators(configs.iter().map(|c| (c.name.as_str(), c.params.clone().unwrap_or_default())))
+            .map_err(ApiError::InvalidParameters)?;
+        Ok(IndicatorRunner::new(indicators).with_pool(self.indicator_pool.clone()))
+    }
 
-                let greeks = if include_greeks {
-                    let g = black_scholes_greeks(
-                        underlying_price,
-                        strike,
-                        time_to_expiry,
-                        risk_free_rate,
-                        volatility,
-                        OptionType::Call,
-                    );
-                    Some(GreeksData {
-                        delta: g.delta,
-                        gamma: g.gamma,
-                        theta: g.theta,
-                        vega: g.vega,
-                        rho: g.rho,
-                        theoretical_price: g.price,
-                    })
-                } else {
-                    None
-                };
+    // Historical Data Endpoint
+    pub async fn get_historical_data(&self, request: HistoricalDataRequest) -> Result<HistoricalDataResponse, ApiError> {
+        let mut check = Validator::new();
+        check
+            .tickers("tickers", &request.tickers, self.max_tickers)
+            .interval("interval", request.interval.as_deref())
+            .range("range", request.range.as_deref());
+        if let Some(benchmark) = request.benchmark.as_deref() {
+            check.ticker("benchmark", benchmark);
+        }
+        check.finish()?;
+        self.historical_data(request).await
+    }
 
-                calls.push(OptionContractData {
-                    strike,
-                    bid: quote.b,
-                    ask: quote.a,
-                    last: quote.l,
-                    volume: quote.v,
-                    open_interest: quote.oi,
-                    implied_volatility: None, // Not available in this data source
-                    greeks,
-                });
-            }
+    // get_historical_data without the per-request checks, for tickers the API already holds
+    async fn historical_data(&self, request: HistoricalDataRequest) -> Result<HistoricalDataResponse, ApiError> {
+        let mut data = BTreeMap::new();
+        let mut errors = Vec::new();
+        if request.limit == Some(0) {
+            return Err(ApiError::InvalidParameters("limit must be at least 1".to_string()));
+        }
+        let after = request.cursor.as_deref().map(parse_cursor).transpose()?;
 
-            // Process puts (similar logic)
-            for (strike_str, quote) in exp_data.p {
-                let strike: f64 = strike_str.parse().unwrap_or(0.0);
-                
-                // Apply filters
-                if let Some(min_strike) = request.min_strike {
-                    if strike < min_strike { continue; }
-                }
-                if let Some(max_strike) = request.max_strike {
-                    if strike > max_strike { continue; }
+        // start_date/end_date take precedence over range
+        let now = Utc::now();
+        let period = date_window(request.start_date.as_deref(), request.end_date.as_deref(), now)?;
+        let options = ChartQueryOptions {
+            interval: request.interval.as_deref().unwrap_or("1d"),
+            range: request.range.as_deref().unwrap_or("1mo"),
+            include_pre_post: request.include_prepost.unwrap_or(false),
+            period,
+        };
+        match period {
+            Some((start, _)) => validate_window_interval(start, options.interval, now.timestamp())?,
+            None => validate_range_interval(options.range, options.interval)?,
+        }
+
+        let resample_to = resample_target(options.interval, request.resample_to.as_deref())?;
+        let series = SeriesOptions::from_request(&request)?;
+
+        // Benchmark for relative strength is fetched once and shared by every ticker
+        let benchmark_candles = match request.benchmark.as_deref() {
+            Some(symbol) => match self.fetch_history_resampled(symbol, &options, resample_to).await {
+                Ok(chart_data) => Some(self.history_candles(&chart_data, &series)?),
+                Err(e) => {
+                    errors.push(format!("Error fetching benchmark {}: {}", symbol, e));
+                    None
                 }
-                if let Some(ref option_type) = request.option_type {
-                    if option_type == "call" { continue; }
+            },
+            None => None,
+        };
+        let benchmark = benchmark_candles.as_deref();
+        let runner = self.runner_for(&request)?;
+
+        let include_indicators = request.include_indicators.unwrap_or(false);
+        let parallel = include_indicators
+            && request.parallel_indicators.unwrap_or(request.tickers.len() > 1);
+
+        if !parallel {
+            for ticker in &request.tickers {
+                match self.fetch_history_resampled(ticker, &options, resample_to).await {
+                    Ok(ticker_data) => {
+                        let processed_data = self.process_ticker_data(ticker_data, &request, &runner, benchmark).await?;
+                        data.insert(ticker.clone(), processed_data);
+                    }
+                    Err(e) => {
+                        errors.push(format!("Error fetching {}: {}", ticker, e));
+                    }
                 }
+            }
 
-                let greeks = if include_greeks {
-                    let g = black_scholes_greeks(
-                        underlying_price,
-                        strike,
-                        time_to_expiry,
-                        risk_free_rate,
-                        volatility,
-                        OptionType::Put,
-                    );
-                    Some(GreeksData {
-                        delta: g.delta,
-                        gamma: g.gamma,
-                        theta: g.theta,
-                        vega: g.vega,
-                        rho: g.rho,
-                        theoretical_price: g.price,
-                    })
-                } else {
-                    None
-                };
+            let mut response = HistoricalDataResponse { data, errors, next_cursor: None };
+            response.paginate(after, request.limit);
+            return Ok(response);
+        }
 
-                puts.push(OptionContractData {
-                    strike,
-                    bid: quote.b,
-                    ask: quote.a,
-                    last: quote.l,
-                    volume: quote.v,
-                    open_interest: quote.oi,
-                    implied_volatility: None,
-                    greeks,
-                });
+        // Fetch everything first, then compute indicators across all tickers in one pass
+        let mut prepared = Vec::new();
+        for ticker in &request.tickers {
+            match self.fetch_history_resampled(ticker, &options, resample_to).await {
+                Ok(chart_data) => {
+                    let candles = self.history_candles(&chart_data, &series)?;
+                    prepared.push((ticker.clone(), chart_data, candles));
+                }
+                Err(e) => {
+                    errors.push(format!("Error fetching {}: {}", ticker, e));
+                }
             }
+        }
 
-            // Sort by strike price
-            calls.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap());
-            puts.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap());
+        let series: Vec<Vec<Candle>> = prepared.iter().map(|(_, _, candles)| candles.clone()).collect();
+        let indicator_maps = self.run_indicators_with_timeout(runner.clone(), move |runner| {
+            let refs: Vec<&[Candle]> = series.iter().map(|s| s.as_slice()).collect();
+            runner.run_many(&refs)
+        }).await?;
 
-            expirations.insert(expiry_str.clone(), ExpirationData {
-                expiration_date: expiry_str,
-                days_to_expiry,
-                calls,
-                puts,
-            });
+        for ((ticker, chart_data, candles), indicators) in prepared.iter().zip(indicator_maps) {
+            let processed_data = self.build_ticker_data(chart_data, candles, Some(indicators), &request, &runner, benchmark)?;
+            data.insert(ticker.clone(), processed_data);
         }
 
-        let greeks_params = if include_greeks {
-            Some(GreeksParams {
-                volatility,
-                risk_free_rate,
-            })
-        } else {
-            None
+        let mut response = HistoricalDataResponse { data, errors, next_cursor: None };
+        response.paginate(after, request.limit);
+        Ok(response)
+    }
+
+    // Options Chain Endpoint
+    pub async fn get_options_chain(&self, request: OptionsChainRequest) -> Result<OptionsChainResponse, ApiError> {
+        validation::ticker(&request.ticker)?;
+        // Get underlying price first
+        let chart_options = ChartQueryOptions::default();
+        let chart_data = self.fetch_ticker_data(&request.ticker, &chart_options).await?;
+        let underlying_price = self.extract_current_price(&chart_data)?;
+
+        // Fetch options data
+        let options_data = self.options_fetcher.fetch_async(&request.ticker).await
+            .map_err(from_fetch_error)?;
+
+        // Contracts without a solvable implied vol are priced at the requested volatility, else the
+        // stock's own recent realized volatility
+        let compute_greeks = request.include_greeks.unwrap_or(false) || request.min_delta.is_some() || request.max_delta.is_some();
+        let volatility = match request.volatility {
+            Some(volatility) => (volatility, "requested"),
+            None if compute_greeks => match self.realized_volatility(&request.ticker).await {
+                Some(volatility) => (volatility, "historical"),
+                None => (DEFAULT_VOLATILITY, "default"),
+            },
+            None => (DEFAULT_VOLATILITY, "default"),
         };
 
-        Ok(OptionsChainResponse {
-            symbol: request.ticker.clone(),
+        // Process and filter options data
+        let processed_data = self.process_options_data(
+            options_data,
+            &request,
             underlying_price,
-            expirations,
-            greeks_params,
-        })
+            volatility,
+        )?;
+
+        Ok(processed_data)
     }
 
-    fn extract_current_price(&self, chart_data: &ChartResponse) -> Result<f64, ApiError> {
-        chart_data.chart.result
-            .as_ref()
-            .and_then(|results| results.get(0))
-            .map(|result| result.meta.regularMarketPrice)
-            .ok_or_else(|| ApiError::DataNotFound("No price data found".to_string()))
+    // Annualised close-to-close volatility of the last REALIZED_VOL_WINDOW daily bars; None when
+    // the history can't be fetched or is too short
+    async fn realized_volatility(&self, ticker: &str) -> Option<f64> {
+        let options = ChartQueryOptions {
+            interval: "1d",
+            range: "3mo",
+            include_pre_post: false,
+            period: None,
+        };
+        let chart_data = self.fetch_history(ticker, &options).await.ok()?;
+        let candles = self.extract_candles(&chart_data).ok()?;
+        realized_volatility(&candles, REALIZED_VOL_WINDOW, VolEstimator::CloseToClose, 252.0).filter(|v| *v > 0.0)
     }
 
-    fn extract_quote_from_data(&self, chart_data: ChartResponse) -> Result<Quote, ApiError> {
-        let result = chart_data.chart.result
-            .as_ref()
-            .and_then(|results| results.get(0))
-            .ok_or_else(|| ApiError::DataNotFound("No quote data found".to_string()))?;
+    // Implied-volatility surface: a spline-fitted smile per expiry, sampled on a shared moneyness grid
+    pub async fn get_vol_surface(&self, request: VolSurfaceRequest) -> Result<VolSurfaceResponse, ApiError> {
+        validation::ticker(&request.ticker)?;
+        let min_moneyness = request.min_moneyness.unwrap_or(0.7);
+        let max_moneyness = request.max_moneyness.unwrap_or(1.3);
+        if !(min_moneyness > 0.0 && min_moneyness < max_moneyness) {
+            return Err(ApiError::InvalidParameters("Moneyness must satisfy 0 < min_moneyness < max_moneyness".to_string()));
+        }
+        let steps = request.steps.unwrap_or(25);
+        if !(2..=SURFACE_MAX_STEPS).contains(&steps) {
+            return Err(ApiError::InvalidParameters(format!("steps must be between 2 and {}", SURFACE_MAX_STEPS)));
+        }
+        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
 
-        let candles = to_candles(result);
-        let latest_candle = candles.last()
-            .ok_or_else(|| ApiError::DataNotFound("No candle data found".to_string()))?;
+        let chart_data = self.fetch_ticker_data(&request.ticker, &ChartQueryOptions::default()).await?;
+        let underlying_price = self.extract_current_price(&chart_data)?;
+        let options_data = self.options_fetcher.fetch_async(&request.ticker).await
+            .map_err(from_fetch_error)?;
 
-        // Calculate change from previous close
-        let prev_close = result.meta.chartPreviousClose;
-        let current_price = result.meta.regularMarketPrice;
-        let change = current_price - prev_close;
-        let change_percent = (change / prev_close) * 100.0;
+        let moneyness = vol_surface::moneyness_grid(min_moneyness, max_moneyness, steps);
+        let strikes: Vec<f64> = moneyness.iter().map(|m| m * underlying_price).collect();
+        let now = Utc::now();
 
-        Ok(Quote {
-            symbol: result.meta.symbol.clone(),
-            price: current_price,
-            change,
-            change_percent,
-            volume: result.meta.regularMarketVolume,
-            bid: None, // Not available in this data
-            ask: None,
-            bid_size: None,
-            ask_size: None,
-            high_52w: result.meta.fiftyTwoWeekHigh,
-            low_52w: result.meta.fiftyTwoWeekLow,
-            market_cap: None,
-            pe_ratio: None,
-            dividend_yield: None,
-            last_updated: Utc::now().to_rfc3339(),
+        let mut expirations = Vec::new();
+        for (expiry_str, exp_data) in options_data.options {
+            let Some((days_to_expiry, _)) = days_to_expiry(&expiry_str, now) else {
+                continue;
+            };
+            if request.max_days.is_some_and(|max| days_to_expiry > max) {
+                continue;
+            }
+            let t = days_to_expiry / 365.0;
+            let quotes: Vec<vol_surface::ChainQuote> = [(&exp_data.c, OptionType::Call), (&exp_data.p, OptionType::Put)]
+                .into_iter()
+                .flat_map(|(side, option_type)| {
+                    side.iter().filter_map(move |(strike, quote)| {
+                        Some(vol_surface::ChainQuote { strike: strike.parse().ok()?, option_type, mid: bid_ask_mid_spread(quote.b, quote.a).0 })
+                    })
+                })
+                .collect();
+
+            let points = vol_surface::smile_points(&quotes, underlying_price, t, risk_free_rate);
+            let Some(smile) = vol_surface::fit_smile(&points, underlying_price, t, risk_free_rate) else {
+                continue; // fewer than two usable quotes
+            };
+            expirations.push(SurfaceSlice {
+                expiration_date: expiry_str,
+                days_to_expiry,
+                atm_iv: vol_surface::sample_smile(&smile, &[underlying_price], underlying_price, t, risk_free_rate)[0],
+                iv: vol_surface::sample_smile(&smile, &strikes, underlying_price, t, risk_free_rate),
+                quotes: points.into_iter().map(|(strike, iv)| SurfaceQuote { strike, iv }).collect(),
+            });
+        }
+        if expirations.is_empty() {
+            return Err(ApiError::DataNotFound(format!("No expiry of {} has enough quoted contracts for a smile", request.ticker)));
+        }
+        expirations.sort_by(|a, b| a.days_to_expiry.total_cmp(&b.days_to_expiry));
+
+        Ok(VolSurfaceResponse {
+            symbol: request.ticker,
+            underlying_price,
+            risk_free_rate,
+            moneyness,
+            strikes,
+            expirations,
         })
     }
 
-    fn calculate_portfolio_analysis(
-        &self,
-        pnl_curves: &[Vec<PnLPoint>],
-        underlying_prices: &[f64],
-    ) -> PortfolioAnalysis {
-        let mut total_pnl_curve = Vec::new();
-        
-        // Calculate total P&L at each price point
-        for (i, &price) in underlying_prices.iter().enumerate() {
-            let total_pnl: f64 = pnl_curves.iter()
-                .map(|curve| curve.get(i).map_or(0.0, |point| point.pnl))
-                .sum();
-            
-            let total_value: f64 = pnl_curves.iter()
-                .map(|curve| curve.get(i).map_or(0.0, |point| point.total_value))
-                .sum();
+    // Open interest per strike, max pain and put/call ratios for each expiry, plus the whole chain
+    pub async fn get_oi_profile(&self, request: OiProfileRequest) -> Result<OiProfileResponse, ApiError> {
+        validation::ticker(&request.ticker)?;
+        let chart_data = self.fetch_ticker_data(&request.ticker, &ChartQueryOptions::default()).await?;
+        let underlying_price = self.extract_current_price(&chart_data)?;
+        let options_data = self.options_fetcher.fetch_async(&request.ticker).await
+            .map_err(from_fetch_error)?;
+        let now = Utc::now();
 
-            total_pnl_curve.push(PnLPoint {
-                underlying_price: price,
-                pnl: total_pnl,
-                total_value,
+        let sides = |quotes: &HashMap<String, OptionQuote>| -> Vec<(String, u64, u64)> {
+            quotes.iter().map(|(strike, quote)| (strike.clone(), quote.oi, quote.v)).collect()
+        };
+        let (mut all_calls, mut all_puts) = (Vec::new(), Vec::new());
+        let mut expirations = Vec::new();
+        for (expiry_str, exp_data) in options_data.options {
+            let Some((days_to_expiry, _)) = days_to_expiry(&expiry_str, now) else {
+                continue;
+            };
+            if request.max_days.is_some_and(|max| days_to_expiry > max) {
+                continue;
+            }
+            let (calls, puts) = (sides(&exp_data.c), sides(&exp_data.p));
+            let strikes = oi_profile::by_strike(&calls, &puts);
+            let totals = total_interest(&strikes);
+            expirations.push(ExpiryOiProfile {
+                expiration_date: expiry_str,
+                days_to_expiry,
+                max_pain: oi_profile::max_pain(&strikes).map(|(strike, _)| strike),
+                total_call_open_interest: totals.call_open_interest,
+                total_put_open_interest: totals.put_open_interest,
+                total_call_volume: totals.call_volume,
+                total_put_volume: totals.put_volume,
+                put_call_oi_ratio: oi_profile::put_call_ratio(totals.put_open_interest, totals.call_open_interest),
+                put_call_volume_ratio: oi_profile::put_call_ratio(totals.put_volume, totals.call_volume),
+                strikes: strikes.into_iter().map(strike_open_interest).collect(),
             });
+            all_calls.extend(calls);
+            all_puts.extend(puts);
+        }
+        if expirations.is_empty() {
+            return Err(ApiError::DataNotFound(format!("No unexpired options listed for {}", request.ticker)));
         }
+        expirations.sort_by(|a, b| a.days_to_expiry.total_cmp(&b.days_to_expiry));
 
-        // Find break-even points (where P&L crosses zero)
-        let mut break_even_points = Vec::new();
-        for i in 1..total_pnl_curve.len() {
-            let prev = &total_pnl_curve[i - 1];
-            let curr = &total_pnl_curve[i];
-            
-            if (prev.pnl <= 0.0 && curr.pnl >= 0.0) || (prev.pnl >= 0.0 && curr.pnl <= 0.0) {
-                // Linear interpolation to find exact break-even point
-                let ratio = prev.pnl.abs() / (prev.pnl.abs() + curr.pnl.abs());
-                let break_even = prev.underlying_price + ratio * (curr.underlying_price - prev.underlying_price);
-                break_even_points.push(break_even);
+        let strikes = oi_profile::by_strike(&all_calls, &all_puts);
+        let totals = total_interest(&strikes);
+        Ok(OiProfileResponse {
+            symbol: request.ticker,
+            underlying_price,
+            total_call_open_interest: totals.call_open_interest,
+            total_put_open_interest: totals.put_open_interest,
+            put_call_oi_ratio: oi_profile::put_call_ratio(totals.put_open_interest, totals.call_open_interest),
+            put_call_volume_ratio: oi_profile::put_call_ratio(totals.put_volume, totals.call_volume),
+            strikes: strikes.into_iter().map(strike_open_interest).collect(),
+            expirations,
+        })
+    }
+
+    // Unusual options activity across one or more chains, ranked by score. A single ticker whose
+    // chain can't be fetched is an error; in a batch it is listed in `errors` and the scan goes on.
+    pub async fn scan_unusual_activity(&self, request: UnusualActivityRequest) -> Result<UnusualActivityResponse, ApiError> {
+        let mut check = Validator::new();
+        for (i, ticker) in request.tickers.iter().enumerate().filter(|(_, t)| !t.trim().is_empty()) {
+            check.ticker(&format!("tickers[{}]", i), ticker);
+        }
+        check.finish()?;
+        let mut tickers: Vec<String> = request.tickers.iter()
+            .map(|t| t.trim().to_uppercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let mut seen = BTreeSet::new();
+        tickers.retain(|t| seen.insert(t.clone()));
+        if tickers.is_empty() || tickers.len() > UNUSUAL_MAX_TICKERS {
+            return Err(ApiError::InvalidParameters(format!("Between 1 and {} tickers are required", UNUSUAL_MAX_TICKERS)));
+        }
+        let thresholds = unusual_activity::Thresholds {
+            min_volume: request.min_volume.unwrap_or(100),
+            volume_oi_ratio: request.min_volume_oi_ratio.unwrap_or(2.0),
+            sweep_premium: request.min_sweep_premium.unwrap_or(50_000.0),
+            iv_ratio: request.min_iv_ratio.unwrap_or(1.5),
+        };
+        if !(thresholds.volume_oi_ratio > 0.0 && thresholds.sweep_premium > 0.0 && thresholds.iv_ratio > 0.0) {
+            return Err(ApiError::InvalidParameters("Unusual activity thresholds must be positive".to_string()));
+        }
+        let (max_days, risk_free_rate) = (request.max_days, request.risk_free_rate.unwrap_or(0.01));
+        let concurrency = request.concurrency
+            .unwrap_or(UNUSUAL_DEFAULT_CONCURRENCY)
+            .clamp(1, UNUSUAL_MAX_CONCURRENCY);
+
+        let scans: Vec<(String, Result<Vec<UnusualContract>, ApiError>)> = stream::iter(tickers.clone())
+            .map(|ticker| {
+                let thresholds = &thresholds;
+                async move {
+                    let contracts = self.unusual_contracts(&ticker, thresholds, max_days, risk_free_rate).await;
+                    (ticker, contracts)
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let single = tickers.len() == 1;
+        let (mut contracts, mut scanned, mut errors) = (Vec::new(), Vec::new(), Vec::new());
+        for (ticker, scan) in scans {
+            match scan {
+                Ok(found) => {
+                    contracts.extend(found);
+                    scanned.push(ticker);
+                }
+                Err(e) if single => return Err(e),
+                Err(e) => errors.push(format!("{}: {}", ticker, e)),
             }
         }
+        contracts.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.premium.total_cmp(&a.premium)));
+        let total_count = contracts.len();
+        contracts.truncate(request.limit.unwrap_or(50));
 
-        // Find max profit and max loss
-        let max_profit = total_pnl_curve.iter()
-            .map(|point| point.pnl)
-            .fold(f64::NEG_INFINITY, f64::max);
-        
-        let max_loss = total_pnl_curve.iter()
-            .map(|point| point.pnl)
-            .fold(f64::INFINITY, f64::min);
+        Ok(UnusualActivityResponse { contracts, total_count, scanned, errors })
+    }
 
-        // Calculate total Greeks (simplified - sum of all position Greeks)
-        let total_greeks = GreeksData {
-            delta: 0.0, // Would sum individual position deltas
-            gamma: 0.0, // Would sum individual position gammas
-            theta: 0.0, // Would sum individual position thetas
-            vega: 0.0,  // Would sum individual position vegas
-            rho: 0.0,   // Would sum individual position rhos
-            theoretical_price: 0.0, // Not applicable for portfolio
-        };
+    async fn unusual_contracts(
+        &self,
+        ticker: &str,
+        thresholds: &unusual_activity::Thresholds,
+        max_days: Option<f64>,
+        risk_free_rate: f64,
+    ) -> Result<Vec<UnusualContract>, ApiError> {
+        let chart_data = self.fetch_ticker_data(ticker, &ChartQueryOptions::default()).await?;
+        let underlying_price = self.extract_current_price(&chart_data)?;
+        let options_data = self.options_fetcher.fetch_async(ticker).await
+            .map_err(from_fetch_error)?;
+        let now = Utc::now();
 
-        PortfolioAnalysis {
-            total_greeks,
-            total_pnl_curve,
-            break_even_points,
-            max_profit: if max_profit.is_finite() { Some(max_profit) } else { None },
-            max_loss: if max_loss.is_finite() { Some(max_loss) } else { None },
+        let mut flagged = Vec::new();
+        for (expiry_str, exp_data) in options_data.options {
+            let Some((days_to_expiry, _)) = days_to_expiry(&expiry_str, now) else {
+                continue;
+            };
+            if max_days.is_some_and(|max| days_to_expiry > max) {
+                continue;
+            }
+            let t = days_to_expiry / 365.0;
+            let quotes: Vec<unusual_activity::ContractQuote> = [(&exp_data.c, OptionType::Call), (&exp_data.p, OptionType::Put)]
+                .into_iter()
+                .flat_map(|(side, option_type)| {
+                    side.iter().filter_map(move |(strike, quote)| {
+                        let strike: f64 = strike.parse().ok()?;
+                        let (mid, _) = bid_ask_mid_spread(quote.b, quote.a);
+                        let price = if mid > 0.0 { mid } else { quote.l };
+                        Some(unusual_activity::ContractQuote {
+                            strike,
+                            option_type,
+                            bid: quote.b,
+                            ask: quote.a,
+                            last: quote.l,
+                            volume: quote.v,
+                            open_interest: quote.oi,
+                            iv: implied_volatility(price, underlying_price, strike, t, risk_free_rate, option_type),
+                        })
+                    })
+                })
+                .collect();
+
+            for (i, detection) in unusual_activity::detect(&quotes, thresholds) {
+                let quote = &quotes[i];
+                flagged.push(UnusualContract {
+                    symbol: ticker.to_string(),
+                    underlying_price,
+                    expiration_date: expiry_str.clone(),
+                    days_to_expiry,
+                    option_type: match quote.option_type {
+                        OptionType::Call => "call".to_string(),
+                        OptionType::Put => "put".to_string(),
+                    },
+                    strike: quote.strike,
+                    bid: quote.bid,
+                    ask: quote.ask,
+                    last: quote.last,
+                    volume: quote.volume,
+                    open_interest: quote.open_interest,
+                    volume_oi_ratio: detection.volume_oi_ratio,
+                    premium: detection.premium,
+                    sweep_side: detection.sweep_side.map(|side| side.as_str().to_string()),
+                    implied_volatility: quote.iv,
+                    iv_ratio: detection.iv_ratio,
+                    signals: detection.signals.iter().map(|s| s.to_string()).collect(),
+                    score: detection.score,
+                });
+            }
         }
+        Ok(flagged)
     }
 
-    // Screener implementation
-    pub async fn screen_stocks(
-        &self,
-        request: ScreenerRequest,
-        cache: &AsyncRwLock<Option<CrumbCache>>,
-    ) -> Result<ScreenerResponse, ApiError> {
-        let mut yahoo_client = YahooFinanceClient::new();
+    // Position Sizing Endpoint
+    pub fn calculate_position_size(&self, request: PositionSizeRequest) -> Result<PositionSizeResponse, ApiError> {
+        if request.entry <= 0.0 || request.account_value <= 0.0 {
+            return Err(ApiError::InvalidParameters("Entry and account value must be positive".to_string()));
+        }
+        // position_size would quietly size these to zero shares
+        if request.risk_pct <= 0.0 {
+            return Err(ApiError::InvalidParameters("risk_pct must be positive".to_string()));
+        }
 
-        let yahoo_response = match request.screener_type.as_deref() {
-            Some("predefined") => {
-                let screener_id = request.predefined_screener
-                    .as_deref()
-                    .unwrap_or("most_actives");
-                
-                yahoo_client.fetch_predefined_screener(
-                    screener_id,
-                    request.limit.map(|l| l as u32),
-                    request.offset.map(|o| o as u32),
-                    cache,
-                ).await?
+        let (stop_distance, shares) = match (request.stop_distance, request.atr) {
+            (Some(distance), _) => (
+                distance,
+                position_size(request.account_value, request.risk_pct, request.entry, distance),
+            ),
+            (None, Some(atr)) => {
+                let multiplier = request.atr_multiplier.unwrap_or(2.0);
+                (
+                    atr * multiplier,
+                    atr_position_size(request.account_value, request.risk_pct, request.entry, atr, multiplier),
+                )
             }
-            _ => {
-                yahoo_client.fetch_custom_screener(
-                    &request.filters,
-                    request.sort_by.as_deref(),
-                    request.sort_order.as_deref(),
-                    request.limit.map(|l| l as u32),
-                    request.offset.map(|o| o as u32),
-                    cache,
-                ).await?
+            (None, None) => return Err(ApiError::InvalidParameters("Either stop_distance or atr is required".to_string())),
+        };
+        if stop_distance <= 0.0 {
+            return Err(ApiError::InvalidParameters("Stop distance must be greater than zero".to_string()));
+        }
+
+        Ok(PositionSizeResponse {
+            shares,
+            stop_distance,
+            stop_price: request.entry - stop_distance,
+            risk_amount: shares as f64 * stop_distance,
+            position_value: shares as f64 * request.entry,
+        })
+    }
+
+    // Options P&L Analysis Endpoint
+    pub fn calculate_options_pnl(&self, request: OptionsPnLRequest) -> Result<OptionsPnLResponse, ApiError> {
+        let volatility = request.volatility.unwrap_or(DEFAULT_VOLATILITY);
+        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
+        let dividend_yield = request.dividend_yield.unwrap_or(0.0);
+        let model = parse_pricing_model(request.pricing_model.as_deref())?;
+        let contract_size = request.contract_size.unwrap_or(100.0);
+        if !(contract_size > 0.0) {
+            return Err(ApiError::InvalidParameters("contract_size must be positive".to_string()));
+        }
+        let Some(&greeks_price) = request.underlying_prices.first() else {
+            return Err(ApiError::InvalidParameters("underlying_prices must not be empty".to_string()));
+        };
+        let nearest_expiry = request.positions.iter().map(|p| p.days_to_expiry).fold(f64::INFINITY, f64::min);
+        let offsets = match &request.valuation_date_offsets {
+            Some(offsets) => offsets.clone(),
+            None if nearest_expiry.is_finite() => vec![0.0, nearest_expiry / 2.0, nearest_expiry],
+            None => Vec::new(),
+        };
+        if offsets.len() > MAX_VALUATION_DATES || offsets.iter().any(|d| !d.is_finite() || *d < 0.0) {
+            return Err(ApiError::InvalidParameters(format!(
+                "valuation_date_offsets takes up to {} non-negative day counts", MAX_VALUATION_DATES
+            )));
+        }
+
+        let mut positions = Vec::new();
+        let mut legs = Vec::new();
+        let mut portfolio_pnl_curves: Vec<Vec<PnLPoint>> = Vec::new();
+        let mut total_greeks = GreeksData {
+            delta: 0.0,
+            gamma: 0.0,
+            theta: 0.0,
+            theta_per_day: 0.0,
+            vega: 0.0,
+            rho: 0.0,
+            theoretical_price: 0.0,
+        };
+
+        // Calculate P&L for each position
+        for position in &request.positions {
+            let option_type = match position.option_type.as_str() {
+                "call" => OptionType::Call,
+                "put" => OptionType::Put,
+                _ => return Err(ApiError::InvalidParameters("Invalid option type".to_string())),
+            };
+            legs.push((position, option_type));
+
+            let greeks = option_greeks(
+                model,
+                greeks_price, // Use first price for Greeks calculation
+                position.strike,
+                position.days_to_expiry / 365.0,
+                risk_free_rate,
+                dividend_yield,
+                volatility,
+                option_type,
+            );
+
+            let mut pnl_curve = Vec::new();
+            for &price in &request.underlying_prices {
+                let current_greeks = option_greeks(
+                    model,
+                    price,
+                    position.strike,
+                    position.days_to_expiry / 365.0,
+                    risk_free_rate,
+                    dividend_yield,
+                    volatility,
+                    option_type,
+                );
+
+                pnl_curve.push(PnLPoint {
+                    underlying_price: price,
+                    pnl: calculate_pnl(position.quantity.into(), position.entry_price, current_greeks.price),
+                    total_value: current_greeks.price * position.quantity as f64,
+                });
+            }
+
+            portfolio_pnl_curves.push(pnl_curve.clone());
+
+            let scale = position.quantity as f64 * contract_size;
+            total_greeks.delta += greeks.delta * scale;
+            total_greeks.gamma += greeks.gamma * scale;
+            total_greeks.theta += greeks.theta * scale;
+            total_greeks.theta_per_day += greeks.theta / 365.0 * scale;
+            total_greeks.vega += greeks.vega * scale;
+            total_greeks.rho += greeks.rho * scale;
+            total_greeks.theoretical_price += greeks.price * scale;
+
+            positions.push(PositionAnalysis {
+                position: position.clone(),
+                greeks: GreeksData {
+                    delta: greeks.delta,
+                    gamma: greeks.gamma,
+                    theta: greeks.theta,
+                    theta_per_day: greeks.theta / 365.0,
+                    vega: greeks.vega,
+                    rho: greeks.rho,
+                    theoretical_price: greeks.price,
+                },
+                pnl_curve,
+            });
+        }
+
+        let exposure = GreeksExposure {
+            underlying_price: greeks_price,
+            net_delta: total_greeks.delta,
+            dollar_delta: total_greeks.delta * greeks_price,
+            net_gamma: total_greeks.gamma,
+            net_theta_per_day: total_greeks.theta_per_day,
+            net_vega: total_greeks.vega / 100.0,
+        };
+
+        // Every leg marked at `price`, `days` from now
+        let mark_at = |price: f64, days: f64| {
+            let (mut pnl, mut total_value) = (0.0, 0.0);
+            for (position, option_type) in &legs {
+                let remaining = (position.days_to_expiry - days) / 365.0;
+                let mark = if remaining > 0.0 {
+                    option_greeks(model, price, position.strike, remaining, risk_free_rate, dividend_yield, volatility, *option_type).price
+                } else {
+                    intrinsic(price, position.strike, *option_type) // expired by then
+                };
+                pnl += calculate_pnl(position.quantity.into(), position.entry_price, mark);
+                total_value += mark * position.quantity as f64;
+            }
+            PnLPoint { underlying_price: price, pnl, total_value }
+        };
+
+        let pnl_curves_by_date = offsets.into_iter()
+            .map(|days| {
+                let pnl_curve: Vec<PnLPoint> = request.underlying_prices.iter().map(|&price| mark_at(price, days)).collect();
+                let label = if days == 0.0 {
+                    "today".to_string()
+                } else if days == nearest_expiry {
+                    "expiry".to_string()
+                } else {
+                    format!("+{}d", days)
+                };
+                DatedPnLCurve { days_from_now: days, label, break_even_points: break_even_points(&pnl_curve), pnl_curve }
+            })
+            .collect();
+
+        // Outcome at the nearest expiry with the underlying lognormal from the first price. The
+        // density is integrated over PROBABILITY_GRID_SIGMAS standard deviations either side.
+        let probability = (nearest_expiry.is_finite() && nearest_expiry > 0.0).then(|| {
+            let t = nearest_expiry / 365.0;
+            let sd = volatility * t.sqrt();
+            let mean_log = greeks_price.ln() + (risk_free_rate - dividend_yield - 0.5 * volatility * volatility) * t;
+            let dz = 2.0 * PROBABILITY_GRID_SIGMAS / PROBABILITY_GRID_POINTS as f64;
+            let outcomes: Vec<(f64, PnLPoint)> = (0..=PROBABILITY_GRID_POINTS)
+                .map(|i| {
+                    let z = -PROBABILITY_GRID_SIGMAS + i as f64 * dz;
+                    (norm_pdf(z) * dz, mark_at((mean_log + sd * z).exp(), nearest_expiry))
+                })
+                .collect();
+            let total_weight: f64 = outcomes.iter().map(|(w, _)| w).sum();
+            let curve: Vec<PnLPoint> = outcomes.iter().map(|(_, point)| point.clone()).collect();
+
+            ProbabilityAnalysis {
+                underlying_price: greeks_price,
+                volatility,
+                risk_free_rate,
+                dividend_yield,
+                days: nearest_expiry,
+                expected_price: greeks_price * ((risk_free_rate - dividend_yield) * t).exp(),
+                probability_of_profit: outcomes.iter().filter(|(_, p)| p.pnl > 0.0).map(|(w, _)| w).sum::<f64>() / total_weight,
+                expected_pnl: outcomes.iter().map(|(w, p)| w * p.pnl).sum::<f64>() / total_weight,
+                breakevens: break_even_points(&curve).into_iter()
+                    .map(|price| BreakevenProbability {
+                        price,
+                        probability_above: probability_above(greeks_price, price, t, risk_free_rate, dividend_yield, volatility),
+                        probability_of_touch: probability_of_touch(greeks_price, price, t, risk_free_rate, dividend_yield, volatility),
+                    })
+                    .collect(),
             }
+        });
+
+        // Calculate portfolio totals
+        let portfolio = self.calculate_portfolio_analysis(&portfolio_pnl_curves, &request.underlying_prices, total_greeks, pnl_curves_by_date);
+
+        Ok(OptionsPnLResponse {
+            positions,
+            portfolio,
+            exposure,
+            probability,
+        })
+    }
+
+    // Multi-timeframe Confluence Endpoint
+    // Scores trend (SMA alignment), RSI and MACD on daily, weekly and monthly bars resampled from one daily fetch
+    pub async fn get_confluence(&self, ticker: &str) -> Result<ConfluenceResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let options = ChartQueryOptions {
+            interval: "1d",
+            range: "10y",
+            include_pre_post: false,
+            period: None,
+        };
+        let chart_data = self.fetch_ticker_data(ticker, &options).await?;
+        let daily = self.extract_candles(&chart_data)?;
+        let gmt_offset = chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.get(0))
+            .map(|result| result.meta.gmtoffset)
+            .unwrap_or(0);
+
+        let session = Session { utc_offset: gmt_offset, open: 0 };
+        let frames = [("daily", Resolution::Days(1)), ("weekly", Resolution::Weeks(1)), ("monthly", Resolution::Months(1))];
+        let series: Vec<Vec<Candle>> = frames.iter().map(|(_, resolution)| resample(&daily, *resolution, &session)).collect();
+
+        let panel = IndicatorRunner::new(vec![
+            ("SMA(20)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 20)),
+            ("SMA(50)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 50)),
+            ("RSI(14)".to_string(), BoundIndicator::new(RSI::new()).with_param("period", 14)),
+            ("MACD(12,26)".to_string(), BoundIndicator::new(MACD::new()).with_output("macd")),
+        ]).with_pool(self.indicator_pool.clone());
+        let refs: Vec<&[Candle]> = series.iter().map(|s| s.as_slice()).collect();
+        let panels = panel.run_many(&refs);
+
+        let mut timeframes = Vec::new();
+        for ((name, _), (candles, values)) in frames.iter().zip(series.iter().zip(panels)) {
+            let latest = |key: &str| values.get(key).and_then(|v| v.last().copied().flatten());
+            let close = candles.last().map(|c| c.close).unwrap_or(0.0);
+
+            let ma_alignment = match (latest("SMA(20)"), latest("SMA(50)")) {
+                (Some(fast), Some(slow)) if close > fast && fast > slow => Some(1.0),
+                (Some(fast), Some(slow)) if close < fast && fast < slow => Some(-1.0),
+                (Some(_), Some(_)) => Some(0.0),
+                _ => None,
+            };
+            let rsi = latest("RSI(14)");
+            let macd = latest("MACD(12,26)");
+
+            let signals: Vec<f64> = [
+                ma_alignment,
+                rsi.map(|r| if r > 50.0 { 1.0 } else if r < 50.0 { -1.0 } else { 0.0 }),
+                macd.map(|m| if m > 0.0 { 1.0 } else if m < 0.0 { -1.0 } else { 0.0 }),
+            ].into_iter().flatten().collect();
+            let score = if signals.is_empty() {
+                None
+            } else {
+                Some(signals.iter().sum::<f64>() / signals.len() as f64)
+            };
+
+            timeframes.push(TimeframeScore {
+                timeframe: name.to_string(),
+                candles: candles.len(),
+                close,
+                ma_alignment,
+                rsi,
+                macd,
+                score,
+                bias: confluence_bias(score),
+            });
+        }
+
+        let scores: Vec<f64> = timeframes.iter().filter_map(|t| t.score).collect();
+        if scores.is_empty() {
+            return Err(ApiError::DataNotFound(format!("Not enough history to score {}", ticker)));
+        }
+        let score = scores.iter().sum::<f64>() / scores.len() as f64;
+
+        Ok(ConfluenceResponse {
+            symbol: ticker.to_string(),
+            timeframes,
+            score,
+            bias: confluence_bias(Some(score)),
+        })
+    }
+
+    // Indicator Signals Endpoint
+    // Buy/sell events per rule plus the weighted score of their stances on every bar
+    pub async fn get_signals(&self, request: SignalsRequest) -> Result<SignalsResponse, ApiError> {
+        Validator::new()
+            .ticker("ticker", &request.ticker)
+            .interval("interval", request.interval.as_deref())
+            .range("range", request.range.as_deref())
+            .finish()?;
+        let model = signal_model(request.rules.as_deref(), request.weights.as_deref(), request.threshold)?;
+        let interval = request.interval.as_deref().unwrap_or("1d");
+        let options = ChartQueryOptions {
+            interval,
+            range: request.range.as_deref().unwrap_or("1y"),
+            include_pre_post: false,
+            period: None,
+        };
+        let chart_data = self.fetch_history(&request.ticker, &options).await?;
+        let candles = self.extract_candles(&chart_data)?;
+        let needed = model.rules.iter().map(|(rule, _)| rule.warmup()).max().unwrap_or(0);
+        if candles.len() < needed {
+            return Err(ApiError::DataNotFound(format!(
+                "{} has {} bars at {}; the rules need at least {}", request.ticker, candles.len(), interval, needed
+            )));
+        }
+
+        let day = |ts: i64| {
+            Utc.timestamp_opt(ts, 0).single()
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default()
+        };
+        let (evaluated, scores) = model.evaluate(&candles);
+        let rules = evaluated.into_iter()
+            .map(|r| RuleSignals {
+                rule: r.rule.label(),
+                weight: r.weight,
+                events: candles.iter().zip(&r.signals)
+                    .filter(|(_, signal)| **signal != Signal::Neutral)
+                    .map(|(candle, signal)| SignalEvent { date: day(candle.timestamp), timestamp: candle.timestamp, signal: *signal })
+                    .collect(),
+                stance: r.stances.last().copied().unwrap_or(Signal::Neutral),
+            })
+            .collect();
+        let history: Vec<ScorePoint> = candles.iter().zip(&scores)
+            .map(|(candle, score)| ScorePoint { timestamp: candle.timestamp, score: *score, signal: model.signal(*score) })
+            .collect();
+        let score = scores.last().copied().unwrap_or(0.0);
+
+        Ok(SignalsResponse {
+            symbol: request.ticker,
+            interval: interval.to_string(),
+            rules,
+            history,
+            score,
+            signal: model.signal(score),
+        })
+    }
+
+    // Real-time Quotes Endpoint
+    // Quotes come from Yahoo's multi-symbol endpoint; if that fails, or leaves a ticker out, those
+    // tickers fall back to a chart quote enriched from quoteSummary
+    pub async fn get_quotes(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+        Validator::new().tickers("tickers", &request.tickers, self.max_tickers).finish()?;
+        self.quotes(request).await
+    }
+
+    // get_quotes without the per-request checks, for watchlists, portfolios and bundles
+    async fn quotes(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+        let mut quotes = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        let mut yahoo_client = self.yahoo_client();
+        let batch = match self.provider {
+            Some(_) => Ok(Vec::new()), // other providers quote one symbol at a time below
+            None => yahoo_client.fetch_quotes_batch(&request.tickers).await,
         };
+        let mut batch: HashMap<String, Quote> = match batch {
+            Ok(batch) => batch.into_iter()
+                .filter_map(YahooQuote::into_quote)
+                .map(|quote| (quote.symbol.to_uppercase(), quote))
+                .collect(),
+            Err(e) => {
+                warn!(error = %e, "Batch quote request failed, falling back to per-symbol quotes");
+                HashMap::new()
+            }
+        };
+
+        for ticker in &request.tickers {
+            if let Some(quote) = batch.remove(&ticker.to_uppercase()) {
+                quotes.insert(ticker.clone(), quote);
+                continue;
+            }
+
+            match self.get_enriched_quote(ticker).await {
+                Ok(quote) => {
+                    quotes.insert(ticker.clone(), quote);
+                }
+                Err(e @ ApiError::DataNotFound(_)) => {
+                    errors.push(format!("Could not extract quote for {}: {}", ticker, e));
+                }
+                Err(e) => {
+                    errors.push(format!("Error fetching quote for {}: {}", ticker, e));
+                }
+            }
+        }
+
+        // Quotes carry extended-hours prices wherever they're available; drop them when not wanted
+        if !request.include_prepost.unwrap_or(true) {
+            for quote in quotes.values_mut() {
+                quote.pre_market_price = None;
+                quote.pre_market_change = None;
+                quote.post_market_price = None;
+                quote.post_market_change = None;
+            }
+        }
+
+        Ok(QuoteResponse { quotes, errors })
+    }
+
+    // Tickers come from exactly one of `tickers`, `symbols` or `nasdaq`. Each runs on its own, so a
+    // symbol that fails is reported in its result without failing the rest.
+    pub async fn batch(&self, request: BatchRequest) -> Result<BatchResponse, ApiError> {
+        let tickers = match (request.tickers, request.symbols, request.nasdaq.unwrap_or(false)) {
+            (Some(tickers), None, false) => tickers,
+            (None, Some(text), false) => parse_symbol_list(&text),
+            (None, None, true) => fetch_nasdaq_symbols_csv().await
+                .map_err(|e| ApiError::FetchError(format!("NASDAQ listing: {}", e)))?,
+            _ => return Err(ApiError::InvalidParameters("Give exactly one of tickers, symbols or nasdaq".to_string())),
+        };
+        if tickers.is_empty() || tickers.len() > BATCH_MAX_TICKERS {
+            return Err(ApiError::InvalidParameters(format!(
+                "{} tickers given; a batch takes 1 to {} (use the CLI's --tickers-file for more)", tickers.len(), BATCH_MAX_TICKERS
+            )));
+        }
+        let concurrency = request.concurrency.unwrap_or(BATCH_DEFAULT_CONCURRENCY).clamp(1, BATCH_MAX_CONCURRENCY);
+        let command = request.command.unwrap_or_default();
+        Ok(self.run_batch(command, tickers, request.range.as_deref(), concurrency, |_, _| {}).await)
+    }
+
+    // Runs `command` for each ticker, `concurrency` at a time, calling `progress` with the number
+    // done so far as each one finishes
+    pub async fn run_batch<F>(
+        &self,
+        command: BatchCommand,
+        tickers: Vec<String>,
+        range: Option<&str>,
+        concurrency: usize,
+        progress: F,
+    ) -> BatchResponse
+    where
+        F: Fn(usize, &BatchResult) + Sync,
+    {
+        let total = tickers.len();
+        let range = range.unwrap_or(BATCH_DEFAULT_RANGE);
+        let done = AtomicUsize::new(0);
+        let mut results: Vec<(usize, BatchResult)> = stream::iter(tickers.into_iter().enumerate())
+            .map(|(i, ticker)| async move {
+                let outcome = self.batch_item(command, &ticker, range).await;
+                let result = match outcome {
+                    Ok(summary) => BatchResult { ticker, summary: Some(summary), error: None },
+                    Err(e) => BatchResult { ticker, summary: None, error: Some(e.to_string()) },
+                };
+                (i, result)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .inspect(|(_, result)| {
+                progress(done.fetch_add(1, Ordering::Relaxed) + 1, result);
+            })
+            .collect()
+            .await;
+        results.sort_by_key(|(i, _)| *i);
+
+        let failed = results.iter().filter(|(_, r)| r.error.is_some()).count();
+        BatchResponse {
+            command,
+            total,
+            succeeded: total - failed,
+            failed,
+            results: results.into_iter().map(|(_, r)| r).collect(),
+        }
+    }
+
+    async fn batch_item(&self, command: BatchCommand, ticker: &str, range: &str) -> Result<String, ApiError> {
+        validation::ticker(ticker)?;
+        match command {
+            BatchCommand::Hist => {
+                let request = HistoricalDataRequest {
+                    tickers: vec![ticker.to_string()],
+                    range: Some(range.to_string()),
+                    include_indicators: Some(true),
+                    ..Default::default()
+                };
+                let mut response = self.get_historical_data(request).await?;
+                let data = response.data.remove(ticker)
+                    .ok_or_else(|| ApiError::DataNotFound(response.errors.join("; ")))?;
+                let close = data.candles.last().map(|c| c.close).unwrap_or(0.0);
+                Ok(format!("{}: {} candles, close ${:.2}", ticker, data.candles.len(), close))
+            }
+            BatchCommand::Options => {
+                let request = OptionsChainRequest {
+                    ticker: ticker.to_string(),
+                    ..Default::default()
+                };
+                let response = self.get_options_chain(request).await?;
+                Ok(format!("{}: {} expirations, underlying ${:.2}",
+                    ticker, response.expirations.len(), response.underlying_price))
+            }
+            BatchCommand::Quote => {
+                let request = QuoteRequest {
+                    tickers: vec![ticker.to_string()],
+                    fields: None,
+                    include_prepost: None,
+                };
+                let mut response = self.get_quotes(request).await?;
+                let quote = response.quotes.remove(ticker)
+                    .ok_or_else(|| ApiError::DataNotFound(response.errors.join("; ")))?;
+                Ok(format!("{}: ${:.2} ({:+.2}%), volume {}",
+                    ticker, quote.price, quote.change_percent, crate::format_volume(quote.volume)))
+            }
+        }
+    }
+
+    // Several views of each item's symbol in one response. Quotes go out as one batch, items with
+    // the same candle spec share a historical request (and its candle cache reads), and news comes
+    // through the response cache; the candle, options and news fetches then run concurrently. A part
+    // that fails is listed in that ticker's errors without failing the rest.
+    pub async fn get_bundle(&self, request: BundleRequest) -> Result<BundleResponse, ApiError> {
+        if request.items.is_empty() || request.items.len() > BUNDLE_MAX_ITEMS {
+            return Err(ApiError::InvalidParameters(format!("Between 1 and {} items are required", BUNDLE_MAX_ITEMS)));
+        }
+        let mut check = Validator::new();
+        for (i, item) in request.items.iter().enumerate() {
+            check.ticker(&format!("items[{}].ticker", i), &item.ticker);
+            if let Some(candles) = &item.candles {
+                check
+                    .interval(&format!("items[{}].candles.interval", i), candles.interval.as_deref())
+                    .range(&format!("items[{}].candles.range", i), candles.range.as_deref());
+            }
+        }
+        check.finish()?;
+        let mut results: BTreeMap<String, BundleResult> = BTreeMap::new();
+        let mut items = Vec::new();
+        for item in request.items {
+            let ticker = item.ticker.trim().to_uppercase();
+            if results.insert(ticker.clone(), BundleResult::default()).is_some() {
+                return Err(ApiError::InvalidParameters(format!("{} is listed more than once", ticker)));
+            }
+            items.push((ticker, item));
+        }
+
+        let quote_tickers: Vec<String> = items.iter()
+            .filter(|(_, item)| item.quote.unwrap_or(false))
+            .map(|(ticker, _)| ticker.clone())
+            .collect();
+        if !quote_tickers.is_empty() {
+            let request = QuoteRequest { tickers: quote_tickers.clone(), fields: None, include_prepost: None };
+            let QuoteResponse { mut quotes, errors } = self.quotes(request).await?;
+            for ticker in &quote_tickers {
+                let result = results.entry(ticker.clone()).or_default();
+                match quotes.remove(ticker) {
+                    Some(quote) => result.quote = Some(quote),
+                    None => result.errors.push(format!("quote: {}", batch_error_for(&errors, ticker))),
+                }
+            }
+        }
+
+        let mut candle_groups: Vec<(&BundleCandles, Vec<String>)> = Vec::new();
+        for (ticker, item) in &items {
+            let Some(spec) = &item.candles else {
+                continue;
+            };
+            match candle_groups.iter_mut().find(|(group, _)| *group == spec) {
+                Some((_, tickers)) => tickers.push(ticker.clone()),
+                None => candle_groups.push((spec, vec![ticker.clone()])),
+            }
+        }
+
+        let mut jobs: Vec<BoxFuture<'_, BundlePart>> = Vec::new();
+        for (spec, tickers) in candle_groups {
+            jobs.push(async move {
+                let result = self.historical_data(spec.to_request(tickers.clone())).await;
+                BundlePart::Candles(tickers, result)
+            }.boxed());
+        }
+        for (ticker, item) in &items {
+            if let Some(options) = &item.options {
+                let request = OiProfileRequest { ticker: ticker.clone(), max_days: options.max_days };
+                let ticker = ticker.clone();
+                jobs.push(async move { BundlePart::Options(ticker, self.get_oi_profile(request).await) }.boxed());
+            }
+            if let Some(news) = &item.news {
+                let (ticker, count) = (ticker.clone(), news.count);
+                jobs.push(async move {
+                    let result = self.get_news(&ticker, count).await;
+                    BundlePart::News(ticker, result)
+                }.boxed());
+            }
+        }
+
+        let parts: Vec<BundlePart> = stream::iter(jobs).buffer_unordered(BUNDLE_CONCURRENCY).collect().await;
+        for part in parts {
+            match part {
+                BundlePart::Candles(tickers, Ok(mut response)) => {
+                    for ticker in tickers {
+                        let result = results.entry(ticker.clone()).or_default();
+                        match response.data.remove(&ticker) {
+                            Some(data) => result.candles = Some(data),
+                            None => result.errors.push(format!("candles: {}", batch_error_for(&response.errors, &ticker))),
+                        }
+                    }
+                }
+                BundlePart::Candles(tickers, Err(e)) => {
+                    for ticker in tickers {
+                        results.entry(ticker).or_default().errors.push(format!("candles: {}", e));
+                    }
+                }
+                BundlePart::Options(ticker, result) => {
+                    let entry = results.entry(ticker).or_default();
+                    match result {
+                        Ok(profile) => entry.options = Some(profile.into()),
+                        Err(e) => entry.errors.push(format!("options: {}", e)),
+                    }
+                }
+                BundlePart::News(ticker, result) => {
+                    let entry = results.entry(ticker).or_default();
+                    match result {
+                        Ok(news) => entry.news = Some(news),
+                        Err(e) => entry.errors.push(format!("news: {}", e)),
+                    }
+                }
+            }
+        }
+
+        Ok(BundleResponse { results })
+    }
+
+    // Dividends and splits from the chart's events payload. `from`/`to` are YYYY-MM-DD and
+    // default to the full history.
+    pub async fn get_corporate_actions(&self, ticker: &str, from: Option<&str>, to: Option<&str>) -> Result<CorporateActionsResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let options = ChartQueryOptions {
+            interval: "1mo", // events come back whatever the bar size, so keep the payload small
+            range: "max",
+            include_pre_post: false,
+            period: date_window(from, to, Utc::now())?,
+        };
+        let chart_data = self.fetch_ticker_data(ticker, &options).await?;
+        let result = chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.first())
+            .ok_or_else(|| ApiError::DataNotFound(format!("No chart data for {}", ticker)))?;
+
+        let day = |ts: i64| {
+            Utc.timestamp_opt(ts, 0).single()
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default()
+        };
+        // A period window still returns nothing outside it, but `range=max` needs no filtering
+        let in_window = |ts: i64| options.period.is_none_or(|(start, end)| ts >= start && ts < end);
+
+        let events = result.events.as_ref();
+        let mut dividends: Vec<DividendAction> = events
+            .map(|e| e.dividends.values().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|d| in_window(d.date))
+            .map(|d| DividendAction { date: day(d.date), timestamp: d.date, amount: d.amount })
+            .collect();
+        dividends.sort_by_key(|d| d.timestamp);
+
+        let mut splits: Vec<SplitAction> = events
+            .map(|e| e.splits.values().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| in_window(s.date))
+            .map(|s| SplitAction {
+                date: day(s.date),
+                timestamp: s.date,
+                numerator: s.numerator,
+                denominator: s.denominator,
+                ratio: s.split_ratio.clone(),
+            })
+            .collect();
+        splits.sort_by_key(|s| s.timestamp);
+
+        Ok(CorporateActionsResponse { symbol: result.meta.symbol.clone(), dividends, splits })
+    }
+
+    // Pulls each held symbol's dividends since its oldest open lot and records the ones the
+    // portfolio hasn't seen yet as dividend transactions
+    pub async fn sync_dividends(&self, portfolio_id: &str) -> Result<Vec<Transaction>, ApiError> {
+        let portfolio = self.portfolio(portfolio_id).await?;
+        let mut recorded = Vec::new();
+
+        for position in &portfolio.positions {
+            let Some(since) = position.lots.iter().map(|lot| lot.acquired_at.as_str()).min() else {
+                continue;
+            };
+            let from = since.get(..10).unwrap_or(since);
+            let actions = self.get_corporate_actions(&position.symbol, Some(from), None).await?;
+            let (id, symbol) = (portfolio_id.to_string(), position.symbol.clone());
+            recorded.extend(self.with_portfolios(move |p| p.record_dividends(&id, &symbol, &actions.dividends)).await?);
+        }
+
+        Ok(recorded)
+    }
+
+    // Risk of the portfolio's current holdings, replayed over `range` of split- and
+    // dividend-adjusted daily closes and measured against a benchmark
+    pub async fn get_portfolio_risk(&self, portfolio_id: &str, request: PortfolioRiskRequest) -> Result<PortfolioRiskResponse, ApiError> {
+        let portfolio = self.portfolio(portfolio_id).await?;
+        let range = request.range.unwrap_or_else(|| RISK_DEFAULT_RANGE.to_string());
+        validate_range_interval(&range, "1d")?;
+        let benchmark = request.benchmark.unwrap_or_else(|| RISK_DEFAULT_BENCHMARK.to_string()).to_uppercase();
+        let confidence = request.confidence.unwrap_or(RISK_DEFAULT_CONFIDENCE);
+        if !(0.5..1.0).contains(&confidence) {
+            return Err(ApiError::InvalidParameters("confidence must be at least 0.5 and below 1".to_string()));
+        }
+        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
+
+        let holdings: Vec<&Position> = portfolio.positions.iter().filter(|p| p.quantity > 0.0).collect();
+        if holdings.is_empty() {
+            return Err(ApiError::InvalidParameters(format!("Portfolio {} has no open positions", portfolio_id)));
+        }
+
+        let mut symbols: Vec<String> = holdings.iter().map(|p| p.symbol.clone()).collect();
+        symbols.push(benchmark.clone());
+        let histories = self.adjusted_daily_candles(&symbols, &range).await.into_iter().collect::<Result<Vec<_>, _>>()?;
+        let slices: Vec<&[Candle]> = histories.iter().map(Vec::as_slice).collect();
+        let closes = risk::align_closes(&slices);
+        let (benchmark_closes, holding_closes) = closes.split_last().expect("benchmark series");
+        if benchmark_closes.len() < 3 {
+            return Err(ApiError::DataNotFound(format!(
+                "Not enough overlapping history for {} and {} over {}", symbols[..holdings.len()].join(", "), benchmark, range,
+            )));
+        }
+
+        let values: Vec<f64> = holdings.iter().zip(holding_closes)
+            .map(|(position, closes)| position.quantity * closes.last().copied().unwrap_or(0.0))
+            .collect();
+        let market_value: f64 = values.iter().sum();
+        if market_value <= 0.0 {
+            return Err(ApiError::CalculationError(format!("Portfolio {} has no market value", portfolio_id)));
+        }
+        let weights: Vec<f64> = values.iter().map(|v| v / market_value).collect();
+
+        let member_returns: Vec<Vec<f64>> = holding_closes.iter().map(|closes| risk::returns(closes)).collect();
+        let portfolio_returns = risk::weighted_returns(&member_returns, &weights);
+        let metrics = risk::metrics(&portfolio_returns, &risk::returns(benchmark_closes), confidence, risk_free_rate)
+            .ok_or_else(|| ApiError::CalculationError("Not enough returns to measure risk".to_string()))?;
+
+        Ok(PortfolioRiskResponse {
+            portfolio_id: portfolio.id,
+            benchmark,
+            range,
+            observations: metrics.observations,
+            market_value,
+            weights: holdings.iter().map(|p| p.symbol.clone()).zip(weights).collect(),
+            beta: metrics.beta,
+            volatility: metrics.volatility,
+            annual_return: metrics.annual_return,
+            sharpe_ratio: metrics.sharpe,
+            sortino_ratio: metrics.sortino,
+            confidence,
+            var: metrics.var,
+            cvar: metrics.cvar,
+            var_amount: metrics.var * market_value,
+            cvar_amount: metrics.cvar * market_value,
+            max_drawdown: metrics.max_drawdown,
+        })
+    }
+
+    // Pairwise correlation of daily returns over `range`, on the days every ticker traded
+    pub async fn get_correlation(&self, request: CorrelationRequest) -> Result<CorrelationResponse, ApiError> {
+        let tickers = distinct_tickers(&request.tickers, ANALYTICS_MAX_TICKERS)?;
+        let range = request.range.unwrap_or_else(|| RISK_DEFAULT_RANGE.to_string());
+        let (tickers, returns, errors) = self.aligned_returns(tickers, &range).await?;
+
+        let matrix = risk::correlation_matrix(&returns);
+        let average_by_ticker = tickers.iter().enumerate()
+            .filter_map(|(i, ticker)| risk::average_correlation(&matrix, Some(i)).map(|avg| (ticker.clone(), avg)))
+            .collect();
+        Ok(CorrelationResponse {
+            average_correlation: risk::average_correlation(&matrix, None),
+            tickers,
+            range,
+            observations: returns[0].len(),
+            matrix,
+            average_by_ticker,
+            errors,
+        })
+    }
+
+    // Minimum-variance and max-Sharpe weights and the efficient frontier, from daily returns over
+    // `range` (annualized)
+    pub async fn optimize_portfolio(&self, request: OptimizeRequest) -> Result<OptimizeResponse, ApiError> {
+        let tickers = distinct_tickers(&request.tickers, ANALYTICS_MAX_TICKERS)?;
+        let range = request.range.unwrap_or_else(|| RISK_DEFAULT_RANGE.to_string());
+        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
+        let frontier_points = request.frontier_points.unwrap_or(OPTIMIZER_DEFAULT_FRONTIER_POINTS);
+        if !(2..=OPTIMIZER_MAX_FRONTIER_POINTS).contains(&frontier_points) {
+            return Err(ApiError::InvalidParameters(format!("frontier_points must be between 2 and {}", OPTIMIZER_MAX_FRONTIER_POINTS)));
+        }
+        let long_only = request.long_only.unwrap_or(true);
+        let upper = request.max_weight.unwrap_or(1.0);
+        if !(upper > 0.0 && upper <= 1.0) {
+            return Err(ApiError::InvalidParameters("max_weight must be above 0 and at most 1".to_string()));
+        }
+        // Shorts are capped like longs
+        let bounds = optimizer::Bounds { lower: if long_only { 0.0 } else { -upper }, upper };
+
+        let (tickers, returns, errors) = self.aligned_returns(tickers, &range).await?;
+        if !bounds.feasible(tickers.len()) {
+            return Err(ApiError::InvalidParameters(format!(
+                "max_weight {} is too small for {} tickers to add up to 1", upper, tickers.len()
+            )));
+        }
+
+        let annualize = |v: f64| v * risk::TRADING_DAYS;
+        let mean: Vec<f64> = returns.iter().map(|r| annualize(r.iter().sum::<f64>() / r.len() as f64)).collect();
+        let covariance: Vec<Vec<f64>> = returns.iter()
+            .map(|a| returns.iter().map(|b| annualize(risk::covariance(a, b))).collect())
+            .collect();
+        let optimizer = optimizer::Optimizer::new(&mean, &covariance, bounds);
+
+        let optimized = |allocation: optimizer::Allocation| OptimizedPortfolio {
+            sharpe_ratio: allocation.sharpe(risk_free_rate),
+            weights: tickers.iter().cloned().zip(allocation.weights).collect(),
+            expected_return: allocation.expected_return,
+            volatility: allocation.volatility,
+        };
+        Ok(OptimizeResponse {
+            range,
+            observations: returns[0].len(),
+            expected_returns: tickers.iter().cloned().zip(mean.iter().copied()).collect(),
+            volatilities: tickers.iter().cloned().zip((0..tickers.len()).map(|i| covariance[i][i].sqrt())).collect(),
+            min_variance: optimized(optimizer.min_variance()),
+            max_sharpe: optimizer.max_sharpe(risk_free_rate).map(optimized),
+            frontier: optimizer.frontier(frontier_points).into_iter().map(optimized).collect(),
+            tickers,
+            errors,
+        })
+    }
+
+    // Daily returns of each ticker on the days they all traded, over `range`. Tickers that can't
+    // be fetched are dropped with an error message; fewer than two left is an error.
+    async fn aligned_returns(&self, tickers: Vec<String>, range: &str) -> Result<(Vec<String>, Vec<Vec<f64>>, Vec<String>), ApiError> {
+        validate_range_interval(range, "1d")?;
+
+        let mut errors = Vec::new();
+        let mut fetched = Vec::new();
+        let histories = self.adjusted_daily_candles(&tickers, range).await;
+        for (ticker, history) in tickers.into_iter().zip(histories) {
+            match history {
+                Ok(candles) => fetched.push((ticker, candles)),
+                Err(e) => errors.push(format!("Error fetching {}: {}", ticker, e)),
+            }
+        }
+        if fetched.len() < 2 {
+            return Err(ApiError::DataNotFound(format!("Fewer than two tickers have history: {}", errors.join("; "))));
+        }
+
+        let slices: Vec<&[Candle]> = fetched.iter().map(|(_, candles)| candles.as_slice()).collect();
+        let returns: Vec<Vec<f64>> = risk::align_closes(&slices).iter().map(|closes| risk::returns(closes)).collect();
+        if returns[0].len() < 2 {
+            return Err(ApiError::DataNotFound(format!("Not enough overlapping history over {}", range)));
+        }
+        Ok((fetched.into_iter().map(|(ticker, _)| ticker).collect(), returns, errors))
+    }
+
+    // Trades that bring the portfolio back to its target weights at current prices
+    pub async fn get_rebalance(&self, portfolio_id: &str, request: RebalanceRequest) -> Result<RebalanceResponse, ApiError> {
+        let portfolio = self.portfolio(portfolio_id).await?;
+        if portfolio.target_weights.is_empty() {
+            return Err(ApiError::InvalidParameters(format!(
+                "Portfolio {} has no target weights; set them with PUT /api/v1/portfolio/{}/targets", portfolio_id, portfolio_id
+            )));
+        }
+        let drift_threshold = request.drift_threshold.unwrap_or(REBALANCE_DEFAULT_DRIFT);
+        if !(0.0..1.0).contains(&drift_threshold) {
+            return Err(ApiError::InvalidParameters("drift_threshold must be at least 0 and below 1".to_string()));
+        }
+
+        // Every holding, plus targets not held yet
+        let mut symbols: Vec<(String, f64)> = portfolio.positions.iter()
+            .filter(|p| p.quantity > 0.0)
+            .map(|p| (p.symbol.clone(), p.quantity))
+            .collect();
+        for symbol in portfolio.target_weights.keys() {
+            if !symbols.iter().any(|(s, _)| s == symbol) {
+                symbols.push((symbol.clone(), 0.0));
+            }
+        }
+
+        let QuoteResponse { quotes, errors } = self.quotes(QuoteRequest {
+            tickers: symbols.iter().map(|(s, _)| s.clone()).collect(),
+            fields: None,
+            include_prepost: None,
+        }).await?;
+        let unpriced: Vec<&str> = symbols.iter()
+            .filter(|(s, _)| quotes.get(s).is_none_or(|q| q.price <= 0.0))
+            .map(|(s, _)| s.as_str())
+            .collect();
+        if !unpriced.is_empty() {
+            return Err(ApiError::DataNotFound(format!("No price for {} ({})", unpriced.join(", "), errors.join("; "))));
+        }
+
+        let holdings: Vec<rebalance::Holding> = symbols.iter()
+            .map(|(symbol, quantity)| rebalance::Holding {
+                quantity: *quantity,
+                price: quotes[symbol].price,
+                target_weight: portfolio.target_weights.get(symbol).copied().unwrap_or(0.0),
+            })
+            .collect();
+        let fractional = request.fractional.unwrap_or(false);
+        let plan = rebalance::plan(&holdings, portfolio.cash_balance, drift_threshold, fractional)
+            .ok_or_else(|| ApiError::CalculationError(format!("Portfolio {} has no value to rebalance", portfolio_id)))?;
+
+        let trades = symbols.into_iter().zip(&holdings).zip(&plan.lines)
+            .map(|(((symbol, quantity), holding), line)| RebalanceTrade {
+                symbol,
+                price: holding.price,
+                quantity,
+                current_weight: line.current_weight,
+                target_weight: holding.target_weight,
+                drift: line.drift,
+                action: match line.quantity {
+                    q if q > 0.0 => "buy",
+                    q if q < 0.0 => "sell",
+                    _ => "hold",
+                }.to_string(),
+                trade_quantity: line.quantity,
+                trade_value: line.quantity * holding.price,
+                weight_after: line.weight_after,
+            })
+            .collect();
+        Ok(RebalanceResponse {
+            portfolio_id: portfolio.id,
+            total_value: plan.total_value,
+            cash_balance: portfolio.cash_balance,
+            cash_after: plan.cash_after,
+            drift_threshold,
+            trades,
+        })
+    }
+
+    // Split- and dividend-adjusted daily candles for each symbol over `range`, fetched a few at a
+    // time, in `symbols` order
+    async fn adjusted_daily_candles(&self, symbols: &[String], range: &str) -> Vec<Result<Vec<Candle>, ApiError>> {
+        let options = ChartQueryOptions {
+            interval: "1d",
+            range,
+            include_pre_post: false,
+            period: None,
+        };
+
+        stream::iter(symbols.to_vec())
+            .map(|symbol| {
+                let options = &options;
+                async move {
+                    let candles = match self.fetch_history(&symbol, options).await {
+                        Ok(chart_data) => self.history_candles(&chart_data, &SeriesOptions::adjusted()),
+                        Err(e) => Err(e),
+                    };
+                    candles.map_err(|e| match e {
+                        ApiError::DataNotFound(message) => ApiError::DataNotFound(format!("{}: {}", symbol, message)),
+                        e => e,
+                    })
+                }
+            })
+            .buffered(HISTORY_FETCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    // Quotes for the stream endpoint
+    pub async fn get_stream_quotes(&self, tickers: &[String]) -> StreamMessage {
+        let QuoteResponse { quotes, errors } = self.poll_quotes(tickers).await;
+        StreamMessage::Quotes { quotes, errors }
+    }
+
+    // Quotes for the push endpoints (/api/v1/stream, /api/v1/sse); each fetch waits on the shared
+    // upstream rate limiter like any other
+    pub async fn poll_quotes(&self, tickers: &[String]) -> QuoteResponse {
+        let mut quotes = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        let options = ChartQueryOptions {
+            interval: "1m",
+            range: "1d",
+            include_pre_post: true,
+            period: None,
+        };
+
+        for ticker in tickers {
+            match self.fetch_ticker_data(ticker, &options).await {
+                Ok(data) => match self.extract_quote_from_data(data) {
+                    Ok(quote) => {
+                        quotes.insert(ticker.clone(), quote);
+                    }
+                    Err(e) => errors.push(format!("Could not extract quote for {}: {}", ticker, e)),
+                },
+                Err(e) => errors.push(format!("Error fetching quote for {}: {}", ticker, e)),
+            }
+        }
+
+        QuoteResponse { quotes, errors }
+    }
+
+    // Indicator values on the last closed `interval` bar of each ticker, for /api/v1/sse. Intraday
+    // intervals only; a bar counts as closed once its full interval has passed.
+    pub async fn get_closed_candle_indicators(
+        &self,
+        tickers: &[String],
+        interval: &str,
+        indicators: &[IndicatorConfig],
+    ) -> ClosedCandles {
+        let request = HistoricalDataRequest {
+            tickers: tickers.to_vec(),
+            interval: Some(interval.to_string()),
+            range: Some(SSE_INDICATOR_RANGE.to_string()),
+            start_date: None,
+            end_date: None,
+            include_indicators: Some(true),
+            indicators: Some(indicators.to_vec()),
+            parallel_indicators: None,
+            min_candles: None,
+            include_returns: None,
+            benchmark: None,
+            adjust: None,
+            resample_to: None,
+            missing_data: None,
+            transform: None,
+            brick_size: None,
+            include_prepost: None,
+            limit: None,
+            cursor: None,
+        };
+        let response = match self.historical_data(request).await {
+            Ok(response) => response,
+            Err(e) => return ClosedCandles { updates: Vec::new(), next_close: None, errors: vec![e.to_string()] },
+        };
+
+        let bar_seconds = Resolution::parse(interval).map_or(60, |r| r.approx_seconds());
+        let now = Utc::now().timestamp();
+        let mut updates = Vec::new();
+        let mut next_close: Option<i64> = None;
+        for data in response.data.into_values() {
+            // The bar still forming, if any, says when the next one closes
+            if let Some(last) = data.candles.last().filter(|c| c.timestamp + bar_seconds > now) {
+                let close = last.timestamp + bar_seconds;
+                next_close = Some(next_close.map_or(close, |n| n.min(close)));
+            }
+            let Some(index) = data.candles.iter().rposition(|c| c.timestamp + bar_seconds <= now) else {
+                continue;
+            };
+            let values = data.indicators.unwrap_or_default().into_iter()
+                .filter_map(|(name, series)| series.get(index).copied().flatten().map(|v| (name, v)))
+                .collect();
+            updates.push(IndicatorUpdate {
+                symbol: data.symbol,
+                interval: interval.to_string(),
+                candle: data.candles[index].clone(),
+                indicators: values,
+            });
+        }
+        ClosedCandles { updates, next_close, errors: response.errors }
+    }
+
+    // Single quote with market cap, P/E and dividend yield filled in from quoteSummary.
+    // If quoteSummary is unavailable the chart quote is still returned with those fields empty.
+    pub async fn get_enriched_quote(&self, symbol: &str) -> Result<Quote, ApiError> {
+        validation::ticker(symbol)?;
+        if let Some(provider) = &self.provider {
+            return provider.quote(symbol).await
+                .map_err(|e| ApiError::FetchError(format!("{}: {}", provider.name(), e)));
+        }
+
+        let options = ChartQueryOptions {
+            interval: "1m",
+            range: "1d",
+            include_pre_post: true,
+            period: None,
+        };
+        let data = self.fetch_ticker_data(symbol, &options).await?;
+        let meta_range = data.chart.result.as_ref()
+            .and_then(|results| results.first())
+            .and_then(|result| result.meta.fiftyTwoWeekLow.zip(result.meta.fiftyTwoWeekHigh));
+        let mut quote = self.extract_quote_from_data(data)?;
+
+        let mut summary_range = None;
+        match self.fetch_fundamentals(symbol).await {
+            Ok(fundamentals) => {
+                quote.market_cap = fundamentals.market_cap;
+                quote.pe_ratio = fundamentals.pe_ratio;
+                quote.dividend_yield = fundamentals.dividend_yield;
+                summary_range = fundamentals.range_52w;
+            }
+            Err(e) => {
+                warn!(symbol, error = %e, "Fundamentals unavailable");
+            }
+        }
+
+        // 52-week range: chart meta, then quoteSummary, then computed from a year of daily bars
+        let range = match meta_range.or(summary_range) {
+            Some(range) => Some(range),
+            None => self.range_52w_from_candles(symbol).await,
+        };
+        if let Some((low, high)) = range {
+            quote.low_52w = low;
+            quote.high_52w = high;
+        }
+
+        Ok(quote)
+    }
+
+    async fn range_52w_from_candles(&self, symbol: &str) -> Option<(f64, f64)> {
+        let options = ChartQueryOptions {
+            interval: "1d",
+            range: "1y",
+            include_pre_post: false,
+            period: None,
+        };
+        let candles = match self.fetch_history(symbol, &options).await.and_then(|data| self.extract_candles(&data)) {
+            Ok(candles) => candles,
+            Err(e) => {
+                warn!(symbol, error = %e, "No 1y candles for the 52-week range");
+                return None;
+            }
+        };
+        let low = candles.iter().map(|c| c.low).reduce(f64::min)?;
+        let high = candles.iter().map(|c| c.high).reduce(f64::max)?;
+        Some((low, high))
+    }
+
+    async fn fetch_fundamentals(&self, symbol: &str) -> Result<FundamentalsCache, ApiError> {
+        {
+            let cache = self.fundamentals_cache.read().await;
+            if let Some(cached) = cache.get(symbol) {
+                if !cached.is_expired() {
+                    return Ok(cached.clone());
+                }
+            }
+        }
+
+        let mut yahoo_client = self.yahoo_client();
+        let summary = yahoo_client.fetch_quote_summary(symbol, &["summaryDetail", "defaultKeyStatistics"]).await?;
+        let detail = summary.summary_detail.as_ref();
+        let fundamentals = FundamentalsCache {
+            market_cap: detail.and_then(|sd| sd.market_cap),
+            pe_ratio: detail.and_then(|sd| sd.trailing_pe)
+                .or_else(|| summary.default_key_statistics.as_ref().and_then(|dks| dks.trailing_pe)),
+            dividend_yield: detail.and_then(|sd| sd.dividend_yield.or(sd.trailing_annual_dividend_yield)),
+            range_52w: detail.and_then(|sd| sd.fifty_two_week_low.zip(sd.fifty_two_week_high)),
+            expires_at: Instant::now() + FUNDAMENTALS_TTL,
+        };
+
+        self.fundamentals_cache.write().await.insert(symbol.to_string(), fundamentals.clone());
+        Ok(fundamentals)
+    }
+
+    // Helper methods
+    async fn fetch_ticker_data(&self, ticker: &str, options: &ChartQueryOptions<'_>) -> Result<ChartResponse, ApiError> {
+        self.chart_fetcher.fetch_async(ticker, options).await
+            .map_err(from_fetch_error)
+    }
+
+    // Historical bars through the candle cache when one is configured: fresh cached series are
+    // served from disk, stale ones only fetch the tail since their last bar. Adjusted requests use
+    // it too, since the store keeps Yahoo's adjusted closes and rescales them when a tail shows a
+    // new split or dividend.
+    async fn fetch_history(&self, ticker: &str, options: &ChartQueryOptions<'_>) -> Result<ChartResponse, ApiError> {
+        let now = Utc::now().timestamp();
+        // The cache tracks regular-session series that run up to now, so explicit date windows and
+        // extended-hours bars always go to Yahoo
+        if options.period.is_some() || options.include_pre_post {
+            return self.fetch_ticker_data(ticker, options).await;
+        }
+        let (Some(store), Some(wanted_from)) = (self.candle_store.as_ref(), range_start(options.range, now)) else {
+            return self.fetch_ticker_data(ticker, options).await;
+        };
+
+        let symbol = ticker.to_uppercase();
+        if let Some(series) = store.load(&symbol, options.interval).await?.filter(|s| s.covers_from <= wanted_from) {
+            if store.is_fresh(&series, now) {
+                return series.to_chart(wanted_from);
+            }
+
+            let tail = series.last_timestamp().and_then(|last| tail_range(options.interval, last, now));
+            if let Some(range) = tail {
+                let tail_options = ChartQueryOptions {
+                    interval: options.interval,
+                    range,
+                    include_pre_post: options.include_pre_post,
+                    period: None,
+                };
+                let chart = self.fetch_ticker_data(ticker, &tail_options).await?;
+                store.merge(&symbol, options.interval, &chart, series.covers_from, now).await?;
+                if let Some(merged) = store.load(&symbol, options.interval).await? {
+                    return merged.to_chart(wanted_from);
+                }
+            }
+        }
+
+        // Nothing usable cached (or the gap is too long for a tail fetch): take the full range
+        let chart = self.fetch_ticker_data(ticker, options).await?;
+        store.merge(&symbol, options.interval, &chart, wanted_from, now).await?;
+        Ok(chart)
+    }
+
+    // Implementation of process_ticker_data
+    async fn process_ticker_data(
+        &self,
+        chart_data: ChartResponse,
+        request: &HistoricalDataRequest,
+        runner: &IndicatorRunner,
+        benchmark: Option<&[Candle]>,
+    ) -> Result<TickerData, ApiError> {
+        // Shared with the blocking indicator task instead of copied into it
+        let candles: Arc<[Candle]> = self.history_candles(&chart_data, &SeriesOptions::from_request(request)?)?.into();
+
+        // Calculate indicators if requested
+        let indicators = if request.include_indicators.unwrap_or(false) {
+            let shared = Arc::clone(&candles);
+            Some(self.run_indicators_with_timeout(runner.clone(), move |runner| runner.run(&shared)).await?)
+        } else {
+            None
+        };
+
+        self.build_ticker_data(&chart_data, &candles, indicators, request, runner, benchmark)
+    }
+
+    // The fetched bars, aggregated when the request asked for a coarser interval. The candle cache
+    // only ever holds the fetched interval, so one cached 1m series serves every coarser timeframe.
+    async fn fetch_history_resampled(
+        &self,
+        ticker: &str,
+        options: &ChartQueryOptions<'_>,
+        resample_to: Option<(Resolution, &str)>,
+    ) -> Result<ChartResponse, ApiError> {
+        let mut chart = self.fetch_history(ticker, options).await?;
+        // Intraday bars stick to the regular session unless extended hours were asked for
+        if is_intraday(options.interval) && !options.include_pre_post {
+            chart = regular_session_chart(chart);
+        }
+        Ok(match resample_to {
+            Some((resolution, interval)) => resample_chart(chart, resolution, interval),
+            None => chart,
+        })
+    }
+
+    // Adjust, then fill gaps (so filled bars carry their neighbours' adjusted prices), then transform
+    fn history_candles(&self, chart_data: &ChartResponse, series: &SeriesOptions) -> Result<Vec<Candle>, ApiError> {
+        let mut candles = self.extract_candles(chart_data)?;
+        let Some(result) = chart_data.chart.result.as_ref().and_then(|results| results.first()) else {
+            return Ok(candles);
+        };
+        if series.adjust {
+            adjust_candles(&mut candles, &adj_closes(result));
+        }
+        let candles = fill_missing(candles, &result.timestamp, series.missing);
+        Ok(match &series.transform {
+            Some(transform) => transform.apply(&candles),
+            None => candles,
+        })
+    }
+
+    fn extract_candles(&self, chart_data: &ChartResponse) -> Result<Vec<Candle>, ApiError> {
+        let result = chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.get(0))
+            .ok_or_else(|| ApiError::DataNotFound("No chart data found".to_string()))?;
+
+        let candles = to_candles(result);
+        if candles.is_empty() {
+            return Err(ApiError::DataNotFound("No valid candles found".to_string()));
+        }
+        Ok(candles)
+    }
+
+    fn build_ticker_data(
+        &self,
+        chart_data: &ChartResponse,
+        candles: &[Candle],
+        mut indicators: Option<HashMap<String, Vec<Option<f64>>>>,
+        request: &HistoricalDataRequest,
+        runner: &IndicatorRunner,
+        benchmark: Option<&[Candle]>,
+    ) -> Result<TickerData, ApiError> {
+        let result = chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.get(0))
+            .ok_or_else(|| ApiError::DataNotFound("No chart data found".to_string()))?;
+
+        // Convert candles to API format. Transformed bars are synthetic, so they have no adjusted close.
+        let include_returns = request.include_returns.unwrap_or(false);
+        let adjusted = if request.transform.is_some() { HashMap::new() } else { adj_closes(result) };
+        let mut candle_data = Vec::new();
+        let mut prev_close: Option<f64> = None;
+        let intraday = is_intraday(&result.meta.dataGranularity);
+        for (local_dt, candle) in localize(candles, &result.meta.exchangeTimezoneName, result.meta.gmtoffset) {
+            let (change, change_percent, log_return) = match prev_close {
+                Some(prev) if include_returns && prev > 0.0 && candle.close > 0.0 => (
+                    Some(candle.close - prev),
+                    Some((candle.close - prev) / prev * 100.0),
+                    Some((candle.close / prev).ln()),
+                ),
+                _ => (None, None, None),
+            };
+            prev_close = Some(candle.close);
+
+            candle_data.push(CandleData {
+                timestamp: candle.timestamp,
+                datetime: local_dt.with_timezone(&Utc).to_rfc3339(),
+                exchange_datetime: local_dt.to_rfc3339(),
+                session: intraday.then(|| candle_session(&result.meta, candle.timestamp)).flatten().map(String::from),
+                open: candle.open,
+                high: candle.high,
+                low: candle.low,
+                close: candle.close,
+                volume: candle.volume,
+                adj_close: adjusted.get(&candle.timestamp).copied(),
+                change,
+                change_percent,
+                log_return,
+            });
+        }
+
+        // Drop all-None series for indicators the range is too short for and say why instead
+        let mut insufficient_data = Vec::new();
+        if let Some(map) = indicators.as_mut() {
+            let min_candles = request.min_candles.unwrap_or(0);
+            for (name, needed) in runner.insufficient(candles.len(), min_candles) {
+                map.remove(&name);
+                insufficient_data.push(InsufficientData { indicator: name, needed, got: candles.len() });
+            }
+        }
+
+        let relative_strength = match (request.benchmark.as_ref(), benchmark) {
+            (Some(symbol), Some(bench)) => {
+                let line = relative_strength(candles, bench);
+                let slope = relative_strength_slope(&line, RS_SLOPE_LOOKBACK);
+                Some(RelativeStrengthData { benchmark: symbol.clone(), line, slope })
+            }
+            _ => None,
+        };
+
+        // Build metadata
+        let meta = TickerMeta {
+            currency: result.meta.currency.clone(),
+            exchange: result.meta.exchangeName.clone(),
+            instrument_type: result.meta.instrumentType.clone(),
+            asset_class: result.meta.asset_class(),
+            volume_unit: result.meta.asset_class().volume_unit().map(str::to_string),
+            timezone: result.meta.timezone.clone(),
+            regular_market_price: result.meta.regularMarketPrice,
+            fifty_two_week_high: result.meta.fiftyTwoWeekHigh,
+            fifty_two_week_low: result.meta.fiftyTwoWeekLow,
+            market_cap: None, // Not available in basic chart data
+            pe_ratio: None,
+            dividend_yield: None,
+        };
+
+        Ok(TickerData {
+            symbol: result.meta.symbol.clone(),
+            candles: candle_data,
+            indicators,
+            insufficient_data,
+            relative_strength,
+            meta,
+        })
+    }
+
+    // Implementation of process_options_data
+    fn process_options_data(
+        &self,
+        options_data: OptionProfitCalculatorResponse,
+        request: &OptionsChainRequest,
+        underlying_price: f64,
+        (volatility, volatility_source): (f64, &str),
+    ) -> Result<OptionsChainResponse, ApiError> {
+        let mut expirations = BTreeMap::new();
+        
+        // `volatility` is used for contracts whose implied vol can't be solved (no quote, price outside bounds)
+        let risk_free_rate = request.risk_free_rate.unwrap_or(0.01);
+        let dividend_yield = request.dividend_yield.unwrap_or(0.0);
+        let model = parse_pricing_model(request.pricing_model.as_deref())?;
+        let include_greeks = request.include_greeks.unwrap_or(false);
+        // Delta filters need Greeks even when the caller didn't ask for them, but they're only returned
+        // when include_greeks is set
+        let compute_greeks = include_greeks || request.min_delta.is_some() || request.max_delta.is_some();
+
+        let moneyness = match request.moneyness.as_deref() {
+            Some(spec) => Some(
+                spec.split(',')
+                    .map(|name| Moneyness::parse(name).ok_or_else(|| ApiError::InvalidParameters(format!("Unknown moneyness: {} (use itm, atm or otm)", name))))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
+        let moneyness_band = request.moneyness_band.unwrap_or(5.0);
+        if !(moneyness_band >= 0.0) {
+            return Err(ApiError::InvalidParameters("moneyness_band must be a non-negative percent".to_string()));
+        }
+        let moneyness_matches = |strike: f64, option_type: OptionType| {
+            underlying_price <= 0.0
+                || moneyness.as_ref().map_or(true, |wanted| wanted.contains(&Moneyness::classify(strike, underlying_price, option_type, moneyness_band)))
+        };
+        if let Some(dates) = &request.expiration_dates {
+            if let Some(bad) = dates.iter().find(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_err()) {
+                return Err(ApiError::InvalidParameters(format!("Invalid expiration date {} (expected YYYY-MM-DD)", bad)));
+            }
+        }
+
+        let now = Utc::now();
+
+        // Requested expiries only, nearest first (YYYY-MM-DD sorts chronologically), capped at max_expirations
+        let mut chain: Vec<_> = options_data.options.into_iter()
+            .filter(|(expiry, _)| request.expiration_dates.as_ref().map_or(true, |dates| dates.contains(expiry)))
+            .filter_map(|(expiry, data)| days_to_expiry(&expiry, now).map(|days| (expiry, data, days))) // unparseable or already expired
+            .collect();
+        chain.sort_by(|a, b| a.0.cmp(&b.0));
+        chain.truncate(request.max_expirations.unwrap_or(usize::MAX));
+
+        for (expiry_str, exp_data, (days_to_expiry, trading_days_to_expiry)) in chain {
+            let time_to_expiry = days_to_expiry / 365.0;
+
+            let mut calls = Vec::new();
+            let mut puts = Vec::new();
+
+            // Process calls
+            for (strike_str, quote) in exp_data.c {
+                let strike: f64 = strike_str.parse().unwrap_or(0.0);
+                
+                // Apply filters
+                if let Some(min_strike) = request.min_strike {
+                    if strike < min_strike { continue; }
+                }
+                if let Some(max_strike) = request.max_strike {
+                    if strike > max_strike { continue; }
+                }
+                if let Some(ref option_type) = request.option_type {
+                    if option_type == "put" { continue; }
+                }
+                if !self.moneyness_in_range(request, strike, underlying_price) { continue; }
+                if !moneyness_matches(strike, OptionType::Call) { continue; }
+                let (mid, spread_pct) = bid_ask_mid_spread(quote.b, quote.a);
+                if !self.spread_in_range(request, mid, spread_pct) { continue; }
+
+                let market_price = if mid > 0.0 { mid } else { quote.l };
+                let iv = implied_volatility(market_price, underlying_price, strike, time_to_expiry, risk_free_rate, OptionType::Call);
+                // An explicit ?volatility= overrides the per-contract implied vol
+                let sigma = request.volatility.or(iv).unwrap_or(volatility);
+
+                let greeks = if compute_greeks {
+                    let g = option_greeks(
+                        model,
+                        underlying_price,
+                        strike,
+                        time_to_expiry,
+                        risk_free_rate,
+                        dividend_yield,
+                        sigma,
+                        OptionType::Call,
+                    );
+                    Some(GreeksData {
+                        delta: g.delta,
+                        gamma: g.gamma,
+                        theta: g.theta,
+                        theta_per_day: g.theta / 365.0,
+                        vega: g.vega,
+                        rho: g.rho,
+                        theoretical_price: g.price,
+                    })
+                } else {
+                    None
+                };
+
+                if !self.delta_in_range(request, greeks.as_ref()) { continue; }
+
+                calls.push(OptionContractData {
+                    strike,
+                    bid: quote.b,
+                    ask: quote.a,
+                    mid,
+                    spread_pct,
+                    last: quote.l,
+                    volume: quote.v,
+                    open_interest: quote.oi,
+                    implied_volatility: iv, // solved from mid (or last) since the feed has no IV
+                    greeks: greeks.filter(|_| include_greeks),
+                });
+            }
+
+            // Process puts (similar logic)
+            for (strike_str, quote) in exp_data.p {
+                let strike: f64 = strike_str.parse().unwrap_or(0.0);
+                
+                // Apply filters
+                if let Some(min_strike) = request.min_strike {
+                    if strike < min_strike { continue; }
+                }
+                if let Some(max_strike) = request.max_strike {
+                    if strike > max_strike { continue; }
+                }
+                if let Some(ref option_type) = request.option_type {
+                    if option_type == "call" { continue; }
+                }
+                if !self.moneyness_in_range(request, strike, underlying_price) { continue; }
+                if !moneyness_matches(strike, OptionType::Put) { continue; }
+                let (mid, spread_pct) = bid_ask_mid_spread(quote.b, quote.a);
+                if !self.spread_in_range(request, mid, spread_pct) { continue; }
+
+                let market_price = if mid > 0.0 { mid } else { quote.l };
+                let iv = implied_volatility(market_price, underlying_price, strike, time_to_expiry, risk_free_rate, OptionType::Put);
+                // An explicit ?volatility= overrides the per-contract implied vol
+                let sigma = request.volatility.or(iv).unwrap_or(volatility);
+
+                let greeks = if compute_greeks {
+                    let g = option_greeks(
+                        model,
+                        underlying_price,
+                        strike,
+                        time_to_expiry,
+                        risk_free_rate,
+                        dividend_yield,
+                        sigma,
+                        OptionType::Put,
+                    );
+                    Some(GreeksData {
+                        delta: g.delta,
+                        gamma: g.gamma,
+                        theta: g.theta,
+                        theta_per_day: g.theta / 365.0,
+                        vega: g.vega,
+                        rho: g.rho,
+                        theoretical_price: g.price,
+                    })
+                } else {
+                    None
+                };
+
+                if !self.delta_in_range(request, greeks.as_ref()) { continue; }
+
+                puts.push(OptionContractData {
+                    strike,
+                    bid: quote.b,
+                    ask: quote.a,
+                    mid,
+                    spread_pct,
+                    last: quote.l,
+                    volume: quote.v,
+                    open_interest: quote.oi,
+                    implied_volatility: iv,
+                    greeks: greeks.filter(|_| include_greeks),
+                });
+            }
+
+            // Sort by strike price
+            calls.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap());
+            puts.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap());
+
+            expirations.insert(expiry_str.clone(), ExpirationData {
+                expiration_date: expiry_str,
+                days_to_expiry,
+                trading_days_to_expiry,
+                calls,
+                puts,
+            });
+        }
+
+        let greeks_params = if include_greeks {
+            Some(GreeksParams {
+                volatility,
+                volatility_source: volatility_source.to_string(),
+                risk_free_rate,
+                pricing_model: model.name().to_string(),
+                dividend_yield,
+            })
+        } else {
+            None
+        };
+
+        Ok(OptionsChainResponse {
+            symbol: request.ticker.clone(),
+            underlying_price,
+            expirations,
+            greeks_params,
+        })
+    }
+
+    fn moneyness_in_range(&self, request: &OptionsChainRequest, strike: f64, underlying_price: f64) -> bool {
+        if underlying_price <= 0.0 {
+            return true;
+        }
+        let moneyness = strike / underlying_price;
+        request.min_moneyness.map_or(true, |min| moneyness >= min)
+            && request.max_moneyness.map_or(true, |max| moneyness <= max)
+    }
+
+    fn spread_in_range(&self, request: &OptionsChainRequest, mid: f64, spread_pct: f64) -> bool {
+        match request.max_spread_pct {
+            Some(max) => mid > 0.0 && spread_pct <= max,
+            None => true,
+        }
+    }
+
+    fn delta_in_range(&self, request: &OptionsChainRequest, greeks: Option<&GreeksData>) -> bool {
+        let delta = match greeks {
+            Some(g) => g.delta.abs(),
+            None => return true,
+        };
+        request.min_delta.map_or(true, |min| delta >= min)
+            && request.max_delta.map_or(true, |max| delta <= max)
+    }
+
+    fn extract_current_price(&self, chart_data: &ChartResponse) -> Result<f64, ApiError> {
+        chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.get(0))
+            .map(|result| result.meta.regularMarketPrice)
+            .ok_or_else(|| ApiError::DataNotFound("No price data found".to_string()))
+    }
+
+    fn extract_quote_from_data(&self, chart_data: ChartResponse) -> Result<Quote, ApiError> {
+        let result = chart_data.chart.result
+            .as_ref()
+            .and_then(|results| results.get(0))
+            .ok_or_else(|| ApiError::DataNotFound("No quote data found".to_string()))?;
+
+        let candles = to_candles(result);
+        let latest_candle = candles.last()
+            .ok_or_else(|| ApiError::DataNotFound("No candle data found".to_string()))?;
+
+        // Calculate change from previous close
+        let prev_close = result.meta.chartPreviousClose;
+        let current_price = result.meta.regularMarketPrice;
+        let change = current_price - prev_close;
+        let change_percent = (change / prev_close) * 100.0;
+
+        // Extended hours: pre-market moves are measured against the previous regular close,
+        // post-market moves against today's regular close
+        let (pre_market_price, post_market_price) = extended_hours_prices(result, &candles);
+
+        Ok(Quote {
+            symbol: result.meta.symbol.clone(),
+            price: current_price,
+            change,
+            change_percent,
+            volume: result.meta.regularMarketVolume,
+            bid: None, // Not available in this data
+            ask: None,
+            bid_size: None,
+            ask_size: None,
+            // Without the meta range, the fetched bars are the best bound available here;
+            // get_enriched_quote replaces it with a real 52-week range
+            high_52w: result.meta.fiftyTwoWeekHigh
+                .unwrap_or_else(|| candles.iter().map(|c| c.high).fold(current_price, f64::max)),
+            low_52w: result.meta.fiftyTwoWeekLow
+                .unwrap_or_else(|| candles.iter().map(|c| c.low).fold(current_price, f64::min)),
+            market_cap: None,
+            pe_ratio: None,
+            dividend_yield: None,
+            pre_market_price,
+            pre_market_change: pre_market_price.map(|p| p - prev_close),
+            post_market_price,
+            post_market_change: post_market_price.map(|p| p - current_price),
+            last_updated: Utc::now().to_rfc3339(),
+        })
+    }
+
+    fn calculate_portfolio_analysis(
+        &self,
+        pnl_curves: &[Vec<PnLPoint>],
+        underlying_prices: &[f64],
+        total_greeks: GreeksData,
+        pnl_curves_by_date: Vec<DatedPnLCurve>,
+    ) -> PortfolioAnalysis {
+        let mut total_pnl_curve = Vec::new();
+        
+        // Calculate total P&L at each price point
+        for (i, &price) in underlying_prices.iter().enumerate() {
+            let total_pnl: f64 = pnl_curves.iter()
+                .map(|curve| curve.get(i).map_or(0.0, |point| point.pnl))
+                .sum();
+            
+            let total_value: f64 = pnl_curves.iter()
+                .map(|curve| curve.get(i).map_or(0.0, |point| point.total_value))
+                .sum();
+
+            total_pnl_curve.push(PnLPoint {
+                underlying_price: price,
+                pnl: total_pnl,
+                total_value,
+            });
+        }
+
+        let break_even_points = break_even_points(&total_pnl_curve);
+
+        // Find max profit and max loss
+        let max_profit = total_pnl_curve.iter()
+            .map(|point| point.pnl)
+            .fold(f64::NEG_INFINITY, f64::max);
+        
+        let max_loss = total_pnl_curve.iter()
+            .map(|point| point.pnl)
+            .fold(f64::INFINITY, f64::min);
+
+        PortfolioAnalysis {
+            total_greeks,
+            total_pnl_curve,
+            break_even_points,
+            max_profit: if max_profit.is_finite() { Some(max_profit) } else { None },
+            max_loss: if max_loss.is_finite() { Some(max_loss) } else { None },
+            pnl_curves_by_date,
+        }
+    }
+
+    // Screener implementation. The universe comes from `tickers`, a predefined Yahoo screener, or
+    // Yahoo's custom screener (which applies the quote filters itself). Quote filters are evaluated
+    // locally for the first two. When `indicators` are given, the survivors' daily candles are
+    // fetched and the indicator filters run on each indicator's latest value. Yahoo universes are
+    // capped at SCREENER_UNIVERSE_SIZE quotes, then filtered, sorted and paged locally; only when
+    // nothing is left to filter or sort locally is offset/limit passed through to Yahoo instead.
+    pub async fn screen_stocks(&self, request: ScreenerRequest) -> Result<ScreenerResponse, ApiError> {
+        let runner = request.indicators.as_deref().map(|configs| self.runner_from_configs(configs)).transpose()?;
+        let labels: Vec<&str> = runner.iter()
+            .flat_map(|r| r.indicators.iter().map(|(label, _)| label.as_str()))
+            .collect();
+        for field in request.filters.iter().flat_map(|f| f.fields()) {
+            if !SCREENER_QUOTE_FIELDS.contains(&field) && !labels.contains(&field) {
+                return Err(ApiError::InvalidParameters(format!(
+                    "Unknown screener field {}; indicator fields must match a requested indicator label such as rsi(period=14)",
+                    field
+                )));
+            }
+        }
+
+        let (indicator_filters, quote_filters): (Vec<ScreenerFilter>, Vec<ScreenerFilter>) =
+            request.filters.iter().cloned().partition(|f| f.uses_indicators());
+        let concurrency = request.concurrency
+            .unwrap_or(SCREENER_DEFAULT_CONCURRENCY)
+            .clamp(1, SCREENER_MAX_CONCURRENCY);
+
+        let predefined = request.screener_type.as_deref() == Some("predefined");
+        if request.tickers.is_none() && runner.is_none() && request.sort_by.is_none() && (!predefined || quote_filters.is_empty()) {
+            let offset = request.offset.unwrap_or(0);
+            let count = request.limit.map_or(SCREENER_UNIVERSE_SIZE, |limit| (limit as u32).clamp(1, SCREENER_UNIVERSE_SIZE));
+            let (mut results, total) = self.screener_universe_from_yahoo(&request, &quote_filters, count, offset as u32).await?;
+            let total_count = total.unwrap_or(offset + results.len());
+            results.truncate(request.limit.unwrap_or(usize::MAX));
+            return Ok(ScreenerResponse { results, total_count });
+        }
+
+        let (universe, filter_locally) = match &request.tickers {
+            Some(tickers) => (self.screener_universe_from_tickers(tickers, concurrency).await, true),
+            None => (self.screener_universe_from_yahoo(&request, &quote_filters, SCREENER_UNIVERSE_SIZE, 0).await?.0, predefined),
+        };
+
+        let mut results = Vec::with_capacity(universe.len());
+        for result in universe {
+            if !filter_locally || matches_all(&quote_filters, &result)? {
+                results.push(result);
+            }
+        }
+
+        if let Some(runner) = runner {
+            self.attach_screener_indicators(&mut results, runner, concurrency).await?;
+            let candidates = std::mem::take(&mut results);
+            for result in candidates {
+                if matches_all(&indicator_filters, &result)? {
+                    results.push(result);
+                }
+            }
+        }
+        let total_count = results.len();
+
+        if let Some(sort_field) = &request.sort_by {
+            let ascending = request.sort_order.as_deref() != Some("desc");
+            self.sort_screener_results(&mut results, sort_field, ascending)?;
+        }
+
+        let offset = request.offset.unwrap_or(0).min(results.len());
+        let mut results = results.split_off(offset);
+        if let Some(limit) = request.limit {
+            results.truncate(limit);
+        }
+
+        Ok(ScreenerResponse {
+            results,
+            total_count,
+        })
+    }
+
+    async fn screener_universe_from_tickers(&self, tickers: &[String], concurrency: usize) -> Vec<ScreenerResult> {
+        // Owned symbols: a closure over borrowed ones makes the future unusable from spawned jobs
+        let symbols: Vec<String> = tickers.iter().map(|t| t.trim().to_uppercase()).collect();
+        let quotes: Vec<(String, Result<Quote, ApiError>)> = stream::iter(symbols)
+            .map(|symbol| async move {
+                let quote = self.get_enriched_quote(&symbol).await;
+                (symbol, quote)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let mut results = Vec::new();
+        for (symbol, quote) in quotes {
+            match quote {
+                Ok(quote) => results.push(ScreenerResult {
+                    name: quote.symbol.clone(),
+                    symbol: quote.symbol,
+                    price: quote.price,
+                    change: quote.change,
+                    change_percent: quote.change_percent,
+                    volume: quote.volume,
+                    market_cap: quote.market_cap,
+                    pe_ratio: quote.pe_ratio,
+                    indicators: None,
+                }),
+                Err(e) => warn!(%symbol, error = %e, "Screener skipping symbol"),
+            }
+        }
+        results
+    }
+
+    // Fills each result's `indicators` with the latest value of every indicator the runner holds.
+    // A symbol whose candles can't be fetched gets an empty map and so fails every indicator filter.
+    async fn attach_screener_indicators(
+        &self,
+        results: &mut [ScreenerResult],
+        runner: IndicatorRunner,
+        concurrency: usize,
+    ) -> Result<(), ApiError> {
+        let symbols: Vec<String> = results.iter().map(|result| result.symbol.clone()).collect();
+        let latest = self.latest_indicator_values(&symbols, runner, concurrency).await?;
+        for (result, indicators) in results.iter_mut().zip(latest) {
+            result.indicators = Some(indicators);
+        }
+        Ok(())
+    }
+
+    // Latest value of every indicator the runner holds, per symbol, on a year of daily bars.
+    // Candles are fetched `concurrency` symbols at a time; a symbol without candles gets an empty map.
+    async fn latest_indicator_values(
+        &self,
+        symbols: &[String],
+        runner: IndicatorRunner,
+        concurrency: usize,
+    ) -> Result<Vec<HashMap<String, f64>>, ApiError> {
+        let options = ChartQueryOptions {
+            interval: "1d",
+            range: SCREENER_INDICATOR_RANGE,
+            include_pre_post: false,
+            period: None,
+        };
+
+        let series: Vec<Vec<Candle>> = stream::iter(symbols.to_vec())
+            .map(|symbol| {
+                let options = &options;
+                async move {
+                    let candles = match self.fetch_history(&symbol, options).await {
+                        Ok(chart_data) => self.extract_candles(&chart_data),
+                        Err(e) => Err(e),
+                    };
+                    candles.unwrap_or_else(|e| {
+                        warn!(%symbol, error = %e, "No candles for watchlist indicators");
+                        Vec::new()
+                    })
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        self.run_indicators_with_timeout(runner, move |runner| {
+            let slices: Vec<&[Candle]> = series.iter().map(Vec::as_slice).collect();
+            runner.run_many(&slices)
+                .into_iter()
+                .map(|values| {
+                    values.into_iter()
+                        .filter_map(|(name, values)| values.iter().rev().find_map(|v| *v).map(|v| (name, v)))
+                        .collect::<HashMap<String, f64>>()
+                })
+                .collect::<Vec<_>>()
+        }).await
+    }
+
+    // Quotes for every member of a watchlist plus the latest value of the indicators in `indicators`
+    // (same spec as /api/v1/historical, e.g. "rsi(period=14),sma(period=50)"); the default
+    // indicator set when None, none at all when empty
+    pub async fn get_watchlist_quotes(&self, id: &str, indicators: Option<&str>) -> Result<WatchlistQuotesResponse, ApiError> {
+        let id = id.to_string();
+        let watchlist = self.with_portfolios(move |p| p.get_watchlist(&id)).await?;
+        if watchlist.symbols.is_empty() {
+            return Ok(WatchlistQuotesResponse { watchlist, members: Vec::new(), errors: Vec::new() });
+        }
+        let QuoteResponse { mut quotes, errors } = self.quotes(QuoteRequest {
+            tickers: watchlist.symbols.clone(),
+            fields: None,
+            include_prepost: None,
+        }).await?;
+
+        let runner = match indicators.map(str::trim) {
+            Some("") => None,
+            Some(spec) => Some(self.runner_from_configs(&indicator_configs(spec))?),
+            None => Some(self.indicator_runner.clone()),
+        };
+        let mut latest = match runner {
+            Some(runner) => self.latest_indicator_values(&watchlist.symbols, runner, WATCHLIST_INDICATOR_CONCURRENCY).await?,
+            None => Vec::new(),
+        }
+        .into_iter();
+
+        let members = watchlist.symbols.iter()
+            .map(|symbol| WatchlistMember {
+                symbol: symbol.clone(),
+                quote: quotes.remove(symbol),
+                indicators: latest.next().unwrap_or_default(),
+            })
+            .collect();
+        Ok(WatchlistQuotesResponse { watchlist, members, errors })
+    }
+
+    // `count` quotes from `offset` in Yahoo's own order, with Yahoo's total match count when given.
+    // Custom screeners get the quote filters; sorting is always left to screen_stocks.
+    async fn screener_universe_from_yahoo(
+        &self,
+        request: &ScreenerRequest,
+        quote_filters: &[ScreenerFilter],
+        count: u32,
+        offset: u32,
+    ) -> Result<(Vec<ScreenerResult>, Option<usize>), ApiError> {
+        let mut yahoo_client = self.yahoo_client();
+
+        let yahoo_response = match request.screener_type.as_deref() {
+            Some("predefined") => {
+                let screener_id = request.predefined_screener
+                    .as_deref()
+                    .unwrap_or("most_actives");
+
+                yahoo_client.fetch_predefined_screener_paginated(screener_id, count, offset).await?
+            }
+            _ => {
+                yahoo_client.fetch_custom_screener(quote_filters, None, None, Some(count), Some(offset)).await?
+            }
+        };
+
+        let total = yahoo_response.finance.result.first().and_then(|result| result.total).map(|total| total as usize);
+        let results = yahoo_response.finance.result
+            .iter()
+            .filter_map(|result| result.quotes.as_ref())
+            .flatten()
+            .map(|quote| yahoo_client.convert_yahoo_quote_to_screener_result(quote, None))
+            .collect();
+        Ok((results, total))
+    }
+
+    fn sort_screener_results(
+        &self,
+        results: &mut [ScreenerResult],
+        sort_field: &str,
+        ascending: bool,
+    ) -> Result<(), ApiError> {
+        match sort_field {
+            "symbol" => {
+                results.sort_by(|a, b| {
+                    if ascending {
+                        a.symbol.cmp(&b.symbol)
+                    } else {
+                        b.symbol.cmp(&a.symbol)
+                    }
+                });
+            }
+            "price" => {
+                results.sort_by(|a, b| {
+                    if ascending {
+                        a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal)
+                    } else {
+                        b.price.partial_cmp(&a.price).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                });
+            }
+            "change_percent" => {
+                results.sort_by(|a, b| {
+                    if ascending {
+                        a.change_percent.partial_cmp(&b.change_percent).unwrap_or(std::cmp::Ordering::Equal)
+                    } else {
+                        b.change_percent.partial_cmp(&a.change_percent).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                });
+            }
+            "volume" => {
+                results.sort_by(|a, b| {
+                    if ascending {
+                        a.volume.cmp(&b.volume)
+                    } else {
+                        b.volume.cmp(&a.volume)
+                    }
+                });
+            }
+            "market_cap" => {
+                results.sort_by(|a, b| {
+                    let a_mc = a.market_cap.unwrap_or(0.0);
+                    let b_mc = b.market_cap.unwrap_or(0.0);
+                    if ascending {
+                        a_mc.partial_cmp(&b_mc).unwrap_or(std::cmp::Ordering::Equal)
+                    } else {
+                        b_mc.partial_cmp(&a_mc).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                });
+            }
+            "pe_ratio" => {
+                results.sort_by(|a, b| {
+                    let a_pe = a.pe_ratio.unwrap_or(f64::INFINITY);
+                    let b_pe = b.pe_ratio.unwrap_or(f64::INFINITY);
+                    if ascending {
+                        a_pe.partial_cmp(&b_pe).unwrap_or(std::cmp::Ordering::Equal)
+                    } else {
+                        b_pe.partial_cmp(&a_pe).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                });
+            }
+            // Indicator labels from an indicator screen; results without the value sort last
+            field if results.iter().any(|r| screener_field(r, field).is_some()) => {
+                results.sort_by(|a, b| match (screener_field(a, field), screener_field(b, field)) {
+                    (Some(x), Some(y)) if ascending => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+                    (Some(x), Some(y)) => y.partial_cmp(&x).unwrap_or(std::cmp::Ordering::Equal),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                });
+            }
+            _ => {
+                return Err(ApiError::InvalidParameters(format!("Unknown sort field: {}", sort_field)));
+            }
+        }
+        Ok(())
+    }
+
+    // Market data aggregation
+    pub async fn get_market_summary(&self) -> Result<MarketSummary, ApiError> {
+        // Fetch major indices and market stats
+        let indices = vec!["^GSPC", "^DJI", "^IXIC"]; // S&P 500, Dow, NASDAQ
+        let options = ChartQueryOptions::default();
+        
+        let mut index_data = HashMap::new();
+        for index in &indices {
+            if let Ok(data) = self.fetch_ticker_data(index, &options).await {
+                if let Ok(quote) = self.extract_quote_from_data(data) {
+                    index_data.insert(index.to_string(), quote);
+                }
+            }
+        }
+
+        let status = calendar::market_status(Utc::now());
+        Ok(MarketSummary {
+            indices: index_data,
+            market_status: status.status,
+            next_open: status.next_open,
+            next_close: status.next_close,
+            last_updated: Utc::now().to_rfc3339(),
+        })
+    }
+
+    // `modules` is a comma-separated subset of QUOTE_SUMMARY_MODULES, in Yahoo's camelCase or
+    // snake_case (summary_detail); None fetches them all
+    pub async fn get_quote_summary(&self, ticker: &str, modules: Option<&str>) -> Result<QuoteSummaryResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let selected: Vec<&str> = match modules {
+            Some(list) => list.split(',')
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(|m| {
+                    let wanted = m.replace('_', "").to_lowercase();
+                    QUOTE_SUMMARY_MODULES.iter().copied()
+                        .find(|module| module.to_lowercase() == wanted)
+                        .ok_or_else(|| ApiError::InvalidParameters(format!(
+                            "Unknown quoteSummary module {}. Valid modules: {}", m, QUOTE_SUMMARY_MODULES.join(", ")
+                        )))
+                })
+                .collect::<Result<_, _>>()?,
+            None => QUOTE_SUMMARY_MODULES.to_vec(),
+        };
+        if selected.is_empty() {
+            return Err(ApiError::InvalidParameters("modules must name at least one module".to_string()));
+        }
+        let mut yahoo_client = self.yahoo_client();
+        yahoo_client.fetch_quote_summary(ticker, &selected).await
+    }
+
+    pub async fn get_news(&self, ticker: &str, count: Option<u32>) -> Result<NewsResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let mut yahoo_client = self.yahoo_client();
+        yahoo_client.fetch_news(ticker, count).await
+    }
+
+    pub async fn cache_stats(&self) -> CacheStatsResponse {
+        CacheStatsResponse {
+            responses: self.response_cache.stats(),
+            fundamentals_entries: self.fundamentals_cache.read().await.len(),
+        }
+    }
+
+    // Empties the in-memory caches; the on-disk candle cache is left alone
+    pub async fn clear_cache(&self) -> CacheClearResponse {
+        let mut fundamentals = self.fundamentals_cache.write().await;
+        let fundamentals_cleared = fundamentals.len();
+        fundamentals.clear();
+        CacheClearResponse { responses_cleared: self.response_cache.clear(), fundamentals_cleared }
+    }
+
+    pub async fn search_symbols(&self, query: &str, limit: Option<u32>) -> Result<SearchResponse, ApiError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(ApiError::InvalidParameters("Search needs a non-empty q".to_string()));
+        }
+        let limit = limit.unwrap_or(SEARCH_DEFAULT_LIMIT).clamp(1, SEARCH_MAX_LIMIT);
+
+        let mut yahoo_client = self.yahoo_client();
+        let results = yahoo_client.fetch_symbol_search(query, limit).await?;
+        Ok(SearchResponse { query: query.to_string(), results })
+    }
+
+    pub async fn get_calendar(&self, request: CalendarRequest) -> Result<CalendarResponse, ApiError> {
+        let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::InvalidDateRange(format!("{} is not a YYYY-MM-DD date", date)));
+        let from = match &request.from {
+            Some(from) => parse(from)?,
+            None => Utc::now().date_naive(),
+        };
+        let to = match &request.to {
+            Some(to) => parse(to)?,
+            None => from + chrono::Duration::days(CALENDAR_DEFAULT_DAYS),
+        };
+        if to < from {
+            return Err(ApiError::InvalidDateRange("to must not be before from".to_string()));
+        }
+        let mut yahoo_client = self.yahoo_client();
+        yahoo_client.fetch_calendar(from, to, request.tickers.as_deref()).await
+    }
+
+    pub async fn get_reports(&self, ticker: &str) -> Result<ReportsResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let mut yahoo_client = self.yahoo_client();
+        yahoo_client.fetch_reports(ticker).await
+    }
+
+    pub async fn get_analysis(&self, ticker: &str) -> Result<AnalysisReports, ApiError> {
+        validation::ticker(ticker)?;
+        let mut yahoo_client = self.yahoo_client();
+        yahoo_client.fetch_analysis(ticker).await
+    }
+
+    // `period` is annual (default) or quarterly
+    pub async fn get_fundamentals(&self, ticker: &str, period: Option<&str>) -> Result<FundamentalsResponse, ApiError> {
+        validation::ticker(ticker)?;
+        let period = match period {
+            Some(name) => StatementPeriod::parse(name)
+                .ok_or_else(|| ApiError::InvalidParameters(format!("Unknown period {}; use annual or quarterly", name)))?,
+            None => StatementPeriod::Annual,
+        };
+        let mut yahoo_client = self.yahoo_client();
+        let (financials, currency) = yahoo_client.fetch_financial_statements(ticker, period).await?;
+        Ok(FundamentalsResponse {
+            symbol: ticker.to_string(),
+            period_type: period.as_str().to_string(),
+            currency,
+            financials,
+        })
+    }
+}
+
+fn confluence_bias(score: Option<f64>) -> String {
+    match score {
+        Some(s) if s > 0.33 => "bullish",
+        Some(s) if s < -0.33 => "bearish",
+        Some(_) => "neutral",
+        None => "insufficient_data",
+    }.to_string()
+}
+
+fn is_intraday(interval: &str) -> bool {
+    matches!(interval, "1m" | "2m" | "5m" | "15m" | "30m" | "60m" | "90m" | "1h")
+}
+
+// Yahoo only serves intraday bars for recent history and rejects longer ranges with an opaque error,
+// so check the combination up front: 1m up to 5d, other minute bars up to 1mo, hourly up to 2y.
+pub fn validate_range_interval(range: &str, interval: &str) -> Result<(), ApiError> {
+    if !VALID_RANGES.contains(&range) {
+        return Err(ApiError::InvalidParameters(format!(
+            "Invalid range '{}'. Valid ranges: {}", range, VALID_RANGES.join(", ")
+        )));
+    }
+
+    let allowed: &[&str] = match interval {
+        "1m" => &["1d", "5d"],
+        "2m" | "5m" | "15m" | "30m" | "90m" => &["1d", "5d", "1mo"],
+        "60m" | "1h" => &["1d", "5d", "1mo", "3mo", "6mo", "1y", "2y", "ytd"],
+        "1d" | "5d" | "1wk" | "1mo" | "3mo" => &VALID_RANGES,
+        _ => {
+            return Err(ApiError::InvalidParameters(format!(
+                "Invalid interval '{}'. Valid intervals: {}", interval, VALID_INTERVALS.join(", ")
+            )));
+        }
+    };
+
+    if allowed.contains(&range) {
+        Ok(())
+    } else {
+        Err(ApiError::InvalidParameters(format!(
+            "Range '{}' is not available for interval '{}'. Valid ranges for {}: {}",
+            range, interval, interval, allowed.join(", ")
+        )))
+    }
+}
+
+// `resample_to` parsed and checked against the fetched interval: it has to be coarser and built
+// from whole fetched bars (1h from 5m, 1wk from 1d; not 1h from 90m or 1mo from 1wk)
+pub fn resample_target<'a>(interval: &str, resample_to: Option<&'a str>) -> Result<Option<(Resolution, &'a str)>, ApiError> {
+    let Some(target) = resample_to.filter(|t| *t != interval) else {
+        return Ok(None);
+    };
+    let invalid = |reason: &str| ApiError::InvalidParameters(format!("Cannot resample {} bars to {}: {}", interval, target, reason));
+    let resolution = Resolution::parse(target).ok_or_else(|| invalid("unknown interval"))?;
+    let fetched = Resolution::parse(interval).ok_or_else(|| invalid("unknown source interval"))?;
+    if resolution.approx_seconds() <= fetched.approx_seconds() {
+        return Err(invalid("the target must be coarser"));
+    }
+    if !resolution.is_multiple_of(&fetched) {
+        return Err(invalid("the target is not a whole number of source bars"));
+    }
+    Ok(Some((resolution, target)))
+}
+
+// How fetched bars become the series that is returned and fed to the indicators
+struct SeriesOptions {
+    adjust: bool,
+    missing: MissingData,
+    transform: Option<CandleTransform>,
+}
+
+impl SeriesOptions {
+    fn from_request(request: &HistoricalDataRequest) -> Result<Self, ApiError> {
+        Ok(Self {
+            adjust: request.adjust.unwrap_or(false),
+            missing: missing_data_policy(request.missing_data.as_deref())?,
+            transform: candle_transform(request.transform.as_deref(), request.brick_size)?,
+        })
+    }
+
+    // Adjusted closes with null bars dropped, for return-based analytics
+    fn adjusted() -> Self {
+        Self { adjust: true, missing: MissingData::Drop, transform: None }
+    }
+}
+
+pub fn candle_transform(name: Option<&str>, brick_size: Option<f64>) -> Result<Option<CandleTransform>, ApiError> {
+    match name.map(str::to_lowercase).as_deref() {
+        None | Some("none") => Ok(None),
+        Some("heikin_ashi") => Ok(Some(CandleTransform::HeikinAshi)),
+        Some("renko") => match brick_size {
+            Some(size) if !size.is_finite() || size <= 0.0 => Err(ApiError::InvalidParameters(format!(
+                "Invalid brick_size {}: must be a positive price", size
+            ))),
+            _ => Ok(Some(CandleTransform::Renko { brick_size })),
+        },
+        Some(other) => Err(ApiError::InvalidParameters(format!(
+            "Invalid transform '{}'. Valid transforms: heikin_ashi, renko", other
+        ))),
+    }
+}
+
+pub fn missing_data_policy(name: Option<&str>) -> Result<MissingData, ApiError> {
+    match name {
+        None => Ok(MissingData::Drop),
+        Some(name) => MissingData::from_name(name).ok_or_else(|| ApiError::InvalidParameters(format!(
+            "Invalid missing_data '{}'. Valid policies: drop, ffill, interpolate, nan", name
+        ))),
+    }
+}
+
+// Rules default to rsi(14,30,70), macd(12,26,9) and price_sma(50), weighted equally
+pub fn signal_model(rules: Option<&str>, weights: Option<&str>, threshold: Option<f64>) -> Result<SignalModel, ApiError> {
+    let mut model = SignalModel::default();
+    if let Some(rules) = rules {
+        let rules = SignalRule::parse_list(rules).map_err(ApiError::InvalidParameters)?;
+        if rules.is_empty() {
+            return Err(ApiError::InvalidParameters("rules must name at least one signal rule".to_string()));
+        }
+        model.rules = rules.into_iter().map(|rule| (rule, 1.0)).collect();
+    }
+    if let Some(weights) = weights {
+        let weights: Vec<f64> = weights.split(',')
+            .map(|w| w.trim().parse::<f64>().ok().filter(|w| w.is_finite() && *w >= 0.0))
+            .collect::<Option<_>>()
+            .ok_or_else(|| ApiError::InvalidParameters(format!("Invalid weights '{}': expected non-negative numbers", weights)))?;
+        if weights.len() != model.rules.len() {
+            return Err(ApiError::InvalidParameters(format!(
+                "Got {} weights for {} rules", weights.len(), model.rules.len()
+            )));
+        }
+        for ((_, weight), w) in model.rules.iter_mut().zip(weights) {
+            *weight = w;
+        }
+    }
+    if let Some(threshold) = threshold {
+        if !(0.0..=1.0).contains(&threshold) || threshold == 0.0 {
+            return Err(ApiError::InvalidParameters(format!("threshold must be in (0, 1], got {}", threshold)));
+        }
+        model.threshold = threshold;
+    }
+    Ok(model)
+}
+
+// Parses YYYY-MM-DD start/end dates into a [period1, period2) window in unix seconds. The end date
+// is inclusive (its whole day is covered) and defaults to now; None when no start date is given.
+pub fn date_window(start_date: Option<&str>, end_date: Option<&str>, now: DateTime<Utc>) -> Result<Option<(i64, i64)>, ApiError> {
+    let parse = |name: &str, value: &str| {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
+            .map_err(|_| ApiError::InvalidDateRange(format!("Invalid {} '{}': expected YYYY-MM-DD", name, value)))
+    };
+
+    let Some(start_date) = start_date else {
+        if end_date.is_some() {
+            return Err(ApiError::InvalidDateRange("end_date requires start_date".to_string()));
+        }
+        return Ok(None);
+    };
+
+    let start = parse("start_date", start_date)?;
+    let end = match end_date {
+        Some(end_date) => (parse("end_date", end_date)? + 86_400).min(now.timestamp()),
+        None => now.timestamp(),
+    };
+
+    if start >= now.timestamp() {
+        return Err(ApiError::InvalidDateRange(format!("start_date {} is in the future", start_date)));
+    }
+    if start >= end {
+        return Err(ApiError::InvalidDateRange(format!(
+            "start_date {} must be before end_date {}", start_date, end_date.unwrap_or_default()
+        )));
+    }
+    Ok(Some((start, end)))
+}
+
+// Date-window counterpart of validate_range_interval: intraday bars only exist for recent history
+// (1m for 30 days, other minute bars for 60, hourly for 730), measured from today
+pub fn validate_window_interval(start: i64, interval: &str, now: i64) -> Result<(), ApiError> {
+    let lookback_days: Option<i64> = match interval {
+        "1m" => Some(30),
+        "2m" | "5m" | "15m" | "30m" | "90m" => Some(60),
+        "60m" | "1h" => Some(730),
+        "1d" | "5d" | "1wk" | "1mo" | "3mo" => None,
+        _ => {
+            return Err(ApiError::InvalidParameters(format!(
+                "Invalid interval '{}'. Valid intervals: {}", interval, VALID_INTERVALS.join(", ")
+            )));
+        }
+    };
+
+    match lookback_days {
+        Some(days) if start < now - days * 86_400 => Err(ApiError::InvalidDateRange(format!(
+            "Interval '{}' only reaches back {} days; choose a later start_date or a daily interval", interval, days
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Mid price and bid/ask spread as a percent of the mid; (0, 0) when there is no two-sided quote
+// Prices where the P&L curve crosses zero, interpolated between neighbouring points
+fn break_even_points(curve: &[PnLPoint]) -> Vec<f64> {
+    let mut points = Vec::new();
+    for pair in curve.windows(2) {
+        let (prev, curr) = (&pair[0], &pair[1]);
+        if (prev.pnl <= 0.0 && curr.pnl >= 0.0) || (prev.pnl >= 0.0 && curr.pnl <= 0.0) {
+            let ratio = prev.pnl.abs() / (prev.pnl.abs() + curr.pnl.abs());
+            points.push(prev.underlying_price + ratio * (curr.underlying_price - prev.underlying_price));
+        }
+    }
+    points
+}
+
+fn total_interest(strikes: &[(f64, oi_profile::StrikeInterest)]) -> oi_profile::StrikeInterest {
+    strikes.iter().fold(oi_profile::StrikeInterest::default(), |mut total, (_, oi)| {
+        total.call_open_interest += oi.call_open_interest;
+        total.put_open_interest += oi.put_open_interest;
+        total.call_volume += oi.call_volume;
+        total.put_volume += oi.put_volume;
+        total
+    })
+}
+
+fn strike_open_interest((strike, oi): (f64, oi_profile::StrikeInterest)) -> StrikeOpenInterest {
+    StrikeOpenInterest {
+        strike,
+        call_open_interest: oi.call_open_interest,
+        put_open_interest: oi.put_open_interest,
+        call_volume: oi.call_volume,
+        put_volume: oi.put_volume,
+    }
+}
+
+// A quoteSummary number, which is either {"raw": ..., "fmt": ...} or bare
+fn raw_f64(module: &serde_json::Value, key: &str) -> Option<f64> {
+    let value = module.get(key)?;
+    value.get("raw").unwrap_or(value).as_f64()
+}
+
+// YYYY-MM-DD from a quoteSummary date ({"raw": unix seconds, ...})
+fn raw_date(value: &serde_json::Value) -> Option<String> {
+    let ts = value.get("raw").unwrap_or(value).as_i64()?;
+    Some(Utc.timestamp_opt(ts, 0).single()?.format("%Y-%m-%d").to_string())
+}
+
+// YYYY-MM-DD from a visualization date: an ISO timestamp string or epoch milliseconds
+fn calendar_date(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(text) => text.get(..10).filter(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok()).map(String::from),
+        serde_json::Value::Number(ms) => Some(Utc.timestamp_millis_opt(ms.as_i64()?).single()?.format("%Y-%m-%d").to_string()),
+        _ => None,
+    }
+}
+
+fn bid_ask_mid_spread(bid: f64, ask: f64) -> (f64, f64) {
+    let mid = (bid + ask) / 2.0;
+    if mid <= 0.0 || ask < bid {
+        return (mid.max(0.0), 0.0);
+    }
+    (mid, (ask - bid) / mid * 100.0)
+}
+
+// Defaults to Black-Scholes so existing callers keep European pricing
+fn parse_pricing_model(name: Option<&str>) -> Result<PricingModel, ApiError> {
+    match name {
+        None => Ok(PricingModel::BlackScholes),
+        Some(name) => PricingModel::parse(name)
+            .ok_or_else(|| ApiError::InvalidParameters(format!("Unknown pricing model: {}", name))),
+    }
+}
+
+// US equity options stop trading at 4pm ET; 20:00 UTC during daylight time, close enough in winter
+const OPTIONS_EXPIRY_UTC_HOUR: u32 = 20;
+
+// Fractional calendar days from `now` until the close on a YYYY-MM-DD expiry, plus the number of
+// weekday sessions left, counting today. Exchange holidays aren't excluded. None for past or
+// unparseable dates
+fn days_to_expiry(expiry: &str, now: DateTime<Utc>) -> Option<(f64, u32)> {
+    let expiry = NaiveDate::parse_from_str(expiry, "%Y-%m-%d").ok()?;
+    let close = expiry.and_hms_opt(OPTIONS_EXPIRY_UTC_HOUR, 0, 0)?.and_utc();
+    let seconds = (close - now).num_seconds();
+    if seconds <= 0 {
+        return None;
+    }
+
+    let trading_days = now.date_naive()
+        .iter_days()
+        .take_while(|day| *day <= expiry)
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+        .count() as u32;
+
+    Some((seconds as f64 / 86_400.0, trading_days))
+}
 
-        // Process the results
-        let mut results = Vec::new();
-        let total_count = yahoo_response.finance.result.len();
-
-        for result in &yahoo_response.finance.result {
-            if let Some(quotes) = &result.quotes {
-                for quote in quotes {
-                    // Calculate indicators if requested
-                    let indicators = if request.indicators.is_some() {
-                        // Would fetch historical data and calculate indicators
-                        // For now, return None to keep response times reasonable
-                        None
-                    } else {
-                        None
-                    };
+// Latest pre- and post-market prices from bars inside the current trading periods
+fn extended_hours_prices(result: &ResultItem, candles: &[Candle]) -> (Option<f64>, Option<f64>) {
+    if !result.meta.hasPrePostMarketData || !result.meta.asset_class().has_extended_hours() {
+        return (None, None);
+    }
 
-                    let screener_result = yahoo_client.convert_yahoo_quote_to_screener_result(quote, indicators);
-                    results.push(screener_result);
-                }
-            }
-        }
+    let last_close_in = |period: &TradingPeriod| {
+        candles.iter()
+            .rev()
+            .find(|c| c.timestamp >= period.start as i64 && c.timestamp < period.end as i64)
+            .map(|c| c.close)
+    };
 
-        // Apply additional sorting if specified
-        if let Some(sort_field) = &request.sort_by {
-            let ascending = request.sort_order.as_deref() != Some("desc");
-            self.sort_screener_results(&mut results, sort_field, ascending)?;
-        }
+    let periods = &result.meta.currentTradingPeriod;
+    (last_close_in(&periods.pre), last_close_in(&periods.post))
+}
 
-        // Apply limit after sorting
-        if let Some(limit) = request.limit {
-            results.truncate(limit);
-        }
+// HTTP Server traits (you can implement with your preferred web framework)
+pub trait ApiServer {
+    fn start(&self, port: u16) -> Result<(), Box<dyn Error>>;
+}
 
-        Ok(ScreenerResponse {
-            results,
-            total_count,
-        })
+// Query-string parsing shared by the hand-rolled and axum servers so both accept the same parameters
+
+pub fn historical_request_from_query(query: &HashMap<String, String>) -> HistoricalDataRequest {
+    let tickers = query.get("tickers")
+        .map(|t| t.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_else(|| vec!["AAPL".to_string()]);
+
+    HistoricalDataRequest {
+        tickers,
+        interval: query.get("interval").cloned(),
+        range: query.get("range").cloned(),
+        start_date: query.get("start_date").cloned(),
+        end_date: query.get("end_date").cloned(),
+        include_indicators: query.get("include_indicators").map(|v| v == "true"),
+        indicators: query.get("indicators").map(|spec| indicator_configs(spec)),
+        parallel_indicators: query.get("parallel_indicators").map(|v| v == "true"),
+        min_candles: query.get("min_candles").and_then(|v| v.parse().ok()),
+        include_returns: query.get("include_returns").map(|v| v == "true"),
+        benchmark: query.get("benchmark").cloned(),
+        adjust: query.get("adjust").map(|v| v == "true"),
+        resample_to: query.get("resample_to").cloned(),
+        missing_data: query.get("missing_data").cloned(),
+        transform: query.get("transform").cloned(),
+        brick_size: query.get("brick_size").and_then(|v| v.parse().ok()),
+        include_prepost: query.get("include_prepost").map(|v| v == "true"),
+        limit: query.get("limit").and_then(|v| v.parse().ok()),
+        cursor: query.get("cursor").cloned(),
     }
+}
 
-    fn sort_screener_results(
-        &self,
-        results: &mut [ScreenerResult],
-        sort_field: &str,
-        ascending: bool,
-    ) -> Result<(), ApiError> {
-        match sort_field {
-            "symbol" => {
-                results.sort_by(|a, b| {
-                    if ascending {
-                        a.symbol.cmp(&b.symbol)
-                    } else {
-                        b.symbol.cmp(&a.symbol)
-                    }
-                });
-            }
-            "price" => {
-                results.sort_by(|a, b| {
-                    if ascending {
-                        a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b.price.partial_cmp(&a.price).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            "change_percent" => {
-                results.sort_by(|a, b| {
-                    if ascending {
-                        a.change_percent.partial_cmp(&b.change_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b.change_percent.partial_cmp(&a.change_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            "volume" => {
-                results.sort_by(|a, b| {
-                    if ascending {
-                        a.volume.cmp(&b.volume)
-                    } else {
-                        b.volume.cmp(&a.volume)
-                    }
-                });
-            }
-            "market_cap" => {
-                results.sort_by(|a, b| {
-                    let a_mc = a.market_cap.unwrap_or(0.0);
-                    let b_mc = b.market_cap.unwrap_or(0.0);
-                    if ascending {
-                        a_mc.partial_cmp(&b_mc).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b_mc.partial_cmp(&a_mc).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            "pe_ratio" => {
-                results.sort_by(|a, b| {
-                    let a_pe = a.pe_ratio.unwrap_or(f64::INFINITY);
-                    let b_pe = b.pe_ratio.unwrap_or(f64::INFINITY);
-                    if ascending {
-                        a_pe.partial_cmp(&b_pe).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b_pe.partial_cmp(&a_pe).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            _ => {
-                return Err(ApiError::InvalidParameters(format!("Unknown sort field: {}", sort_field)));
-            }
+// fields=timestamp,close,volume keeps only those CandleData fields in JSON responses
+pub fn candle_fields_from_query(query: &HashMap<String, String>) -> Result<Option<Vec<String>>, ApiError> {
+    let Some(spec) = query.get("fields") else {
+        return Ok(None);
+    };
+    let fields: Vec<String> = spec.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect();
+    if let Some(unknown) = fields.iter().find(|f| !CANDLE_FIELDS.contains(&f.as_str())) {
+        return Err(ApiError::InvalidParameters(format!(
+            "Unknown candle field '{}'. Valid fields: {}", unknown, CANDLE_FIELDS.join(", ")
+        )));
+    }
+    Ok(Some(fields))
+}
+
+pub const CANDLE_FIELDS: [&str; 13] = [
+    "timestamp", "datetime", "exchange_datetime", "session", "open", "high", "low", "close", "volume",
+    "adj_close", "change", "change_percent", "log_return",
+];
+
+// The response as JSON with each candle cut down to `fields`
+pub fn project_candle_fields(response: &HistoricalDataResponse, fields: &[String]) -> Result<serde_json::Value, serde_json::Error> {
+    let mut value = serde_json::to_value(response)?;
+    let tickers = value.get_mut("data").and_then(|data| data.as_object_mut());
+    for ticker in tickers.into_iter().flat_map(|tickers| tickers.values_mut()) {
+        let candles = ticker.get_mut("candles").and_then(|candles| candles.as_array_mut());
+        for candle in candles.into_iter().flatten().filter_map(|candle| candle.as_object_mut()) {
+            candle.retain(|key, _| fields.iter().any(|f| f == key));
         }
-        Ok(())
     }
+    Ok(value)
+}
 
-    // Market data aggregation
-    pub async fn get_market_summary(&self) -> Result<MarketSummary, ApiError> {
-        // Fetch major indices and market stats
-        let indices = vec!["^GSPC", "^DJI", "^IXIC"]; // S&P 500, Dow, NASDAQ
-        let options = ChartQueryOptions::default();
-        
-        let mut index_data = HashMap::new();
-        for index in &indices {
-            if let Ok(data) = self.fetch_ticker_data(index, &options).await {
-                if let Ok(quote) = self.extract_quote_from_data(data) {
-                    index_data.insert(index.to_string(), quote);
-                }
+// "sma(period=20),rsi,keltner_channels(ema_period=20,multiplier=1.5)", the same form as the output labels.
+// Values are read as JSON where they parse (numbers, true/false) and as strings otherwise; a
+// parameter without a value is passed as null so the indicator factory reports it.
+pub fn indicator_configs(spec: &str) -> Vec<IndicatorConfig> {
+    split_specs(spec).into_iter()
+        .map(|entry| {
+            let (name, args) = match entry.split_once('(') {
+                Some((name, args)) => (name, args.trim_end_matches(')')),
+                None => (entry, ""),
+            };
+            let params: HashMap<String, serde_json::Value> = args.split(',')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(|arg| match arg.split_once('=') {
+                    Some((key, value)) => {
+                        let value = value.trim();
+                        let parsed = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                        (key.trim().to_string(), parsed)
+                    }
+                    None => (arg.to_string(), serde_json::Value::Null),
+                })
+                .collect();
+            IndicatorConfig {
+                name: name.trim().to_string(),
+                params: (!params.is_empty()).then_some(params),
             }
+        })
+        .collect()
+}
+
+pub fn signals_request_from_query(query: &HashMap<String, String>) -> SignalsRequest {
+    SignalsRequest {
+        ticker: query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string()),
+        interval: query.get("interval").cloned(),
+        range: query.get("range").cloned(),
+        rules: query.get("rules").cloned(),
+        weights: query.get("weights").cloned(),
+        threshold: query.get("threshold").and_then(|v| v.parse().ok()),
+    }
+}
+
+pub fn vol_surface_request_from_query(query: &HashMap<String, String>) -> VolSurfaceRequest {
+    VolSurfaceRequest {
+        ticker: query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string()),
+        min_moneyness: query.get("min_moneyness").and_then(|s| s.parse().ok()),
+        max_moneyness: query.get("max_moneyness").and_then(|s| s.parse().ok()),
+        steps: query.get("steps").and_then(|s| s.parse().ok()),
+        max_days: query.get("max_days").and_then(|s| s.parse().ok()),
+        risk_free_rate: query.get("risk_free_rate").and_then(|s| s.parse().ok()),
+    }
+}
+
+pub fn calendar_request_from_query(query: &HashMap<String, String>) -> CalendarRequest {
+    CalendarRequest {
+        from: query.get("from").cloned(),
+        to: query.get("to").cloned(),
+        tickers: query.get("tickers").or_else(|| query.get("ticker"))
+            .map(|list| list.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()),
+    }
+}
+
+pub fn oi_profile_request_from_query(query: &HashMap<String, String>) -> OiProfileRequest {
+    OiProfileRequest {
+        ticker: query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string()),
+        max_days: query.get("max_days").and_then(|s| s.parse().ok()),
+    }
+}
+
+// Trimmed, upper-cased and de-duplicated in order; between 2 and `max` required
+fn distinct_tickers(list: &[String], max: usize) -> Result<Vec<String>, ApiError> {
+    let mut check = Validator::new();
+    let mut tickers: Vec<String> = Vec::new();
+    for (i, ticker) in list.iter().enumerate() {
+        let ticker = ticker.trim().to_uppercase();
+        if !ticker.is_empty() {
+            check.ticker(&format!("tickers[{}]", i), &ticker);
         }
+        if !ticker.is_empty() && !tickers.contains(&ticker) {
+            tickers.push(ticker);
+        }
+    }
+    check.finish()?;
+    if tickers.len() < 2 || tickers.len() > max {
+        return Err(ApiError::InvalidParameters(format!("Between 2 and {} distinct tickers are required", max)));
+    }
+    Ok(tickers)
+}
 
-        Ok(MarketSummary {
-            indices: index_data,
-            market_status: "OPEN".to_string(), // You'd determine this from market hours
-            last_updated: Utc::now().to_rfc3339(),
-        })
+pub fn rebalance_request_from_query(query: &HashMap<String, String>) -> RebalanceRequest {
+    RebalanceRequest {
+        drift_threshold: query.get("drift_threshold").and_then(|s| s.parse().ok()),
+        fractional: query.get("fractional").map(|v| v == "true"),
     }
+}
+
+// `portfolio_id` and `status` filters of GET /api/v1/orders
+pub fn order_filter_from_query(query: &HashMap<String, String>) -> Result<(Option<String>, Option<OrderStatus>), ApiError> {
+    let status = match query.get("status") {
+        Some(status) => Some(OrderStatus::parse(status).ok_or_else(|| {
+            ApiError::InvalidParameters(format!("Unknown order status '{}'. Valid: open, filled, cancelled, rejected", status))
+        })?),
+        None => None,
+    };
+    Ok((query.get("portfolio_id").cloned(), status))
+}
+
+// `year` of a realized gains report; absent (or unparseable) covers every year
+// `from` and `to` as YYYY-MM-DD, defaulting to today in New York and a month after `from`
+pub fn market_calendar_window_from_query(query: &HashMap<String, String>) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    let parse = |name: &str| query.get(name).map(|value| {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::InvalidDateRange(format!("Invalid {} '{}': expected YYYY-MM-DD", name, value)))
+    }).transpose();
+    let from = parse("from")?.unwrap_or_else(|| calendar::new_york_time(Utc::now()).date_naive());
+    let to = parse("to")?.unwrap_or(from + chrono::Duration::days(30));
+    Ok((from, to))
+}
+
+pub fn realized_gains_year_from_query(query: &HashMap<String, String>) -> Option<i32> {
+    query.get("year").and_then(|s| s.parse().ok())
+}
 
-    pub async fn get_quote_summary(&self, ticker: &str) -> Result<QuoteSummaryResponse, ApiError> {
-        let mut yahoo_client = YahooFinanceClient::new();
-        yahoo_client.fetch_quote_summary(ticker).await
+pub fn portfolio_risk_request_from_query(query: &HashMap<String, String>) -> PortfolioRiskRequest {
+    PortfolioRiskRequest {
+        range: query.get("range").cloned(),
+        benchmark: query.get("benchmark").cloned(),
+        confidence: query.get("confidence").and_then(|s| s.parse().ok()),
+        risk_free_rate: query.get("risk_free_rate").and_then(|s| s.parse().ok()),
     }
+}
 
-    pub async fn get_news(&self, ticker: &str, count: Option<u32>) -> Result<NewsResponse, ApiError> {
-        let mut yahoo_client = YahooFinanceClient::new();
-        yahoo_client.fetch_news(ticker, count).await
+// GET form of a scan: `tickers` (or `ticker`) comma-separated
+pub fn unusual_activity_request_from_query(query: &HashMap<String, String>) -> UnusualActivityRequest {
+    let tickers = query.get("tickers").or_else(|| query.get("ticker"))
+        .map(|list| list.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+        .unwrap_or_else(|| vec!["AAPL".to_string()]);
+    UnusualActivityRequest {
+        tickers,
+        min_volume: query.get("min_volume").and_then(|s| s.parse().ok()),
+        min_volume_oi_ratio: query.get("min_volume_oi_ratio").and_then(|s| s.parse().ok()),
+        min_sweep_premium: query.get("min_sweep_premium").and_then(|s| s.parse().ok()),
+        min_iv_ratio: query.get("min_iv_ratio").and_then(|s| s.parse().ok()),
+        max_days: query.get("max_days").and_then(|s| s.parse().ok()),
+        risk_free_rate: query.get("risk_free_rate").and_then(|s| s.parse().ok()),
+        limit: query.get("limit").and_then(|s| s.parse().ok()),
+        concurrency: query.get("concurrency").and_then(|s| s.parse().ok()),
     }
+}
 
-    pub async fn get_calendar(&self, from: &str, to: &str) -> Result<CalendarResponse, ApiError> {
-        let mut yahoo_client = YahooFinanceClient::new();
-        yahoo_client.fetch_calendar(from, to).await
+pub fn options_request_from_query(query: &HashMap<String, String>) -> OptionsChainRequest {
+    let ticker = query.get("ticker")
+        .cloned()
+        .unwrap_or_else(|| "AAPL".to_string());
+
+    OptionsChainRequest {
+        ticker,
+        expiration_dates: query.get("expiration_dates")
+            .map(|dates| dates.split(',').map(|d| d.trim().to_string()).filter(|d| !d.is_empty()).collect()),
+        min_strike: query.get("min_strike").and_then(|s| s.parse().ok()),
+        max_strike: query.get("max_strike").and_then(|s| s.parse().ok()),
+        option_type: query.get("option_type").cloned(),
+        include_greeks: query.get("include_greeks").map(|v| v == "true"),
+        volatility: query.get("volatility").and_then(|s| s.parse().ok()),
+        risk_free_rate: query.get("risk_free_rate").and_then(|s| s.parse().ok()),
+        min_moneyness: query.get("min_moneyness").and_then(|s| s.parse().ok()),
+        max_moneyness: query.get("max_moneyness").and_then(|s| s.parse().ok()),
+        min_delta: query.get("min_delta").and_then(|s| s.parse().ok()),
+        max_delta: query.get("max_delta").and_then(|s| s.parse().ok()),
+        max_spread_pct: query.get("max_spread_pct").and_then(|s| s.parse().ok()),
+        pricing_model: query.get("pricing_model").cloned(),
+        dividend_yield: query.get("dividend_yield").and_then(|s| s.parse().ok()),
+        moneyness: query.get("moneyness").cloned(),
+        moneyness_band: query.get("moneyness_band").and_then(|s| s.parse().ok()),
+        max_expirations: query.get("max_expirations").and_then(|s| s.parse().ok()),
     }
+}
 
-    pub async fn get_reports(&self, ticker: &str) -> Result<ReportsResponse, ApiError> {
-        let mut yahoo_client = YahooFinanceClient::new();
-        yahoo_client.fetch_reports(ticker).await
+// Push interval (seconds, clamped to MIN_STREAM_INTERVAL) and initial subscriptions for /api/v1/stream
+pub fn stream_settings_from_query(query: &HashMap<String, String>) -> (Duration, BTreeSet<String>) {
+    let interval = query.get("interval")
+        .and_then(|s| s.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_STREAM_INTERVAL)
+        .max(MIN_STREAM_INTERVAL);
+    let subscriptions = query.get("tickers")
+        .map(|t| t.split(',').filter(|s| !s.is_empty()).map(|s| s.to_uppercase()).collect())
+        .unwrap_or_default();
+    (interval, subscriptions)
+}
+
+// /api/v1/sse: tickers and interval as for /api/v1/stream, plus candle_interval (intraday,
+// default 1m) and the indicators spec whose values are sent as those bars close
+pub fn sse_settings_from_query(query: &HashMap<String, String>, max_tickers: usize) -> Result<SseSettings, ApiError> {
+    let (interval, tickers) = stream_settings_from_query(query);
+    let candle_interval = query.get("candle_interval").cloned().unwrap_or_else(|| "1m".to_string());
+    let listed: Vec<String> = tickers.iter().cloned().collect();
+    Validator::new()
+        .tickers("tickers", &listed, max_tickers)
+        .interval("candle_interval", Some(&candle_interval))
+        .finish()?;
+    if !is_intraday(&candle_interval) {
+        return Err(ApiError::InvalidParameters(format!(
+            "Invalid candle_interval '{}'. Valid intervals: 1m, 2m, 5m, 15m, 30m, 60m, 90m, 1h", candle_interval
+        )));
     }
+    let indicators = query.get("indicators")
+        .filter(|spec| !spec.trim().is_empty())
+        .map(|spec| indicator_configs(spec))
+        .unwrap_or_default();
+    build_indicators(indicators.iter().map(|c| (c.name.as_str(), c.params.clone().unwrap_or_default())))
+        .map_err(ApiError::InvalidParameters)?;
+    Ok(SseSettings { tickers, interval, candle_interval, indicators })
 }
 
-// HTTP Server traits (you can implement with your preferred web framework)
-pub trait ApiServer {
-    fn start(&self, port: u16) -> Result<(), Box<dyn Error>>;
+// Applies a client command to the subscription set. Returns the reply and whether quotes
+// should be pushed right away (new symbols shouldn't wait a full interval)
+pub fn apply_stream_command(subscriptions: &mut BTreeSet<String>, text: &str) -> (StreamMessage, bool) {
+    match serde_json::from_str::<StreamCommand>(text) {
+        Ok(StreamCommand::Subscribe { tickers }) => {
+            subscriptions.extend(tickers.iter().map(|t| t.to_uppercase()));
+            (StreamMessage::Subscriptions { tickers: subscriptions.iter().cloned().collect() }, true)
+        }
+        Ok(StreamCommand::Unsubscribe { tickers }) => {
+            for ticker in tickers {
+                subscriptions.remove(&ticker.to_uppercase());
+            }
+            (StreamMessage::Subscriptions { tickers: subscriptions.iter().cloned().collect() }, false)
+        }
+        Err(e) => (StreamMessage::Error { message: format!("Invalid command: {}", e) }, false),
+    }
 }
 
 // REST endpoints would be implemented here using your preferred web framework
@@ -2318,6 +6884,7 @@ mod simple_server {
     use std::net::TcpStream;
     use std::sync::Arc;
     use std::error::Error;
+    use tracing::info;
     use crate::StockDataApi;
 
     pub struct SimpleApiServer {
@@ -2333,7 +6900,7 @@ mod simple_server {
         
         pub fn start(&self, port: u16) -> Result<(), Box<dyn Error>> {
             let listener = TcpListener::bind(format!("127.0.0.1:{}", port))?;
-            println!("Server running on http://127.0.0.1:{}", port);
+            info!("Server running on http://127.0.0.1:{}", port);
             
             for stream in listener.incoming() {
                 let stream = stream?;
@@ -2366,51 +6933,143 @@ mod simple_server {
 #[derive(Debug, Serialize)]
 pub struct MarketSummary {
     pub indices: HashMap<String, Quote>,
-    pub market_status: String,
+    pub market_status: String, // PRE, OPEN, POST or CLOSED on the NYSE/Nasdaq calendar
+    pub next_open: String,
+    pub next_close: String,
     pub last_updated: String,
 }
 
 // HTTP Server Implementation using std library only
-#[cfg(feature = "simple-server")]
+// Superseded by axum_server when both features are on, as in main
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
 pub mod http_server {
     use super::*;
-    use std::net::{TcpListener, TcpStream};
+    use std::net::TcpListener;
     use std::io::{Read, Write, BufRead, BufReader};
     use std::collections::HashMap;
-    use crate::StockDataApi;
+    use tungstenite::{Message, WebSocket, Error as WsError};
+    use tungstenite::handshake::derive_accept_key;
+    use tungstenite::protocol::Role;
+    use std::cell::Cell;
+    use crate::{metrics, StockDataApi};
+    use crate::export::{self, ExportFormat};
+    use crate::portfolio::WatchlistSymbolsRequest;
+    use crate::tls::ServerStream;
+    use serde_json::from_str;
+    use tracing::{error, info, Instrument};
+    use crate::compression::{ContentEncoding, MIN_COMPRESS_BYTES};
+    use crate::sse::{self, SseEvent, SseSession};
+    use std::borrow::Cow;
+    use rustls::ServerConfig;
+
+    tokio::task_local! {
+        // Status of the response written for the current request, for the metrics
+        static RESPONSE_STATUS: Cell<u16>;
+        // What the response helpers need to know about the current request
+        static REQUEST_CONTEXT: RequestContext;
+    }
+
+    struct RequestContext {
+        allowed_origin: Option<String>, // the request's Origin when it is one of the configured CORS origins, "*" for any
+        encoding: Option<ContentEncoding>, // from Accept-Encoding
+    }
 
     pub struct StockApiServer {
         api: Arc<StockDataApi>,
+        cors_origins: Arc<Vec<String>>,
+        tls: Option<Arc<ServerConfig>>,
     }
 
     impl StockApiServer {
-        pub fn new(api: StockDataApi) -> Self {
-            Self {
-                api: Arc::new(api),
-            }
+        pub fn new(api: Arc<StockDataApi>) -> Self {
+            Self { api, cors_origins: Arc::new(vec!["http://localhost:3000".to_string()]), tls: None }
+        }
+
+        // Serve HTTPS (ApiConfig.tls) instead of plain HTTP
+        pub fn with_tls(mut self, config: Arc<ServerConfig>) -> Self {
+            self.tls = Some(config);
+            self
+        }
+
+        // ApiConfig.cors_origins; "*" allows any origin (without credentials), an empty list none
+        pub fn with_cors_origins(mut self, origins: Vec<String>) -> Self {
+            self.cors_origins = Arc::new(origins);
+            self
         }
 
         pub fn start(&self, addr: &str) -> Result<(), Box<dyn Error>> {
             let listener = TcpListener::bind(addr)?;
-            println!("Stock API Server running on http://{}", addr);
+            let scheme = if self.tls.is_some() { "https" } else { "http" };
+            info!("Stock API Server running on {}://{}", scheme, addr);
             println!("Available endpoints:");
             println!("  GET  /api/v1/historical?tickers=AAPL,MSFT&range=1mo");
             println!("  GET  /api/v1/options?ticker=AAPL&include_greeks=true");
+            println!("  GET  /api/v1/options/surface?ticker=AAPL");
+            println!("  GET  /api/v1/options/oi-profile?ticker=AAPL");
+            println!("  GET  /api/v1/options/unusual?tickers=AAPL,TSLA");
+            println!("  POST /api/v1/options/unusual");
             println!("  POST /api/v1/options/pnl");
             println!("  GET  /api/v1/quotes?tickers=AAPL,MSFT");
+            println!("  GET  /api/v1/quote?ticker=AAPL");
+            println!("  WS   /api/v1/stream?tickers=AAPL,MSFT&interval=5");
+            println!("  GET  /api/v1/sse?tickers=AAPL,MSFT&interval=5&candle_interval=1m&indicators=rsi(period=14)");
             println!("  GET  /api/v1/quotesummary?ticker=AAPL");
             println!("  GET  /api/v1/market/summary");
+            println!("  GET  /api/v1/market/status");
+            println!("  GET  /api/v1/market/calendar?from=2024-12-01&to=2024-12-31");
             println!("  GET /api/v1/news?ticker=AAPL&count=10");
             println!("  GET /api/v1/calendar?from=2024-01-01&to=2024-01-31");
             println!("  GET /api/v1/reports?ticker=AAPL");
+            println!("  GET  /api/v1/fundamentals?ticker=AAPL&period=quarterly");
+            println!("  GET  /api/v1/analysis?ticker=AAPL");
+            println!("  POST /api/v1/screener");
+            println!("  POST /api/v1/bundle");
+            println!("  POST /api/v1/batch");
+            println!("  GET  /api/v1/search?q=apple&limit=10");
+            println!("  GET  /api/v1/cache/stats");
+            println!("  POST /api/v1/cache/clear");
+            println!("  GET  /metrics");
+            println!("  GET  /api/v1/openapi.json");
+            println!("  GET  /api/v1/docs");
+            println!("  GET  /api/v1/events?ticker=AAPL&from=2020-01-01&to=2024-12-31");
+            println!("  GET  /api/v1/signals?ticker=AAPL&rules=rsi(14,30,70),macd(12,26,9)");
+            println!("  POST /api/v1/analysis/correlation");
+            println!("  POST /api/v1/portfolio/optimize");
+            println!("  GET|POST /api/v1/portfolio");
+            println!("  GET  /api/v1/portfolio/{{id}}");
+            println!("  POST /api/v1/portfolio/{{id}}/positions");
+            println!("  DELETE /api/v1/portfolio/{{id}}/positions/{{symbol}}");
+            println!("  GET|POST /api/v1/portfolio/{{id}}/transactions");
+            println!("  POST /api/v1/portfolio/{{id}}/alerts");
+            println!("  DELETE /api/v1/portfolio/{{id}}/alerts/{{alert_id}}");
+            println!("  POST /api/v1/portfolio/{{id}}/dividends");
+            println!("  GET  /api/v1/portfolio/{{id}}/risk?range=1y&benchmark=SPY&confidence=0.95");
+            println!("  PUT  /api/v1/portfolio/{{id}}/targets");
+            println!("  GET  /api/v1/portfolio/{{id}}/rebalance?drift_threshold=0.05");
+            println!("  GET  /api/v1/portfolio/{{id}}/realized-gains?year=2024");
+            println!("  GET  /api/v1/portfolio/{{id}}/snapshots");
+            println!("  GET|POST /api/v1/watchlists");
+            println!("  GET|PUT|DELETE /api/v1/watchlists/{{id}}");
+            println!("  POST /api/v1/watchlists/{{id}}/symbols");
+            println!("  DELETE /api/v1/watchlists/{{id}}/symbols/{{symbol}}");
+            println!("  GET  /api/v1/watchlists/{{id}}/quotes?indicators=rsi(period=14),sma(period=50)");
+            println!("  GET|POST /api/v1/orders?portfolio_id=<id>&status=open");
+            println!("  GET|DELETE /api/v1/orders/{{id}}");
+            println!("  POST /api/v1/orders/process");
+            println!("  GET  /api/v1/scheduler");
+            println!("  POST /api/v1/scheduler/start|stop");
+            println!("  POST /api/v1/scheduler/jobs");
+            println!("  GET|DELETE /api/v1/scheduler/jobs/{{id}}");
+            println!("  POST /api/v1/scheduler/jobs/{{id}}/start|stop|run");
 
             for stream in listener.incoming() {
-                let stream = stream?;
+                let stream = ServerStream::accept(stream?, self.tls.as_ref())?;
                 let api = Arc::clone(&self.api);
-                
+                let cors_origins = Arc::clone(&self.cors_origins);
+
                 tokio::spawn(async move {
-                    if let Err(e) = handle_request(stream, api).await {
-                        eprintln!("Request handling error: {}", e);
+                    if let Err(e) = handle_request(stream, api, cors_origins).await {
+                        error!(error = %e, "Request handling failed");
                     }
                 });
             }
@@ -2419,7 +7078,13 @@ pub mod http_server {
         }
     }
 
-    async fn handle_request(mut stream: TcpStream, api: Arc<StockDataApi>) -> Result<(), Box<dyn Error>> {
+    // Connection reader positioned at the body, with the request headers already parsed
+    struct RequestReader {
+        reader: BufReader<ServerStream>,
+        headers: HashMap<String, String>,
+    }
+
+    async fn handle_request(mut stream: ServerStream, api: Arc<StockDataApi>, cors_origins: Arc<Vec<String>>) -> Result<(), Box<dyn Error>> {
         let reader_stream = stream.try_clone()?;
         let mut reader = BufReader::new(reader_stream);
         let mut request_line = String::new();
@@ -2430,25 +7095,34 @@ pub mod http_server {
             send_response(&mut stream, 400, "Bad Request", "Invalid request line")?;
             return Ok(());
         }
+        let headers = read_headers(&mut reader)?;
+        let mut reader = RequestReader { reader, headers };
 
         let method = parts[0];
         let path_with_query = parts[1];
         let (path, query) = parse_path_query(path_with_query);
 
-        // CORS headers to be reused
-        let cors_headers = concat!(
-            "Access-Control-Allow-Origin: http://localhost:3000\r\n",
-            "Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n",
-            "Access-Control-Allow-Headers: Content-Type, Authorization\r\n",
-            "Access-Control-Allow-Credentials: true\r\n",
-        );
+        // "*" answers any Origin with a literal wildcard; listed origins are echoed back
+        let allowed_origin = reader.headers.get("origin").and_then(|origin| {
+            if cors_origins.iter().any(|allowed| allowed == "*") {
+                Some("*".to_string())
+            } else {
+                cors_origins.iter().any(|allowed| allowed == origin).then(|| origin.clone())
+            }
+        });
+        // Read before the handler takes the headers
+        let accept_encoding = reader.headers.get("accept-encoding").cloned();
 
         // Handle OPTIONS preflight request
         if method == "OPTIONS" {
             // Usually you just reply with headers + 204 No Content
             let response = format!(
-                "HTTP/1.1 204 No Content\r\n{}\r\n",
-                cors_headers
+                "HTTP/1.1 204 No Content\r\n{}{}\r\n",
+                cors_origin_headers(allowed_origin.as_deref()),
+                concat!(
+                    "Access-Control-Allow-Methods: GET, POST, PUT, DELETE, OPTIONS\r\n",
+                    "Access-Control-Allow-Headers: Content-Type, Authorization, X-API-Key\r\n",
+                ),
             );
             stream.write_all(response.as_bytes())?;
             stream.flush()?;
@@ -2459,40 +7133,304 @@ pub mod http_server {
         // For example in your send_json_response function:
         // add Access-Control-Allow-Origin and other headers there
 
-        match (method, path.as_str()) {
-            ("GET", "/api/v1/historical") => {
-                handle_historical_data(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/options") => {
-                handle_options_chain(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/quotes") => {
-                handle_quotes(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/quotesummary") => {
-                handle_quote_summary(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/news") => {
-                handle_news(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/calendar") => {
-                handle_calendar(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/reports") => {
-                handle_reports(&mut stream, &*api, query).await?;
-            }
-            ("GET", "/api/v1/market/summary") => {
-                handle_market_summary(&mut stream, &*api).await?;
-            }
-            ("POST", "/api/v1/options/pnl") => {
-                handle_options_pnl(&mut stream, &*api, &mut reader).await?;
+        let started = Instant::now();
+        let mut route = metrics::route_label(&path);
+        let request_id = request_id::from_header(reader.headers.get(request_id::HEADER).map(String::as_str));
+        let span = tracing::info_span!("http_request", method, path = %path, request_id = %request_id);
+        let handled = async {
+            let public = crate::auth::PUBLIC_PATHS.contains(&path.as_str());
+            if let Some(authenticator) = api.authenticator().filter(|_| !public) {
+                let key = crate::auth::presented_key(
+                    reader.headers.get("x-api-key").map(String::as_str),
+                    reader.headers.get("authorization").map(String::as_str),
+                    query.get("api_key").map(String::as_str),
+                );
+                if let Err(e) = authenticator.check(key) {
+                    send_auth_error(&mut stream, &e)?;
+                    return Ok(());
+                }
             }
-            _ => {
-                send_response(&mut stream, 404, "Not Found", "Endpoint not found")?;
+
+            match (method, path.as_str()) {
+                ("GET", "/api/v1/historical") => {
+                    handle_historical_data(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/options") => {
+                    handle_options_chain(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/quotes") => {
+                    handle_quotes(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/quote") => {
+                    handle_enriched_quote(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/stream") => {
+                    handle_stream(stream, api, query, reader.headers).await?;
+                    return Ok(());
+                }
+                ("GET", "/api/v1/sse") => {
+                    handle_sse(&mut stream, api, query, &reader.headers).await?;
+                }
+                ("GET", "/api/v1/quotesummary") => {
+                    handle_quote_summary(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/news") => {
+                    handle_news(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/calendar") => {
+                    handle_calendar(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/reports") => {
+                    handle_reports(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/analysis") => {
+                    let ticker = query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string());
+                    send_result(&mut stream, api.get_analysis(&ticker).await)?;
+                }
+                ("GET", "/api/v1/fundamentals") => {
+                    let ticker = query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string());
+                    send_result(&mut stream, api.get_fundamentals(&ticker, query.get("period").map(String::as_str)).await)?;
+                }
+                ("GET", "/api/v1/confluence") => {
+                    handle_confluence(&mut stream, &*api, query).await?;
+                }
+                ("GET", "/api/v1/market/summary") => {
+                    handle_market_summary(&mut stream, &*api).await?;
+                }
+                ("GET", "/api/v1/market/status") => {
+                    let json = serde_json::to_string(&calendar::market_status(Utc::now()))?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("GET", "/api/v1/market/calendar") => {
+                    let days = market_calendar_window_from_query(&query).and_then(|(from, to)| calendar::trading_days(from, to));
+                    send_result(&mut stream, days)?;
+                }
+                ("GET", "/api/v1/options/surface") => {
+                    send_result(&mut stream, api.get_vol_surface(vol_surface_request_from_query(&query)).await)?;
+                }
+                ("GET", "/api/v1/options/oi-profile") => {
+                    send_result(&mut stream, api.get_oi_profile(oi_profile_request_from_query(&query)).await)?;
+                }
+                ("GET", "/api/v1/options/unusual") => {
+                    send_result(&mut stream, api.scan_unusual_activity(unusual_activity_request_from_query(&query)).await)?;
+                }
+                ("POST", "/api/v1/options/unusual") => {
+                    let Some(request) = read_json_body::<UnusualActivityRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.scan_unusual_activity(request).await)?;
+                }
+                ("POST", "/api/v1/options/pnl") => {
+                    handle_options_pnl(&mut stream, &*api, &mut reader).await?;
+                }
+                ("POST", "/api/v1/position-size") => {
+                    handle_position_size(&mut stream, &*api, &mut reader).await?;
+                }
+                ("POST", "/api/v1/portfolio/optimize") => {
+                    let Some(request) = read_json_body::<OptimizeRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.optimize_portfolio(request).await)?;
+                }
+                ("POST", "/api/v1/analysis/correlation") => {
+                    let Some(request) = read_json_body::<CorrelationRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.get_correlation(request).await)?;
+                }
+                ("POST", "/api/v1/bundle") => {
+                    let Some(request) = read_json_body::<BundleRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.get_bundle(request).await)?;
+                }
+                ("POST", "/api/v1/batch") => {
+                    let Some(request) = read_json_body::<BatchRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.batch(request).await)?;
+                }
+                ("POST", "/api/v1/screener") => {
+                    let Some(request) = read_json_body::<ScreenerRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.screen_stocks(request).await)?;
+                }
+                ("GET", "/api/v1/cache/stats") => {
+                    let json = serde_json::to_string(&api.cache_stats().await)?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("GET", "/api/v1/openapi.json") => {
+                    let json = serde_json::to_string(&crate::openapi::document())?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("GET", "/api/v1/docs") => {
+                    send_html_response(&mut stream, crate::openapi::SWAGGER_UI_HTML)?;
+                }
+                ("GET", "/metrics") => {
+                    send_response(&mut stream, 200, "OK", &api.render_metrics())?;
+                }
+                ("POST", "/api/v1/cache/clear") => {
+                    let json = serde_json::to_string(&api.clear_cache().await)?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("GET", "/api/v1/search") => {
+                    let q = query.get("q").map(|q| decode_query_value(q)).unwrap_or_default();
+                    let limit = query.get("limit").and_then(|l| l.parse::<u32>().ok());
+                    send_result(&mut stream, api.search_symbols(&q, limit).await)?;
+                }
+                ("GET", "/api/v1/events") => {
+                    let ticker = query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string());
+                    let from = query.get("from").map(String::as_str);
+                    let to = query.get("to").map(String::as_str);
+                    send_result(&mut stream, api.get_corporate_actions(&ticker, from, to).await)?;
+                }
+                ("GET", "/api/v1/signals") => {
+                    let mut request = signals_request_from_query(&query);
+                    request.rules = request.rules.map(|r| decode_query_value(&r));
+                    request.weights = request.weights.map(|w| decode_query_value(&w));
+                    send_result(&mut stream, api.get_signals(request).await)?;
+                }
+                ("POST", p) if p.starts_with("/api/v1/portfolio/") && p.ends_with("/dividends") => {
+                    let id = p.strip_prefix("/api/v1/portfolio/").and_then(|rest| rest.strip_suffix("/dividends")).unwrap_or_default();
+                    send_result(&mut stream, api.sync_dividends(id).await)?;
+                }
+                ("GET", p) if p.starts_with("/api/v1/portfolio/") && p.ends_with("/rebalance") => {
+                    let id = p.strip_prefix("/api/v1/portfolio/").and_then(|rest| rest.strip_suffix("/rebalance")).unwrap_or_default();
+                    send_result(&mut stream, api.get_rebalance(id, rebalance_request_from_query(&query)).await)?;
+                }
+                ("GET", p) if p.starts_with("/api/v1/portfolio/") && p.ends_with("/realized-gains") => {
+                    let id = p.strip_prefix("/api/v1/portfolio/").and_then(|rest| rest.strip_suffix("/realized-gains")).unwrap_or_default();
+                    let (id, year) = (id.to_string(), realized_gains_year_from_query(&query));
+                    send_result(&mut stream, api.with_portfolios(move |p| p.realized_gains(&id, year)).await)?;
+                }
+                ("GET", p) if p.starts_with("/api/v1/portfolio/") && p.ends_with("/risk") => {
+                    let id = p.strip_prefix("/api/v1/portfolio/").and_then(|rest| rest.strip_suffix("/risk")).unwrap_or_default();
+                    send_result(&mut stream, api.get_portfolio_risk(id, portfolio_risk_request_from_query(&query)).await)?;
+                }
+                ("GET", "/api/v1/portfolio") => {
+                    send_result(&mut stream, api.with_portfolios(|p| p.list_portfolios()).await)?;
+                }
+                ("POST", "/api/v1/portfolio") => {
+                    handle_create_portfolio(&mut stream, &api, &mut reader).await?;
+                }
+                (_, p) if p.starts_with("/api/v1/portfolio/") => {
+                    let rest = &p["/api/v1/portfolio/".len()..];
+                    handle_portfolio_route(&mut stream, &api, method, rest, &mut reader).await?;
+                }
+                ("GET", "/api/v1/watchlists") => {
+                    send_result(&mut stream, api.with_portfolios(|p| p.list_watchlists()).await)?;
+                }
+                ("POST", "/api/v1/watchlists") => {
+                    let Some(request) = read_json_body(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.with_portfolios(move |p| p.create_watchlist(request)).await)?;
+                }
+                ("GET", p) if p.starts_with("/api/v1/watchlists/") && p.ends_with("/quotes") => {
+                    let id = p.strip_prefix("/api/v1/watchlists/").and_then(|rest| rest.strip_suffix("/quotes")).unwrap_or_default();
+                    let indicators = query.get("indicators").map(String::as_str);
+                    send_result(&mut stream, api.get_watchlist_quotes(id, indicators).await)?;
+                }
+                (_, p) if p.starts_with("/api/v1/watchlists/") => {
+                    let rest = &p["/api/v1/watchlists/".len()..];
+                    handle_watchlist_route(&mut stream, &api, method, rest, &mut reader).await?;
+                }
+                ("GET", "/api/v1/scheduler") => {
+                    let json = serde_json::to_string(&api.scheduler().status())?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("POST", "/api/v1/scheduler/start") | ("POST", "/api/v1/scheduler/stop") => {
+                    let json = serde_json::to_string(&api.scheduler().set_running(path.ends_with("/start")))?;
+                    send_json_response(&mut stream, 200, &json)?;
+                }
+                ("POST", "/api/v1/scheduler/jobs") => {
+                    let Some(request) = read_json_body(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.add_scheduled_job(request).await)?;
+                }
+                ("POST", p) if p.starts_with("/api/v1/scheduler/jobs/") && p.ends_with("/run") => {
+                    let id = p.strip_prefix("/api/v1/scheduler/jobs/").and_then(|rest| rest.strip_suffix("/run")).unwrap_or_default();
+                    send_result(&mut stream, api.run_job_now(id).await)?;
+                }
+                (_, p) if p.starts_with("/api/v1/scheduler/jobs/") => {
+                    let rest = &p["/api/v1/scheduler/jobs/".len()..];
+                    handle_scheduler_job_route(&mut stream, &*api, method, rest)?;
+                }
+                ("GET", "/api/v1/orders") => {
+                    let result = match order_filter_from_query(&query) {
+                        Ok((portfolio_id, status)) => api.with_portfolios(move |p| p.list_orders(portfolio_id.as_deref(), status)).await,
+                        Err(e) => Err(e),
+                    };
+                    send_result(&mut stream, result)?;
+                }
+                ("POST", "/api/v1/orders") => {
+                    let Some(request) = read_json_body::<PlaceOrderRequest>(&mut stream, &mut reader)? else {
+                        return Ok(());
+                    };
+                    send_result(&mut stream, api.place_order(request).await)?;
+                }
+                ("POST", "/api/v1/orders/process") => {
+                    send_result(&mut stream, api.process_orders_once().await)?;
+                }
+                ("GET", p) if p.starts_with("/api/v1/orders/") => {
+                    let id = p["/api/v1/orders/".len()..].to_string();
+                    send_result(&mut stream, api.with_portfolios(move |p| p.get_order(&id)).await)?;
+                }
+                ("DELETE", p) if p.starts_with("/api/v1/orders/") => {
+                    let id = p["/api/v1/orders/".len()..].to_string();
+                    send_result(&mut stream, api.with_portfolios(move |p| p.cancel_order(&id)).await)?;
+                }
+                _ => {
+                    route = "unmatched".to_string();
+                    send_route_not_found(&mut stream)?;
+                }
             }
+
+            Ok::<(), Box<dyn Error>>(())
+        };
+        let context = RequestContext {
+            allowed_origin,
+            encoding: accept_encoding.as_deref().and_then(ContentEncoding::negotiate),
+        };
+        let (result, status) = request_id::scope(request_id, REQUEST_CONTEXT.scope(context, RESPONSE_STATUS.scope(Cell::new(0), async {
+            let result = handled.instrument(span.clone()).await;
+            (result, RESPONSE_STATUS.with(Cell::get))
+        }))).await;
+
+        // Upgraded streams write their own 101 and are long-lived, so they aren't timed
+        if status != 0 {
+            let latency = started.elapsed();
+            metrics::global().record_request(method, &route, status, latency);
+            span.in_scope(|| info!(%route, status, latency_ms = latency.as_millis() as u64, "Request completed"));
         }
+        result
+    }
 
-        Ok(())
+    // Undoes form encoding ('+' and %XX) for free-text values; other params are plain symbols
+    fn decode_query_value(value: &str) -> String {
+        let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let escaped = match bytes.get(i..i + 3) {
+                Some([b'%', hi, lo]) => hex(*hi).zip(hex(*lo)).map(|(hi, lo)| (hi << 4) | lo),
+                _ => None,
+            };
+            match (escaped, bytes[i]) {
+                (Some(byte), _) => {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                (None, b'+') => decoded.push(b' '),
+                (None, b) => decoded.push(b),
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
     }
 
     fn parse_path_query(path_with_query: &str) -> (String, HashMap<String, String>) {
@@ -2517,39 +7455,66 @@ pub mod http_server {
     }
 
     async fn handle_historical_data(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
-        let tickers = query.get("tickers")
-            .map(|t| t.split(',').map(|s| s.to_string()).collect())
-            .unwrap_or_else(|| vec!["AAPL".to_string()]);
-
-        let request = HistoricalDataRequest {
-            tickers,
-            interval: query.get("interval").cloned(),
-            range: query.get("range").cloned(),
-            start_date: query.get("start_date").cloned(),
-            end_date: query.get("end_date").cloned(),
-            include_indicators: query.get("include_indicators").map(|v| v == "true"),
-            indicators: None, // Could parse from query params
+        let mut query = query;
+        if let Some(spec) = query.get_mut("indicators") {
+            *spec = decode_query_value(spec);
+        }
+        let request = historical_request_from_query(&query);
+        let format = match query.get("format").map(|f| ExportFormat::from_name(f)).transpose() {
+            Ok(format) => format.unwrap_or(ExportFormat::Json),
+            Err(e) => return send_error(stream, &e),
+        };
+        let fields = match candle_fields_from_query(&query) {
+            Ok(fields) => fields,
+            Err(e) => return send_error(stream, &e),
         };
 
         match api.get_historical_data(request).await {
+            Ok(response) if format == ExportFormat::Json => {
+                let json = match &fields {
+                    Some(fields) => serde_json::to_string(&project_candle_fields(&response, fields)?)?,
+                    None => serde_json::to_string(&response)?,
+                };
+                send_json_response(stream, 200, &json)?;
+            }
+            Ok(response) => match export::render(&response, format) {
+                Ok(body) => send_file_response(stream, format.content_type(), &format!("historical.{}", format.extension()), &body)?,
+                Err(e) => send_error(stream, &e)?,
+            },
+            Err(e) => {
+                send_error(stream, &e)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_options_chain(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        query: HashMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let request = options_request_from_query(&query);
+
+        match api.get_options_chain(request).await {
             Ok(response) => {
                 let json = serde_json::to_string(&response)?;
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                send_response(stream, 500, "Internal Server Error", &e.to_string())?;
+                send_error(stream, &e)?;
             }
         }
 
         Ok(())
     }
 
-    async fn handle_options_chain(
-        stream: &mut TcpStream,
+    async fn handle_enriched_quote(
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
@@ -2557,24 +7522,13 @@ pub mod http_server {
             .cloned()
             .unwrap_or_else(|| "AAPL".to_string());
 
-        let request = OptionsChainRequest {
-            ticker,
-            expiration_dates: None,
-            min_strike: query.get("min_strike").and_then(|s| s.parse().ok()),
-            max_strike: query.get("max_strike").and_then(|s| s.parse().ok()),
-            option_type: query.get("option_type").cloned(),
-            include_greeks: query.get("include_greeks").map(|v| v == "true"),
-            volatility: query.get("volatility").and_then(|s| s.parse().ok()),
-            risk_free_rate: query.get("risk_free_rate").and_then(|s| s.parse().ok()),
-        };
-
-        match api.get_options_chain(request).await {
-            Ok(response) => {
-                let json = serde_json::to_string(&response)?;
+        match api.get_enriched_quote(&ticker).await {
+            Ok(quote) => {
+                let json = serde_json::to_string(&quote)?;
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                send_response(stream, 500, "Internal Server Error", &e.to_string())?;
+                send_error(stream, &e)?;
             }
         }
 
@@ -2582,7 +7536,7 @@ pub mod http_server {
     }
 
     async fn handle_quotes(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
@@ -2593,6 +7547,7 @@ pub mod http_server {
         let request = QuoteRequest {
             tickers,
             fields: None,
+            include_prepost: query.get("include_prepost").map(|v| v == "true"),
         };
 
         match api.get_quotes(request).await {
@@ -2601,7 +7556,29 @@ pub mod http_server {
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                send_response(stream, 500, "Internal Server Error", &e.to_string())?;
+                send_error(stream, &e)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_confluence(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        query: HashMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let ticker = query.get("ticker")
+            .cloned()
+            .unwrap_or_else(|| "AAPL".to_string());
+
+        match api.get_confluence(&ticker).await {
+            Ok(response) => {
+                let json = serde_json::to_string(&response)?;
+                send_json_response(stream, 200, &json)?;
+            }
+            Err(e) => {
+                send_error(stream, &e)?;
             }
         }
 
@@ -2609,7 +7586,7 @@ pub mod http_server {
     }
 
     async fn handle_market_summary(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
     ) -> Result<(), Box<dyn Error>> {
         match api.get_market_summary().await {
@@ -2618,7 +7595,7 @@ pub mod http_server {
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                send_response(stream, 500, "Internal Server Error", &e.to_string())?;
+                send_error(stream, &e)?;
             }
         }
 
@@ -2626,59 +7603,382 @@ pub mod http_server {
     }
 
     pub async fn handle_options_pnl(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
-        reader: &mut BufReader<TcpStream>,
+        reader: &mut RequestReader,
     ) -> Result<(), Box<dyn Error>> {
-        // Step 1: Read headers
-        let mut content_length = None;
+        // Step 1-2: Read headers and body
+        let body = match read_request_body(reader)? {
+            Some(body) => body,
+            None => {
+                send_error(stream, &ApiError::InvalidParameters("Missing Content-Length".to_string()))?;
+                return Ok(());
+            }
+        };
+
+        // Step 3: Parse JSON
+        let pnl_request: OptionsPnLRequest = match from_str(std::str::from_utf8(&body)?) {
+            Ok(req) => req,
+            Err(_) => {
+                send_error(stream, &ApiError::InvalidParameters("Invalid JSON in body".to_string()))?;
+                return Ok(());
+            }
+        };
+
+        // Step 4: Call API
+        let result = api.calculate_options_pnl(pnl_request);
+        match result {
+            Ok(response) => {
+                let json = serde_json::to_string(&response)?;
+                send_json_response(stream, 200, &json)?
+            }
+            Err(e) => {
+                warn!(error = %e, "P&L calculation failed");
+                send_error(stream, &e)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Header names are lowercased; the stream handshake needs them, other routes skip them
+    fn read_headers(reader: &mut BufReader<ServerStream>) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        let mut headers = HashMap::new();
         let mut line = String::new();
 
         loop {
             line.clear();
-            reader.read_line(&mut line)?;
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
             let trimmed = line.trim();
-
             if trimmed.is_empty() {
                 break; // End of headers
             }
+            if let Some((name, value)) = trimmed.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        Ok(headers)
+    }
+
+    // WebSocket quote stream. Upgrades the connection, then pushes a StreamMessage::Quotes for the
+    // subscribed tickers every `interval` seconds while applying subscribe/unsubscribe commands.
+    async fn handle_stream(
+        mut stream: ServerStream,
+        api: Arc<StockDataApi>,
+        query: HashMap<String, String>,
+        headers: HashMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let is_upgrade = headers.get("upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        let Some(key) = headers.get("sec-websocket-key").filter(|_| is_upgrade) else {
+            send_response(&mut stream, 426, "Upgrade Required", "Expected a WebSocket upgrade")?;
+            return Ok(());
+        };
+
+        let response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            derive_accept_key(key.as_bytes())
+        );
+        stream.write_all(response.as_bytes())?;
+        stream.flush()?;
+
+        let (interval, subscriptions) = stream_settings_from_query(&query);
+
+        // The socket is blocking std I/O, so run it off the async workers and only re-enter the
+        // runtime to fetch quotes
+        let handle = tokio::runtime::Handle::current();
+        let result = tokio::task::spawn_blocking(move || run_stream(stream, api, handle, interval, subscriptions)).await?;
+        result.map_err(|e| e as Box<dyn Error>)
+    }
+
+    fn run_stream(
+        stream: ServerStream,
+        api: Arc<StockDataApi>,
+        handle: tokio::runtime::Handle,
+        interval: Duration,
+        mut subscriptions: BTreeSet<String>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Short read timeout so we can interleave client commands with periodic pushes
+        stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+        let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+        let mut gauge = metrics::StreamGauge::open(subscriptions.len());
+
+        send_stream_message(&mut socket, &StreamMessage::Subscriptions {
+            tickers: subscriptions.iter().cloned().collect(),
+        })?;
+        let mut next_push = Instant::now();
+
+        loop {
+            if Instant::now() >= next_push {
+                if !subscriptions.is_empty() {
+                    let tickers: Vec<String> = subscriptions.iter().cloned().collect();
+                    let message = handle.block_on(api.get_stream_quotes(&tickers));
+                    send_stream_message(&mut socket, &message)?;
+                }
+                next_push = Instant::now() + interval;
+            }
 
-            if let Some(cl) = trimmed.strip_prefix("Content-Length:") {
-                content_length = Some(cl.trim().parse::<usize>()?);
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    let (reply, push_now) = apply_stream_command(&mut subscriptions, &text);
+                    gauge.set_subscriptions(subscriptions.len());
+                    if push_now {
+                        next_push = Instant::now();
+                    }
+                    send_stream_message(&mut socket, &reply)?;
+                }
+                Ok(Message::Close(_)) => break,
+                Ok(_) => {} // pings are answered by tungstenite; binary frames are ignored
+                Err(WsError::Io(e)) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+                Err(WsError::ConnectionClosed | WsError::AlreadyClosed) => break,
+                Err(e) => return Err(Box::new(e)),
             }
         }
 
-        let content_length = match content_length {
-            Some(len) => len,
-            None => {
-                send_response(stream, 400, "Bad Request", "Missing Content-Length")?;
+        Ok(())
+    }
+
+    // Server-sent events for the tickers in the query. The response has no length and runs until a
+    // write fails, i.e. the client went away; the heartbeats make sure that's noticed.
+    async fn handle_sse(
+        stream: &mut ServerStream,
+        api: Arc<StockDataApi>,
+        query: HashMap<String, String>,
+        headers: &HashMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let settings = match sse_settings_from_query(&query, api.max_tickers_per_request()) {
+            Ok(settings) => settings,
+            Err(e) => {
+                send_error(stream, &e)?;
                 return Ok(());
             }
         };
+        let last_event_id = sse::last_event_id(headers.get("last-event-id").map(String::as_str), &query);
 
-        // Step 2: Read body
-        let mut body = vec![0u8; content_length];
-        reader.read_exact(&mut body)?;
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(200));
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nX-Accel-Buffering: no\r\n{}\r\n{}",
+            current_cors_headers(), sse::retry_frame()
+        );
+        stream.write_all(head.as_bytes())?;
+        stream.flush()?;
 
-        // Step 3: Parse JSON
-        let pnl_request: OptionsPnLRequest = match from_str(std::str::from_utf8(&body)?) {
+        let mut session = SseSession::open(api, settings, last_event_id);
+        loop {
+            let events = session.next_events().await;
+            let frames = if events.is_empty() {
+                sse::HEARTBEAT_FRAME.to_string()
+            } else {
+                events.iter().map(SseEvent::frame).collect()
+            };
+            if stream.write_all(frames.as_bytes()).and_then(|_| stream.flush()).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn send_stream_message(
+        socket: &mut WebSocket<ServerStream>,
+        message: &StreamMessage,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        socket.send(Message::Text(serde_json::to_string(message)?))?;
+        Ok(())
+    }
+
+    // Serializes a route's result, or its error with the status that error maps to
+    fn send_result<T: Serialize>(stream: &mut ServerStream, result: Result<T, ApiError>) -> Result<(), Box<dyn Error>> {
+        match result {
+            Ok(value) => {
+                let json = serde_json::to_string(&value)?;
+                send_json_response(stream, 200, &json)?;
+            }
+            Err(e) => {
+                send_error(stream, &e)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Parses a JSON body, answering 400 itself when it's missing or malformed
+    fn read_json_body<T: serde::de::DeserializeOwned>(
+        stream: &mut ServerStream,
+        reader: &mut RequestReader,
+    ) -> Result<Option<T>, Box<dyn Error>> {
+        let Some(body) = read_request_body(reader)? else {
+            send_error(stream, &ApiError::InvalidParameters("Missing Content-Length".to_string()))?;
+            return Ok(None);
+        };
+        match from_str(std::str::from_utf8(&body)?) {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => {
+                send_error(stream, &ApiError::InvalidParameters("Invalid JSON in body".to_string()))?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn handle_create_portfolio(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        reader: &mut RequestReader,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(request) = read_json_body(stream, reader)? else {
+            return Ok(());
+        };
+        send_result(stream, api.with_portfolios(move |p| p.create_portfolio(request)).await)
+    }
+
+    // /api/v1/portfolio/{id}[/...] with `rest` being everything after the prefix
+    async fn handle_portfolio_route(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        method: &str,
+        rest: &str,
+        reader: &mut RequestReader,
+    ) -> Result<(), Box<dyn Error>> {
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        let Some(id) = segments.first().map(|id| id.to_string()) else {
+            return send_route_not_found(stream);
+        };
+
+        match (method, segments.as_slice()) {
+            ("GET", [_]) => send_result(stream, api.with_portfolios(move |p| p.get_portfolio(&id)).await),
+            ("POST", [_, "positions"]) => {
+                let Some(request) = read_json_body(stream, reader)? else {
+                    return Ok(());
+                };
+                send_result(stream, api.with_portfolios(move |p| p.add_position(&id, request)).await)
+            }
+            ("DELETE", [_, "positions", symbol]) => {
+                let symbol = symbol.to_string();
+                send_result(stream, api.with_portfolios(move |p| p.remove_position(&id, &symbol)).await)
+            }
+            ("GET", [_, "transactions"]) => send_result(stream, api.with_portfolios(move |p| p.transactions(&id)).await),
+            ("GET", [_, "snapshots"]) => send_result(stream, api.with_portfolios(move |p| p.snapshots(&id)).await),
+            ("POST", [_, "alerts"]) => {
+                let Some(request) = read_json_body(stream, reader)? else {
+                    return Ok(());
+                };
+                send_result(stream, api.with_portfolios(move |p| p.add_alert(&id, request)).await)
+            }
+            ("DELETE", [_, "alerts", alert_id]) => {
+                let alert_id = alert_id.to_string();
+                send_result(stream, api.with_portfolios(move |p| p.remove_alert(&id, &alert_id)).await)
+            }
+            ("PUT", [_, "targets"]) => {
+                let Some(request) = read_json_body(stream, reader)? else {
+                    return Ok(());
+                };
+                send_result(stream, api.with_portfolios(move |p| p.set_target_weights(&id, request)).await)
+            }
+            ("POST", [_, "transactions"]) => {
+                let Some(request) = read_json_body(stream, reader)? else {
+                    return Ok(());
+                };
+                send_result(stream, api.with_portfolios(move |p| p.apply_transaction(&id, request)).await)
+            }
+            _ => send_route_not_found(stream),
+        }
+    }
+
+    // /api/v1/scheduler/jobs/{id}[/start|/stop] with `rest` being everything after the prefix
+    fn handle_scheduler_job_route(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        method: &str,
+        rest: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        let scheduler = api.scheduler();
+
+        match (method, segments.as_slice()) {
+            ("GET", [id]) => send_result(stream, scheduler.job(id)),
+            ("DELETE", [id]) => send_result(stream, scheduler.remove_job(id)),
+            ("POST", [id, "start"]) => send_result(stream, scheduler.set_enabled(id, true)),
+            ("POST", [id, "stop"]) => send_result(stream, scheduler.set_enabled(id, false)),
+            _ => send_route_not_found(stream),
+        }
+    }
+
+    // /api/v1/watchlists/{id}[/...] with `rest` being everything after the prefix
+    async fn handle_watchlist_route(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        method: &str,
+        rest: &str,
+        reader: &mut RequestReader,
+    ) -> Result<(), Box<dyn Error>> {
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        let Some(id) = segments.first().map(|id| id.to_string()) else {
+            return send_route_not_found(stream);
+        };
+
+        let result = match (method, segments.as_slice()) {
+            ("GET", [_]) => api.with_portfolios(move |p| p.get_watchlist(&id)).await,
+            ("PUT", [_]) => {
+                let Some(request) = read_json_body(stream, reader)? else {
+                    return Ok(());
+                };
+                api.with_portfolios(move |p| p.update_watchlist(&id, request)).await
+            }
+            ("DELETE", [_]) => api.with_portfolios(move |p| p.delete_watchlist(&id)).await,
+            ("POST", [_, "symbols"]) => {
+                let Some(request) = read_json_body::<WatchlistSymbolsRequest>(stream, reader)? else {
+                    return Ok(());
+                };
+                api.with_portfolios(move |p| p.add_watchlist_symbols(&id, &request.symbols)).await
+            }
+            ("DELETE", [_, "symbols", symbol]) => {
+                let symbol = symbol.to_string();
+                api.with_portfolios(move |p| p.remove_watchlist_symbol(&id, &symbol)).await
+            }
+            _ => return send_route_not_found(stream),
+        };
+        send_result(stream, result)
+    }
+
+    // Reads the remaining headers and returns the body, or None when there's no Content-Length
+    fn read_request_body(reader: &mut RequestReader) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let Some(content_length) = reader.headers.get("content-length") else {
+            return Ok(None);
+        };
+
+        let mut body = vec![0u8; content_length.parse::<usize>()?];
+        reader.reader.read_exact(&mut body)?;
+        Ok(Some(body))
+    }
+
+    async fn handle_position_size(
+        stream: &mut ServerStream,
+        api: &StockDataApi,
+        reader: &mut RequestReader,
+    ) -> Result<(), Box<dyn Error>> {
+        let body = match read_request_body(reader)? {
+            Some(body) => body,
+            None => {
+                send_error(stream, &ApiError::InvalidParameters("Missing Content-Length".to_string()))?;
+                return Ok(());
+            }
+        };
+
+        let request: PositionSizeRequest = match from_str(std::str::from_utf8(&body)?) {
             Ok(req) => req,
             Err(_) => {
-                send_response(stream, 400, "Bad Request", "Invalid JSON in body")?;
+                send_error(stream, &ApiError::InvalidParameters("Invalid JSON in body".to_string()))?;
                 return Ok(());
             }
         };
 
-        // Step 4: Call API
-        let result = api.calculate_options_pnl(pnl_request);
-        match result {
+        match api.calculate_position_size(request) {
             Ok(response) => {
                 let json = serde_json::to_string(&response)?;
                 send_json_response(stream, 200, &json)?
             }
             Err(e) => {
-                eprintln!("P&L calculation error: {}", e);
-                send_response(stream, 500, "Internal Server Error", &format!("Error: {}", e))?;
+                send_error(stream, &e)?;
             }
         }
 
@@ -2686,7 +7986,7 @@ pub mod http_server {
     }
 
     pub async fn handle_quote_summary(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -2694,25 +7994,20 @@ pub mod http_server {
             .cloned()
             .unwrap_or_else(|| "AAPL".to_string());
     
-        match api.get_quote_summary(&ticker).await {
+        match api.get_quote_summary(&ticker, query.get("modules").map(String::as_str)).await {
             Ok(response) => {
                 let json = serde_json::to_string(&response)?;
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                let error_response = serde_json::json!({
-                    "error": e.to_string(),
-                    "ticker": ticker
-                });
-                let json = serde_json::to_string(&error_response)?;
-                send_json_response(stream, 500, &json)?;
+                send_error(stream, &e)?;
             }
         }
         Ok(())
     }
     
     pub async fn handle_news(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -2729,50 +8024,33 @@ pub mod http_server {
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                let error_response = serde_json::json!({
-                    "error": e.to_string(),
-                    "ticker": ticker
-                });
-                let json = serde_json::to_string(&error_response)?;
-                send_json_response(stream, 500, &json)?;
+                send_error(stream, &e)?;
             }
         }
         Ok(())
     }
     
     pub async fn handle_calendar(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let from = query.get("from")
-            .cloned()
-            .unwrap_or_else(|| "2024-01-01".to_string());
-        
-        let to = query.get("to")
-            .cloned()
-            .unwrap_or_else(|| "2024-12-31".to_string());
-    
-        match api.get_calendar(&from, &to).await {
+        let request = calendar_request_from_query(&query);
+
+        match api.get_calendar(request).await {
             Ok(response) => {
                 let json = serde_json::to_string(&response)?;
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                let error_response = serde_json::json!({
-                    "error": e.to_string(),
-                    "from": from,
-                    "to": to
-                });
-                let json = serde_json::to_string(&error_response)?;
-                send_json_response(stream, 500, &json)?;
+                send_error(stream, &e)?;
             }
         }
         Ok(())
     }
     
     pub async fn handle_reports(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         api: &StockDataApi,
         query: HashMap<String, String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -2786,23 +8064,53 @@ pub mod http_server {
                 send_json_response(stream, 200, &json)?;
             }
             Err(e) => {
-                let error_response = serde_json::json!({
-                    "error": e.to_string(),
-                    "ticker": ticker
-                });
-                let json = serde_json::to_string(&error_response)?;
-                send_json_response(stream, 500, &json)?;
+                send_error(stream, &e)?;
             }
         }
         Ok(())
     }
 
+    // Echoes an allowed Origin back, with credentials; Vary keeps caches from serving it to other
+    // origins. The "*" wildcard can't carry credentials, so it goes out without them.
+    fn cors_origin_headers(origin: Option<&str>) -> String {
+        match origin {
+            Some("*") => "Access-Control-Allow-Origin: *\r\n".to_string(),
+            Some(origin) => format!(
+                "Access-Control-Allow-Origin: {}\r\nAccess-Control-Allow-Credentials: true\r\nVary: Origin\r\n",
+                origin
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn current_cors_headers() -> String {
+        REQUEST_CONTEXT
+            .try_with(|context| cors_origin_headers(context.allowed_origin.as_deref()))
+            .unwrap_or_default()
+    }
+
+    // Compresses bodies big enough to be worth it when the client accepts gzip or br; returns the
+    // body to send and the headers describing it
+    fn encode_body(body: &[u8]) -> Result<(Cow<'_, [u8]>, String), Box<dyn Error>> {
+        if body.len() < MIN_COMPRESS_BYTES {
+            return Ok((Cow::Borrowed(body), String::new()));
+        }
+        match REQUEST_CONTEXT.try_with(|context| context.encoding).ok().flatten() {
+            Some(encoding) => Ok((
+                Cow::Owned(encoding.compress(body)?),
+                format!("Content-Encoding: {}\r\nVary: Accept-Encoding\r\n", encoding.as_str()),
+            )),
+            None => Ok((Cow::Borrowed(body), "Vary: Accept-Encoding\r\n".to_string())),
+        }
+    }
+
     fn send_response(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         status_code: u16,
         status_text: &str,
         body: &str,
     ) -> Result<(), Box<dyn Error>> {
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(status_code));
         let response = format!(
             "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nContent-Type: text/plain\r\n\r\n{}",
             status_code, status_text, body.len(), body
@@ -2812,16 +8120,90 @@ pub mod http_server {
         Ok(())
     }
 
+    fn status_text(status_code: u16) -> &'static str {
+        match status_code {
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            429 => "Too Many Requests",
+            502 => "Bad Gateway",
+            _ => "Internal Server Error",
+        }
+    }
+
+    // An ErrorResponse body with the request id repeated in X-Request-Id
+    fn send_error_body(stream: &mut ServerStream, status_code: u16, body: &ErrorResponse, extra_headers: &str) -> Result<(), Box<dyn Error>> {
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(status_code));
+        let json = serde_json::to_string(body)?;
+        let response = format!(
+            "HTTP/1.1 {} {}\r\n{}X-Request-Id: {}\r\nContent-Length: {}\r\nContent-Type: application/json\r\n{}\r\n{}",
+            status_code, status_text(status_code), extra_headers, body.request_id, json.len(), current_cors_headers(), json
+        );
+        stream.write_all(response.as_bytes())?;
+        stream.flush()?;
+        Ok(())
+    }
+
+    // A failed call, with the status its ApiError maps to
+    fn send_error(stream: &mut ServerStream, error: &ApiError) -> Result<(), Box<dyn Error>> {
+        send_error_body(stream, error.status(), &ErrorResponse::from(error), "")
+    }
+
+    fn send_route_not_found(stream: &mut ServerStream) -> Result<(), Box<dyn Error>> {
+        send_error_body(stream, 404, &ErrorResponse::new("not_found", "Endpoint not found".to_string()), "")
+    }
+
+    // 401 or 429; quota rejections say when to come back
+    fn send_auth_error(stream: &mut ServerStream, error: &crate::auth::AuthError) -> Result<(), Box<dyn Error>> {
+        let extra_header = match error.retry_after_secs() {
+            Some(secs) => format!("Retry-After: {}\r\n", secs),
+            None => "WWW-Authenticate: Bearer\r\n".to_string(),
+        };
+        send_error_body(stream, error.status(), &ErrorResponse::new(error.code(), error.to_string()), &extra_header)
+    }
+
+    fn send_file_response(stream: &mut ServerStream, content_type: &str, filename: &str, body: &[u8]) -> Result<(), Box<dyn Error>> {
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(200));
+        // CSV compresses well; Parquet is already compressed
+        let (body, encoding_headers) = if content_type.starts_with("text/") {
+            encode_body(body)?
+        } else {
+            (Cow::Borrowed(body), String::new())
+        };
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nContent-Type: {}\r\nContent-Disposition: attachment; filename=\"{}\"\r\n{}{}\r\n",
+            body.len(), content_type, filename, encoding_headers, current_cors_headers()
+        );
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&body)?;
+        stream.flush()?;
+        Ok(())
+    }
+
+    fn send_html_response(stream: &mut ServerStream, html: &str) -> Result<(), Box<dyn Error>> {
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(200));
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nContent-Type: text/html; charset=utf-8\r\n\r\n{}",
+            html.len(), html
+        );
+        stream.write_all(response.as_bytes())?;
+        stream.flush()?;
+        Ok(())
+    }
+
     fn send_json_response(
-        stream: &mut TcpStream,
+        stream: &mut ServerStream,
         status_code: u16,
         json: &str,
     ) -> Result<(), Box<dyn Error>> {
-        let response = format!(
-            "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: http://localhost:3000\r\nAccess-Control-Allow-Credentials: true\r\n\r\n{}",
-            status_code, json.len(), json
+        let _ = RESPONSE_STATUS.try_with(|status| status.set(status_code));
+        let (body, encoding_headers) = encode_body(json.as_bytes())?;
+        let head = format!(
+            "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nContent-Type: application/json\r\n{}{}\r\n",
+            status_code, body.len(), encoding_headers, current_cors_headers()
         );
-        stream.write_all(response.as_bytes())?;
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&body)?;
         stream.flush()?;
         Ok(())
     }
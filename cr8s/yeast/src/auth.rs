@@ -0,0 +1,227 @@
+// auth.rs - API keys and per-key request quotas for the HTTP servers
+//
+// Auth is off unless keys are configured (--api-keys / --api-keys-db). Once on, every request
+// needs a known key in X-API-Key, `Authorization: Bearer`, or the `api_key` query parameter (for
+// browser WebSockets, which can't set headers). Each key gets fixed per-minute and per-hour
+// windows; ApiConfig.rate_limit is the default and a key can override either limit.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use crate::api::ApiError;
+
+// Served without a key: the API description and docs page hold no market or portfolio data
+pub const PUBLIC_PATHS: [&str; 2] = ["/api/v1/openapi.json", "/api/v1/docs"];
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    pub requests_per_minute: u32,
+    pub requests_per_hour: u32,
+}
+
+// Where keys are loaded from, carried on ApiConfig
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    File(PathBuf),   // JSON array of ApiKey
+    Sqlite(PathBuf), // api_keys table, created empty if missing
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiKey {
+    pub key: String,
+    #[serde(default)]
+    pub name: String,
+    pub requests_per_minute: Option<u32>, // overrides the default limit
+    pub requests_per_hour: Option<u32>,
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    MissingKey,
+    InvalidKey,
+    QuotaExceeded { retry_after: Duration },
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::MissingKey => write!(f, "API key required"),
+            AuthError::InvalidKey => write!(f, "Invalid API key"),
+            AuthError::QuotaExceeded { retry_after } => {
+                write!(f, "Rate limit exceeded; retry in {}s", retry_after.as_secs().max(1))
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl AuthError {
+    pub fn status(&self) -> u16 {
+        match self {
+            AuthError::MissingKey | AuthError::InvalidKey => 401,
+            AuthError::QuotaExceeded { .. } => 429,
+        }
+    }
+
+    // The `code` in the ErrorResponse body
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthError::MissingKey => "missing_api_key",
+            AuthError::InvalidKey => "invalid_api_key",
+            AuthError::QuotaExceeded { .. } => "quota_exceeded",
+        }
+    }
+
+    // Whole seconds for the Retry-After header
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            AuthError::QuotaExceeded { retry_after } => Some(retry_after.as_secs().max(1)),
+            _ => None,
+        }
+    }
+}
+
+struct Window {
+    start: Instant,
+    count: u32,
+}
+
+impl Window {
+    fn new(now: Instant) -> Self {
+        Self { start: now, count: 0 }
+    }
+
+    // Time until the window resets if it's already at `limit`
+    fn blocked_for(&mut self, now: Instant, length: Duration, limit: Option<u32>) -> Option<Duration> {
+        if now.duration_since(self.start) >= length {
+            *self = Window::new(now);
+        }
+        match limit {
+            Some(limit) if self.count >= limit => Some((self.start + length).saturating_duration_since(now)),
+            _ => None,
+        }
+    }
+}
+
+struct Usage {
+    minute: Window,
+    hour: Window,
+}
+
+pub struct Authenticator {
+    keys: HashMap<String, ApiKey>,
+    default_limit: Option<RateLimit>,
+    usage: Mutex<HashMap<String, Usage>>,
+}
+
+// The key a request presented: X-API-Key, then a bearer token, then ?api_key=
+pub fn presented_key<'a>(
+    x_api_key: Option<&'a str>,
+    authorization: Option<&'a str>,
+    query_key: Option<&'a str>,
+) -> Option<&'a str> {
+    x_api_key
+        .or_else(|| authorization.and_then(|value| value.strip_prefix("Bearer ")))
+        .or(query_key)
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+fn storage_error(e: impl fmt::Display) -> ApiError {
+    ApiError::StorageError(e.to_string())
+}
+
+fn load_file(path: &Path) -> Result<Vec<ApiKey>, ApiError> {
+    let contents = std::fs::read_to_string(path).map_err(storage_error)?;
+    serde_json::from_str(&contents).map_err(|e| ApiError::StorageError(format!("{}: {}", path.display(), e)))
+}
+
+fn load_sqlite(path: &Path) -> Result<Vec<ApiKey>, ApiError> {
+    let conn = Connection::open(path).map_err(storage_error)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS api_keys (
+            key TEXT PRIMARY KEY,
+            name TEXT NOT NULL DEFAULT '',
+            requests_per_minute INTEGER,
+            requests_per_hour INTEGER
+        );",
+    ).map_err(storage_error)?;
+
+    let mut stmt = conn.prepare("SELECT key, name, requests_per_minute, requests_per_hour FROM api_keys")
+        .map_err(storage_error)?;
+    let keys = stmt.query_map([], |row| {
+        Ok(ApiKey {
+            key: row.get(0)?,
+            name: row.get(1)?,
+            requests_per_minute: row.get(2)?,
+            requests_per_hour: row.get(3)?,
+        })
+    }).map_err(storage_error)?;
+    keys.collect::<Result<Vec<_>, _>>().map_err(storage_error)
+}
+
+impl Authenticator {
+    pub fn new(keys: Vec<ApiKey>, default_limit: Option<RateLimit>) -> Self {
+        Self {
+            keys: keys.into_iter().map(|k| (k.key.clone(), k)).collect(),
+            default_limit,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn load(source: &KeySource, default_limit: Option<RateLimit>) -> Result<Self, ApiError> {
+        let (keys, path) = match source {
+            KeySource::File(path) => (load_file(path)?, path),
+            KeySource::Sqlite(path) => (load_sqlite(path)?, path),
+        };
+        if keys.is_empty() {
+            return Err(ApiError::StorageError(format!("No API keys in {}", path.display())));
+        }
+        Ok(Self::new(keys, default_limit))
+    }
+
+    // Key names for the startup log, so the keys themselves stay out of it
+    pub fn key_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.keys.values().map(|k| if k.name.is_empty() { "unnamed" } else { k.name.as_str() }).collect();
+        names.sort_unstable();
+        names
+    }
+
+    // Admits the request and counts it against the key's quota, or says why not. Rejected
+    // requests don't count.
+    pub fn check(&self, key: Option<&str>) -> Result<(), AuthError> {
+        let key = key.ok_or(AuthError::MissingKey)?;
+        let api_key = self.keys.get(key).ok_or(AuthError::InvalidKey)?;
+        let per_minute = api_key.requests_per_minute.or(self.default_limit.as_ref().map(|l| l.requests_per_minute));
+        let per_hour = api_key.requests_per_hour.or(self.default_limit.as_ref().map(|l| l.requests_per_hour));
+
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(key.to_string()).or_insert_with(|| Usage {
+            minute: Window::new(now),
+            hour: Window::new(now),
+        });
+
+        let blocked = [
+            usage.minute.blocked_for(now, MINUTE, per_minute),
+            usage.hour.blocked_for(now, HOUR, per_hour),
+        ];
+        if let Some(retry_after) = blocked.into_iter().flatten().max() {
+            return Err(AuthError::QuotaExceeded { retry_after });
+        }
+
+        usage.minute.count += 1;
+        usage.hour.count += 1;
+        Ok(())
+    }
+}
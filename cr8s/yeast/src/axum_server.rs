@@ -0,0 +1,720 @@
+// axum_server.rs - axum/hyper backend for the /api/v1 routes
+//
+// Serves the same endpoints as api::http_server, but with real HTTP parsing (chunked bodies,
+// keep-alive), typed extractors, and graceful shutdown on Ctrl-C. Enabled with the
+// `axum-server` feature.
+
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{MatchedPath, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use futures::{Stream, StreamExt};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{debug, error, info, Instrument};
+
+use crate::api::*;
+use crate::auth::{self, AuthError};
+use crate::export::{self, ExportFormat};
+use crate::{metrics, openapi, request_id, sse};
+use crate::portfolio::{
+    AddPositionRequest, CreateAlertRequest, CreatePortfolioRequest, CreateWatchlistRequest, Order, PlaceOrderRequest, Portfolio,
+    PortfolioAlert, PortfolioSnapshot, Transaction, RealizedGainsReport, TargetWeightsRequest, TransactionRequest,
+    UpdateWatchlistRequest, Watchlist, WatchlistSymbolsRequest,
+};
+use crate::calendar::{MarketStatus, TradingDay};
+use crate::scheduler::{Job, JobRequest, SchedulerStatus};
+
+type ApiState = Arc<StockDataApi>;
+type QueryParams = Query<HashMap<String, String>>;
+
+// An ErrorResponse body with the request id repeated in X-Request-Id
+fn error_response(status: u16, body: ErrorResponse) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let request_id = HeaderValue::from_str(&body.request_id).ok();
+    let mut response = (status, Json(body)).into_response();
+    if let Some(request_id) = request_id {
+        response.headers_mut().insert(request_id::HEADER, request_id);
+    }
+    response
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        error_response(self.status(), ErrorResponse::from(&self))
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let mut response = error_response(self.status(), ErrorResponse::new(self.code(), self.to_string()));
+        let (name, value) = match self.retry_after_secs() {
+            Some(secs) => (header::RETRY_AFTER, HeaderValue::from(secs)),
+            None => (header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer")),
+        };
+        response.headers_mut().insert(name, value);
+        response
+    }
+}
+
+// `origins` is ApiConfig.cors_origins. "*" is a plain wildcard, so browsers send no cookies or
+// HTTP auth cross-origin; only explicitly listed origins are allowed credentials.
+pub fn cors_layer(origins: &[String]) -> Result<CorsLayer, Box<dyn Error>> {
+    let cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, header::HeaderName::from_static("x-api-key")]);
+    if origins.iter().any(|origin| origin == "*") {
+        return Ok(cors.allow_origin(AllowOrigin::any()));
+    }
+    let origins = origins
+        .iter()
+        .map(|origin| HeaderValue::from_str(origin).map_err(|_| format!("Invalid CORS origin '{}'", origin)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(cors.allow_origin(AllowOrigin::list(origins)).allow_credentials(true))
+}
+
+pub fn router(api: ApiState, cors: CorsLayer) -> Router {
+    Router::new()
+        .route("/api/v1/historical", get(historical_data))
+        .route("/api/v1/options", get(options_chain))
+        .route("/api/v1/options/surface", get(options_surface))
+        .route("/api/v1/options/oi-profile", get(oi_profile))
+        .route("/api/v1/options/unusual", get(unusual_activity).post(unusual_activity_scan))
+        .route("/api/v1/options/pnl", post(options_pnl))
+        .route("/api/v1/position-size", post(position_size))
+        .route("/api/v1/quotes", get(quotes))
+        .route("/api/v1/quote", get(enriched_quote))
+        .route("/api/v1/stream", get(stream))
+        .route("/api/v1/sse", get(sse_events))
+        .route("/api/v1/quotesummary", get(quote_summary))
+        .route("/api/v1/market/summary", get(market_summary))
+        .route("/api/v1/market/status", get(market_status))
+        .route("/api/v1/market/calendar", get(market_calendar))
+        .route("/api/v1/news", get(news))
+        .route("/api/v1/calendar", get(calendar))
+        .route("/api/v1/search", get(search))
+        .route("/api/v1/cache/stats", get(cache_stats))
+        .route("/api/v1/cache/clear", post(clear_cache))
+        .route("/api/v1/events", get(corporate_actions))
+        .route("/api/v1/reports", get(reports))
+        .route("/api/v1/fundamentals", get(fundamentals))
+        .route("/api/v1/analysis", get(analysis))
+        .route("/api/v1/analysis/correlation", post(correlation))
+        .route("/api/v1/confluence", get(confluence))
+        .route("/api/v1/signals", get(signals))
+        .route("/api/v1/screener", post(screener))
+        .route("/api/v1/bundle", post(bundle))
+        .route("/api/v1/batch", post(batch))
+        .route("/api/v1/portfolio", get(list_portfolios).post(create_portfolio))
+        .route("/api/v1/portfolio/optimize", post(optimize_portfolio))
+        .route("/api/v1/portfolio/:id", get(get_portfolio))
+        .route("/api/v1/portfolio/:id/positions", post(add_position))
+        .route("/api/v1/portfolio/:id/positions/:symbol", delete(remove_position))
+        .route("/api/v1/portfolio/:id/transactions", get(list_transactions).post(apply_transaction))
+        .route("/api/v1/portfolio/:id/snapshots", get(list_snapshots))
+        .route("/api/v1/portfolio/:id/alerts", post(add_alert))
+        .route("/api/v1/portfolio/:id/dividends", post(sync_dividends))
+        .route("/api/v1/portfolio/:id/alerts/:alert_id", delete(remove_alert))
+        .route("/api/v1/portfolio/:id/risk", get(portfolio_risk))
+        .route("/api/v1/portfolio/:id/targets", put(set_target_weights))
+        .route("/api/v1/portfolio/:id/rebalance", get(rebalance))
+        .route("/api/v1/portfolio/:id/realized-gains", get(realized_gains))
+        .route("/api/v1/watchlists", get(list_watchlists).post(create_watchlist))
+        .route("/api/v1/watchlists/:id", get(get_watchlist).put(update_watchlist).delete(delete_watchlist))
+        .route("/api/v1/watchlists/:id/symbols", post(add_watchlist_symbols))
+        .route("/api/v1/watchlists/:id/symbols/:symbol", delete(remove_watchlist_symbol))
+        .route("/api/v1/watchlists/:id/quotes", get(watchlist_quotes))
+        .route("/api/v1/orders", get(list_orders).post(place_order))
+        .route("/api/v1/orders/process", post(process_orders))
+        .route("/api/v1/orders/:id", get(get_order).delete(cancel_order))
+        .route("/api/v1/scheduler", get(scheduler_status))
+        .route("/api/v1/scheduler/start", post(start_scheduler))
+        .route("/api/v1/scheduler/stop", post(stop_scheduler))
+        .route("/api/v1/scheduler/jobs", post(add_job))
+        .route("/api/v1/scheduler/jobs/:id", get(get_job).delete(remove_job))
+        .route("/api/v1/scheduler/jobs/:id/start", post(start_job))
+        .route("/api/v1/scheduler/jobs/:id/stop", post(stop_job))
+        .route("/api/v1/scheduler/jobs/:id/run", post(run_job))
+        .route("/metrics", get(render_metrics))
+        .route_layer(middleware::from_fn_with_state(api.clone(), require_api_key))
+        // Registered after the auth layer so it doesn't apply (auth::PUBLIC_PATHS)
+        .route("/api/v1/openapi.json", get(openapi_document))
+        .route("/api/v1/docs", get(swagger_ui))
+        .route_layer(middleware::from_fn(track_metrics))
+        .fallback(route_not_found)
+        .layer(CompressionLayer::new())
+        .layer(cors)
+        .layer(middleware::from_fn(assign_request_id))
+        .with_state(api)
+}
+
+pub async fn serve(api: ApiState, addr: &str, cors_origins: &[String], tls: Option<Arc<ServerConfig>>) -> Result<(), Box<dyn Error>> {
+    let cors = cors_layer(cors_origins)?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let app = router(api, cors);
+
+    match tls {
+        Some(config) => {
+            info!("Stock API Server (axum) running on https://{}", addr);
+            serve_tls(listener, app, config).await;
+        }
+        None => {
+            info!("Stock API Server (axum) running on http://{}", addr);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+    }
+
+    info!("Stock API Server stopped");
+    Ok(())
+}
+
+// axum::serve only takes plain listeners, so TLS connections are accepted here and handed to hyper
+// directly. Ctrl-C stops accepting; connections already open finish on their own.
+async fn serve_tls(listener: tokio::net::TcpListener, app: Router, config: Arc<ServerConfig>) {
+    let acceptor = TlsAcceptor::from(config);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        let (socket, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!(error = %e, "Failed to accept connection");
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return,
+        };
+        let acceptor = acceptor.clone();
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(socket).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    debug!(error = %e, %peer, "TLS handshake failed");
+                    return;
+                }
+            };
+            let connection = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await;
+            if let Err(e) = connection {
+                debug!(error = %e, %peer, "Connection closed with an error");
+            }
+        });
+    }
+}
+
+// Counts, times and logs each request under its route pattern, inside a span that upstream calls
+// made for it are logged under. Stream upgrades are skipped since the connection outlives the 101
+// response.
+async fn track_metrics(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request.extensions().get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let span = tracing::info_span!("http_request", %method, path = %request.uri().path(), request_id = %request_id::current());
+
+    let response = next.run(request).instrument(span.clone()).await;
+    if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+        let (status, latency) = (response.status().as_u16(), started.elapsed());
+        metrics::global().record_request(&method, &route, status, latency);
+        span.in_scope(|| info!(%route, status, latency_ms = latency.as_millis() as u64, "Request completed"));
+    }
+    response
+}
+
+// Outermost layer, so auth failures, handler errors and the request's logs all see the id
+async fn assign_request_id(request: Request, next: Next) -> Response {
+    let header = request.headers().get(request_id::HEADER).and_then(|v| v.to_str().ok());
+    let id = request_id::from_header(header);
+    request_id::scope(id, next.run(request)).await
+}
+
+async fn route_not_found() -> Response {
+    error_response(404, ErrorResponse::new("not_found", "Endpoint not found".to_string()))
+}
+
+// Rejects requests without a valid key, or over the key's quota, once auth is configured
+async fn require_api_key(State(api): State<ApiState>, request: Request, next: Next) -> Response {
+    let Some(authenticator) = api.authenticator() else {
+        return next.run(request).await;
+    };
+    // Owned so nothing borrowed from the request is held across next.run(...).await
+    let key: Option<String> = {
+        let query = Query::<HashMap<String, String>>::try_from_uri(request.uri()).map(|Query(q)| q).unwrap_or_default();
+        let header_value = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+        auth::presented_key(
+            header_value("x-api-key"),
+            header_value(header::AUTHORIZATION.as_str()),
+            query.get("api_key").map(String::as_str),
+        )
+        .map(str::to_string)
+    };
+    match authenticator.check(key.as_deref()) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn shutdown_signal() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!(error = %e, "Failed to listen for shutdown signal");
+    }
+}
+
+fn ticker_param(query: &HashMap<String, String>) -> String {
+    query.get("ticker").cloned().unwrap_or_else(|| "AAPL".to_string())
+}
+
+// JSON by default; format=csv|parquet returns a file download instead
+async fn historical_data(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Response, ApiError> {
+    let format = query.get("format").map(|f| ExportFormat::from_name(f)).transpose()?.unwrap_or(ExportFormat::Json);
+    let fields = candle_fields_from_query(&query)?;
+    let response = api.get_historical_data(historical_request_from_query(&query)).await?;
+    if format == ExportFormat::Json {
+        return match fields {
+            Some(fields) => project_candle_fields(&response, &fields)
+                .map(|projected| Json(projected).into_response())
+                .map_err(|e| ApiError::CalculationError(e.to_string())),
+            None => Ok(Json(response).into_response()),
+        };
+    }
+
+    let body = export::render(&response, format)?;
+    let disposition = format!("attachment; filename=\"historical.{}\"", format.extension());
+    Ok(([(header::CONTENT_TYPE, format.content_type().to_string()), (header::CONTENT_DISPOSITION, disposition)], body).into_response())
+}
+
+async fn options_chain(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<OptionsChainResponse>, ApiError> {
+    api.get_options_chain(options_request_from_query(&query)).await.map(Json)
+}
+
+async fn options_surface(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<VolSurfaceResponse>, ApiError> {
+    api.get_vol_surface(vol_surface_request_from_query(&query)).await.map(Json)
+}
+
+async fn oi_profile(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<OiProfileResponse>, ApiError> {
+    api.get_oi_profile(oi_profile_request_from_query(&query)).await.map(Json)
+}
+
+async fn unusual_activity(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<UnusualActivityResponse>, ApiError> {
+    api.scan_unusual_activity(unusual_activity_request_from_query(&query)).await.map(Json)
+}
+
+async fn unusual_activity_scan(State(api): State<ApiState>, Json(request): Json<UnusualActivityRequest>) -> Result<Json<UnusualActivityResponse>, ApiError> {
+    api.scan_unusual_activity(request).await.map(Json)
+}
+
+async fn options_pnl(State(api): State<ApiState>, Json(request): Json<OptionsPnLRequest>) -> Result<Json<OptionsPnLResponse>, ApiError> {
+    api.calculate_options_pnl(request).map(Json)
+}
+
+async fn position_size(State(api): State<ApiState>, Json(request): Json<PositionSizeRequest>) -> Result<Json<PositionSizeResponse>, ApiError> {
+    api.calculate_position_size(request).map(Json)
+}
+
+async fn quotes(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<QuoteResponse>, ApiError> {
+    let tickers = query.get("tickers")
+        .map(|t| t.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_else(|| vec!["AAPL".to_string()]);
+
+    let include_prepost = query.get("include_prepost").map(|v| v == "true");
+    api.get_quotes(QuoteRequest { tickers, fields: None, include_prepost }).await.map(Json)
+}
+
+async fn enriched_quote(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<Quote>, ApiError> {
+    api.get_enriched_quote(&ticker_param(&query)).await.map(Json)
+}
+
+async fn quote_summary(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<QuoteSummaryResponse>, ApiError> {
+    api.get_quote_summary(&ticker_param(&query), query.get("modules").map(String::as_str)).await.map(Json)
+}
+
+async fn market_summary(State(api): State<ApiState>) -> Result<Json<MarketSummary>, ApiError> {
+    api.get_market_summary().await.map(Json)
+}
+
+async fn market_status() -> Json<MarketStatus> {
+    Json(crate::calendar::market_status(chrono::Utc::now()))
+}
+
+async fn market_calendar(Query(query): QueryParams) -> Result<Json<Vec<TradingDay>>, ApiError> {
+    let (from, to) = market_calendar_window_from_query(&query)?;
+    crate::calendar::trading_days(from, to).map(Json)
+}
+
+async fn news(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<NewsResponse>, ApiError> {
+    let count = query.get("count").and_then(|c| c.parse::<u32>().ok());
+    api.get_news(&ticker_param(&query), count).await.map(Json)
+}
+
+async fn calendar(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<CalendarResponse>, ApiError> {
+    api.get_calendar(calendar_request_from_query(&query)).await.map(Json)
+}
+
+async fn cache_stats(State(api): State<ApiState>) -> Json<CacheStatsResponse> {
+    Json(api.cache_stats().await)
+}
+
+async fn clear_cache(State(api): State<ApiState>) -> Json<CacheClearResponse> {
+    Json(api.clear_cache().await)
+}
+
+async fn openapi_document() -> Json<serde_json::Value> {
+    Json(openapi::document())
+}
+
+async fn swagger_ui() -> Html<&'static str> {
+    Html(openapi::SWAGGER_UI_HTML)
+}
+
+async fn render_metrics(State(api): State<ApiState>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], api.render_metrics())
+}
+
+async fn search(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<SearchResponse>, ApiError> {
+    let q = query.get("q").map(String::as_str).unwrap_or_default();
+    let limit = query.get("limit").and_then(|l| l.parse::<u32>().ok());
+    api.search_symbols(q, limit).await.map(Json)
+}
+
+async fn corporate_actions(
+    State(api): State<ApiState>,
+    Query(query): QueryParams,
+) -> Result<Json<CorporateActionsResponse>, ApiError> {
+    let from = query.get("from").map(String::as_str);
+    let to = query.get("to").map(String::as_str);
+    api.get_corporate_actions(&ticker_param(&query), from, to).await.map(Json)
+}
+
+async fn reports(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<ReportsResponse>, ApiError> {
+    api.get_reports(&ticker_param(&query)).await.map(Json)
+}
+
+async fn analysis(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<AnalysisReports>, ApiError> {
+    api.get_analysis(&ticker_param(&query)).await.map(Json)
+}
+
+async fn fundamentals(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<FundamentalsResponse>, ApiError> {
+    api.get_fundamentals(&ticker_param(&query), query.get("period").map(String::as_str)).await.map(Json)
+}
+
+async fn correlation(State(api): State<ApiState>, Json(request): Json<CorrelationRequest>) -> Result<Json<CorrelationResponse>, ApiError> {
+    api.get_correlation(request).await.map(Json)
+}
+
+async fn bundle(State(api): State<ApiState>, Json(request): Json<BundleRequest>) -> Result<Json<BundleResponse>, ApiError> {
+    api.get_bundle(request).await.map(Json)
+}
+
+async fn batch(State(api): State<ApiState>, Json(request): Json<BatchRequest>) -> Result<Json<BatchResponse>, ApiError> {
+    api.batch(request).await.map(Json)
+}
+
+async fn confluence(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<ConfluenceResponse>, ApiError> {
+    api.get_confluence(&ticker_param(&query)).await.map(Json)
+}
+
+async fn signals(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<SignalsResponse>, ApiError> {
+    api.get_signals(signals_request_from_query(&query)).await.map(Json)
+}
+
+async fn screener(State(api): State<ApiState>, Json(request): Json<ScreenerRequest>) -> Result<Json<ScreenerResponse>, ApiError> {
+    api.screen_stocks(request).await.map(Json)
+}
+
+async fn list_portfolios(State(api): State<ApiState>) -> Result<Json<Vec<Portfolio>>, ApiError> {
+    api.with_portfolios(|p| p.list_portfolios()).await.map(Json)
+}
+
+async fn create_portfolio(State(api): State<ApiState>, Json(request): Json<CreatePortfolioRequest>) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.create_portfolio(request)).await.map(Json)
+}
+
+async fn optimize_portfolio(State(api): State<ApiState>, Json(request): Json<OptimizeRequest>) -> Result<Json<OptimizeResponse>, ApiError> {
+    api.optimize_portfolio(request).await.map(Json)
+}
+
+async fn get_portfolio(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.get_portfolio(&id)).await.map(Json)
+}
+
+async fn add_position(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<AddPositionRequest>,
+) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.add_position(&id, request)).await.map(Json)
+}
+
+async fn remove_position(
+    State(api): State<ApiState>,
+    Path((id, symbol)): Path<(String, String)>,
+) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.remove_position(&id, &symbol)).await.map(Json)
+}
+
+async fn list_transactions(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Vec<Transaction>>, ApiError> {
+    api.with_portfolios(move |p| p.transactions(&id)).await.map(Json)
+}
+
+async fn list_snapshots(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Vec<PortfolioSnapshot>>, ApiError> {
+    api.with_portfolios(move |p| p.snapshots(&id)).await.map(Json)
+}
+
+async fn apply_transaction(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<TransactionRequest>,
+) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.apply_transaction(&id, request)).await.map(Json)
+}
+
+async fn sync_dividends(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Vec<Transaction>>, ApiError> {
+    api.sync_dividends(&id).await.map(Json)
+}
+
+async fn portfolio_risk(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): QueryParams,
+) -> Result<Json<PortfolioRiskResponse>, ApiError> {
+    api.get_portfolio_risk(&id, portfolio_risk_request_from_query(&query)).await.map(Json)
+}
+
+async fn set_target_weights(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<TargetWeightsRequest>,
+) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.set_target_weights(&id, request)).await.map(Json)
+}
+
+async fn rebalance(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): QueryParams,
+) -> Result<Json<RebalanceResponse>, ApiError> {
+    api.get_rebalance(&id, rebalance_request_from_query(&query)).await.map(Json)
+}
+
+async fn realized_gains(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): QueryParams,
+) -> Result<Json<RealizedGainsReport>, ApiError> {
+    api.with_portfolios(move |p| p.realized_gains(&id, realized_gains_year_from_query(&query))).await.map(Json)
+}
+
+async fn add_alert(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<CreateAlertRequest>,
+) -> Result<Json<PortfolioAlert>, ApiError> {
+    api.with_portfolios(move |p| p.add_alert(&id, request)).await.map(Json)
+}
+
+async fn remove_alert(
+    State(api): State<ApiState>,
+    Path((id, alert_id)): Path<(String, String)>,
+) -> Result<Json<Portfolio>, ApiError> {
+    api.with_portfolios(move |p| p.remove_alert(&id, &alert_id)).await.map(Json)
+}
+
+async fn list_watchlists(State(api): State<ApiState>) -> Result<Json<Vec<Watchlist>>, ApiError> {
+    api.with_portfolios(|p| p.list_watchlists()).await.map(Json)
+}
+
+async fn create_watchlist(State(api): State<ApiState>, Json(request): Json<CreateWatchlistRequest>) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.create_watchlist(request)).await.map(Json)
+}
+
+async fn get_watchlist(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.get_watchlist(&id)).await.map(Json)
+}
+
+async fn update_watchlist(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateWatchlistRequest>,
+) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.update_watchlist(&id, request)).await.map(Json)
+}
+
+async fn delete_watchlist(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.delete_watchlist(&id)).await.map(Json)
+}
+
+async fn add_watchlist_symbols(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<WatchlistSymbolsRequest>,
+) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.add_watchlist_symbols(&id, &request.symbols)).await.map(Json)
+}
+
+async fn remove_watchlist_symbol(
+    State(api): State<ApiState>,
+    Path((id, symbol)): Path<(String, String)>,
+) -> Result<Json<Watchlist>, ApiError> {
+    api.with_portfolios(move |p| p.remove_watchlist_symbol(&id, &symbol)).await.map(Json)
+}
+
+async fn watchlist_quotes(
+    State(api): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): QueryParams,
+) -> Result<Json<WatchlistQuotesResponse>, ApiError> {
+    api.get_watchlist_quotes(&id, query.get("indicators").map(String::as_str)).await.map(Json)
+}
+
+async fn list_orders(State(api): State<ApiState>, Query(query): QueryParams) -> Result<Json<Vec<Order>>, ApiError> {
+    let (portfolio_id, status) = order_filter_from_query(&query)?;
+    api.with_portfolios(move |p| p.list_orders(portfolio_id.as_deref(), status)).await.map(Json)
+}
+
+async fn place_order(State(api): State<ApiState>, Json(request): Json<PlaceOrderRequest>) -> Result<Json<Order>, ApiError> {
+    api.place_order(request).await.map(Json)
+}
+
+async fn process_orders(State(api): State<ApiState>) -> Result<Json<Vec<Order>>, ApiError> {
+    api.process_orders_once().await.map(Json)
+}
+
+async fn get_order(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Order>, ApiError> {
+    api.with_portfolios(move |p| p.get_order(&id)).await.map(Json)
+}
+
+async fn cancel_order(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Order>, ApiError> {
+    api.with_portfolios(move |p| p.cancel_order(&id)).await.map(Json)
+}
+
+async fn scheduler_status(State(api): State<ApiState>) -> Json<SchedulerStatus> {
+    Json(api.scheduler().status())
+}
+
+async fn start_scheduler(State(api): State<ApiState>) -> Json<SchedulerStatus> {
+    Json(api.scheduler().set_running(true))
+}
+
+async fn stop_scheduler(State(api): State<ApiState>) -> Json<SchedulerStatus> {
+    Json(api.scheduler().set_running(false))
+}
+
+async fn add_job(State(api): State<ApiState>, Json(request): Json<JobRequest>) -> Result<Json<Job>, ApiError> {
+    api.add_scheduled_job(request).await.map(Json)
+}
+
+async fn get_job(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    api.scheduler().job(&id).map(Json)
+}
+
+async fn remove_job(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    api.scheduler().remove_job(&id).map(Json)
+}
+
+async fn start_job(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    api.scheduler().set_enabled(&id, true).map(Json)
+}
+
+async fn stop_job(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    api.scheduler().set_enabled(&id, false).map(Json)
+}
+
+async fn run_job(State(api): State<ApiState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    api.run_job_now(&id).await.map(Json)
+}
+
+async fn stream(ws: WebSocketUpgrade, State(api): State<ApiState>, Query(query): QueryParams) -> Response {
+    let (interval, subscriptions) = stream_settings_from_query(&query);
+    ws.on_upgrade(move |socket| run_stream(socket, api, interval, subscriptions))
+}
+
+async fn run_stream(mut socket: WebSocket, api: ApiState, interval: Duration, mut subscriptions: BTreeSet<String>) {
+    let initial = StreamMessage::Subscriptions { tickers: subscriptions.iter().cloned().collect() };
+    if send_stream_message(&mut socket, &initial).await.is_err() {
+        return;
+    }
+
+    let mut gauge = metrics::StreamGauge::open(subscriptions.len());
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if subscriptions.is_empty() {
+                    continue;
+                }
+                let tickers: Vec<String> = subscriptions.iter().cloned().collect();
+                let message = api.get_stream_quotes(&tickers).await;
+                if send_stream_message(&mut socket, &message).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    let (reply, push_now) = apply_stream_command(&mut subscriptions, &text);
+                    gauge.set_subscriptions(subscriptions.len());
+                    if push_now {
+                        ticker.reset_immediately();
+                    }
+                    if send_stream_message(&mut socket, &reply).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {} // pings are answered by axum; binary frames are ignored
+            },
+        }
+    }
+}
+
+// Same events as the std server's /api/v1/sse; KeepAlive sends the heartbeats
+async fn sse_events(
+    State(api): State<ApiState>,
+    Query(query): QueryParams,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let settings = sse_settings_from_query(&query, api.max_tickers_per_request())?;
+    let last_event_id = sse::last_event_id(headers.get("last-event-id").and_then(|v| v.to_str().ok()), &query);
+    let session = sse::SseSession::open(api, settings, last_event_id);
+
+    let events = futures::stream::unfold(session, |mut session| async move {
+        loop {
+            let events = session.next_events().await;
+            if !events.is_empty() {
+                return Some((futures::stream::iter(events), session));
+            }
+        }
+    })
+    .flatten()
+    .map(|event| Event::default().id(event.id.to_string()).event(event.event).data(event.data));
+    let retry = futures::stream::once(async { Event::default().retry(sse::RECONNECT_DELAY) });
+
+    Ok(Sse::new(retry.chain(events).map(Ok))
+        .keep_alive(KeepAlive::new().interval(sse::HEARTBEAT_INTERVAL).text("heartbeat")))
+}
+
+async fn send_stream_message(socket: &mut WebSocket, message: &StreamMessage) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(message).map_err(axum::Error::new)?;
+    socket.send(Message::Text(json)).await
+}
@@ -0,0 +1,374 @@
+// calendar.rs - NYSE/Nasdaq trading calendar
+//
+// Both exchanges keep the same schedule: a 9:30-16:00 regular session in New York time, with
+// pre-market trading from 4:00 and post-market until 20:00. They're closed on weekends and NYSE
+// holidays, and close at 13:00 (post-market until 17:00) on the half days around Independence
+// Day, Thanksgiving and Christmas. There's no timezone database in the build, so New York time
+// comes from the US DST rules in force since 2007.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Utc, Weekday};
+use serde::Serialize;
+
+use crate::api::ApiError;
+
+pub const EXCHANGE_TIMEZONE: &str = "America/New_York";
+
+// Seconds after midnight New York time
+const PRE_OPEN: i64 = 4 * 3600;
+const REGULAR_OPEN: i64 = 9 * 3600 + 30 * 60;
+const REGULAR_CLOSE: i64 = 16 * 3600;
+const EARLY_CLOSE: i64 = 13 * 3600;
+const POST_CLOSE: i64 = 20 * 3600;
+const EARLY_POST_CLOSE: i64 = 17 * 3600;
+
+const EST_OFFSET: i32 = -5 * 3600;
+const EDT_OFFSET: i32 = -4 * 3600;
+
+// Longest span `trading_days` will list
+const MAX_CALENDAR_DAYS: i64 = 366;
+
+// Unscheduled full-day closures
+const SPECIAL_CLOSURES: [(i32, u32, u32, &str); 5] = [
+    (2007, 1, 2, "National Day of Mourning (Gerald Ford)"),
+    (2012, 10, 29, "Hurricane Sandy"),
+    (2012, 10, 30, "Hurricane Sandy"),
+    (2018, 12, 5, "National Day of Mourning (George H.W. Bush)"),
+    (2025, 1, 9, "National Day of Mourning (Jimmy Carter)"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketSession {
+    Pre,
+    Regular,
+    Post,
+    Closed,
+}
+
+impl MarketSession {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketSession::Pre => "PRE",
+            MarketSession::Regular => "OPEN",
+            MarketSession::Post => "POST",
+            MarketSession::Closed => "CLOSED",
+        }
+    }
+}
+
+// One trading day's session boundaries
+#[derive(Debug, Clone, Copy)]
+pub struct SessionHours {
+    pub pre_open: DateTime<Utc>,
+    pub open: DateTime<Utc>,
+    pub close: DateTime<Utc>,
+    pub post_close: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradingDay {
+    pub date: String,
+    pub is_trading_day: bool,
+    pub holiday: Option<String>,
+    pub early_close: bool,
+    // RFC 3339 in New York time, None when closed
+    pub pre_open: Option<String>,
+    pub open: Option<String>,
+    pub close: Option<String>,
+    pub post_close: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketStatus {
+    pub status: String, // PRE, OPEN, POST or CLOSED
+    pub timezone: String,
+    pub exchange_time: String,
+    pub is_trading_day: bool,
+    pub holiday: Option<String>,
+    pub early_close: bool,
+    pub next_open: String,
+    pub next_close: String,
+}
+
+// New York's UTC offset at an instant: EDT from 2:00 EST on the second Sunday of March (07:00
+// UTC) until 2:00 EDT on the first Sunday of November (06:00 UTC)
+pub fn eastern_offset(time: DateTime<Utc>) -> FixedOffset {
+    let year = time.year();
+    let dst_start = nth_weekday(year, 3, Weekday::Sun, 2).and_hms_opt(7, 0, 0).unwrap().and_utc();
+    let dst_end = nth_weekday(year, 11, Weekday::Sun, 1).and_hms_opt(6, 0, 0).unwrap().and_utc();
+    let secs = if time >= dst_start && time < dst_end { EDT_OFFSET } else { EST_OFFSET };
+    FixedOffset::east_opt(secs).unwrap()
+}
+
+pub fn new_york_time(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    time.with_timezone(&eastern_offset(time))
+}
+
+// `timestamp` in the exchange's local time. New York listings get the offset in force at that
+// instant; other exchanges the fixed offset their chart metadata reports.
+pub fn exchange_time(timestamp: i64, timezone: &str, gmt_offset_secs: i64) -> Option<DateTime<FixedOffset>> {
+    let time = DateTime::from_timestamp(timestamp, 0)?;
+    if timezone == EXCHANGE_TIMEZONE {
+        return Some(new_york_time(time));
+    }
+    let offset = FixedOffset::east_opt(gmt_offset_secs as i32)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    Some(time.with_timezone(&offset))
+}
+
+// Why the exchange is closed on a weekday, if it is
+pub fn holiday(date: NaiveDate) -> Option<&'static str> {
+    let year = date.year();
+    if let Some(&(.., name)) = SPECIAL_CLOSURES.iter()
+        .find(|&&(y, m, d, _)| (y, m, d) == (year, date.month(), date.day()))
+    {
+        return Some(name);
+    }
+
+    let fixed = |month, day| observed(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+    let holidays = [
+        (new_years_day(year), "New Year's Day"),
+        (Some(nth_weekday(year, 1, Weekday::Mon, 3)), "Martin Luther King Jr. Day"),
+        (Some(nth_weekday(year, 2, Weekday::Mon, 3)), "Washington's Birthday"),
+        (Some(easter(year) - Duration::days(2)), "Good Friday"),
+        (Some(last_weekday(year, 5, Weekday::Mon)), "Memorial Day"),
+        ((year >= 2022).then(|| fixed(6, 19)), "Juneteenth"),
+        (Some(fixed(7, 4)), "Independence Day"),
+        (Some(nth_weekday(year, 9, Weekday::Mon, 1)), "Labor Day"),
+        (Some(nth_weekday(year, 11, Weekday::Thu, 4)), "Thanksgiving Day"),
+        (Some(fixed(12, 25)), "Christmas Day"),
+    ];
+    holidays.into_iter().find(|(day, _)| *day == Some(date)).map(|(_, name)| name)
+}
+
+pub fn is_trading_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && holiday(date).is_none()
+}
+
+// July 3rd and Christmas Eve when the holiday itself falls on a weekday, and the day after
+// Thanksgiving
+pub fn is_early_close(date: NaiveDate) -> bool {
+    if !is_trading_day(date) {
+        return false;
+    }
+    let midweek = matches!(date.weekday(), Weekday::Mon | Weekday::Tue | Weekday::Wed | Weekday::Thu);
+    match (date.month(), date.day()) {
+        (7, 3) | (12, 24) => midweek,
+        _ => date.pred_opt() == Some(nth_weekday(date.year(), 11, Weekday::Thu, 4)),
+    }
+}
+
+pub fn session_hours(date: NaiveDate) -> Option<SessionHours> {
+    if !is_trading_day(date) {
+        return None;
+    }
+    let (close, post_close) = if is_early_close(date) {
+        (EARLY_CLOSE, EARLY_POST_CLOSE)
+    } else {
+        (REGULAR_CLOSE, POST_CLOSE)
+    };
+    Some(SessionHours {
+        pre_open: at_local(date, PRE_OPEN),
+        open: at_local(date, REGULAR_OPEN),
+        close: at_local(date, close),
+        post_close: at_local(date, post_close),
+    })
+}
+
+pub fn session_at(time: DateTime<Utc>) -> MarketSession {
+    let Some(hours) = session_hours(new_york_time(time).date_naive()) else {
+        return MarketSession::Closed;
+    };
+    if time < hours.pre_open || time >= hours.post_close {
+        MarketSession::Closed
+    } else if time < hours.open {
+        MarketSession::Pre
+    } else if time < hours.close {
+        MarketSession::Regular
+    } else {
+        MarketSession::Post
+    }
+}
+
+// Whether a bar starting at `timestamp` belongs to the regular session
+pub fn in_regular_session(timestamp: i64) -> bool {
+    DateTime::from_timestamp(timestamp, 0).is_some_and(|time| session_at(time) == MarketSession::Regular)
+}
+
+// Whether today in New York is a trading day
+pub fn is_trading_day_at(time: DateTime<Utc>) -> bool {
+    is_trading_day(new_york_time(time).date_naive())
+}
+
+pub fn next_open(time: DateTime<Utc>) -> DateTime<Utc> {
+    next_boundary(time, |hours| hours.open)
+}
+
+pub fn next_close(time: DateTime<Utc>) -> DateTime<Utc> {
+    next_boundary(time, |hours| hours.close)
+}
+
+pub fn market_status(now: DateTime<Utc>) -> MarketStatus {
+    let local = new_york_time(now);
+    let today = local.date_naive();
+    let is_weekday = !matches!(today.weekday(), Weekday::Sat | Weekday::Sun);
+    MarketStatus {
+        status: session_at(now).as_str().to_string(),
+        timezone: EXCHANGE_TIMEZONE.to_string(),
+        exchange_time: local.to_rfc3339(),
+        is_trading_day: is_trading_day(today),
+        holiday: holiday(today).filter(|_| is_weekday).map(str::to_string),
+        early_close: is_early_close(today),
+        next_open: new_york_time(next_open(now)).to_rfc3339(),
+        next_close: new_york_time(next_close(now)).to_rfc3339(),
+    }
+}
+
+// Every date from `from` to `to` inclusive, closed days included
+pub fn trading_days(from: NaiveDate, to: NaiveDate) -> Result<Vec<TradingDay>, ApiError> {
+    if to < from {
+        return Err(ApiError::InvalidDateRange(format!("from {} must not be after to {}", from, to)));
+    }
+    if (to - from).num_days() >= MAX_CALENDAR_DAYS {
+        return Err(ApiError::InvalidDateRange(format!("Calendar spans at most {} days", MAX_CALENDAR_DAYS)));
+    }
+
+    let local = |time: DateTime<Utc>| Some(new_york_time(time).to_rfc3339());
+    Ok(from.iter_days().take_while(|date| *date <= to).map(|date| {
+        let is_weekday = !matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let hours = session_hours(date);
+        TradingDay {
+            date: date.to_string(),
+            is_trading_day: hours.is_some(),
+            holiday: holiday(date).filter(|_| is_weekday).map(str::to_string),
+            early_close: is_early_close(date),
+            pre_open: hours.and_then(|h| local(h.pre_open)),
+            open: hours.and_then(|h| local(h.open)),
+            close: hours.and_then(|h| local(h.close)),
+            post_close: hours.and_then(|h| local(h.post_close)),
+        }
+    }).collect())
+}
+
+// First session boundary after `time`; there's always a trading day within a week
+fn next_boundary(time: DateTime<Utc>, boundary: fn(&SessionHours) -> DateTime<Utc>) -> DateTime<Utc> {
+    new_york_time(time).date_naive().iter_days()
+        .filter_map(session_hours)
+        .map(|hours| boundary(&hours))
+        .find(|at| *at > time)
+        .unwrap()
+}
+
+// `seconds` after midnight New York time. Session times are clear of the 2:00 DST switch, so the
+// offset at the standard-time reading is the one in force.
+fn at_local(date: NaiveDate, seconds: i64) -> DateTime<Utc> {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap() + Duration::seconds(seconds);
+    let standard = (naive - Duration::seconds(EST_OFFSET as i64)).and_utc();
+    let offset = eastern_offset(standard).local_minus_utc() as i64;
+    (naive - Duration::seconds(offset)).and_utc()
+}
+
+// Saturday holidays are observed on Friday, Sunday ones on Monday
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+// Except New Year's Day, which NYSE doesn't move back into the previous year
+fn new_years_day(year: i32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    match date.weekday() {
+        Weekday::Sat => None,
+        Weekday::Sun => Some(date + Duration::days(1)),
+        _ => Some(date),
+    }
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).unwrap()
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    let first_of_next = NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap();
+    let back = (first_of_next.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday() - 1) % 7 + 1;
+    first_of_next - Duration::days(back as i64)
+}
+
+// Western Easter Sunday (anonymous Gregorian computus)
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        date(year, month, day).and_hms_opt(hour, minute, 0).unwrap().and_utc()
+    }
+
+    #[test]
+    fn good_friday_follows_easter() {
+        assert_eq!(easter(2024), date(2024, 3, 31));
+        assert_eq!(holiday(date(2024, 3, 29)), Some("Good Friday"));
+        assert!(!is_trading_day(date(2024, 3, 29)));
+        assert!(is_trading_day(date(2024, 3, 28)));
+    }
+
+    #[test]
+    fn saturday_independence_day_is_observed_on_friday() {
+        // July 4th 2026 is a Saturday
+        assert_eq!(holiday(date(2026, 7, 3)), Some("Independence Day"));
+        assert!(!is_trading_day(date(2026, 7, 3)));
+        // The observed holiday has no half day before it
+        assert!(!is_early_close(date(2026, 7, 2)));
+        assert!(session_hours(date(2026, 7, 2)).is_some_and(|h| h.close == utc(2026, 7, 2, 20, 0)));
+    }
+
+    #[test]
+    fn day_after_thanksgiving_closes_at_one() {
+        assert_eq!(holiday(date(2024, 11, 28)), Some("Thanksgiving Day"));
+        assert!(is_early_close(date(2024, 11, 29)));
+        let hours = session_hours(date(2024, 11, 29)).unwrap();
+        assert_eq!(hours.close, utc(2024, 11, 29, 18, 0)); // 13:00 EST
+        assert_eq!(hours.post_close, utc(2024, 11, 29, 22, 0));
+        assert_eq!(session_at(utc(2024, 11, 29, 18, 30)), MarketSession::Post);
+    }
+
+    #[test]
+    fn dst_starts_on_the_second_sunday_of_march() {
+        assert_eq!(eastern_offset(utc(2024, 3, 10, 6, 59)).local_minus_utc(), EST_OFFSET);
+        assert_eq!(eastern_offset(utc(2024, 3, 10, 7, 0)).local_minus_utc(), EDT_OFFSET);
+        // 9:30 New York is 14:30 UTC the Friday before and 13:30 UTC the Monday after
+        assert_eq!(session_hours(date(2024, 3, 8)).unwrap().open, utc(2024, 3, 8, 14, 30));
+        assert_eq!(session_hours(date(2024, 3, 11)).unwrap().open, utc(2024, 3, 11, 13, 30));
+    }
+
+    #[test]
+    fn dst_ends_on_the_first_sunday_of_november() {
+        assert_eq!(eastern_offset(utc(2024, 11, 3, 5, 59)).local_minus_utc(), EDT_OFFSET);
+        assert_eq!(eastern_offset(utc(2024, 11, 3, 6, 0)).local_minus_utc(), EST_OFFSET);
+        assert_eq!(session_hours(date(2024, 11, 1)).unwrap().open, utc(2024, 11, 1, 13, 30));
+        assert_eq!(session_hours(date(2024, 11, 4)).unwrap().open, utc(2024, 11, 4, 14, 30));
+    }
+}
@@ -0,0 +1,244 @@
+// candle_store.rs - On-disk cache of chart candles per (symbol, interval)
+//
+// Each series remembers how far back it is known to be complete (`covers_from`) and when it was
+// last refreshed. A request inside that window is answered from disk while the series is fresh;
+// once stale, only the tail since the last stored bar is fetched and merged in.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Datelike, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::api::{validate_range_interval, ApiError};
+use crate::og::{adj_closes, chart_from_candles, to_candles, ChartResponse, Meta};
+use crate::types::Candle;
+
+const DAY: i64 = 86_400;
+
+// Tail fetches use the shortest range that reaches back past the last stored bar
+const TAIL_RANGES: [(&str, i64); 9] = [
+    ("1d", DAY),
+    ("5d", 5 * DAY),
+    ("1mo", 30 * DAY),
+    ("3mo", 91 * DAY),
+    ("6mo", 182 * DAY),
+    ("1y", 365 * DAY),
+    ("2y", 730 * DAY),
+    ("5y", 1826 * DAY),
+    ("10y", 3652 * DAY),
+];
+
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE series (
+        symbol TEXT NOT NULL,
+        interval TEXT NOT NULL,
+        covers_from INTEGER NOT NULL,
+        fetched_at INTEGER NOT NULL,
+        meta TEXT NOT NULL,
+        PRIMARY KEY (symbol, interval)
+    );
+    CREATE TABLE candles (
+        symbol TEXT NOT NULL,
+        interval TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        open REAL NOT NULL,
+        high REAL NOT NULL,
+        low REAL NOT NULL,
+        close REAL NOT NULL,
+        volume REAL,
+        adj_close REAL,
+        PRIMARY KEY (symbol, interval, timestamp)
+    );",
+];
+
+pub struct CachedSeries {
+    pub covers_from: i64,
+    pub fetched_at: i64,
+    pub meta: String, // JSON of the latest chart Meta
+    pub candles: Vec<Candle>,
+    pub adj_closes: HashMap<i64, f64>, // Yahoo's adjusted close by bar timestamp, as og::adj_closes
+}
+
+impl CachedSeries {
+    pub fn last_timestamp(&self) -> Option<i64> {
+        self.candles.last().map(|c| c.timestamp)
+    }
+
+    // Rebuilds a chart response holding the bars from `from` onwards
+    pub fn to_chart(&self, from: i64) -> Result<ChartResponse, ApiError> {
+        let meta: Meta = serde_json::from_str(&self.meta)
+            .map_err(|e| ApiError::StorageError(format!("Corrupt cached meta: {}", e)))?;
+        let start = self.candles.partition_point(|c| c.timestamp < from);
+        Ok(chart_from_candles(meta, &self.candles[start..], &self.adj_closes))
+    }
+}
+
+pub struct CandleStore {
+    conn: Arc<Mutex<Connection>>, // only locked on the blocking pool, see with_conn
+    ttl: Duration,
+}
+
+fn storage_error(e: rusqlite::Error) -> ApiError {
+    ApiError::StorageError(e.to_string())
+}
+
+impl CandleStore {
+    pub fn open(path: &PathBuf, ttl: Duration) -> Result<Self, ApiError> {
+        let mut conn = Connection::open(path).map_err(storage_error)?;
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(storage_error)?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction().map_err(storage_error)?;
+            tx.execute_batch(migration).map_err(storage_error)?;
+            tx.execute_batch(&format!("PRAGMA user_version = {}", i + 1)).map_err(storage_error)?;
+            tx.commit().map_err(storage_error)?;
+        }
+        Ok(Self { conn: Arc::new(Mutex::new(conn)), ttl })
+    }
+
+    pub fn is_fresh(&self, series: &CachedSeries, now: i64) -> bool {
+        now - series.fetched_at < self.ttl.as_secs() as i64
+    }
+
+    // Runs a query on the blocking pool so disk I/O never stalls the runtime's worker threads
+    async fn with_conn<T, F>(&self, job: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, ApiError> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || job(&mut conn.lock().unwrap()))
+            .await
+            .map_err(|e| ApiError::StorageError(format!("storage task failed: {}", e)))?
+    }
+
+    pub async fn load(&self, symbol: &str, interval: &str) -> Result<Option<CachedSeries>, ApiError> {
+        let (symbol, interval) = (symbol.to_string(), interval.to_string());
+        self.with_conn(move |conn| Self::load_series(conn, &symbol, &interval)).await
+    }
+
+    fn load_series(conn: &Connection, symbol: &str, interval: &str) -> Result<Option<CachedSeries>, ApiError> {
+        let header = conn.query_row(
+            "SELECT covers_from, fetched_at, meta FROM series WHERE symbol = ?1 AND interval = ?2",
+            params![symbol, interval],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?)),
+        ).optional().map_err(storage_error)?;
+
+        let Some((covers_from, fetched_at, meta)) = header else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, open, high, low, close, volume, adj_close FROM candles
+             WHERE symbol = ?1 AND interval = ?2 AND timestamp >= ?3 ORDER BY timestamp",
+        ).map_err(storage_error)?;
+        let rows = stmt.query_map(params![symbol, interval, covers_from], |row| {
+            let candle = Candle {
+                timestamp: row.get(0)?,
+                open: row.get(1)?,
+                high: row.get(2)?,
+                low: row.get(3)?,
+                close: row.get(4)?,
+                volume: row.get(5)?,
+            };
+            Ok((candle, row.get::<_, Option<f64>>(6)?))
+        }).and_then(|rows| rows.collect::<Result<Vec<_>, _>>()).map_err(storage_error)?;
+
+        let adj_closes = rows.iter()
+            .filter_map(|(candle, adj)| adj.map(|adj| (candle.timestamp, adj)))
+            .collect();
+        let candles = rows.into_iter().map(|(candle, _)| candle).collect();
+        Ok(Some(CachedSeries { covers_from, fetched_at, meta, candles, adj_closes }))
+    }
+
+    // Upserts the fetched bars (overwriting the still-forming last bar) and records the series as
+    // complete from `covers_from`. Bars older than that are dropped so the series has no gaps.
+    pub async fn merge(&self, symbol: &str, interval: &str, chart: &ChartResponse, covers_from: i64, now: i64) -> Result<(), ApiError> {
+        let result = chart.chart.result
+            .as_ref()
+            .and_then(|results| results.first())
+            .ok_or_else(|| ApiError::DataNotFound("No chart data found".to_string()))?;
+        let meta = serde_json::to_string(&result.meta)
+            .map_err(|e| ApiError::StorageError(e.to_string()))?;
+        let adjusted = adj_closes(result);
+        let rows: Vec<(Candle, Option<f64>)> = to_candles(result)
+            .into_iter()
+            .map(|candle| {
+                let adj = adjusted.get(&candle.timestamp).copied();
+                (candle, adj)
+            })
+            .collect();
+        let (symbol, interval) = (symbol.to_string(), interval.to_string());
+        self.with_conn(move |conn| Self::write_series(conn, &symbol, &interval, &rows, &meta, covers_from, now)).await
+    }
+
+    fn write_series(
+        conn: &mut Connection,
+        symbol: &str,
+        interval: &str,
+        rows: &[(Candle, Option<f64>)],
+        meta: &str,
+        covers_from: i64,
+        now: i64,
+    ) -> Result<(), ApiError> {
+        let tx = conn.transaction().map_err(storage_error)?;
+        // A split or dividend since the last fetch rescales Yahoo's whole adjusted history. The
+        // first fetched bar overlaps the stored ones, so its change in adj_close / close is carried
+        // back to the bars before it.
+        if let Some((first, Some(adj))) = rows.first().filter(|(first, _)| first.close > 0.0) {
+            let stored: Option<(f64, Option<f64>)> = tx.query_row(
+                "SELECT close, adj_close FROM candles WHERE symbol = ?1 AND interval = ?2 AND timestamp = ?3",
+                params![symbol, interval, first.timestamp],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional().map_err(storage_error)?;
+            if let Some((close, Some(stored_adj))) = stored.filter(|(close, _)| *close > 0.0) {
+                let rescale = (adj / first.close) / (stored_adj / close);
+                if (rescale - 1.0).abs() > 1e-9 {
+                    tx.execute(
+                        "UPDATE candles SET adj_close = adj_close * ?4 WHERE symbol = ?1 AND interval = ?2 AND timestamp < ?3",
+                        params![symbol, interval, first.timestamp, rescale],
+                    ).map_err(storage_error)?;
+                }
+            }
+        }
+        for (candle, adj_close) in rows {
+            tx.execute(
+                "INSERT OR REPLACE INTO candles (symbol, interval, timestamp, open, high, low, close, volume, adj_close)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![symbol, interval, candle.timestamp, candle.open, candle.high, candle.low, candle.close, candle.volume, adj_close],
+            ).map_err(storage_error)?;
+        }
+        tx.execute(
+            "DELETE FROM candles WHERE symbol = ?1 AND interval = ?2 AND timestamp < ?3",
+            params![symbol, interval, covers_from],
+        ).map_err(storage_error)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO series (symbol, interval, covers_from, fetched_at, meta) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![symbol, interval, covers_from, now, meta],
+        ).map_err(storage_error)?;
+        tx.commit().map_err(storage_error)
+    }
+}
+
+// Earliest timestamp a Yahoo range reaches back to; None for ranges the cache doesn't understand
+pub fn range_start(range: &str, now: i64) -> Option<i64> {
+    match range {
+        "max" => Some(0),
+        "ytd" => {
+            let year = Utc.timestamp_opt(now, 0).single()?.year();
+            Some(Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single()?.timestamp())
+        }
+        _ => TAIL_RANGES.iter()
+            .find(|(name, _)| *name == range)
+            .map(|(_, secs)| now - secs),
+    }
+}
+
+// Shortest range that reaches back to `since` and Yahoo accepts for this interval
+pub fn tail_range(interval: &str, since: i64, now: i64) -> Option<&'static str> {
+    TAIL_RANGES.iter()
+        .find(|(name, secs)| now - secs <= since && validate_range_interval(name, interval).is_ok())
+        .map(|(name, _)| *name)
+}
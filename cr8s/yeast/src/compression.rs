@@ -0,0 +1,104 @@
+// compression.rs - gzip/brotli response bodies for api::http_server
+//
+// The client's Accept-Encoding picks the coding: whichever of br and gzip has the higher q-value,
+// br on a tie, and q=0 rules one out. Small bodies go out as-is since the framing would eat the
+// savings. The axum backend gets the same from tower-http's CompressionLayer, so this module is
+// only built for the std server.
+
+use std::io::{self, Write};
+
+use flate2::write::GzEncoder;
+
+pub const MIN_COMPRESS_BYTES: usize = 1024;
+
+// Middle of brotli's 0-11 range: most of the size win at a fraction of max-quality CPU
+const BROTLI_QUALITY: u32 = 5;
+const BROTLI_WINDOW_BITS: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Brotli,
+}
+
+impl ContentEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Brotli => "br",
+        }
+    }
+
+    // None when the header allows neither (or only identity)
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut wildcard = None;
+        let (mut gzip, mut brotli) = (None, None);
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';');
+            let coding = parts.next().unwrap_or("").trim().to_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f64>().ok())
+                .unwrap_or(1.0);
+            match coding.as_str() {
+                "gzip" | "x-gzip" => gzip = Some(quality),
+                "br" => brotli = Some(quality),
+                "*" => wildcard = Some(quality),
+                _ => {}
+            }
+        }
+        let gzip = gzip.or(wildcard).unwrap_or(0.0);
+        let brotli = brotli.or(wildcard).unwrap_or(0.0);
+        if brotli > 0.0 && brotli >= gzip {
+            Some(ContentEncoding::Brotli)
+        } else if gzip > 0.0 {
+            Some(ContentEncoding::Gzip)
+        } else {
+            None
+        }
+    }
+
+    pub fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentEncoding::Brotli => {
+                let mut output = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(&mut output, 4096, BROTLI_QUALITY, BROTLI_WINDOW_BITS);
+                    encoder.write_all(body)?;
+                }
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_honours_q_values() {
+        assert_eq!(ContentEncoding::negotiate("gzip;q=0, br"), Some(ContentEncoding::Brotli));
+        assert_eq!(ContentEncoding::negotiate("br;q=0, gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(ContentEncoding::negotiate("br;q=0"), None);
+        assert_eq!(ContentEncoding::negotiate("gzip;q=0.8, br;q=0.5"), Some(ContentEncoding::Gzip));
+    }
+
+    #[test]
+    fn negotiate_wildcard_covers_unlisted_codings() {
+        assert_eq!(ContentEncoding::negotiate("*;q=0.5"), Some(ContentEncoding::Brotli));
+        assert_eq!(ContentEncoding::negotiate("br;q=0, *;q=0.5"), Some(ContentEncoding::Gzip));
+        assert_eq!(ContentEncoding::negotiate("*;q=0"), None);
+    }
+
+    #[test]
+    fn negotiate_identity_only_sends_plain() {
+        assert_eq!(ContentEncoding::negotiate("identity"), None);
+        assert_eq!(ContentEncoding::negotiate(""), None);
+    }
+}
@@ -0,0 +1,368 @@
+// config.rs - ApiConfig and where its settings come from
+//
+// Settings are layered: the defaults below, then a TOML or YAML config file (--config, or
+// YEAST_CONFIG), then environment variables, then command-line flags. Each layer only overrides
+// what it sets, so a file can hold the deployment's settings while a flag tweaks one of them for
+// a run. File keys are the flag names with underscores (`cache_ttl = 300` for --cache-ttl);
+// environment variables are the same names upper-cased with a YEAST_ prefix, except for the
+// existing MARKET_DATA_PROVIDER, OPTIONS_SOURCE and RUST_LOG. Provider API keys may sit in the
+// file's [provider_keys] table; ALPHAVANTAGE_API_KEY / POLYGON_API_KEY still win when set.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::api::ApiError;
+use crate::arg_value;
+use crate::auth::{KeySource, RateLimit};
+use crate::logging::{LogConfig, LogFormat};
+use crate::notify::NotificationConfig;
+use crate::og::OptionsSource;
+use crate::portfolio::StorageConfig;
+use crate::providers::{ProviderConfig, ProviderKeys};
+use crate::rate_limit::DEFAULT_REQUESTS_PER_MINUTE;
+use crate::retry::{BreakerConfig, RetryPolicy};
+use crate::tls::{SniCertificate, TlsConfig};
+
+// Configuration for different deployment scenarios
+pub struct ApiConfig {
+    pub bind: String, // interface the HTTP server listens on
+    pub port: u16,
+    pub grpc_addr: String, // with the `grpc` feature
+    pub cors_origins: Vec<String>, // browser origins allowed to call the API; "*" allows any without credentials, empty none
+    pub tls: TlsConfig, // HTTPS when a certificate is set
+    pub rate_limit: Option<RateLimit>, // default per-key quota once API keys are configured
+    pub api_keys: Option<KeySource>, // None leaves the servers open
+    pub cache_ttl: u64, // seconds; upstream responses
+    pub candle_cache_ttl: Option<u64>, // seconds; the candle cache, cache_ttl when unset
+    pub max_tickers_per_request: usize, // symbols per quotes, historical or SSE request
+    pub storage: StorageConfig, // where portfolios live; SQLite files are migrated on startup
+    pub candle_cache: Option<PathBuf>, // SQLite file for historical bars, fresh for candle_cache_ttl
+    pub yahoo_session: Option<PathBuf>, // JSON file keeping the Yahoo crumb and cookies across restarts
+    pub alert_check_interval: u64, // seconds between background alert evaluations
+    pub order_check_interval: u64, // seconds between attempts to fill open paper orders
+    pub notifications: NotificationConfig, // where triggered alerts are delivered
+    pub provider: ProviderConfig, // market data backend
+    pub options_source: OptionsSource, // option chains when the provider is Yahoo
+    pub upstream_requests_per_minute: u32, // per host, for every outbound request
+    pub upstream_host_limits: BTreeMap<String, u32>, // requests per minute for hosts that need their own rate
+    pub retry: RetryPolicy, // for every outbound fetch
+    pub circuit_breaker: BreakerConfig, // per upstream host
+    pub log: LogConfig, // level filter and text/JSON output
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1".to_string(),
+            port: 8080,
+            grpc_addr: "127.0.0.1:50051".to_string(),
+            cors_origins: vec!["http://localhost:3000".to_string()],
+            tls: TlsConfig::default(),
+            rate_limit: Some(default_rate_limit()),
+            api_keys: None,
+            cache_ttl: 60, // 1 minute cache
+            candle_cache_ttl: None,
+            max_tickers_per_request: 10,
+            storage: StorageConfig::Memory,
+            candle_cache: None,
+            yahoo_session: None,
+            alert_check_interval: 60,
+            order_check_interval: 30,
+            notifications: NotificationConfig::default(),
+            provider: ProviderConfig::default(),
+            options_source: OptionsSource::default(),
+            upstream_requests_per_minute: DEFAULT_REQUESTS_PER_MINUTE,
+            upstream_host_limits: BTreeMap::new(),
+            retry: RetryPolicy::default(),
+            circuit_breaker: BreakerConfig::default(),
+            log: LogConfig::default(),
+        }
+    }
+}
+
+fn default_rate_limit() -> RateLimit {
+    RateLimit {
+        requests_per_minute: 60,
+        requests_per_hour: 1000,
+    }
+}
+
+impl ApiConfig {
+    // Defaults, then the config file, then the environment, then command-line flags
+    pub fn load() -> Result<Self, ApiError> {
+        let file = match arg_value("--config").or_else(|| env_value("YEAST_CONFIG")) {
+            Some(path) => ConfigLayer::from_file(Path::new(&path))?,
+            None => ConfigLayer::default(),
+        };
+        let mut config = Self::default();
+        // The provider is resolved once every layer is in, so a key missing for a provider a
+        // later layer replaces isn't an error
+        let mut provider = None;
+        let mut provider_keys = ProviderKeys::default();
+        for layer in [file, ConfigLayer::from_env()?, ConfigLayer::from_args()] {
+            provider = layer.provider.clone().or(provider);
+            provider_keys.alphavantage = layer.provider_keys.alphavantage.clone().or(provider_keys.alphavantage);
+            provider_keys.polygon = layer.provider_keys.polygon.clone().or(provider_keys.polygon);
+            config.apply(layer)?;
+        }
+        if let Some(name) = provider {
+            config.provider = ProviderConfig::from_name(&name, &provider_keys)?;
+        }
+        Ok(config)
+    }
+
+    pub fn server_addr(&self) -> String {
+        format!("{}:{}", self.bind, self.port)
+    }
+
+    pub fn candle_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.candle_cache_ttl.unwrap_or(self.cache_ttl))
+    }
+
+    fn apply(&mut self, layer: ConfigLayer) -> Result<(), ApiError> {
+        if let Some(bind) = layer.bind {
+            self.bind = bind;
+        }
+        if let Some(port) = layer.port {
+            self.port = port;
+        }
+        if let Some(addr) = layer.grpc_addr {
+            self.grpc_addr = addr;
+        }
+        if let Some(origins) = layer.cors_origins {
+            self.cors_origins = origins;
+        }
+        if let Some(path) = layer.tls_cert {
+            self.tls.cert = Some(path);
+        }
+        if let Some(path) = layer.tls_key {
+            self.tls.key = Some(path);
+        }
+        if let Some(certificates) = layer.tls_sni {
+            self.tls.sni = certificates;
+        }
+        if let Some(port) = layer.http_redirect_port {
+            self.tls.redirect_port = Some(port);
+        }
+        if layer.rate_limit_per_minute.is_some() || layer.rate_limit_per_hour.is_some() {
+            let current = self.rate_limit.clone().unwrap_or_else(default_rate_limit);
+            self.rate_limit = Some(RateLimit {
+                requests_per_minute: layer.rate_limit_per_minute.unwrap_or(current.requests_per_minute),
+                requests_per_hour: layer.rate_limit_per_hour.unwrap_or(current.requests_per_hour),
+            });
+        }
+        if let Some(path) = layer.api_keys {
+            self.api_keys = Some(KeySource::File(path));
+        }
+        if let Some(path) = layer.api_keys_db {
+            self.api_keys = Some(KeySource::Sqlite(path));
+        }
+        if let Some(secs) = layer.cache_ttl {
+            self.cache_ttl = secs;
+        }
+        if let Some(secs) = layer.candle_cache_ttl {
+            self.candle_cache_ttl = Some(secs);
+        }
+        if let Some(max) = layer.max_tickers_per_request {
+            self.max_tickers_per_request = max.max(1);
+        }
+        if let Some(path) = layer.portfolio_db {
+            self.storage = StorageConfig::Sqlite(path);
+        }
+        if let Some(path) = layer.candle_cache {
+            self.candle_cache = Some(path);
+        }
+        if let Some(path) = layer.yahoo_session {
+            self.yahoo_session = Some(path);
+        }
+        if let Some(secs) = layer.alert_interval {
+            self.alert_check_interval = secs;
+        }
+        if let Some(secs) = layer.order_interval {
+            self.order_check_interval = secs;
+        }
+        if let Some(name) = layer.options_source {
+            self.options_source = OptionsSource::from_name(&name).map_err(ApiError::InvalidParameters)?;
+        }
+        if let Some(rpm) = layer.upstream_requests_per_minute {
+            self.upstream_requests_per_minute = rpm.max(1);
+        }
+        for (host, rpm) in layer.upstream_host_limits.unwrap_or_default() {
+            self.upstream_host_limits.insert(host.to_lowercase(), rpm.max(1));
+        }
+        if let Some(attempts) = layer.fetch_attempts {
+            self.retry.max_attempts = attempts.max(1);
+        }
+        if let Some(failures) = layer.breaker_threshold {
+            self.circuit_breaker.failure_threshold = failures.max(1);
+        }
+        if let Some(secs) = layer.breaker_cooldown {
+            self.circuit_breaker.cooldown = Duration::from_secs(secs);
+        }
+        if let Some(filter) = layer.log_level {
+            self.log.filter = filter;
+        }
+        if let Some(format) = layer.log_format {
+            self.log.format = LogFormat::parse(&format)?;
+        }
+        Ok(())
+    }
+}
+
+// One source of settings; anything left None keeps the value from the layers below
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigLayer {
+    bind: Option<String>,
+    port: Option<u16>,
+    grpc_addr: Option<String>,
+    cors_origins: Option<Vec<String>>,
+    tls_cert: Option<PathBuf>,
+    tls_key: Option<PathBuf>,
+    tls_sni: Option<Vec<SniCertificate>>, // file only: [[tls_sni]] tables of hostnames, cert and key
+    http_redirect_port: Option<u16>,
+    rate_limit_per_minute: Option<u32>,
+    rate_limit_per_hour: Option<u32>,
+    api_keys: Option<PathBuf>,
+    api_keys_db: Option<PathBuf>,
+    cache_ttl: Option<u64>,
+    candle_cache_ttl: Option<u64>,
+    max_tickers_per_request: Option<usize>,
+    portfolio_db: Option<PathBuf>,
+    candle_cache: Option<PathBuf>,
+    yahoo_session: Option<PathBuf>,
+    alert_interval: Option<u64>,
+    order_interval: Option<u64>,
+    provider: Option<String>,
+    provider_keys: ProviderKeys,
+    options_source: Option<String>,
+    upstream_requests_per_minute: Option<u32>,
+    upstream_host_limits: Option<BTreeMap<String, u32>>, // a [upstream_host_limits] table; host=rpm,... in env and flags
+    fetch_attempts: Option<u32>,
+    breaker_threshold: Option<u32>,
+    breaker_cooldown: Option<u64>,
+    log_level: Option<String>,
+    log_format: Option<String>,
+}
+
+impl ConfigLayer {
+    // .toml, or .yaml / .yml
+    fn from_file(path: &Path) -> Result<Self, ApiError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::InvalidParameters(format!("Could not read config file {}: {}", path.display(), e)))?;
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+        let parsed = match extension.as_deref() {
+            Some("toml") => toml::from_str(&text).map_err(|e| e.to_string()),
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&text).map_err(|e| e.to_string()),
+            _ => Err("expected a .toml, .yaml or .yml file".to_string()),
+        };
+        parsed.map_err(|e| ApiError::InvalidParameters(format!("Invalid config file {}: {}", path.display(), e)))
+    }
+
+    fn from_env() -> Result<Self, ApiError> {
+        Ok(Self {
+            bind: env_value("YEAST_BIND"),
+            port: env_parsed("YEAST_PORT")?,
+            grpc_addr: env_value("YEAST_GRPC_ADDR"),
+            cors_origins: env_value("YEAST_CORS_ORIGINS").map(|origins| split_list(&origins)),
+            tls_cert: env_value("YEAST_TLS_CERT").map(PathBuf::from),
+            tls_key: env_value("YEAST_TLS_KEY").map(PathBuf::from),
+            tls_sni: None,
+            http_redirect_port: env_parsed("YEAST_HTTP_REDIRECT_PORT")?,
+            rate_limit_per_minute: env_parsed("YEAST_RATE_LIMIT_PER_MINUTE")?,
+            rate_limit_per_hour: env_parsed("YEAST_RATE_LIMIT_PER_HOUR")?,
+            api_keys: env_value("YEAST_API_KEYS").map(PathBuf::from),
+            api_keys_db: env_value("YEAST_API_KEYS_DB").map(PathBuf::from),
+            cache_ttl: env_parsed("YEAST_CACHE_TTL")?,
+            candle_cache_ttl: env_parsed("YEAST_CANDLE_CACHE_TTL")?,
+            max_tickers_per_request: env_parsed("YEAST_MAX_TICKERS_PER_REQUEST")?,
+            portfolio_db: env_value("YEAST_PORTFOLIO_DB").map(PathBuf::from),
+            candle_cache: env_value("YEAST_CANDLE_CACHE").map(PathBuf::from),
+            yahoo_session: env_value("YEAST_YAHOO_SESSION").map(PathBuf::from),
+            alert_interval: env_parsed("YEAST_ALERT_INTERVAL")?,
+            order_interval: env_parsed("YEAST_ORDER_INTERVAL")?,
+            provider: env_value("MARKET_DATA_PROVIDER"),
+            provider_keys: ProviderKeys::default(), // read by ProviderConfig itself
+            options_source: env_value("OPTIONS_SOURCE"),
+            upstream_requests_per_minute: env_parsed("YEAST_UPSTREAM_REQUESTS_PER_MINUTE")?,
+            upstream_host_limits: env_value("YEAST_UPSTREAM_HOST_LIMITS")
+                .map(|limits| parse_host_limits(&limits).map_err(|e| ApiError::InvalidParameters(format!("YEAST_UPSTREAM_HOST_LIMITS: {}", e))))
+                .transpose()?,
+            fetch_attempts: env_parsed("YEAST_FETCH_ATTEMPTS")?,
+            breaker_threshold: env_parsed("YEAST_BREAKER_THRESHOLD")?,
+            breaker_cooldown: env_parsed("YEAST_BREAKER_COOLDOWN")?,
+            log_level: env_value("RUST_LOG"),
+            log_format: env_value("YEAST_LOG_FORMAT"),
+        })
+    }
+
+    fn from_args() -> Self {
+        Self {
+            bind: arg_value("--bind"),
+            port: arg_parsed("--port"),
+            grpc_addr: arg_value("--grpc-addr"),
+            cors_origins: arg_value("--cors-origins").map(|origins| split_list(&origins)),
+            tls_cert: arg_value("--tls-cert").map(PathBuf::from),
+            tls_key: arg_value("--tls-key").map(PathBuf::from),
+            tls_sni: None,
+            http_redirect_port: arg_parsed("--http-redirect-port"),
+            rate_limit_per_minute: arg_parsed("--rate-limit-per-minute"),
+            rate_limit_per_hour: arg_parsed("--rate-limit-per-hour"),
+            api_keys: arg_value("--api-keys").map(PathBuf::from),
+            api_keys_db: arg_value("--api-keys-db").map(PathBuf::from),
+            cache_ttl: arg_parsed("--cache-ttl"),
+            candle_cache_ttl: arg_parsed("--candle-cache-ttl"),
+            max_tickers_per_request: arg_parsed("--max-tickers-per-request"),
+            portfolio_db: arg_value("--portfolio-db").map(PathBuf::from),
+            candle_cache: arg_value("--candle-cache").map(PathBuf::from),
+            yahoo_session: arg_value("--yahoo-session").map(PathBuf::from),
+            alert_interval: arg_parsed("--alert-interval"),
+            order_interval: arg_parsed("--order-interval"),
+            provider: arg_value("--provider"),
+            provider_keys: ProviderKeys::default(), // kept off the command line
+            options_source: arg_value("--options-source"),
+            upstream_requests_per_minute: arg_parsed("--upstream-requests-per-minute"),
+            upstream_host_limits: arg_value("--upstream-host-limits").and_then(|limits| parse_host_limits(&limits).ok()),
+            fetch_attempts: arg_parsed("--fetch-attempts"),
+            breaker_threshold: arg_parsed("--breaker-threshold"),
+            breaker_cooldown: arg_parsed("--breaker-cooldown"),
+            log_level: arg_value("--log-level"),
+            log_format: arg_value("--log-format"),
+        }
+    }
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn env_parsed<T: FromStr>(name: &str) -> Result<Option<T>, ApiError> {
+    env_value(name)
+        .map(|v| v.parse().map_err(|_| ApiError::InvalidParameters(format!("{} must be a number, got '{}'", name, v))))
+        .transpose()
+}
+
+// Unparseable flag values are ignored, as they always have been
+fn arg_parsed<T: FromStr>(flag: &str) -> Option<T> {
+    arg_value(flag).and_then(|v| v.parse().ok())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+// "query1.finance.yahoo.com=60,api.polygon.io=5"
+fn parse_host_limits(value: &str) -> Result<BTreeMap<String, u32>, String> {
+    split_list(value)
+        .iter()
+        .map(|entry| {
+            let (host, rpm) = entry.split_once('=').ok_or_else(|| format!("expected host=requests_per_minute, got '{}'", entry))?;
+            let rpm = rpm.trim().parse().map_err(|_| format!("'{}' is not a number of requests per minute", rpm.trim()))?;
+            Ok((host.trim().to_lowercase(), rpm))
+        })
+        .collect()
+}
@@ -0,0 +1,191 @@
+// export.rs - Historical data as CSV or Parquet (/api/v1/historical?format=..., CLI `export`)
+//
+// One row per candle in long format: symbol and the OHLCV columns, then one column per indicator
+// label across all tickers, empty/null where a ticker doesn't have it or is still in warmup.
+// Parquet goes through arrow and needs the `parquet` feature.
+
+use std::collections::BTreeSet;
+
+use crate::api::{ApiError, CandleData, HistoricalDataResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Result<Self, ApiError> {
+        match name.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            other => Err(ApiError::InvalidParameters(format!("Unknown format {} (expected json, csv or parquet)", other))),
+        }
+    }
+
+    // From a file name, for the CLI; anything unrecognised is CSV
+    pub fn from_path(path: &str) -> Self {
+        match path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).as_deref() {
+            Some("json") => ExportFormat::Json,
+            Some("parquet") => ExportFormat::Parquet,
+            _ => ExportFormat::Csv,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+// The response flattened into columns
+struct Table<'a> {
+    symbols: Vec<&'a str>,
+    candles: Vec<&'a CandleData>,
+    indicators: Vec<(String, Vec<Option<f64>>)>, // label, one value per row
+}
+
+impl<'a> Table<'a> {
+    fn new(response: &'a HistoricalDataResponse) -> Self {
+        let labels: BTreeSet<&String> = response.data.values()
+            .filter_map(|d| d.indicators.as_ref())
+            .flat_map(|indicators| indicators.keys())
+            .collect();
+
+        let mut table = Table {
+            symbols: Vec::new(),
+            candles: Vec::new(),
+            indicators: labels.iter().map(|label| (label.to_string(), Vec::new())).collect(),
+        };
+        for (symbol, data) in &response.data {
+            for (i, candle) in data.candles.iter().enumerate() {
+                table.symbols.push(symbol);
+                table.candles.push(candle);
+                for (label, column) in table.indicators.iter_mut() {
+                    let value = data.indicators.as_ref()
+                        .and_then(|indicators| indicators.get(label))
+                        .and_then(|values| values.get(i).copied().flatten());
+                    column.push(value);
+                }
+            }
+        }
+        table
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_number(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+pub fn to_csv(response: &HistoricalDataResponse) -> String {
+    let table = Table::new(response);
+    let mut header = vec!["symbol", "timestamp", "datetime", "open", "high", "low", "close", "volume", "adj_close"]
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    header.extend(table.indicators.iter().map(|(label, _)| csv_field(label)));
+
+    let mut out = header.join(",");
+    out.push('\n');
+    for (row, (symbol, candle)) in table.symbols.iter().zip(&table.candles).enumerate() {
+        let mut fields = vec![
+            csv_field(symbol),
+            candle.timestamp.to_string(),
+            candle.datetime.clone(),
+            candle.open.to_string(),
+            candle.high.to_string(),
+            candle.low.to_string(),
+            candle.close.to_string(),
+            csv_number(candle.volume),
+            csv_number(candle.adj_close),
+        ];
+        fields.extend(table.indicators.iter().map(|(_, column)| csv_number(column[row])));
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(feature = "parquet")]
+pub fn to_parquet(response: &HistoricalDataResponse) -> Result<Vec<u8>, ApiError> {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    let table = Table::new(response);
+    let price = |f: fn(&CandleData) -> f64| -> ArrayRef { Arc::new(Float64Array::from_iter_values(table.candles.iter().map(|c| f(c)))) };
+    let optional = |f: fn(&CandleData) -> Option<f64>| -> ArrayRef { Arc::new(table.candles.iter().map(|c| f(c)).collect::<Float64Array>()) };
+
+    let mut fields = vec![
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("timestamp", DataType::Int64, false),
+        Field::new("datetime", DataType::Utf8, false),
+        Field::new("open", DataType::Float64, false),
+        Field::new("high", DataType::Float64, false),
+        Field::new("low", DataType::Float64, false),
+        Field::new("close", DataType::Float64, false),
+        Field::new("volume", DataType::Float64, true),
+        Field::new("adj_close", DataType::Float64, true),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(table.symbols.iter())),
+        Arc::new(Int64Array::from_iter_values(table.candles.iter().map(|c| c.timestamp))),
+        Arc::new(StringArray::from_iter_values(table.candles.iter().map(|c| c.datetime.as_str()))),
+        price(|c| c.open),
+        price(|c| c.high),
+        price(|c| c.low),
+        price(|c| c.close),
+        optional(|c| c.volume),
+        optional(|c| c.adj_close),
+    ];
+    for (label, values) in &table.indicators {
+        fields.push(Field::new(label, DataType::Float64, true));
+        columns.push(Arc::new(values.iter().copied().collect::<Float64Array>()));
+    }
+
+    let to_error = |e: &dyn std::fmt::Display| ApiError::CalculationError(format!("Parquet export failed: {}", e));
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| to_error(&e))?;
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).map_err(|e| to_error(&e))?;
+    writer.write(&batch).map_err(|e| to_error(&e))?;
+    writer.close().map_err(|e| to_error(&e))?;
+    Ok(buffer)
+}
+
+#[cfg(not(feature = "parquet"))]
+pub fn to_parquet(_response: &HistoricalDataResponse) -> Result<Vec<u8>, ApiError> {
+    Err(ApiError::InvalidParameters("Parquet export needs the `parquet` feature".to_string()))
+}
+
+// The response in the requested format, as bytes for a file or an HTTP body
+pub fn render(response: &HistoricalDataResponse, format: ExportFormat) -> Result<Vec<u8>, ApiError> {
+    match format {
+        ExportFormat::Json => serde_json::to_vec(response).map_err(|e| ApiError::CalculationError(e.to_string())),
+        ExportFormat::Csv => Ok(to_csv(response).into_bytes()),
+        ExportFormat::Parquet => to_parquet(response),
+    }
+}
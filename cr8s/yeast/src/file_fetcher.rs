@@ -0,0 +1,231 @@
+// file_fetcher.rs - ChartFetcher over OHLCV files on disk, for offline analysis and CI
+//
+// Looks for <dir>/<SYMBOL>.csv, then <dir>/<SYMBOL>.json (an array of objects). Columns are
+// matched by name, case-insensitively, through a ColumnMapping; timestamps may be unix seconds or
+// milliseconds, YYYY-MM-DD, "YYYY-MM-DD HH:MM:SS" (UTC) or RFC 3339. Ranges are measured back from
+// the file's last bar rather than from today, so a fixture from years ago still answers "1mo".
+// Bars are served at whatever interval the file holds.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use futures::future::BoxFuture;
+
+use crate::candle_store::range_start;
+use crate::og::{ChartFetcher, ChartQueryOptions, ChartResponse, FetchError};
+use crate::providers::chart_response;
+use crate::types::Candle;
+
+// Column names for each field. The defaults cover yeast's own CSV export and the usual
+// Yahoo/broker downloads (Date, Open, High, Low, Close, Volume).
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    pub timestamp: Vec<String>,
+    pub open: Vec<String>,
+    pub high: Vec<String>,
+    pub low: Vec<String>,
+    pub close: Vec<String>,
+    pub volume: Vec<String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        Self {
+            timestamp: names(&["timestamp", "date", "datetime", "time"]),
+            open: names(&["open"]),
+            high: names(&["high"]),
+            low: names(&["low"]),
+            close: names(&["close"]),
+            volume: names(&["volume"]),
+        }
+    }
+}
+
+impl ColumnMapping {
+    // Overrides from "field=Column,field=Column", e.g. "close=Adj Close,timestamp=Day"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut mapping = ColumnMapping::default();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (field, column) = pair.split_once('=').ok_or_else(|| format!("Expected field=column, got {}", pair))?;
+            let target = match field.trim() {
+                "timestamp" | "date" => &mut mapping.timestamp,
+                "open" => &mut mapping.open,
+                "high" => &mut mapping.high,
+                "low" => &mut mapping.low,
+                "close" => &mut mapping.close,
+                "volume" => &mut mapping.volume,
+                other => return Err(format!("Unknown candle field {}", other)),
+            };
+            *target = vec![column.trim().to_string()];
+        }
+        Ok(mapping)
+    }
+}
+
+// Index of the first of `names` present in the header
+fn find_column(header: &[String], names: &[String]) -> Option<usize> {
+    names.iter().find_map(|name| header.iter().position(|h| h.eq_ignore_ascii_case(name)))
+}
+
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<i64>() {
+        return Some(if n > 100_000_000_000 { n / 1000 } else { n }); // milliseconds past ~5138 AD in seconds
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(dt.and_utc().timestamp());
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+// Splits one CSV line, honouring double-quoted fields with "" escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn parse_csv(contents: &str, mapping: &ColumnMapping) -> Result<Vec<Candle>, FetchError> {
+    let mut lines = contents.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = lines.next().map(split_csv_line).ok_or("Empty CSV file")?
+        .into_iter().map(|h| h.trim().to_string()).collect();
+    let column = |names: &[String], field: &str| find_column(&header, names).ok_or_else(|| format!("No {} column (tried {})", field, names.join(", ")));
+    let (ts, open, high, low, close) = (
+        column(&mapping.timestamp, "timestamp")?,
+        column(&mapping.open, "open")?,
+        column(&mapping.high, "high")?,
+        column(&mapping.low, "low")?,
+        column(&mapping.close, "close")?,
+    );
+    let volume = find_column(&header, &mapping.volume);
+
+    let mut candles = Vec::new();
+    for (line_no, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let number = |i: usize| fields.get(i).and_then(|v| v.trim().parse::<f64>().ok());
+        let bad_row = || format!("Row {}: could not parse {}", line_no + 2, line);
+        // Rows with a blank or "null" price (holidays in some downloads) are skipped
+        let (Some(o), Some(h), Some(l), Some(c)) = (number(open), number(high), number(low), number(close)) else {
+            continue;
+        };
+        candles.push(Candle {
+            timestamp: fields.get(ts).and_then(|v| parse_timestamp(v)).ok_or_else(bad_row)?,
+            open: o,
+            high: h,
+            low: l,
+            close: c,
+            volume: volume.and_then(number),
+        });
+    }
+    Ok(candles)
+}
+
+fn parse_json(contents: &str, mapping: &ColumnMapping) -> Result<Vec<Candle>, FetchError> {
+    let rows: Vec<HashMap<String, serde_json::Value>> = serde_json::from_str(contents)?;
+    let field = |row: &HashMap<String, serde_json::Value>, names: &[String]| -> Option<serde_json::Value> {
+        names.iter().find_map(|name| row.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone()))
+    };
+    let number = |value: Option<serde_json::Value>| match value? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+
+    let mut candles = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let timestamp = match field(row, &mapping.timestamp) {
+            Some(serde_json::Value::Number(n)) => n.as_i64().and_then(|n| parse_timestamp(&n.to_string())),
+            Some(serde_json::Value::String(s)) => parse_timestamp(&s),
+            _ => None,
+        }.ok_or_else(|| format!("Row {}: missing or unreadable timestamp", i))?;
+        let (Some(open), Some(high), Some(low), Some(close)) = (
+            number(field(row, &mapping.open)),
+            number(field(row, &mapping.high)),
+            number(field(row, &mapping.low)),
+            number(field(row, &mapping.close)),
+        ) else {
+            continue;
+        };
+        candles.push(Candle { timestamp, open, high, low, close, volume: number(field(row, &mapping.volume)) });
+    }
+    Ok(candles)
+}
+
+pub struct FileChartFetcher {
+    dir: PathBuf,
+    mapping: ColumnMapping,
+}
+
+impl FileChartFetcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), mapping: ColumnMapping::default() }
+    }
+
+    pub fn with_columns(mut self, mapping: ColumnMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    fn file_for(&self, ticker: &str) -> Option<PathBuf> {
+        ["csv", "json"].iter()
+            .map(|ext| self.dir.join(format!("{}.{}", ticker.to_uppercase(), ext)))
+            .find(|path| path.is_file())
+    }
+
+    // Every bar in the ticker's file, oldest first
+    pub fn load(&self, ticker: &str) -> Result<Vec<Candle>, FetchError> {
+        let path = self.file_for(ticker)
+            .ok_or_else(|| format!("No {}.csv or {}.json in {}", ticker.to_uppercase(), ticker.to_uppercase(), self.dir.display()))?;
+        let contents = std::fs::read_to_string(&path)?;
+        let mut candles = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => parse_json(&contents, &self.mapping),
+            _ => parse_csv(&contents, &self.mapping),
+        }.map_err(|e| format!("{}: {}", display(&path), e))?;
+        candles.sort_by_key(|c| c.timestamp);
+        candles.dedup_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+
+    fn chart(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError> {
+        let candles = self.load(ticker)?;
+        let last = candles.last().map_or(0, |c| c.timestamp);
+        let period = opts.period.unwrap_or_else(|| (range_start(opts.range, last).unwrap_or(0), last + 1));
+        let window = ChartQueryOptions { interval: opts.interval, range: opts.range, include_pre_post: false, period: Some(period) };
+        Ok(chart_response("file", ticker, &window, candles))
+    }
+}
+
+fn display(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+impl ChartFetcher for FileChartFetcher {
+    fn fetch_sync(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError> {
+        self.chart(ticker, opts)
+    }
+
+    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
+        Box::pin(async move { self.chart(ticker, opts) })
+    }
+}
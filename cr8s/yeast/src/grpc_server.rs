@@ -0,0 +1,230 @@
+// grpc_server.rs - tonic backend for quotes, candles and indicators (proto/yeast.proto)
+//
+// Mirrors the REST routes for non-browser clients: the same StockDataApi calls, typed messages
+// instead of JSON, and server-streaming for candles and live quotes. API keys, when configured,
+// are read from the x-api-key or authorization metadata. Enabled with the `grpc` feature.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+use crate::api::{self, ApiError, HistoricalDataRequest, IndicatorConfig, QuoteRequest, StockDataApi, StreamMessage};
+use crate::auth::{self, AuthError, Authenticator};
+
+pub mod proto {
+    tonic::include_proto!("yeast.v1");
+}
+
+use proto::market_data_server::{MarketData, MarketDataServer};
+
+impl From<ApiError> for Status {
+    fn from(e: ApiError) -> Self {
+        let message = e.to_string();
+        match e {
+            ApiError::InvalidTicker(_)
+            | ApiError::InvalidDateRange(_)
+            | ApiError::InvalidParameters(_)
+            | ApiError::InvalidFields(_) => {
+                Status::invalid_argument(message)
+            }
+            ApiError::DataNotFound(_) => Status::not_found(message),
+            ApiError::RateLimited(_) => Status::resource_exhausted(message),
+            ApiError::FetchError(_) => Status::unavailable(message),
+            ApiError::CalculationError(_) | ApiError::StorageError(_) => Status::internal(message),
+        }
+    }
+}
+
+impl From<AuthError> for Status {
+    fn from(e: AuthError) -> Self {
+        match e {
+            AuthError::QuotaExceeded { .. } => Status::resource_exhausted(e.to_string()),
+            AuthError::MissingKey | AuthError::InvalidKey => Status::unauthenticated(e.to_string()),
+        }
+    }
+}
+
+fn check_api_key(authenticator: Option<&Authenticator>, request: Request<()>) -> Result<Request<()>, Status> {
+    let Some(authenticator) = authenticator else {
+        return Ok(request);
+    };
+    let metadata = |name: &str| request.metadata().get(name).and_then(|v| v.to_str().ok());
+    authenticator.check(auth::presented_key(metadata("x-api-key"), metadata("authorization"), None))?;
+    Ok(request)
+}
+
+impl From<api::Quote> for proto::Quote {
+    fn from(q: api::Quote) -> Self {
+        Self {
+            symbol: q.symbol,
+            price: q.price,
+            change: q.change,
+            change_percent: q.change_percent,
+            volume: q.volume,
+            bid: q.bid,
+            ask: q.ask,
+            high_52w: q.high_52w,
+            low_52w: q.low_52w,
+            market_cap: q.market_cap,
+            pe_ratio: q.pe_ratio,
+            dividend_yield: q.dividend_yield,
+            pre_market_price: q.pre_market_price,
+            post_market_price: q.post_market_price,
+            last_updated: q.last_updated,
+        }
+    }
+}
+
+impl From<api::CandleData> for proto::Candle {
+    fn from(c: api::CandleData) -> Self {
+        Self {
+            timestamp: c.timestamp,
+            datetime: c.datetime,
+            open: c.open,
+            high: c.high,
+            low: c.low,
+            close: c.close,
+            volume: c.volume,
+            adj_close: c.adj_close,
+            session: c.session,
+        }
+    }
+}
+
+fn quotes_reply(quotes: impl IntoIterator<Item = (String, api::Quote)>, errors: Vec<String>) -> proto::QuotesReply {
+    proto::QuotesReply {
+        quotes: quotes.into_iter().map(|(symbol, quote)| (symbol, quote.into())).collect(),
+        errors,
+    }
+}
+
+fn historical_request(request: proto::HistoricalRequest, indicators: Option<Vec<IndicatorConfig>>) -> HistoricalDataRequest {
+    HistoricalDataRequest {
+        tickers: vec![request.ticker.to_uppercase()],
+        interval: request.interval,
+        range: request.range,
+        start_date: request.start_date,
+        end_date: request.end_date,
+        include_indicators: Some(indicators.is_some()),
+        indicators: indicators.filter(|configs| !configs.is_empty()),
+        parallel_indicators: None,
+        min_candles: None,
+        include_returns: None,
+        benchmark: None,
+        adjust: Some(request.adjust),
+        resample_to: request.resample_to,
+        missing_data: request.missing_data,
+        transform: request.transform,
+        brick_size: request.brick_size,
+        include_prepost: request.include_prepost,
+        limit: None, // candles are streamed instead
+        cursor: None,
+    }
+}
+
+pub struct MarketDataService {
+    api: Arc<StockDataApi>,
+}
+
+impl MarketDataService {
+    // The one ticker's data, or the error the REST response would have listed for it
+    async fn ticker_data(&self, request: HistoricalDataRequest) -> Result<api::TickerData, Status> {
+        let ticker = request.tickers[0].clone();
+        let mut response = self.api.get_historical_data(request).await?;
+        response.data.remove(&ticker).ok_or_else(|| {
+            let reason = if response.errors.is_empty() { format!("No data for {}", ticker) } else { response.errors.join("; ") };
+            Status::unavailable(reason)
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl MarketData for MarketDataService {
+    async fn get_quotes(&self, request: Request<proto::QuotesRequest>) -> Result<Response<proto::QuotesReply>, Status> {
+        let proto::QuotesRequest { tickers, include_prepost } = request.into_inner();
+        if tickers.is_empty() {
+            return Err(Status::invalid_argument("tickers is empty"));
+        }
+        let response = self.api.get_quotes(QuoteRequest { tickers, fields: None, include_prepost }).await?;
+        Ok(Response::new(quotes_reply(response.quotes, response.errors)))
+    }
+
+    type GetHistoricalStream = BoxStream<'static, Result<proto::Candle, Status>>;
+
+    async fn get_historical(&self, request: Request<proto::HistoricalRequest>) -> Result<Response<Self::GetHistoricalStream>, Status> {
+        let data = self.ticker_data(historical_request(request.into_inner(), None)).await?;
+        let candles = stream::iter(data.candles.into_iter().map(|c| Ok(c.into())));
+        Ok(Response::new(candles.boxed()))
+    }
+
+    async fn compute_indicators(&self, request: Request<proto::IndicatorsRequest>) -> Result<Response<proto::IndicatorsReply>, Status> {
+        let request = request.into_inner();
+        let candles = request.candles.ok_or_else(|| Status::invalid_argument("candles is required"))?;
+        let configs = request.indicators.into_iter().map(|spec| IndicatorConfig {
+            name: spec.name,
+            params: Some(spec.params.into_iter().map(|(k, v)| (k, serde_json::Value::from(v))).collect()),
+        }).collect();
+
+        let data = self.ticker_data(historical_request(candles, Some(configs))).await?;
+        let indicators: HashMap<String, proto::IndicatorSeries> = data.indicators.unwrap_or_default()
+            .into_iter()
+            .map(|(label, values)| (label, proto::IndicatorSeries { values: values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect() }))
+            .collect();
+
+        Ok(Response::new(proto::IndicatorsReply {
+            ticker: data.symbol,
+            timestamps: data.candles.iter().map(|c| c.timestamp).collect(),
+            indicators,
+            insufficient_data: data.insufficient_data.into_iter().map(|i| i.indicator).collect(),
+        }))
+    }
+
+    type StreamQuotesStream = BoxStream<'static, Result<proto::QuotesReply, Status>>;
+
+    async fn stream_quotes(&self, request: Request<proto::StreamQuotesRequest>) -> Result<Response<Self::StreamQuotesStream>, Status> {
+        let request = request.into_inner();
+        let tickers: Vec<String> = request.tickers.iter().map(|t| t.to_uppercase()).collect();
+        if tickers.is_empty() {
+            return Err(Status::invalid_argument("tickers is empty"));
+        }
+        let interval = match request.interval_secs {
+            0 => api::DEFAULT_STREAM_INTERVAL,
+            secs => Duration::from_secs(secs.into()).max(api::MIN_STREAM_INTERVAL),
+        };
+
+        // Ends when the client disconnects and tonic drops the stream
+        let api = Arc::clone(&self.api);
+        let ticks = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(interval));
+        let quotes = ticks.then(move |_| {
+            let api = Arc::clone(&api);
+            let tickers = tickers.clone();
+            async move {
+                match api.get_stream_quotes(&tickers).await {
+                    StreamMessage::Quotes { quotes, errors } => Ok(quotes_reply(quotes, errors)),
+                    StreamMessage::Error { message } => Err(Status::unavailable(message)),
+                    StreamMessage::Subscriptions { .. } => Ok(proto::QuotesReply::default()),
+                }
+            }
+        });
+        Ok(Response::new(quotes.boxed()))
+    }
+}
+
+pub async fn serve(api: Arc<StockDataApi>, addr: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let addr = addr.parse()?;
+    tracing::info!("gRPC server (yeast.v1.MarketData) running on {}", addr);
+
+    let authenticator = api.authenticator().cloned();
+    Server::builder()
+        .add_service(MarketDataServer::with_interceptor(MarketDataService { api }, move |request: Request<()>| {
+            check_api_key(authenticator.as_deref(), request)
+        }))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// src/indicators/factory.rs
+// Builds the indicators a request names ({"name": "SMA", "params": {"period": 20}}) from the shared
+// indicators-core registry, rejecting unknown names and options the indicator doesn't declare.
+
+use std::collections::HashMap;
+
+use crate::indicators::BoundIndicator;
+
+// Registry key for a requested name: "SMA" -> "sma", "Keltner Channels" -> "keltner_channels"
+pub fn indicator_key(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+// Label for the output series: the key, plus sorted params when any were given ("sma(period=20)")
+pub fn indicator_label(key: &str, params: &HashMap<String, serde_json::Value>) -> String {
+    if params.is_empty() {
+        return key.to_string();
+    }
+    let mut pairs: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    pairs.sort();
+    format!("{}({})", key, pairs.join(","))
+}
+
+pub fn build_indicator(
+    name: &str,
+    params: HashMap<String, serde_json::Value>,
+) -> Result<(String, BoundIndicator), String> {
+    let key = indicator_key(name);
+    if key.is_empty() {
+        return Err("Indicator name is empty".to_string());
+    }
+    let Some(core) = indicators_core::INDICATOR_REGISTRY.get(key.as_str()) else {
+        return Err(unknown_indicator(name));
+    };
+    let options = indicators_core::IndicatorOptions { values: params };
+    indicators_core::validate_options(core.as_ref(), &options).map_err(|e| format!("{}: {}", key, e))?;
+
+    let label = indicator_label(&key, &options.values);
+    let indicator = BoundIndicator::from_registry(&key, options.values).ok_or_else(|| unknown_indicator(name))?;
+    Ok((label, indicator))
+}
+
+// Every config built in order; the same indicator with the same params twice is an error, as
+// both would write the same output series
+pub fn build_indicators<'a>(
+    configs: impl IntoIterator<Item = (&'a str, HashMap<String, serde_json::Value>)>,
+) -> Result<Vec<(String, BoundIndicator)>, String> {
+    let mut indicators: Vec<(String, BoundIndicator)> = Vec::new();
+    for (name, params) in configs {
+        let (label, indicator) = build_indicator(name, params)?;
+        if indicators.iter().any(|(existing, _)| *existing == label) {
+            return Err(format!("{} is requested more than once", label));
+        }
+        indicators.push((label, indicator));
+    }
+    Ok(indicators)
+}
+
+// Splits "sma(period=20),rsi,keltner_channels(ema_period=20,multiplier=1.5)" at the commas between entries,
+// leaving the ones inside parentheses alone
+pub fn split_specs(spec: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0usize);
+    for (i, c) in spec.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(spec[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(spec[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+fn unknown_indicator(name: &str) -> String {
+    let key = indicator_key(name);
+    let mut known: Vec<&str> = indicators_core::INDICATOR_REGISTRY.keys().copied().collect();
+    known.sort_unstable();
+    let closest = known.iter()
+        .map(|k| (edit_distance(&key, k), *k))
+        .filter(|(distance, k)| *distance <= 2 || k.starts_with(key.as_str()) || key.starts_with(k))
+        .min();
+    match closest {
+        Some((_, suggestion)) => format!("Unknown indicator {}; did you mean {}?", name, suggestion),
+        None => format!("Unknown indicator {}; available: {}", name, known.join(", ")),
+    }
+}
+
+// Levenshtein distance, for suggesting the registry key a misspelt name was meant to be
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
@@ -2,122 +2,90 @@
 
 use crate::types::Candle;
 use std::collections::HashMap;
-use std::thread;
 use std::sync::Arc;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
 
-pub mod sma;
-pub mod ema;
-pub mod rsi;
-pub mod macd;
-pub mod bollinger_bands;
-pub mod vwap;
-pub mod atr;
-pub mod stochastic;
-pub mod cci;
-pub mod adx;
-pub mod parabolic_sar;
-pub mod obv;
-pub mod cmf;
-pub mod williams_r;
-pub mod ichimoku;
-pub mod momentum;
-pub mod tema;
-pub mod dema;
-pub mod kama;
-pub mod wma;
-pub mod hma;
-pub mod frama;
-pub mod chandelier_exit;
-pub mod trix;
-pub mod mfi;
-pub mod force_index;
-pub mod ease_of_movement;
-pub mod accum_dist_line;
-pub mod price_volume_trend;
-pub mod volume_oscillator;
-pub mod ultimate_oscillator;
-pub mod detrended_price_oscillator;
-pub mod roc;
-pub mod z_score;
-pub mod gmma;
-pub mod schaff_trend_cycle;
-pub mod fibonacci_retracement;
-pub mod kalman_filter_smoother; 
-pub mod heikin_ashi_slope; 
-pub mod percent_b; 
+pub mod relative_strength;
+pub mod factory;
 
-pub use sma::SMA;
-pub use ema::EMA;
-pub use rsi::RSI;
-pub use macd::MACD;
-pub use bollinger_bands::BollingerBands;
-pub use vwap::VWAP;
-pub use atr::ATR;
-pub use stochastic::Stochastic;
-pub use cci::CCI;
-pub use adx::ADX;
-pub use parabolic_sar::ParabolicSAR;
-pub use obv::OBV;
-pub use cmf::CMF;
-pub use williams_r::WilliamsR;
-pub use ichimoku::Ichimoku;
-pub use momentum::Momentum;
-pub use tema::Tema;
-pub use dema::Dema;
-pub use kama::Kama;
-pub use wma::WMA;
-pub use hma::Hma;
-pub use frama::Frama;
-pub use chandelier_exit::ChandelierExit;
-pub use trix::TRIX;
-pub use mfi::MFI;
-pub use force_index::ForceIndex;
-pub use ease_of_movement::EaseOfMovement;
-pub use accum_dist_line::AccumDistLine;
-pub use price_volume_trend::PriceVolumeTrend;
-pub use volume_oscillator::VolumeOscillator;
-pub use ultimate_oscillator::UltimateOscillator;
-pub use detrended_price_oscillator::DetrendedPriceOscillator;
-pub use roc::RateOfChange;
-pub use z_score::ZScore;
-pub use gmma::GMMA;
-pub use schaff_trend_cycle::SchaffTrendCycle;
-pub use fibonacci_retracement::FibonacciRetracement;
-pub use heikin_ashi_slope::HeikinAshiSlope;
-pub use kalman_filter_smoother::KalmanFilterSmoother;
-pub use percent_b::PercentB;
-
-pub trait TechnicalIndicator: Sync {
-    fn name(&self) -> &'static str;
-    fn compute(&self, candles: &[Candle]) -> Vec<Option<f64>>;
-}
+// Indicator implementations live in indicators-core; runners hold them bound to their options
+pub use indicators_core::BoundIndicator;
+pub use relative_strength::{relative_strength, relative_strength_slope};
+pub use factory::{build_indicators, split_specs};
 
+#[derive(Clone)]
 pub struct IndicatorRunner {
-    pub indicators: Vec<(String, Arc<dyn TechnicalIndicator + Send + Sync>)>,
+    pub indicators: Vec<(String, BoundIndicator)>,
+    // Bounds how many indicators compute at once; None runs on rayon's global pool
+    pool: Option<Arc<ThreadPool>>,
 }
 
 impl IndicatorRunner {
-    pub fn run(&self, candles: &[Candle]) -> HashMap<String, Vec<Option<f64>>> {
-        let mut handles = Vec::new();
+    pub fn new(indicators: Vec<(String, BoundIndicator)>) -> Self {
+        Self { indicators, pool: None }
+    }
+
+    // A pool of `threads` workers that runners can share via `with_pool`
+    pub fn build_pool(threads: usize) -> Result<Arc<ThreadPool>, ThreadPoolBuildError> {
+        ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(|i| format!("indicators-{}", i))
+            .build()
+            .map(Arc::new)
+    }
+
+    pub fn with_pool(mut self, pool: Option<Arc<ThreadPool>>) -> Self {
+        self.pool = pool;
+        self
+    }
 
-        for (name, indicator) in self.indicators.iter() {
-            let name = name.clone();
-            let candles = candles.to_vec();
-            let indicator = Arc::clone(indicator);
-        
-            let handle = thread::spawn(move || {
-                let values = indicator.compute(&candles);
-                (name, values)
-            });
-        
-            handles.push(handle);
+    fn install<R: Send>(&self, job: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(job),
+            None => job(),
         }
+    }
+
+    // Computes every indicator in parallel over the same borrowed candles
+    pub fn run(&self, candles: &[Candle]) -> HashMap<String, Vec<Option<f64>>> {
+        self.install(|| {
+            self.indicators
+                .par_iter()
+                .map(|(name, indicator)| (name.clone(), indicator.compute(candles)))
+                .collect()
+        })
+    }
 
-        let mut map = std::collections::HashMap::new();
-        for handle in handles {
-            let (name, values) = handle.join().expect("Thread panicked");
-            map.insert(name, values);
+    // Computes every indicator for every candle series on the rayon pool, borrowing the
+    // candles instead of cloning them per thread. Results are returned in input order.
+    pub fn run_many(&self, series: &[&[Candle]]) -> Vec<HashMap<String, Vec<Option<f64>>>> {
+        let results: Vec<(usize, String, Vec<Option<f64>>)> = self.install(|| {
+            series
+                .par_iter()
+                .enumerate()
+                .flat_map(|(i, candles)| {
+                    self.indicators.par_iter().map(move |(name, indicator)| {
+                        (i, name.clone(), indicator.compute(candles))
+                    })
+                })
+                .collect()
+        });
+
+        let mut maps = vec![HashMap::new(); series.len()];
+        for (i, name, values) in results {
+            maps[i].insert(name, values);
         }
-        map
+        maps
+    }
+
+    // Indicators that can't produce a value from `candle_count` candles, with the count they need.
+    // `min_candles` raises every indicator's requirement to at least that floor.
+    pub fn insufficient(&self, candle_count: usize, min_candles: usize) -> Vec<(String, usize)> {
+        self.indicators
+            .iter()
+            .map(|(name, indicator)| (name.clone(), indicator.warmup().max(min_candles)))
+            .filter(|(_, needed)| *needed > candle_count)
+            .collect()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,37 @@
+// src/indicators/relative_strength.rs
+// Relative strength compares two series, so it lives here as free functions rather than a TechnicalIndicator.
+
+use crate::types::Candle;
+use std::collections::HashMap;
+
+/// Ratio of the asset close to the benchmark close on matching timestamps, rebased so the
+/// first matched bar is 100. Aligned to `asset`; bars with no benchmark candle are None.
+pub fn relative_strength(asset: &[Candle], benchmark: &[Candle]) -> Vec<Option<f64>> {
+    let benchmark_closes: HashMap<i64, f64> = benchmark.iter().map(|c| (c.timestamp, c.close)).collect();
+    let mut base: Option<f64> = None;
+
+    asset.iter()
+        .map(|candle| {
+            let bench_close = *benchmark_closes.get(&candle.timestamp)?;
+            if bench_close <= 0.0 || candle.close <= 0.0 {
+                return None;
+            }
+            let ratio = candle.close / bench_close;
+            let base = *base.get_or_insert(ratio);
+            Some(ratio / base * 100.0)
+        })
+        .collect()
+}
+
+/// 1.0 when the line is above its value `lookback` bars ago (outperforming), -1.0 when below, 0.0 when flat.
+pub fn relative_strength_slope(line: &[Option<f64>], lookback: usize) -> Vec<Option<f64>> {
+    (0..line.len())
+        .map(|i| {
+            if lookback == 0 || i < lookback {
+                return None;
+            }
+            let (now, then) = (line[i]?, line[i - lookback]?);
+            Some(if now > then { 1.0 } else if now < then { -1.0 } else { 0.0 })
+        })
+        .collect()
+}
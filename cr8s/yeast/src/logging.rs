@@ -0,0 +1,66 @@
+// logging.rs - tracing subscriber setup
+//
+// The servers, fetchers and background jobs log through `tracing`; this installs the subscriber
+// that writes it out. The filter takes RUST_LOG-style directives ("debug", "info,yeast=debug"):
+// --log-level wins over RUST_LOG, which wins over "info". Text is for terminals; JSON puts one
+// object per line, with the enclosing request span's fields, for log shippers. Logs go to stderr
+// so CLI output on stdout stays clean.
+
+use tracing_subscriber::EnvFilter;
+
+use crate::api::ApiError;
+
+const DEFAULT_FILTER: &str = "info";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, ApiError> {
+        match name.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(ApiError::InvalidParameters(format!("Unknown log format '{}'. Valid formats: text, json", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub filter: String,
+    pub format: LogFormat,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: std::env::var("RUST_LOG").ok().filter(|f| !f.is_empty()).unwrap_or_else(|| DEFAULT_FILTER.to_string()),
+            format: LogFormat::default(),
+        }
+    }
+}
+
+// Installs the global subscriber; call once, before anything logs
+pub fn init(config: &LogConfig) -> Result<(), ApiError> {
+    let filter = EnvFilter::try_new(&config.filter)
+        .map_err(|e| ApiError::InvalidParameters(format!("Invalid log level '{}': {}", config.filter, e)))?;
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    let installed = match config.format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(false).try_init(),
+    };
+    installed.map_err(|e| ApiError::InvalidParameters(format!("Could not install {} logging: {}", config.format.as_str(), e)))
+}
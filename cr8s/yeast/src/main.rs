@@ -9,14 +9,63 @@ mod types;
 mod options_math;
 mod api; // The API layer we just created
 mod og;
+mod portfolio;
+mod candle_store;
+mod notify;
+mod providers;
+mod retry;
+mod response_cache;
+mod metrics;
+mod auth;
+mod openapi;
+mod export;
+mod file_fetcher;
+mod signals;
+mod vol_surface;
+mod unusual_activity;
+mod oi_profile;
+mod risk;
+mod optimizer;
+mod rebalance;
+mod orders;
+mod scheduler;
+mod calendar;
+mod logging;
+mod config;
+mod tls;
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+mod compression;
+mod sse;
+mod rate_limit;
+mod yahoo_session;
+mod request_id;
+mod validation;
+#[cfg(feature = "axum-server")]
+mod axum_server;
+#[cfg(feature = "grpc")]
+mod grpc_server;
 
 use api::*;
 use crate::indicators::*;
+use indicators_core::{
+    SMA, EMA, WMA, RSI, MACD, Stochastic, CCI, WilliamsR, BollingerBands, ATR, VWAP, OBV, CMF, ADX,
+    ParabolicSAR, SuperTrend, Aroon, KeltnerChannels, DonchianChannels, Ichimoku,
+};
 use crate::og::*;
+use crate::portfolio::PortfolioManager;
+use crate::candle_store::CandleStore;
+use crate::notify::{EmailConfig, Notifier, SinkConfig};
+use crate::export::ExportFormat;
+use crate::auth::Authenticator;
+use crate::file_fetcher::{ColumnMapping, FileChartFetcher};
+use crate::config::ApiConfig;
+use crate::rate_limit::HostRateLimiter;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    println!("🚀 Starting Stock Data API Server");
+    let mut config = ApiConfig::load()?;
+    logging::init(&config.log)?;
+    tracing::info!("🚀 Starting Stock Data API Server");
 
     // Initialize fetchers
     let chart_fetcher = Arc::new(AsyncFetcher::new());
@@ -26,7 +75,47 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let indicators = build_comprehensive_indicators();
     
     // Create API instance
-    let api = StockDataApi::new(chart_fetcher, options_fetcher, indicators);
+    let mut api = StockDataApi::new(chart_fetcher, options_fetcher, indicators)
+        .with_max_tickers_per_request(config.max_tickers_per_request)
+        .with_response_cache_ttl(std::time::Duration::from_secs(config.cache_ttl));
+    if let Some(secs) = arg_value("--indicator-timeout").and_then(|s| s.parse::<u64>().ok()) {
+        api = api.with_indicator_timeout(std::time::Duration::from_secs(secs));
+    }
+    if let Some(threads) = arg_value("--indicator-threads").and_then(|s| s.parse::<usize>().ok()) {
+        api = api.with_indicator_parallelism(threads)?;
+    }
+
+    retry::configure(
+        config.retry.clone(),
+        config.circuit_breaker.clone(),
+        HostRateLimiter::new(config.upstream_requests_per_minute).with_host_limits(config.upstream_host_limits.clone()),
+    );
+    yahoo_session::configure(config.yahoo_session.clone());
+    api = api.with_options_fetcher(config.options_source.build());
+    if let Some(provider) = config.provider.build() {
+        tracing::info!("Using {} for candles, quotes and options", provider.name());
+        api = api.with_provider(provider);
+    }
+    if let Some(dir) = arg_value("--candles-dir") {
+        let columns = match arg_value("--candle-columns") {
+            Some(spec) => ColumnMapping::parse(&spec)?,
+            None => ColumnMapping::default(),
+        };
+        tracing::info!("Reading candles from {}", dir);
+        api = api.with_chart_fetcher(Arc::new(FileChartFetcher::new(dir).with_columns(columns)));
+    }
+    if let Some(url) = arg_value("--alert-webhook") {
+        config.notifications.sinks.push(SinkConfig::Webhook { url });
+    }
+    if let Some(to) = arg_value("--alert-email") {
+        config.notifications.sinks.push(SinkConfig::Email(email_config_from_env(&to)?));
+    }
+    let portfolios = PortfolioManager::from_config(&config.storage)?
+        .with_notifier(Notifier::from_config(&config.notifications)?);
+    api = api.with_portfolio_manager(portfolios);
+    if let Some(path) = &config.candle_cache {
+        api = api.with_candle_store(CandleStore::open(path, config.candle_cache_ttl())?);
+    }
 
     // Option 1: Run examples
     if std::env::args().any(|arg| arg == "--examples") {
@@ -36,24 +125,138 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     // Option 2: Start HTTP server
     if std::env::args().any(|arg| arg == "--server") {
-        #[cfg(feature = "simple-server")]
+        if let Some(source) = &config.api_keys {
+            // Key files and SQLite key stores are read off the runtime like the other stores
+            let (source, limit) = (source.clone(), config.rate_limit.clone());
+            let authenticator = tokio::task::spawn_blocking(move || Authenticator::load(&source, limit)).await??;
+            tracing::info!("API key auth enabled for: {}", authenticator.key_names().join(", "));
+            api = api.with_authenticator(authenticator);
+        }
+        if let Some(path) = arg_value("--scheduler-jobs") {
+            let jobs: Vec<scheduler::JobRequest> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            for job in jobs {
+                let job = api.add_scheduled_job(job).await?;
+                tracing::info!(job_id = %job.id, "Scheduled job {}", job.name);
+            }
+        }
+        let tls = config.tls.server_config()?;
+        if let (Some(_), Some(port)) = (&tls, config.tls.redirect_port) {
+            let addr = format!("{}:{}", config.bind, port);
+            let https_port = config.port;
+            tokio::spawn(async move {
+                if let Err(e) = tls::serve_redirect(addr, https_port).await {
+                    tracing::error!(error = %e, "HTTPS redirect listener failed");
+                }
+            });
+        }
+        let api = Arc::new(api);
+        tokio::spawn(Arc::clone(&api).run_scheduler());
+        tokio::spawn(Arc::clone(&api).run_alert_checker(std::time::Duration::from_secs(config.alert_check_interval)));
+        tokio::spawn(Arc::clone(&api).run_order_processor(std::time::Duration::from_secs(config.order_check_interval)));
+
+        #[cfg(feature = "grpc")]
+        {
+            let addr = config.grpc_addr.clone();
+            let api = Arc::clone(&api);
+            tokio::spawn(async move {
+                if let Err(e) = grpc_server::serve(api, &addr).await {
+                    tracing::error!(error = %e, "gRPC server failed");
+                }
+            });
+        }
+
+        #[cfg(feature = "axum-server")]
+        {
+            axum_server::serve(api, &config.server_addr(), &config.cors_origins, tls).await?;
+        }
+        #[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
         {
-            let server = http_server::StockApiServer::new(api);
-            server.start("127.0.0.1:8080")?;
+            let mut server = http_server::StockApiServer::new(api).with_cors_origins(config.cors_origins.clone());
+            if let Some(tls) = tls {
+                server = server.with_tls(tls);
+            }
+            server.start(&config.server_addr())?;
         }
-        #[cfg(not(feature = "simple-server"))]
+        #[cfg(not(any(feature = "simple-server", feature = "axum-server")))]
         {
-            println!("Server feature not enabled. Compile with --features simple-server");
+            println!("Server feature not enabled. Compile with --features simple-server or --features axum-server");
         }
         return Ok(());
     }
 
-    // Option 3: Interactive CLI
+    // Option 3: Run a command over a tickers file (or the NASDAQ listing)
+    if let Some(tickers) = load_batch_tickers().await? {
+        let command = arg_value("--command").unwrap_or_else(|| "quote".to_string());
+        let concurrency = arg_value("--concurrency")
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(8);
+        run_batch(&api, &command, tickers, concurrency).await?;
+        return Ok(());
+    }
+
+    // Option 4: Interactive CLI
     run_interactive_cli(&api).await?;
 
     Ok(())
 }
 
+// SMTP settings come from the environment so credentials stay off the command line
+fn email_config_from_env(to: &str) -> Result<EmailConfig, Box<dyn Error>> {
+    let var = |name: &str| std::env::var(name).map_err(|_| format!("--alert-email requires {} to be set", name));
+    Ok(EmailConfig {
+        smtp_host: var("SMTP_HOST")?,
+        smtp_port: std::env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(587),
+        username: var("SMTP_USERNAME")?,
+        password: var("SMTP_PASSWORD")?,
+        from: var("SMTP_FROM")?,
+        to: to.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+    })
+}
+
+// Returns the value following a flag, e.g. `--tickers-file symbols.txt`
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1).cloned())
+}
+
+async fn load_batch_tickers() -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    if let Some(path) = arg_value("--tickers-file") {
+        return Ok(Some(load_tickers_file(&path)?));
+    }
+    if std::env::args().any(|arg| arg == "--nasdaq") {
+        return Ok(Some(fetch_nasdaq_symbols_csv().await?));
+    }
+    Ok(None)
+}
+
+async fn run_batch(
+    api: &StockDataApi,
+    command: &str,
+    tickers: Vec<String>,
+    concurrency: usize,
+) -> Result<(), Box<dyn Error>> {
+    let Some(batch_command) = BatchCommand::parse(command) else {
+        return Err(format!("Unsupported batch command: {} (expected hist, quote or options)", command).into());
+    };
+
+    let total = tickers.len();
+    let concurrency = concurrency.max(1);
+    println!("📦 Running '{}' over {} tickers ({} concurrent)", command, total, concurrency);
+
+    let range = arg_value("--range");
+    let response = api.run_batch(batch_command, tickers, range.as_deref(), concurrency, |done, result| {
+        match (&result.summary, &result.error) {
+            (Some(summary), _) => println!("[{}/{}] {}", done, total, summary),
+            (None, error) => println!("[{}/{}] ❌ {}: {}", done, total, result.ticker, error.as_deref().unwrap_or("failed")),
+        }
+    }).await;
+
+    println!("✅ Batch complete: {} succeeded, {} failed", response.succeeded, response.failed);
+    Ok(())
+}
+
 async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
     println!("📊 Running API Examples\n");
 
@@ -68,25 +271,37 @@ async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
         include_indicators: Some(true),
         indicators: Some(vec![
             IndicatorConfig {
-                name: "SMA".to_string(),
+                name: "sma".to_string(),
                 params: Some([
                     ("period".to_string(), serde_json::Value::Number(20.into()))
                 ].iter().cloned().collect()),
             },
             IndicatorConfig {
-                name: "RSI".to_string(),
+                name: "rsi".to_string(),
                 params: Some([
                     ("period".to_string(), serde_json::Value::Number(14.into()))
                 ].iter().cloned().collect()),
             },
             IndicatorConfig {
-                name: "MACD".to_string(),
+                name: "macd".to_string(),
                 params: Some([
-                    ("fast".to_string(), serde_json::Value::Number(12.into())),
-                    ("slow".to_string(), serde_json::Value::Number(26.into()))
+                    ("short_period".to_string(), serde_json::Value::Number(12.into())),
+                    ("long_period".to_string(), serde_json::Value::Number(26.into()))
                 ].iter().cloned().collect()),
             },
         ]),
+        parallel_indicators: Some(true),
+        min_candles: None,
+        include_returns: None,
+        benchmark: None,
+        adjust: None,
+        resample_to: None,
+        missing_data: None,
+        transform: None,
+        brick_size: None,
+        include_prepost: None,
+        limit: None,
+        cursor: None,
     };
 
     match api.get_historical_data(hist_request).await {
@@ -133,6 +348,16 @@ async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
         include_greeks: Some(true),
         volatility: Some(0.3),
         risk_free_rate: Some(0.02),
+        min_moneyness: None,
+        max_moneyness: None,
+        min_delta: None,
+        max_delta: None,
+        max_spread_pct: None,
+        pricing_model: Some("binomial".to_string()),
+        dividend_yield: Some(0.005),
+        moneyness: None,
+        moneyness_band: None,
+        max_expirations: None,
     };
 
     match api.get_options_chain(options_request).await {
@@ -218,6 +443,10 @@ async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
         volatility: Some(0.25),
         risk_free_rate: Some(0.02),
         days_to_expiry: Some(30.0),
+        pricing_model: None,
+        dividend_yield: None,
+        contract_size: None,
+        valuation_date_offsets: None,
     };
 
     match api.calculate_options_pnl(pnl_request) {
@@ -276,6 +505,7 @@ async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
             "TSLA".to_string(), "NVDA".to_string(), "META".to_string()
         ],
         fields: None,
+        include_prepost: None,
     };
 
     match api.get_quotes(quote_request).await {
@@ -324,7 +554,7 @@ async fn run_api_examples(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
 
 async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
     println!("🖥️  Interactive Stock Data CLI");
-    println!("Commands: hist <ticker>, options <ticker>, quote <ticker>, help, quit");
+    println!("Commands: hist <ticker>, export <tickers> <file>, options <ticker>, quote <ticker>, help, quit");
 
     loop {
         print!("\n> ");
@@ -343,6 +573,8 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
             "help" => {
                 println!("Available commands:");
                 println!("  hist <ticker> [range]  - Get historical data (default: 1mo)");
+                println!("  export <tickers> <file> [range] [interval]");
+                println!("                         - Write candles and indicators to .csv, .parquet or .json");
                 println!("  options <ticker>       - Get options chain with Greeks");
                 println!("  quote <ticker>         - Get real-time quote");
                 println!("  market                 - Get market summary");
@@ -387,6 +619,37 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
                     Err(e) => println!("❌ Error: {}", e),
                 }
             }
+            "export" => {
+                if parts.len() < 3 {
+                    println!("Usage: export <ticker[,ticker...]> <file> [range] [interval]");
+                    continue;
+                }
+                let path = parts[2];
+                let format = ExportFormat::from_path(path);
+                let request = HistoricalDataRequest {
+                    tickers: parts[1].split(',').map(|t| t.to_uppercase()).collect(),
+                    range: Some(parts.get(3).unwrap_or(&"1y").to_string()),
+                    interval: Some(parts.get(4).unwrap_or(&"1d").to_string()),
+                    include_indicators: Some(true),
+                    ..Default::default()
+                };
+
+                let result = api.get_historical_data(request).await
+                    .and_then(|response| Ok((export::render(&response, format)?, response)));
+                match result {
+                    Ok((body, response)) => match std::fs::write(path, &body) {
+                        Ok(()) => {
+                            let rows: usize = response.data.values().map(|d| d.candles.len()).sum();
+                            println!("💾 Wrote {} rows to {} ({})", rows, path, format.extension());
+                            for error in &response.errors {
+                                println!("   ⚠️  {}", error);
+                            }
+                        }
+                        Err(e) => println!("❌ Could not write {}: {}", path, e),
+                    },
+                    Err(e) => println!("❌ Error: {}", e),
+                }
+            }
             "options" => {
                 if parts.len() < 2 {
                     println!("Usage: options <ticker>");
@@ -421,6 +684,7 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
                 let request = QuoteRequest {
                     tickers: vec![ticker.clone()],
                     fields: None,
+                    include_prepost: None,
                 };
 
                 match api.get_quotes(request).await {
@@ -430,6 +694,12 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
                                 ticker, quote.price, quote.change_percent);
                             println!("   Volume: {}, 52W Range: ${:.2} - ${:.2}",
                                 format_volume(quote.volume), quote.low_52w, quote.high_52w);
+                            if let (Some(price), Some(change)) = (quote.pre_market_price, quote.pre_market_change) {
+                                println!("   Pre-market: ${:.2} ({:+.2})", price, change);
+                            }
+                            if let (Some(price), Some(change)) = (quote.post_market_price, quote.post_market_change) {
+                                println!("   After hours: ${:.2} ({:+.2})", price, change);
+                            }
                         }
                     }
                     Err(e) => println!("❌ Error: {}", e),
@@ -455,12 +725,18 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
             //                 field: "price".to_string(),
             //                 operator: "gt".to_string(),
             //                 value: serde_json::Value::Number(50.into()),
+            //                 secondary_value: None,
             //             }
             //         ],
+            //         tickers: None,
             //         sort_by: Some("volume".to_string()),
             //         sort_order: Some("desc".to_string()),
             //         limit: Some(10),
+            //         offset: None,
             //         indicators: None,
+            //         screener_type: Some("predefined".to_string()),
+            //         predefined_screener: Some("most_actives".to_string()),
+            //         concurrency: None,
             //     };
 
             //     match api.screen_stocks(request).await {
@@ -483,43 +759,44 @@ async fn run_interactive_cli(api: &StockDataApi) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn build_comprehensive_indicators() -> Vec<(String, Arc<dyn TechnicalIndicator + Send + Sync>)> {
+fn build_comprehensive_indicators() -> Vec<(String, BoundIndicator)> {
     vec![
         // Moving Averages
-        ("SMA(10)".to_string(), Arc::new(SMA { period: 10 })),
-        ("SMA(20)".to_string(), Arc::new(SMA { period: 20 })),
-        ("SMA(50)".to_string(), Arc::new(SMA { period: 50 })),
-        ("EMA(12)".to_string(), Arc::new(EMA { period: 12 })),
-        ("EMA(26)".to_string(), Arc::new(EMA { period: 26 })),
-        ("WMA(20)".to_string(), Arc::new(WMA { period: 20 })),
+        ("SMA(10)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 10)),
+        ("SMA(20)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 20)),
+        ("SMA(50)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 50)),
+        ("EMA(12)".to_string(), BoundIndicator::new(EMA::new()).with_param("period", 12)),
+        ("EMA(26)".to_string(), BoundIndicator::new(EMA::new()).with_param("period", 26)),
+        ("WMA(20)".to_string(), BoundIndicator::new(WMA::new()).with_param("period", 20)),
         
         // Momentum Indicators
-        ("RSI(14)".to_string(), Arc::new(RSI { period: 14 })),
-        ("MACD(12,26)".to_string(), Arc::new(MACD { fast_period: 12, slow_period: 26 })),
-        ("Stochastic(14,3)".to_string(), Arc::new(Stochastic { k_period: 14, d_period: 3 })),
-        ("CCI(20)".to_string(), Arc::new(CCI { period: 20 })),
-        ("WilliamsR(14)".to_string(), Arc::new(WilliamsR { period: 14 })),
+        ("RSI(14)".to_string(), BoundIndicator::new(RSI::new()).with_param("period", 14)),
+        ("MACD(12,26)".to_string(), BoundIndicator::new(MACD::new()).with_output("macd")),
+        ("Stochastic(14,3)".to_string(), BoundIndicator::new(Stochastic::new()).with_param("k_period", 14)),
+        ("CCI(20)".to_string(), BoundIndicator::new(CCI::new()).with_param("period", 20)),
+        ("WilliamsR(14)".to_string(), BoundIndicator::new(WilliamsR::new()).with_param("period", 14)),
         
         // Volatility Indicators
-        ("BollingerBands(20)".to_string(), Arc::new(BollingerBands { period: 20, k: 2.0 })),
-        ("ATR(14)".to_string(), Arc::new(ATR { period: 14 })),
+        ("BollingerBands(20)".to_string(), BoundIndicator::new(BollingerBands::new()).with_param("period", 20)),
+        ("ATR(14)".to_string(), BoundIndicator::new(ATR::new()).with_param("period", 14)),
         
         // Volume Indicators
-        ("VWAP".to_string(), Arc::new(VWAP {})),
-        ("OBV".to_string(), Arc::new(OBV {})),
-        ("CMF(20)".to_string(), Arc::new(CMF { period: 20 })),
+        ("VWAP".to_string(), BoundIndicator::new(VWAP::new())),
+        ("OBV".to_string(), BoundIndicator::new(OBV::new())),
+        ("CMF(20)".to_string(), BoundIndicator::new(CMF::new()).with_param("period", 20)),
         
         // Trend Indicators
-        ("ADX(14)".to_string(), Arc::new(ADX { period: 14 })),
-        ("ParabolicSAR".to_string(), Arc::new(ParabolicSAR { step: 0.02, max_step: 0.2 })),
+        ("ADX(14)".to_string(), BoundIndicator::new(ADX::new()).with_param("period", 14)),
+        ("ParabolicSAR".to_string(), BoundIndicator::new(ParabolicSAR::new())),
+        ("SuperTrend(10,3)".to_string(), BoundIndicator::new(SuperTrend::new())),
+        ("Aroon(25)".to_string(), BoundIndicator::new(Aroon::new())),
+
+        // Channels
+        ("Keltner(20,10,2)".to_string(), BoundIndicator::new(KeltnerChannels::new())),
+        ("Donchian(20)".to_string(), BoundIndicator::new(DonchianChannels::new())),
         
         // Advanced Indicators
-        ("Ichimoku".to_string(), Arc::new(Ichimoku {
-            conversion_period: 9,
-            base_period: 26,
-            leading_span_b_period: 52,
-            displacement: 26,
-        })),
+        ("Ichimoku".to_string(), BoundIndicator::new(Ichimoku::new())),
     ]
 }
 
@@ -546,6 +823,18 @@ impl Default for HistoricalDataRequest {
             end_date: None,
             include_indicators: Some(false),
             indicators: None,
+            parallel_indicators: None,
+            min_candles: None,
+            include_returns: None,
+            benchmark: None,
+            adjust: None,
+            resample_to: None,
+            missing_data: None,
+            transform: None,
+            brick_size: None,
+            include_prepost: None,
+            limit: None,
+            cursor: None,
         }
     }
 }
@@ -561,35 +850,16 @@ impl Default for OptionsChainRequest {
             include_greeks: Some(false),
             volatility: Some(0.25),
             risk_free_rate: Some(0.01),
-        }
-    }
-}
-
-// Configuration for different deployment scenarios
-pub struct ApiConfig {
-    pub port: u16,
-    pub enable_cors: bool,
-    pub rate_limit: Option<RateLimit>,
-    pub cache_ttl: u64, // seconds
-    pub max_tickers_per_request: usize,
-}
-
-pub struct RateLimit {
-    pub requests_per_minute: u32,
-    pub requests_per_hour: u32,
-}
-
-impl Default for ApiConfig {
-    fn default() -> Self {
-        Self {
-            port: 8080,
-            enable_cors: true,
-            rate_limit: Some(RateLimit {
-                requests_per_minute: 60,
-                requests_per_hour: 1000,
-            }),
-            cache_ttl: 60, // 1 minute cache
-            max_tickers_per_request: 10,
+            min_moneyness: None,
+            max_moneyness: None,
+            min_delta: None,
+            max_delta: None,
+            max_spread_pct: None,
+            pricing_model: None,
+            dividend_yield: None,
+            moneyness: None,
+            moneyness_band: None,
+            max_expirations: None,
         }
     }
 }
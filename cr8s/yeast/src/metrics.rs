@@ -0,0 +1,218 @@
+// metrics.rs - Process-wide counters rendered in the Prometheus text format (/metrics)
+//
+// Both servers record each request under its route pattern (portfolio ids and symbols are
+// collapsed to :id, :symbol, ...), so label cardinality stays bounded by the route table.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::response_cache::ResponseCacheStats;
+
+// Upper bounds in seconds for the request latency and rate limiter wait histograms
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, le) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+// Token waits for one upstream host's rate limiter bucket
+#[derive(Default)]
+struct RateLimitWaits {
+    delayed: u64, // acquisitions that found the bucket empty
+    waits: Histogram, // every acquisition, zero waits included
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>, // (method, route, status)
+    latencies: Mutex<BTreeMap<String, Histogram>>,          // by route
+    upstream_failures: Mutex<BTreeMap<String, u64>>,       // by host
+    rate_limit_waits: Mutex<BTreeMap<String, RateLimitWaits>>, // by host
+    stream_connections: AtomicI64,
+    stream_subscriptions: AtomicI64,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn global() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::default)
+}
+
+// The route pattern for a concrete path as the hand-rolled server sees it, matching axum's
+// MatchedPath so both servers report the same labels. Paths outside the route table are the
+// caller's to relabel "unmatched". axum_server reads MatchedPath itself.
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+pub fn route_label(path: &str) -> String {
+    let Some(rest) = path.strip_prefix("/api/v1/portfolio/") else {
+        return path.to_string();
+    };
+    let segments: Vec<&str> = rest.split('/').collect();
+    match segments.as_slice() {
+        [_] => "/api/v1/portfolio/:id".to_string(),
+        [_, "positions", _] => "/api/v1/portfolio/:id/positions/:symbol".to_string(),
+        [_, "alerts", _] => "/api/v1/portfolio/:id/alerts/:alert_id".to_string(),
+        [_, sub @ ("positions" | "transactions" | "alerts" | "dividends")] => format!("/api/v1/portfolio/:id/{}", sub),
+        _ => "unmatched".to_string(),
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Metrics {
+    pub fn record_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        *self.requests.lock().unwrap()
+            .entry((method.to_string(), route.to_string(), status))
+            .or_default() += 1;
+        self.latencies.lock().unwrap()
+            .entry(route.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_upstream_failure(&self, host: &str) {
+        *self.upstream_failures.lock().unwrap().entry(host.to_string()).or_default() += 1;
+    }
+
+    pub fn record_rate_limit_wait(&self, host: &str, waited: Duration, delayed: bool) {
+        let mut hosts = self.rate_limit_waits.lock().unwrap();
+        let entry = hosts.entry(host.to_string()).or_default();
+        entry.delayed += delayed as u64;
+        entry.waits.observe(waited.as_secs_f64());
+    }
+
+    // `cache` comes from the API's ResponseCache, which the registry doesn't own
+    pub fn render(&self, cache: &ResponseCacheStats) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP yeast_http_requests_total HTTP requests handled, by route and status.\n");
+        out.push_str("# TYPE yeast_http_requests_total counter\n");
+        for ((method, route, status), count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out, "yeast_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                escape(method), escape(route), status, count
+            );
+        }
+
+        out.push_str("# HELP yeast_http_request_duration_seconds Time to handle a request, by route.\n");
+        out.push_str("# TYPE yeast_http_request_duration_seconds histogram\n");
+        for (route, histogram) in self.latencies.lock().unwrap().iter() {
+            let route = escape(route);
+            for (le, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(out, "yeast_http_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}", route, le, count);
+            }
+            let _ = writeln!(out, "yeast_http_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}", route, histogram.count);
+            let _ = writeln!(out, "yeast_http_request_duration_seconds_sum{{route=\"{}\"}} {}", route, histogram.sum);
+            let _ = writeln!(out, "yeast_http_request_duration_seconds_count{{route=\"{}\"}} {}", route, histogram.count);
+        }
+
+        out.push_str("# HELP yeast_upstream_failures_total Upstream requests that failed after retries.\n");
+        out.push_str("# TYPE yeast_upstream_failures_total counter\n");
+        for (host, count) in self.upstream_failures.lock().unwrap().iter() {
+            let _ = writeln!(out, "yeast_upstream_failures_total{{host=\"{}\"}} {}", escape(host), count);
+        }
+
+        out.push_str("# HELP yeast_rate_limiter_waits_total Upstream requests the rate limiter made wait, by host.\n");
+        out.push_str("# TYPE yeast_rate_limiter_waits_total counter\n");
+        let rate_limit_waits = self.rate_limit_waits.lock().unwrap();
+        for (host, waits) in rate_limit_waits.iter() {
+            let _ = writeln!(out, "yeast_rate_limiter_waits_total{{host=\"{}\"}} {}", escape(host), waits.delayed);
+        }
+        out.push_str("# HELP yeast_rate_limiter_wait_seconds Time upstream requests waited for a rate limiter token, by host.\n");
+        out.push_str("# TYPE yeast_rate_limiter_wait_seconds histogram\n");
+        for (host, waits) in rate_limit_waits.iter() {
+            let host = escape(host);
+            let histogram = &waits.waits;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(out, "yeast_rate_limiter_wait_seconds_bucket{{host=\"{}\",le=\"{}\"}} {}", host, le, count);
+            }
+            let _ = writeln!(out, "yeast_rate_limiter_wait_seconds_bucket{{host=\"{}\",le=\"+Inf\"}} {}", host, histogram.count);
+            let _ = writeln!(out, "yeast_rate_limiter_wait_seconds_sum{{host=\"{}\"}} {}", host, histogram.sum);
+            let _ = writeln!(out, "yeast_rate_limiter_wait_seconds_count{{host=\"{}\"}} {}", host, histogram.count);
+        }
+        drop(rate_limit_waits);
+
+        let lookups = cache.hits + cache.misses;
+        let hit_ratio = if lookups > 0 { cache.hits as f64 / lookups as f64 } else { 0.0 };
+        let _ = write!(
+            out,
+            "# HELP yeast_response_cache_hits_total Upstream responses served from the cache.\n\
+             # TYPE yeast_response_cache_hits_total counter\n\
+             yeast_response_cache_hits_total {}\n\
+             # HELP yeast_response_cache_misses_total Cacheable requests that went upstream.\n\
+             # TYPE yeast_response_cache_misses_total counter\n\
+             yeast_response_cache_misses_total {}\n\
+             # HELP yeast_response_cache_revalidated_total Stale entries renewed by a 304.\n\
+             # TYPE yeast_response_cache_revalidated_total counter\n\
+             yeast_response_cache_revalidated_total {}\n\
+             # HELP yeast_response_cache_hit_ratio Hits over lookups since start.\n\
+             # TYPE yeast_response_cache_hit_ratio gauge\n\
+             yeast_response_cache_hit_ratio {}\n\
+             # HELP yeast_response_cache_entries Responses currently cached.\n\
+             # TYPE yeast_response_cache_entries gauge\n\
+             yeast_response_cache_entries {}\n",
+            cache.hits, cache.misses, cache.revalidated, hit_ratio, cache.entries
+        );
+
+        let _ = write!(
+            out,
+            "# HELP yeast_stream_connections Open quote streams (WebSocket and server-sent events).\n\
+             # TYPE yeast_stream_connections gauge\n\
+             yeast_stream_connections {}\n\
+             # HELP yeast_stream_subscriptions Tickers subscribed across open streams.\n\
+             # TYPE yeast_stream_subscriptions gauge\n\
+             yeast_stream_subscriptions {}\n",
+            self.stream_connections.load(Ordering::Relaxed),
+            self.stream_subscriptions.load(Ordering::Relaxed),
+        );
+
+        out
+    }
+}
+
+// Counts one open stream and its subscriptions for as long as it lives
+pub struct StreamGauge {
+    subscriptions: i64,
+}
+
+impl StreamGauge {
+    pub fn open(subscriptions: usize) -> Self {
+        let metrics = global();
+        metrics.stream_connections.fetch_add(1, Ordering::Relaxed);
+        metrics.stream_subscriptions.fetch_add(subscriptions as i64, Ordering::Relaxed);
+        Self { subscriptions: subscriptions as i64 }
+    }
+
+    pub fn set_subscriptions(&mut self, subscriptions: usize) {
+        let subscriptions = subscriptions as i64;
+        global().stream_subscriptions.fetch_add(subscriptions - self.subscriptions, Ordering::Relaxed);
+        self.subscriptions = subscriptions;
+    }
+}
+
+impl Drop for StreamGauge {
+    fn drop(&mut self) {
+        let metrics = global();
+        metrics.stream_connections.fetch_sub(1, Ordering::Relaxed);
+        metrics.stream_subscriptions.fetch_sub(self.subscriptions, Ordering::Relaxed);
+    }
+}
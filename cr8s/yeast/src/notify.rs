@@ -0,0 +1,207 @@
+// notify.rs - Delivery of triggered portfolio alerts
+//
+// A Notifier fans each triggered alert out to every configured sink. Delivery runs on a spawned
+// task so a slow webhook or SMTP server never holds up the alert check; failures are logged.
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::api::ApiError;
+use crate::portfolio::TriggeredAlert;
+
+#[derive(Debug, Clone)]
+pub enum SinkConfig {
+    Log,
+    Webhook { url: String },
+    Email(EmailConfig),
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16, // STARTTLS, usually 587
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotificationConfig {
+    pub sinks: Vec<SinkConfig>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self { sinks: vec![SinkConfig::Log] }
+    }
+}
+
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &str;
+    fn notify<'a>(&'a self, alert: &'a TriggeredAlert) -> BoxFuture<'a, Result<(), String>>;
+}
+
+fn summary(alert: &TriggeredAlert) -> String {
+    format!(
+        "{} {} {} in '{}' (observed {})",
+        alert.alert.symbol,
+        alert.alert.condition.as_str(),
+        alert.alert.value,
+        alert.portfolio_name,
+        alert.alert.triggered_value.unwrap_or_default()
+    )
+}
+
+pub struct LogSink;
+
+impl NotificationSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn notify<'a>(&'a self, alert: &'a TriggeredAlert) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            tracing::info!(alert_id = %alert.alert.id, "Alert triggered: {}", summary(alert));
+            Ok(())
+        })
+    }
+}
+
+// POSTs the TriggeredAlert as JSON; any non-2xx response counts as a failure
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn notify<'a>(&'a self, alert: &'a TriggeredAlert) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let response = self.client.post(&self.url)
+                .json(alert)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("{} returned HTTP {}", self.url, response.status()));
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct EmailSink {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailSink {
+    pub fn new(config: &EmailConfig) -> Result<Self, ApiError> {
+        let invalid = |e: String| ApiError::InvalidParameters(format!("Email notifications: {}", e));
+
+        let from = config.from.parse::<Mailbox>().map_err(|e| invalid(e.to_string()))?;
+        let to = config.to.iter()
+            .map(|addr| addr.parse::<Mailbox>().map_err(|e| invalid(e.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        if to.is_empty() {
+            return Err(invalid("no recipients".to_string()));
+        }
+
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+            .map_err(|e| invalid(e.to_string()))?
+            .port(config.smtp_port)
+            .credentials(Credentials::new(config.username.clone(), config.password.clone()))
+            .build();
+
+        Ok(Self { transport, from, to })
+    }
+}
+
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    fn notify<'a>(&'a self, alert: &'a TriggeredAlert) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let mut builder = Message::builder()
+                .from(self.from.clone())
+                .subject(format!("Alert triggered: {} {}", alert.alert.symbol, alert.alert.condition.as_str()));
+            for to in &self.to {
+                builder = builder.to(to.clone());
+            }
+            let body = format!(
+                "{}\n\nTriggered at {}\nAlert id {}\n",
+                summary(alert),
+                alert.alert.triggered_at.as_deref().unwrap_or("-"),
+                alert.alert.id
+            );
+            let message = builder.body(body).map_err(|e| e.to_string())?;
+            self.transport.send(message).await.map_err(|e| e.to_string())?;
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Notifier {
+    sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+impl Notifier {
+    pub fn from_config(config: &NotificationConfig) -> Result<Self, ApiError> {
+        let mut notifier = Self::default();
+        for sink in &config.sinks {
+            let sink: Arc<dyn NotificationSink> = match sink {
+                SinkConfig::Log => Arc::new(LogSink),
+                SinkConfig::Webhook { url } => Arc::new(WebhookSink::new(url.clone())),
+                SinkConfig::Email(email) => Arc::new(EmailSink::new(email)?),
+            };
+            notifier = notifier.with_sink(sink);
+        }
+        Ok(notifier)
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn NotificationSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    // Hands the alerts to every sink in the background. Outside a tokio runtime there is nothing
+    // to run the deliveries on, so they are dropped with a warning.
+    pub fn dispatch(&self, alerts: Vec<TriggeredAlert>) {
+        if alerts.is_empty() || self.sinks.is_empty() {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(count = alerts.len(), "No async runtime; alert notifications not delivered");
+            return;
+        };
+
+        let sinks = self.sinks.clone();
+        handle.spawn(async move {
+            for alert in &alerts {
+                for sink in &sinks {
+                    if let Err(e) = sink.notify(alert).await {
+                        tracing::warn!(alert_id = %alert.alert.id, sink = sink.name(), error = %e, "Failed to deliver alert");
+                    }
+                }
+            }
+        });
+    }
+}
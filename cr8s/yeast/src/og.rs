@@ -4,11 +4,13 @@ use std::time::{SystemTime, UNIX_EPOCH, Duration};
 use std::error::Error;
 use std::sync::Arc;
 use std::collections::HashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use reqwest;
 
 // For async
 use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
+use chrono::TimeZone;
 
 // // internal
 // mod ml_models;
@@ -20,13 +22,17 @@ use futures::future::BoxFuture;
 // mod types;
 // mod options_math;
 use crate::types::Candle;
-use crate::indicators::{
+use crate::calendar::{self, MarketSession};
+use indicators_core::resample::{bucket, resample, Resolution, Session};
+use crate::retry::SendWithRetry;
+use crate::yahoo_session;
+use crate::indicators::{BoundIndicator, IndicatorRunner};
+use indicators_core::{
     SMA, EMA, RSI, MACD, BollingerBands, VWAP, ATR, Stochastic, CCI, ADX, ParabolicSAR, OBV,
     CMF, WilliamsR, Ichimoku, Momentum, Tema, Dema, Kama, WMA, Hma, Frama, ChandelierExit,
     TRIX, MFI, ForceIndex, EaseOfMovement, AccumDistLine, PriceVolumeTrend, VolumeOscillator,
     UltimateOscillator, DetrendedPriceOscillator, RateOfChange, ZScore, GMMA, SchaffTrendCycle,
     FibonacciRetracement, KalmanFilterSmoother, HeikinAshiSlope, PercentB,
-    TechnicalIndicator, IndicatorRunner
 };
 use crate::options_math::{black_scholes_greeks, calculate_pnl, OptionData, OptionType};
 
@@ -76,6 +82,8 @@ mod tls {
 pub struct ChartQueryOptions<'a> {
     pub interval: &'a str,  // e.g., "1d", "1h"
     pub range: &'a str,     // e.g., "5d", "1mo"
+    pub include_pre_post: bool, // include pre/post-market bars (intraday intervals only)
+    pub period: Option<(i64, i64)>, // explicit [start, end) in unix seconds; replaces `range` when set
 }
 
 impl Default for ChartQueryOptions<'_> {
@@ -83,15 +91,30 @@ impl Default for ChartQueryOptions<'_> {
         Self {
             interval: "1d",
             range: "5d",
+            include_pre_post: false,
+            period: None,
+        }
+    }
+}
+
+impl ChartQueryOptions<'_> {
+    // `period1=..&period2=..` for an explicit window, `range=..` otherwise
+    pub fn span_query(&self) -> String {
+        match self.period {
+            Some((start, end)) => format!("period1={}&period2={}", start, end),
+            None => format!("range={}", self.range),
         }
     }
 }
 
 // Trait for both sync and async fetching
+// Send + Sync so fetch errors can cross await points inside the Arc-held API and tokio tasks
+pub type FetchError = Box<dyn Error + Send + Sync>;
+
 pub trait ChartFetcher {
-    fn fetch_sync(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, Box<dyn Error>>;
+    fn fetch_sync(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError>;
 
-    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, Box<dyn Error>>>;
+    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>>;
 }
 
 // Sync implementation using native TLS + TcpStream
@@ -101,8 +124,8 @@ impl SyncFetcher {
     fn fetch_yahoo_chart_for_ticker(ticker: &str, opts: &ChartQueryOptions) -> Result<String, String> {
         let domain = "query1.finance.yahoo.com";
         let path = format!(
-            "/v8/finance/chart/{}?interval={}&range={}",
-            ticker, opts.interval, opts.range
+            "/v8/finance/chart/{}?interval={}&{}&includePrePost={}&events=div,splits",
+            ticker, opts.interval, opts.span_query(), opts.include_pre_post
         );
 
         let mut stream = tls::connect(domain, 443)?;
@@ -125,14 +148,14 @@ impl SyncFetcher {
 }
 
 impl ChartFetcher for SyncFetcher {
-    fn fetch_sync(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, Box<dyn Error>> {
+    fn fetch_sync(&self, ticker: &str, opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError> {
         let json = Self::fetch_yahoo_chart_for_ticker(ticker, opts)
-            .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+            .map_err(|e| -> FetchError { e.into() })?;
         let parsed = extract_all_data(&json)?;
         Ok(parsed)
     }
 
-    fn fetch_async<'a>(&'a self, _ticker: &'a str, _opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, Box<dyn Error>>> {
+    fn fetch_async<'a>(&'a self, _ticker: &'a str, _opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
         // SyncFetcher doesn't support async fetch
         Box::pin(async { Err("SyncFetcher does not support async fetch".into()) })
     }
@@ -152,20 +175,21 @@ impl AsyncFetcher {
 }
 
 impl ChartFetcher for AsyncFetcher {
-    fn fetch_sync(&self, _ticker: &str, _opts: &ChartQueryOptions) -> Result<ChartResponse, Box<dyn Error>> {
+    fn fetch_sync(&self, _ticker: &str, _opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError> {
         Err("AsyncFetcher does not support sync fetch".into())
     }
 
-    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, Box<dyn Error>>> {
+    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
         let client = &self.client;
-        let interval = opts.interval.to_string();
-        let range = opts.range.to_string();
-        let url = format!("https://query1.finance.yahoo.com/v8/finance/chart/{}?interval={}&range={}", ticker, interval, range);
+        let url = format!(
+            "https://query1.finance.yahoo.com/v8/finance/chart/{}?interval={}&{}&includePrePost={}&events=div,splits",
+            ticker, opts.interval, opts.span_query(), opts.include_pre_post
+        );
 
         Box::pin(async move {
             let resp = client.get(&url)
                 .header("User-Agent", "stock-client/1.0")
-                .send()
+                .send_with_retry()
                 .await?
                 .text()
                 .await?;
@@ -177,44 +201,77 @@ impl ChartFetcher for AsyncFetcher {
 }
 
 // Your parsing structs & function remain unchanged here
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChartResponse {
     pub chart: Chart,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Chart {
     pub result: Option<Vec<ResultItem>>,
     pub error: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResultItem {
     pub meta: Meta,
     pub timestamp: Vec<u64>,
     pub indicators: Indicators,
+    #[serde(default)]
+    pub events: Option<ChartEvents>, // present when requested with events=div,splits
 }
 
-#[derive(Debug, Deserialize)]
+// Keyed by the event's unix timestamp as a string
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ChartEvents {
+    #[serde(default)]
+    pub dividends: HashMap<String, DividendPayload>,
+    #[serde(default)]
+    pub splits: HashMap<String, SplitPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DividendPayload {
+    pub amount: f64,
+    pub date: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitPayload {
+    pub date: i64,
+    pub numerator: f64,
+    pub denominator: f64,
+    #[serde(rename = "splitRatio")]
+    pub split_ratio: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Meta {
     pub currency: String,
     pub symbol: String,
     pub exchangeName: String,
     pub fullExchangeName: String,
+    #[serde(default)]
     pub instrumentType: String,
-    pub firstTradeDate: u64,
+    #[serde(default)]
+    pub firstTradeDate: u64, // null for some FX pairs
     pub regularMarketTime: u64,
     pub hasPrePostMarketData: bool,
     pub gmtoffset: i64,
     pub timezone: String,
     pub exchangeTimezoneName: String,
     pub regularMarketPrice: f64,
-    pub fiftyTwoWeekHigh: f64,
-    pub fiftyTwoWeekLow: f64,
+    #[serde(default)]
+    pub fiftyTwoWeekHigh: Option<f64>, // absent for some indices and new listings
+    #[serde(default)]
+    pub fiftyTwoWeekLow: Option<f64>,
     pub regularMarketDayHigh: f64,
     pub regularMarketDayLow: f64,
-    pub regularMarketVolume: u64,
+    #[serde(default)]
+    pub regularMarketVolume: u64, // always 0 for FX
+    #[serde(default)]
     pub longName: String,
+    #[serde(default)]
     pub shortName: String,
     pub chartPreviousClose: f64,
     pub priceHint: u8,
@@ -224,14 +281,89 @@ pub struct Meta {
     pub validRanges: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Meta {
+    pub fn asset_class(&self) -> AssetClass {
+        AssetClass::classify(&self.instrumentType, &self.symbol)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    Equity,
+    Etf,
+    MutualFund,
+    Index,
+    Future,
+    Crypto,
+    Forex,
+    Other,
+}
+
+impl AssetClass {
+    // From the chart meta's instrumentType, falling back to the symbol's shape when Yahoo
+    // leaves it out: EURUSD=X is a currency pair, BTC-USD a crypto pair, ^GSPC an index
+    pub fn classify(instrument_type: &str, symbol: &str) -> Self {
+        match instrument_type.to_uppercase().as_str() {
+            "EQUITY" => return AssetClass::Equity,
+            "ETF" => return AssetClass::Etf,
+            "MUTUALFUND" => return AssetClass::MutualFund,
+            "INDEX" => return AssetClass::Index,
+            "FUTURE" => return AssetClass::Future,
+            "CRYPTOCURRENCY" => return AssetClass::Crypto,
+            "CURRENCY" => return AssetClass::Forex,
+            "" => {}
+            _ => return AssetClass::Other,
+        }
+
+        let symbol = symbol.to_uppercase();
+        if symbol.ends_with("=X") {
+            AssetClass::Forex
+        } else if symbol.ends_with("=F") {
+            AssetClass::Future
+        } else if symbol.starts_with('^') {
+            AssetClass::Index
+        } else if CRYPTO_QUOTE_CURRENCIES.iter().any(|quote| symbol.ends_with(&format!("-{}", quote))) {
+            AssetClass::Crypto
+        } else {
+            AssetClass::Equity
+        }
+    }
+
+    // Crypto never closes and FX runs around the clock on weekdays; neither has a pre/post session
+    pub fn has_extended_hours(self) -> bool {
+        !matches!(self, AssetClass::Crypto | AssetClass::Forex)
+    }
+
+    // Crypto trades 24/7: every bar is in the regular session and days run midnight to midnight
+    // UTC, whatever exchange hours the chart meta carries
+    pub fn trades_around_the_clock(self) -> bool {
+        self == AssetClass::Crypto
+    }
+
+    // What a bar's volume counts. Yahoo reports crypto volume in the quote currency (USD for
+    // BTC-USD) and has no volume at all for FX, where the reported zeros are dropped.
+    pub fn volume_unit(self) -> Option<&'static str> {
+        match self {
+            AssetClass::Equity | AssetClass::Etf | AssetClass::MutualFund | AssetClass::Index => Some("shares"),
+            AssetClass::Future => Some("contracts"),
+            AssetClass::Crypto => Some("quote_currency"),
+            AssetClass::Forex => None,
+            AssetClass::Other => Some("units"),
+        }
+    }
+}
+
+const CRYPTO_QUOTE_CURRENCIES: [&str; 6] = ["USD", "USDT", "USDC", "EUR", "GBP", "BTC"];
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TradingPeriodWrapper {
     pub pre: TradingPeriod,
     pub regular: TradingPeriod,
     pub post: TradingPeriod,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TradingPeriod {
     pub timezone: String,
     pub end: u64,
@@ -239,13 +371,13 @@ pub struct TradingPeriod {
     pub gmtoffset: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Indicators {
     pub quote: Option<Vec<Quote>>,
     pub adjclose: Option<Vec<AdjClose>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Quote {
     pub close: Option<Vec<Option<f64>>>,
     pub open: Option<Vec<Option<f64>>>,
@@ -254,31 +386,244 @@ pub struct Quote {
     pub low: Option<Vec<Option<f64>>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AdjClose {
     pub adjclose: Option<Vec<Option<f64>>>,
 }
 
-fn extract_all_data(json: &str) -> Result<ChartResponse, Box<dyn Error>> {
-    serde_json::from_str(json).map_err(|e| -> Box<dyn std::error::Error> { e.into() })
+fn extract_all_data(json: &str) -> Result<ChartResponse, FetchError> {
+    serde_json::from_str(json).map_err(|e| -> FetchError { e.into() })
 }
 
-async fn fetch_nasdaq_symbols_csv() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+pub async fn fetch_nasdaq_symbols_csv() -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let url = "https://datahub.io/core/nasdaq-listings/r/nasdaq-listed-symbols.csv";
     let resp = reqwest::get(url).await?.text().await?;
-    let mut lines = resp.lines();
-    let header = lines.next(); // ignore header
+    Ok(parse_symbol_list(&resp))
+}
 
+// Accepts either one symbol per line or the NASDAQ listings CSV (symbol in the first column)
+pub fn parse_symbol_list(text: &str) -> Vec<String> {
     let mut tickers = Vec::new();
-    for line in lines {
-        if line.trim().is_empty() { continue; }
-        let symbol = line.split(',').next().unwrap_or("").to_string();
-        tickers.push(symbol);
+    for (i, line) in text.lines().enumerate() {
+        let symbol = line.split(',').next().unwrap_or("").trim().trim_matches('"');
+        if symbol.is_empty() || symbol.starts_with('#') { continue; }
+        if i == 0 && symbol.eq_ignore_ascii_case("symbol") { continue; } // CSV header
+        tickers.push(symbol.to_uppercase());
+    }
+    tickers
+}
+
+pub fn load_tickers_file(path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let tickers = parse_symbol_list(&text);
+    if tickers.is_empty() {
+        return Err(format!("No ticker symbols found in {}", path).into());
     }
     Ok(tickers)
 }
 
+// Inverse of to_candles and adj_closes: a single-result chart response around already-parsed
+// candles, used to serve cached history through the same path as a live fetch. The adjclose
+// series is left out when `adj_closes` is empty.
+pub fn chart_from_candles(meta: Meta, candles: &[Candle], adj_closes: &HashMap<i64, f64>) -> ChartResponse {
+    let quote = quote_from_candles(candles);
+    let adjclose = (!adj_closes.is_empty()).then(|| {
+        let values = candles.iter().map(|c| adj_closes.get(&c.timestamp).copied());
+        vec![AdjClose { adjclose: Some(values.collect()) }]
+    });
+
+    ChartResponse {
+        chart: Chart {
+            result: Some(vec![ResultItem {
+                meta,
+                timestamp: candles.iter().map(|c| c.timestamp as u64).collect(),
+                indicators: Indicators {
+                    quote: Some(vec![quote]),
+                    adjclose,
+                },
+                events: None,
+            }]),
+            error: None,
+        },
+    }
+}
+
+fn quote_from_candles(candles: &[Candle]) -> Quote {
+    Quote {
+        close: Some(candles.iter().map(|c| Some(c.close)).collect()),
+        open: Some(candles.iter().map(|c| Some(c.open)).collect()),
+        volume: Some(candles.iter().map(|c| c.volume.map(|v| v as u64)).collect()),
+        high: Some(candles.iter().map(|c| Some(c.high)).collect()),
+        low: Some(candles.iter().map(|c| Some(c.low)).collect()),
+    }
+}
+
+// The exchange's regular session for bucketing. Charts built from other providers carry an empty
+// trading period; US listings then get the 9:30 open and everything else midnight.
+fn chart_session(meta: &Meta) -> Session {
+    if meta.asset_class().trades_around_the_clock() {
+        return Session::UTC;
+    }
+    let regular = &meta.currentTradingPeriod.regular;
+    let open = if regular.end > regular.start {
+        (regular.start as i64 + regular.gmtoffset).rem_euclid(86_400)
+    } else if meta.exchangeTimezoneName == calendar::EXCHANGE_TIMEZONE {
+        9 * 3600 + 30 * 60
+    } else {
+        0
+    };
+    Session { utc_offset: meta.gmtoffset, open }
+}
+
+// Which part of the trading day an intraday bar falls in: "pre", "regular" or "post". New York
+// listings go by the trading calendar; elsewhere the chart's current regular hours are applied to
+// every day.
+pub fn candle_session(meta: &Meta, timestamp: i64) -> Option<&'static str> {
+    if meta.asset_class().trades_around_the_clock() {
+        return Some("regular");
+    }
+    if meta.exchangeTimezoneName == calendar::EXCHANGE_TIMEZONE {
+        return match calendar::session_at(chrono::DateTime::from_timestamp(timestamp, 0)?) {
+            MarketSession::Pre => Some("pre"),
+            MarketSession::Regular => Some("regular"),
+            MarketSession::Post => Some("post"),
+            MarketSession::Closed => None,
+        };
+    }
+    let regular = &meta.currentTradingPeriod.regular;
+    let time_of_day = |ts: i64| (ts + regular.gmtoffset).rem_euclid(86_400);
+    let (open, close) = (time_of_day(regular.start as i64), time_of_day(regular.end as i64));
+    if regular.end <= regular.start || close <= open {
+        return None;
+    }
+    let at = time_of_day(timestamp);
+    Some(if at < open { "pre" } else if at >= close { "post" } else { "regular" })
+}
+
+// The chart re-aggregated to a coarser resolution, so one fetch can serve several timeframes.
+// Each bucket keeps the adjusted close of its last bar.
+pub fn resample_chart(mut chart: ChartResponse, resolution: Resolution, interval: &str) -> ChartResponse {
+    let Some(result) = chart.chart.result.as_mut().and_then(|results| results.first_mut()) else {
+        return chart;
+    };
+    let session = chart_session(&result.meta);
+    let candles = to_candles(result);
+    let adjusted = adj_closes(result);
+    let resampled = resample(&candles, resolution, &session);
+
+    let mut bucket_adj = HashMap::new();
+    for candle in &candles {
+        if let Some(adj) = adjusted.get(&candle.timestamp) {
+            bucket_adj.insert(bucket(candle.timestamp, resolution, &session), *adj);
+        }
+    }
+    let adjclose = (!bucket_adj.is_empty()).then(|| {
+        let values = resampled.iter().map(|c| bucket_adj.get(&bucket(c.timestamp, resolution, &session)).copied());
+        vec![AdjClose { adjclose: Some(values.collect()) }]
+    });
+
+    result.timestamp = resampled.iter().map(|c| c.timestamp as u64).collect();
+    result.indicators = Indicators { quote: Some(vec![quote_from_candles(&resampled)]), adjclose };
+    result.meta.dataGranularity = interval.to_string();
+    chart
+}
+
+// Drops pre- and post-market bars from an intraday chart of a New York listing. Other exchanges'
+// calendars aren't known, so their charts pass through as fetched, as do crypto and FX, which
+// have no pre/post session to drop.
+pub fn regular_session_chart(mut chart: ChartResponse) -> ChartResponse {
+    let Some(result) = chart.chart.result.as_mut().and_then(|results| results.first_mut()) else {
+        return chart;
+    };
+    if result.meta.exchangeTimezoneName != calendar::EXCHANGE_TIMEZONE || !result.meta.asset_class().has_extended_hours() {
+        return chart;
+    }
+    let keep: Vec<bool> = result.timestamp.iter().map(|&ts| calendar::in_regular_session(ts as i64)).collect();
+    if keep.iter().all(|&k| k) {
+        return chart;
+    }
+
+    fn retain<T>(values: &mut Option<Vec<T>>, keep: &[bool]) {
+        if let Some(values) = values.as_mut() {
+            let mut rows = keep.iter();
+            values.retain(|_| rows.next().copied().unwrap_or(false));
+        }
+    }
+    for quote in result.indicators.quote.iter_mut().flatten() {
+        retain(&mut quote.open, &keep);
+        retain(&mut quote.high, &keep);
+        retain(&mut quote.low, &keep);
+        retain(&mut quote.close, &keep);
+        retain(&mut quote.volume, &keep);
+    }
+    for adjclose in result.indicators.adjclose.iter_mut().flatten() {
+        retain(&mut adjclose.adjclose, &keep);
+    }
+    let mut rows = keep.iter();
+    result.timestamp.retain(|_| rows.next().copied().unwrap_or(false));
+    chart
+}
+
+// Yahoo's split- and dividend-adjusted close keyed by bar timestamp, for bars that have one
+pub fn adj_closes(result: &ResultItem) -> HashMap<i64, f64> {
+    let Some(values) = result.indicators.adjclose.as_ref()
+        .and_then(|series| series.first())
+        .and_then(|series| series.adjclose.as_ref())
+    else {
+        return HashMap::new();
+    };
+
+    result.timestamp.iter()
+        .zip(values)
+        .filter_map(|(ts, adj)| adj.map(|adj| (*ts as i64, adj)))
+        .collect()
+}
+
+// Back-adjusts OHLC by each bar's adjclose / close ratio, so the series has no gaps at splits and
+// dividends and the close equals the adjusted close. Volume is left as reported (Yahoo already
+// split-adjusts it). Bars without an adjusted close keep their raw prices.
+pub fn adjust_candles(candles: &mut [Candle], adj_closes: &HashMap<i64, f64>) {
+    for candle in candles.iter_mut() {
+        let Some(adj) = adj_closes.get(&candle.timestamp) else { continue };
+        if candle.close <= 0.0 {
+            continue;
+        }
+        let factor = adj / candle.close;
+        candle.open *= factor;
+        candle.high *= factor;
+        candle.low *= factor;
+        candle.close = *adj;
+    }
+}
+
+// What to do with bars Yahoo returns with a null price (halts, illiquid minutes). Drop keeps only
+// complete bars; the others keep one candle per timestamp, so candles and the indicator series
+// computed from them stay on the chart's own timestamp axis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MissingData {
+    #[default]
+    Drop,
+    ForwardFill, // flat bar at the previous close; leading gaps are dropped
+    Interpolate, // flat bar on the line between the neighbouring closes
+    Nan,         // NaN prices, so indicators show the gap instead of bridging it
+}
+
+impl MissingData {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "drop" => Some(MissingData::Drop),
+            "ffill" | "forward_fill" => Some(MissingData::ForwardFill),
+            "interpolate" => Some(MissingData::Interpolate),
+            "nan" => Some(MissingData::Nan),
+            _ => None,
+        }
+    }
+}
+
+// Bars need all four prices. Volume is optional since indices and FX often report none; FX
+// volume is always dropped because Yahoo fills it with zeros.
 pub fn to_candles(result: &ResultItem) -> Vec<Candle> {
+    let has_volume = result.meta.asset_class().volume_unit().is_some();
     let mut candles = Vec::new();
     if let Some(quote_vec) = &result.indicators.quote {
         if let Some(quote) = quote_vec.get(0) {
@@ -290,18 +635,18 @@ pub fn to_candles(result: &ResultItem) -> Vec<Candle> {
             let timestamps = &result.timestamp;
 
             for i in 0..timestamps.len() {
-                if let (Some(Some(c)), Some(Some(o)), Some(Some(h)), Some(Some(l)), Some(Some(v))) =
+                if let (Some(Some(c)), Some(Some(o)), Some(Some(h)), Some(Some(l))) =
                     (close.and_then(|v| v.get(i)), open.and_then(|v| v.get(i)),
-                     high.and_then(|v| v.get(i)), low.and_then(|v| v.get(i)),
-                     volume.and_then(|v| v.get(i)))
+                     high.and_then(|v| v.get(i)), low.and_then(|v| v.get(i)))
                 {
+                    let v = volume.and_then(|v| v.get(i)).copied().flatten().filter(|_| has_volume);
                     candles.push(Candle {
                         timestamp: timestamps[i].try_into().unwrap(),
                         open: *o,
                         high: *h,
                         low: *l,
                         close: *c,
-                        volume: Some(*v as f64),
+                        volume: v.map(|v| v as f64),
                     });
                 }
             }
@@ -310,69 +655,112 @@ pub fn to_candles(result: &ResultItem) -> Vec<Candle> {
     candles
 }
 
-pub fn build_indicators() -> Vec<(String, Arc<dyn TechnicalIndicator + Send + Sync>)> {
+// Puts complete bars from to_candles (possibly adjusted since) back on the chart's full
+// timestamp axis, filling the rows it skipped as `missing` says
+pub fn fill_missing(candles: Vec<Candle>, timestamps: &[u64], missing: MissingData) -> Vec<Candle> {
+    let mut bars = candles.into_iter().peekable();
+    let rows: Vec<(i64, Option<Candle>)> = timestamps.iter()
+        .map(|&ts| (ts as i64, bars.next_if(|bar| bar.timestamp == ts as i64)))
+        .collect();
+
+    match missing {
+        MissingData::Drop => rows.into_iter().filter_map(|(_, bar)| bar).collect(),
+        MissingData::Nan => rows.into_iter()
+            .map(|(timestamp, bar)| bar.unwrap_or_else(|| Candle::from_close(timestamp, f64::NAN)))
+            .collect(),
+        MissingData::ForwardFill => {
+            let mut previous = None;
+            rows.into_iter().filter_map(|(timestamp, bar)| match bar {
+                Some(bar) => {
+                    previous = Some(bar.close);
+                    Some(bar)
+                }
+                None => previous.map(|close| Candle::from_close(timestamp, close)),
+            }).collect()
+        }
+        MissingData::Interpolate => {
+            let known: Vec<(i64, f64)> = rows.iter().filter_map(|(_, bar)| bar.as_ref().map(|b| (b.timestamp, b.close))).collect();
+            let mut seen: usize = 0; // complete bars so far, so known[seen] is the next one
+            rows.into_iter().filter_map(|(timestamp, bar)| {
+                if let Some(bar) = bar {
+                    seen += 1;
+                    return Some(bar);
+                }
+                let close = match (seen.checked_sub(1).map(|i| known[i]), known.get(seen).copied()) {
+                    (Some((t0, c0)), Some((t1, c1))) => c0 + (c1 - c0) * (timestamp - t0) as f64 / (t1 - t0) as f64,
+                    (Some((_, c)), None) | (None, Some((_, c))) => c,
+                    (None, None) => return None,
+                };
+                Some(Candle::from_close(timestamp, close))
+            }).collect()
+        }
+    }
+}
+
+pub type DateTimeLocal = chrono::DateTime<chrono::FixedOffset>;
+
+// Pairs each candle with its exchange-local time. New York listings follow DST bar by bar; other
+// exchanges use `gmt_offset_secs`, the chart meta's `gmtoffset`, which is the current DST state.
+pub fn localize(candles: &[Candle], timezone: &str, gmt_offset_secs: i64) -> Vec<(DateTimeLocal, Candle)> {
+    candles.iter()
+        .filter_map(|candle| {
+            calendar::exchange_time(candle.timestamp, timezone, gmt_offset_secs)
+                .map(|dt| (dt, candle.clone()))
+        })
+        .collect()
+}
+
+pub fn build_indicators() -> Vec<(String, BoundIndicator)> {
     vec![
-        ("SMA(5)".to_string(), Arc::new(SMA { period: 5 })),
-        ("EMA(5)".to_string(), Arc::new(EMA { period: 5 })),
-        ("RSI(14)".to_string(), Arc::new(RSI { period: 14 })),
-        ("MACD(12,26)".to_string(), Arc::new(MACD { fast_period: 12, slow_period: 26 })),
-        ("BollingerBands(20)".to_string(), Arc::new(BollingerBands { period: 20, k: 2.0 })),
-        ("VWAP".to_string(), Arc::new(VWAP {})),
-        ("ATR(14)".to_string(), Arc::new(ATR { period: 14 })),
-        ("Stochastic(14,3)".to_string(), Arc::new(Stochastic { k_period: 14, d_period: 3 })),
-        ("CCI(20)".to_string(), Arc::new(CCI { period: 20 })),
-        ("ADX(14)".to_string(), Arc::new(ADX { period: 14 })),
-        ("ParabolicSAR".to_string(), Arc::new(ParabolicSAR { step: 0.02, max_step: 0.2 })),
-        ("OBV".to_string(), Arc::new(OBV {})),
-        ("CMF(20)".to_string(), Arc::new(CMF { period: 20 })),
-        ("WilliamsR(14)".to_string(), Arc::new(WilliamsR { period: 14 })),
-        ("Ichimoku".to_string(), Arc::new(Ichimoku {
-            conversion_period: 9,
-            base_period: 26,
-            leading_span_b_period: 52,
-            displacement: 26,
-        })),
-        ("Momentum(10)".to_string(), Arc::new(Momentum { period: 10 })),
-        ("Tema(10)".to_string(), Arc::new(Tema { period: 10 })),
-        ("Dema(10)".to_string(), Arc::new(Dema { period: 10 })),
-        ("Kama(10)".to_string(), Arc::new(Kama { period: 10 })),
-        ("WMA(10)".to_string(), Arc::new(WMA { period: 10 })),
-        ("HMA(10)".to_string(), Arc::new(Hma { period: 10 })),
-        ("Frama(10)".to_string(), Arc::new(Frama { period: 10 })),
-        ("ChandelierExit(22, 3.0)".to_string(), Arc::new(ChandelierExit { period: 22, atr_multiplier: 3.0 })),
-        ("TRIX(15)".to_string(), Arc::new(TRIX { period: 15 })),
-        ("MFI(14)".to_string(), Arc::new(MFI { period: 14 })),
-        ("ForceIndex(13)".to_string(), Arc::new(ForceIndex { period: 13 })),
-        ("EaseOfMovement(14)".to_string(), Arc::new(EaseOfMovement { period: 14 })),
-        ("AccumDistLine".to_string(), Arc::new(AccumDistLine {})),
-        ("PriceVolumeTrend".to_string(), Arc::new(PriceVolumeTrend {})),
-        ("VolumeOscillator(14,28)".to_string(), Arc::new(VolumeOscillator { short_period: 14, long_period: 28 })),
-        ("UltimateOscillator(7,14,28)".to_string(), Arc::new(UltimateOscillator {
-            short_period: 7,
-            mid_period: 14,
-            long_period: 28,
-        })),
-        ("DetrendedPriceOscillator(20)".to_string(), Arc::new(DetrendedPriceOscillator { period: 20 })),
-        ("RateOfChange(12)".to_string(), Arc::new(RateOfChange { period: 12 })),
-        ("ZScore(20)".to_string(), Arc::new(ZScore { period: 20 })),
-        ("GMMA".to_string(), Arc::new(GMMA {
-            short_periods: vec![3, 5, 8, 10, 12, 15],
-            long_periods: vec![30, 35, 40, 45, 50, 60],
-        })),
-        ("SchaffTrendCycle".to_string(), Arc::new(SchaffTrendCycle {
-            cycle_period: 10,
-            fast_k: 23,
-            fast_d: 50,
-            short_period: 50,
-            long_period: 50,
-        })),
-        ("FibonacciRetracement(14)".to_string(), Arc::new(FibonacciRetracement { period: 14 })),
-        ("KalmanFilterSmoother".to_string(), Arc::new(KalmanFilterSmoother {
-            measurement_variance: 1.0,
-            process_variance: 1.0,
-        })),
-        ("HeikinAshiSlope(10)".to_string(), Arc::new(HeikinAshiSlope { period: 10 })),
-        ("PercentB(20, 2.0)".to_string(), Arc::new(PercentB { period: 20, std_dev_mult: 2.0 })),
+        ("SMA(5)".to_string(), BoundIndicator::new(SMA::new()).with_param("period", 5)),
+        ("EMA(5)".to_string(), BoundIndicator::new(EMA::new()).with_param("period", 5)),
+        ("RSI(14)".to_string(), BoundIndicator::new(RSI::new()).with_param("period", 14)),
+        ("MACD(12,26)".to_string(), BoundIndicator::new(MACD::new()).with_output("macd")),
+        ("BollingerBands(20)".to_string(), BoundIndicator::new(BollingerBands::new()).with_param("period", 20)),
+        ("VWAP".to_string(), BoundIndicator::new(VWAP::new())),
+        ("ATR(14)".to_string(), BoundIndicator::new(ATR::new()).with_param("period", 14)),
+        ("Stochastic(14,3)".to_string(), BoundIndicator::new(Stochastic::new()).with_param("k_period", 14)),
+        ("CCI(20)".to_string(), BoundIndicator::new(CCI::new()).with_param("period", 20)),
+        ("ADX(14)".to_string(), BoundIndicator::new(ADX::new()).with_param("period", 14)),
+        ("ParabolicSAR".to_string(), BoundIndicator::new(ParabolicSAR::new())),
+        ("OBV".to_string(), BoundIndicator::new(OBV::new())),
+        ("CMF(20)".to_string(), BoundIndicator::new(CMF::new()).with_param("period", 20)),
+        ("WilliamsR(14)".to_string(), BoundIndicator::new(WilliamsR::new()).with_param("period", 14)),
+        ("Ichimoku".to_string(), BoundIndicator::new(Ichimoku::new())),
+        ("Momentum(10)".to_string(), BoundIndicator::new(Momentum::new()).with_param("period", 10)),
+        ("Tema(10)".to_string(), BoundIndicator::new(Tema::new()).with_param("period", 10)),
+        ("Dema(10)".to_string(), BoundIndicator::new(Dema::new()).with_param("period", 10)),
+        ("Kama(10)".to_string(), BoundIndicator::new(Kama::new()).with_param("period", 10)),
+        ("WMA(10)".to_string(), BoundIndicator::new(WMA::new()).with_param("period", 10)),
+        ("HMA(10)".to_string(), BoundIndicator::new(Hma::new()).with_param("period", 10)),
+        ("Frama(10)".to_string(), BoundIndicator::new(Frama::new()).with_param("period", 10)),
+        ("ChandelierExit(22, 3.0)".to_string(), BoundIndicator::new(ChandelierExit::new())
+            .with_param("period", 22)
+            .with_param("atr_multiplier", 3.0)),
+        ("TRIX(15)".to_string(), BoundIndicator::new(TRIX::new()).with_param("period", 15)),
+        ("MFI(14)".to_string(), BoundIndicator::new(MFI::new()).with_param("period", 14)),
+        ("ForceIndex(13)".to_string(), BoundIndicator::new(ForceIndex::new()).with_param("period", 13)),
+        ("EaseOfMovement(14)".to_string(), BoundIndicator::new(EaseOfMovement::new()).with_param("period", 14)),
+        ("AccumDistLine".to_string(), BoundIndicator::new(AccumDistLine::new())),
+        ("PriceVolumeTrend".to_string(), BoundIndicator::new(PriceVolumeTrend::new())),
+        ("VolumeOscillator(14,28)".to_string(), BoundIndicator::new(VolumeOscillator::new())
+            .with_param("short_period", 14)
+            .with_param("long_period", 28)),
+        ("UltimateOscillator(7,14,28)".to_string(), BoundIndicator::new(UltimateOscillator::new())
+            .with_param("short_period", 7)
+            .with_param("mid_period", 14)
+            .with_param("long_period", 28)),
+        ("DetrendedPriceOscillator(20)".to_string(), BoundIndicator::new(DetrendedPriceOscillator::new()).with_param("period", 20)),
+        ("RateOfChange(12)".to_string(), BoundIndicator::new(RateOfChange::new()).with_param("period", 12)),
+        ("ZScore(20)".to_string(), BoundIndicator::new(ZScore::new()).with_param("period", 20)),
+        ("GMMA".to_string(), BoundIndicator::new(GMMA::new())),
+        ("SchaffTrendCycle".to_string(), BoundIndicator::new(SchaffTrendCycle::new())),
+        ("FibonacciRetracement(14)".to_string(), BoundIndicator::new(FibonacciRetracement::new()).with_param("period", 14)),
+        ("KalmanFilterSmoother".to_string(), BoundIndicator::new(KalmanFilterSmoother::new())),
+        ("HeikinAshiSlope(10)".to_string(), BoundIndicator::new(HeikinAshiSlope::new()).with_param("period", 10)),
+        ("PercentB(20, 2.0)".to_string(), BoundIndicator::new(PercentB::new())
+            .with_param("period", 20)
+            .with_param("std_dev", 2.0)),
     ]
 }
 
@@ -398,9 +786,9 @@ pub struct OptionQuote {
 }
 
 pub trait OptionsFetcher {
-    fn fetch_sync(&self, ticker: &str) -> Result<OptionProfitCalculatorResponse, Box<dyn Error>>;
+    fn fetch_sync(&self, ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError>;
 
-    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, Box<dyn Error>>>;
+    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>>;
 }
 
 struct SyncOptionsFetcher;
@@ -430,14 +818,14 @@ impl SyncOptionsFetcher {
 }
 
 impl OptionsFetcher for SyncOptionsFetcher {
-    fn fetch_sync(&self, ticker: &str) -> Result<OptionProfitCalculatorResponse, Box<dyn Error>> {
+    fn fetch_sync(&self, ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError> {
         let json = Self::fetch_options_for_ticker(ticker)
-            .map_err(|e| -> Box<dyn Error> { e.into() })?;
+            .map_err(|e| -> FetchError { e.into() })?;
         let parsed: OptionProfitCalculatorResponse = serde_json::from_str(&json)?;
         Ok(parsed)
     }
 
-    fn fetch_async<'a>(&'a self, _ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, Box<dyn Error>>> {
+    fn fetch_async<'a>(&'a self, _ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
         // SyncOptionsFetcher doesn't support async fetch
         Box::pin(async { Err("SyncOptionsFetcher does not support async fetch".into()) })
     }
@@ -456,18 +844,18 @@ impl AsyncOptionsFetcher {
 }
 
 impl OptionsFetcher for AsyncOptionsFetcher {
-    fn fetch_sync(&self, _ticker: &str) -> Result<OptionProfitCalculatorResponse, Box<dyn Error>> {
+    fn fetch_sync(&self, _ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError> {
         Err("AsyncOptionsFetcher does not support sync fetch".into())
     }
 
-    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, Box<dyn Error>>> {
+    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
         let client = &self.client;
         let url = format!("https://www.optionsprofitcalculator.com/ajax/getOptions?stock={}&reqId=1", ticker);
 
         Box::pin(async move {
             let resp = client.get(&url)
                 .header("User-Agent", "stock-client/1.0")
-                .send()
+                .send_with_retry()
                 .await?
                 .text()
                 .await?;
@@ -478,6 +866,199 @@ impl OptionsFetcher for AsyncOptionsFetcher {
     }
 }
 
+// Where option chains come from when no market data provider replaces Yahoo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OptionsSource {
+    #[default]
+    OptionsProfitCalculator,
+    Yahoo,
+}
+
+impl OptionsSource {
+    // `name` is opc (optionsprofitcalculator.com) or yahoo
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "opc" | "optionsprofitcalculator" => Ok(OptionsSource::OptionsProfitCalculator),
+            "yahoo" => Ok(OptionsSource::Yahoo),
+            other => Err(format!("Unknown options source '{}'. Valid sources: opc, yahoo", other)),
+        }
+    }
+
+    pub fn build(self) -> Arc<dyn OptionsFetcher + Send + Sync> {
+        match self {
+            OptionsSource::OptionsProfitCalculator => Arc::new(AsyncOptionsFetcher::new()),
+            OptionsSource::Yahoo => Arc::new(YahooOptionsFetcher::new()),
+        }
+    }
+}
+
+const YAHOO_OPTIONS_URL: &str = "https://query2.finance.yahoo.com/v7/finance/options";
+const YAHOO_CRUMB_URL: &str = "https://query2.finance.yahoo.com/v1/test/getcrumb";
+// Expiry pages requested at once after the first
+const YAHOO_OPTIONS_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooOptionsResponse {
+    option_chain: YahooOptionChain,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooOptionChain {
+    result: Option<Vec<YahooOptionResult>>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooOptionResult {
+    #[serde(default)]
+    expiration_dates: Vec<i64>,
+    #[serde(default)]
+    options: Vec<YahooOptionSet>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooOptionSet {
+    expiration_date: i64,
+    #[serde(default)]
+    calls: Vec<YahooOptionContract>,
+    #[serde(default)]
+    puts: Vec<YahooOptionContract>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooOptionContract {
+    strike: f64,
+    #[serde(default)]
+    last_price: f64,
+    #[serde(default)]
+    bid: f64,
+    #[serde(default)]
+    ask: f64,
+    volume: Option<u64>,
+    open_interest: Option<u64>,
+}
+
+// Chains from Yahoo's /v7/finance/options, so the API doesn't depend on optionsprofitcalculator.com.
+// The endpoint wants a session cookie and the crumb issued with it; both come from the shared
+// yahoo_session, fetched when it has none and again whenever Yahoo rejects the crumb. The first
+// page holds the nearest expiry and the list of the rest, which are fetched
+// YAHOO_OPTIONS_CONCURRENCY at a time.
+pub struct YahooOptionsFetcher {
+    client: reqwest::Client,
+}
+
+impl YahooOptionsFetcher {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .cookie_provider(yahoo_session::global().cookie_store())
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+
+    async fn crumb(&self) -> Result<String, FetchError> {
+        let session = yahoo_session::global();
+        let _renewal = session.renewal().await;
+        if let Some(crumb) = session.crumb() {
+            return Ok(crumb);
+        }
+        // fc.yahoo.com only sets the session cookie; its 404 is expected
+        self.client.get("https://fc.yahoo.com").send().await?;
+        let fresh = self.client.get(YAHOO_CRUMB_URL).send_with_retry().await?.text().await?;
+        let fresh = fresh.trim().trim_matches('"').to_string();
+        if fresh.is_empty() || fresh.len() > 50 || fresh.contains(char::is_whitespace) {
+            return Err("Yahoo did not issue a crumb".into());
+        }
+        session.set_crumb(&fresh);
+        Ok(fresh)
+    }
+
+    // The chain for one expiry (unix seconds), or the nearest when `date` is None
+    async fn page(&self, ticker: &str, date: Option<i64>) -> Result<YahooOptionResult, FetchError> {
+        for _ in 0..2 {
+            let crumb = self.crumb().await?;
+            let mut request = self.client
+                .get(format!("{}/{}", YAHOO_OPTIONS_URL, ticker))
+                .query(&[("crumb", crumb.as_str())]);
+            if let Some(date) = date {
+                request = request.query(&[("date", date)]);
+            }
+            let response = request.send_with_retry().await?;
+            let status = response.status();
+            if yahoo_session::is_rejected(status) {
+                // crumb or cookie expired; get new ones and try once more
+                yahoo_session::global().reject(&crumb);
+                continue;
+            }
+            if !status.is_success() {
+                return Err(format!("Yahoo options returned HTTP {} for {}", status, ticker).into());
+            }
+            let parsed: YahooOptionsResponse = serde_json::from_str(&response.text().await?)?;
+            if let Some(error) = parsed.option_chain.error.filter(|e| !e.is_null()) {
+                return Err(format!("Yahoo options error for {}: {}", ticker, error).into());
+            }
+            return parsed.option_chain.result
+                .and_then(|results| results.into_iter().next())
+                .ok_or_else(|| format!("No options listed for {}", ticker).into());
+        }
+        Err(format!("Yahoo rejected a fresh crumb for {}", ticker).into())
+    }
+}
+
+// Yahoo contracts keyed the way optionsprofitcalculator.com keys them: expiry as YYYY-MM-DD (Yahoo
+// stamps expiries at midnight UTC), strike as text
+fn yahoo_chain_to_opc(sets: Vec<YahooOptionSet>) -> OptionProfitCalculatorResponse {
+    let quotes = |contracts: Vec<YahooOptionContract>| -> HashMap<String, OptionQuote> {
+        contracts.into_iter()
+            .map(|c| (format!("{:.2}", c.strike), OptionQuote {
+                oi: c.open_interest.unwrap_or(0),
+                l: c.last_price,
+                b: c.bid,
+                a: c.ask,
+                v: c.volume.unwrap_or(0),
+            }))
+            .collect()
+    };
+    let options = sets.into_iter()
+        .filter_map(|set| {
+            let expiry = chrono::Utc.timestamp_opt(set.expiration_date, 0).single()?.format("%Y-%m-%d").to_string();
+            Some((expiry, ExpiryOptionData { c: quotes(set.calls), p: quotes(set.puts) }))
+        })
+        .collect();
+    OptionProfitCalculatorResponse { options }
+}
+
+impl OptionsFetcher for YahooOptionsFetcher {
+    fn fetch_sync(&self, _ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError> {
+        Err("YahooOptionsFetcher does not support sync fetch".into())
+    }
+
+    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
+        Box::pin(async move {
+            let first = self.page(ticker, None).await?;
+            let mut sets = first.options;
+            let remaining: Vec<i64> = first.expiration_dates.into_iter()
+                .filter(|date| !sets.iter().any(|set| set.expiration_date == *date))
+                .collect();
+            let pages: Vec<_> = stream::iter(remaining)
+                .map(|date| self.page(ticker, Some(date)))
+                .buffered(YAHOO_OPTIONS_CONCURRENCY)
+                .collect()
+                .await;
+            for page in pages {
+                sets.extend(page?.options);
+            }
+            Ok(yahoo_chain_to_opc(sets))
+        })
+    }
+}
+
 fn print_opc_option_chain(data: OptionProfitCalculatorResponse) {
     for (expiry, exp_data) in data.options {
         println!("Expiration Date: {}", expiry);
@@ -1327,103 +1908,148 @@ fn print_chart_response(chart_response: ChartResponse, runner: &IndicatorRunner)
     None
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Daily bars of a single-result chart; closes double as open/high/low
+    fn chart_result(symbol: &str, instrument_type: &str, timestamps: &[u64], closes: &[f64], adjclose: Option<&[f64]>) -> ResultItem {
+        let period = serde_json::json!({ "timezone": "EDT", "start": 0, "end": 0, "gmtoffset": -14400 });
+        serde_json::from_value(serde_json::json!({
+            "meta": {
+                "currency": "USD", "symbol": symbol, "exchangeName": "NMS", "fullExchangeName": "NasdaqGS",
+                "instrumentType": instrument_type, "regularMarketTime": 0, "hasPrePostMarketData": true,
+                "gmtoffset": -14400, "timezone": "EDT", "exchangeTimezoneName": "America/New_York",
+                "regularMarketPrice": 0.0, "regularMarketDayHigh": 0.0, "regularMarketDayLow": 0.0,
+                "chartPreviousClose": 0.0, "priceHint": 2,
+                "currentTradingPeriod": { "pre": period, "regular": period, "post": period },
+                "dataGranularity": "1d", "range": "5d", "validRanges": []
+            },
+            "timestamp": timestamps,
+            "indicators": {
+                "quote": [{
+                    "open": closes, "high": closes, "low": closes, "close": closes,
+                    "volume": vec![1_000u64; closes.len()]
+                }],
+                "adjclose": adjclose.map(|values| vec![serde_json::json!({ "adjclose": values })])
+            }
+        })).unwrap()
+    }
 
+    // AAPL around its 4-for-1 split on 2020-08-31: raw closes before it are four times the
+    // adjusted ones
+    const SPLIT_TIMESTAMPS: [u64; 4] = [1598535000, 1598621400, 1598880600, 1598967000];
+    const SPLIT_CLOSES: [f64; 4] = [500.04, 499.23, 129.04, 134.18];
+    const SPLIT_ADJ_CLOSES: [f64; 4] = [125.01, 124.8075, 129.04, 134.18];
+
+    #[test]
+    fn adj_closes_are_keyed_by_bar_timestamp() {
+        let result = chart_result("AAPL", "EQUITY", &SPLIT_TIMESTAMPS, &SPLIT_CLOSES, Some(&SPLIT_ADJ_CLOSES));
+        let adjusted = adj_closes(&result);
+        assert_eq!(adjusted.len(), 4);
+        assert_eq!(adjusted[&1598535000], 125.01);
+        assert_eq!(adjusted[&1598967000], 134.18);
+
+        let unadjusted = chart_result("AAPL", "EQUITY", &SPLIT_TIMESTAMPS, &SPLIT_CLOSES, None);
+        assert!(adj_closes(&unadjusted).is_empty());
+    }
 
-/*
-// OPTIONS FETCHER
-#[derive(Debug, Deserialize)]
-struct OptionChainResponse {
-    optionChain: OptionChain,
-}
+    #[test]
+    fn adjust_candles_removes_the_split_gap() {
+        let result = chart_result("AAPL", "EQUITY", &SPLIT_TIMESTAMPS, &SPLIT_CLOSES, Some(&SPLIT_ADJ_CLOSES));
+        let mut candles = to_candles(&result);
+        adjust_candles(&mut candles, &adj_closes(&result));
+
+        let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+        assert_eq!(closes, SPLIT_ADJ_CLOSES);
+        // Pre-split OHLC is scaled by the same quarter as the close
+        assert!((candles[0].open - 125.01).abs() < 1e-9);
+        assert!((candles[1].high - 124.8075).abs() < 1e-9);
+        // Volume stays as reported
+        assert_eq!(candles[0].volume, Some(1_000.0));
+        // The day-over-day move across the split is a small gain, not a 74% drop
+        assert!(candles[2].close / candles[1].close > 1.0);
+    }
 
-#[derive(Debug, Deserialize)]
-struct OptionChain {
-    result: Option<Vec<OptionChainResult>>,
-    error: Option<serde_json::Value>,
-}
+    #[test]
+    fn adjust_candles_keeps_raw_prices_without_an_adjusted_close() {
+        let result = chart_result("AAPL", "EQUITY", &SPLIT_TIMESTAMPS, &SPLIT_CLOSES, None);
+        let mut candles = to_candles(&result);
+        adjust_candles(&mut candles, &HashMap::from([(1598967000, 134.18)]));
+        assert_eq!(candles[0].close, 500.04);
+        assert_eq!(candles[3].close, 134.18);
+    }
 
-#[derive(Debug, Deserialize)]
-struct OptionChainResult {
-    underlyingSymbol: String,
-    expirationDates: Vec<u64>,
-    options: Vec<OptionSet>,
-}
+    #[test]
+    fn chart_from_candles_round_trips_adjclose() {
+        let result = chart_result("AAPL", "EQUITY", &SPLIT_TIMESTAMPS, &SPLIT_CLOSES, Some(&SPLIT_ADJ_CLOSES));
+        let (candles, adjusted) = (to_candles(&result), adj_closes(&result));
+        let chart = chart_from_candles(result.meta, &candles, &adjusted);
+        let rebuilt = &chart.chart.result.unwrap()[0];
+        assert_eq!(adj_closes(rebuilt), adjusted);
+    }
 
-#[derive(Debug, Deserialize)]
-struct OptionSet {
-    calls: Vec<OptionContract>,
-    puts: Vec<OptionContract>,
-}
+    #[test]
+    fn classify_falls_back_to_the_symbol_shape() {
+        assert_eq!(AssetClass::classify("", "BTC-USD"), AssetClass::Crypto);
+        assert_eq!(AssetClass::classify("", "EURUSD=X"), AssetClass::Forex);
+        assert_eq!(AssetClass::classify("", "^GSPC"), AssetClass::Index);
+        assert_eq!(AssetClass::classify("", "CL=F"), AssetClass::Future);
+        assert_eq!(AssetClass::classify("", "AAPL"), AssetClass::Equity);
+        // Yahoo's instrumentType wins over the symbol
+        assert_eq!(AssetClass::classify("CRYPTOCURRENCY", "BTC-USD"), AssetClass::Crypto);
+        assert_eq!(AssetClass::classify("CURRENCY", "EURUSD=X"), AssetClass::Forex);
+        assert_eq!(AssetClass::classify("INDEX", "^GSPC"), AssetClass::Index);
+        assert_eq!(AssetClass::classify("FUTURE", "CL=F"), AssetClass::Future);
+        assert_eq!(AssetClass::classify("ETF", "SPY"), AssetClass::Etf);
+    }
 
-#[derive(Debug, Deserialize)]
-struct OptionContract {
-    contractSymbol: String,
-    strike: f64,
-    lastPrice: f64,
-    bid: f64,
-    ask: f64,
-    volume: Option<u64>,
-    openInterest: Option<u64>,
-    impliedVolatility: f64,
-    inTheMoney: bool,
-    expiration: u64,
-}
+    // Friday 22:00 UTC through Saturday 01:00 UTC, hourly
+    const WEEKEND_HOURS: [u64; 4] = [1709330400, 1709334000, 1709337600, 1709341200];
+    const WEEKEND_CLOSES: [f64; 4] = [62_000.0, 62_100.0, 62_200.0, 62_300.0];
 
-async fn fetch_option_chain(ticker: &str, expiration: Option<u64>) -> Result<OptionChainResponse, Box<dyn Error>> {
-    let mut url = format!("https://query1.finance.yahoo.com/v7/finance/options/{}", ticker);
-    // https://www.optionsprofitcalculator.com/ajax/getOptions?stock=tsla&reqId=1
-    if let Some(date) = expiration {
-        url.push_str(&format!("?date={}", date));
+    #[test]
+    fn to_candles_drops_fx_volume_and_keeps_crypto_volume() {
+        let fx = chart_result("EURUSD=X", "CURRENCY", &WEEKEND_HOURS, &[1.08; 4], None);
+        assert!(to_candles(&fx).iter().all(|c| c.volume.is_none()));
+        assert_eq!(fx.meta.asset_class().volume_unit(), None);
+
+        let crypto = chart_result("BTC-USD", "CRYPTOCURRENCY", &WEEKEND_HOURS, &WEEKEND_CLOSES, None);
+        assert!(to_candles(&crypto).iter().all(|c| c.volume == Some(1_000.0)));
+        assert_eq!(crypto.meta.asset_class().volume_unit(), Some("quote_currency"));
     }
 
-    let resp = reqwest::get(&url).await?.text().await?;
-    println!("{:?}", resp);
-    let parsed: OptionChainResponse = serde_json::from_str(&resp)?;
-    Ok(parsed)
-}
-
-fn print_option_chain(response: OptionChainResponse) {
-    if let Some(chain) = response.optionChain.result {
-        for result in chain {
-            println!("Options for: {}", result.underlyingSymbol);
-            println!("Available Expirations: {:?}", result.expirationDates);
-
-            for option_set in result.options {
-                println!("Calls:");
-                for call in &option_set.calls {
-                    println!(
-                        "  {} | Strike: ${:.2} | Last: {:.2} | Bid: {:.2} | Ask: {:.2} | Vol: {:?} | OI: {:?} | IV: {:.2}%",
-                        call.contractSymbol,
-                        call.strike,
-                        call.lastPrice,
-                        call.bid,
-                        call.ask,
-                        call.volume,
-                        call.openInterest,
-                        call.impliedVolatility * 100.0,
-                    );
-                }
+    // The fixture meta is a New York listing, so these check crypto ignores exchange hours
+    fn chart(result: ResultItem) -> ChartResponse {
+        ChartResponse { chart: Chart { result: Some(vec![result]), error: None } }
+    }
 
-                println!("Puts:");
-                for put in &option_set.puts {
-                    println!(
-                        "  {} | Strike: ${:.2} | Last: {:.2} | Bid: {:.2} | Ask: {:.2} | Vol: {:?} | OI: {:?} | IV: {:.2}%",
-                        put.contractSymbol,
-                        put.strike,
-                        put.lastPrice,
-                        put.bid,
-                        put.ask,
-                        put.volume,
-                        put.openInterest,
-                        put.impliedVolatility * 100.0,
-                    );
-                }
+    #[test]
+    fn crypto_bars_are_all_regular_session() {
+        let crypto = chart_result("BTC-USD", "CRYPTOCURRENCY", &WEEKEND_HOURS, &WEEKEND_CLOSES, None);
+        assert!(WEEKEND_HOURS.iter().all(|&ts| candle_session(&crypto.meta, ts as i64) == Some("regular")));
+        let filtered = regular_session_chart(chart(crypto));
+        assert_eq!(filtered.chart.result.unwrap()[0].timestamp, WEEKEND_HOURS);
+
+        let equity = chart_result("AAPL", "EQUITY", &WEEKEND_HOURS, &WEEKEND_CLOSES, None);
+        assert_eq!(candle_session(&equity.meta, WEEKEND_HOURS[3] as i64), None);
+        let filtered = regular_session_chart(chart(equity));
+        assert!(filtered.chart.result.unwrap()[0].timestamp.is_empty());
+    }
 
-                println!("---");
-            }
-        }
-    } else {
-        eprintln!("Error: {:?}", response.optionChain.error);
+    #[test]
+    fn crypto_days_resample_from_utc_midnight() {
+        let crypto = chart_result("BTC-USD", "CRYPTOCURRENCY", &WEEKEND_HOURS, &WEEKEND_CLOSES, None);
+        let daily = resample_chart(chart(crypto), Resolution::Days(1), "1d");
+        let result = &daily.chart.result.unwrap()[0];
+        let candles = to_candles(result);
+        assert_eq!(result.timestamp, [WEEKEND_HOURS[0], WEEKEND_HOURS[2]]);
+        assert_eq!((candles[0].close, candles[1].close), (62_100.0, 62_300.0));
+        assert_eq!(candles[1].volume, Some(2_000.0));
+
+        // In New York time all four bars fall on the Friday
+        let equity = chart_result("AAPL", "EQUITY", &WEEKEND_HOURS, &WEEKEND_CLOSES, None);
+        let daily = resample_chart(chart(equity), Resolution::Days(1), "1d");
+        assert_eq!(daily.chart.result.unwrap()[0].timestamp, [WEEKEND_HOURS[0]]);
     }
 }
-*/
\ No newline at end of file
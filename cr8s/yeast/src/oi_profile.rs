@@ -0,0 +1,69 @@
+// oi_profile.rs - open interest by strike, max pain and put/call ratios for one expiry
+//
+// Max pain is the settlement price at which the open contracts pay their holders the least in
+// total: for each listed strike S, the sum of call OI x max(S - K, 0) and put OI x max(K - S, 0).
+// Only listed strikes are tried, since the payout is piecewise linear between them and so has its
+// minimum at one of them.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrikeInterest {
+    pub call_open_interest: u64,
+    pub put_open_interest: u64,
+    pub call_volume: u64,
+    pub put_volume: u64,
+}
+
+// Open interest and volume per strike from (strike, open interest, volume) for each side. Strikes
+// merge on their text, as both sides of a chain quote them the same way; sorted by strike.
+pub fn by_strike<S: AsRef<str>>(calls: &[(S, u64, u64)], puts: &[(S, u64, u64)]) -> Vec<(f64, StrikeInterest)> {
+    let mut strikes: BTreeMap<&str, (f64, StrikeInterest)> = BTreeMap::new();
+    for (side, is_call) in [(calls, true), (puts, false)] {
+        for (text, open_interest, volume) in side {
+            let (text, open_interest, volume) = (text.as_ref(), *open_interest, *volume);
+            let Ok(strike) = text.parse::<f64>() else {
+                continue;
+            };
+            let entry = &mut strikes.entry(text).or_insert((strike, StrikeInterest::default())).1;
+            if is_call {
+                entry.call_open_interest += open_interest;
+                entry.call_volume += volume;
+            } else {
+                entry.put_open_interest += open_interest;
+                entry.put_volume += volume;
+            }
+        }
+    }
+    let mut sorted: Vec<(f64, StrikeInterest)> = strikes.into_values().collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    sorted
+}
+
+// Intrinsic value owed to holders if the underlying settles at `price`, per share of the contract
+// multiplier
+pub fn holder_payout(strikes: &[(f64, StrikeInterest)], price: f64) -> f64 {
+    strikes
+        .iter()
+        .map(|(strike, oi)| {
+            oi.call_open_interest as f64 * (price - strike).max(0.0)
+                + oi.put_open_interest as f64 * (strike - price).max(0.0)
+        })
+        .sum()
+}
+
+// (strike, payout) minimising the holders' payout; None without open interest
+pub fn max_pain(strikes: &[(f64, StrikeInterest)]) -> Option<(f64, f64)> {
+    if strikes.iter().all(|(_, oi)| oi.call_open_interest + oi.put_open_interest == 0) {
+        return None;
+    }
+    strikes
+        .iter()
+        .map(|(strike, _)| (*strike, holder_payout(strikes, *strike)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// puts / calls; None when there are no calls
+pub fn put_call_ratio(puts: u64, calls: u64) -> Option<f64> {
+    (calls > 0).then(|| puts as f64 / calls as f64)
+}
@@ -0,0 +1,780 @@
+// openapi.rs - OpenAPI 3 description of the /api/v1 routes (/api/v1/openapi.json) and a Swagger UI
+// page that renders it (/api/v1/docs)
+//
+// Built by hand so no schema derives leak into the request/response types. Field types are
+// written in a small notation: "string", "number", "integer", "boolean", "any", a component name
+// such as "Quote", "[]T" for arrays, "{}T" for string-keyed maps, and a trailing "?" for nullable
+// (which also makes the field optional). Keep it in step with the structs in api.rs and
+// portfolio.rs when they change.
+
+use serde_json::{json, Map, Value};
+
+// Swagger UI assets come from a pinned CDN build, so the page needs network access in the browser
+pub const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>yeast API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+fn ty(spec: &str) -> Value {
+    if let Some(inner) = spec.strip_suffix('?') {
+        let schema = ty(inner);
+        // OpenAPI 3.0 ignores siblings of $ref, so nullable references go through allOf
+        return match schema.get("$ref") {
+            Some(_) => json!({ "allOf": [schema], "nullable": true }),
+            None => {
+                let mut schema = schema;
+                schema["nullable"] = json!(true);
+                schema
+            }
+        };
+    }
+    if let Some(items) = spec.strip_prefix("[]") {
+        return json!({ "type": "array", "items": ty(items) });
+    }
+    if let Some(values) = spec.strip_prefix("{}") {
+        return json!({ "type": "object", "additionalProperties": ty(values) });
+    }
+    match spec {
+        "string" | "number" | "integer" | "boolean" => json!({ "type": spec }),
+        "any" => json!({}),
+        component => json!({ "$ref": format!("#/components/schemas/{}", component) }),
+    }
+}
+
+fn object(fields: &[(&str, &str)]) -> Value {
+    let properties: Map<String, Value> = fields.iter().map(|(name, spec)| (name.to_string(), ty(spec))).collect();
+    let required: Vec<&str> = fields.iter().filter(|(_, spec)| !spec.ends_with('?')).map(|(name, _)| *name).collect();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+// (name, type, description); parameters named in the route as {name} are path parameters
+type Param<'a> = (&'a str, &'a str, &'a str);
+
+// (route, method, tag, summary, parameters, request body, response)
+type Endpoint<'a> = (&'a str, &'a str, &'a str, &'a str, &'a [Param<'a>], Option<&'a str>, &'a str);
+
+// A JSON endpoint: 200 with `response`, the ApiError statuses otherwise
+fn operation(path: &str, tag: &str, summary: &str, params: &[Param], body: Option<&str>, response: &str) -> Value {
+    let parameters: Vec<Value> = params.iter().map(|(name, spec, description)| {
+        let in_path = path.contains(&format!("{{{}}}", name));
+        json!({
+            "name": name,
+            "in": if in_path { "path" } else { "query" },
+            "required": in_path,
+            "description": description,
+            "schema": ty(spec),
+        })
+    }).collect();
+
+    let error = |description: &str| json!({
+        "description": description,
+        "content": { "application/json": { "schema": ty("Error") } },
+    });
+    let mut op = json!({
+        "tags": [tag],
+        "summary": summary,
+        "parameters": parameters,
+        "responses": {
+            "200": { "description": "OK", "content": { "application/json": { "schema": ty(response) } } },
+            "400": error("Invalid parameters"),
+            "401": error("Missing or unknown API key"),
+            "404": error("No such data, or no such record"),
+            "429": error("Quota exceeded, or the upstream is throttling us"),
+            "500": error("Internal failure"),
+            "502": error("The upstream data source failed"),
+        },
+    });
+    if let Some(body) = body {
+        op["requestBody"] = json!({ "required": true, "content": { "application/json": { "schema": ty(body) } } });
+    }
+    op
+}
+
+fn paths() -> Value {
+    let ticker: Param = ("ticker", "string", "Symbol, e.g. AAPL");
+    let id: Param = ("id", "string", "Portfolio id");
+    let watchlist_id: Param = ("id", "string", "Watchlist id");
+    let order_id: Param = ("id", "string", "Order id");
+    let job_id: Param = ("id", "string", "Job id");
+    let date_range: [Param; 2] = [("from", "string", "YYYY-MM-DD"), ("to", "string", "YYYY-MM-DD")];
+    let historical_params: &[Param] = &[
+        ("tickers", "string", "Comma-separated symbols"),
+        ("interval", "string", "1m, 5m, 15m, 30m, 1h, 1d, 1wk or 1mo"),
+        ("range", "string", "1d, 5d, 1mo, 3mo, 6mo, 1y, 2y, 5y, 10y, ytd or max"),
+        ("start_date", "string", "YYYY-MM-DD, instead of range"),
+        ("end_date", "string", "YYYY-MM-DD"),
+        ("include_indicators", "boolean", "Compute the default indicator set"),
+        ("indicators", "string", "Indicators to compute instead of the default set, e.g. sma(period=20),rsi(period=21),aroon"),
+        ("parallel_indicators", "boolean", "Compute indicators on the worker pool"),
+        ("min_candles", "integer", "Floor on the candles each indicator needs"),
+        ("include_returns", "boolean", "Add change, change_percent and log_return to candles"),
+        ("benchmark", "string", "Symbol for a relative strength line, e.g. SPY"),
+        ("adjust", "boolean", "Back-adjust OHLC for splits and dividends"),
+        ("resample_to", "string", "Aggregate the fetched bars to a coarser interval, e.g. 1h from 5m or 1wk from 1d"),
+        ("missing_data", "string", "Bars with a null price: drop (default), ffill, interpolate or nan; all but drop keep every timestamp"),
+        ("transform", "string", "heikin_ashi or renko; candles and indicators then use the transformed series"),
+        ("brick_size", "number", "Renko brick in price units (default: 14-bar average true range)"),
+        ("include_prepost", "boolean", "Keep pre- and post-market bars on intraday intervals (default regular session only)"),
+        ("format", "string", "json (default), csv or parquet; csv and parquet come back as a file download"),
+        ("limit", "integer", "Page size in bars; the response's next_cursor fetches the next page"),
+        ("cursor", "string", "next_cursor from the previous page"),
+        ("fields", "string", "CandleData fields to keep in JSON responses, e.g. timestamp,close,volume"),
+    ];
+    let options_params: &[Param] = &[
+        ticker,
+        ("min_strike", "number", ""),
+        ("max_strike", "number", ""),
+        ("option_type", "string", "call, put or both"),
+        ("include_greeks", "boolean", ""),
+        ("volatility", "number", "For Greeks; defaults to each contract's implied volatility, then the stock's 20-day realized volatility"),
+        ("risk_free_rate", "number", ""),
+        ("min_moneyness", "number", "strike / underlying"),
+        ("max_moneyness", "number", ""),
+        ("min_delta", "number", "Compared against |delta|"),
+        ("max_delta", "number", ""),
+        ("max_spread_pct", "number", "Drop contracts with a wider bid/ask spread"),
+        ("pricing_model", "string", "black_scholes or binomial"),
+        ("dividend_yield", "number", "Continuous yield for the binomial model"),
+        ("expiration_dates", "string", "Comma-separated YYYY-MM-DD expiries to keep"),
+        ("moneyness", "string", "itm, atm or otm, or a comma-separated mix"),
+        ("moneyness_band", "number", "Percent either side of the underlying counted as ATM, default 5"),
+        ("max_expirations", "integer", "Keep only the nearest N expiries"),
+    ];
+
+    let endpoints: &[Endpoint] = &[
+        ("/api/v1/historical", "get", "market", "Candles, indicators and relative strength", historical_params, None, "HistoricalDataResponse"),
+        ("/api/v1/quotes", "get", "market", "Quotes for several symbols", &[
+            ("tickers", "string", "Comma-separated symbols"),
+            ("include_prepost", "boolean", "Pre/post-market prices (default true)"),
+        ], None, "QuoteResponse"),
+        ("/api/v1/quote", "get", "market", "One quote with market cap, P/E and dividend yield", &[ticker], None, "Quote"),
+        ("/api/v1/quotesummary", "get", "market", "Company profile, financials, key statistics, earnings and calendar events", &[
+            ticker,
+            ("modules", "string", "Comma-separated subset of assetProfile, financialData, defaultKeyStatistics, summaryDetail, price, summaryProfile, earnings, calendarEvents, recommendationTrend, earningsTrend (default all)"),
+        ], None, "QuoteSummaryResponse"),
+        ("/api/v1/market/summary", "get", "market", "Major indices", &[], None, "MarketSummary"),
+        ("/api/v1/market/status", "get", "market", "NYSE/Nasdaq session right now, and the next open and close", &[], None, "MarketStatus"),
+        ("/api/v1/market/calendar", "get", "market", "NYSE/Nasdaq trading days, holidays, half days and session times", &[
+            ("from", "string", "YYYY-MM-DD, default today"),
+            ("to", "string", "YYYY-MM-DD inclusive, default a month after from; at most a year"),
+        ], None, "[]TradingDay"),
+        ("/api/v1/news", "get", "market", "Recent news", &[ticker, ("count", "integer", "Stories to return")], None, "NewsResponse"),
+        ("/api/v1/calendar", "get", "market", "Earnings, dividends, splits and IPOs", &[
+            ("from", "string", "YYYY-MM-DD, default today"),
+            ("to", "string", "YYYY-MM-DD inclusive, default a week after from"),
+            ("tickers", "string", "Comma-separated symbols to keep"),
+        ], None, "CalendarResponse"),
+        ("/api/v1/reports", "get", "market", "Financial statements and analyst estimates", &[ticker], None, "ReportsResponse"),
+        ("/api/v1/analysis", "get", "market", "Analyst recommendation counts, EPS and revenue estimates and EPS trend", &[ticker], None, "AnalysisReports"),
+        ("/api/v1/analysis/correlation", "post", "market", "Pairwise correlation of daily returns, for diversification", &[], Some("CorrelationRequest"), "CorrelationResponse"),
+        ("/api/v1/fundamentals", "get", "market", "Income statement, balance sheet and cash flow", &[
+            ticker,
+            ("period", "string", "annual (default) or quarterly"),
+        ], None, "FundamentalsResponse"),
+        ("/api/v1/events", "get", "market", "Historical dividends and splits", &[ticker, date_range[0], date_range[1]], None, "CorporateActionsResponse"),
+        ("/api/v1/search", "get", "market", "Symbol search", &[("q", "string", "Name or symbol"), ("limit", "integer", "At most 25")], None, "SearchResponse"),
+        ("/api/v1/confluence", "get", "analysis", "Daily, weekly and monthly trend confluence", &[ticker], None, "ConfluenceResponse"),
+        ("/api/v1/signals", "get", "analysis", "Buy/sell signals from indicator rules and their weighted score", &[
+            ticker,
+            ("interval", "string", "Bar size, default 1d"),
+            ("range", "string", "History to evaluate, default 1y"),
+            ("rules", "string", "Comma-separated rules: rsi(period,oversold,overbought), macd(fast,slow,signal), price_sma(period), sma_cross(fast,slow)"),
+            ("weights", "string", "Comma-separated weight per rule, default 1 each"),
+            ("threshold", "number", "Score needed for a combined buy or sell, default 0.5"),
+        ], None, "SignalsResponse"),
+        ("/api/v1/screener", "post", "analysis", "Screen a list or a predefined Yahoo screener", &[], Some("ScreenerRequest"), "ScreenerResponse"),
+        ("/api/v1/bundle", "post", "market", "Quote, candles, options summary and news for several symbols in one call", &[], Some("BundleRequest"), "BundleResponse"),
+        ("/api/v1/batch", "post", "market", "Run quote, hist or options over a ticker list, a tickers file's contents or the NASDAQ listing (at most 500 symbols)", &[], Some("BatchRequest"), "BatchResponse"),
+        ("/api/v1/position-size", "post", "analysis", "Shares to buy for a fixed account risk", &[], Some("PositionSizeRequest"), "PositionSizeResponse"),
+        ("/api/v1/options", "get", "options", "Options chain with optional Greeks", options_params, None, "OptionsChainResponse"),
+        ("/api/v1/options/surface", "get", "options", "Implied-volatility surface (moneyness x expiry) for 3D plots", &[
+            ticker,
+            ("min_moneyness", "number", "Grid strike / underlying, default 0.7"),
+            ("max_moneyness", "number", "Default 1.3"),
+            ("steps", "integer", "Grid strikes, default 25"),
+            ("max_days", "number", "Leave out expiries further away"),
+            ("risk_free_rate", "number", ""),
+        ], None, "VolSurfaceResponse"),
+        ("/api/v1/options/oi-profile", "get", "options", "Open interest per strike, max pain and put/call ratios by expiry", &[
+            ticker,
+            ("max_days", "number", "Leave out expiries further away"),
+        ], None, "OiProfileResponse"),
+        ("/api/v1/options/unusual", "get", "options", "Contracts with unusual volume / open interest, sweeps or implied-vol spikes, ranked", &[
+            ("tickers", "string", "Comma-separated symbols"),
+            ("min_volume", "integer", "Contracts trading less are ignored, default 100"),
+            ("min_volume_oi_ratio", "number", "Default 2.0"),
+            ("min_sweep_premium", "number", "Dollars traded at the bid or ask, default 50000"),
+            ("min_iv_ratio", "number", "Implied vol / expiry median, default 1.5"),
+            ("max_days", "number", "Leave out expiries further away"),
+            ("limit", "integer", "Contracts returned, default 50"),
+        ], None, "UnusualActivityResponse"),
+        ("/api/v1/options/unusual", "post", "options", "Unusual options activity across a watchlist", &[], Some("UnusualActivityRequest"), "UnusualActivityResponse"),
+        ("/api/v1/options/pnl", "post", "options", "P&L curves and Greeks for option positions", &[], Some("OptionsPnLRequest"), "OptionsPnLResponse"),
+        ("/api/v1/portfolio", "get", "portfolio", "List portfolios", &[], None, "[]Portfolio"),
+        ("/api/v1/portfolio", "post", "portfolio", "Create a portfolio", &[], Some("CreatePortfolioRequest"), "Portfolio"),
+        ("/api/v1/portfolio/optimize", "post", "portfolio", "Minimum-variance and max-Sharpe weights and the efficient frontier", &[], Some("OptimizeRequest"), "OptimizeResponse"),
+        ("/api/v1/portfolio/{id}", "get", "portfolio", "Get a portfolio", &[id], None, "Portfolio"),
+        ("/api/v1/portfolio/{id}/positions", "post", "portfolio", "Add to a position", &[id], Some("AddPositionRequest"), "Portfolio"),
+        ("/api/v1/portfolio/{id}/positions/{symbol}", "delete", "portfolio", "Remove a position", &[id, ("symbol", "string", "")], None, "Portfolio"),
+        ("/api/v1/portfolio/{id}/transactions", "get", "portfolio", "Transaction history", &[id], None, "[]Transaction"),
+        ("/api/v1/portfolio/{id}/snapshots", "get", "portfolio", "Values recorded by snapshot_portfolios jobs, oldest first", &[id], None, "[]PortfolioSnapshot"),
+        ("/api/v1/portfolio/{id}/transactions", "post", "portfolio", "Record a trade or cash movement", &[id], Some("TransactionRequest"), "Transaction"),
+        ("/api/v1/portfolio/{id}/alerts", "post", "portfolio", "Add a price alert", &[id], Some("CreateAlertRequest"), "PortfolioAlert"),
+        ("/api/v1/portfolio/{id}/alerts/{alert_id}", "delete", "portfolio", "Remove an alert", &[id, ("alert_id", "string", "")], None, "Portfolio"),
+        ("/api/v1/portfolio/{id}/dividends", "post", "portfolio", "Credit dividends paid on held lots", &[id], None, "[]Transaction"),
+        ("/api/v1/portfolio/{id}/risk", "get", "portfolio", "Beta, volatility, VaR/CVaR, Sharpe/Sortino and max drawdown of the current holdings", &[
+            id,
+            ("range", "string", "Daily history to measure over, default 1y"),
+            ("benchmark", "string", "Symbol for beta, default SPY"),
+            ("confidence", "number", "VaR / CVaR level, default 0.95"),
+            ("risk_free_rate", "number", "Annual, for Sharpe and Sortino; default 0.01"),
+        ], None, "PortfolioRiskResponse"),
+        ("/api/v1/portfolio/{id}/targets", "put", "portfolio", "Replace the target allocation", &[id], Some("TargetWeightsRequest"), "Portfolio"),
+        ("/api/v1/portfolio/{id}/rebalance", "get", "portfolio", "Trades that bring holdings back to their target weights", &[
+            id,
+            ("drift_threshold", "number", "Weights closer than this to target are left alone, default 0.05"),
+            ("fractional", "boolean", "Trade fractional shares"),
+        ], None, "RebalanceResponse"),
+        ("/api/v1/portfolio/{id}/realized-gains", "get", "portfolio", "Realized gains by holding period, for tax reporting", &[
+            id,
+            ("year", "integer", "Calendar year of the sales (UTC); every year when omitted"),
+        ], None, "RealizedGainsReport"),
+        ("/api/v1/watchlists", "get", "watchlists", "List watchlists", &[], None, "[]Watchlist"),
+        ("/api/v1/watchlists", "post", "watchlists", "Create a watchlist", &[], Some("CreateWatchlistRequest"), "Watchlist"),
+        ("/api/v1/watchlists/{id}", "get", "watchlists", "Get a watchlist", &[watchlist_id], None, "Watchlist"),
+        ("/api/v1/watchlists/{id}", "put", "watchlists", "Rename, describe or replace the symbols of a watchlist", &[watchlist_id], Some("UpdateWatchlistRequest"), "Watchlist"),
+        ("/api/v1/watchlists/{id}", "delete", "watchlists", "Delete a watchlist", &[watchlist_id], None, "Watchlist"),
+        ("/api/v1/watchlists/{id}/symbols", "post", "watchlists", "Add symbols", &[watchlist_id], Some("WatchlistSymbolsRequest"), "Watchlist"),
+        ("/api/v1/watchlists/{id}/symbols/{symbol}", "delete", "watchlists", "Remove a symbol", &[watchlist_id, ("symbol", "string", "")], None, "Watchlist"),
+        ("/api/v1/watchlists/{id}/quotes", "get", "watchlists", "Quotes and latest indicator values for every member", &[
+            watchlist_id,
+            ("indicators", "string", "e.g. rsi(period=14),sma(period=50); the default set when left out, none when empty"),
+        ], None, "WatchlistQuotesResponse"),
+        ("/api/v1/orders", "get", "orders", "List paper orders, oldest first", &[
+            ("portfolio_id", "string", "Only this portfolio's orders"),
+            ("status", "string", "open, filled, cancelled or rejected"),
+        ], None, "[]Order"),
+        ("/api/v1/orders", "post", "orders", "Place a paper order; marketable quote-filled orders fill at once", &[], Some("PlaceOrderRequest"), "Order"),
+        ("/api/v1/orders/process", "post", "orders", "Try to fill every open order now; returns the ones filled or rejected", &[], None, "[]Order"),
+        ("/api/v1/orders/{id}", "get", "orders", "Get an order", &[order_id], None, "Order"),
+        ("/api/v1/orders/{id}", "delete", "orders", "Cancel an open order", &[order_id], None, "Order"),
+        ("/api/v1/scheduler", "get", "scheduler", "Whether the scheduler is running, and every job's status", &[], None, "SchedulerStatus"),
+        ("/api/v1/scheduler/start", "post", "scheduler", "Resume running scheduled jobs", &[], None, "SchedulerStatus"),
+        ("/api/v1/scheduler/stop", "post", "scheduler", "Pause every scheduled job", &[], None, "SchedulerStatus"),
+        ("/api/v1/scheduler/jobs", "post", "scheduler", "Schedule a job", &[], Some("JobRequest"), "Job"),
+        ("/api/v1/scheduler/jobs/{id}", "get", "scheduler", "Get a job and its last run", &[job_id], None, "Job"),
+        ("/api/v1/scheduler/jobs/{id}", "delete", "scheduler", "Remove a job", &[job_id], None, "Job"),
+        ("/api/v1/scheduler/jobs/{id}/start", "post", "scheduler", "Enable a job", &[job_id], None, "Job"),
+        ("/api/v1/scheduler/jobs/{id}/stop", "post", "scheduler", "Disable a job", &[job_id], None, "Job"),
+        ("/api/v1/scheduler/jobs/{id}/run", "post", "scheduler", "Run a job now and wait for it", &[job_id], None, "Job"),
+        ("/api/v1/cache/stats", "get", "system", "Response cache statistics", &[], None, "CacheStatsResponse"),
+        ("/api/v1/cache/clear", "post", "system", "Empty the response and fundamentals caches", &[], None, "CacheClearResponse"),
+    ];
+
+    let mut paths = Map::new();
+    for &(path, method, tag, summary, params, body, response) in endpoints {
+        let item = paths.entry(path).or_insert_with(|| json!({}));
+        item[method] = operation(path, tag, summary, params, body, response);
+    }
+
+    paths.insert("/api/v1/stream".to_string(), json!({
+        "get": {
+            "tags": ["market"],
+            "summary": "WebSocket quote stream",
+            "description": "Upgrade to a WebSocket, then send {\"action\": \"subscribe\", \"tickers\": [...]} or \"unsubscribe\". The server pushes a StreamMessage every interval seconds.",
+            "parameters": [
+                { "name": "tickers", "in": "query", "schema": ty("string"), "description": "Initial subscriptions, comma-separated" },
+                { "name": "interval", "in": "query", "schema": ty("integer"), "description": "Seconds between pushes" },
+            ],
+            "responses": { "101": { "description": "Switching Protocols" } },
+        }
+    }));
+    paths.insert("/api/v1/sse".to_string(), json!({
+        "get": {
+            "tags": ["market"],
+            "summary": "Server-sent events: quotes, indicators on bar close, alerts",
+            "description": "A text/event-stream of `quote` events (Quote) every interval seconds, `indicators` events (IndicatorUpdate) when a candle_interval bar closes, `alert` events (TriggeredAlert) for alerts on the tickers, and `warning` events for failed fetches. Event ids only increase; reconnect with Last-Event-ID (or last_event_id) to replay alerts missed in between.",
+            "parameters": [
+                { "name": "tickers", "in": "query", "required": true, "schema": ty("string"), "description": "Comma-separated symbols, at most max_tickers_per_request" },
+                { "name": "interval", "in": "query", "schema": ty("integer"), "description": "Seconds between quote events" },
+                { "name": "candle_interval", "in": "query", "schema": ty("string"), "description": "Intraday bars for indicators events: 1m (default), 2m, 5m, 15m, 30m, 60m, 90m or 1h" },
+                { "name": "indicators", "in": "query", "schema": ty("string"), "description": "Indicators sent as bars close, e.g. rsi(period=14),sma(period=20); none when omitted" },
+                { "name": "last_event_id", "in": "query", "schema": ty("integer"), "description": "For clients that can't send the Last-Event-ID header" },
+            ],
+            "responses": {
+                "200": { "description": "Event stream", "content": { "text/event-stream": { "schema": ty("string") } } },
+                "400": { "description": "Invalid parameters", "content": { "application/json": { "schema": ty("Error") } } },
+            },
+        }
+    }));
+    paths.insert("/metrics".to_string(), json!({
+        "get": {
+            "tags": ["system"],
+            "summary": "Prometheus metrics",
+            "responses": { "200": { "description": "OK", "content": { "text/plain": { "schema": ty("string") } } } },
+        }
+    }));
+    Value::Object(paths)
+}
+
+// ErrorResponse; `code` is ApiError::code, AuthError::code, or not_found for unknown routes
+fn error_schema() -> Value {
+    let mut schema = object(&[("error", "string"), ("code", "string"), ("request_id", "string"), ("fields", "[]FieldError?")]);
+    schema["properties"]["code"] = string_enum(&[
+        "invalid_ticker", "invalid_date_range", "invalid_parameters", "not_found", "rate_limited", "upstream_error",
+        "calculation_error", "storage_error", "missing_api_key", "invalid_api_key", "quota_exceeded",
+    ]);
+    schema["properties"]["request_id"]["description"] = json!("The caller's X-Request-Id, or a generated one; also sent as X-Request-Id");
+    schema["properties"]["fields"]["description"] = json!("Each rejected request field, when the request failed validation");
+    schema
+}
+
+fn schemas() -> Value {
+    let schemas = vec![
+        ("Error", error_schema()),
+        ("FieldError", object(&[("field", "string"), ("message", "string")])),
+        ("HistoricalDataResponse", object(&[("data", "{}TickerData"), ("errors", "[]string"), ("next_cursor", "string?")])),
+        ("TickerData", object(&[
+            ("symbol", "string"), ("candles", "[]CandleData"), ("indicators", "{}[]number?"),
+            ("insufficient_data", "[]InsufficientData"), ("relative_strength", "RelativeStrengthData?"), ("meta", "TickerMeta"),
+        ])),
+        ("CandleData", object(&[
+            ("timestamp", "integer"), ("datetime", "string"), ("exchange_datetime", "string"), ("session", "string?"),
+            ("open", "number"), ("high", "number"), ("low", "number"), ("close", "number"),
+            ("volume", "number?"), ("adj_close", "number?"), ("change", "number?"), ("change_percent", "number?"), ("log_return", "number?"),
+        ])),
+        ("InsufficientData", object(&[("indicator", "string"), ("needed", "integer"), ("got", "integer")])),
+        ("IndicatorUpdate", object(&[("symbol", "string"), ("interval", "string"), ("candle", "CandleData"), ("indicators", "{}number")])),
+        ("RelativeStrengthData", object(&[("benchmark", "string"), ("line", "[]number"), ("slope", "[]number")])),
+        ("TickerMeta", object(&[
+            ("currency", "string"), ("exchange", "string"), ("instrument_type", "string"), ("asset_class", "AssetClass"),
+            ("volume_unit", "string?"), ("timezone", "string"), ("regular_market_price", "number"),
+            ("fifty_two_week_high", "number?"), ("fifty_two_week_low", "number?"),
+            ("market_cap", "number?"), ("pe_ratio", "number?"), ("dividend_yield", "number?"),
+        ])),
+        ("AssetClass", string_enum(&["equity", "etf", "mutual_fund", "index", "future", "crypto", "forex", "other"])),
+        ("Quote", object(&[
+            ("symbol", "string"), ("price", "number"), ("change", "number"), ("change_percent", "number"), ("volume", "integer"),
+            ("bid", "number?"), ("ask", "number?"), ("bid_size", "integer?"), ("ask_size", "integer?"),
+            ("high_52w", "number"), ("low_52w", "number"), ("market_cap", "number?"), ("pe_ratio", "number?"), ("dividend_yield", "number?"),
+            ("pre_market_price", "number?"), ("pre_market_change", "number?"), ("post_market_price", "number?"), ("post_market_change", "number?"),
+            ("last_updated", "string"),
+        ])),
+        ("QuoteResponse", object(&[("quotes", "{}Quote"), ("errors", "[]string")])),
+        ("StreamMessage", json!({
+            "type": "object",
+            "description": "type is subscriptions (tickers), quotes (quotes, errors) or error (message)",
+            "properties": {
+                "type": string_enum(&["subscriptions", "quotes", "error"]),
+                "tickers": ty("[]string"), "quotes": ty("{}Quote"), "errors": ty("[]string"), "message": ty("string"),
+            },
+            "required": ["type"],
+        })),
+        ("MarketSummary", object(&[
+            ("indices", "{}Quote"), ("market_status", "string"), ("next_open", "string"), ("next_close", "string"),
+            ("last_updated", "string"),
+        ])),
+        ("MarketStatus", object(&[
+            ("status", "string"), ("timezone", "string"), ("exchange_time", "string"), ("is_trading_day", "boolean"),
+            ("holiday", "string?"), ("early_close", "boolean"), ("next_open", "string"), ("next_close", "string"),
+        ])),
+        ("TradingDay", object(&[
+            ("date", "string"), ("is_trading_day", "boolean"), ("holiday", "string?"), ("early_close", "boolean"),
+            ("pre_open", "string?"), ("open", "string?"), ("close", "string?"), ("post_close", "string?"),
+        ])),
+        ("QuoteSummaryResponse", json!({
+            "type": "object",
+            "description": "Yahoo quoteSummary modules; each is null when Yahoo doesn't return it",
+            "properties": {
+                "symbol": ty("string"),
+                "asset_profile": ty("any"), "financial_data": ty("any"), "default_key_statistics": ty("any"),
+                "summary_detail": ty("any"), "price": ty("any"), "summary_profile": ty("any"),
+                "earnings": ty("EarningsData?"), "calendar_events": ty("CalendarEvents?"),
+                "recommendation_trend": ty("[]AnalystRecommendation?"), "earnings_trend": ty("EarningsTrend?"),
+                "next_earnings": ty("UpcomingEarnings?"),
+            },
+            "required": ["symbol"],
+        })),
+        ("EarningsData", object(&[
+            ("financial_currency", "string?"), ("quarterly_eps", "[]QuarterlyEps"), ("current_quarter", "string?"),
+            ("current_quarter_estimate", "number?"), ("yearly", "[]PeriodFinancials"), ("quarterly", "[]PeriodFinancials"),
+        ])),
+        ("QuarterlyEps", object(&[("quarter", "string"), ("actual", "number?"), ("estimate", "number?")])),
+        ("PeriodFinancials", object(&[("period", "string"), ("revenue", "number?"), ("earnings", "number?")])),
+        ("CalendarEvents", object(&[
+            ("earnings_dates", "[]string"), ("is_earnings_date_estimate", "boolean"),
+            ("earnings_average", "number?"), ("earnings_low", "number?"), ("earnings_high", "number?"),
+            ("revenue_average", "number?"), ("revenue_low", "number?"), ("revenue_high", "number?"),
+            ("ex_dividend_date", "string?"), ("dividend_date", "string?"),
+        ])),
+        ("UpcomingEarnings", object(&[
+            ("date", "string"), ("date_range_end", "string?"), ("is_estimate", "boolean"), ("eps_estimate", "number?"), ("revenue_estimate", "number?"),
+        ])),
+        ("NewsResponse", object(&[("stories", "[]NewsStory"), ("total_count", "integer")])),
+        ("NewsStory", object(&[
+            ("uuid", "string"), ("title", "string"), ("link", "string"), ("summary", "string?"), ("publisher", "string"),
+            ("author", "string?"), ("publish_time", "integer"), ("provider_publish_time", "integer"), ("news_type", "string"),
+            ("thumbnail", "string?"), ("related_tickers", "[]string"),
+        ])),
+        ("CalendarResponse", object(&[("earnings", "[]EarningsEvent"), ("dividends", "[]DividendEvent"), ("splits", "[]SplitEvent"), ("ipos", "[]IpoEvent")])),
+        ("EarningsEvent", object(&[
+            ("ticker", "string"), ("company_name", "string"), ("earnings_date", "string"), ("earnings_call_time", "string?"),
+            ("eps_estimate", "number?"), ("reported_eps", "number?"), ("surprise_percent", "number?"),
+        ])),
+        ("DividendEvent", object(&[
+            ("ticker", "string"), ("company_name", "string"), ("ex_dividend_date", "string"), ("dividend_rate", "number"),
+            ("annual_dividend_rate", "number"), ("annual_dividend_yield", "number"), ("pay_date", "string?"),
+        ])),
+        ("SplitEvent", object(&[
+            ("ticker", "string"), ("company_name", "string"), ("ex_date", "string"), ("split_ratio", "string"),
+            ("from_factor", "number"), ("to_factor", "number"),
+        ])),
+        ("IpoEvent", object(&[
+            ("ticker", "string"), ("company_name", "string"), ("ipo_date", "string"), ("price_range_low", "number?"),
+            ("price_range_high", "number?"), ("currency", "string"), ("exchange", "string"),
+        ])),
+        ("ReportsResponse", json!({
+            "type": "object",
+            "description": "financials: income_statement, balance_sheet and cash_flow statements; analysis: recommendations and estimates",
+            "properties": { "financials": ty("FinancialReports"), "analysis": ty("AnalysisReports") },
+            "required": ["financials", "analysis"],
+        })),
+        ("AnalysisReports", object(&[
+            ("symbol", "string"), ("analyst_recommendations", "[]AnalystRecommendation"), ("earnings_estimates", "[]EarningsEstimate"),
+            ("revenue_estimates", "[]RevenueEstimate"), ("eps_trend", "[]EpsTrend"),
+        ])),
+        ("EarningsTrend", object(&[("earnings_estimates", "[]EarningsEstimate"), ("revenue_estimates", "[]RevenueEstimate"), ("eps_trend", "[]EpsTrend")])),
+        ("AnalystRecommendation", object(&[
+            ("period", "string"), ("strong_buy", "integer"), ("buy", "integer"), ("hold", "integer"), ("sell", "integer"),
+            ("strong_sell", "integer"), ("mean_recommendation", "number"),
+        ])),
+        ("EarningsEstimate", object(&[
+            ("period", "string"), ("avg", "number?"), ("low", "number?"), ("high", "number?"), ("year_ago_eps", "number?"),
+            ("number_of_estimates", "integer?"), ("growth", "number?"),
+        ])),
+        ("RevenueEstimate", object(&[
+            ("period", "string"), ("avg", "number?"), ("low", "number?"), ("high", "number?"), ("year_ago_sales", "number?"),
+            ("number_of_estimates", "integer?"), ("sales_growth", "number?"),
+        ])),
+        ("EpsTrend", object(&[
+            ("period", "string"), ("current", "number?"), ("seven_days_ago", "number?"), ("thirty_days_ago", "number?"),
+            ("sixty_days_ago", "number?"), ("ninety_days_ago", "number?"), ("up_last_7_days", "integer?"), ("up_last_30_days", "integer?"),
+            ("down_last_7_days", "integer?"), ("down_last_30_days", "integer?"),
+        ])),
+        ("FundamentalsResponse", object(&[("symbol", "string"), ("period_type", "string"), ("currency", "string?"), ("financials", "FinancialReports")])),
+        ("FinancialReports", object(&[
+            ("income_statement", "[]FinancialStatement"), ("balance_sheet", "[]FinancialStatement"), ("cash_flow", "[]FinancialStatement"),
+        ])),
+        ("FinancialStatement", object(&[("date", "string"), ("period_type", "string"), ("data", "{}number?")])),
+        ("CorporateActionsResponse", object(&[("symbol", "string"), ("dividends", "[]DividendAction"), ("splits", "[]SplitAction")])),
+        ("DividendAction", object(&[("date", "string"), ("timestamp", "integer"), ("amount", "number")])),
+        ("SplitAction", object(&[("date", "string"), ("timestamp", "integer"), ("numerator", "number"), ("denominator", "number"), ("ratio", "string")])),
+        ("SearchResponse", object(&[("query", "string"), ("results", "[]SymbolMatch")])),
+        ("SymbolMatch", object(&[("symbol", "string"), ("name", "string"), ("exchange", "string"), ("asset_type", "string")])),
+        ("ConfluenceResponse", object(&[("symbol", "string"), ("timeframes", "[]TimeframeScore"), ("score", "number"), ("bias", "string")])),
+        ("TimeframeScore", object(&[
+            ("timeframe", "string"), ("candles", "integer"), ("close", "number"), ("ma_alignment", "number?"),
+            ("rsi", "number?"), ("macd", "number?"), ("score", "number?"), ("bias", "string"),
+        ])),
+        ("SignalsResponse", object(&[
+            ("symbol", "string"), ("interval", "string"), ("rules", "[]RuleSignals"), ("history", "[]ScorePoint"),
+            ("score", "number"), ("signal", "string"),
+        ])),
+        ("RuleSignals", object(&[("rule", "string"), ("weight", "number"), ("events", "[]SignalEvent"), ("stance", "string")])),
+        ("SignalEvent", object(&[("date", "string"), ("timestamp", "integer"), ("signal", "string")])),
+        ("ScorePoint", object(&[("timestamp", "integer"), ("score", "number"), ("signal", "string")])),
+        ("IndicatorConfig", object(&[("name", "string"), ("params", "{}any?")])),
+        ("ScreenerRequest", object(&[
+            ("filters", "[]ScreenerFilter?"), ("tickers", "[]string?"), ("indicators", "[]IndicatorConfig?"),
+            ("sort_by", "string?"), ("sort_order", "string?"), ("limit", "integer?"), ("offset", "integer?"),
+            ("screener_type", "string?"), ("predefined_screener", "string?"), ("concurrency", "integer?"),
+        ])),
+        ("ScreenerFilter", object(&[("field", "string"), ("operator", "string"), ("value", "any"), ("secondary_value", "any?")])),
+        ("ScreenerResponse", object(&[("results", "[]ScreenerResult"), ("total_count", "integer")])),
+        ("ScreenerResult", object(&[
+            ("symbol", "string"), ("name", "string"), ("price", "number"), ("change", "number"), ("change_percent", "number"),
+            ("volume", "integer"), ("market_cap", "number?"), ("pe_ratio", "number?"), ("indicators", "{}number?"),
+        ])),
+        ("BundleRequest", object(&[("items", "[]BundleItem")])),
+        ("BundleItem", object(&[
+            ("ticker", "string"), ("quote", "boolean?"), ("candles", "BundleCandles?"), ("options", "BundleOptions?"), ("news", "BundleNews?"),
+        ])),
+        ("BundleCandles", object(&[
+            ("interval", "string?"), ("range", "string?"), ("start_date", "string?"), ("end_date", "string?"), ("include_indicators", "boolean?"),
+            ("indicators", "[]IndicatorConfig?"), ("include_returns", "boolean?"), ("adjust", "boolean?"), ("include_prepost", "boolean?"),
+        ])),
+        ("BundleOptions", object(&[("max_days", "number?")])),
+        ("BundleNews", object(&[("count", "integer?")])),
+        ("BundleResponse", object(&[("results", "{}BundleResult")])),
+        ("BatchCommand", string_enum(&["quote", "hist", "options"])),
+        ("BatchRequest", object(&[
+            ("command", "BatchCommand?"), ("tickers", "[]string?"), ("symbols", "string?"), ("nasdaq", "boolean?"), ("range", "string?"),
+            ("concurrency", "integer?"),
+        ])),
+        ("BatchResponse", object(&[
+            ("command", "BatchCommand"), ("total", "integer"), ("succeeded", "integer"), ("failed", "integer"), ("results", "[]BatchResult"),
+        ])),
+        ("BatchResult", object(&[("ticker", "string"), ("summary", "string?"), ("error", "string?")])),
+        ("BundleResult", object(&[
+            ("quote", "Quote?"), ("candles", "TickerData?"), ("options", "OptionsSummary?"), ("news", "NewsResponse?"), ("errors", "[]string"),
+        ])),
+        ("OptionsSummary", object(&[
+            ("underlying_price", "number"), ("total_call_open_interest", "integer"), ("total_put_open_interest", "integer"),
+            ("put_call_oi_ratio", "number?"), ("put_call_volume_ratio", "number?"), ("nearest_expiration", "string?"),
+            ("nearest_max_pain", "number?"), ("expirations", "[]string"),
+        ])),
+        ("PositionSizeRequest", object(&[
+            ("account_value", "number"), ("risk_pct", "number"), ("entry", "number"),
+            ("stop_distance", "number?"), ("atr", "number?"), ("atr_multiplier", "number?"),
+        ])),
+        ("PositionSizeResponse", object(&[
+            ("shares", "integer"), ("stop_distance", "number"), ("stop_price", "number"), ("risk_amount", "number"), ("position_value", "number"),
+        ])),
+        ("OptionsChainResponse", object(&[
+            ("symbol", "string"), ("underlying_price", "number"), ("expirations", "{}ExpirationData"), ("greeks_params", "GreeksParams?"),
+        ])),
+        ("ExpirationData", object(&[
+            ("expiration_date", "string"), ("days_to_expiry", "number"), ("trading_days_to_expiry", "integer"),
+            ("calls", "[]OptionContractData"), ("puts", "[]OptionContractData"),
+        ])),
+        ("OptionContractData", object(&[
+            ("strike", "number"), ("bid", "number"), ("ask", "number"), ("mid", "number"), ("spread_pct", "number"), ("last", "number"),
+            ("volume", "integer"), ("open_interest", "integer"), ("implied_volatility", "number?"), ("greeks", "GreeksData?"),
+        ])),
+        ("GreeksData", object(&[
+            ("delta", "number"), ("gamma", "number"), ("theta", "number"), ("theta_per_day", "number"),
+            ("vega", "number"), ("rho", "number"), ("theoretical_price", "number"),
+        ])),
+        ("GreeksParams", object(&[("volatility", "number"), ("volatility_source", "string"), ("risk_free_rate", "number"), ("pricing_model", "string"), ("dividend_yield", "number")])),
+        ("OptionPosition", object(&[
+            ("option_type", "string"), ("strike", "number"), ("quantity", "integer"), ("entry_price", "number"), ("days_to_expiry", "number"),
+        ])),
+        ("OptionsPnLRequest", object(&[
+            ("positions", "[]OptionPosition"), ("underlying_prices", "[]number"), ("volatility", "number?"),
+            ("risk_free_rate", "number?"), ("days_to_expiry", "number?"), ("pricing_model", "string?"), ("dividend_yield", "number?"),
+            ("contract_size", "number?"), ("valuation_date_offsets", "[]number?"),
+        ])),
+        ("VolSurfaceResponse", object(&[
+            ("symbol", "string"), ("underlying_price", "number"), ("risk_free_rate", "number"),
+            ("moneyness", "[]number"), ("strikes", "[]number"), ("expirations", "[]SurfaceSlice"),
+        ])),
+        ("SurfaceSlice", object(&[
+            ("expiration_date", "string"), ("days_to_expiry", "number"), ("atm_iv", "number?"),
+            ("iv", "[]number?"), ("quotes", "[]SurfaceQuote"),
+        ])),
+        ("SurfaceQuote", object(&[("strike", "number"), ("iv", "number")])),
+        ("OiProfileResponse", object(&[
+            ("symbol", "string"), ("underlying_price", "number"), ("total_call_open_interest", "integer"), ("total_put_open_interest", "integer"),
+            ("put_call_oi_ratio", "number?"), ("put_call_volume_ratio", "number?"), ("strikes", "[]StrikeOpenInterest"), ("expirations", "[]ExpiryOiProfile"),
+        ])),
+        ("ExpiryOiProfile", object(&[
+            ("expiration_date", "string"), ("days_to_expiry", "number"), ("max_pain", "number?"),
+            ("total_call_open_interest", "integer"), ("total_put_open_interest", "integer"), ("total_call_volume", "integer"), ("total_put_volume", "integer"),
+            ("put_call_oi_ratio", "number?"), ("put_call_volume_ratio", "number?"), ("strikes", "[]StrikeOpenInterest"),
+        ])),
+        ("StrikeOpenInterest", object(&[
+            ("strike", "number"), ("call_open_interest", "integer"), ("put_open_interest", "integer"), ("call_volume", "integer"), ("put_volume", "integer"),
+        ])),
+        ("UnusualActivityRequest", object(&[
+            ("tickers", "[]string"), ("min_volume", "integer?"), ("min_volume_oi_ratio", "number?"), ("min_sweep_premium", "number?"),
+            ("min_iv_ratio", "number?"), ("max_days", "number?"), ("risk_free_rate", "number?"), ("limit", "integer?"), ("concurrency", "integer?"),
+        ])),
+        ("UnusualActivityResponse", object(&[("contracts", "[]UnusualContract"), ("total_count", "integer"), ("scanned", "[]string"), ("errors", "[]string")])),
+        ("UnusualContract", object(&[
+            ("symbol", "string"), ("underlying_price", "number"), ("expiration_date", "string"), ("days_to_expiry", "number"),
+            ("option_type", "string"), ("strike", "number"), ("bid", "number"), ("ask", "number"), ("last", "number"),
+            ("volume", "integer"), ("open_interest", "integer"), ("volume_oi_ratio", "number?"), ("premium", "number"),
+            ("sweep_side", "string?"), ("implied_volatility", "number?"), ("iv_ratio", "number?"), ("signals", "[]string"), ("score", "number"),
+        ])),
+        ("OptionsPnLResponse", object(&[("positions", "[]PositionAnalysis"), ("portfolio", "PortfolioAnalysis"), ("exposure", "GreeksExposure"), ("probability", "ProbabilityAnalysis?")])),
+        ("ProbabilityAnalysis", object(&[
+            ("underlying_price", "number"), ("volatility", "number"), ("risk_free_rate", "number"), ("dividend_yield", "number"),
+            ("days", "number"), ("expected_price", "number"), ("probability_of_profit", "number"), ("expected_pnl", "number"),
+            ("breakevens", "[]BreakevenProbability"),
+        ])),
+        ("BreakevenProbability", object(&[("price", "number"), ("probability_above", "number"), ("probability_of_touch", "number")])),
+        ("GreeksExposure", object(&[
+            ("underlying_price", "number"), ("net_delta", "number"), ("dollar_delta", "number"), ("net_gamma", "number"),
+            ("net_theta_per_day", "number"), ("net_vega", "number"),
+        ])),
+        ("PositionAnalysis", object(&[("position", "OptionPosition"), ("greeks", "GreeksData"), ("pnl_curve", "[]PnLPoint")])),
+        ("PortfolioAnalysis", object(&[
+            ("total_greeks", "GreeksData"), ("total_pnl_curve", "[]PnLPoint"), ("break_even_points", "[]number"),
+            ("max_profit", "number?"), ("max_loss", "number?"), ("pnl_curves_by_date", "[]DatedPnLCurve"),
+        ])),
+        ("DatedPnLCurve", object(&[
+            ("days_from_now", "number"), ("label", "string"), ("pnl_curve", "[]PnLPoint"), ("break_even_points", "[]number"),
+        ])),
+        ("PnLPoint", object(&[("underlying_price", "number"), ("pnl", "number"), ("total_value", "number")])),
+        ("Portfolio", object(&[
+            ("id", "string"), ("name", "string"), ("description", "string?"), ("created_at", "string"), ("updated_at", "string"),
+            ("cash_balance", "number"), ("cost_basis_method", "CostBasisMethod"), ("realized_pnl", "number"),
+            ("positions", "[]Position"), ("alerts", "[]PortfolioAlert"), ("target_weights", "{}number"),
+        ])),
+        ("Position", object(&[
+            ("symbol", "string"), ("quantity", "number"), ("average_cost", "number"), ("opened_at", "string"),
+            ("realized_pnl", "number"), ("lots", "[]Lot"),
+        ])),
+        ("Lot", object(&[("id", "string"), ("quantity", "number"), ("price", "number"), ("acquired_at", "string")])),
+        ("CostBasisMethod", string_enum(&["average_cost", "fifo", "lifo"])),
+        ("AlertCondition", string_enum(&["price_above", "price_below", "change_percent_above", "change_percent_below", "volume_above"])),
+        ("PortfolioAlert", object(&[
+            ("id", "string"), ("symbol", "string"), ("condition", "AlertCondition"), ("value", "number"),
+            ("created_at", "string"), ("triggered_at", "string?"), ("triggered_value", "number?"),
+        ])),
+        ("TriggeredAlert", object(&[("portfolio_id", "string"), ("portfolio_name", "string"), ("alert", "PortfolioAlert")])),
+        ("TransactionType", string_enum(&["buy", "sell", "deposit", "withdrawal", "dividend"])),
+        ("Transaction", object(&[
+            ("id", "string"), ("portfolio_id", "string"), ("transaction_type", "TransactionType"), ("symbol", "string?"),
+            ("quantity", "number"), ("price", "number"), ("amount", "number"), ("realized_pnl", "number?"), ("timestamp", "string"),
+            ("lots", "[]RealizedLot"),
+        ])),
+        ("HoldingTerm", string_enum(&["short_term", "long_term"])),
+        ("RealizedLot", object(&[
+            ("lot_id", "string"), ("quantity", "number"), ("acquired_at", "string"), ("proceeds", "number"),
+            ("cost_basis", "number"), ("gain", "number"), ("term", "HoldingTerm"),
+        ])),
+        ("CreatePortfolioRequest", object(&[("name", "string"), ("description", "string?"), ("cost_basis_method", "CostBasisMethod?")])),
+        ("AddPositionRequest", object(&[("symbol", "string"), ("quantity", "number"), ("price", "number")])),
+        ("TransactionRequest", object(&[
+            ("type", "TransactionType"), ("symbol", "string?"), ("quantity", "number?"), ("price", "number?"), ("amount", "number?"),
+            ("cost_basis_method", "CostBasisMethod?"), ("lots", "[]LotQuantity?"),
+        ])),
+        ("LotQuantity", object(&[("lot_id", "string"), ("quantity", "number?")])),
+        ("RealizedGainsReport", object(&[
+            ("portfolio_id", "string"), ("year", "integer?"), ("short_term", "GainSummary"), ("long_term", "GainSummary"),
+            ("unclassified", "GainSummary"), ("total_gain", "number"), ("lots", "[]RealizedGain"),
+        ])),
+        ("GainSummary", object(&[("proceeds", "number"), ("cost_basis", "number"), ("gain", "number"), ("lots", "integer")])),
+        ("RealizedGain", object(&[
+            ("transaction_id", "string"), ("symbol", "string"), ("sold_at", "string"), ("lot_id", "string"),
+            ("quantity", "number"), ("acquired_at", "string"), ("proceeds", "number"), ("cost_basis", "number"),
+            ("gain", "number"), ("term", "HoldingTerm"),
+        ])),
+        ("CreateAlertRequest", object(&[("symbol", "string"), ("condition", "AlertCondition"), ("value", "number")])),
+        ("CorrelationRequest", object(&[("tickers", "[]string"), ("range", "string?")])),
+        ("CorrelationResponse", object(&[
+            ("tickers", "[]string"), ("range", "string"), ("observations", "integer"), ("matrix", "[][]number?"),
+            ("average_correlation", "number?"), ("average_by_ticker", "{}number"), ("errors", "[]string"),
+        ])),
+        ("TargetWeightsRequest", object(&[("target_weights", "{}number")])),
+        ("RebalanceResponse", object(&[
+            ("portfolio_id", "string"), ("total_value", "number"), ("cash_balance", "number"), ("cash_after", "number"),
+            ("drift_threshold", "number"), ("trades", "[]RebalanceTrade"),
+        ])),
+        ("RebalanceTrade", object(&[
+            ("symbol", "string"), ("price", "number"), ("quantity", "number"), ("current_weight", "number"),
+            ("target_weight", "number"), ("drift", "number"), ("action", "string"), ("trade_quantity", "number"),
+            ("trade_value", "number"), ("weight_after", "number"),
+        ])),
+        ("OptimizeRequest", object(&[
+            ("tickers", "[]string"), ("range", "string?"), ("long_only", "boolean?"), ("max_weight", "number?"),
+            ("risk_free_rate", "number?"), ("frontier_points", "integer?"),
+        ])),
+        ("OptimizeResponse", object(&[
+            ("tickers", "[]string"), ("range", "string"), ("observations", "integer"), ("expected_returns", "{}number"),
+            ("volatilities", "{}number"), ("min_variance", "OptimizedPortfolio"), ("max_sharpe", "OptimizedPortfolio?"),
+            ("frontier", "[]OptimizedPortfolio"), ("errors", "[]string"),
+        ])),
+        ("OptimizedPortfolio", object(&[
+            ("weights", "{}number"), ("expected_return", "number"), ("volatility", "number"), ("sharpe_ratio", "number?"),
+        ])),
+        ("PortfolioRiskResponse", object(&[
+            ("portfolio_id", "string"), ("benchmark", "string"), ("range", "string"), ("observations", "integer"),
+            ("market_value", "number"), ("weights", "{}number"), ("beta", "number?"), ("volatility", "number"),
+            ("annual_return", "number"), ("sharpe_ratio", "number?"), ("sortino_ratio", "number?"), ("confidence", "number"),
+            ("var", "number"), ("cvar", "number"), ("var_amount", "number"), ("cvar_amount", "number"), ("max_drawdown", "number"),
+        ])),
+        ("Watchlist", object(&[
+            ("id", "string"), ("name", "string"), ("description", "string?"), ("symbols", "[]string"),
+            ("created_at", "string"), ("updated_at", "string"),
+        ])),
+        ("CreateWatchlistRequest", object(&[("name", "string"), ("description", "string?"), ("symbols", "[]string?")])),
+        ("UpdateWatchlistRequest", object(&[("name", "string?"), ("description", "string?"), ("symbols", "[]string?")])),
+        ("WatchlistSymbolsRequest", object(&[("symbols", "[]string")])),
+        ("WatchlistQuotesResponse", object(&[("watchlist", "Watchlist"), ("members", "[]WatchlistMember"), ("errors", "[]string")])),
+        ("OrderSide", string_enum(&["buy", "sell"])),
+        ("OrderType", string_enum(&["market", "limit", "stop"])),
+        ("OrderStatus", string_enum(&["open", "filled", "cancelled", "rejected"])),
+        ("FillSource", string_enum(&["quote", "next_candle"])),
+        ("Order", object(&[
+            ("id", "string"), ("portfolio_id", "string"), ("symbol", "string"), ("side", "OrderSide"), ("order_type", "OrderType"),
+            ("quantity", "number"), ("limit_price", "number?"), ("stop_price", "number?"), ("fill_source", "FillSource"),
+            ("status", "OrderStatus"), ("created_at", "string"), ("updated_at", "string"), ("filled_at", "string?"),
+            ("fill_price", "number?"), ("transaction_id", "string?"), ("reason", "string?"),
+        ])),
+        ("PlaceOrderRequest", object(&[
+            ("portfolio_id", "string"), ("symbol", "string"), ("side", "OrderSide"), ("type", "OrderType"), ("quantity", "number"),
+            ("limit_price", "number?"), ("stop_price", "number?"), ("fill_source", "FillSource?"),
+        ])),
+        ("PortfolioSnapshot", object(&[
+            ("portfolio_id", "string"), ("taken_at", "string"), ("cash_balance", "number"), ("market_value", "number"),
+            ("total_value", "number"),
+        ])),
+        ("JobTask", object(&[
+            ("type", "string"), ("watchlist_id", "string?"), ("screener", "ScreenerRequest?"),
+        ])),
+        ("JobRequest", object(&[
+            ("name", "string"), ("task", "JobTask"), ("every_seconds", "integer?"), ("cron", "string?"), ("enabled", "boolean?"),
+            ("trading_days_only", "boolean?"),
+        ])),
+        ("Schedule", object(&[("kind", "string"), ("seconds", "integer?"), ("expression", "string?")])),
+        ("JobRun", object(&[
+            ("started_at", "string"), ("duration_ms", "integer"), ("ok", "boolean"), ("summary", "string"), ("result", "any?"),
+        ])),
+        ("Job", object(&[
+            ("id", "string"), ("name", "string"), ("task", "JobTask"), ("schedule", "Schedule"), ("enabled", "boolean"),
+            ("trading_days_only", "boolean"), ("running", "boolean"), ("created_at", "string"), ("next_run", "string?"),
+            ("last_run", "JobRun?"), ("runs", "integer"), ("failures", "integer"),
+        ])),
+        ("SchedulerStatus", object(&[("running", "boolean"), ("jobs", "[]Job")])),
+        ("WatchlistMember", object(&[("symbol", "string"), ("quote", "Quote?"), ("indicators", "{}number")])),
+        ("CacheStatsResponse", object(&[("responses", "ResponseCacheStats"), ("fundamentals_entries", "integer")])),
+        ("ResponseCacheStats", object(&[
+            ("entries", "integer"), ("fresh_entries", "integer"), ("hits", "integer"), ("misses", "integer"),
+            ("revalidated", "integer"), ("ttl_secs", "integer"),
+        ])),
+        ("CacheClearResponse", object(&[("responses_cleared", "integer"), ("fundamentals_cleared", "integer")])),
+    ];
+    Value::Object(schemas.into_iter().map(|(name, schema)| (name.to_string(), schema)).collect())
+}
+
+pub fn document() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "yeast",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Market data, indicators, options analytics and portfolios. When the server runs with API keys, send one in X-API-Key, as a bearer token, or as ?api_key=. Failures answer with an Error body: 400 for invalid parameters, 401 for a missing or unknown key, 404 for missing data or records, 429 when a quota is used up or the upstream throttles us, 502 when the upstream fails and 500 otherwise; branch on `code` rather than the message. Send X-Request-Id to choose the id errors and logs carry.",
+        },
+        "paths": paths(),
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "ApiKeyHeader": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+                "BearerAuth": { "type": "http", "scheme": "bearer" },
+                "ApiKeyQuery": { "type": "apiKey", "in": "query", "name": "api_key" },
+            },
+        },
+        // The empty requirement covers servers started without keys
+        "security": [{ "ApiKeyHeader": [] }, { "BearerAuth": [] }, { "ApiKeyQuery": [] }, {}],
+    })
+}
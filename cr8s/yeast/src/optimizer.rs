@@ -0,0 +1,185 @@
+// optimizer.rs - mean-variance portfolio weights from expected returns and their covariance
+//
+// Every portfolio here solves   min  w'Σw - λ μ'w   with weights summing to 1 and each weight in
+// [lower, upper], by projected gradient descent (the projection onto that set is a clamp plus a
+// shift found by bisection). λ = 0 gives the minimum-variance portfolio; sweeping λ upward walks
+// the efficient frontier towards the highest-return corner, and the max-Sharpe portfolio is the
+// frontier point with the best (return - risk free) / volatility. μ and Σ are annualized.
+
+const MAX_ITERATIONS: usize = 5_000;
+const TOLERANCE: f64 = 1e-10;
+// λ values swept when searching the frontier for the max-Sharpe portfolio, then refinement rounds
+const SHARPE_SEARCH_STEPS: usize = 40;
+const GOLDEN_SECTION_STEPS: usize = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Bounds {
+    // Whether weights summing to 1 fit in the bounds at all
+    pub fn feasible(&self, assets: usize) -> bool {
+        self.lower <= self.upper && self.lower * assets as f64 <= 1.0 && self.upper * assets as f64 >= 1.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    pub weights: Vec<f64>,
+    pub expected_return: f64,
+    pub volatility: f64,
+}
+
+impl Allocation {
+    pub fn sharpe(&self, risk_free_rate: f64) -> Option<f64> {
+        (self.volatility > 0.0).then(|| (self.expected_return - risk_free_rate) / self.volatility)
+    }
+}
+
+pub struct Optimizer<'a> {
+    mean: &'a [f64],
+    covariance: &'a [Vec<f64>],
+    bounds: Bounds,
+    step: f64,
+}
+
+impl<'a> Optimizer<'a> {
+    pub fn new(mean: &'a [f64], covariance: &'a [Vec<f64>], bounds: Bounds) -> Self {
+        // 1 / Lipschitz constant of the gradient; the largest row sum bounds Σ's top eigenvalue
+        let max_row: f64 = covariance.iter().map(|row| row.iter().map(|v| v.abs()).sum::<f64>()).fold(0.0, f64::max);
+        let step = if max_row > 0.0 { 1.0 / (2.0 * max_row) } else { 1.0 };
+        Self { mean, covariance, bounds, step }
+    }
+
+    fn allocation(&self, weights: Vec<f64>) -> Allocation {
+        let expected_return = weights.iter().zip(self.mean).map(|(w, m)| w * m).sum();
+        let variance: f64 = (0..weights.len())
+            .map(|i| weights[i] * (0..weights.len()).map(|j| self.covariance[i][j] * weights[j]).sum::<f64>())
+            .sum();
+        Allocation { weights, expected_return, volatility: variance.max(0.0).sqrt() }
+    }
+
+    // Closest point to `v` with weights summing to 1 inside the bounds
+    fn project(&self, v: &[f64]) -> Vec<f64> {
+        let Bounds { lower, upper } = self.bounds;
+        let total = |shift: f64| v.iter().map(|x| (x - shift).clamp(lower, upper)).sum::<f64>();
+        let (mut lo, mut hi) = (
+            v.iter().fold(f64::INFINITY, |m, x| m.min(*x)) - upper,
+            v.iter().fold(f64::NEG_INFINITY, |m, x| m.max(*x)) - lower,
+        );
+        // total() falls from n x upper to n x lower as the shift grows
+        for _ in 0..100 {
+            let mid = (lo + hi) / 2.0;
+            if total(mid) > 1.0 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let shift = (lo + hi) / 2.0;
+        v.iter().map(|x| (x - shift).clamp(lower, upper)).collect()
+    }
+
+    // min w'Σw - λ μ'w, starting from `start` (warm starts make a sweep over λ cheap)
+    pub fn solve(&self, risk_tolerance: f64, start: &[f64]) -> Allocation {
+        let n = self.mean.len();
+        let mut weights = self.project(start);
+        for _ in 0..MAX_ITERATIONS {
+            let stepped: Vec<f64> = (0..n)
+                .map(|i| {
+                    let gradient = 2.0 * (0..n).map(|j| self.covariance[i][j] * weights[j]).sum::<f64>()
+                        - risk_tolerance * self.mean[i];
+                    weights[i] - self.step * gradient
+                })
+                .collect();
+            let next = self.project(&stepped);
+            let change: f64 = next.iter().zip(&weights).map(|(a, b)| (a - b).abs()).sum();
+            weights = next;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+        self.allocation(weights)
+    }
+
+    fn equal_weights(&self) -> Vec<f64> {
+        vec![1.0 / self.mean.len() as f64; self.mean.len()]
+    }
+
+    pub fn min_variance(&self) -> Allocation {
+        self.solve(0.0, &self.equal_weights())
+    }
+
+    // λ at which return differences outweigh variance, so the sweep spans the whole frontier
+    fn tolerance_scale(&self) -> f64 {
+        let spread = self.mean.iter().fold(f64::NEG_INFINITY, |m, x| m.max(*x))
+            - self.mean.iter().fold(f64::INFINITY, |m, x| m.min(*x));
+        if spread > 0.0 { 0.5 / (self.step * spread) } else { 1.0 }
+    }
+
+    // 0 followed by `steps - 1` values growing geometrically across four decades around the scale
+    fn risk_tolerances(&self, steps: usize) -> Vec<f64> {
+        let scale = self.tolerance_scale();
+        let last = steps.saturating_sub(2).max(1) as f64;
+        std::iter::once(0.0)
+            .chain((0..steps.saturating_sub(1)).map(|k| scale * 10f64.powf(-2.0 + 4.0 * k as f64 / last)))
+            .collect()
+    }
+
+    // `steps` frontier portfolios from minimum variance towards the highest return; neighbours that
+    // land on the same weights are merged
+    pub fn frontier(&self, steps: usize) -> Vec<Allocation> {
+        let mut points: Vec<Allocation> = Vec::with_capacity(steps);
+        let mut start = self.equal_weights();
+        for risk_tolerance in self.risk_tolerances(steps) {
+            let point = self.solve(risk_tolerance, &start);
+            start.clone_from(&point.weights);
+            let repeat = points.last().is_some_and(|last| {
+                last.weights.iter().zip(&point.weights).all(|(a, b)| (a - b).abs() < 1e-6)
+            });
+            if !repeat {
+                points.push(point);
+            }
+        }
+        points
+    }
+
+    // Best Sharpe ratio along the frontier: the best of a λ sweep, refined by golden-section search
+    // between its neighbours. None when no portfolio has volatility.
+    pub fn max_sharpe(&self, risk_free_rate: f64) -> Option<Allocation> {
+        let tolerances = self.risk_tolerances(SHARPE_SEARCH_STEPS);
+        let mut start = self.equal_weights();
+        let mut best: Option<(usize, f64, Allocation)> = None;
+        for (k, risk_tolerance) in tolerances.iter().enumerate() {
+            let point = self.solve(*risk_tolerance, &start);
+            start.clone_from(&point.weights);
+            match point.sharpe(risk_free_rate) {
+                Some(sharpe) if best.as_ref().is_none_or(|(_, s, _)| sharpe > *s) => best = Some((k, sharpe, point)),
+                _ => {}
+            }
+        }
+        let (k, mut best_sharpe, mut best_point) = best?;
+
+        let (mut lo, mut hi) = (tolerances[k.saturating_sub(1)], tolerances[(k + 1).min(tolerances.len() - 1)]);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..GOLDEN_SECTION_STEPS {
+            let (a, b) = (hi - ratio * (hi - lo), lo + ratio * (hi - lo));
+            let (pa, pb) = (self.solve(a, &best_point.weights), self.solve(b, &best_point.weights));
+            let (sa, sb) = (pa.sharpe(risk_free_rate).unwrap_or(f64::MIN), pb.sharpe(risk_free_rate).unwrap_or(f64::MIN));
+            let (better, sharpe) = if sa >= sb {
+                hi = b;
+                (pa, sa)
+            } else {
+                lo = a;
+                (pb, sb)
+            };
+            if sharpe > best_sharpe {
+                best_sharpe = sharpe;
+                best_point = better;
+            }
+        }
+        Some(best_point)
+    }
+}
@@ -1,88 +1,11 @@
 // options_math.rs
-use std::f64::consts::E;
+// Pricing and Greeks live in indicators-core so the WASM package can use them too
 //use std::thread;
 
-#[derive(Debug, Clone, Copy)]
-pub enum OptionType {
-    Call,
-    Put,
-}
-
-#[derive(Debug)]
-pub struct OptionGreeks {
-    pub delta: f64,
-    pub gamma: f64,
-    pub theta: f64,
-    pub vega: f64,
-    pub rho: f64,
-    pub price: f64,
-}
-
-/// Approximate the standard normal PDF
-fn norm_pdf(x: f64) -> f64 {
-    (1.0 / (2.0 * std::f64::consts::PI).sqrt()) * (-0.5 * x * x).exp()
-}
-
-/// Approximate the standard normal CDF (Abramowitz and Stegun formula 7.1.26)
-fn norm_cdf(x: f64) -> f64 {
-    let k = 1.0 / (1.0 + 0.2316419 * x.abs());
-    let k_sum = k * (0.319381530 + k * (-0.356563782 + k * (1.781477937 + k * (-1.821255978 + 1.330274429 * k))));
-    let cdf = 1.0 - norm_pdf(x) * k_sum;
-
-    if x < 0.0 { 1.0 - cdf } else { cdf }
-}
-
-pub fn black_scholes_greeks(
-    s: f64,      // underlying price
-    k: f64,      // strike price
-    t: f64,      // time to expiration in years
-    r: f64,      // risk-free rate
-    sigma: f64,  // volatility
-    option_type: OptionType,
-) -> OptionGreeks {
-    let sqrt_t = t.sqrt();
-    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * sqrt_t);
-    let d2 = d1 - sigma * sqrt_t;
-
-    let price = match option_type {
-        OptionType::Call => s * norm_cdf(d1) - k * E.powf(-r * t) * norm_cdf(d2),
-        OptionType::Put => k * E.powf(-r * t) * norm_cdf(-d2) - s * norm_cdf(-d1),
-    };
-
-    let delta = match option_type {
-        OptionType::Call => norm_cdf(d1),
-        OptionType::Put => norm_cdf(d1) - 1.0,
-    };
-
-    let gamma = norm_pdf(d1) / (s * sigma * sqrt_t);
-
-    let theta = match option_type {
-        OptionType::Call => {
-            -(s * norm_pdf(d1) * sigma) / (2.0 * sqrt_t)
-            - r * k * E.powf(-r * t) * norm_cdf(d2)
-        }
-        OptionType::Put => {
-            -(s * norm_pdf(d1) * sigma) / (2.0 * sqrt_t)
-            + r * k * E.powf(-r * t) * norm_cdf(-d2)
-        }
-    };
-
-    let vega = s * norm_pdf(d1) * sqrt_t;
-
-    let rho = match option_type {
-        OptionType::Call => k * t * E.powf(-r * t) * norm_cdf(d2),
-        OptionType::Put => -k * t * E.powf(-r * t) * norm_cdf(-d2),
-    };
-
-    OptionGreeks {
-        delta,
-        gamma,
-        theta,
-        vega,
-        rho,
-        price,
-    }
-}
+pub use indicators_core::options::{
+    black_scholes_greeks, implied_volatility, intrinsic, norm_pdf, option_greeks, probability_above, probability_of_touch, OptionGreeks,
+    OptionType, PricingModel,
+};
 
 // fn compute_greeks_parallel(
 //     underlying_price: f64,
@@ -134,6 +57,23 @@ pub fn calculate_pnl(position_size: f64, old_price: f64, new_price: f64) -> f64
     (new_price - old_price) * position_size
 }
 
+/// Shares to buy so that hitting the stop loses `risk_pct` percent of the account,
+/// capped at what the account can afford at `entry`. Zero when the stop distance or entry isn't positive.
+pub fn position_size(account_value: f64, risk_pct: f64, entry: f64, stop_distance: f64) -> u64 {
+    if stop_distance <= 0.0 || entry <= 0.0 || account_value <= 0.0 || risk_pct <= 0.0 {
+        return 0;
+    }
+    let risk_amount = account_value * risk_pct / 100.0;
+    let by_risk = (risk_amount / stop_distance).floor();
+    let affordable = (account_value / entry).floor();
+    by_risk.min(affordable) as u64
+}
+
+/// Volatility-based variant: the stop sits `multiplier` ATRs away from entry
+pub fn atr_position_size(account_value: f64, risk_pct: f64, entry: f64, atr: f64, multiplier: f64) -> u64 {
+    position_size(account_value, risk_pct, entry, atr * multiplier)
+}
+
 #[derive(Debug, Clone)]
 pub struct OptionData {
     pub strike: f64,
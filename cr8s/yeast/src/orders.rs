@@ -0,0 +1,131 @@
+// orders.rs - when a paper order fills, and at what price
+//
+// Against a quote the order fills at the quoted price once it's marketable: any price for market
+// orders, at or through the limit for limit orders, and at or past the stop for stop orders.
+// Against a bar the fill is at the open if that's already marketable (a gap through the limit or
+// stop gets the better or worse open, as it would in the market), otherwise at the limit or stop
+// itself when the bar's range reaches it.
+
+use crate::portfolio::{Order, OrderSide, OrderType};
+use crate::types::Candle;
+
+pub fn quote_fill_price(order: &Order, price: f64) -> Option<f64> {
+    (price > 0.0 && marketable(order, price)).then_some(price)
+}
+
+pub fn candle_fill_price(order: &Order, candle: &Candle) -> Option<f64> {
+    if candle.open <= 0.0 {
+        return None;
+    }
+    if marketable(order, candle.open) {
+        return Some(candle.open);
+    }
+    // Price the bar has to reach, and whether reaching it means trading up to it
+    let (level, upward) = match (order.order_type, order.side) {
+        (OrderType::Market, _) => return None,
+        (OrderType::Limit, OrderSide::Buy) => (order.limit_price?, false),
+        (OrderType::Limit, OrderSide::Sell) => (order.limit_price?, true),
+        (OrderType::Stop, OrderSide::Buy) => (order.stop_price?, true),
+        (OrderType::Stop, OrderSide::Sell) => (order.stop_price?, false),
+    };
+    let reached = if upward { candle.high >= level } else { candle.low <= level };
+    reached.then_some(level)
+}
+
+fn marketable(order: &Order, price: f64) -> bool {
+    match (order.order_type, order.side) {
+        (OrderType::Market, _) => true,
+        (OrderType::Limit, OrderSide::Buy) => order.limit_price.is_some_and(|limit| price <= limit),
+        (OrderType::Limit, OrderSide::Sell) => order.limit_price.is_some_and(|limit| price >= limit),
+        (OrderType::Stop, OrderSide::Buy) => order.stop_price.is_some_and(|stop| price >= stop),
+        (OrderType::Stop, OrderSide::Sell) => order.stop_price.is_some_and(|stop| price <= stop),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::{FillSource, OrderStatus};
+
+    fn order(order_type: OrderType, side: OrderSide, level: Option<f64>) -> Order {
+        Order {
+            id: "o1".to_string(),
+            portfolio_id: "p1".to_string(),
+            symbol: "AAPL".to_string(),
+            side,
+            order_type,
+            quantity: 10.0,
+            limit_price: level.filter(|_| order_type == OrderType::Limit),
+            stop_price: level.filter(|_| order_type == OrderType::Stop),
+            fill_source: FillSource::NextCandle,
+            status: OrderStatus::Open,
+            created_at: String::new(),
+            updated_at: String::new(),
+            filled_at: None,
+            fill_price: None,
+            transaction_id: None,
+            reason: None,
+        }
+    }
+
+    fn bar(open: f64, high: f64, low: f64) -> Candle {
+        Candle { timestamp: 0, open, high, low, close: open, volume: None }
+    }
+
+    #[test]
+    fn market_orders_fill_at_the_quote_or_open() {
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            let market = order(OrderType::Market, side, None);
+            assert_eq!(quote_fill_price(&market, 101.0), Some(101.0));
+            assert_eq!(candle_fill_price(&market, &bar(100.0, 105.0, 95.0)), Some(100.0));
+            assert_eq!(quote_fill_price(&market, 0.0), None);
+        }
+    }
+
+    #[test]
+    fn buy_limit_fills_at_or_below_the_limit() {
+        let limit = order(OrderType::Limit, OrderSide::Buy, Some(100.0));
+        assert_eq!(quote_fill_price(&limit, 100.0), Some(100.0));
+        assert_eq!(quote_fill_price(&limit, 100.5), None);
+        assert_eq!(candle_fill_price(&limit, &bar(98.0, 99.0, 97.0)), Some(98.0)); // gapped below
+        assert_eq!(candle_fill_price(&limit, &bar(102.0, 103.0, 99.0)), Some(100.0));
+        assert_eq!(candle_fill_price(&limit, &bar(102.0, 103.0, 101.0)), None);
+    }
+
+    #[test]
+    fn sell_limit_fills_at_or_above_the_limit() {
+        let limit = order(OrderType::Limit, OrderSide::Sell, Some(100.0));
+        assert_eq!(quote_fill_price(&limit, 100.0), Some(100.0));
+        assert_eq!(quote_fill_price(&limit, 99.5), None);
+        assert_eq!(candle_fill_price(&limit, &bar(103.0, 104.0, 102.0)), Some(103.0)); // gapped above
+        assert_eq!(candle_fill_price(&limit, &bar(98.0, 101.0, 97.0)), Some(100.0));
+        assert_eq!(candle_fill_price(&limit, &bar(98.0, 99.0, 97.0)), None);
+    }
+
+    #[test]
+    fn buy_stop_fills_at_or_above_the_stop() {
+        let stop = order(OrderType::Stop, OrderSide::Buy, Some(100.0));
+        assert_eq!(quote_fill_price(&stop, 100.0), Some(100.0));
+        assert_eq!(quote_fill_price(&stop, 99.5), None);
+        assert_eq!(candle_fill_price(&stop, &bar(103.0, 104.0, 102.0)), Some(103.0)); // gapped through
+        assert_eq!(candle_fill_price(&stop, &bar(98.0, 101.0, 97.0)), Some(100.0));
+        assert_eq!(candle_fill_price(&stop, &bar(98.0, 99.0, 97.0)), None);
+    }
+
+    #[test]
+    fn sell_stop_fills_at_or_below_the_stop() {
+        let stop = order(OrderType::Stop, OrderSide::Sell, Some(100.0));
+        assert_eq!(quote_fill_price(&stop, 100.0), Some(100.0));
+        assert_eq!(quote_fill_price(&stop, 100.5), None);
+        assert_eq!(candle_fill_price(&stop, &bar(97.0, 98.0, 96.0)), Some(97.0)); // gapped through
+        assert_eq!(candle_fill_price(&stop, &bar(102.0, 103.0, 99.0)), Some(100.0));
+        assert_eq!(candle_fill_price(&stop, &bar(102.0, 103.0, 101.0)), None);
+    }
+
+    #[test]
+    fn orders_missing_their_price_never_fill() {
+        let limit = order(OrderType::Limit, OrderSide::Buy, None);
+        assert_eq!(quote_fill_price(&limit, 1.0), None);
+        assert_eq!(candle_fill_price(&limit, &bar(1.0, 2.0, 0.5)), None);
+    }
+}
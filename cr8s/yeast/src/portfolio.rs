@@ -0,0 +1,2039 @@
+// portfolio.rs - Portfolios, positions and their transaction history, watchlists and paper orders
+//
+// PortfolioManager holds the business rules; where the data lives is behind PortfolioStore so the
+// server can run against memory (tests, throwaway sessions) or a SQLite file that survives restarts.
+// Watchlists and orders are kept in the same store, so --portfolio-db persists them too.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::{ApiError, DividendAction, Quote};
+use crate::notify::Notifier;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Portfolio {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String, // ISO 8601 timestamp
+    pub updated_at: String,
+    pub cash_balance: f64,   // buys debit it and sells credit it, so it can go negative without deposits
+    pub cost_basis_method: CostBasisMethod,
+    pub realized_pnl: f64,   // across all sells, including positions since closed
+    pub positions: Vec<Position>,
+    pub alerts: Vec<PortfolioAlert>,
+    pub target_weights: BTreeMap<String, f64>, // symbol -> share of total value to rebalance towards; the rest is cash
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Position {
+    pub symbol: String,
+    pub quantity: f64,
+    pub average_cost: f64,
+    pub opened_at: String,
+    pub realized_pnl: f64,
+    pub lots: Vec<Lot>, // open buys, oldest first
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Lot {
+    pub id: String, // for picking specific lots on a sale
+    pub quantity: f64,
+    pub price: f64,
+    pub acquired_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    PriceAbove,
+    PriceBelow,
+    ChangePercentAbove,
+    ChangePercentBelow,
+    VolumeAbove,
+}
+
+impl AlertCondition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertCondition::PriceAbove => "price_above",
+            AlertCondition::PriceBelow => "price_below",
+            AlertCondition::ChangePercentAbove => "change_percent_above",
+            AlertCondition::ChangePercentBelow => "change_percent_below",
+            AlertCondition::VolumeAbove => "volume_above",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "price_above" => Some(AlertCondition::PriceAbove),
+            "price_below" => Some(AlertCondition::PriceBelow),
+            "change_percent_above" => Some(AlertCondition::ChangePercentAbove),
+            "change_percent_below" => Some(AlertCondition::ChangePercentBelow),
+            "volume_above" => Some(AlertCondition::VolumeAbove),
+            _ => None,
+        }
+    }
+
+    // The quote field this condition watches
+    fn observed(&self, quote: &Quote) -> f64 {
+        match self {
+            AlertCondition::PriceAbove | AlertCondition::PriceBelow => quote.price,
+            AlertCondition::ChangePercentAbove | AlertCondition::ChangePercentBelow => quote.change_percent,
+            AlertCondition::VolumeAbove => quote.volume as f64,
+        }
+    }
+
+    fn is_met(&self, observed: f64, threshold: f64) -> bool {
+        match self {
+            AlertCondition::PriceAbove | AlertCondition::ChangePercentAbove | AlertCondition::VolumeAbove => observed > threshold,
+            AlertCondition::PriceBelow | AlertCondition::ChangePercentBelow => observed < threshold,
+        }
+    }
+}
+
+// One-shot: once triggered an alert keeps its trigger time and value and is no longer evaluated
+#[derive(Debug, Serialize, Clone)]
+pub struct PortfolioAlert {
+    pub id: String,
+    pub symbol: String,
+    pub condition: AlertCondition,
+    pub value: f64,
+    pub created_at: String,
+    pub triggered_at: Option<String>,
+    pub triggered_value: Option<f64>, // the observed price / change / volume that tripped it
+}
+
+// An alert that tripped during a check, with the portfolio it belongs to
+#[derive(Debug, Serialize, Clone)]
+pub struct TriggeredAlert {
+    pub portfolio_id: String,
+    pub portfolio_name: String,
+    pub alert: PortfolioAlert,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAlertRequest {
+    pub symbol: String,
+    pub condition: AlertCondition,
+    pub value: f64,
+}
+
+// How a sale's cost is measured: against the position's running average, or against the oldest
+// or newest lots. A sale can also name its lots outright (TransactionRequest::lots).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasisMethod {
+    #[default]
+    AverageCost,
+    Fifo,
+    Lifo,
+}
+
+impl CostBasisMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CostBasisMethod::AverageCost => "average_cost",
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::Lifo => "lifo",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "average_cost" => Some(CostBasisMethod::AverageCost),
+            "fifo" => Some(CostBasisMethod::Fifo),
+            "lifo" => Some(CostBasisMethod::Lifo),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionType {
+    Buy,
+    Sell,
+    Deposit,
+    Withdrawal,
+    Dividend,
+}
+
+impl TransactionType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransactionType::Buy => "buy",
+            TransactionType::Sell => "sell",
+            TransactionType::Deposit => "deposit",
+            TransactionType::Withdrawal => "withdrawal",
+            TransactionType::Dividend => "dividend",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "buy" => Some(TransactionType::Buy),
+            "sell" => Some(TransactionType::Sell),
+            "deposit" => Some(TransactionType::Deposit),
+            "withdrawal" => Some(TransactionType::Withdrawal),
+            "dividend" => Some(TransactionType::Dividend),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Transaction {
+    pub id: String,
+    pub portfolio_id: String,
+    pub transaction_type: TransactionType,
+    pub symbol: Option<String>, // None for cash movements
+    pub quantity: f64,
+    pub price: f64,
+    pub amount: f64, // quantity * price for trades, the cash amount otherwise
+    pub realized_pnl: Option<f64>, // sells only
+    pub timestamp: String,
+    pub lots: Vec<RealizedLot>, // sells only: the tax lots the sale closed
+}
+
+// Held more than a year counts as long-term
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldingTerm {
+    ShortTerm,
+    LongTerm,
+}
+
+impl HoldingTerm {
+    fn between(acquired_at: &str, sold_at: &str) -> Self {
+        let date = |s: &str| DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.date_naive());
+        match (date(acquired_at).and_then(|d| d.checked_add_months(Months::new(12))), date(sold_at)) {
+            (Some(anniversary), Some(sold)) if sold > anniversary => HoldingTerm::LongTerm,
+            _ => HoldingTerm::ShortTerm,
+        }
+    }
+}
+
+// The part of one lot closed by a sale
+#[derive(Debug, Serialize, Clone)]
+pub struct RealizedLot {
+    pub lot_id: String,
+    pub quantity: f64,
+    pub acquired_at: String,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub term: HoldingTerm,
+}
+
+impl RealizedLot {
+    fn new(lot_id: String, quantity: f64, acquired_at: String, proceeds: f64, cost_basis: f64, sold_at: &str) -> Self {
+        let term = HoldingTerm::between(&acquired_at, sold_at);
+        Self { lot_id, quantity, acquired_at, proceeds, cost_basis, gain: proceeds - cost_basis, term }
+    }
+}
+
+// A lot to sell from by id; the whole lot when quantity is left out
+#[derive(Debug, Deserialize, Clone)]
+pub struct LotQuantity {
+    pub lot_id: String,
+    pub quantity: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct GainSummary {
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub lots: usize,
+}
+
+impl GainSummary {
+    fn add(&mut self, proceeds: f64, cost_basis: f64, gain: f64) {
+        self.proceeds += proceeds;
+        self.cost_basis += cost_basis;
+        self.gain += gain;
+        self.lots += 1;
+    }
+}
+
+// One closed lot in a realized gains report
+#[derive(Debug, Serialize)]
+pub struct RealizedGain {
+    pub transaction_id: String,
+    pub symbol: String,
+    pub sold_at: String,
+    #[serde(flatten)]
+    pub lot: RealizedLot,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RealizedGainsReport {
+    pub portfolio_id: String,
+    pub year: Option<i32>, // None covers every year
+    pub short_term: GainSummary,
+    pub long_term: GainSummary,
+    pub unclassified: GainSummary, // sells recorded before lots were tracked: gain only, no holding period
+    pub total_gain: f64,
+    pub lots: Vec<RealizedGain>, // by sale date
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePortfolioRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub cost_basis_method: Option<CostBasisMethod>, // default average_cost
+}
+
+// Body of POST /api/v1/portfolio/{id}/transactions. Trades need symbol, quantity and price;
+// deposits and withdrawals need amount
+#[derive(Debug, Deserialize)]
+pub struct TransactionRequest {
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub symbol: Option<String>,
+    pub quantity: Option<f64>,
+    pub price: Option<f64>,
+    pub amount: Option<f64>,
+    pub cost_basis_method: Option<CostBasisMethod>, // sells: overrides the portfolio's method for this sale
+    pub lots: Option<Vec<LotQuantity>>,             // sells: close exactly these lots
+}
+
+// Body of PUT /api/v1/portfolio/{id}/targets; replaces every target, so {} clears them
+#[derive(Debug, Deserialize)]
+pub struct TargetWeightsRequest {
+    pub target_weights: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddPositionRequest {
+    pub symbol: String,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+// A named list of symbols to follow, in the order they were added
+#[derive(Debug, Serialize, Clone)]
+pub struct Watchlist {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub symbols: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWatchlistRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub symbols: Option<Vec<String>>,
+}
+
+// Body of PUT /api/v1/watchlists/{id}; fields left out keep their value, `symbols` replaces the list
+#[derive(Debug, Deserialize)]
+pub struct UpdateWatchlistRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub symbols: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchlistSymbolsRequest {
+    pub symbols: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+// Market fills at the next price seen; limit at the limit or better; stop becomes a market order
+// once the price reaches the stop
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    Market,
+    Limit,
+    Stop,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Open,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+// What a paper order fills against: the live quote when orders are processed, or the intraday
+// bars that open after the order was placed (at the bar's open, or its limit/stop if the bar
+// trades through it)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FillSource {
+    #[default]
+    Quote,
+    NextCandle,
+}
+
+impl OrderSide {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "buy" => Some(OrderSide::Buy),
+            "sell" => Some(OrderSide::Sell),
+            _ => None,
+        }
+    }
+}
+
+impl OrderType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::Stop => "stop",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "market" => Some(OrderType::Market),
+            "limit" => Some(OrderType::Limit),
+            "stop" => Some(OrderType::Stop),
+            _ => None,
+        }
+    }
+}
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(OrderStatus::Open),
+            "filled" => Some(OrderStatus::Filled),
+            "cancelled" => Some(OrderStatus::Cancelled),
+            "rejected" => Some(OrderStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+impl FillSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FillSource::Quote => "quote",
+            FillSource::NextCandle => "next_candle",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "quote" => Some(FillSource::Quote),
+            "next_candle" => Some(FillSource::NextCandle),
+            _ => None,
+        }
+    }
+}
+
+// A simulated order against a portfolio; filling it records a buy or sell transaction
+#[derive(Debug, Serialize, Clone)]
+pub struct Order {
+    pub id: String,
+    pub portfolio_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    pub limit_price: Option<f64>, // limit orders
+    pub stop_price: Option<f64>,  // stop orders
+    pub fill_source: FillSource,
+    pub status: OrderStatus,
+    pub created_at: String,
+    pub updated_at: String,
+    pub filled_at: Option<String>,
+    pub fill_price: Option<f64>,
+    pub transaction_id: Option<String>, // the trade a fill recorded
+    pub reason: Option<String>,         // why it was rejected
+}
+
+// A portfolio's value at a point in time, recorded by the snapshot_portfolios job
+#[derive(Debug, Serialize, Clone)]
+pub struct PortfolioSnapshot {
+    pub portfolio_id: String,
+    pub taken_at: String,
+    pub cash_balance: f64,
+    pub market_value: f64, // holdings at their quotes; unquoted ones at average cost
+    pub total_value: f64,
+}
+
+// Body of POST /api/v1/orders
+#[derive(Debug, Deserialize)]
+pub struct PlaceOrderRequest {
+    pub portfolio_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub quantity: f64,
+    pub limit_price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub fill_source: Option<FillSource>, // default quote
+}
+
+// Storage backend selection, carried on ApiConfig
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Memory,
+    Sqlite(PathBuf),
+}
+
+pub trait PortfolioStore: Send + Sync {
+    // Writes the portfolio (replacing its positions) and, if given, appends the transaction that
+    // produced this state. Both land or neither does.
+    fn save(&self, portfolio: &Portfolio, transaction: Option<&Transaction>) -> Result<(), ApiError>;
+    fn load(&self, id: &str) -> Result<Option<Portfolio>, ApiError>;
+    fn list(&self) -> Result<Vec<Portfolio>, ApiError>;
+    fn transactions(&self, portfolio_id: &str) -> Result<Vec<Transaction>, ApiError>;
+    fn save_watchlist(&self, watchlist: &Watchlist) -> Result<(), ApiError>;
+    fn load_watchlist(&self, id: &str) -> Result<Option<Watchlist>, ApiError>;
+    fn list_watchlists(&self) -> Result<Vec<Watchlist>, ApiError>;
+    // Ok(false) when there was no such watchlist
+    fn delete_watchlist(&self, id: &str) -> Result<bool, ApiError>;
+    fn save_order(&self, order: &Order) -> Result<(), ApiError>;
+    fn load_order(&self, id: &str) -> Result<Option<Order>, ApiError>;
+    // Oldest first
+    fn list_orders(&self) -> Result<Vec<Order>, ApiError>;
+    fn save_snapshot(&self, snapshot: &PortfolioSnapshot) -> Result<(), ApiError>;
+    // Oldest first
+    fn snapshots(&self, portfolio_id: &str) -> Result<Vec<PortfolioSnapshot>, ApiError>;
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    portfolios: Mutex<HashMap<String, Portfolio>>,
+    transactions: Mutex<Vec<Transaction>>,
+    watchlists: Mutex<HashMap<String, Watchlist>>,
+    orders: Mutex<HashMap<String, Order>>,
+    snapshots: Mutex<Vec<PortfolioSnapshot>>,
+}
+
+impl PortfolioStore for MemoryStore {
+    fn save(&self, portfolio: &Portfolio, transaction: Option<&Transaction>) -> Result<(), ApiError> {
+        self.portfolios.lock().unwrap().insert(portfolio.id.clone(), portfolio.clone());
+        if let Some(transaction) = transaction {
+            self.transactions.lock().unwrap().push(transaction.clone());
+        }
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<Option<Portfolio>, ApiError> {
+        Ok(self.portfolios.lock().unwrap().get(id).cloned())
+    }
+
+    fn list(&self) -> Result<Vec<Portfolio>, ApiError> {
+        let mut portfolios: Vec<Portfolio> = self.portfolios.lock().unwrap().values().cloned().collect();
+        portfolios.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(portfolios)
+    }
+
+    fn transactions(&self, portfolio_id: &str) -> Result<Vec<Transaction>, ApiError> {
+        Ok(self.transactions.lock().unwrap()
+            .iter()
+            .filter(|t| t.portfolio_id == portfolio_id)
+            .cloned()
+            .collect())
+    }
+
+    fn save_watchlist(&self, watchlist: &Watchlist) -> Result<(), ApiError> {
+        self.watchlists.lock().unwrap().insert(watchlist.id.clone(), watchlist.clone());
+        Ok(())
+    }
+
+    fn load_watchlist(&self, id: &str) -> Result<Option<Watchlist>, ApiError> {
+        Ok(self.watchlists.lock().unwrap().get(id).cloned())
+    }
+
+    fn list_watchlists(&self) -> Result<Vec<Watchlist>, ApiError> {
+        let mut watchlists: Vec<Watchlist> = self.watchlists.lock().unwrap().values().cloned().collect();
+        watchlists.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(watchlists)
+    }
+
+    fn delete_watchlist(&self, id: &str) -> Result<bool, ApiError> {
+        Ok(self.watchlists.lock().unwrap().remove(id).is_some())
+    }
+
+    fn save_order(&self, order: &Order) -> Result<(), ApiError> {
+        self.orders.lock().unwrap().insert(order.id.clone(), order.clone());
+        Ok(())
+    }
+
+    fn load_order(&self, id: &str) -> Result<Option<Order>, ApiError> {
+        Ok(self.orders.lock().unwrap().get(id).cloned())
+    }
+
+    fn list_orders(&self) -> Result<Vec<Order>, ApiError> {
+        let mut orders: Vec<Order> = self.orders.lock().unwrap().values().cloned().collect();
+        orders.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(orders)
+    }
+
+    fn save_snapshot(&self, snapshot: &PortfolioSnapshot) -> Result<(), ApiError> {
+        self.snapshots.lock().unwrap().push(snapshot.clone());
+        Ok(())
+    }
+
+    fn snapshots(&self, portfolio_id: &str) -> Result<Vec<PortfolioSnapshot>, ApiError> {
+        Ok(self.snapshots.lock().unwrap()
+            .iter()
+            .filter(|s| s.portfolio_id == portfolio_id)
+            .cloned()
+            .collect())
+    }
+}
+
+// Schema changes are appended here, never edited; PRAGMA user_version records how many have run
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE portfolios (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        cash_balance REAL NOT NULL DEFAULT 0
+    );
+    CREATE TABLE positions (
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        quantity REAL NOT NULL,
+        average_cost REAL NOT NULL,
+        opened_at TEXT NOT NULL,
+        PRIMARY KEY (portfolio_id, symbol)
+    );
+    CREATE TABLE transactions (
+        id TEXT PRIMARY KEY,
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        transaction_type TEXT NOT NULL,
+        symbol TEXT,
+        quantity REAL NOT NULL,
+        price REAL NOT NULL,
+        amount REAL NOT NULL,
+        timestamp TEXT NOT NULL
+    );
+    CREATE INDEX transactions_by_portfolio ON transactions (portfolio_id, timestamp);",
+    // Realized P&L and lot accounting; existing positions become a single lot at their average cost
+    "ALTER TABLE portfolios ADD COLUMN cost_basis_method TEXT NOT NULL DEFAULT 'average_cost';
+    ALTER TABLE portfolios ADD COLUMN realized_pnl REAL NOT NULL DEFAULT 0;
+    ALTER TABLE positions ADD COLUMN realized_pnl REAL NOT NULL DEFAULT 0;
+    ALTER TABLE transactions ADD COLUMN realized_pnl REAL;
+    CREATE TABLE lots (
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        seq INTEGER NOT NULL,
+        quantity REAL NOT NULL,
+        price REAL NOT NULL,
+        acquired_at TEXT NOT NULL,
+        PRIMARY KEY (portfolio_id, symbol, seq)
+    );
+    INSERT INTO lots (portfolio_id, symbol, seq, quantity, price, acquired_at)
+        SELECT portfolio_id, symbol, 0, quantity, average_cost, opened_at FROM positions;",
+    "CREATE TABLE alerts (
+        id TEXT PRIMARY KEY,
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        condition TEXT NOT NULL,
+        value REAL NOT NULL,
+        created_at TEXT NOT NULL,
+        triggered_at TEXT,
+        triggered_value REAL
+    );",
+    "CREATE TABLE watchlists (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE TABLE watchlist_symbols (
+        watchlist_id TEXT NOT NULL REFERENCES watchlists(id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        symbol TEXT NOT NULL,
+        PRIMARY KEY (watchlist_id, symbol)
+    );",
+    "CREATE TABLE target_weights (
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        weight REAL NOT NULL,
+        PRIMARY KEY (portfolio_id, symbol)
+    );",
+    // Lots get ids so sales can pick them; sales record the lots they closed
+    "ALTER TABLE lots ADD COLUMN id TEXT NOT NULL DEFAULT '';
+    UPDATE lots SET id = lower(hex(randomblob(16)));
+    CREATE TABLE realized_lots (
+        transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        lot_id TEXT NOT NULL,
+        quantity REAL NOT NULL,
+        acquired_at TEXT NOT NULL,
+        proceeds REAL NOT NULL,
+        cost_basis REAL NOT NULL,
+        PRIMARY KEY (transaction_id, seq)
+    );",
+    "CREATE TABLE orders (
+        id TEXT PRIMARY KEY,
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        symbol TEXT NOT NULL,
+        side TEXT NOT NULL,
+        order_type TEXT NOT NULL,
+        quantity REAL NOT NULL,
+        limit_price REAL,
+        stop_price REAL,
+        fill_source TEXT NOT NULL,
+        status TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        filled_at TEXT,
+        fill_price REAL,
+        transaction_id TEXT,
+        reason TEXT
+    );
+    CREATE INDEX orders_by_status ON orders (status, created_at);",
+    "CREATE TABLE portfolio_snapshots (
+        portfolio_id TEXT NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+        taken_at TEXT NOT NULL,
+        cash_balance REAL NOT NULL,
+        market_value REAL NOT NULL,
+        total_value REAL NOT NULL,
+        PRIMARY KEY (portfolio_id, taken_at)
+    );",
+];
+
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+fn storage_error(e: rusqlite::Error) -> ApiError {
+    ApiError::StorageError(e.to_string())
+}
+
+impl SqliteStore {
+    pub fn open(path: &PathBuf) -> Result<Self, ApiError> {
+        let mut conn = Connection::open(path).map_err(storage_error)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;").map_err(storage_error)?;
+        Self::migrate(&mut conn)?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn migrate(conn: &mut Connection) -> Result<(), ApiError> {
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(storage_error)?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction().map_err(storage_error)?;
+            tx.execute_batch(migration).map_err(storage_error)?;
+            tx.execute_batch(&format!("PRAGMA user_version = {}", i + 1)).map_err(storage_error)?;
+            tx.commit().map_err(storage_error)?;
+        }
+        Ok(())
+    }
+
+    fn load_positions(conn: &Connection, portfolio_id: &str) -> Result<Vec<Position>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT symbol, quantity, average_cost, opened_at, realized_pnl FROM positions
+             WHERE portfolio_id = ?1 ORDER BY opened_at",
+        )?;
+        let mut positions = stmt.query_map(params![portfolio_id], |row| {
+            Ok(Position {
+                symbol: row.get(0)?,
+                quantity: row.get(1)?,
+                average_cost: row.get(2)?,
+                opened_at: row.get(3)?,
+                realized_pnl: row.get(4)?,
+                lots: Vec::new(),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        let mut lots = conn.prepare(
+            "SELECT id, quantity, price, acquired_at FROM lots WHERE portfolio_id = ?1 AND symbol = ?2 ORDER BY seq",
+        )?;
+        for position in &mut positions {
+            position.lots = lots.query_map(params![portfolio_id, position.symbol], |row| {
+                Ok(Lot {
+                    id: row.get(0)?,
+                    quantity: row.get(1)?,
+                    price: row.get(2)?,
+                    acquired_at: row.get(3)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+        }
+        Ok(positions)
+    }
+
+    fn load_alerts(conn: &Connection, portfolio_id: &str) -> Result<Vec<PortfolioAlert>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, condition, value, created_at, triggered_at, triggered_value FROM alerts
+             WHERE portfolio_id = ?1 ORDER BY created_at",
+        )?;
+        let rows = stmt.query_map(params![portfolio_id], |row| {
+            let condition: String = row.get(2)?;
+            let condition = AlertCondition::parse(&condition).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, format!("unknown alert condition {}", condition).into())
+            })?;
+            Ok(PortfolioAlert {
+                id: row.get(0)?,
+                symbol: row.get(1)?,
+                condition,
+                value: row.get(3)?,
+                created_at: row.get(4)?,
+                triggered_at: row.get(5)?,
+                triggered_value: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    fn load_target_weights(conn: &Connection, portfolio_id: &str) -> Result<BTreeMap<String, f64>, rusqlite::Error> {
+        let mut stmt = conn.prepare("SELECT symbol, weight FROM target_weights WHERE portfolio_id = ?1")?;
+        let rows = stmt.query_map(params![portfolio_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    fn portfolio_from_row(row: &rusqlite::Row) -> Result<Portfolio, rusqlite::Error> {
+        let method: String = row.get(6)?;
+        Ok(Portfolio {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            created_at: row.get(3)?,
+            updated_at: row.get(4)?,
+            cash_balance: row.get(5)?,
+            cost_basis_method: CostBasisMethod::parse(&method).unwrap_or_default(),
+            realized_pnl: row.get(7)?,
+            positions: Vec::new(),
+            alerts: Vec::new(),
+            target_weights: BTreeMap::new(),
+        })
+    }
+
+    fn load_watchlist_symbols(conn: &Connection, watchlist_id: &str) -> Result<Vec<String>, rusqlite::Error> {
+        let mut stmt = conn.prepare("SELECT symbol FROM watchlist_symbols WHERE watchlist_id = ?1 ORDER BY seq")?;
+        let rows = stmt.query_map(params![watchlist_id], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    fn order_from_row(row: &rusqlite::Row) -> Result<Order, rusqlite::Error> {
+        fn column<T>(row: &rusqlite::Row, index: usize, parse: fn(&str) -> Option<T>) -> Result<T, rusqlite::Error> {
+            let value: String = row.get(index)?;
+            parse(&value).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, format!("unknown order field value {}", value).into())
+            })
+        }
+        Ok(Order {
+            id: row.get(0)?,
+            portfolio_id: row.get(1)?,
+            symbol: row.get(2)?,
+            side: column(row, 3, OrderSide::parse)?,
+            order_type: column(row, 4, OrderType::parse)?,
+            quantity: row.get(5)?,
+            limit_price: row.get(6)?,
+            stop_price: row.get(7)?,
+            fill_source: column(row, 8, FillSource::parse)?,
+            status: column(row, 9, OrderStatus::parse)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+            filled_at: row.get(12)?,
+            fill_price: row.get(13)?,
+            transaction_id: row.get(14)?,
+            reason: row.get(15)?,
+        })
+    }
+
+    fn watchlist_from_row(row: &rusqlite::Row) -> Result<Watchlist, rusqlite::Error> {
+        Ok(Watchlist {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            created_at: row.get(3)?,
+            updated_at: row.get(4)?,
+            symbols: Vec::new(),
+        })
+    }
+}
+
+impl PortfolioStore for SqliteStore {
+    fn save(&self, portfolio: &Portfolio, transaction: Option<&Transaction>) -> Result<(), ApiError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(storage_error)?;
+
+        tx.execute(
+            "INSERT INTO portfolios (id, name, description, created_at, updated_at, cash_balance, cost_basis_method, realized_pnl)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(id) DO UPDATE SET name = ?2, description = ?3, updated_at = ?5, cash_balance = ?6,
+                cost_basis_method = ?7, realized_pnl = ?8",
+            params![
+                portfolio.id, portfolio.name, portfolio.description, portfolio.created_at, portfolio.updated_at,
+                portfolio.cash_balance, portfolio.cost_basis_method.as_str(), portfolio.realized_pnl
+            ],
+        ).map_err(storage_error)?;
+
+        tx.execute("DELETE FROM positions WHERE portfolio_id = ?1", params![portfolio.id]).map_err(storage_error)?;
+        tx.execute("DELETE FROM lots WHERE portfolio_id = ?1", params![portfolio.id]).map_err(storage_error)?;
+        for position in &portfolio.positions {
+            tx.execute(
+                "INSERT INTO positions (portfolio_id, symbol, quantity, average_cost, opened_at, realized_pnl)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![portfolio.id, position.symbol, position.quantity, position.average_cost, position.opened_at, position.realized_pnl],
+            ).map_err(storage_error)?;
+            for (seq, lot) in position.lots.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO lots (portfolio_id, symbol, seq, id, quantity, price, acquired_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![portfolio.id, position.symbol, seq as i64, lot.id, lot.quantity, lot.price, lot.acquired_at],
+                ).map_err(storage_error)?;
+            }
+        }
+
+        tx.execute("DELETE FROM alerts WHERE portfolio_id = ?1", params![portfolio.id]).map_err(storage_error)?;
+        for alert in &portfolio.alerts {
+            tx.execute(
+                "INSERT INTO alerts (id, portfolio_id, symbol, condition, value, created_at, triggered_at, triggered_value)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    alert.id, portfolio.id, alert.symbol, alert.condition.as_str(), alert.value,
+                    alert.created_at, alert.triggered_at, alert.triggered_value
+                ],
+            ).map_err(storage_error)?;
+        }
+
+        tx.execute("DELETE FROM target_weights WHERE portfolio_id = ?1", params![portfolio.id]).map_err(storage_error)?;
+        for (symbol, weight) in &portfolio.target_weights {
+            tx.execute(
+                "INSERT INTO target_weights (portfolio_id, symbol, weight) VALUES (?1, ?2, ?3)",
+                params![portfolio.id, symbol, weight],
+            ).map_err(storage_error)?;
+        }
+
+        if let Some(t) = transaction {
+            tx.execute(
+                "INSERT INTO transactions (id, portfolio_id, transaction_type, symbol, quantity, price, amount, timestamp, realized_pnl)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![t.id, t.portfolio_id, t.transaction_type.as_str(), t.symbol, t.quantity, t.price, t.amount, t.timestamp, t.realized_pnl],
+            ).map_err(storage_error)?;
+            for (seq, lot) in t.lots.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO realized_lots (transaction_id, seq, lot_id, quantity, acquired_at, proceeds, cost_basis)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![t.id, seq as i64, lot.lot_id, lot.quantity, lot.acquired_at, lot.proceeds, lot.cost_basis],
+                ).map_err(storage_error)?;
+            }
+        }
+
+        tx.commit().map_err(storage_error)
+    }
+
+    fn load(&self, id: &str) -> Result<Option<Portfolio>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let portfolio = conn.query_row(
+            "SELECT id, name, description, created_at, updated_at, cash_balance, cost_basis_method, realized_pnl
+             FROM portfolios WHERE id = ?1",
+            params![id],
+            Self::portfolio_from_row,
+        ).optional().map_err(storage_error)?;
+
+        match portfolio {
+            Some(mut portfolio) => {
+                portfolio.positions = Self::load_positions(&conn, id).map_err(storage_error)?;
+                portfolio.alerts = Self::load_alerts(&conn, id).map_err(storage_error)?;
+                portfolio.target_weights = Self::load_target_weights(&conn, id).map_err(storage_error)?;
+                Ok(Some(portfolio))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<Portfolio>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, description, created_at, updated_at, cash_balance, cost_basis_method, realized_pnl
+             FROM portfolios ORDER BY created_at",
+        ).map_err(storage_error)?;
+        let mut portfolios = stmt.query_map([], Self::portfolio_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(storage_error)?;
+
+        for portfolio in &mut portfolios {
+            portfolio.positions = Self::load_positions(&conn, &portfolio.id).map_err(storage_error)?;
+            portfolio.alerts = Self::load_alerts(&conn, &portfolio.id).map_err(storage_error)?;
+            portfolio.target_weights = Self::load_target_weights(&conn, &portfolio.id).map_err(storage_error)?;
+        }
+        Ok(portfolios)
+    }
+
+    fn transactions(&self, portfolio_id: &str) -> Result<Vec<Transaction>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, portfolio_id, transaction_type, symbol, quantity, price, amount, timestamp, realized_pnl
+             FROM transactions WHERE portfolio_id = ?1 ORDER BY timestamp",
+        ).map_err(storage_error)?;
+        let rows = stmt.query_map(params![portfolio_id], |row| {
+            let kind: String = row.get(2)?;
+            Ok(Transaction {
+                id: row.get(0)?,
+                portfolio_id: row.get(1)?,
+                transaction_type: TransactionType::parse(&kind).unwrap_or(TransactionType::Buy),
+                symbol: row.get(3)?,
+                quantity: row.get(4)?,
+                price: row.get(5)?,
+                amount: row.get(6)?,
+                timestamp: row.get(7)?,
+                realized_pnl: row.get(8)?,
+                lots: Vec::new(),
+            })
+        }).map_err(storage_error)?;
+        let mut transactions = rows.collect::<Result<Vec<_>, _>>().map_err(storage_error)?;
+
+        let mut lots_stmt = conn.prepare(
+            "SELECT lot_id, quantity, acquired_at, proceeds, cost_basis FROM realized_lots
+             WHERE transaction_id = ?1 ORDER BY seq",
+        ).map_err(storage_error)?;
+        for t in transactions.iter_mut().filter(|t| t.transaction_type == TransactionType::Sell) {
+            let sold_at = t.timestamp.clone();
+            let lots = lots_stmt.query_map(params![t.id], |row| {
+                Ok(RealizedLot::new(row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, &sold_at))
+            }).map_err(storage_error)?;
+            t.lots = lots.collect::<Result<Vec<_>, _>>().map_err(storage_error)?;
+        }
+        Ok(transactions)
+    }
+
+    fn save_watchlist(&self, watchlist: &Watchlist) -> Result<(), ApiError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(storage_error)?;
+
+        tx.execute(
+            "INSERT INTO watchlists (id, name, description, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET name = ?2, description = ?3, updated_at = ?5",
+            params![watchlist.id, watchlist.name, watchlist.description, watchlist.created_at, watchlist.updated_at],
+        ).map_err(storage_error)?;
+
+        tx.execute("DELETE FROM watchlist_symbols WHERE watchlist_id = ?1", params![watchlist.id]).map_err(storage_error)?;
+        for (seq, symbol) in watchlist.symbols.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO watchlist_symbols (watchlist_id, seq, symbol) VALUES (?1, ?2, ?3)",
+                params![watchlist.id, seq as i64, symbol],
+            ).map_err(storage_error)?;
+        }
+
+        tx.commit().map_err(storage_error)
+    }
+
+    fn load_watchlist(&self, id: &str) -> Result<Option<Watchlist>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let watchlist = conn.query_row(
+            "SELECT id, name, description, created_at, updated_at FROM watchlists WHERE id = ?1",
+            params![id],
+            Self::watchlist_from_row,
+        ).optional().map_err(storage_error)?;
+
+        match watchlist {
+            Some(mut watchlist) => {
+                watchlist.symbols = Self::load_watchlist_symbols(&conn, id).map_err(storage_error)?;
+                Ok(Some(watchlist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn list_watchlists(&self) -> Result<Vec<Watchlist>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, description, created_at, updated_at FROM watchlists ORDER BY created_at",
+        ).map_err(storage_error)?;
+        let mut watchlists = stmt.query_map([], Self::watchlist_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(storage_error)?;
+
+        for watchlist in &mut watchlists {
+            watchlist.symbols = Self::load_watchlist_symbols(&conn, &watchlist.id).map_err(storage_error)?;
+        }
+        Ok(watchlists)
+    }
+
+    fn delete_watchlist(&self, id: &str) -> Result<bool, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute("DELETE FROM watchlists WHERE id = ?1", params![id]).map_err(storage_error)?;
+        Ok(deleted > 0)
+    }
+
+    fn save_order(&self, order: &Order) -> Result<(), ApiError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO orders (id, portfolio_id, symbol, side, order_type, quantity, limit_price, stop_price, fill_source,
+                                 status, created_at, updated_at, filled_at, fill_price, transaction_id, reason)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+             ON CONFLICT(id) DO UPDATE SET status = ?10, updated_at = ?12, filled_at = ?13, fill_price = ?14,
+                                           transaction_id = ?15, reason = ?16",
+            params![
+                order.id, order.portfolio_id, order.symbol, order.side.as_str(), order.order_type.as_str(), order.quantity,
+                order.limit_price, order.stop_price, order.fill_source.as_str(), order.status.as_str(), order.created_at,
+                order.updated_at, order.filled_at, order.fill_price, order.transaction_id, order.reason,
+            ],
+        ).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn load_order(&self, id: &str) -> Result<Option<Order>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(&format!("SELECT {} FROM orders WHERE id = ?1", ORDER_COLUMNS), params![id], Self::order_from_row)
+            .optional()
+            .map_err(storage_error)
+    }
+
+    fn list_orders(&self) -> Result<Vec<Order>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM orders ORDER BY created_at", ORDER_COLUMNS)).map_err(storage_error)?;
+        stmt.query_map([], Self::order_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(storage_error)
+    }
+
+    fn save_snapshot(&self, snapshot: &PortfolioSnapshot) -> Result<(), ApiError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO portfolio_snapshots (portfolio_id, taken_at, cash_balance, market_value, total_value)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![snapshot.portfolio_id, snapshot.taken_at, snapshot.cash_balance, snapshot.market_value, snapshot.total_value],
+        ).map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn snapshots(&self, portfolio_id: &str) -> Result<Vec<PortfolioSnapshot>, ApiError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT portfolio_id, taken_at, cash_balance, market_value, total_value FROM portfolio_snapshots
+             WHERE portfolio_id = ?1 ORDER BY taken_at",
+        ).map_err(storage_error)?;
+        stmt.query_map(params![portfolio_id], |row| {
+            Ok(PortfolioSnapshot {
+                portfolio_id: row.get(0)?,
+                taken_at: row.get(1)?,
+                cash_balance: row.get(2)?,
+                market_value: row.get(3)?,
+                total_value: row.get(4)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(storage_error)
+    }
+}
+
+const ORDER_COLUMNS: &str = "id, portfolio_id, symbol, side, order_type, quantity, limit_price, stop_price, fill_source, \
+                             status, created_at, updated_at, filled_at, fill_price, transaction_id, reason";
+
+pub struct PortfolioManager {
+    store: Arc<dyn PortfolioStore>,
+    // Serializes load-modify-save so concurrent requests can't drop each other's updates
+    write_lock: Mutex<()>,
+    notifier: Notifier,
+}
+
+impl PortfolioManager {
+    pub fn new(store: Arc<dyn PortfolioStore>) -> Self {
+        Self { store, write_lock: Mutex::new(()), notifier: Notifier::default() }
+    }
+
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    pub fn from_config(config: &StorageConfig) -> Result<Self, ApiError> {
+        let store: Arc<dyn PortfolioStore> = match config {
+            StorageConfig::Memory => Arc::new(MemoryStore::default()),
+            StorageConfig::Sqlite(path) => Arc::new(SqliteStore::open(path)?),
+        };
+        Ok(Self::new(store))
+    }
+
+    pub fn create_portfolio(&self, request: CreatePortfolioRequest) -> Result<Portfolio, ApiError> {
+        if request.name.trim().is_empty() {
+            return Err(ApiError::InvalidParameters("Portfolio name is required".to_string()));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let portfolio = Portfolio {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            description: request.description,
+            created_at: now.clone(),
+            updated_at: now,
+            cash_balance: 0.0,
+            cost_basis_method: request.cost_basis_method.unwrap_or_default(),
+            realized_pnl: 0.0,
+            positions: Vec::new(),
+            alerts: Vec::new(),
+            target_weights: BTreeMap::new(),
+        };
+
+        self.store.save(&portfolio, None)?;
+        Ok(portfolio)
+    }
+
+    pub fn get_portfolio(&self, id: &str) -> Result<Portfolio, ApiError> {
+        self.store.load(id)?
+            .ok_or_else(|| ApiError::DataNotFound(format!("Portfolio {} not found", id)))
+    }
+
+    pub fn list_portfolios(&self) -> Result<Vec<Portfolio>, ApiError> {
+        self.store.list()
+    }
+
+    pub fn transactions(&self, id: &str) -> Result<Vec<Transaction>, ApiError> {
+        self.get_portfolio(id)?;
+        self.store.transactions(id)
+    }
+
+    // Dispatches a transaction request to the matching operation
+    pub fn apply_transaction(&self, id: &str, request: TransactionRequest) -> Result<Portfolio, ApiError> {
+        match request.transaction_type {
+            TransactionType::Buy | TransactionType::Sell => {
+                let (Some(symbol), Some(quantity), Some(price)) = (request.symbol, request.quantity, request.price) else {
+                    return Err(ApiError::InvalidParameters("Trades need symbol, quantity and price".to_string()));
+                };
+                if request.transaction_type == TransactionType::Buy {
+                    self.add_position(id, AddPositionRequest { symbol, quantity, price })
+                } else {
+                    self.sell_position(id, &symbol, quantity, price, request.cost_basis_method, request.lots.as_deref())
+                }
+            }
+            TransactionType::Deposit | TransactionType::Withdrawal => {
+                let Some(amount) = request.amount else {
+                    return Err(ApiError::InvalidParameters("Cash transactions need an amount".to_string()));
+                };
+                if request.transaction_type == TransactionType::Deposit {
+                    self.deposit_cash(id, amount)
+                } else {
+                    self.withdraw_cash(id, amount)
+                }
+            }
+            TransactionType::Dividend => {
+                let (Some(symbol), Some(amount)) = (request.symbol, request.amount) else {
+                    return Err(ApiError::InvalidParameters("Dividends need symbol and amount".to_string()));
+                };
+                self.credit_dividend(id, &symbol, amount)
+            }
+        }
+    }
+
+    // A dividend received in cash, entered by hand
+    pub fn credit_dividend(&self, id: &str, symbol: &str, amount: f64) -> Result<Portfolio, ApiError> {
+        if amount <= 0.0 {
+            return Err(ApiError::InvalidParameters("Dividend amount must be positive".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let symbol = symbol.to_uppercase();
+        let quantity = portfolio.positions.iter().find(|p| p.symbol == symbol).map_or(0.0, |p| p.quantity);
+        let per_share = if quantity > 0.0 { amount / quantity } else { 0.0 };
+        portfolio.cash_balance += amount;
+        let transaction = self.transaction(&portfolio, TransactionType::Dividend, Some(symbol), quantity, per_share, amount, None);
+        self.commit(portfolio, transaction)
+    }
+
+    // Records dividends from corporate-action data, once per symbol and ex-date. The eligible
+    // quantity is what the open lots held before the ex-date, so shares since sold are not counted.
+    pub fn record_dividends(&self, id: &str, symbol: &str, events: &[DividendAction]) -> Result<Vec<Transaction>, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let symbol = symbol.to_uppercase();
+
+        let recorded_dates: Vec<String> = self.store.transactions(id)?
+            .into_iter()
+            .filter(|t| t.transaction_type == TransactionType::Dividend && t.symbol.as_deref() == Some(symbol.as_str()))
+            .map(|t| t.timestamp.get(..10).unwrap_or_default().to_string())
+            .collect();
+
+        let mut recorded = Vec::new();
+        for event in events {
+            if recorded_dates.contains(&event.date) || event.amount <= 0.0 {
+                continue;
+            }
+            let quantity: f64 = portfolio.positions.iter()
+                .filter(|p| p.symbol == symbol)
+                .flat_map(|p| p.lots.iter())
+                .filter(|lot| lot.acquired_at.get(..10).is_some_and(|acquired| acquired < event.date.as_str()))
+                .map(|lot| lot.quantity)
+                .sum();
+            if quantity <= QUANTITY_EPSILON {
+                continue;
+            }
+
+            let amount = quantity * event.amount;
+            portfolio.cash_balance += amount;
+            let mut transaction = self.transaction(&portfolio, TransactionType::Dividend, Some(symbol.clone()), quantity, event.amount, amount, None);
+            transaction.timestamp = Utc.timestamp_opt(event.timestamp, 0).single()
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or(transaction.timestamp);
+            portfolio = self.commit(portfolio, transaction.clone())?;
+            recorded.push(transaction);
+        }
+
+        Ok(recorded)
+    }
+
+    // Buy into a position, folding the fill into its average cost and adding a lot
+    pub fn add_position(&self, id: &str, request: AddPositionRequest) -> Result<Portfolio, ApiError> {
+        if request.quantity <= 0.0 || request.price <= 0.0 {
+            return Err(ApiError::InvalidParameters("Quantity and price must be positive".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let transaction = self.buy_into(&mut portfolio, &request.symbol, request.quantity, request.price, &Utc::now().to_rfc3339());
+        self.commit(portfolio, transaction)
+    }
+
+    fn buy_into(&self, portfolio: &mut Portfolio, symbol: &str, quantity: f64, price: f64, at: &str) -> Transaction {
+        let symbol = symbol.to_uppercase();
+        let lot = Lot {
+            id: Uuid::new_v4().to_string(),
+            quantity,
+            price,
+            acquired_at: at.to_string(),
+        };
+
+        match portfolio.positions.iter_mut().find(|p| p.symbol == symbol) {
+            Some(position) => {
+                let total_cost = position.average_cost * position.quantity + price * quantity;
+                position.quantity += quantity;
+                position.average_cost = total_cost / position.quantity;
+                position.lots.push(lot);
+            }
+            None => portfolio.positions.push(Position {
+                symbol: symbol.clone(),
+                quantity,
+                average_cost: price,
+                opened_at: at.to_string(),
+                realized_pnl: 0.0,
+                lots: vec![lot],
+            }),
+        }
+
+        let amount = quantity * price;
+        portfolio.cash_balance -= amount;
+        let mut transaction = self.transaction(portfolio, TransactionType::Buy, Some(symbol), quantity, price, amount, None);
+        transaction.timestamp = at.to_string();
+        transaction
+    }
+
+    // Sell part or all of a position. `lots` names the lots to close; otherwise they're taken per
+    // `method`, defaulting to the portfolio's cost basis method (average cost closes the oldest lots
+    // but measures realized P&L against the average). A fully sold position is closed
+    pub fn sell_position(
+        &self,
+        id: &str,
+        symbol: &str,
+        quantity: f64,
+        price: f64,
+        method: Option<CostBasisMethod>,
+        lots: Option<&[LotQuantity]>,
+    ) -> Result<Portfolio, ApiError> {
+        if quantity <= 0.0 || price <= 0.0 {
+            return Err(ApiError::InvalidParameters("Quantity and price must be positive".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let transaction = self.sell_from(&mut portfolio, symbol, quantity, price, method, lots, &Utc::now().to_rfc3339())?;
+        self.commit(portfolio, transaction)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sell_from(
+        &self,
+        portfolio: &mut Portfolio,
+        symbol: &str,
+        quantity: f64,
+        price: f64,
+        method: Option<CostBasisMethod>,
+        lots: Option<&[LotQuantity]>,
+        at: &str,
+    ) -> Result<Transaction, ApiError> {
+        let symbol = symbol.to_uppercase();
+        let index = portfolio.positions.iter()
+            .position(|p| p.symbol == symbol)
+            .ok_or_else(|| ApiError::DataNotFound(format!("No {} position in portfolio {}", symbol, portfolio.id)))?;
+
+        let position = &mut portfolio.positions[index];
+        if quantity > position.quantity + QUANTITY_EPSILON {
+            return Err(ApiError::InvalidParameters(format!(
+                "Cannot sell {} {}; only {} held", quantity, symbol, position.quantity
+            )));
+        }
+
+        let method = method.unwrap_or(portfolio.cost_basis_method);
+        let closed = match lots {
+            Some(selection) => take_selected_lots(&mut position.lots, selection, quantity)?,
+            None => take_lots(&mut position.lots, quantity, method == CostBasisMethod::Lifo),
+        };
+        let by_average = lots.is_none() && method == CostBasisMethod::AverageCost;
+
+        let realized_lots: Vec<RealizedLot> = closed
+            .into_iter()
+            .map(|lot| {
+                let cost = if by_average { position.average_cost } else { lot.price };
+                RealizedLot::new(lot.id, lot.quantity, lot.acquired_at, lot.quantity * price, lot.quantity * cost, at)
+            })
+            .collect();
+        let realized = if by_average {
+            (price - position.average_cost) * quantity
+        } else {
+            realized_lots.iter().map(|l| l.gain).sum()
+        };
+
+        position.quantity -= quantity;
+        position.realized_pnl += realized;
+        if position.quantity <= QUANTITY_EPSILON {
+            portfolio.positions.remove(index);
+        } else if !by_average {
+            let remaining_cost: f64 = position.lots.iter().map(|l| l.quantity * l.price).sum();
+            position.average_cost = remaining_cost / position.quantity;
+        }
+
+        let amount = quantity * price;
+        portfolio.cash_balance += amount;
+        portfolio.realized_pnl += realized;
+        let mut transaction = self.transaction(portfolio, TransactionType::Sell, Some(symbol), quantity, price, amount, Some(realized));
+        transaction.timestamp = at.to_string();
+        transaction.lots = realized_lots;
+        Ok(transaction)
+    }
+
+    // Realized gains from sells, split by holding period, for one calendar year (UTC) or all of them
+    pub fn realized_gains(&self, id: &str, year: Option<i32>) -> Result<RealizedGainsReport, ApiError> {
+        let sells: Vec<Transaction> = self.transactions(id)?
+            .into_iter()
+            .filter(|t| t.transaction_type == TransactionType::Sell)
+            .filter(|t| {
+                year.is_none_or(|year| DateTime::parse_from_rfc3339(&t.timestamp).is_ok_and(|dt| dt.with_timezone(&Utc).year() == year))
+            })
+            .collect();
+
+        let mut report = RealizedGainsReport {
+            portfolio_id: id.to_string(),
+            year,
+            short_term: GainSummary::default(),
+            long_term: GainSummary::default(),
+            unclassified: GainSummary::default(),
+            total_gain: 0.0,
+            lots: Vec::new(),
+        };
+        for sell in sells {
+            if sell.lots.is_empty() {
+                let gain = sell.realized_pnl.unwrap_or(0.0);
+                report.unclassified.add(sell.amount, sell.amount - gain, gain);
+                report.total_gain += gain;
+                continue;
+            }
+            for lot in sell.lots {
+                let summary = match lot.term {
+                    HoldingTerm::ShortTerm => &mut report.short_term,
+                    HoldingTerm::LongTerm => &mut report.long_term,
+                };
+                summary.add(lot.proceeds, lot.cost_basis, lot.gain);
+                report.total_gain += lot.gain;
+                report.lots.push(RealizedGain {
+                    transaction_id: sell.id.clone(),
+                    symbol: sell.symbol.clone().unwrap_or_default(),
+                    sold_at: sell.timestamp.clone(),
+                    lot,
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    // Drops a position outright (e.g. a mistaken entry) without touching cash or realized P&L
+    pub fn remove_position(&self, id: &str, symbol: &str) -> Result<Portfolio, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let symbol = symbol.to_uppercase();
+        let before = portfolio.positions.len();
+        portfolio.positions.retain(|p| p.symbol != symbol);
+        if portfolio.positions.len() == before {
+            return Err(ApiError::DataNotFound(format!("No {} position in portfolio {}", symbol, id)));
+        }
+
+        portfolio.updated_at = Utc::now().to_rfc3339();
+        self.store.save(&portfolio, None)?;
+        Ok(portfolio)
+    }
+
+    pub fn deposit_cash(&self, id: &str, amount: f64) -> Result<Portfolio, ApiError> {
+        if amount <= 0.0 {
+            return Err(ApiError::InvalidParameters("Deposit amount must be positive".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        portfolio.cash_balance += amount;
+        let transaction = self.transaction(&portfolio, TransactionType::Deposit, None, 0.0, 0.0, amount, None);
+        self.commit(portfolio, transaction)
+    }
+
+    pub fn withdraw_cash(&self, id: &str, amount: f64) -> Result<Portfolio, ApiError> {
+        if amount <= 0.0 {
+            return Err(ApiError::InvalidParameters("Withdrawal amount must be positive".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        if amount > portfolio.cash_balance {
+            return Err(ApiError::InvalidParameters(format!(
+                "Cannot withdraw {:.2}; cash balance is {:.2}", amount, portfolio.cash_balance
+            )));
+        }
+        portfolio.cash_balance -= amount;
+        let transaction = self.transaction(&portfolio, TransactionType::Withdrawal, None, 0.0, 0.0, amount, None);
+        self.commit(portfolio, transaction)
+    }
+
+    pub fn add_alert(&self, id: &str, request: CreateAlertRequest) -> Result<PortfolioAlert, ApiError> {
+        if request.symbol.trim().is_empty() {
+            return Err(ApiError::InvalidParameters("Alert symbol is required".to_string()));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let now = Utc::now().to_rfc3339();
+        let alert = PortfolioAlert {
+            id: Uuid::new_v4().to_string(),
+            symbol: request.symbol.trim().to_uppercase(),
+            condition: request.condition,
+            value: request.value,
+            created_at: now.clone(),
+            triggered_at: None,
+            triggered_value: None,
+        };
+
+        portfolio.alerts.push(alert.clone());
+        portfolio.updated_at = now;
+        self.store.save(&portfolio, None)?;
+        Ok(alert)
+    }
+
+    pub fn remove_alert(&self, id: &str, alert_id: &str) -> Result<Portfolio, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        let before = portfolio.alerts.len();
+        portfolio.alerts.retain(|a| a.id != alert_id);
+        if portfolio.alerts.len() == before {
+            return Err(ApiError::DataNotFound(format!("Alert {} not found in portfolio {}", alert_id, id)));
+        }
+
+        portfolio.updated_at = Utc::now().to_rfc3339();
+        self.store.save(&portfolio, None)?;
+        Ok(portfolio)
+    }
+
+    // Replaces the portfolio's target allocation. Weights are fractions of total value (positions
+    // plus cash); whatever they leave unallocated is the cash target.
+    pub fn set_target_weights(&self, id: &str, request: TargetWeightsRequest) -> Result<Portfolio, ApiError> {
+        let mut targets = BTreeMap::new();
+        for (symbol, weight) in request.target_weights {
+            let symbol = symbol.trim().to_uppercase();
+            if symbol.is_empty() {
+                return Err(ApiError::InvalidParameters("Target symbols can't be empty".to_string()));
+            }
+            if !(0.0..=1.0).contains(&weight) {
+                return Err(ApiError::InvalidParameters(format!("Target weight for {} must be between 0 and 1", symbol)));
+            }
+            *targets.entry(symbol).or_insert(0.0) += weight;
+        }
+        let total: f64 = targets.values().sum();
+        if total > 1.0 + QUANTITY_EPSILON {
+            return Err(ApiError::InvalidParameters(format!("Target weights add up to {:.4}, more than 1", total)));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut portfolio = self.get_portfolio(id)?;
+        portfolio.target_weights = targets;
+        portfolio.updated_at = Utc::now().to_rfc3339();
+        self.store.save(&portfolio, None)?;
+        Ok(portfolio)
+    }
+
+    // Symbols with at least one untriggered alert, i.e. what the checker needs quotes for
+    pub fn alert_symbols(&self) -> Result<Vec<String>, ApiError> {
+        let mut symbols: Vec<String> = self.store.list()?
+            .into_iter()
+            .flat_map(|p| p.alerts)
+            .filter(|a| a.triggered_at.is_none())
+            .map(|a| a.symbol)
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        Ok(symbols)
+    }
+
+    // Evaluates untriggered alerts against fresh quotes, stamps the ones that fire, and returns them
+    pub fn check_alerts(&self, quotes: &BTreeMap<String, Quote>) -> Result<Vec<TriggeredAlert>, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        let mut triggered = Vec::new();
+
+        for mut portfolio in self.store.list()? {
+            let mut changed = false;
+            for alert in portfolio.alerts.iter_mut().filter(|a| a.triggered_at.is_none()) {
+                let Some(quote) = quotes.get(&alert.symbol) else { continue };
+                let observed = alert.condition.observed(quote);
+                if alert.condition.is_met(observed, alert.value) {
+                    alert.triggered_at = Some(now.clone());
+                    alert.triggered_value = Some(observed);
+                    changed = true;
+                    triggered.push(TriggeredAlert {
+                        portfolio_id: portfolio.id.clone(),
+                        portfolio_name: portfolio.name.clone(),
+                        alert: alert.clone(),
+                    });
+                }
+            }
+            if changed {
+                self.store.save(&portfolio, None)?;
+            }
+        }
+
+        // Only after the trigger is persisted, so a failed save can't notify twice on the next pass
+        self.notifier.dispatch(triggered.clone());
+        Ok(triggered)
+    }
+
+    // Values every portfolio at `prices` (symbol -> last price) and records the snapshots
+    pub fn snapshot_portfolios(&self, prices: &BTreeMap<String, f64>) -> Result<Vec<PortfolioSnapshot>, ApiError> {
+        let taken_at = Utc::now().to_rfc3339();
+        let mut snapshots = Vec::new();
+        for portfolio in self.store.list()? {
+            let market_value: f64 = portfolio.positions.iter()
+                .map(|p| p.quantity * prices.get(&p.symbol).copied().unwrap_or(p.average_cost))
+                .sum();
+            let snapshot = PortfolioSnapshot {
+                portfolio_id: portfolio.id,
+                taken_at: taken_at.clone(),
+                cash_balance: portfolio.cash_balance,
+                market_value,
+                total_value: portfolio.cash_balance + market_value,
+            };
+            self.store.save_snapshot(&snapshot)?;
+            snapshots.push(snapshot);
+        }
+        Ok(snapshots)
+    }
+
+    pub fn snapshots(&self, id: &str) -> Result<Vec<PortfolioSnapshot>, ApiError> {
+        self.get_portfolio(id)?;
+        self.store.snapshots(id)
+    }
+
+    // Symbols held across every portfolio
+    pub fn held_symbols(&self) -> Result<Vec<String>, ApiError> {
+        let mut symbols: Vec<String> = self.store.list()?
+            .into_iter()
+            .flat_map(|p| p.positions)
+            .map(|p| p.symbol)
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        Ok(symbols)
+    }
+
+    pub fn place_order(&self, request: PlaceOrderRequest) -> Result<Order, ApiError> {
+        self.get_portfolio(&request.portfolio_id)?;
+        let symbol = request.symbol.trim().to_uppercase();
+        if symbol.is_empty() {
+            return Err(ApiError::InvalidParameters("Orders need a symbol".to_string()));
+        }
+        if !request.quantity.is_finite() || request.quantity <= 0.0 {
+            return Err(ApiError::InvalidParameters("Order quantity must be positive".to_string()));
+        }
+        let positive = |price: Option<f64>| price.is_some_and(|p| p > 0.0);
+        let prices_ok = match request.order_type {
+            OrderType::Market => request.limit_price.is_none() && request.stop_price.is_none(),
+            OrderType::Limit => positive(request.limit_price) && request.stop_price.is_none(),
+            OrderType::Stop => positive(request.stop_price) && request.limit_price.is_none(),
+        };
+        if !prices_ok {
+            return Err(ApiError::InvalidParameters(
+                "Limit orders need a positive limit_price, stop orders a positive stop_price, and market orders neither".to_string(),
+            ));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let order = Order {
+            id: Uuid::new_v4().to_string(),
+            portfolio_id: request.portfolio_id,
+            symbol,
+            side: request.side,
+            order_type: request.order_type,
+            quantity: request.quantity,
+            limit_price: request.limit_price,
+            stop_price: request.stop_price,
+            fill_source: request.fill_source.unwrap_or_default(),
+            status: OrderStatus::Open,
+            created_at: now.clone(),
+            updated_at: now,
+            filled_at: None,
+            fill_price: None,
+            transaction_id: None,
+            reason: None,
+        };
+        self.store.save_order(&order)?;
+        Ok(order)
+    }
+
+    pub fn get_order(&self, id: &str) -> Result<Order, ApiError> {
+        self.store.load_order(id)?
+            .ok_or_else(|| ApiError::DataNotFound(format!("Order {} not found", id)))
+    }
+
+    // Oldest first, optionally for one portfolio and/or in one status
+    pub fn list_orders(&self, portfolio_id: Option<&str>, status: Option<OrderStatus>) -> Result<Vec<Order>, ApiError> {
+        Ok(self.store.list_orders()?
+            .into_iter()
+            .filter(|o| portfolio_id.is_none_or(|id| o.portfolio_id == id))
+            .filter(|o| status.is_none_or(|status| o.status == status))
+            .collect())
+    }
+
+    pub fn cancel_order(&self, id: &str) -> Result<Order, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut order = self.get_order(id)?;
+        if order.status != OrderStatus::Open {
+            return Err(ApiError::InvalidParameters(format!("Order {} is already {}", id, order.status.as_str())));
+        }
+        order.status = OrderStatus::Cancelled;
+        order.updated_at = Utc::now().to_rfc3339();
+        self.store.save_order(&order)?;
+        Ok(order)
+    }
+
+    // Executes an open order at `price` as of `at`, recording the trade on its portfolio. A buy the
+    // cash balance can't cover, or a sell of more than is held, rejects the order instead. Orders no
+    // longer open are returned as they are.
+    pub fn fill_order(&self, id: &str, price: f64, at: &str) -> Result<Order, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut order = self.get_order(id)?;
+        if order.status != OrderStatus::Open {
+            return Ok(order);
+        }
+
+        let mut portfolio = self.get_portfolio(&order.portfolio_id)?;
+        let cost = order.quantity * price;
+        let trade = match order.side {
+            OrderSide::Buy if cost > portfolio.cash_balance + QUANTITY_EPSILON => Err(ApiError::InvalidParameters(format!(
+                "Buying {} {} at {:.2} needs {:.2}; cash balance is {:.2}", order.quantity, order.symbol, price, cost, portfolio.cash_balance
+            ))),
+            OrderSide::Buy => Ok(self.buy_into(&mut portfolio, &order.symbol, order.quantity, price, at)),
+            OrderSide::Sell => self.sell_from(&mut portfolio, &order.symbol, order.quantity, price, None, None, at),
+        };
+
+        order.updated_at = Utc::now().to_rfc3339();
+        match trade {
+            Ok(transaction) => {
+                order.status = OrderStatus::Filled;
+                order.filled_at = Some(at.to_string());
+                order.fill_price = Some(price);
+                order.transaction_id = Some(transaction.id.clone());
+                self.commit(portfolio, transaction)?;
+            }
+            Err(ApiError::InvalidParameters(reason) | ApiError::DataNotFound(reason)) => {
+                order.status = OrderStatus::Rejected;
+                order.reason = Some(reason);
+            }
+            Err(e) => return Err(e),
+        }
+        self.store.save_order(&order)?;
+        Ok(order)
+    }
+
+    pub fn create_watchlist(&self, request: CreateWatchlistRequest) -> Result<Watchlist, ApiError> {
+        if request.name.trim().is_empty() {
+            return Err(ApiError::InvalidParameters("Watchlist name is required".to_string()));
+        }
+
+        let mut symbols = Vec::new();
+        merge_symbols(&mut symbols, &request.symbols.unwrap_or_default())?;
+        let now = Utc::now().to_rfc3339();
+        let watchlist = Watchlist {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            description: request.description,
+            symbols,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        self.store.save_watchlist(&watchlist)?;
+        Ok(watchlist)
+    }
+
+    pub fn get_watchlist(&self, id: &str) -> Result<Watchlist, ApiError> {
+        self.store.load_watchlist(id)?
+            .ok_or_else(|| ApiError::DataNotFound(format!("Watchlist {} not found", id)))
+    }
+
+    pub fn list_watchlists(&self) -> Result<Vec<Watchlist>, ApiError> {
+        self.store.list_watchlists()
+    }
+
+    pub fn update_watchlist(&self, id: &str, request: UpdateWatchlistRequest) -> Result<Watchlist, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut watchlist = self.get_watchlist(id)?;
+        if let Some(name) = request.name {
+            if name.trim().is_empty() {
+                return Err(ApiError::InvalidParameters("Watchlist name is required".to_string()));
+            }
+            watchlist.name = name;
+        }
+        if request.description.is_some() {
+            watchlist.description = request.description;
+        }
+        if let Some(symbols) = request.symbols {
+            watchlist.symbols.clear();
+            merge_symbols(&mut watchlist.symbols, &symbols)?;
+        }
+
+        watchlist.updated_at = Utc::now().to_rfc3339();
+        self.store.save_watchlist(&watchlist)?;
+        Ok(watchlist)
+    }
+
+    pub fn delete_watchlist(&self, id: &str) -> Result<Watchlist, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let watchlist = self.get_watchlist(id)?;
+        self.store.delete_watchlist(id)?;
+        Ok(watchlist)
+    }
+
+    // Appends symbols not already on the list
+    pub fn add_watchlist_symbols(&self, id: &str, symbols: &[String]) -> Result<Watchlist, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut watchlist = self.get_watchlist(id)?;
+        merge_symbols(&mut watchlist.symbols, symbols)?;
+        watchlist.updated_at = Utc::now().to_rfc3339();
+        self.store.save_watchlist(&watchlist)?;
+        Ok(watchlist)
+    }
+
+    pub fn remove_watchlist_symbol(&self, id: &str, symbol: &str) -> Result<Watchlist, ApiError> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut watchlist = self.get_watchlist(id)?;
+        let symbol = symbol.to_uppercase();
+        let before = watchlist.symbols.len();
+        watchlist.symbols.retain(|s| *s != symbol);
+        if watchlist.symbols.len() == before {
+            return Err(ApiError::DataNotFound(format!("{} is not on watchlist {}", symbol, id)));
+        }
+
+        watchlist.updated_at = Utc::now().to_rfc3339();
+        self.store.save_watchlist(&watchlist)?;
+        Ok(watchlist)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn transaction(
+        &self,
+        portfolio: &Portfolio,
+        transaction_type: TransactionType,
+        symbol: Option<String>,
+        quantity: f64,
+        price: f64,
+        amount: f64,
+        realized_pnl: Option<f64>,
+    ) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4().to_string(),
+            portfolio_id: portfolio.id.clone(),
+            transaction_type,
+            symbol,
+            quantity,
+            price,
+            amount,
+            realized_pnl,
+            timestamp: Utc::now().to_rfc3339(),
+            lots: Vec::new(),
+        }
+    }
+
+    fn commit(&self, mut portfolio: Portfolio, transaction: Transaction) -> Result<Portfolio, ApiError> {
+        portfolio.updated_at = transaction.timestamp.clone();
+        self.store.save(&portfolio, Some(&transaction))?;
+        Ok(portfolio)
+    }
+}
+
+const WATCHLIST_MAX_SYMBOLS: usize = 200;
+
+// Adds `symbols` (trimmed, upper-cased) to `list` in order, skipping blanks and ones already there
+fn merge_symbols(list: &mut Vec<String>, symbols: &[String]) -> Result<(), ApiError> {
+    for symbol in symbols {
+        let symbol = symbol.trim().to_uppercase();
+        if !symbol.is_empty() && !list.contains(&symbol) {
+            list.push(symbol);
+        }
+    }
+    if list.len() > WATCHLIST_MAX_SYMBOLS {
+        return Err(ApiError::InvalidParameters(format!("A watchlist holds at most {} symbols", WATCHLIST_MAX_SYMBOLS)));
+    }
+    Ok(())
+}
+
+// Fractional shares are allowed, so compare quantities with some slack
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+// Removes `quantity` from the oldest (or newest) lots first and returns what was taken
+fn take_lots(lots: &mut Vec<Lot>, mut quantity: f64, newest_first: bool) -> Vec<Lot> {
+    let mut taken = Vec::new();
+    while quantity > QUANTITY_EPSILON {
+        let index = if newest_first { lots.len().checked_sub(1) } else { (!lots.is_empty()).then_some(0) };
+        let Some(index) = index else { break };
+        let lot = &mut lots[index];
+        let amount = lot.quantity.min(quantity);
+        taken.push(Lot { quantity: amount, ..lot.clone() });
+        lot.quantity -= amount;
+        quantity -= amount;
+        if lot.quantity <= QUANTITY_EPSILON {
+            lots.remove(index);
+        }
+    }
+    taken
+}
+
+// Removes the named lots (all of each unless a quantity is given), which must add up to `quantity`
+fn take_selected_lots(lots: &mut Vec<Lot>, selection: &[LotQuantity], quantity: f64) -> Result<Vec<Lot>, ApiError> {
+    let mut taken = Vec::new();
+    for pick in selection {
+        let lot = lots.iter_mut()
+            .find(|l| l.id == pick.lot_id)
+            .ok_or_else(|| ApiError::InvalidParameters(format!("No open lot {}", pick.lot_id)))?;
+        let amount = pick.quantity.unwrap_or(lot.quantity);
+        if amount <= 0.0 || amount > lot.quantity + QUANTITY_EPSILON {
+            return Err(ApiError::InvalidParameters(format!(
+                "Cannot sell {} from lot {}; it holds {}", amount, lot.id, lot.quantity
+            )));
+        }
+        let amount = amount.min(lot.quantity);
+        taken.push(Lot { quantity: amount, ..lot.clone() });
+        lot.quantity -= amount;
+    }
+    let total: f64 = taken.iter().map(|l| l.quantity).sum();
+    if (total - quantity).abs() > QUANTITY_EPSILON {
+        return Err(ApiError::InvalidParameters(format!(
+            "Selected lots add up to {}, not the {} being sold", total, quantity
+        )));
+    }
+    lots.retain(|l| l.quantity > QUANTITY_EPSILON);
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AT: &str = "2024-05-01T14:30:00+00:00";
+
+    fn manager_with_cash(cash: f64) -> (PortfolioManager, String) {
+        let manager = PortfolioManager::from_config(&StorageConfig::Memory).unwrap();
+        let portfolio = manager.create_portfolio(CreatePortfolioRequest {
+            name: "Paper".to_string(),
+            description: None,
+            cost_basis_method: None,
+        }).unwrap();
+        manager.deposit_cash(&portfolio.id, cash).unwrap();
+        (manager, portfolio.id)
+    }
+
+    fn place(manager: &PortfolioManager, portfolio_id: &str, side: OrderSide, order_type: OrderType, level: Option<f64>) -> Order {
+        manager.place_order(PlaceOrderRequest {
+            portfolio_id: portfolio_id.to_string(),
+            symbol: "AAPL".to_string(),
+            side,
+            order_type,
+            quantity: 10.0,
+            limit_price: level.filter(|_| order_type == OrderType::Limit),
+            stop_price: level.filter(|_| order_type == OrderType::Stop),
+            fill_source: None,
+        }).unwrap()
+    }
+
+    fn held(manager: &PortfolioManager, portfolio_id: &str) -> f64 {
+        let portfolio = manager.get_portfolio(portfolio_id).unwrap();
+        portfolio.positions.iter().find(|p| p.symbol == "AAPL").map_or(0.0, |p| p.quantity)
+    }
+
+    #[test]
+    fn filled_orders_trade_on_their_portfolio() {
+        for order_type in [OrderType::Market, OrderType::Limit, OrderType::Stop] {
+            let (manager, id) = manager_with_cash(2000.0);
+
+            let buy = place(&manager, &id, OrderSide::Buy, order_type, Some(150.0));
+            let filled = manager.fill_order(&buy.id, 150.0, AT).unwrap();
+            assert_eq!(filled.status, OrderStatus::Filled, "{:?} buy", order_type);
+            assert_eq!((filled.fill_price, filled.filled_at.as_deref()), (Some(150.0), Some(AT)));
+            assert!(filled.transaction_id.is_some());
+            assert_eq!(held(&manager, &id), 10.0);
+            assert_eq!(manager.get_portfolio(&id).unwrap().cash_balance, 500.0);
+
+            let sell = place(&manager, &id, OrderSide::Sell, order_type, Some(160.0));
+            let filled = manager.fill_order(&sell.id, 160.0, AT).unwrap();
+            assert_eq!(filled.status, OrderStatus::Filled, "{:?} sell", order_type);
+            assert_eq!(held(&manager, &id), 0.0);
+            assert_eq!(manager.get_portfolio(&id).unwrap().cash_balance, 2100.0);
+        }
+    }
+
+    #[test]
+    fn buy_without_the_cash_is_rejected() {
+        let (manager, id) = manager_with_cash(1000.0);
+        let buy = place(&manager, &id, OrderSide::Buy, OrderType::Market, None);
+        let rejected = manager.fill_order(&buy.id, 150.0, AT).unwrap();
+        assert_eq!(rejected.status, OrderStatus::Rejected);
+        assert!(rejected.reason.unwrap().contains("cash balance is 1000.00"));
+        assert_eq!(rejected.fill_price, None);
+        assert_eq!(held(&manager, &id), 0.0);
+        assert_eq!(manager.get_portfolio(&id).unwrap().cash_balance, 1000.0);
+    }
+
+    #[test]
+    fn settled_orders_are_left_alone() {
+        let (manager, id) = manager_with_cash(2000.0);
+        let buy = place(&manager, &id, OrderSide::Buy, OrderType::Market, None);
+        manager.fill_order(&buy.id, 150.0, AT).unwrap();
+        let again = manager.fill_order(&buy.id, 120.0, AT).unwrap();
+        assert_eq!(again.fill_price, Some(150.0));
+        assert_eq!(held(&manager, &id), 10.0);
+    }
+}
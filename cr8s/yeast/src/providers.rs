@@ -0,0 +1,569 @@
+// providers.rs - Market data backends other than Yahoo
+//
+// A MarketDataProvider serves candles, quotes and option chains. Candles and chains come back in
+// the Yahoo chart / optionsprofitcalculator shapes the rest of the crate already parses, so
+// switching providers changes where the data comes from and nothing downstream. Yahoo stays the
+// built-in default; news, calendars, screeners and quote summaries are Yahoo-only either way.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+
+use crate::api::{ApiError, Quote};
+use crate::candle_store::range_start;
+use crate::retry::SendWithRetry;
+use crate::og::{
+    chart_from_candles, ChartFetcher, ChartQueryOptions, ChartResponse, ExpiryOptionData, FetchError, Meta,
+    OptionProfitCalculatorResponse, OptionQuote, OptionsFetcher, TradingPeriod, TradingPeriodWrapper,
+};
+use crate::types::Candle;
+
+pub trait MarketDataProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn candles<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>>;
+
+    fn quote<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<Quote, FetchError>>;
+
+    fn options<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum ProviderConfig {
+    #[default]
+    Yahoo,
+    AlphaVantage { api_key: String },
+    Polygon { api_key: String },
+}
+
+// Provider API keys from the config file; the environment fills in whatever is missing
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderKeys {
+    pub alphavantage: Option<String>,
+    pub polygon: Option<String>,
+}
+
+impl ProviderConfig {
+    // `name` is yahoo, alphavantage or polygon; keys come from ALPHAVANTAGE_API_KEY / POLYGON_API_KEY,
+    // falling back to `keys` (the config file's) when the variable is unset
+    pub fn from_name(name: &str, keys: &ProviderKeys) -> Result<Self, ApiError> {
+        let key = |configured: &Option<String>, var: &str| {
+            std::env::var(var)
+                .ok()
+                .filter(|k| !k.is_empty())
+                .or_else(|| configured.clone().filter(|k| !k.is_empty()))
+                .ok_or_else(|| ApiError::InvalidParameters(format!("Provider '{}' requires {} to be set", name, var)))
+        };
+        match name.to_lowercase().as_str() {
+            "yahoo" => Ok(ProviderConfig::Yahoo),
+            "alphavantage" | "alpha_vantage" => Ok(ProviderConfig::AlphaVantage { api_key: key(&keys.alphavantage, "ALPHAVANTAGE_API_KEY")? }),
+            "polygon" => Ok(ProviderConfig::Polygon { api_key: key(&keys.polygon, "POLYGON_API_KEY")? }),
+            other => Err(ApiError::InvalidParameters(format!(
+                "Unknown data provider '{}'. Valid providers: yahoo, alphavantage, polygon", other
+            ))),
+        }
+    }
+
+    // None for Yahoo, which is served by the default fetchers
+    pub fn build(&self) -> Option<Arc<dyn MarketDataProvider>> {
+        match self {
+            ProviderConfig::Yahoo => None,
+            ProviderConfig::AlphaVantage { api_key } => Some(Arc::new(AlphaVantageProvider::new(api_key.clone()))),
+            ProviderConfig::Polygon { api_key } => Some(Arc::new(PolygonProvider::new(api_key.clone()))),
+        }
+    }
+}
+
+// Lets a provider stand in wherever StockDataApi expects the chart and options fetchers
+pub struct ProviderFetcher(pub Arc<dyn MarketDataProvider>);
+
+impl ChartFetcher for ProviderFetcher {
+    fn fetch_sync(&self, _ticker: &str, _opts: &ChartQueryOptions) -> Result<ChartResponse, FetchError> {
+        Err(format!("{} provider does not support sync fetch", self.0.name()).into())
+    }
+
+    fn fetch_async<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
+        self.0.candles(ticker, opts)
+    }
+}
+
+impl OptionsFetcher for ProviderFetcher {
+    fn fetch_sync(&self, _ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError> {
+        Err(format!("{} provider does not support sync fetch", self.0.name()).into())
+    }
+
+    fn fetch_async<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
+        self.0.options(ticker)
+    }
+}
+
+// Both providers report US equities in New York time. DST runs from the second Sunday of March
+// to the first Sunday of November.
+fn us_eastern_offset(date: NaiveDate) -> i64 {
+    let nth_sunday = |month: u32, n: u8| {
+        NaiveDate::from_weekday_of_month_opt(date.year(), month, Weekday::Sun, n).unwrap_or(date)
+    };
+    if date >= nth_sunday(3, 2) && date < nth_sunday(11, 1) { -4 * 3600 } else { -5 * 3600 }
+}
+
+fn eastern_timestamp(local: NaiveDateTime) -> i64 {
+    local.and_utc().timestamp() - us_eastern_offset(local.date())
+}
+
+// [start, end) in unix seconds for the requested range or explicit period
+fn query_window(opts: &ChartQueryOptions) -> (i64, i64) {
+    let now = Utc::now().timestamp();
+    opts.period.unwrap_or_else(|| (range_start(opts.range, now).unwrap_or(0), now + 1))
+}
+
+// Chart meta for bars that did not come from Yahoo: prices from the last two bars, the rest
+// filled with what the provider can't tell us
+pub(crate) fn chart_response(provider: &str, symbol: &str, opts: &ChartQueryOptions, mut candles: Vec<Candle>) -> ChartResponse {
+    let (start, end) = query_window(opts);
+    candles.retain(|c| c.timestamp >= start && c.timestamp < end);
+    candles.sort_by_key(|c| c.timestamp);
+
+    let last = candles.last();
+    let price = last.map_or(0.0, |c| c.close);
+    let previous_close = candles.len().checked_sub(2).and_then(|i| candles.get(i)).map_or(price, |c| c.close);
+    let gmtoffset = last
+        .and_then(|c| Utc.timestamp_opt(c.timestamp, 0).single())
+        .map_or(-5 * 3600, |dt| us_eastern_offset(dt.date_naive()));
+    let period = |start: u64, end: u64| TradingPeriod { timezone: "America/New_York".to_string(), start, end, gmtoffset };
+
+    let meta = Meta {
+        currency: "USD".to_string(),
+        symbol: symbol.to_uppercase(),
+        exchangeName: provider.to_string(),
+        fullExchangeName: provider.to_string(),
+        instrumentType: String::new(),
+        firstTradeDate: 0,
+        regularMarketTime: last.map_or(0, |c| c.timestamp as u64),
+        hasPrePostMarketData: false,
+        gmtoffset,
+        timezone: if gmtoffset == -4 * 3600 { "EDT" } else { "EST" }.to_string(),
+        exchangeTimezoneName: "America/New_York".to_string(),
+        regularMarketPrice: price,
+        fiftyTwoWeekHigh: None,
+        fiftyTwoWeekLow: None,
+        regularMarketDayHigh: last.map_or(price, |c| c.high),
+        regularMarketDayLow: last.map_or(price, |c| c.low),
+        regularMarketVolume: last.and_then(|c| c.volume).unwrap_or(0.0) as u64,
+        longName: String::new(),
+        shortName: String::new(),
+        chartPreviousClose: previous_close,
+        priceHint: 2,
+        currentTradingPeriod: TradingPeriodWrapper { pre: period(0, 0), regular: period(0, 0), post: period(0, 0) },
+        dataGranularity: opts.interval.to_string(),
+        range: opts.range.to_string(),
+        validRanges: Vec::new(),
+    };
+    chart_from_candles(meta, &candles, &HashMap::new())
+}
+
+fn quote_from_candles(symbol: &str, candles: &[Candle]) -> Result<Quote, FetchError> {
+    let last = candles.last().ok_or_else(|| format!("No bars for {}", symbol))?;
+    let previous_close = candles.len().checked_sub(2).and_then(|i| candles.get(i)).map_or(last.open, |c| c.close);
+    let change = last.close - previous_close;
+    let year_ago = last.timestamp - 365 * 86_400;
+    let year = candles.iter().filter(|c| c.timestamp >= year_ago);
+
+    Ok(Quote {
+        symbol: symbol.to_uppercase(),
+        price: last.close,
+        change,
+        change_percent: if previous_close > 0.0 { change / previous_close * 100.0 } else { 0.0 },
+        volume: last.volume.unwrap_or(0.0) as u64,
+        bid: None,
+        ask: None,
+        bid_size: None,
+        ask_size: None,
+        high_52w: year.clone().map(|c| c.high).fold(last.close, f64::max),
+        low_52w: year.map(|c| c.low).fold(last.close, f64::min),
+        market_cap: None,
+        pe_ratio: None,
+        dividend_yield: None,
+        pre_market_price: None,
+        pre_market_change: None,
+        post_market_price: None,
+        post_market_change: None,
+        last_updated: Utc.timestamp_opt(last.timestamp, 0).single().unwrap_or_else(Utc::now).to_rfc3339(),
+    })
+}
+
+// Alpha Vantage (alphavantage.co). Numbers arrive as strings and errors as a 200 with a
+// "Note", "Information" or "Error Message" key, e.g. when the free tier's quota is spent.
+pub struct AlphaVantageProvider {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+const ALPHA_VANTAGE_URL: &str = "https://www.alphavantage.co/query";
+
+impl AlphaVantageProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { client: reqwest::Client::new(), api_key }
+    }
+
+    async fn query(&self, params: &[(&str, &str)]) -> Result<serde_json::Value, FetchError> {
+        let json: serde_json::Value = self.client.get(ALPHA_VANTAGE_URL)
+            .query(params)
+            .query(&[("apikey", self.api_key.as_str())])
+            .send_with_retry()
+            .await?
+            .json()
+            .await?;
+        for key in ["Error Message", "Note", "Information"] {
+            if let Some(message) = json.get(key).and_then(|m| m.as_str()) {
+                return Err(format!("Alpha Vantage: {}", message).into());
+            }
+        }
+        Ok(json)
+    }
+
+    // (function, interval param) for a Yahoo-style interval
+    fn series_function(interval: &str) -> Result<(&'static str, Option<&'static str>), FetchError> {
+        Ok(match interval {
+            "1m" => ("TIME_SERIES_INTRADAY", Some("1min")),
+            "5m" => ("TIME_SERIES_INTRADAY", Some("5min")),
+            "15m" => ("TIME_SERIES_INTRADAY", Some("15min")),
+            "30m" => ("TIME_SERIES_INTRADAY", Some("30min")),
+            "60m" | "1h" => ("TIME_SERIES_INTRADAY", Some("60min")),
+            "1d" => ("TIME_SERIES_DAILY", None),
+            "1wk" => ("TIME_SERIES_WEEKLY", None),
+            "1mo" => ("TIME_SERIES_MONTHLY", None),
+            other => return Err(format!("Alpha Vantage has no {} bars", other).into()),
+        })
+    }
+
+    async fn series(&self, ticker: &str, interval: &str) -> Result<Vec<Candle>, FetchError> {
+        let (function, av_interval) = Self::series_function(interval)?;
+        let mut params = vec![("function", function), ("symbol", ticker), ("outputsize", "full")];
+        if let Some(av_interval) = av_interval {
+            params.push(("interval", av_interval));
+        }
+        let json = self.query(&params).await?;
+
+        let series = json.as_object()
+            .and_then(|obj| obj.iter().find(|(key, _)| key.contains("Time Series")))
+            .and_then(|(_, series)| series.as_object())
+            .ok_or_else(|| format!("Alpha Vantage returned no series for {}", ticker))?;
+
+        let field = |bar: &serde_json::Value, name: &str| {
+            bar.as_object()
+                .and_then(|fields| fields.iter().find(|(key, _)| key.ends_with(name)))
+                .and_then(|(_, v)| v.as_str())
+                .and_then(|v| v.parse::<f64>().ok())
+        };
+
+        let mut candles = Vec::with_capacity(series.len());
+        for (stamp, bar) in series {
+            let local = NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDate::parse_from_str(stamp, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default()));
+            let (Ok(local), Some(open), Some(high), Some(low), Some(close)) =
+                (local, field(bar, "open"), field(bar, "high"), field(bar, "low"), field(bar, "close"))
+            else {
+                continue;
+            };
+            candles.push(Candle { timestamp: eastern_timestamp(local), open, high, low, close, volume: field(bar, "volume") });
+        }
+        Ok(candles)
+    }
+}
+
+impl MarketDataProvider for AlphaVantageProvider {
+    fn name(&self) -> &'static str {
+        "alphavantage"
+    }
+
+    fn candles<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
+        Box::pin(async move {
+            let candles = self.series(ticker, opts.interval).await?;
+            Ok(chart_response(self.name(), ticker, opts, candles))
+        })
+    }
+
+    // GLOBAL_QUOTE for the price, OVERVIEW for the 52-week range and fundamentals. OVERVIEW only
+    // covers stocks, so ETFs and the like get the day's range instead.
+    fn quote<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<Quote, FetchError>> {
+        Box::pin(async move {
+            let json = self.query(&[("function", "GLOBAL_QUOTE"), ("symbol", ticker)]).await?;
+            let global = json.get("Global Quote")
+                .filter(|q| q.as_object().is_some_and(|q| !q.is_empty()))
+                .ok_or_else(|| format!("Alpha Vantage has no quote for {}", ticker))?;
+            let num = |value: Option<&serde_json::Value>| {
+                value.and_then(|v| v.as_str()).and_then(|v| v.trim_end_matches('%').parse::<f64>().ok())
+            };
+
+            let price = num(global.get("05. price")).ok_or_else(|| format!("Alpha Vantage quote for {} has no price", ticker))?;
+            let mut quote = Quote {
+                symbol: ticker.to_uppercase(),
+                price,
+                change: num(global.get("09. change")).unwrap_or(0.0),
+                change_percent: num(global.get("10. change percent")).unwrap_or(0.0),
+                volume: num(global.get("06. volume")).unwrap_or(0.0) as u64,
+                bid: None,
+                ask: None,
+                bid_size: None,
+                ask_size: None,
+                high_52w: num(global.get("03. high")).unwrap_or(price).max(price),
+                low_52w: num(global.get("04. low")).unwrap_or(price).min(price),
+                market_cap: None,
+                pe_ratio: None,
+                dividend_yield: None,
+                pre_market_price: None,
+                pre_market_change: None,
+                post_market_price: None,
+                post_market_change: None,
+                last_updated: Utc::now().to_rfc3339(),
+            };
+
+            match self.query(&[("function", "OVERVIEW"), ("symbol", ticker)]).await {
+                Ok(overview) => {
+                    if let (Some(high), Some(low)) = (num(overview.get("52WeekHigh")), num(overview.get("52WeekLow"))) {
+                        quote.high_52w = high.max(price);
+                        quote.low_52w = low.min(price);
+                    }
+                    quote.market_cap = num(overview.get("MarketCapitalization"));
+                    quote.pe_ratio = num(overview.get("PERatio"));
+                    quote.dividend_yield = num(overview.get("DividendYield")).map(|y| y * 100.0);
+                }
+                Err(e) => tracing::warn!(ticker, error = %e, "Alpha Vantage overview unavailable"),
+            }
+            Ok(quote)
+        })
+    }
+
+    // HISTORICAL_OPTIONS without a date returns the previous session's chain
+    fn options<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
+        Box::pin(async move {
+            #[derive(Deserialize)]
+            struct Contract {
+                expiration: String,
+                strike: String,
+                #[serde(rename = "type")]
+                contract_type: String,
+                last: String,
+                bid: String,
+                ask: String,
+                volume: String,
+                open_interest: String,
+            }
+
+            let json = self.query(&[("function", "HISTORICAL_OPTIONS"), ("symbol", ticker)]).await?;
+            let contracts: Vec<Contract> = serde_json::from_value(json.get("data").cloned().unwrap_or_default())?;
+
+            let mut chain = OptionChainBuilder::default();
+            for contract in contracts {
+                let num = |v: &str| v.parse::<f64>().unwrap_or(0.0);
+                chain.insert(&contract.expiration, &contract.contract_type, num(&contract.strike), OptionQuote {
+                    oi: num(&contract.open_interest) as u64,
+                    l: num(&contract.last),
+                    b: num(&contract.bid),
+                    a: num(&contract.ask),
+                    v: num(&contract.volume) as u64,
+                });
+            }
+            chain.finish(ticker)
+        })
+    }
+}
+
+// Polygon.io aggregates and snapshots. Quotes are built from daily aggregates so they work on
+// the free plan; option chains need a plan that includes options snapshots.
+pub struct PolygonProvider {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+const POLYGON_URL: &str = "https://api.polygon.io";
+
+#[derive(Debug, Deserialize)]
+struct PolygonAggregates {
+    #[serde(default)]
+    results: Vec<PolygonBar>,
+    status: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonBar {
+    t: i64, // milliseconds
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    v: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonOptionsPage {
+    #[serde(default)]
+    results: Vec<PolygonOptionSnapshot>,
+    next_url: Option<String>,
+    status: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonOptionSnapshot {
+    details: PolygonOptionDetails,
+    open_interest: Option<f64>,
+    day: Option<PolygonOptionDay>,
+    last_quote: Option<PolygonOptionQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonOptionDetails {
+    contract_type: String,
+    expiration_date: String,
+    strike_price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonOptionDay {
+    close: Option<f64>,
+    volume: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonOptionQuote {
+    bid: Option<f64>,
+    ask: Option<f64>,
+}
+
+// Pages fetched from the options snapshot; 250 contracts each
+const POLYGON_MAX_OPTION_PAGES: usize = 20;
+
+impl PolygonProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { client: reqwest::Client::new(), api_key }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, FetchError> {
+        let response = self.client.get(url)
+            .query(&[("apiKey", self.api_key.as_str())])
+            .send_with_retry()
+            .await?;
+        if !response.status().is_success() {
+            return Err(format!("Polygon returned HTTP {}", response.status()).into());
+        }
+        Ok(response.json().await?)
+    }
+
+    // (multiplier, timespan) for a Yahoo-style interval
+    fn timespan(interval: &str) -> Result<(u32, &'static str), FetchError> {
+        Ok(match interval {
+            "1m" => (1, "minute"),
+            "2m" => (2, "minute"),
+            "5m" => (5, "minute"),
+            "15m" => (15, "minute"),
+            "30m" => (30, "minute"),
+            "60m" | "1h" => (1, "hour"),
+            "90m" => (90, "minute"),
+            "1d" => (1, "day"),
+            "5d" => (5, "day"),
+            "1wk" => (1, "week"),
+            "1mo" => (1, "month"),
+            "3mo" => (3, "month"),
+            other => return Err(format!("Polygon has no {} bars", other).into()),
+        })
+    }
+
+    async fn aggregates(&self, ticker: &str, interval: &str, start: i64, end: i64) -> Result<Vec<Candle>, FetchError> {
+        let (multiplier, timespan) = Self::timespan(interval)?;
+        let url = format!(
+            "{}/v2/aggs/ticker/{}/range/{}/{}/{}/{}?adjusted=true&sort=asc&limit=50000",
+            POLYGON_URL, ticker.to_uppercase(), multiplier, timespan, start.max(0) * 1000, end * 1000
+        );
+        let aggregates: PolygonAggregates = self.get(&url).await?;
+        if aggregates.status.as_deref() == Some("ERROR") {
+            return Err(format!("Polygon: {}", aggregates.error.unwrap_or_default()).into());
+        }
+
+        Ok(aggregates.results.into_iter()
+            .map(|bar| Candle { timestamp: bar.t / 1000, open: bar.o, high: bar.h, low: bar.l, close: bar.c, volume: bar.v })
+            .collect())
+    }
+}
+
+impl MarketDataProvider for PolygonProvider {
+    fn name(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn candles<'a>(&'a self, ticker: &'a str, opts: &'a ChartQueryOptions) -> BoxFuture<'a, Result<ChartResponse, FetchError>> {
+        Box::pin(async move {
+            let (start, end) = query_window(opts);
+            let candles = self.aggregates(ticker, opts.interval, start, end).await?;
+            Ok(chart_response(self.name(), ticker, opts, candles))
+        })
+    }
+
+    fn quote<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<Quote, FetchError>> {
+        Box::pin(async move {
+            let now = Utc::now().timestamp();
+            let candles = self.aggregates(ticker, "1d", now - 366 * 86_400, now).await?;
+            quote_from_candles(ticker, &candles)
+        })
+    }
+
+    fn options<'a>(&'a self, ticker: &'a str) -> BoxFuture<'a, Result<OptionProfitCalculatorResponse, FetchError>> {
+        Box::pin(async move {
+            let mut chain = OptionChainBuilder::default();
+            let mut url = Some(format!("{}/v3/snapshot/options/{}?limit=250", POLYGON_URL, ticker.to_uppercase()));
+
+            for _ in 0..POLYGON_MAX_OPTION_PAGES {
+                let Some(page_url) = url.take() else { break };
+                let page: PolygonOptionsPage = self.get(&page_url).await?;
+                if page.status.as_deref() == Some("ERROR") {
+                    return Err(format!("Polygon: {}", page.error.unwrap_or_default()).into());
+                }
+
+                for contract in page.results {
+                    let day = contract.day.as_ref();
+                    let last_quote = contract.last_quote.as_ref();
+                    chain.insert(&contract.details.expiration_date, &contract.details.contract_type, contract.details.strike_price, OptionQuote {
+                        oi: contract.open_interest.unwrap_or(0.0) as u64,
+                        l: day.and_then(|d| d.close).unwrap_or(0.0),
+                        b: last_quote.and_then(|q| q.bid).unwrap_or(0.0),
+                        a: last_quote.and_then(|q| q.ask).unwrap_or(0.0),
+                        v: day.and_then(|d| d.volume).unwrap_or(0.0) as u64,
+                    });
+                }
+                url = page.next_url;
+            }
+            chain.finish(ticker)
+        })
+    }
+}
+
+// Groups contracts by expiry and side the way optionsprofitcalculator returns them
+#[derive(Default)]
+struct OptionChainBuilder {
+    options: HashMap<String, ExpiryOptionData>,
+}
+
+impl OptionChainBuilder {
+    fn insert(&mut self, expiry: &str, contract_type: &str, strike: f64, quote: OptionQuote) {
+        let expiry = self.options.entry(expiry.to_string())
+            .or_insert_with(|| ExpiryOptionData { c: HashMap::new(), p: HashMap::new() });
+        let side = match contract_type.to_lowercase().as_str() {
+            "call" => &mut expiry.c,
+            "put" => &mut expiry.p,
+            _ => return,
+        };
+        side.insert(format!("{:.2}", strike), quote);
+    }
+
+    fn finish(self, ticker: &str) -> Result<OptionProfitCalculatorResponse, FetchError> {
+        if self.options.is_empty() {
+            return Err(format!("No option contracts for {}", ticker).into());
+        }
+        Ok(OptionProfitCalculatorResponse { options: self.options })
+    }
+}
@@ -0,0 +1,148 @@
+// rate_limit.rs - Token buckets for upstream requests
+//
+// Every outbound request takes a token in retry::Resilience::send, so quotes, history, the
+// screener and the stream/SSE polls share one budget. Each upstream host gets its own bucket, so
+// a slow Polygon budget never holds up Yahoo requests.
+// A bucket is a semaphore whose permits are the tokens: callers take one and keep it, and a
+// background task puts one back every 60s / requests_per_minute, up to BURST_SECONDS' worth.
+// Nothing holds a lock while waiting, so callers only queue behind others for the same host,
+// and the semaphore hands out tokens in arrival order, so one busy stream can't starve the rest.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+use tokio::time::MissedTickBehavior;
+use tracing::debug;
+
+use crate::metrics;
+
+// Per upstream host unless ApiConfig.upstream_host_limits says otherwise
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+// Tokens a quiet bucket saves up, in seconds of its rate
+const BURST_SECONDS: u32 = 10;
+
+pub struct TokenBucket {
+    host: String,
+    capacity: usize,
+    refill_every: Duration,
+    tokens: Arc<Semaphore>,
+    refill: OnceLock<()>, // set once the refill task is running
+}
+
+impl TokenBucket {
+    pub fn new(host: &str, requests_per_minute: u32) -> Self {
+        let requests_per_minute = requests_per_minute.max(1);
+        let capacity = (requests_per_minute * BURST_SECONDS / 60).max(1) as usize;
+        Self {
+            host: host.to_string(),
+            capacity,
+            refill_every: Duration::from_secs(60) / requests_per_minute,
+            tokens: Arc::new(Semaphore::new(capacity)),
+            refill: OnceLock::new(),
+        }
+    }
+
+    // Started on first use rather than in new(), which may run before the runtime exists. The
+    // task only holds a weak handle and stops once the bucket is dropped.
+    fn start_refill(&self) {
+        self.refill.get_or_init(|| {
+            let tokens = Arc::downgrade(&self.tokens);
+            let (capacity, every) = (self.capacity, self.refill_every);
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(every);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                ticker.tick().await; // the first tick is immediate
+                loop {
+                    ticker.tick().await;
+                    let Some(tokens) = tokens.upgrade() else { break };
+                    // Only this task adds tokens, so the check can't race past capacity
+                    if tokens.available_permits() < capacity {
+                        tokens.add_permits(1);
+                    }
+                }
+            });
+        });
+    }
+
+    // Waits for a token and spends it
+    pub async fn acquire(&self) {
+        self.start_refill();
+        if let Ok(permit) = self.tokens.try_acquire() {
+            permit.forget();
+            metrics::global().record_rate_limit_wait(&self.host, Duration::ZERO, false);
+            return;
+        }
+
+        let started = Instant::now();
+        if let Ok(permit) = self.tokens.acquire().await {
+            permit.forget();
+        }
+        let waited = started.elapsed();
+        debug!(host = %self.host, waited_ms = waited.as_millis() as u64, "Rate limiter delayed an upstream request");
+        metrics::global().record_rate_limit_wait(&self.host, waited, true);
+    }
+}
+
+// One bucket per upstream host, created on first use
+pub struct HostRateLimiter {
+    requests_per_minute: u32,        // hosts without an override
+    overrides: BTreeMap<String, u32>, // ApiConfig.upstream_host_limits
+    buckets: Mutex<HashMap<String, Arc<TokenBucket>>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self { requests_per_minute, overrides: BTreeMap::new(), buckets: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn with_host_limits(mut self, overrides: BTreeMap<String, u32>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    fn bucket(&self, host: &str) -> Arc<TokenBucket> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bucket = buckets.entry(host.to_string()).or_insert_with(|| {
+            let rpm = self.overrides.get(host).copied().unwrap_or(self.requests_per_minute);
+            Arc::new(TokenBucket::new(host, rpm))
+        });
+        Arc::clone(bucket)
+    }
+
+    pub async fn acquire(&self, host: &str) {
+        self.bucket(host).acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_drained_bucket_waits_for_the_refill() {
+        // 120/min saves up 20 tokens and puts one back every 500ms
+        let bucket = TokenBucket::new("example.com", 120);
+        assert_eq!(bucket.capacity, 20);
+        for _ in 0..bucket.capacity {
+            tokio::time::timeout(Duration::from_millis(50), bucket.acquire()).await
+                .expect("a token within capacity is immediate");
+        }
+
+        let started = Instant::now();
+        assert!(tokio::time::timeout(Duration::from_millis(200), bucket.acquire()).await.is_err());
+        tokio::time::timeout(Duration::from_secs(2), bucket.acquire()).await
+            .expect("the refill frees a token");
+        assert!(started.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn hosts_have_separate_buckets() {
+        let limiter = HostRateLimiter::new(6).with_host_limits(BTreeMap::from([("api.polygon.io".to_string(), 120)]));
+        limiter.acquire("query1.finance.yahoo.com").await; // 6/min holds a single token
+        assert!(tokio::time::timeout(Duration::from_millis(100), limiter.acquire("query1.finance.yahoo.com")).await.is_err());
+        tokio::time::timeout(Duration::from_millis(100), limiter.acquire("api.polygon.io")).await
+            .expect("another host's budget is untouched");
+    }
+}
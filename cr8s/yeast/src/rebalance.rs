@@ -0,0 +1,76 @@
+// rebalance.rs - trades that move holdings back towards target weights
+//
+// Weights are shares of total value (holdings at the current price plus cash). Only holdings whose
+// weight has drifted more than the threshold from target are traded, each all the way back to its
+// target. Sells are sized first and their proceeds added to cash; buys are then scaled down
+// together if they would spend more than that, so the plan never takes cash below zero (or
+// further below it, for an overdrawn account). Quantities round towards zero to whole shares
+// unless fractional trading is allowed.
+
+pub struct Holding {
+    pub quantity: f64,
+    pub price: f64,
+    pub target_weight: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Line {
+    pub current_weight: f64,
+    pub drift: f64,    // current - target
+    pub quantity: f64, // to trade: positive buys, negative sells, 0 holds
+    pub weight_after: f64,
+}
+
+pub struct Plan {
+    pub total_value: f64,
+    pub cash_after: f64,
+    pub lines: Vec<Line>,
+}
+
+fn round_quantity(quantity: f64, fractional: bool) -> f64 {
+    if fractional { quantity } else { quantity.trunc() }
+}
+
+// None when there's nothing to weigh against (no positive total value or a holding without a price)
+pub fn plan(holdings: &[Holding], cash: f64, drift_threshold: f64, fractional: bool) -> Option<Plan> {
+    if holdings.iter().any(|h| h.price <= 0.0) {
+        return None;
+    }
+    let total_value = holdings.iter().map(|h| h.quantity * h.price).sum::<f64>() + cash;
+    if total_value <= 0.0 {
+        return None;
+    }
+
+    let mut lines: Vec<Line> = holdings
+        .iter()
+        .map(|h| {
+            let current_weight = h.quantity * h.price / total_value;
+            let drift = current_weight - h.target_weight;
+            let quantity = if drift.abs() > drift_threshold {
+                let wanted = (h.target_weight * total_value - h.quantity * h.price) / h.price;
+                // Never sell more than is held
+                round_quantity(wanted.max(-h.quantity), fractional)
+            } else {
+                0.0
+            };
+            Line { current_weight, drift, quantity, weight_after: current_weight }
+        })
+        .collect();
+
+    let trade_value = |line: &Line, h: &Holding| line.quantity * h.price;
+    let proceeds: f64 = lines.iter().zip(holdings).filter(|(l, _)| l.quantity < 0.0).map(|(l, h)| -trade_value(l, h)).sum();
+    let spend: f64 = lines.iter().zip(holdings).filter(|(l, _)| l.quantity > 0.0).map(|(l, h)| trade_value(l, h)).sum();
+    let budget = (cash + proceeds).max(0.0);
+    if spend > budget {
+        let scale = budget / spend;
+        for line in lines.iter_mut().filter(|l| l.quantity > 0.0) {
+            line.quantity = round_quantity(line.quantity * scale, fractional);
+        }
+    }
+
+    let spend: f64 = lines.iter().zip(holdings).filter(|(l, _)| l.quantity > 0.0).map(|(l, h)| trade_value(l, h)).sum();
+    for (line, h) in lines.iter_mut().zip(holdings) {
+        line.weight_after = (h.quantity + line.quantity) * h.price / total_value;
+    }
+    Some(Plan { total_value, cash_after: cash + proceeds - spend, lines })
+}
@@ -0,0 +1,37 @@
+// request_id.rs - Per-request ids for error bodies and logs
+//
+// Both HTTP servers give each request an id: the caller's X-Request-Id when it looks like one,
+// so ids can be traced across services, otherwise a new UUID. The id is a field of the request's
+// log span, the `request_id` of any ErrorResponse, and the X-Request-Id header on error responses.
+// It lives in a task-local scoped around the handler, so code deep in a handler can read it
+// without it being threaded through.
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+pub const HEADER: &str = "x-request-id";
+
+// Longest caller-supplied id that's kept
+const MAX_LEN: usize = 128;
+
+// The caller's id when it's a short token of safe characters, else a fresh one; it ends up in a
+// response header, so anything that could break the header is replaced
+pub fn from_header(header: Option<&str>) -> String {
+    header
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_LEN)
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+// Runs `handler` with `id` as the current request id
+pub async fn scope<F: std::future::Future>(id: String, handler: F) -> F::Output {
+    REQUEST_ID.scope(id, handler).await
+}
+
+// The id of the request being handled; empty outside a request
+pub fn current() -> String {
+    REQUEST_ID.try_with(String::clone).unwrap_or_default()
+}
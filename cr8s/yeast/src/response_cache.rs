@@ -0,0 +1,296 @@
+// response_cache.rs - In-memory cache for upstream GET responses
+//
+// Entries are keyed by method + URL with the query string, minus the session crumb so a new
+// crumb doesn't orphan them. A fresh entry is served without touching the network. A stale
+// entry that came with an ETag is revalidated with If-None-Match, and a 304 renews it.
+// Only 200 responses are stored, at most MAX_ENTRIES of them. Prices that need to be live (quotes,
+// charts, the stream) skip the cache and call send_with_retry directly. StockDataApi owns the
+// cache and hands it to each YahooFinanceClient it builds.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderName, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::api::ApiError;
+use crate::retry::SendWithRetry;
+
+// Query parameters left out of cache keys
+const UNKEYED_PARAMS: [&str; 1] = ["crumb"];
+// Past this, an insert first drops expired entries, then the ones closest to expiring
+const MAX_ENTRIES: usize = 1000;
+
+struct CachedResponse {
+    body: Vec<u8>,
+    content_type: Option<String>,
+    etag: Option<String>,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseCacheStats {
+    pub entries: usize,
+    pub fresh_entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub revalidated: u64, // stale entries renewed by a 304
+    pub ttl_secs: u64,
+}
+
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    revalidated: AtomicU64,
+}
+
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+fn cache_key(method: &Method, url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let kept: Vec<(String, String)> = url.query_pairs()
+        .filter(|(k, _)| !UNKEYED_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    format!("{} {}", method, url)
+}
+
+// Rebuilds a response around a stored body so callers read it exactly like a live one
+fn to_response(entry: &CachedResponse) -> Response {
+    let mut builder = http::Response::builder().status(StatusCode::OK);
+    if let Some(content_type) = &entry.content_type {
+        builder = builder.header(CONTENT_TYPE, content_type);
+    }
+    if let Some(etag) = &entry.etag {
+        builder = builder.header(ETAG, etag);
+    }
+    Response::from(builder.body(entry.body.clone()).expect("valid cached response"))
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            revalidated: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> ResponseCacheStats {
+        let entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        ResponseCacheStats {
+            entries: entries.len(),
+            fresh_entries: entries.values().filter(|e| e.expires_at > now).count(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            revalidated: self.revalidated.load(Ordering::Relaxed),
+            ttl_secs: self.ttl.as_secs(),
+        }
+    }
+
+    // Drops every entry and returns how many there were; counters keep running
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, ApiError> {
+        let (key, path) = match request.try_clone().and_then(|r| r.build().ok()) {
+            Some(built) if built.method() == Method::GET => (cache_key(built.method(), built.url()), built.url().path().to_string()),
+            _ => return request.send_with_retry().await,
+        };
+
+        let etag = {
+            let entries = self.entries.lock().unwrap();
+            match entries.get(&key) {
+                Some(entry) if entry.expires_at > Instant::now() => {
+                    debug!(%path, "Response cache hit");
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(to_response(entry));
+                }
+                Some(entry) => entry.etag.clone(),
+                None => None,
+            }
+        };
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let request = match &etag {
+            Some(etag) => request.header(IF_NONE_MATCH, etag),
+            None => request,
+        };
+        let response = request.send_with_retry().await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let mut entries = self.entries.lock().unwrap();
+            if let Some(entry) = entries.get_mut(&key) {
+                entry.expires_at = Instant::now() + self.ttl;
+                self.revalidated.fetch_add(1, Ordering::Relaxed);
+                return Ok(to_response(entry));
+            }
+            return Err(ApiError::FetchError("304 for a response no longer cached".to_string()));
+        }
+        if response.status() != StatusCode::OK {
+            return Ok(response);
+        }
+
+        let header = |name: HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let content_type = header(CONTENT_TYPE);
+        let etag = header(ETAG);
+        let body = response.bytes().await.map_err(|e| ApiError::FetchError(e.to_string()))?.to_vec();
+
+        let entry = CachedResponse { body, content_type, etag, expires_at: Instant::now() + self.ttl };
+        let response = to_response(&entry);
+        self.insert(key, entry);
+        Ok(response)
+    }
+
+    fn insert(&self, key: String, entry: CachedResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, e| e.expires_at > now);
+            // Still full of fresh entries: drop the oldest
+            while entries.len() >= MAX_ENTRIES {
+                let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.expires_at).map(|(k, _)| k.clone()) else { break };
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, entry);
+    }
+}
+
+// `.send_cached(&cache)` in place of `.send_with_retry()` for responses that can be reused
+pub trait SendCached {
+    fn send_cached(self, cache: &Arc<ResponseCache>) -> BoxFuture<'static, Result<Response, ApiError>>;
+}
+
+impl SendCached for RequestBuilder {
+    fn send_cached(self, cache: &Arc<ResponseCache>) -> BoxFuture<'static, Result<Response, ApiError>> {
+        let cache = Arc::clone(cache);
+        Box::pin(async move { cache.send(self).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn key(url: &str) -> String {
+        cache_key(&Method::GET, &reqwest::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn cache_key_ignores_the_crumb() {
+        assert_eq!(
+            key("https://query1.finance.yahoo.com/v10/finance/quoteSummary/AAPL?modules=price&crumb=abc"),
+            key("https://query1.finance.yahoo.com/v10/finance/quoteSummary/AAPL?modules=price&crumb=xyz"),
+        );
+        assert_eq!(
+            key("https://query1.finance.yahoo.com/v10/finance/quoteSummary/AAPL?crumb=abc&modules=price"),
+            "GET https://query1.finance.yahoo.com/v10/finance/quoteSummary/AAPL?modules=price",
+        );
+        assert_eq!(key("https://example.com/search?crumb=abc"), "GET https://example.com/search");
+        assert_ne!(key("https://example.com/search?q=AAPL"), key("https://example.com/search?q=MSFT"));
+    }
+
+    // Answers 200 with an ETag, then 304 to any request that sends it back. Returns the base URL
+    // and how many requests reached the server.
+    async fn etag_server() -> (String, Arc<AtomicU64>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicU64::new(0));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                seen.fetch_add(1, Ordering::Relaxed);
+                let reply = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = "{\"price\":1}";
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(), body
+                    )
+                };
+                let _ = socket.write_all(reply.as_bytes()).await;
+            }
+        });
+        (base, requests)
+    }
+
+    #[tokio::test]
+    async fn stale_entry_is_renewed_by_a_304() {
+        let (base, requests) = etag_server().await;
+        let client = reqwest::Client::new();
+        let cache = ResponseCache::new(Duration::ZERO); // every entry is stale at once
+
+        let first = cache.send(client.get(format!("{}/quote?crumb=a", base))).await.unwrap();
+        assert_eq!(first.text().await.unwrap(), "{\"price\":1}");
+
+        let renewed = cache.send(client.get(format!("{}/quote?crumb=b", base))).await.unwrap();
+        assert_eq!(renewed.status(), StatusCode::OK);
+        assert_eq!(renewed.text().await.unwrap(), "{\"price\":1}");
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.misses, stats.revalidated, stats.hits), (1, 2, 1, 0));
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn fresh_entry_is_served_without_a_request() {
+        let (base, requests) = etag_server().await;
+        let client = reqwest::Client::new();
+        let cache = ResponseCache::new(Duration::from_secs(60));
+
+        cache.send(client.get(format!("{}/quote", base))).await.unwrap();
+        let cached = cache.send(client.get(format!("{}/quote", base))).await.unwrap();
+        assert_eq!(cached.text().await.unwrap(), "{\"price\":1}");
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn inserts_past_the_cap_evict() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+        let entry = |expires_at| CachedResponse { body: Vec::new(), content_type: None, etag: None, expires_at };
+        cache.insert("expired".to_string(), entry(now - Duration::from_secs(1)));
+        for i in 1..MAX_ENTRIES {
+            cache.insert(format!("fresh {}", i), entry(now + Duration::from_secs(i as u64)));
+        }
+        assert_eq!(cache.stats().entries, MAX_ENTRIES);
+
+        // Expired entries go first
+        cache.insert("new".to_string(), entry(now + Duration::from_secs(5000)));
+        assert_eq!(cache.stats().entries, MAX_ENTRIES);
+        assert!(!cache.entries.lock().unwrap().contains_key("expired"));
+
+        // Then the entry closest to expiring
+        cache.insert("newer".to_string(), entry(now + Duration::from_secs(5000)));
+        let entries = cache.entries.lock().unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert!(!entries.contains_key("fresh 1") && entries.contains_key("fresh 2"));
+    }
+}
@@ -0,0 +1,215 @@
+// retry.rs - Retries and per-host circuit breaking for outbound HTTP
+//
+// Transient failures (connection errors, timeouts, 429 and 5xx) are retried with jittered
+// exponential backoff. A host that keeps failing across requests trips its breaker, and calls
+// to it fail fast with ApiError::RateLimited until the cooldown passes; the first request after
+// that is a probe whose outcome closes or re-opens the breaker. Every attempt first takes a token
+// from its host's bucket in the shared HostRateLimiter.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tracing::{debug, warn};
+
+use crate::api::ApiError;
+use crate::rate_limit::{HostRateLimiter, DEFAULT_REQUESTS_PER_MINUTE};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32, // including the first try
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Full jitter over the upper half: attempt n waits between d/2 and d, d = base * 2^n
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+        let jitter = RandomState::new().build_hasher().finish() % 1000;
+        ceiling / 2 + ceiling / 2 * jitter as u32 / 1000
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BreakerConfig {
+    pub failure_threshold: u32, // consecutive failed requests before the breaker opens
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self { failure_threshold: 5, cooldown: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Default)]
+struct HostState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+pub struct Resilience {
+    policy: RetryPolicy,
+    breaker: BreakerConfig,
+    hosts: Mutex<HashMap<String, HostState>>,
+    limiter: HostRateLimiter,
+}
+
+static RESILIENCE: OnceLock<Resilience> = OnceLock::new();
+
+// Sets the process-wide policy and upstream rate limits. Only the first call (before any
+// request) takes effect.
+pub fn configure(policy: RetryPolicy, breaker: BreakerConfig, limiter: HostRateLimiter) {
+    let _ = RESILIENCE.set(Resilience::new(policy, breaker).with_rate_limiter(limiter));
+}
+
+pub fn global() -> &'static Resilience {
+    RESILIENCE.get_or_init(|| Resilience::new(RetryPolicy::default(), BreakerConfig::default()))
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Retry-After in seconds, when the upstream sent one
+fn retry_after(response: &Response) -> Option<Duration> {
+    response.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+impl Resilience {
+    pub fn new(policy: RetryPolicy, breaker: BreakerConfig) -> Self {
+        Self {
+            policy,
+            breaker,
+            hosts: Mutex::new(HashMap::new()),
+            limiter: HostRateLimiter::new(DEFAULT_REQUESTS_PER_MINUTE),
+        }
+    }
+
+    pub fn with_rate_limiter(mut self, limiter: HostRateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    fn check_breaker(&self, host: &str) -> Result<(), ApiError> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let Some(state) = hosts.get_mut(host) else { return Ok(()) };
+        match state.open_until {
+            Some(until) if Instant::now() < until => Err(ApiError::RateLimited(format!(
+                "{} is failing; retry in {}s", host, until.saturating_duration_since(Instant::now()).as_secs().max(1)
+            ))),
+            Some(_) => {
+                state.open_until = None; // cooldown over: let this request through as a probe
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn record(&self, host: &str, success: bool) {
+        let mut hosts = self.hosts.lock().unwrap();
+        let state = hosts.entry(host.to_string()).or_default();
+        if success {
+            *state = HostState::default();
+            return;
+        }
+        crate::metrics::global().record_upstream_failure(host);
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.breaker.failure_threshold {
+            if state.open_until.is_none() {
+                warn!(host, failures = state.consecutive_failures, "Circuit open after repeated upstream failures");
+            }
+            state.open_until = Some(Instant::now() + self.breaker.cooldown);
+        }
+    }
+
+    // Sends the request, retrying transient failures. Once attempts run out a transient status
+    // is returned as-is so callers keep their own status handling; transport errors become
+    // FetchError. Requests whose body can't be cloned are sent once. Each attempt is logged with
+    // its URL minus the query string, which can carry API keys and crumbs.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, ApiError> {
+        let Some((host, method, url)) = request.try_clone()
+            .and_then(|r| r.build().ok())
+            .map(|r| {
+                let host = r.url().host_str().unwrap_or_default().to_string();
+                (host.clone(), r.method().to_string(), format!("{}://{}{}", r.url().scheme(), host, r.url().path()))
+            })
+        else {
+            return request.send().await.map_err(|e| ApiError::FetchError(e.to_string()));
+        };
+        self.check_breaker(&host)?;
+
+        let mut attempt = 0;
+        loop {
+            let Some(this_try) = request.try_clone() else {
+                return request.send().await.map_err(|e| ApiError::FetchError(e.to_string()));
+            };
+            let last_attempt = attempt + 1 >= self.policy.max_attempts;
+
+            self.limiter.acquire(&host).await;
+            let started = Instant::now();
+            let sent = this_try.send().await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            let delay = match sent {
+                Ok(response) if !is_transient(response.status()) => {
+                    debug!(%method, %url, status = response.status().as_u16(), attempt, elapsed_ms, "Upstream response");
+                    self.record(&host, true);
+                    return Ok(response);
+                }
+                Ok(response) if last_attempt => {
+                    warn!(%method, %url, status = response.status().as_u16(), attempt, elapsed_ms, "Upstream failed; out of retries");
+                    self.record(&host, false);
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let delay = retry_after(&response).unwrap_or_else(|| self.policy.backoff(attempt)).min(self.policy.max_delay);
+                    warn!(%method, %url, status = response.status().as_u16(), attempt, elapsed_ms, delay_ms = delay.as_millis() as u64, "Upstream failed; retrying");
+                    delay
+                }
+                Err(e) if last_attempt || !(e.is_timeout() || e.is_connect() || e.is_request()) => {
+                    warn!(%method, %url, error = %e, attempt, elapsed_ms, "Upstream request failed");
+                    self.record(&host, false);
+                    return Err(ApiError::FetchError(format!("{} {}: {}", method, url, e)));
+                }
+                Err(e) => {
+                    let delay = self.policy.backoff(attempt);
+                    warn!(%method, %url, error = %e, attempt, elapsed_ms, delay_ms = delay.as_millis() as u64, "Upstream request failed; retrying");
+                    delay
+                }
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+// `.send_with_retry()` in place of `.send()` on any request builder
+pub trait SendWithRetry {
+    fn send_with_retry(self) -> BoxFuture<'static, Result<Response, ApiError>>;
+}
+
+impl SendWithRetry for RequestBuilder {
+    fn send_with_retry(self) -> BoxFuture<'static, Result<Response, ApiError>> {
+        Box::pin(global().send(self))
+    }
+}
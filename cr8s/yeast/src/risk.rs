@@ -0,0 +1,165 @@
+// risk.rs - portfolio risk from daily closes: beta, volatility, VaR/CVaR, Sharpe/Sortino, drawdown,
+// and the return correlations between symbols
+//
+// Series are aligned on the trading days every symbol (and the benchmark) has a close for, and
+// turned into simple daily returns. The portfolio return is the weighted sum of its members'
+// returns with today's market-value weights held constant, i.e. how the current holdings would
+// have behaved over the window rather than the account's actual history. VaR and CVaR are
+// historical (no distribution assumed) and reported as positive fractions of portfolio value.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::types::Candle;
+
+pub const TRADING_DAYS: f64 = 252.0;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RiskMetrics {
+    pub observations: usize,
+    pub beta: Option<f64>,    // None when the benchmark doesn't move
+    pub volatility: f64,      // annualized
+    pub annual_return: f64,   // mean daily return x 252
+    pub var: f64,
+    pub cvar: f64,
+    pub sharpe: Option<f64>,  // None without volatility
+    pub sortino: Option<f64>, // None without downside
+    pub max_drawdown: f64,    // largest peak-to-trough fall of the compounded returns, as a fraction
+}
+
+// Closes of every series on the days they all share, oldest first; one row per series
+pub fn align_closes(series: &[&[Candle]]) -> Vec<Vec<f64>> {
+    let day = |c: &Candle| c.timestamp.div_euclid(SECONDS_PER_DAY);
+    let mut common: Option<HashSet<i64>> = None;
+    for candles in series {
+        let days: HashSet<i64> = candles.iter().filter(|c| c.close.is_finite()).map(day).collect();
+        common = Some(match common {
+            Some(common) => common.intersection(&days).copied().collect(),
+            None => days,
+        });
+    }
+    let common = common.unwrap_or_default();
+
+    series
+        .iter()
+        .map(|candles| {
+            // Last close per day, so an intraday bar doesn't shadow the daily one
+            let by_day: BTreeMap<i64, f64> = candles
+                .iter()
+                .filter(|c| c.close.is_finite() && common.contains(&day(c)))
+                .map(|c| (day(c), c.close))
+                .collect();
+            by_day.into_values().collect()
+        })
+        .collect()
+}
+
+// Simple returns between consecutive closes; a non-positive close yields 0 rather than inf
+pub fn returns(closes: &[f64]) -> Vec<f64> {
+    closes.windows(2).map(|w| if w[0] > 0.0 { w[1] / w[0] - 1.0 } else { 0.0 }).collect()
+}
+
+// Weighted sum of aligned return series
+pub fn weighted_returns(series: &[Vec<f64>], weights: &[f64]) -> Vec<f64> {
+    let len = series.iter().map(Vec::len).min().unwrap_or(0);
+    (0..len).map(|i| series.iter().zip(weights).map(|(r, w)| r[i] * w).sum()).collect()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Sample covariance
+pub fn covariance(a: &[f64], b: &[f64]) -> f64 {
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum::<f64>() / (a.len() as f64 - 1.0)
+}
+
+// (VaR, CVaR) at `confidence`: the loss not exceeded on that share of days, and the average loss
+// on the days beyond it
+pub fn historical_var(returns: &[f64], confidence: f64) -> (f64, f64) {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let tail = (((1.0 - confidence) * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1;
+    let var = -sorted[tail];
+    let cvar = -mean(&sorted[..=tail]);
+    (var.max(0.0), cvar.max(0.0))
+}
+
+pub fn max_drawdown(returns: &[f64]) -> f64 {
+    let (mut value, mut peak, mut worst) = (1.0_f64, 1.0_f64, 0.0_f64);
+    for r in returns {
+        value *= 1.0 + r;
+        peak = peak.max(value);
+        worst = worst.max(1.0 - value / peak);
+    }
+    worst
+}
+
+// Metrics of `portfolio` daily returns against `benchmark` returns of the same days. `risk_free_rate`
+// is annual. None with fewer than two observations.
+pub fn metrics(portfolio: &[f64], benchmark: &[f64], confidence: f64, risk_free_rate: f64) -> Option<RiskMetrics> {
+    let n = portfolio.len().min(benchmark.len());
+    if n < 2 {
+        return None;
+    }
+    let (portfolio, benchmark) = (&portfolio[..n], &benchmark[..n]);
+
+    let benchmark_variance = covariance(benchmark, benchmark);
+    let beta = (benchmark_variance > 0.0).then(|| covariance(portfolio, benchmark) / benchmark_variance);
+    let volatility = covariance(portfolio, portfolio).sqrt() * TRADING_DAYS.sqrt();
+    let annual_return = mean(portfolio) * TRADING_DAYS;
+    let excess = annual_return - risk_free_rate;
+
+    let daily_rf = risk_free_rate / TRADING_DAYS;
+    let downside = (portfolio.iter().map(|r| (r - daily_rf).min(0.0).powi(2)).sum::<f64>() / n as f64).sqrt()
+        * TRADING_DAYS.sqrt();
+
+    let (var, cvar) = historical_var(portfolio, confidence);
+    Some(RiskMetrics {
+        observations: n,
+        beta,
+        volatility,
+        annual_return,
+        var,
+        cvar,
+        sharpe: (volatility > 0.0).then(|| excess / volatility),
+        sortino: (downside > 0.0).then(|| excess / downside),
+        max_drawdown: max_drawdown(portfolio),
+    })
+}
+
+// Pearson correlation of two return series; None when either doesn't move
+pub fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return None;
+    }
+    let (a, b) = (&a[..n], &b[..n]);
+    let (var_a, var_b) = (covariance(a, a), covariance(b, b));
+    (var_a > 0.0 && var_b > 0.0).then(|| (covariance(a, b) / (var_a * var_b).sqrt()).clamp(-1.0, 1.0))
+}
+
+// Symmetric matrix of pairwise correlations with 1 on the diagonal (None for a flat series)
+pub fn correlation_matrix(series: &[Vec<f64>]) -> Vec<Vec<Option<f64>>> {
+    let mut matrix = vec![vec![None; series.len()]; series.len()];
+    for i in 0..series.len() {
+        matrix[i][i] = correlation(&series[i], &series[i]);
+        for j in i + 1..series.len() {
+            let c = correlation(&series[i], &series[j]);
+            matrix[i][j] = c;
+            matrix[j][i] = c;
+        }
+    }
+    matrix
+}
+
+// Mean of the off-diagonal correlations in row `row`, or of the whole upper triangle with None
+pub fn average_correlation(matrix: &[Vec<Option<f64>>], row: Option<usize>) -> Option<f64> {
+    let values: Vec<f64> = (0..matrix.len())
+        .flat_map(|i| (i + 1..matrix.len()).map(move |j| (i, j)))
+        .filter(|(i, j)| row.is_none_or(|r| r == *i || r == *j))
+        .filter_map(|(i, j)| matrix[i][j])
+        .collect();
+    (!values.is_empty()).then(|| mean(&values))
+}
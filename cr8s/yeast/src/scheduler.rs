@@ -0,0 +1,423 @@
+// scheduler.rs - recurring background jobs: what runs, when, and how the last run went
+//
+// A job runs every N seconds or on a cron expression (minute hour day-of-month month day-of-week,
+// in UTC, with *, lists, ranges and /steps; day-of-week 0 and 7 are Sunday, and when both day
+// fields are restricted either may match, as in cron). The Scheduler only keeps the books:
+// StockDataApi::run_scheduler asks it for due jobs, does the work and reports back. Jobs live in
+// memory, loaded at startup from --scheduler-jobs or added through the API; stopping the scheduler
+// pauses every job without forgetting them.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::ApiError;
+use crate::calendar;
+
+// Shortest interval a job may repeat at
+const MIN_INTERVAL_SECS: u64 = 10;
+// How far ahead a cron expression is searched for its next match (covers Feb 29 schedules)
+const CRON_SEARCH_YEARS: i64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobTask {
+    // Quote every watchlist's symbols (or one watchlist's), keeping the caches warm
+    RefreshWatchlists { watchlist_id: Option<String> },
+    // Record each portfolio's cash, market value and total
+    SnapshotPortfolios,
+    // A screener request body, as POSTed to /api/v1/screener; the last run's results are kept
+    RunScreener { screener: serde_json::Value },
+    EvaluateAlerts,
+    ProcessOrders,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Schedule {
+    Every { seconds: u64 },
+    Cron { expression: String },
+}
+
+// Body of POST /api/v1/scheduler/jobs, and each entry of a --scheduler-jobs file. Exactly one of
+// every_seconds and cron.
+#[derive(Debug, Deserialize)]
+pub struct JobRequest {
+    pub name: String,
+    pub task: JobTask,
+    pub every_seconds: Option<u64>,
+    pub cron: Option<String>,
+    pub enabled: Option<bool>, // default true
+    pub trading_days_only: Option<bool>, // skip runs on NYSE weekends and holidays; default false
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct JobRun {
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub ok: bool,
+    pub summary: String, // what was done, or the error
+    pub result: Option<serde_json::Value>, // screener results
+}
+
+// What a task reports when it succeeds
+pub struct JobOutcome {
+    pub summary: String,
+    pub result: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Job {
+    pub id: String,
+    pub name: String,
+    pub task: JobTask,
+    pub schedule: Schedule,
+    pub enabled: bool,
+    pub trading_days_only: bool,
+    pub running: bool,
+    pub created_at: String,
+    pub next_run: Option<String>, // None while disabled or running
+    pub last_run: Option<JobRun>,
+    pub runs: u64,
+    pub failures: u64,
+    #[serde(skip)]
+    cron: Option<Cron>,
+    #[serde(skip)]
+    due: Option<DateTime<Utc>>,
+}
+
+impl Job {
+    fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (&self.schedule, &self.cron) {
+            (Schedule::Every { seconds }, _) => Some(time + Duration::seconds(*seconds as i64)),
+            (Schedule::Cron { .. }, Some(cron)) => cron.next_after(time),
+            (Schedule::Cron { .. }, None) => None,
+        }
+    }
+
+    fn set_due(&mut self, due: Option<DateTime<Utc>>) {
+        self.due = due;
+        self.next_run = due.map(|d| d.to_rfc3339());
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulerStatus {
+    pub running: bool,
+    pub jobs: Vec<Job>,
+}
+
+pub struct Scheduler {
+    state: Mutex<State>,
+}
+
+struct State {
+    running: bool,
+    jobs: Vec<Job>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self { state: Mutex::new(State { running: true, jobs: Vec::new() }) }
+    }
+}
+
+impl Scheduler {
+    pub fn status(&self) -> SchedulerStatus {
+        let state = self.state.lock().unwrap();
+        SchedulerStatus { running: state.running, jobs: state.jobs.clone() }
+    }
+
+    pub fn set_running(&self, running: bool) -> SchedulerStatus {
+        self.state.lock().unwrap().running = running;
+        self.status()
+    }
+
+    pub fn add_job(&self, request: JobRequest) -> Result<Job, ApiError> {
+        if request.name.trim().is_empty() {
+            return Err(ApiError::InvalidParameters("Job name is required".to_string()));
+        }
+        let (schedule, cron) = match (request.every_seconds, request.cron) {
+            (Some(seconds), None) if seconds >= MIN_INTERVAL_SECS => (Schedule::Every { seconds }, None),
+            (Some(_), None) => {
+                return Err(ApiError::InvalidParameters(format!("every_seconds must be at least {}", MIN_INTERVAL_SECS)));
+            }
+            (None, Some(expression)) => {
+                let cron = Cron::parse(&expression)?;
+                (Schedule::Cron { expression }, Some(cron))
+            }
+            _ => return Err(ApiError::InvalidParameters("A job needs exactly one of every_seconds and cron".to_string())),
+        };
+
+        let now = Utc::now();
+        let mut job = Job {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            task: request.task,
+            schedule,
+            enabled: request.enabled.unwrap_or(true),
+            trading_days_only: request.trading_days_only.unwrap_or(false),
+            running: false,
+            created_at: now.to_rfc3339(),
+            next_run: None,
+            last_run: None,
+            runs: 0,
+            failures: 0,
+            cron,
+            due: None,
+        };
+        if job.enabled {
+            job.set_due(job.next_after(now));
+        }
+        self.state.lock().unwrap().jobs.push(job.clone());
+        Ok(job)
+    }
+
+    pub fn job(&self, id: &str) -> Result<Job, ApiError> {
+        self.with_job(id, |job| Ok(job.clone()))
+    }
+
+    pub fn remove_job(&self, id: &str) -> Result<Job, ApiError> {
+        let mut state = self.state.lock().unwrap();
+        let index = state.jobs.iter()
+            .position(|j| j.id == id)
+            .ok_or_else(|| ApiError::DataNotFound(format!("Job {} not found", id)))?;
+        Ok(state.jobs.remove(index))
+    }
+
+    // Pauses or resumes one job; a resumed job next runs one period from now
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<Job, ApiError> {
+        self.with_job(id, |job| {
+            job.enabled = enabled;
+            if !job.running {
+                let due = if enabled { job.next_after(Utc::now()) } else { None };
+                job.set_due(due);
+            }
+            Ok(job.clone())
+        })
+    }
+
+    // Enabled jobs whose time has come, marked running so a slow run can't overlap the next one.
+    // Nothing while the scheduler is stopped. Trading-days-only jobs due when the market's closed
+    // for the day are skipped to their next time rather than run.
+    pub fn take_due(&self, now: DateTime<Utc>) -> Vec<(String, JobTask)> {
+        let mut state = self.state.lock().unwrap();
+        if !state.running {
+            return Vec::new();
+        }
+        let trading_day = calendar::is_trading_day_at(now);
+        let mut taken = Vec::new();
+        for job in state.jobs.iter_mut().filter(|job| job.enabled && !job.running && job.due.is_some_and(|due| due <= now)) {
+            if job.trading_days_only && !trading_day {
+                job.set_due(job.next_after(now));
+                continue;
+            }
+            job.running = true;
+            job.set_due(None);
+            taken.push((job.id.clone(), job.task.clone()));
+        }
+        taken
+    }
+
+    // Marks a job running for an on-demand run, even while it's disabled or the scheduler is stopped
+    pub fn take_now(&self, id: &str) -> Result<JobTask, ApiError> {
+        self.with_job(id, |job| {
+            if job.running {
+                return Err(ApiError::InvalidParameters(format!("Job {} is already running", job.id)));
+            }
+            job.running = true;
+            job.set_due(None);
+            Ok(job.task.clone())
+        })
+    }
+
+    // Records a run and schedules the next one (from when it started, for intervals)
+    pub fn finish(&self, id: &str, started: DateTime<Utc>, duration_ms: u64, outcome: Result<JobOutcome, ApiError>) {
+        // The job may have been removed while it ran
+        let _ = self.with_job(id, |job| {
+            let (ok, summary, result) = match outcome {
+                Ok(outcome) => (true, outcome.summary, outcome.result),
+                Err(e) => (false, e.to_string(), None),
+            };
+            job.running = false;
+            job.runs += 1;
+            if !ok {
+                job.failures += 1;
+            }
+            job.last_run = Some(JobRun { started_at: started.to_rfc3339(), duration_ms, ok, summary, result });
+            let now = Utc::now();
+            let due = if job.enabled { job.next_after(started).map(|next| next.max(now)) } else { None };
+            job.set_due(due);
+            Ok(())
+        });
+    }
+
+    fn with_job<T>(&self, id: &str, f: impl FnOnce(&mut Job) -> Result<T, ApiError>) -> Result<T, ApiError> {
+        let mut state = self.state.lock().unwrap();
+        let job = state.jobs.iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| ApiError::DataNotFound(format!("Job {} not found", id)))?;
+        f(job)
+    }
+}
+
+// A parsed cron expression: one bit per allowed value of each field
+#[derive(Debug, Clone)]
+struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,     // 1..=31
+    months: u64,   // 1..=12
+    weekdays: u64, // 0..=6, Sunday = 0
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    fn parse(expression: &str) -> Result<Self, ApiError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return Err(ApiError::InvalidParameters(format!(
+                "Cron expression '{}' needs 5 fields: minute hour day-of-month month day-of-week", expression
+            )));
+        };
+        let field = |text: &str, min: u32, max: u32| {
+            cron_field(text, min, max).ok_or_else(|| {
+                ApiError::InvalidParameters(format!("Invalid cron field '{}' (allowed {}-{})", text, min, max))
+            })
+        };
+        let mut weekday_bits = field(weekdays, 0, 7)?;
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits = (weekday_bits | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: field(minutes, 0, 59)?,
+            hours: field(hours, 0, 23)?,
+            days: field(days, 1, 31)?,
+            months: field(months, 1, 12)?,
+            weekdays: weekday_bits,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: DateTime<Utc>) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    // First matching minute strictly after `time`
+    fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = time + Duration::days(366 * CRON_SEARCH_YEARS);
+        while t <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(t) {
+                t = t.with_hour(0)?.with_minute(0)? + Duration::days(1);
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+// Bits for a comma-separated list of *, N, A-B, each optionally /STEP
+fn cron_field(text: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().ok().filter(|s| *s > 0)?)),
+            None => (part, None),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (a.parse().ok()?, b.parse().ok()?),
+                // N/STEP runs from N to the end of the field
+                None => {
+                    let n = range.parse().ok()?;
+                    (n, if step.is_some() { max } else { n })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Some(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> DateTime<Utc> {
+        Cron::parse(expression).unwrap().next_after(after).unwrap()
+    }
+
+    #[test]
+    fn steps_over_the_whole_field() {
+        let cron = Cron::parse("*/15 * * * *").unwrap();
+        assert_eq!(cron.minutes, (1 << 0) | (1 << 15) | (1 << 30) | (1 << 45));
+        assert_eq!(next("*/15 * * * *", utc(2024, 5, 1, 10, 7)), utc(2024, 5, 1, 10, 15));
+        assert_eq!(next("*/15 * * * *", utc(2024, 5, 1, 10, 45)), utc(2024, 5, 1, 11, 0));
+        // N/STEP starts at N
+        assert_eq!(Cron::parse("5/20 * * * *").unwrap().minutes, (1 << 5) | (1 << 25) | (1 << 45));
+    }
+
+    #[test]
+    fn weekday_range_skips_the_weekend() {
+        // Friday evening to Monday morning
+        assert_eq!(next("0 9 * * 1-5", utc(2024, 5, 3, 18, 0)), utc(2024, 5, 6, 9, 0));
+        assert_eq!(Cron::parse("0 9 * * 1-5").unwrap().weekdays, 0b0111110);
+    }
+
+    #[test]
+    fn weekday_seven_is_sunday() {
+        assert_eq!(Cron::parse("0 9 * * 7").unwrap().weekdays, 1);
+        assert_eq!(next("0 9 * * 7", utc(2024, 5, 1, 0, 0)), utc(2024, 5, 5, 9, 0));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // Every Friday, and every 13th whatever the weekday
+        assert_eq!(next("0 0 13 * 5", utc(2024, 10, 1, 0, 0)), utc(2024, 10, 4, 0, 0));
+        assert_eq!(next("0 0 13 * 5", utc(2024, 10, 12, 0, 0)), utc(2024, 10, 13, 0, 0)); // a Sunday
+        assert_eq!(next("0 0 13 * 5", utc(2024, 10, 13, 0, 0)), utc(2024, 10, 18, 0, 0));
+        // With the weekday left as *, only the day of month counts
+        assert_eq!(next("0 0 13 * *", utc(2024, 10, 1, 0, 0)), utc(2024, 10, 13, 0, 0));
+    }
+
+    #[test]
+    fn leap_day_waits_for_a_leap_year() {
+        assert_eq!(next("0 0 29 2 *", utc(2024, 3, 1, 0, 0)), utc(2028, 2, 29, 0, 0));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(Cron::parse("* * * *").is_err());
+        assert!(Cron::parse("60 * * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+        assert!(Cron::parse("0 0 * * 5-1").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+// signals.rs - discrete Buy/Sell/Neutral signals from indicators, and a weighted score combining them
+//
+// A rule fires on the bars where its indicator crosses something: RSI leaving the oversold or
+// overbought zone, the MACD line crossing its signal line, price or a fast SMA crossing an SMA.
+// Between firings a rule holds its last signal, so every bar has a stance per rule. The score is the
+// weighted mean of those stances, from -1 (every rule says sell) to 1 (every rule says buy), and is
+// kept per bar so a backtest can trade the combined signal without recomputing anything.
+
+use indicators_core::indicators::crossover::{cross_level, crossover};
+use indicators_core::{MACD, RSI, SMA};
+use serde::Serialize;
+
+use crate::indicators::{split_specs, BoundIndicator};
+use crate::types::Candle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Signal {
+    Buy,
+    Sell,
+    Neutral,
+}
+
+impl Signal {
+    pub fn value(self) -> f64 {
+        match self {
+            Signal::Buy => 1.0,
+            Signal::Sell => -1.0,
+            Signal::Neutral => 0.0,
+        }
+    }
+
+    // Buy at or above `threshold`, sell at or below its negative
+    pub fn from_score(score: f64, threshold: f64) -> Self {
+        if score >= threshold {
+            Signal::Buy
+        } else if score <= -threshold {
+            Signal::Sell
+        } else {
+            Signal::Neutral
+        }
+    }
+
+    fn from_cross(cross: Option<f64>) -> Self {
+        match cross {
+            Some(c) if c > 0.0 => Signal::Buy,
+            Some(c) if c < 0.0 => Signal::Sell,
+            _ => Signal::Neutral,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignalRule {
+    Rsi { period: usize, oversold: f64, overbought: f64 }, // buy crossing up through oversold, sell crossing down through overbought
+    Macd { fast: usize, slow: usize, signal: usize },       // MACD line crossing its signal-line EMA
+    PriceSma { period: usize },                             // close crossing the SMA
+    SmaCross { fast: usize, slow: usize },                  // golden / death cross
+}
+
+impl SignalRule {
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            SignalRule::Rsi { period: 14, oversold: 30.0, overbought: 70.0 },
+            SignalRule::Macd { fast: 12, slow: 26, signal: 9 },
+            SignalRule::PriceSma { period: 50 },
+        ]
+    }
+
+    // "rsi(14,30,70)", "macd(12,26,9)", "price_sma(50)", "sma_cross(50,200)"; omitted arguments
+    // take the defaults, so "rsi" alone is rsi(14,30,70)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (name, args) = match spec.split_once('(') {
+            Some((name, rest)) => {
+                let args = rest.strip_suffix(')').ok_or_else(|| format!("Unclosed parenthesis in {}", spec))?;
+                (name.trim(), args)
+            }
+            None => (spec, ""),
+        };
+        let args: Vec<f64> = args.split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(|a| a.parse::<f64>().map_err(|_| format!("Invalid argument {} in {}", a, spec)))
+            .collect::<Result<_, _>>()?;
+        let arg = |i: usize, default: f64| args.get(i).copied().unwrap_or(default);
+        let period = |i: usize, default: usize| -> Result<usize, String> {
+            let value = arg(i, default as f64);
+            if value >= 1.0 && value.fract() == 0.0 {
+                Ok(value as usize)
+            } else {
+                Err(format!("Periods must be positive integers, got {} in {}", value, spec))
+            }
+        };
+
+        let (rule, arity) = match name.to_lowercase().as_str() {
+            "rsi" => (SignalRule::Rsi { period: period(0, 14)?, oversold: arg(1, 30.0), overbought: arg(2, 70.0) }, 3),
+            "macd" => (SignalRule::Macd { fast: period(0, 12)?, slow: period(1, 26)?, signal: period(2, 9)? }, 3),
+            "price_sma" | "sma" => (SignalRule::PriceSma { period: period(0, 50)? }, 1),
+            "sma_cross" => (SignalRule::SmaCross { fast: period(0, 50)?, slow: period(1, 200)? }, 2),
+            other => return Err(format!("Unknown signal rule {} (expected rsi, macd, price_sma or sma_cross)", other)),
+        };
+        if args.len() > arity {
+            return Err(format!("{} takes at most {} arguments", name, arity));
+        }
+        match rule {
+            SignalRule::Rsi { oversold, overbought, .. } if !(0.0..=100.0).contains(&oversold) || !(0.0..=100.0).contains(&overbought) || oversold >= overbought => {
+                Err(format!("RSI levels must satisfy 0 <= oversold < overbought <= 100 in {}", spec))
+            }
+            SignalRule::Macd { fast, slow, .. } | SignalRule::SmaCross { fast, slow } if fast >= slow => {
+                Err(format!("The fast period must be shorter than the slow one in {}", spec))
+            }
+            rule => Ok(rule),
+        }
+    }
+
+    // Comma-separated rules; commas inside parentheses belong to the rule
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        split_specs(spec).into_iter().map(SignalRule::parse).collect()
+    }
+
+    pub fn label(&self) -> String {
+        match *self {
+            SignalRule::Rsi { period, oversold, overbought } => format!("rsi({},{},{})", period, oversold, overbought),
+            SignalRule::Macd { fast, slow, signal } => format!("macd({},{},{})", fast, slow, signal),
+            SignalRule::PriceSma { period } => format!("price_sma({})", period),
+            SignalRule::SmaCross { fast, slow } => format!("sma_cross({},{})", fast, slow),
+        }
+    }
+
+    // Bars needed before the rule can fire at all
+    pub fn warmup(&self) -> usize {
+        match *self {
+            SignalRule::Rsi { period, .. } => period + 2,
+            SignalRule::Macd { slow, signal, .. } => slow + signal,
+            SignalRule::PriceSma { period } => period + 1,
+            SignalRule::SmaCross { slow, .. } => slow + 1,
+        }
+    }
+
+    // One signal per candle: Buy or Sell on the bars where the rule fires, Neutral elsewhere
+    pub fn signals(&self, candles: &[Candle]) -> Vec<Signal> {
+        let crosses = match *self {
+            SignalRule::Rsi { period, oversold, overbought } => {
+                let rsi = BoundIndicator::new(RSI::new()).with_param("period", period).compute(candles);
+                let up = cross_level(&rsi, oversold);
+                let down = cross_level(&rsi, overbought);
+                up.into_iter().zip(down)
+                    .map(|(up, down)| match (up, down) {
+                        (Some(u), _) if u > 0.0 => Some(1.0),
+                        (_, Some(d)) if d < 0.0 => Some(-1.0),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            SignalRule::Macd { fast, slow, signal } => {
+                let line = BoundIndicator::new(MACD::new())
+                    .with_param("short_period", fast)
+                    .with_param("long_period", slow)
+                    .with_output("macd")
+                    .compute(candles);
+                crossover(&line, &ema_of(&line, signal))
+            }
+            SignalRule::PriceSma { period } => {
+                let closes: Vec<Option<f64>> = candles.iter().map(|c| Some(c.close)).collect();
+                crossover(&closes, &sma(candles, period))
+            }
+            SignalRule::SmaCross { fast, slow } => {
+                crossover(&sma(candles, fast), &sma(candles, slow))
+            }
+        };
+        let mut signals: Vec<Signal> = crosses.into_iter().map(Signal::from_cross).collect();
+        signals.resize(candles.len(), Signal::Neutral);
+        signals
+    }
+}
+
+fn sma(candles: &[Candle], period: usize) -> Vec<Option<f64>> {
+    BoundIndicator::new(SMA::new()).with_param("period", period).compute(candles)
+}
+
+// EMA over a series with a leading gap (the MACD line), seeded with the mean of its first `period` values
+fn ema_of(series: &[Option<f64>], period: usize) -> Vec<Option<f64>> {
+    let mut result = vec![None; series.len()];
+    let Some(first) = series.iter().position(Option::is_some) else {
+        return result;
+    };
+    let values: Vec<f64> = series[first..].iter().map_while(|v| *v).collect();
+    if period == 0 || values.len() < period {
+        return result;
+    }
+    let k = 2.0 / (period as f64 + 1.0);
+    let mut ema = values[..period].iter().sum::<f64>() / period as f64;
+    result[first + period - 1] = Some(ema);
+    for (i, value) in values.iter().enumerate().skip(period) {
+        ema = value * k + ema * (1.0 - k);
+        result[first + i] = Some(ema);
+    }
+    result
+}
+
+// Each bar's stance: the last Buy or Sell up to and including it, Neutral before the first one
+pub fn stances(signals: &[Signal]) -> Vec<Signal> {
+    let mut stance = Signal::Neutral;
+    signals.iter()
+        .map(|signal| {
+            if *signal != Signal::Neutral {
+                stance = *signal;
+            }
+            stance
+        })
+        .collect()
+}
+
+pub struct RuleEvaluation {
+    pub rule: SignalRule,
+    pub weight: f64,
+    pub signals: Vec<Signal>,
+    pub stances: Vec<Signal>,
+}
+
+pub struct SignalModel {
+    pub rules: Vec<(SignalRule, f64)>, // rule and its (non-negative) weight
+    pub threshold: f64,                // |score| needed for a combined Buy or Sell
+}
+
+impl Default for SignalModel {
+    fn default() -> Self {
+        Self {
+            rules: SignalRule::defaults().into_iter().map(|rule| (rule, 1.0)).collect(),
+            threshold: 0.5,
+        }
+    }
+}
+
+impl SignalModel {
+    // Per-rule signals and the combined score for every candle
+    pub fn evaluate(&self, candles: &[Candle]) -> (Vec<RuleEvaluation>, Vec<f64>) {
+        let rules: Vec<RuleEvaluation> = self.rules.iter()
+            .map(|(rule, weight)| {
+                let signals = rule.signals(candles);
+                let stances = stances(&signals);
+                RuleEvaluation { rule: *rule, weight: *weight, signals, stances }
+            })
+            .collect();
+
+        let total_weight: f64 = rules.iter().map(|r| r.weight).sum();
+        let scores = (0..candles.len())
+            .map(|i| {
+                if total_weight <= 0.0 {
+                    return 0.0;
+                }
+                rules.iter().map(|r| r.weight * r.stances[i].value()).sum::<f64>() / total_weight
+            })
+            .collect();
+        (rules, scores)
+    }
+
+    pub fn signal(&self, score: f64) -> Signal {
+        Signal::from_score(score, self.threshold)
+    }
+}
@@ -0,0 +1,255 @@
+// sse.rs - Server-sent events (/api/v1/sse) for clients that can't use WebSockets
+//
+// One long-lived text/event-stream response carries three kinds of event for the tickers in the
+// query: `quote` every push interval, `indicators` once a bar of the chosen interval closes, and
+// `alert` when a portfolio alert on one of them triggers. Event ids come from one process-wide
+// counter seeded with the startup time, so they keep increasing across restarts and a
+// reconnecting client's Last-Event-ID compares with any of them. Alerts after it are replayed
+// from a short history; quotes and indicators are simply sent fresh. A comment line goes out
+// whenever the stream has been quiet for HEARTBEAT_INTERVAL so proxies keep the connection open.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+use crate::api::{QuoteResponse, SseSettings, StockDataApi};
+use crate::metrics;
+use crate::portfolio::TriggeredAlert;
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+// The std server writes its own frames; axum_server builds them with axum's Event and KeepAlive
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+pub const HEARTBEAT_FRAME: &str = ": heartbeat\n\n";
+// Sent as `retry:` so EventSource waits this long before reconnecting
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+// Alerts kept for Last-Event-ID replay
+const ALERT_HISTORY: usize = 256;
+const ALERT_CHANNEL_CAPACITY: usize = 64;
+// Upstream bars show up a few seconds after their interval ends
+const CANDLE_CLOSE_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    pub id: u64,
+    pub event: &'static str,
+    pub data: String, // JSON on one line
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+impl SseEvent {
+    pub fn frame(&self) -> String {
+        format!("id: {}\nevent: {}\ndata: {}\n\n", self.id, self.event, self.data)
+    }
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+pub fn retry_frame() -> String {
+    format!("retry: {}\n\n", RECONNECT_DELAY.as_millis())
+}
+
+// Last-Event-ID from the header EventSource sends on reconnect, or from the query for clients
+// that can't set headers
+pub fn last_event_id(header: Option<&str>, query: &HashMap<String, String>) -> Option<u64> {
+    header
+        .or_else(|| query.get("last_event_id").map(String::as_str))
+        .and_then(|id| id.trim().parse().ok())
+}
+
+struct AlertEvent {
+    symbol: String,
+    event: SseEvent,
+}
+
+// Hands triggered alerts to every open event stream and keeps the latest for replay
+pub struct EventHub {
+    next_id: AtomicU64,
+    alerts: broadcast::Sender<Arc<AlertEvent>>,
+    history: Mutex<VecDeque<Arc<AlertEvent>>>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        let (alerts, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self {
+            next_id: AtomicU64::new(Utc::now().timestamp_millis().max(0) as u64),
+            alerts,
+            history: Mutex::new(VecDeque::with_capacity(ALERT_HISTORY)),
+        }
+    }
+}
+
+impl EventHub {
+    fn event<T: Serialize>(&self, event: &'static str, data: &T) -> SseEvent {
+        let data = serde_json::to_string(data)
+            .unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }).to_string());
+        SseEvent { id: self.next_id.fetch_add(1, Ordering::Relaxed), event, data }
+    }
+
+    // Id of the latest event handed out so far
+    fn last_id(&self) -> u64 {
+        self.next_id.load(Ordering::Relaxed).saturating_sub(1)
+    }
+
+    pub fn publish_alerts(&self, alerts: &[TriggeredAlert]) {
+        for alert in alerts {
+            let event = Arc::new(AlertEvent { symbol: alert.alert.symbol.clone(), event: self.event("alert", alert) });
+            {
+                let mut history = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                if history.len() == ALERT_HISTORY {
+                    history.pop_front();
+                }
+                history.push_back(Arc::clone(&event));
+            }
+            let _ = self.alerts.send(event); // fails only when no stream is open
+        }
+    }
+
+    fn alerts_after(&self, id: u64, symbols: &BTreeSet<String>) -> Vec<SseEvent> {
+        let history = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        history
+            .iter()
+            .filter(|alert| alert.event.id > id && symbols.contains(&alert.symbol))
+            .map(|alert| alert.event.clone())
+            .collect()
+    }
+}
+
+// One client's event stream. Both servers drive it the same way: call next_events and write
+// what it returns, or a heartbeat when it returns nothing.
+pub struct SseSession {
+    api: Arc<StockDataApi>,
+    settings: SseSettings,
+    alerts: broadcast::Receiver<Arc<AlertEvent>>,
+    last_alert_id: u64,
+    backlog: Vec<SseEvent>,            // replayed alerts, sent first
+    last_candle: HashMap<String, i64>, // timestamp of the bar last reported per ticker
+    next_quotes: Instant,
+    next_candle_check: Instant,
+    last_sent: Instant,
+    _gauge: metrics::StreamGauge, // counted with the WebSocket streams
+}
+
+impl SseSession {
+    pub fn open(api: Arc<StockDataApi>, settings: SseSettings, last_event_id: Option<u64>) -> Self {
+        // Subscribe before reading the history so nothing published in between is lost
+        let alerts = api.events().alerts.subscribe();
+        let backlog = last_event_id
+            .map(|id| api.events().alerts_after(id, &settings.tickers))
+            .unwrap_or_default();
+        let last_alert_id = backlog
+            .last()
+            .map(|event| event.id)
+            .or(last_event_id)
+            .unwrap_or_else(|| api.events().last_id());
+        let now = Instant::now();
+        Self {
+            _gauge: metrics::StreamGauge::open(settings.tickers.len()),
+            api,
+            settings,
+            alerts,
+            last_alert_id,
+            backlog,
+            last_candle: HashMap::new(),
+            next_quotes: now,
+            next_candle_check: now,
+            last_sent: now,
+        }
+    }
+
+    // Waits until something is due and returns the events to send; empty when the stream has been
+    // quiet for HEARTBEAT_INTERVAL
+    pub async fn next_events(&mut self) -> Vec<SseEvent> {
+        let mut events = std::mem::take(&mut self.backlog);
+        loop {
+            let heartbeat = self.last_sent + HEARTBEAT_INTERVAL;
+            if events.is_empty() {
+                let mut wake = self.next_quotes.min(heartbeat);
+                if !self.settings.indicators.is_empty() {
+                    wake = wake.min(self.next_candle_check);
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep_until(wake) => {}
+                    received = self.alerts.recv() => match received {
+                        Ok(alert) => {
+                            if alert.event.id > self.last_alert_id && self.settings.tickers.contains(&alert.symbol) {
+                                self.last_alert_id = alert.event.id;
+                                events.push(alert.event.clone());
+                            }
+                        }
+                        // Fell behind the channel: pick the missed alerts up from the history
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            let missed = self.api.events().alerts_after(self.last_alert_id, &self.settings.tickers);
+                            if let Some(last) = missed.last() {
+                                self.last_alert_id = last.id;
+                            }
+                            events.extend(missed);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {}
+                    },
+                }
+            }
+
+            let now = Instant::now();
+            if now >= self.next_quotes {
+                events.extend(self.quote_events().await);
+                self.next_quotes = now + self.settings.interval;
+            }
+            if !self.settings.indicators.is_empty() && now >= self.next_candle_check {
+                events.extend(self.indicator_events().await);
+            }
+            if !events.is_empty() || now >= heartbeat {
+                self.last_sent = now;
+                return events;
+            }
+        }
+    }
+
+    async fn quote_events(&self) -> Vec<SseEvent> {
+        let tickers: Vec<String> = self.settings.tickers.iter().cloned().collect();
+        let QuoteResponse { quotes, errors } = self.api.poll_quotes(&tickers).await;
+        let hub = self.api.events();
+        quotes
+            .values()
+            .map(|quote| hub.event("quote", quote))
+            .chain(errors.iter().map(|message| hub.event("warning", &serde_json::json!({ "message": message }))))
+            .collect()
+    }
+
+    // Bars that closed since the last check, then schedules the next check for when the bar now
+    // forming closes (or one interval out when nothing is trading)
+    async fn indicator_events(&mut self) -> Vec<SseEvent> {
+        let tickers: Vec<String> = self.settings.tickers.iter().cloned().collect();
+        let closed = self
+            .api
+            .get_closed_candle_indicators(&tickers, &self.settings.candle_interval, &self.settings.indicators)
+            .await;
+
+        let hub = self.api.events();
+        let mut events = Vec::new();
+        for update in &closed.updates {
+            let timestamp = update.candle.timestamp;
+            if self.last_candle.get(&update.symbol).is_some_and(|last| *last >= timestamp) {
+                continue;
+            }
+            self.last_candle.insert(update.symbol.clone(), timestamp);
+            events.push(hub.event("indicators", update));
+        }
+        events.extend(closed.errors.iter().map(|message| hub.event("warning", &serde_json::json!({ "message": message }))));
+
+        let bar = indicators_core::Resolution::parse(&self.settings.candle_interval).map_or(60, |r| r.approx_seconds());
+        let wait = closed
+            .next_close
+            .map(|close| close - Utc::now().timestamp())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(bar);
+        self.next_candle_check = Instant::now() + Duration::from_secs(wait as u64) + CANDLE_CLOSE_GRACE;
+        events
+    }
+}
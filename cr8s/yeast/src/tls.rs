@@ -0,0 +1,284 @@
+// tls.rs - HTTPS for the API servers
+//
+// Certificates are PEM files named in ApiConfig.tls. One cert/key pair serves every hostname;
+// extra pairs listed with their hostnames are picked by SNI, so a LAN box can answer for
+// several names (wildcards like *.lan match one label). Clients that send no SNI, or a name with
+// no cert of its own, get the default pair. With a redirect port set, a plain-HTTP listener
+// there sends every request on to the https:// URL. rustls is the same library the fetchers
+// already use as a client.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::ServerConfig;
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tracing::{debug, info};
+
+use crate::api::ApiError;
+
+// Only api::http_server reads and writes blocking sockets
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+use {
+    rustls::{ServerConnection, StreamOwned},
+    std::io::{Read, Write},
+    std::net::TcpStream,
+    std::sync::Mutex,
+    std::time::Duration,
+};
+
+// A cert/key pair for the hostnames a client asks for via SNI
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SniCertificate {
+    pub hostnames: Vec<String>,
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    pub cert: Option<PathBuf>, // PEM chain, leaf first
+    pub key: Option<PathBuf>, // PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub sni: Vec<SniCertificate>,
+    pub redirect_port: Option<u16>, // plain-HTTP port that redirects to HTTPS
+}
+
+impl TlsConfig {
+    pub fn enabled(&self) -> bool {
+        self.cert.is_some() || self.key.is_some() || !self.sni.is_empty()
+    }
+
+    // None when TLS isn't configured; errors name the file that couldn't be used
+    pub fn server_config(&self) -> Result<Option<Arc<ServerConfig>>, ApiError> {
+        if !self.enabled() {
+            return Ok(None);
+        }
+        let provider = crypto_provider();
+        let default = match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => Some(load_certified_key(&provider, cert, key)?),
+            (None, None) => None,
+            _ => return Err(ApiError::InvalidParameters("TLS needs both a certificate and a key".to_string())),
+        };
+        let mut by_name = HashMap::new();
+        for entry in &self.sni {
+            let key = load_certified_key(&provider, &entry.cert, &entry.key)?;
+            for hostname in &entry.hostnames {
+                by_name.insert(hostname.to_lowercase(), Arc::clone(&key));
+            }
+        }
+        let Some(default) = default.or_else(|| by_name.values().next().cloned()) else {
+            return Err(ApiError::InvalidParameters("TLS SNI certificates need at least one hostname".to_string()));
+        };
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| ApiError::InvalidParameters(format!("TLS setup failed: {}", e)))?
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::new(SniResolver { by_name, default }));
+        Ok(Some(Arc::new(config)))
+    }
+}
+
+// The process-wide provider if something installed one, else rustls' default (aws-lc-rs)
+fn crypto_provider() -> Arc<CryptoProvider> {
+    if let Some(provider) = CryptoProvider::get_default() {
+        return Arc::clone(provider);
+    }
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    Arc::clone(CryptoProvider::get_default().expect("crypto provider installed"))
+}
+
+fn load_certified_key(provider: &CryptoProvider, cert: &Path, key: &Path) -> Result<Arc<CertifiedKey>, ApiError> {
+    let open = |path: &Path| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| ApiError::InvalidParameters(format!("Could not read {}: {}", path.display(), e)))
+    };
+    let invalid = |path: &Path, what: &str| ApiError::InvalidParameters(format!("{} has no valid PEM {}", path.display(), what));
+
+    let chain: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut open(cert)?)
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid(cert, "certificate"))?;
+    if chain.is_empty() {
+        return Err(invalid(cert, "certificate"));
+    }
+    let private_key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut open(key)?)
+        .ok()
+        .flatten()
+        .ok_or_else(|| invalid(key, "private key"))?;
+    let signing_key = provider
+        .key_provider
+        .load_private_key(private_key)
+        .map_err(|e| ApiError::InvalidParameters(format!("Unsupported private key in {}: {}", key.display(), e)))?;
+    Ok(Arc::new(CertifiedKey::new(chain, signing_key)))
+}
+
+#[derive(Debug)]
+struct SniResolver {
+    by_name: HashMap<String, Arc<CertifiedKey>>,
+    default: Arc<CertifiedKey>,
+}
+
+impl ResolvesServerCert for SniResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let Some(name) = client_hello.server_name().map(str::to_lowercase) else {
+            return Some(Arc::clone(&self.default));
+        };
+        let wildcard = name.split_once('.').map(|(_, parent)| format!("*.{}", parent));
+        let key = self
+            .by_name
+            .get(&name)
+            .or_else(|| wildcard.and_then(|w| self.by_name.get(&w)))
+            .unwrap_or(&self.default);
+        Some(Arc::clone(key))
+    }
+}
+
+// A std server connection, plain or TLS. The TLS side is shared so the request reader and the
+// response writer can each hold a handle, as try_clone gives them for plain sockets; a connection
+// only ever reads or writes from one place at a time.
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+pub enum ServerStream {
+    Plain(TcpStream),
+    Tls(Arc<Mutex<StreamOwned<ServerConnection, TcpStream>>>),
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+impl ServerStream {
+    // The handshake runs on first read or write, off the accept loop
+    pub fn accept(socket: TcpStream, tls: Option<&Arc<ServerConfig>>) -> io::Result<Self> {
+        match tls {
+            Some(config) => {
+                let connection = ServerConnection::new(Arc::clone(config)).map_err(io::Error::other)?;
+                Ok(ServerStream::Tls(Arc::new(Mutex::new(StreamOwned::new(connection, socket)))))
+            }
+            None => Ok(ServerStream::Plain(socket)),
+        }
+    }
+
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            ServerStream::Plain(socket) => socket.try_clone().map(ServerStream::Plain),
+            ServerStream::Tls(stream) => Ok(ServerStream::Tls(Arc::clone(stream))),
+        }
+    }
+
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            ServerStream::Plain(socket) => socket.set_read_timeout(timeout),
+            ServerStream::Tls(stream) => lock(stream).sock.set_read_timeout(timeout),
+        }
+    }
+}
+
+// Sends close_notify once the last handle goes, so clients don't see a truncated stream. Write-only,
+// so a connection that never finished its handshake can't hold the drop up.
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+impl Drop for ServerStream {
+    fn drop(&mut self) {
+        if let ServerStream::Tls(stream) = self
+            && Arc::strong_count(stream) == 1
+        {
+            let mut guard = lock(stream);
+            let stream = &mut *guard;
+            stream.conn.send_close_notify();
+            while stream.conn.wants_write() {
+                if !matches!(stream.conn.write_tls(&mut stream.sock), Ok(n) if n > 0) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+fn lock(stream:&Mutex<StreamOwned<ServerConnection, TcpStream>>) -> std::sync::MutexGuard<'_, StreamOwned<ServerConnection, TcpStream>> {
+    stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+impl Read for ServerStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            ServerStream::Plain(socket) => socket.read(buf),
+            ServerStream::Tls(stream) => lock(stream).read(buf),
+        }
+    }
+}
+
+#[cfg(all(feature = "simple-server", not(feature = "axum-server")))]
+impl Write for ServerStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ServerStream::Plain(socket) => socket.write(buf),
+            ServerStream::Tls(stream) => lock(stream).write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ServerStream::Plain(socket) => socket.flush(),
+            ServerStream::Tls(stream) => lock(stream).flush(),
+        }
+    }
+}
+
+// Answers every plain-HTTP request on `addr` with a 308 to the same path over HTTPS on `https_port`
+pub async fn serve_redirect(addr: String, https_port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    info!("Redirecting http://{} to HTTPS on port {}", addr, https_port);
+    loop {
+        let (socket, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            if let Err(e) = redirect(socket, https_port).await {
+                debug!(error = %e, "HTTPS redirect failed");
+            }
+        });
+    }
+}
+
+async fn redirect(socket: tokio::net::TcpStream, https_port: u16) -> io::Result<()> {
+    let mut reader = tokio::io::BufReader::new(socket);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let path = request_line.split_whitespace().nth(1).filter(|p| p.starts_with('/')).unwrap_or("/").to_string();
+
+    let mut host = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("host")
+        {
+            host = Some(value.trim().to_string());
+        }
+    }
+
+    // Drop the plain port from Host; bracketed IPv6 literals keep their colons
+    let host = host.unwrap_or_else(|| "localhost".to_string());
+    let hostname = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => name.to_string(),
+        _ => host,
+    };
+    let location = match https_port {
+        443 => format!("https://{}{}", hostname, path),
+        port => format!("https://{}:{}{}", hostname, port, path),
+    };
+    let response = format!(
+        "HTTP/1.1 308 Permanent Redirect\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    );
+    let socket = reader.get_mut();
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await
+}
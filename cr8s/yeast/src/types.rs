@@ -1,9 +1,2 @@
-#[derive(Debug, Clone)]
-pub struct Candle {
-    pub timestamp: i64,
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
-    pub volume: Option<f64>,
-}
+// Shared with the WASM package so candles pass between the API and indicators-core without conversion
+pub use indicators_core::Candle;
@@ -0,0 +1,125 @@
+// unusual_activity.rs - flags option contracts trading unlike the rest of their chain
+//
+// Three signals, each measured against a threshold so contracts can be ranked across tickers:
+//   volume_oi  day volume well above open interest, i.e. mostly new positions
+//   sweep      last trade at or through the ask (bought) or bid (sold) with a large premium,
+//              the footprint a sweep leaves in a quote snapshot
+//   iv_spike   implied vol well above the median of its expiry
+// Only contracts with at least `min_volume` traded are considered. The score sums measure /
+// threshold over the signals that fired, so a contract firing two signals ranks above one that
+// barely fires a single one.
+
+use crate::options_math::OptionType;
+
+// Premium is quoted per share; one contract covers this many
+const CONTRACT_SIZE: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    pub min_volume: u64,
+    pub volume_oi_ratio: f64,
+    pub sweep_premium: f64, // dollars traded (volume x last x 100)
+    pub iv_ratio: f64,      // implied vol / expiry median
+}
+
+// One contract of an expiry; `iv` is None when it can't be solved from the quote
+pub struct ContractQuote {
+    pub strike: f64,
+    pub option_type: OptionType,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+    pub volume: u64,
+    pub open_interest: u64,
+    pub iv: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepSide {
+    Ask,
+    Bid,
+}
+
+impl SweepSide {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SweepSide::Ask => "ask",
+            SweepSide::Bid => "bid",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub volume_oi_ratio: Option<f64>, // None without open interest
+    pub premium: f64,
+    pub sweep_side: Option<SweepSide>,
+    pub iv_ratio: Option<f64>,
+    pub signals: Vec<&'static str>,
+    pub score: f64,
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
+
+// Which side the last trade hit; None inside the spread or without a two-sided quote
+fn sweep_side(quote: &ContractQuote) -> Option<SweepSide> {
+    if quote.last <= 0.0 || quote.bid <= 0.0 || quote.ask < quote.bid {
+        return None;
+    }
+    if quote.last >= quote.ask {
+        Some(SweepSide::Ask)
+    } else if quote.last <= quote.bid {
+        Some(SweepSide::Bid)
+    } else {
+        None
+    }
+}
+
+// Flags within one expiry, as (index into `quotes`, detection) for contracts firing any signal
+pub fn detect(quotes: &[ContractQuote], thresholds: &Thresholds) -> Vec<(usize, Detection)> {
+    let mut ivs: Vec<f64> = quotes.iter().filter_map(|q| q.iv).filter(|iv| *iv > 0.0).collect();
+    let median_iv = median(&mut ivs);
+
+    quotes
+        .iter()
+        .enumerate()
+        .filter(|(_, q)| q.volume > 0 && q.volume >= thresholds.min_volume)
+        .filter_map(|(i, q)| {
+            let mut signals = Vec::new();
+            let mut score = 0.0;
+
+            let volume_oi_ratio = (q.open_interest > 0).then(|| q.volume as f64 / q.open_interest as f64);
+            // No open interest at all means every contract traded opened a position
+            let oi_measure = volume_oi_ratio.unwrap_or(q.volume as f64);
+            if oi_measure >= thresholds.volume_oi_ratio {
+                signals.push("volume_oi");
+                score += oi_measure / thresholds.volume_oi_ratio;
+            }
+
+            let premium = q.volume as f64 * q.last * CONTRACT_SIZE;
+            let side = sweep_side(q).filter(|_| premium >= thresholds.sweep_premium);
+            if side.is_some() {
+                signals.push("sweep");
+                score += premium / thresholds.sweep_premium;
+            }
+
+            let iv_ratio = q.iv.zip(median_iv).filter(|(_, m)| *m > 0.0).map(|(iv, m)| iv / m);
+            if let Some(ratio) = iv_ratio.filter(|r| *r >= thresholds.iv_ratio) {
+                signals.push("iv_spike");
+                score += ratio / thresholds.iv_ratio;
+            }
+
+            (!signals.is_empty()).then_some((
+                i,
+                Detection { volume_oi_ratio, premium, sweep_side: side, iv_ratio, signals, score },
+            ))
+        })
+        .collect()
+}
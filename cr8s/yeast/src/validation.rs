@@ -0,0 +1,165 @@
+// validation.rs - Checks on tickers, intervals and ranges before a request goes upstream
+//
+// Yahoo answers a malformed symbol, an interval it doesn't serve or an unknown range with an
+// error that doesn't say which parameter was wrong, or with a chart body that later fails to
+// parse. A Validator collects every problem in a request instead of stopping at the first, and
+// finish() turns them into ApiError::InvalidFields: a 400 whose `fields` list names each
+// offending field, e.g. tickers[2] or interval. Interval/range combinations and date windows are
+// still checked by api::validate_range_interval and api::validate_window_interval.
+
+use serde::Serialize;
+
+use crate::api::ApiError;
+
+// Bar sizes Yahoo's chart API serves
+pub const VALID_INTERVALS: [&str; 13] = ["1m", "2m", "5m", "15m", "30m", "60m", "90m", "1h", "1d", "5d", "1wk", "1mo", "3mo"];
+pub const VALID_RANGES: [&str; 11] = ["1d", "5d", "1mo", "3mo", "6mo", "1y", "2y", "5y", "10y", "ytd", "max"];
+
+// Longer than any listed symbol, including futures and FX pairs like ES=F and EURUSD=X
+const MAX_TICKER_LEN: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String, // request field, with an index for list entries: tickers[1]
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+// Why `ticker` can't be a symbol, if it can't: letters and digits plus . - = and a leading ^
+// for indices (BRK.B, BTC-USD, ES=F, ^GSPC)
+fn ticker_problem(ticker: &str) -> Option<String> {
+    let ticker = ticker.trim();
+    if ticker.is_empty() {
+        return Some("must not be empty".to_string());
+    }
+    if ticker.len() > MAX_TICKER_LEN {
+        return Some(format!("'{}' is longer than {} characters", ticker, MAX_TICKER_LEN));
+    }
+    let body = ticker.strip_prefix('^').unwrap_or(ticker);
+    if !body.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Some(format!("'{}' must start with a letter or digit (or ^ for an index)", ticker));
+    }
+    if let Some(bad) = body.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '='))) {
+        return Some(format!("'{}' contains '{}'; symbols use letters, digits, '.', '-' and '='", ticker, bad));
+    }
+    None
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn reject(&mut self, field: String, message: String) {
+        self.errors.push(FieldError { field, message });
+    }
+
+    pub fn ticker(&mut self, field: &str, ticker: &str) -> &mut Self {
+        if let Some(message) = ticker_problem(ticker) {
+            self.reject(field.to_string(), message);
+        }
+        self
+    }
+
+    // At least one symbol and at most `max` (ApiConfig.max_tickers_per_request or an endpoint's
+    // own cap), each a valid symbol
+    pub fn tickers(&mut self, field: &str, tickers: &[String], max: usize) -> &mut Self {
+        if tickers.is_empty() {
+            self.reject(field.to_string(), "at least one ticker is required".to_string());
+        } else if tickers.len() > max {
+            self.reject(field.to_string(), format!("{} tickers given; at most {} per request", tickers.len(), max));
+        }
+        for (i, ticker) in tickers.iter().enumerate() {
+            if let Some(message) = ticker_problem(ticker) {
+                self.reject(format!("{}[{}]", field, i), message);
+            }
+        }
+        self
+    }
+
+    // None means the field was left out and its default applies
+    pub fn interval(&mut self, field: &str, interval: Option<&str>) -> &mut Self {
+        if let Some(interval) = interval.filter(|i| !VALID_INTERVALS.contains(i)) {
+            self.reject(field.to_string(), format!("'{}' is not an interval; use one of {}", interval, VALID_INTERVALS.join(", ")));
+        }
+        self
+    }
+
+    pub fn range(&mut self, field: &str, range: Option<&str>) -> &mut Self {
+        if let Some(range) = range.filter(|r| !VALID_RANGES.contains(r)) {
+            self.reject(field.to_string(), format!("'{}' is not a range; use one of {}", range, VALID_RANGES.join(", ")));
+        }
+        self
+    }
+
+    pub fn finish(&mut self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::InvalidFields(std::mem::take(&mut self.errors)))
+        }
+    }
+}
+
+// The common case of a single `ticker` parameter
+pub fn ticker(ticker: &str) -> Result<(), ApiError> {
+    Validator::new().ticker("ticker", ticker).finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(result: Result<(), ApiError>) -> Vec<String> {
+        match result {
+            Err(ApiError::InvalidFields(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected InvalidFields, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn accepts_listed_symbol_shapes() {
+        for symbol in ["AAPL", "BRK.B", "BTC-USD", "ES=F", "^GSPC", "EURUSD=X", " msft "] {
+            assert_eq!(ticker_problem(symbol), None, "{}", symbol);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_symbols() {
+        for symbol in ["", "  ", "^", "^^GSPC", ".B", "AAPL MSFT", "AAPL/", "AA$PL", "ÄPPL"] {
+            assert!(ticker_problem(symbol).is_some(), "{:?}", symbol);
+        }
+        assert!(ticker_problem(&"A".repeat(MAX_TICKER_LEN)).is_none());
+        assert!(ticker_problem(&"A".repeat(MAX_TICKER_LEN + 1)).unwrap().contains("longer than 20"));
+    }
+
+    #[test]
+    fn validator_names_every_bad_field() {
+        let tickers = vec!["AAPL".to_string(), "BAD/".to_string(), String::new()];
+        let result = Validator::new()
+            .tickers("tickers", &tickers, 10)
+            .interval("interval", Some("7m"))
+            .range("range", Some("1y"))
+            .finish();
+        assert_eq!(fields(result), ["tickers[1]", "tickers[2]", "interval"]);
+    }
+
+    #[test]
+    fn validator_caps_the_ticker_count() {
+        let tickers: Vec<String> = ["AAPL", "MSFT", "GOOG"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(fields(Validator::new().tickers("tickers", &tickers, 2).finish()), ["tickers"]);
+        assert_eq!(fields(Validator::new().tickers("tickers", &[], 2).finish()), ["tickers"]);
+        assert!(Validator::new().tickers("tickers", &tickers, 3).finish().is_ok());
+    }
+
+    #[test]
+    fn left_out_fields_pass() {
+        assert!(Validator::new().interval("interval", None).range("range", None).finish().is_ok());
+        assert!(ticker("^GSPC").is_ok());
+        assert_eq!(fields(ticker("^")), ["ticker"]);
+    }
+}
@@ -0,0 +1,115 @@
+// vol_surface.rs - implied-volatility surface (strike x expiry) from an options chain
+//
+// Each expiry's smile runs through its out-of-the-money quotes (puts below the forward, calls above),
+// where prices are liquid and calls and puts agree after parity. Implied vols are fitted with a
+// natural cubic spline in log-moneyness ln(K / F) and sampled on a moneyness grid shared by every
+// expiry, so rows line up for a 3D plot. Grid points outside a smile's quoted strikes are None
+// rather than extrapolated.
+
+use crate::options_math::{implied_volatility, OptionType};
+
+// Implied vols outside this range come from stale or crossed quotes
+const MIN_IV: f64 = 0.01;
+const MAX_IV: f64 = 5.0;
+
+// One quoted contract of an expiry
+pub struct ChainQuote {
+    pub strike: f64,
+    pub option_type: OptionType,
+    pub mid: f64,
+}
+
+// Natural cubic spline through points sorted by x
+pub struct CubicSpline {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    second: Vec<f64>, // second derivatives at each knot, zero at both ends
+}
+
+impl CubicSpline {
+    // None with fewer than two points or x values that don't strictly increase
+    pub fn new(points: &[(f64, f64)]) -> Option<Self> {
+        let n = points.len();
+        if n < 2 || points.windows(2).any(|w| w[1].0 <= w[0].0) {
+            return None;
+        }
+        let (xs, ys): (Vec<f64>, Vec<f64>) = points.iter().copied().unzip();
+
+        // Tridiagonal system for the interior second derivatives (Thomas algorithm)
+        let mut second = vec![0.0; n];
+        let mut diag = vec![0.0; n];
+        let mut rhs = vec![0.0; n];
+        for i in 1..n - 1 {
+            let (h0, h1) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+            diag[i] = 2.0 * (h0 + h1);
+            rhs[i] = 6.0 * ((ys[i + 1] - ys[i]) / h1 - (ys[i] - ys[i - 1]) / h0);
+            if i > 1 {
+                let factor = h0 / diag[i - 1];
+                diag[i] -= factor * h0;
+                rhs[i] -= factor * rhs[i - 1];
+            }
+        }
+        for i in (1..n - 1).rev() {
+            let h1 = xs[i + 1] - xs[i];
+            second[i] = (rhs[i] - h1 * second[i + 1]) / diag[i];
+        }
+        Some(CubicSpline { xs, ys, second })
+    }
+
+    // None outside the knots
+    pub fn eval(&self, x: f64) -> Option<f64> {
+        let (first, last) = (self.xs[0], self.xs[self.xs.len() - 1]);
+        if !(first..=last).contains(&x) {
+            return None;
+        }
+        let i = self.xs.partition_point(|k| *k <= x).clamp(1, self.xs.len() - 1) - 1;
+        let h = self.xs[i + 1] - self.xs[i];
+        let (a, b) = ((self.xs[i + 1] - x) / h, (x - self.xs[i]) / h);
+        Some(
+            a * self.ys[i] + b * self.ys[i + 1]
+                + ((a.powi(3) - a) * self.second[i] + (b.powi(3) - b) * self.second[i + 1]) * h * h / 6.0,
+        )
+    }
+}
+
+// Out-of-the-money implied vols of one expiry, by strike: (strike, iv)
+pub fn smile_points(quotes: &[ChainQuote], spot: f64, t: f64, rate: f64) -> Vec<(f64, f64)> {
+    let forward = spot * (rate * t).exp();
+    let mut points: Vec<(f64, f64)> = quotes
+        .iter()
+        .filter(|q| q.mid > 0.0 && q.strike > 0.0)
+        .filter(|q| match q.option_type {
+            OptionType::Put => q.strike < forward,
+            OptionType::Call => q.strike >= forward,
+        })
+        .filter_map(|q| {
+            let iv = implied_volatility(q.mid, spot, q.strike, t, rate, q.option_type)?;
+            (MIN_IV..=MAX_IV).contains(&iv).then_some((q.strike, iv))
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    points.dedup_by(|a, b| a.0 == b.0);
+    points
+}
+
+// The smile fitted in log-moneyness; None with fewer than two usable quotes
+pub fn fit_smile(points: &[(f64, f64)], spot: f64, t: f64, rate: f64) -> Option<CubicSpline> {
+    let forward = spot * (rate * t).exp();
+    let knots: Vec<(f64, f64)> = points.iter().map(|(strike, iv)| ((strike / forward).ln(), *iv)).collect();
+    CubicSpline::new(&knots)
+}
+
+// Implied vol at each strike; None outside the quoted strikes or where the spline dips below MIN_IV
+pub fn sample_smile(smile: &CubicSpline, strikes: &[f64], spot: f64, t: f64, rate: f64) -> Vec<Option<f64>> {
+    let forward = spot * (rate * t).exp();
+    strikes.iter().map(|strike| smile.eval((strike / forward).ln()).filter(|iv| *iv >= MIN_IV)).collect()
+}
+
+// `steps` evenly spaced strike / spot ratios from `min` to `max`
+pub fn moneyness_grid(min: f64, max: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![(min + max) / 2.0],
+        _ => (0..steps).map(|i| min + (max - min) * i as f64 / (steps - 1) as f64).collect(),
+    }
+}
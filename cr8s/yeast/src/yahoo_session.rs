@@ -0,0 +1,277 @@
+// yahoo_session.rs - The Yahoo cookie session and crumb, shared by every Yahoo client
+//
+// Yahoo's crumb-protected endpoints want a session cookie and the crumb issued alongside it.
+// Every YahooFinanceClient and the Yahoo options fetcher send through one cookie store and reuse
+// one crumb, so a crumb is scraped once per CRUMB_TTL rather than once per request. With a session
+// file (ApiConfig.yahoo_session) the crumb and cookies are written out whenever a crumb is issued
+// or dropped and read back on startup, minus anything that has expired, so a restart doesn't
+// re-scrape. A crumb Yahoo rejects is dropped together with its cookies; callers then get a
+// fresh pair and retry once.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::HeaderValue;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+// Yahoo doesn't say how long a crumb lasts; one it rejects sooner is renewed on the spot
+pub const CRUMB_TTL: Duration = Duration::from_secs(24 * 3600);
+
+// How Yahoo answers a request whose crumb or cookie has expired
+pub fn is_rejected(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCrumb {
+    value: String,
+    expires_at: i64, // unix seconds
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCookie {
+    url: String,        // where it was set, to resolve a missing Domain or Path
+    set_cookie: String, // the Set-Cookie header minus Expires and Max-Age
+    expires_at: Option<i64>, // unix seconds; None for a session cookie
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionFile {
+    crumb: Option<StoredCrumb>,
+    #[serde(default)]
+    cookies: Vec<StoredCookie>,
+}
+
+// A reqwest cookie jar that also keeps each Set-Cookie it accepts, so the jar can be saved
+#[derive(Default)]
+pub struct SessionCookies {
+    jar: RwLock<Jar>,
+    stored: Mutex<HashMap<(String, String), StoredCookie>>, // by (domain, name)
+}
+
+impl SessionCookies {
+    fn clear(&self) {
+        *self.jar.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Jar::default();
+        self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+
+    fn restore(&self, cookie: StoredCookie, now: i64) {
+        let Ok(url) = Url::parse(&cookie.url) else { return };
+        let header = match cookie.expires_at {
+            Some(expires_at) if expires_at <= now => return,
+            Some(expires_at) => format!("{}; Max-Age={}", cookie.set_cookie, expires_at - now),
+            None => cookie.set_cookie.clone(),
+        };
+        self.jar.read().unwrap_or_else(|poisoned| poisoned.into_inner()).add_cookie_str(&header, &url);
+        if let Some(key) = cookie_key(&cookie.set_cookie, &url) {
+            self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(key, cookie);
+        }
+    }
+
+    fn snapshot(&self) -> Vec<StoredCookie> {
+        let now = Utc::now().timestamp();
+        let stored = self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        stored.values().filter(|cookie| cookie.expires_at.is_none_or(|at| at > now)).cloned().collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).is_empty()
+    }
+
+    // Keeps one Set-Cookie header for saving, or forgets the cookie when the header deletes it
+    fn remember(&self, header: &HeaderValue, url: &Url) {
+        let Ok(header) = header.to_str() else { return };
+        let Some(key) = cookie_key(header, url) else { return };
+        let (set_cookie, expires_at) = split_expiry(header);
+        let mut stored = self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if expires_at.is_some_and(|at| at <= Utc::now().timestamp()) {
+            stored.remove(&key);
+        } else {
+            stored.insert(key, StoredCookie { url: url.to_string(), set_cookie, expires_at });
+        }
+    }
+}
+
+impl CookieStore for SessionCookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let headers: Vec<&HeaderValue> = cookie_headers.collect();
+        for header in &headers {
+            self.remember(header, url);
+        }
+        let jar = self.jar.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        jar.set_cookies(&mut headers.into_iter(), url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.read().unwrap_or_else(|poisoned| poisoned.into_inner()).cookies(url)
+    }
+}
+
+// (domain, name) for a Set-Cookie header; the domain is the request host without a Domain attribute
+fn cookie_key(header: &str, url: &Url) -> Option<(String, String)> {
+    let mut parts = header.split(';');
+    let name = parts.next()?.split_once('=')?.0.trim().to_string();
+    let domain = parts
+        .filter_map(|attr| attr.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("domain"))
+        .map(|(_, value)| value.trim().trim_start_matches('.').to_lowercase())
+        .or_else(|| url.host_str().map(str::to_lowercase))?;
+    Some((domain, name))
+}
+
+// The header without Expires and Max-Age, and the absolute expiry they gave (Max-Age wins)
+fn split_expiry(header: &str) -> (String, Option<i64>) {
+    let now = Utc::now().timestamp();
+    let (mut max_age, mut expires) = (None, None);
+    let mut kept = Vec::new();
+    for (i, part) in header.split(';').enumerate() {
+        let (key, value) = part.split_once('=').unwrap_or((part, ""));
+        match key.trim().to_lowercase().as_str() {
+            "max-age" if i > 0 => max_age = value.trim().parse::<i64>().ok().map(|secs| now.saturating_add(secs)),
+            "expires" if i > 0 => expires = parse_cookie_date(value.trim()),
+            _ => kept.push(part.trim()),
+        }
+    }
+    (kept.join("; "), max_age.or(expires))
+}
+
+// "Wed, 21 Oct 2026 07:28:00 GMT", or the older "Wed, 21-Oct-2026 07:28:00 GMT"
+fn parse_cookie_date(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc2822(value)
+        .map(|date| date.timestamp())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%a, %d-%b-%Y %H:%M:%S GMT").map(|date| date.and_utc().timestamp()))
+        .ok()
+}
+
+pub struct YahooSession {
+    path: Option<PathBuf>, // None keeps the session in memory only
+    cookies: Arc<SessionCookies>,
+    crumb: Mutex<Option<StoredCrumb>>,
+    renewal: tokio::sync::Mutex<()>, // one crumb fetch at a time
+}
+
+static SESSION: OnceLock<YahooSession> = OnceLock::new();
+
+// Loads the session file (ApiConfig.yahoo_session) when there is one. Only the first call, before
+// any Yahoo request, takes effect.
+pub fn configure(path: Option<PathBuf>) {
+    let _ = SESSION.set(YahooSession::open(path));
+}
+
+pub fn global() -> &'static YahooSession {
+    SESSION.get_or_init(|| YahooSession::open(None))
+}
+
+impl YahooSession {
+    fn open(path: Option<PathBuf>) -> Self {
+        let session = Self {
+            path,
+            cookies: Arc::new(SessionCookies::default()),
+            crumb: Mutex::new(None),
+            renewal: tokio::sync::Mutex::new(()),
+        };
+        if let Some(path) = &session.path {
+            session.load(path);
+        }
+        session
+    }
+
+    // A missing file is a first run; an unreadable one is logged and replaced on the next save
+    fn load(&self, path: &Path) {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Could not read the Yahoo session file");
+                return;
+            }
+        };
+        let file: SessionFile = match serde_json::from_str(&text) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring an invalid Yahoo session file");
+                return;
+            }
+        };
+
+        let now = Utc::now().timestamp();
+        for cookie in file.cookies {
+            self.cookies.restore(cookie, now);
+        }
+        let crumb = file.crumb.filter(|crumb| crumb.expires_at > now);
+        info!(
+            path = %path.display(),
+            crumb = crumb.is_some(),
+            cookies = self.cookies.snapshot().len(),
+            "Restored the Yahoo session"
+        );
+        *self.crumb.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = crumb;
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else { return };
+        let file = SessionFile {
+            crumb: self.crumb.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone(),
+            cookies: self.cookies.snapshot(),
+        };
+        // Written aside and renamed so a crash mid-write can't leave half a file
+        let temp = path.with_extension("tmp");
+        let written = serde_json::to_vec_pretty(&file)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&temp, json))
+            .and_then(|_| std::fs::rename(&temp, path));
+        match written {
+            Ok(()) => debug!(path = %path.display(), "Saved the Yahoo session"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Could not save the Yahoo session"),
+        }
+    }
+
+    // For reqwest::ClientBuilder::cookie_provider
+    pub fn cookie_store(&self) -> Arc<SessionCookies> {
+        Arc::clone(&self.cookies)
+    }
+
+    pub fn has_cookies(&self) -> bool {
+        !self.cookies.is_empty()
+    }
+
+    // The current crumb while it's within CRUMB_TTL
+    pub fn crumb(&self) -> Option<String> {
+        let crumb = self.crumb.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        crumb.as_ref().filter(|crumb| crumb.expires_at > Utc::now().timestamp()).map(|crumb| crumb.value.clone())
+    }
+
+    // Held while fetching a crumb, so concurrent requests wait for one fetch instead of each
+    // scraping Yahoo; check crumb() again once it's held
+    pub async fn renewal(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.renewal.lock().await
+    }
+
+    pub fn set_crumb(&self, crumb: &str) {
+        let expires_at = Utc::now().timestamp() + CRUMB_TTL.as_secs() as i64;
+        *self.crumb.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Some(StoredCrumb { value: crumb.to_string(), expires_at });
+        self.save();
+    }
+
+    // Drops a crumb Yahoo refused, along with the cookies it was issued with. Does nothing once
+    // the crumb has been dropped or replaced, so requests that fail together only renew it once.
+    pub fn reject(&self, crumb: &str) {
+        {
+            let mut current = self.crumb.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if current.as_ref().is_none_or(|current| current.value != crumb) {
+                return;
+            }
+            *current = None;
+        }
+        info!("Yahoo rejected the session crumb; starting a new session");
+        self.cookies.clear();
+        self.save();
+    }
+}